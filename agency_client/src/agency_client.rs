@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use crate::agency_settings;
 
 use crate::error::{AgencyClientResult, AgencyClientErrorKind, AgencyClientError};
@@ -6,7 +8,7 @@ use crate::utils::{error_utils, validation};
 use crate::mocking;
 use url::Url;
 
-#[derive(Default, Deserialize)]
+#[derive(Clone, Default, Deserialize)]
 pub struct AgencyClient {
     wallet_handle: i32,
     agency_url: String,
@@ -147,9 +149,47 @@ impl AgencyClient {
         error_utils::SUCCESS.code_num
     }
 
-    // pub fn new(config: &str, wallet_handle: i32, validate: bool) -> AgencyClientResult<Self> {
-    //     let mut agency_client = Self::default();
-    //     agency_client.process_config_string(config, wallet_handle, validate)?;
-    //     Ok(agency_client)
-    // }
+    /**
+    Creates agency client from config without touching global agency settings,
+    so that several clients (e.g. one per tenant) can exist in one process.
+     */
+    pub fn new(config: &str, wallet_handle: i32, validate: bool) -> AgencyClientResult<Self> {
+        trace!("AgencyClient::new >>> config {:?}, wallet_handle: {:?}, validate: {:?}", config, wallet_handle, validate);
+
+        let configuration: HashMap<String, Value> = serde_json::from_str(config)
+            .map_err(|err| AgencyClientError::from_msg(AgencyClientErrorKind::InvalidJson, format!("Cannot parse config: {}", err)))?;
+
+        let value = |key: &str| configuration.get(key).and_then(Value::as_str).unwrap_or_default().to_string();
+
+        let agency_client = AgencyClient {
+            wallet_handle,
+            agency_url: format!("{}/agency/msg", value(agency_settings::CONFIG_AGENCY_ENDPOINT)),
+            agency_did: value(agency_settings::CONFIG_AGENCY_DID),
+            agency_pwdid: value(agency_settings::CONFIG_REMOTE_TO_SDK_DID),
+            agency_vk: value(agency_settings::CONFIG_AGENCY_VERKEY),
+            agent_pwdid: value(agency_settings::CONFIG_REMOTE_TO_SDK_DID),
+            agent_vk: value(agency_settings::CONFIG_REMOTE_TO_SDK_VERKEY),
+            my_pwdid: value(agency_settings::CONFIG_SDK_TO_REMOTE_DID),
+            my_vk: value(agency_settings::CONFIG_SDK_TO_REMOTE_VERKEY),
+        };
+        if validate { agency_client.validate()?; };
+        Ok(agency_client)
+    }
+
+    /**
+    Value of agency setting `key` held by this client.
+     */
+    pub fn get_config_value(&self, key: &str) -> AgencyClientResult<String> {
+        match key {
+            agency_settings::CONFIG_AGENCY_ENDPOINT => self.get_agency_url(),
+            agency_settings::CONFIG_AGENCY_DID => self.get_agency_did(),
+            agency_settings::CONFIG_AGENCY_VERKEY => self.get_agency_vk(),
+            agency_settings::CONFIG_REMOTE_TO_SDK_DID => self.get_agent_pwdid(),
+            agency_settings::CONFIG_REMOTE_TO_SDK_VERKEY => self.get_agent_vk(),
+            agency_settings::CONFIG_SDK_TO_REMOTE_DID => self.get_my_pwdid(),
+            agency_settings::CONFIG_SDK_TO_REMOTE_VERKEY => self.get_my_vk(),
+            agency_settings::CONFIG_WALLET_HANDLE => Ok(self.wallet_handle.to_string()),
+            _ => Err(AgencyClientError::from_msg(AgencyClientErrorKind::InvalidConfiguration, format!("Cannot read \"{}\" from agency client", key)))
+        }
+    }
 }
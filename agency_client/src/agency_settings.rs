@@ -5,6 +5,7 @@ use std::sync::RwLock;
 use serde_json::Value;
 use url::Url;
 
+use crate::agency_client::AgencyClient;
 use crate::error::{AgencyClientErrorKind, AgencyClientError, AgencyClientResult};
 use crate::utils::{error_utils, validation};
 
@@ -140,6 +141,16 @@ pub fn get_config_value(key: &str) -> AgencyClientResult<String> {
         .ok_or(AgencyClientError::from_msg(AgencyClientErrorKind::InvalidConfiguration, format!("Cannot read \"{}\" from AGENCY_SETTINGS", key)))
 }
 
+/**
+Reads setting of given agency client, or of the global agency settings if no client is given.
+ */
+pub fn get_client_config_value(agency_client: Option<&AgencyClient>, key: &str) -> AgencyClientResult<String> {
+    match agency_client {
+        Some(agency_client) => agency_client.get_config_value(key),
+        None => get_config_value(key)
+    }
+}
+
 pub fn set_config_value(key: &str, value: &str) {
     trace!("set_config_value >>> key: {}, value: {}", key, value);
     if !VALID_AGENCY_CONFIG_KEYS.contains(&key) {
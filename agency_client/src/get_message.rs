@@ -1,4 +1,5 @@
 use crate::{get_messages, MessageStatusCode, prepare_message_for_agent, A2AMessageKinds, A2AMessageV2, A2AMessage, GeneralMessage, parse_response_from_agency, prepare_message_for_agency, agency_settings, mocking};
+use crate::agency_client::AgencyClient;
 use crate::error::{AgencyClientResult, AgencyClientErrorKind, AgencyClientError};
 use crate::utils::encryption_envelope::EncryptionEnvelope;
 use crate::utils::comm::post_to_agency;
@@ -69,6 +70,7 @@ pub struct GetMessagesBuilder {
     uids: Option<Vec<String>>,
     status_codes: Option<Vec<MessageStatusCode>>,
     pairwise_dids: Option<Vec<String>>,
+    agency_client: Option<AgencyClient>,
 }
 
 impl GetMessagesBuilder {
@@ -84,6 +86,7 @@ impl GetMessagesBuilder {
             exclude_payload: None,
             status_codes: None,
             pairwise_dids: None,
+            agency_client: None,
         }
     }
 
@@ -109,6 +112,11 @@ impl GetMessagesBuilder {
         Ok(self)
     }
 
+    pub fn agency_client(&mut self, agency_client: Option<&AgencyClient>) -> AgencyClientResult<&mut Self> {
+        self.agency_client = agency_client.cloned();
+        Ok(self)
+    }
+
     pub fn include_edge_payload(&mut self, payload: &str) -> AgencyClientResult<&mut Self> {
         //todo: is this a json value, String??
         self.exclude_payload = Some(payload.to_string());
@@ -120,7 +128,7 @@ impl GetMessagesBuilder {
 
         let data = self.prepare_request()?;
 
-        let response = post_to_agency(&data)?;
+        let response = post_to_agency(self.agency_client.as_ref(), &data)?;
 
         self.parse_response(response)
     }
@@ -128,7 +136,7 @@ impl GetMessagesBuilder {
     fn parse_response(&self, response: Vec<u8>) -> AgencyClientResult<Vec<Message>> {
         trace!("parse_get_messages_response >>> processing payload of {} bytes", response.len());
 
-        let mut response = parse_response_from_agency(&response)?;
+        let mut response = parse_response_from_agency(self.agency_client.as_ref(), &response)?;
 
         trace!("parse_get_messages_response >>> obtained agency response {:?}", response);
 
@@ -146,7 +154,7 @@ impl GetMessagesBuilder {
 
         let data = self.prepare_download_request()?;
 
-        let response = post_to_agency(&data)?;
+        let response = post_to_agency(self.agency_client.as_ref(), &data)?;
 
         if mocking::agency_mocks_enabled() && response.len() == 0 {
             return Ok(Vec::new());
@@ -167,15 +175,15 @@ impl GetMessagesBuilder {
                                    self.pairwise_dids.clone()))
         );
 
-        let agency_did = agency_settings::get_config_value(agency_settings::CONFIG_REMOTE_TO_SDK_DID)?;
+        let agency_did = agency_settings::get_client_config_value(self.agency_client.as_ref(), agency_settings::CONFIG_REMOTE_TO_SDK_DID)?;
 
-        prepare_message_for_agency(&message, &agency_did)
+        prepare_message_for_agency(self.agency_client.as_ref(), &message, &agency_did)
     }
 
     // todo: This should be removed after public method vcx_messages_download is removed
     fn parse_download_messages_response_noauth(&self, response: Vec<u8>) -> AgencyClientResult<Vec<MessageByConnection>> {
         trace!("parse_download_messages_response >>>");
-        let mut response = parse_response_from_agency(&response)?;
+        let mut response = parse_response_from_agency(self.agency_client.as_ref(), &response)?;
 
         trace!("parse_download_messages_response: parsed response {:?}", response);
         let msgs = match response.remove(0) {
@@ -188,7 +196,7 @@ impl GetMessagesBuilder {
             .map(|connection| {
                 Ok(MessageByConnection {
                     pairwise_did: connection.pairwise_did.clone(),
-                    msgs: connection.msgs.iter().map(|message| message.decrypt_noauth()).collect(),
+                    msgs: connection.msgs.iter().map(|message| message.decrypt_noauth(self.agency_client.as_ref())).collect(),
                 })
             })
             .collect()
@@ -215,7 +223,7 @@ impl GeneralMessage for GetMessagesBuilder {
                                    self.pairwise_dids.clone()))
         );
 
-        prepare_message_for_agent(vec![message], &self.to_vk, &self.agent_did, &self.agent_vk)
+        prepare_message_for_agent(self.agency_client.as_ref(), vec![message], &self.to_vk, &self.agent_did, &self.agent_vk)
     }
 }
 
@@ -262,9 +270,9 @@ impl Message {
         }
     }
 
-    pub fn decrypt_noauth(&self) -> Message {
+    pub fn decrypt_noauth(&self, agency_client: Option<&AgencyClient>) -> Message {
         let mut new_message = self.clone();
-        if let Ok(decrypted_msg) = self._noauth_decrypt_v3_message() {
+        if let Ok(decrypted_msg) = self._noauth_decrypt_v3_message(agency_client) {
             new_message.decrypted_msg = Some(decrypted_msg);
         } else {
             new_message.decrypted_msg = None;
@@ -273,29 +281,30 @@ impl Message {
         new_message
     }
 
-    pub fn decrypt_auth(&self, expected_sender_vk: &str) -> AgencyClientResult<Message> {
+    pub fn decrypt_auth(&self, agency_client: Option<&AgencyClient>, expected_sender_vk: &str) -> AgencyClientResult<Message> {
         let mut new_message = self.clone();
-        let decrypted_msg = self._auth_decrypt_v3_message(expected_sender_vk)?;
+        let decrypted_msg = self._auth_decrypt_v3_message(agency_client, expected_sender_vk)?;
         trace!("decrypt_auth >>> decrypted_msg: {:?}", decrypted_msg);
         new_message.decrypted_msg = Some(decrypted_msg);
         new_message.payload = None;
         Ok(new_message)
     }
 
-    fn _noauth_decrypt_v3_message(&self) -> AgencyClientResult<String> {
-        EncryptionEnvelope::anon_unpack(self.payload()?)
+    fn _noauth_decrypt_v3_message(&self, agency_client: Option<&AgencyClient>) -> AgencyClientResult<String> {
+        EncryptionEnvelope::anon_unpack(agency_client, self.payload()?)
     }
 
-    fn _auth_decrypt_v3_message(&self, expected_sender_vk: &str) -> AgencyClientResult<String> {
-        EncryptionEnvelope::auth_unpack(self.payload()?, &expected_sender_vk)
+    fn _auth_decrypt_v3_message(&self, agency_client: Option<&AgencyClient>, expected_sender_vk: &str) -> AgencyClientResult<String> {
+        EncryptionEnvelope::auth_unpack(agency_client, self.payload()?, &expected_sender_vk)
     }
 }
 
-pub fn get_connection_messages(pw_did: &str, pw_vk: &str, agent_did: &str, agent_vk: &str, msg_uid: Option<Vec<String>>, status_codes: Option<Vec<MessageStatusCode>>) -> AgencyClientResult<Vec<Message>> {
+pub fn get_connection_messages(agency_client: Option<&AgencyClient>, pw_did: &str, pw_vk: &str, agent_did: &str, agent_vk: &str, msg_uid: Option<Vec<String>>, status_codes: Option<Vec<MessageStatusCode>>) -> AgencyClientResult<Vec<Message>> {
     trace!("get_connection_messages >>> pw_did: {}, pw_vk: {}, agent_vk: {}, msg_uid: {:?}",
            pw_did, pw_vk, agent_vk, msg_uid);
 
     let response = get_messages()
+        .agency_client(agency_client)?
         .to(&pw_did)?
         .to_vk(&pw_vk)?
         .agent_did(&agent_did)?
@@ -35,7 +35,9 @@ use serde::{de, Deserialize, Deserializer, ser, Serialize, Serializer};
 use serde_json::Value;
 
 use self::error::prelude::*;
+use self::agency_client::AgencyClient;
 use self::utils::libindy::crypto;
+use self::utils::wallet::get_client_wallet_handle;
 
 use self::agent_utils::{ComMethodUpdated, Connect, ConnectResponse, CreateAgent, CreateAgentResponse, SignUp, SignUpResponse, UpdateComMethod};
 use self::utils::validation;
@@ -515,14 +517,14 @@ impl A2AMessageKinds {
     }
 }
 
-pub fn prepare_message_for_agency(message: &A2AMessage, agency_did: &str) -> AgencyClientResult<Vec<u8>> {
-    pack_for_agency_v2(message, agency_did)
+pub fn prepare_message_for_agency(agency_client: Option<&AgencyClient>, message: &A2AMessage, agency_did: &str) -> AgencyClientResult<Vec<u8>> {
+    pack_for_agency_v2(agency_client, message, agency_did)
 }
 
-fn pack_for_agency_v2(message: &A2AMessage, agency_did: &str) -> AgencyClientResult<Vec<u8>> {
+fn pack_for_agency_v2(agency_client: Option<&AgencyClient>, message: &A2AMessage, agency_did: &str) -> AgencyClientResult<Vec<u8>> {
     trace!("pack_for_agency_v2 >>>");
-    let agent_vk = agency_settings::get_config_value(agency_settings::CONFIG_REMOTE_TO_SDK_VERKEY)?;
-    let my_vk = agency_settings::get_config_value(agency_settings::CONFIG_SDK_TO_REMOTE_VERKEY)?;
+    let agent_vk = agency_settings::get_client_config_value(agency_client, agency_settings::CONFIG_REMOTE_TO_SDK_VERKEY)?;
+    let my_vk = agency_settings::get_client_config_value(agency_client, agency_settings::CONFIG_SDK_TO_REMOTE_VERKEY)?;
 
     let message = ::serde_json::to_string(&message)
         .map_err(|err| AgencyClientError::from_msg(AgencyClientErrorKind::SerializationError, format!("Cannot serialize A2A message: {}", err)))?;
@@ -530,13 +532,13 @@ fn pack_for_agency_v2(message: &A2AMessage, agency_did: &str) -> AgencyClientRes
     let receiver_keys = ::serde_json::to_string(&vec![&agent_vk])
         .map_err(|err| AgencyClientError::from_msg(AgencyClientErrorKind::SerializationError, format!("Cannot serialize receiver keys: {}", err)))?;
 
-    let message = crypto::pack_message(Some(&my_vk), &receiver_keys, message.as_bytes())?;
+    let message = crypto::pack_message(get_client_wallet_handle(agency_client), Some(&my_vk), &receiver_keys, message.as_bytes())?;
 
-    prepare_forward_message(message, agency_did)
+    prepare_forward_message(agency_client, message, agency_did)
 }
 
-pub fn parse_message_from_response(response: &Vec<u8>) -> AgencyClientResult<String> {
-    let unpacked_msg = crypto::unpack_message(&response[..])?;
+pub fn parse_message_from_response(agency_client: Option<&AgencyClient>, response: &Vec<u8>) -> AgencyClientResult<String> {
+    let unpacked_msg = crypto::unpack_message(get_client_wallet_handle(agency_client), &response[..])?;
 
     let message: Value = ::serde_json::from_slice(unpacked_msg.as_slice())
         .map_err(|err| AgencyClientError::from_msg(AgencyClientErrorKind::InvalidJson, format!("Cannot deserialize response: {}", err)))?;
@@ -545,14 +547,14 @@ pub fn parse_message_from_response(response: &Vec<u8>) -> AgencyClientResult<Str
         .ok_or(AgencyClientError::from_msg(AgencyClientErrorKind::InvalidJson, "Cannot find `message` field on response"))?.to_string())
 }
 
-fn parse_response_from_agency(response: &Vec<u8>) -> AgencyClientResult<Vec<A2AMessage>> {
+fn parse_response_from_agency(agency_client: Option<&AgencyClient>, response: &Vec<u8>) -> AgencyClientResult<Vec<A2AMessage>> {
     trace!("parse_response_from_agency >>> processing payload of {} bytes", response.len());
 
     let message: String = if AgencyMockDecrypted::has_decrypted_mock_responses() {
         warn!("parse_response_from_agency_v2 >> retrieving decrypted mock response");
         AgencyMockDecrypted::get_next_decrypted_response()
     } else {
-        parse_message_from_response(response)?
+        parse_message_from_response(agency_client, response)?
     };
 
     trace!("AgencyComm Inbound V2 A2AMessage: {}", message);
@@ -620,29 +622,29 @@ pub fn bundle_from_u8(data: Vec<u8>) -> AgencyClientResult<Bundled<Vec<u8>>> {
         })
 }
 
-fn prepare_forward_message(message: Vec<u8>, did: &str) -> AgencyClientResult<Vec<u8>> {
+fn prepare_forward_message(agency_client: Option<&AgencyClient>, message: Vec<u8>, did: &str) -> AgencyClientResult<Vec<u8>> {
     trace!("prepare_forward_message >>>");
-    let agency_vk = agency_settings::get_config_value(agency_settings::CONFIG_AGENCY_VERKEY)?;
+    let agency_vk = agency_settings::get_client_config_value(agency_client, agency_settings::CONFIG_AGENCY_VERKEY)?;
 
     let message = ForwardV2::new(did.to_string(), message)?;
 
     match message {
-        A2AMessage::Version2(A2AMessageV2::Forward(msg)) => prepare_forward_message_for_agency_v2(&msg, &agency_vk),
+        A2AMessage::Version2(A2AMessageV2::Forward(msg)) => prepare_forward_message_for_agency_v2(agency_client, &msg, &agency_vk),
         _ => Err(AgencyClientError::from_msg(AgencyClientErrorKind::InvalidState, "Invalid message type"))
     }
 }
 
-fn prepare_forward_message_for_agency_v2(message: &ForwardV2, agency_vk: &str) -> AgencyClientResult<Vec<u8>> {
+fn prepare_forward_message_for_agency_v2(agency_client: Option<&AgencyClient>, message: &ForwardV2, agency_vk: &str) -> AgencyClientResult<Vec<u8>> {
     let message = serde_json::to_string(message)
         .map_err(|err| AgencyClientError::from_msg(AgencyClientErrorKind::SerializationError, format!("Cannot serialize Forward message: {}", err)))?;
 
     let receiver_keys = serde_json::to_string(&vec![agency_vk])
         .map_err(|err| AgencyClientError::from_msg(AgencyClientErrorKind::SerializationError, format!("Cannot serialize receiver keys: {}", err)))?;
 
-    crypto::pack_message(None, &receiver_keys, message.as_bytes())
+    crypto::pack_message(get_client_wallet_handle(agency_client), None, &receiver_keys, message.as_bytes())
 }
 
-fn prepare_message_for_agent(messages: Vec<A2AMessage>, pw_vk: &str, agent_did: &str, agent_vk: &str) -> AgencyClientResult<Vec<u8>> {
+fn prepare_message_for_agent(agency_client: Option<&AgencyClient>, messages: Vec<A2AMessage>, pw_vk: &str, agent_did: &str, agent_vk: &str) -> AgencyClientResult<Vec<u8>> {
     debug!("prepare_message_for_agent >> {:?}", messages);
     let message = messages.get(0)
         .ok_or(AgencyClientError::from_msg(AgencyClientErrorKind::SerializationError, "Cannot get message"))?;
@@ -653,14 +655,14 @@ fn prepare_message_for_agent(messages: Vec<A2AMessage>, pw_vk: &str, agent_did:
     let receiver_keys = serde_json::to_string(&vec![&agent_vk])
         .map_err(|err| AgencyClientError::from_msg(AgencyClientErrorKind::SerializationError, format!("Cannot receiver keys: {}", err)))?;
 
-    let message = crypto::pack_message(Some(pw_vk), &receiver_keys, message.as_bytes())?;
+    let message = crypto::pack_message(get_client_wallet_handle(agency_client), Some(pw_vk), &receiver_keys, message.as_bytes())?;
 
     /* forward to did */
     let message = ForwardV2::new(agent_did.to_owned(), message)?;
 
-    let to_did = agency_settings::get_config_value(agency_settings::CONFIG_REMOTE_TO_SDK_DID)?;
+    let to_did = agency_settings::get_client_config_value(agency_client, agency_settings::CONFIG_REMOTE_TO_SDK_DID)?;
 
-    pack_for_agency_v2(&message, &to_did)
+    pack_for_agency_v2(agency_client, &message, &to_did)
 }
 
 pub trait GeneralMessage {
@@ -3,6 +3,7 @@ use serde_json::Value;
 
 use crate::{prepare_message_for_agent, A2AMessageKinds, A2AMessageV2, A2AMessage, GeneralMessage, delete_connection, parse_response_from_agency};
 use crate::message_type::MessageTypes;
+use crate::agency_client::AgencyClient;
 use crate::error::{AgencyClientResult, AgencyClientErrorKind, AgencyClientError};
 use crate::utils::comm::post_to_agency;
 
@@ -60,6 +61,7 @@ pub struct DeleteConnectionBuilder {
     status_code: ConnectionStatus,
     agent_did: String,
     agent_vk: String,
+    agency_client: Option<AgencyClient>,
 }
 
 impl DeleteConnectionBuilder {
@@ -72,15 +74,21 @@ impl DeleteConnectionBuilder {
             status_code: ConnectionStatus::Deleted,
             agent_did: String::new(),
             agent_vk: String::new(),
+            agency_client: None,
         }
     }
 
+    pub fn agency_client(&mut self, agency_client: Option<&AgencyClient>) -> AgencyClientResult<&mut Self> {
+        self.agency_client = agency_client.cloned();
+        Ok(self)
+    }
+
     pub fn send_secure(&mut self) -> AgencyClientResult<()> {
         trace!("DeleteConnection::send >>>");
 
         let data = self.prepare_request()?;
 
-        let response = post_to_agency(&data)?;
+        let response = post_to_agency(self.agency_client.as_ref(), &data)?;
 
         self.parse_response(&response)
     }
@@ -88,7 +96,7 @@ impl DeleteConnectionBuilder {
     fn parse_response(&self, response: &Vec<u8>) -> AgencyClientResult<()> {
         trace!("parse_response >>>");
 
-        let mut response = parse_response_from_agency(response)?;
+        let mut response = parse_response_from_agency(self.agency_client.as_ref(), response)?;
 
         match response.remove(0) {
             A2AMessage::Version2(A2AMessageV2::UpdateConnectionResponse(_)) => Ok(()),
@@ -97,10 +105,11 @@ impl DeleteConnectionBuilder {
     }
 }
 
-pub fn send_delete_connection_message(pw_did: &str, pw_verkey: &str, agent_did: &str, agent_vk: &str) -> AgencyClientResult<()> {
+pub fn send_delete_connection_message(agency_client: Option<&AgencyClient>, pw_did: &str, pw_verkey: &str, agent_did: &str, agent_vk: &str) -> AgencyClientResult<()> {
     trace!("send_delete_connection_message >>>");
 
     delete_connection()
+        .agency_client(agency_client)?
         .to(pw_did)?
         .to_vk(pw_verkey)?
         .agent_did(agent_did)?
@@ -134,7 +143,7 @@ impl GeneralMessage for DeleteConnectionBuilder {
             )
         );
 
-        prepare_message_for_agent(vec![message], &self.to_vk, &self.agent_did, &self.agent_vk)
+        prepare_message_for_agent(self.agency_client.as_ref(), vec![message], &self.to_vk, &self.agent_did, &self.agent_vk)
     }
 }
 
@@ -1,4 +1,5 @@
 use crate::{mocking, MessageStatusCode, A2AMessageV2, A2AMessage, parse_response_from_agency, prepare_message_for_agency, agency_settings, A2AMessageKinds};
+use crate::agency_client::AgencyClient;
 use crate::error::{AgencyClientResult, AgencyClientErrorKind, AgencyClientError};
 use crate::message_type::MessageTypes;
 use crate::utils::comm::post_to_agency;
@@ -34,6 +35,7 @@ pub struct UIDsByConn {
 struct UpdateMessageStatusByConnectionsBuilder {
     status_code: Option<MessageStatusCode>,
     uids_by_conns: Vec<UIDsByConn>,
+    agency_client: Option<AgencyClient>,
 }
 
 impl UpdateMessageStatusByConnectionsBuilder {
@@ -43,6 +45,7 @@ impl UpdateMessageStatusByConnectionsBuilder {
         UpdateMessageStatusByConnectionsBuilder {
             status_code: None,
             uids_by_conns: Vec::new(),
+            agency_client: None,
         }
     }
 
@@ -58,6 +61,11 @@ impl UpdateMessageStatusByConnectionsBuilder {
         Ok(self)
     }
 
+    pub fn agency_client(&mut self, agency_client: Option<&AgencyClient>) -> AgencyClientResult<&mut Self> {
+        self.agency_client = agency_client.cloned();
+        Ok(self)
+    }
+
     pub fn send_secure(&mut self) -> AgencyClientResult<()> {
         trace!("UpdateMessages::send >>>");
 
@@ -65,7 +73,7 @@ impl UpdateMessageStatusByConnectionsBuilder {
 
         let data = self.prepare_request()?;
 
-        let response = post_to_agency(&data)?;
+        let response = post_to_agency(self.agency_client.as_ref(), &data)?;
 
         self.parse_response(&response)
     }
@@ -81,14 +89,14 @@ impl UpdateMessageStatusByConnectionsBuilder {
             )
         );
 
-        let agency_did = agency_settings::get_config_value(agency_settings::CONFIG_REMOTE_TO_SDK_DID)?;
-        prepare_message_for_agency(&message, &agency_did)
+        let agency_did = agency_settings::get_client_config_value(self.agency_client.as_ref(), agency_settings::CONFIG_REMOTE_TO_SDK_DID)?;
+        prepare_message_for_agency(self.agency_client.as_ref(), &message, &agency_did)
     }
 
     fn parse_response(&self, response: &Vec<u8>) -> AgencyClientResult<()> {
         trace!("UpdateMessageStatusByConnectionsBuilder::parse_response >>>");
 
-        let mut response = parse_response_from_agency(response)?;
+        let mut response = parse_response_from_agency(self.agency_client.as_ref(), response)?;
 
         match response.remove(0) {
             A2AMessage::Version2(A2AMessageV2::UpdateMessageStatusByConnectionsResponse(_)) => Ok(()),
@@ -108,10 +116,10 @@ pub fn update_agency_messages(status_code: &str, msg_json: &str) -> AgencyClient
     let uids_by_conns: Vec<UIDsByConn> = serde_json::from_str(msg_json)
         .map_err(|err| AgencyClientError::from_msg(AgencyClientErrorKind::InvalidJson, format!("Cannot deserialize UIDsByConn: {}", err)))?;
 
-    update_messages(status_code, uids_by_conns)
+    update_messages(None, status_code, uids_by_conns)
 }
 
-pub fn update_messages(status_code: MessageStatusCode, uids_by_conns: Vec<UIDsByConn>) -> AgencyClientResult<()> {
+pub fn update_messages(agency_client: Option<&AgencyClient>, status_code: MessageStatusCode, uids_by_conns: Vec<UIDsByConn>) -> AgencyClientResult<()> {
     trace!("update_messages >>> ");
 
     if mocking::agency_mocks_enabled() {
@@ -120,6 +128,7 @@ pub fn update_messages(status_code: MessageStatusCode, uids_by_conns: Vec<UIDsBy
     };
 
     UpdateMessageStatusByConnectionsBuilder::create()
+        .agency_client(agency_client)?
         .uids_by_conns(uids_by_conns)?
         .status_code(status_code)?
         .send_secure()
@@ -1,7 +1,8 @@
 use crate::{httpclient, agency_settings};
+use crate::agency_client::AgencyClient;
 use crate::error::AgencyClientResult;
 
-pub fn post_to_agency(body_content: &Vec<u8>) -> AgencyClientResult<Vec<u8>> {
-    let endpoint = agency_settings::get_config_value(agency_settings::CONFIG_AGENCY_ENDPOINT)?;
+pub fn post_to_agency(agency_client: Option<&AgencyClient>, body_content: &Vec<u8>) -> AgencyClientResult<Vec<u8>> {
+    let endpoint = agency_settings::get_client_config_value(agency_client, agency_settings::CONFIG_AGENCY_ENDPOINT)?;
     httpclient::post_message(body_content, &endpoint)
 }
@@ -1,3 +1,4 @@
+use crate::agency_client::AgencyClient;
 use crate::error::{AgencyClientErrorKind, AgencyClientResult, AgencyClientError};
 use crate::{A2AMessageV2, A2AMessage, parse_response_from_agency, prepare_message_for_agency, agency_settings, A2AMessageKinds, mocking};
 use crate::message_type::MessageTypes;
@@ -30,6 +31,7 @@ pub struct CreateKeyResponse {
 pub struct CreateKeyBuilder {
     for_did: String,
     for_verkey: String,
+    agency_client: Option<AgencyClient>,
 }
 
 impl CreateKeyBuilder {
@@ -39,6 +41,7 @@ impl CreateKeyBuilder {
         CreateKeyBuilder {
             for_did: String::new(),
             for_verkey: String::new(),
+            agency_client: None,
         }
     }
 
@@ -56,6 +59,11 @@ impl CreateKeyBuilder {
         Ok(self)
     }
 
+    pub fn agency_client(&mut self, agency_client: Option<&AgencyClient>) -> AgencyClientResult<&mut Self> {
+        self.agency_client = agency_client.cloned();
+        Ok(self)
+    }
+
     pub fn send_secure(&self) -> AgencyClientResult<(String, String)> {
         trace!("CreateKeyBuilder::send_secure >>>");
 
@@ -66,7 +74,7 @@ impl CreateKeyBuilder {
 
         let data = self.prepare_request()?;
 
-        let response = post_to_agency(&data)?;
+        let response = post_to_agency(self.agency_client.as_ref(), &data)?;
 
         self.parse_response(&response)
     }
@@ -81,13 +89,13 @@ impl CreateKeyBuilder {
             })
         );
 
-        let agency_did = agency_settings::get_config_value(agency_settings::CONFIG_REMOTE_TO_SDK_DID)?;
+        let agency_did = agency_settings::get_client_config_value(self.agency_client.as_ref(), agency_settings::CONFIG_REMOTE_TO_SDK_DID)?;
 
-        prepare_message_for_agency(&message, &agency_did)
+        prepare_message_for_agency(self.agency_client.as_ref(), &message, &agency_did)
     }
 
     fn parse_response(&self, response: &Vec<u8>) -> AgencyClientResult<(String, String)> {
-        let mut response = parse_response_from_agency(response)?;
+        let mut response = parse_response_from_agency(self.agency_client.as_ref(), response)?;
         match response.remove(0) {
             A2AMessage::Version2(A2AMessageV2::CreateKeyResponse(res)) => Ok((res.for_did, res.for_verkey)),
             _ => Err(AgencyClientError::from(AgencyClientErrorKind::InvalidHttpResponse))
@@ -1,15 +1,17 @@
+use crate::agency_client::AgencyClient;
 use crate::error::{AgencyClientErrorKind, AgencyClientError, AgencyClientResult};
 use crate::mocking::AgencyMockDecrypted;
 use crate::utils::libindy::crypto;
+use crate::utils::wallet::get_client_wallet_handle;
 
 #[derive(Debug)]
 pub struct EncryptionEnvelope(pub Vec<u8>);
 
 impl EncryptionEnvelope {
-    fn _unpack_a2a_message(payload: Vec<u8>) -> AgencyClientResult<(String, Option<String>)> {
+    fn _unpack_a2a_message(agency_client: Option<&AgencyClient>, payload: Vec<u8>) -> AgencyClientResult<(String, Option<String>)> {
         trace!("EncryptionEnvelope::_unpack_a2a_message >>> processing payload of {} bytes", payload.len());
 
-        let unpacked_msg = crypto::unpack_message(&payload)?;
+        let unpacked_msg = crypto::unpack_message(get_client_wallet_handle(agency_client), &payload)?;
 
         let msg_value: ::serde_json::Value = ::serde_json::from_slice(unpacked_msg.as_slice())
             .map_err(|err| AgencyClientError::from_msg(AgencyClientErrorKind::InvalidJson, format!("Cannot deserialize message: {}", err)))?;
@@ -27,26 +29,26 @@ impl EncryptionEnvelope {
     }
 
     // todo: we should use auth_unpack wherever possible
-    pub fn anon_unpack(payload: Vec<u8>) -> AgencyClientResult<String> {
+    pub fn anon_unpack(agency_client: Option<&AgencyClient>, payload: Vec<u8>) -> AgencyClientResult<String> {
         trace!("EncryptionEnvelope::anon_unpack >>> processing payload of {} bytes", payload.len());
         if AgencyMockDecrypted::has_decrypted_mock_messages() {
             trace!("EncryptionEnvelope::anon_unpack >>> returning decrypted mock message");
             Ok(AgencyMockDecrypted::get_next_decrypted_message())
         } else {
-            let (a2a_message, _sender_vk) = Self::_unpack_a2a_message(payload)?;
+            let (a2a_message, _sender_vk) = Self::_unpack_a2a_message(agency_client, payload)?;
             trace!("EncryptionEnvelope::anon_unpack >>> a2a_message: {:?}", a2a_message);
             Ok(a2a_message)
         }
     }
 
-    pub fn auth_unpack(payload: Vec<u8>, expected_vk: &str) -> AgencyClientResult<String> {
+    pub fn auth_unpack(agency_client: Option<&AgencyClient>, payload: Vec<u8>, expected_vk: &str) -> AgencyClientResult<String> {
         trace!("EncryptionEnvelope::auth_unpack >>> processing payload of {} bytes, expected_vk={}", payload.len(), expected_vk);
 
         if AgencyMockDecrypted::has_decrypted_mock_messages() {
             trace!("EncryptionEnvelope::auth_unpack >>> returning decrypted mock message");
             Ok(AgencyMockDecrypted::get_next_decrypted_message())
         } else {
-            let (a2a_message, sender_vk) = Self::_unpack_a2a_message(payload)?;
+            let (a2a_message, sender_vk) = Self::_unpack_a2a_message(agency_client, payload)?;
             trace!("EncryptionEnvelope::auth_unpack >>> a2a_message: {:?}, sender_vk: {:?}", a2a_message, sender_vk);
 
             match sender_vk {
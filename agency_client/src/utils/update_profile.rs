@@ -78,7 +78,7 @@ impl UpdateProfileDataBuilder {
 
         let data = self.prepare_request()?;
 
-        let response = post_to_agency(&data)?;
+        let response = post_to_agency(None, &data)?;
 
         self.parse_response(response)
     }
@@ -95,11 +95,11 @@ impl UpdateProfileDataBuilder {
 
         let agency_did = agency_settings::get_config_value(agency_settings::CONFIG_REMOTE_TO_SDK_DID)?;
 
-        prepare_message_for_agency(&message, &agency_did)
+        prepare_message_for_agency(None, &message, &agency_did)
     }
 
     fn parse_response(&self, response: Vec<u8>) -> AgencyClientResult<()> {
-        let mut response = parse_response_from_agency(&response)?;
+        let mut response = parse_response_from_agency(None, &response)?;
 
         match response.remove(0) {
             A2AMessage::Version2(A2AMessageV2::UpdateConfigsResponse(_)) => Ok(()),
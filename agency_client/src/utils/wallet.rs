@@ -1,13 +1,27 @@
+use std::sync::atomic::{AtomicI32, Ordering};
+
 use indy::{INVALID_WALLET_HANDLE, WalletHandle};
 
-pub static mut WALLET_HANDLE: WalletHandle = INVALID_WALLET_HANDLE;
+use crate::agency_client::AgencyClient;
+
+static WALLET_HANDLE: AtomicI32 = AtomicI32::new(INVALID_WALLET_HANDLE.0);
 
 pub fn set_wallet_handle(handle: WalletHandle) -> WalletHandle {
      trace!("set_wallet_handle >>> handle: {:?}", handle);
-    unsafe { WALLET_HANDLE = handle; }
-    unsafe { WALLET_HANDLE }
+    WALLET_HANDLE.store(handle.0, Ordering::SeqCst);
+    handle
 }
 
-pub fn get_wallet_handle() -> WalletHandle { unsafe { WALLET_HANDLE } }
+pub fn get_wallet_handle() -> WalletHandle { WalletHandle(WALLET_HANDLE.load(Ordering::SeqCst)) }
+
+/**
+Wallet of given agency client, or the global wallet if no client is given.
+ */
+pub fn get_client_wallet_handle(agency_client: Option<&AgencyClient>) -> WalletHandle {
+    agency_client
+        .and_then(|agency_client| agency_client.get_wallet_handle().ok())
+        .map(WalletHandle)
+        .unwrap_or_else(get_wallet_handle)
+}
 
 pub fn reset_wallet_handle() { trace!("Resetting wallet handle"); set_wallet_handle(INVALID_WALLET_HANDLE); }
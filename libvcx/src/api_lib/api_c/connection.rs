@@ -89,6 +89,7 @@ use crate::utils::error;
 
     aries:
         Invitation - https://github.com/hyperledger/aries-rfcs/tree/master/features/0160-connection-protocol#0-invitation-to-connect
+        OutOfBandInvitation - https://github.com/hyperledger/aries-rfcs/tree/master/features/0434-outofband#messages
         ConnectionRequest - https://github.com/hyperledger/aries-rfcs/tree/master/features/0160-connection-protocol#1-connection-request
         ConnectionResponse - https://github.com/hyperledger/aries-rfcs/tree/master/features/0160-connection-protocol#2-connection-response
         ConnectionProblemReport - https://github.com/hyperledger/aries-rfcs/tree/master/features/0160-connection-protocol#error-message-example
@@ -157,19 +158,46 @@ pub extern fn vcx_connection_delete_connection(command_handle: CommandHandle,
 /// # Returns
 /// Error code as a u32
 #[no_mangle]
-#[allow(unused_assignments)]
 pub extern fn vcx_connection_create(command_handle: CommandHandle,
                                     source_id: *const c_char,
                                     cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, connection_handle: u32)>) -> u32 {
     info!("vcx_connection_create >>>");
 
+    _vcx_connection_create(command_handle, None, source_id, cb)
+}
+
+/// Same as `vcx_connection_create`, the object is created within the profile and bound to it.
+/// Every operation on the object runs within the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_connection_create`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_connection_create_in_profile(command_handle: CommandHandle,
+                                               profile_handle: u32,
+                                               source_id: *const c_char,
+                                               cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, connection_handle: u32)>) -> u32 {
+    info!("vcx_connection_create_in_profile >>>");
+
+    _vcx_connection_create(command_handle, Some(profile_handle), source_id, cb)
+}
+
+#[allow(unused_assignments)]
+fn _vcx_connection_create(command_handle: CommandHandle,
+                          profile_handle: Option<u32>,
+                          source_id: *const c_char,
+                          cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, connection_handle: u32)>) -> u32 {
     check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
     check_useful_c_str!(source_id, VcxErrorKind::InvalidOption);
 
     trace!("vcx_connection_create(command_handle: {}, source_id: {})", command_handle, source_id);
 
     execute(move || {
-        match create_connection(&source_id) {
+        match create_connection_in_profile(profile_handle, &source_id) {
             Ok(handle) => {
                 trace!("vcx_connection_create_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
                        command_handle, error::SUCCESS.message, handle, source_id);
@@ -188,6 +216,84 @@ pub extern fn vcx_connection_create(command_handle: CommandHandle,
     error::SUCCESS.code_num
 }
 
+/// Create a Connection object that provides a pairwise connection for an institution's user
+/// established via the given handshake protocol.
+///
+/// # Params
+/// command_handle: command handle to map callback to user context.
+///
+/// source_id: institution's personal identification for the connection
+///
+/// handshake_protocol: handshake protocol to be used: "connections/1.0" | "didexchange/1.0"
+///     For "didexchange/1.0" the invitation is generated as out-of-band invitation.
+///
+/// cb: Callback that provides connection handle and error status of request
+///
+/// # Returns
+/// Error code as a u32
+#[no_mangle]
+#[allow(unused_assignments)]
+pub extern fn vcx_connection_create_with_handshake_protocol(command_handle: CommandHandle,
+                                                            source_id: *const c_char,
+                                                            handshake_protocol: *const c_char,
+                                                            cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, connection_handle: u32)>) -> u32 {
+    info!("vcx_connection_create_with_handshake_protocol >>>");
+
+    _vcx_connection_create_with_handshake_protocol(command_handle, None, source_id, handshake_protocol, cb)
+}
+
+/// Same as `vcx_connection_create_with_handshake_protocol`, the object is created within the profile and bound to it.
+/// Every operation on the object runs within the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_connection_create_with_handshake_protocol`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_connection_create_with_handshake_protocol_in_profile(command_handle: CommandHandle,
+                                                                       profile_handle: u32,
+                                                                       source_id: *const c_char,
+                                                                       handshake_protocol: *const c_char,
+                                                                       cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, connection_handle: u32)>) -> u32 {
+    info!("vcx_connection_create_with_handshake_protocol_in_profile >>>");
+
+    _vcx_connection_create_with_handshake_protocol(command_handle, Some(profile_handle), source_id, handshake_protocol, cb)
+}
+
+fn _vcx_connection_create_with_handshake_protocol(command_handle: CommandHandle,
+                                                  profile_handle: Option<u32>,
+                                                  source_id: *const c_char,
+                                                  handshake_protocol: *const c_char,
+                                                  cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, connection_handle: u32)>) -> u32 {
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(source_id, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(handshake_protocol, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_connection_create_with_handshake_protocol(command_handle: {}, source_id: {}, handshake_protocol: {})", command_handle, source_id, handshake_protocol);
+
+    execute(move || {
+        match create_connection_with_handshake_protocol_in_profile(profile_handle, &source_id, &handshake_protocol) {
+            Ok(handle) => {
+                trace!("vcx_connection_create_with_handshake_protocol_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
+                       command_handle, error::SUCCESS.message, handle, source_id);
+                cb(command_handle, error::SUCCESS.code_num, handle);
+            }
+            Err(x) => {
+                warn!("vcx_connection_create_with_handshake_protocol_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
+                      command_handle, x, 0, source_id);
+                cb(command_handle, x.into(), 0);
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
 /// Create a Connection object from the given invite_details that provides a pairwise connection.
 ///
 /// # Params
@@ -209,6 +315,26 @@ pub extern fn vcx_connection_create(command_handle: CommandHandle,
 ///         "serviceEndpoint": "https://example.com/endpoint",
 ///         "routingKeys": ["8HH5gYEeNc3z7PYXmd54d4x6qAfCNrqQqEB3nS7Zfu7K"]
 ///      }
+///     aries out-of-band: https://github.com/hyperledger/aries-rfcs/tree/master/features/0434-outofband#messages
+///      {
+///         "@type": "did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/out-of-band/1.0/invitation",
+///         "@id": "69212a3a-d068-4f9d-a2dd-4741bca89af3",
+///         "label": "Faber College",
+///         "handshake_protocols": ["did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/connections/1.0"],
+///         "services": [{
+///             "id": "#inline",
+///             "type": "did-communication",
+///             "recipientKeys": ["did:key:z6MkpTHR8VNsBxYAAWHut2Geadd9jSwuBV8xRoAnwWsdvktH"],
+///             "serviceEndpoint": "https://example.com/endpoint"
+///         }]
+///      }
+///     aries public DID (keys and endpoint resolved from the ledger):
+///      {
+///         "@type": "did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/connections/1.0/invitation",
+///         "@id": "69212a3a-d068-4f9d-a2dd-4741bca89af3",
+///         "label": "Faber College",
+///         "did": "did:sov:V4SGRU86Z58d6TV7PBUe6f"
+///      }
 ///
 /// # Returns
 /// Error code as a u32
@@ -219,12 +345,41 @@ pub extern fn vcx_connection_create_with_invite(command_handle: CommandHandle,
                                                 cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, connection_handle: u32)>) -> u32 {
     info!("vcx_connection_create_with_invite >>>");
 
+    _vcx_connection_create_with_invite(command_handle, None, source_id, invite_details, cb)
+}
+
+/// Same as `vcx_connection_create_with_invite`, the object is created within the profile and bound to it.
+/// Every operation on the object runs within the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_connection_create_with_invite`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_connection_create_with_invite_in_profile(command_handle: CommandHandle,
+                                                           profile_handle: u32,
+                                                           source_id: *const c_char,
+                                                           invite_details: *const c_char,
+                                                           cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, connection_handle: u32)>) -> u32 {
+    info!("vcx_connection_create_with_invite_in_profile >>>");
+
+    _vcx_connection_create_with_invite(command_handle, Some(profile_handle), source_id, invite_details, cb)
+}
+
+fn _vcx_connection_create_with_invite(command_handle: CommandHandle,
+                                      profile_handle: Option<u32>,
+                                      source_id: *const c_char,
+                                      invite_details: *const c_char,
+                                      cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, connection_handle: u32)>) -> u32 {
     check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
     check_useful_c_str!(source_id, VcxErrorKind::InvalidOption);
     check_useful_c_str!(invite_details, VcxErrorKind::InvalidOption);
     trace!("vcx_connection_create_with_invite(command_handle: {}, source_id: {})", command_handle, source_id);
     execute(move || {
-        match create_connection_with_invite(&source_id, &invite_details) {
+        match create_connection_with_invite_in_profile(profile_handle, &source_id, &invite_details) {
             Ok(handle) => {
                 trace!("vcx_connection_create_with_invite_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
                        command_handle, error::SUCCESS.message, handle, source_id);
@@ -243,6 +398,80 @@ pub extern fn vcx_connection_create_with_invite(command_handle: CommandHandle,
     error::SUCCESS.code_num
 }
 
+/// Create a Connection object with the institution which published its DID on the ledger.
+/// Verkey and endpoint of the DID are resolved from the ledger (NYM and "endpoint" ATTRIB).
+///
+/// # Params
+/// command_handle: command handle to map callback to user context.
+///
+/// source_id: institution's personal identification for the connection
+///
+/// public_did: public DID of the institution to connect to
+///
+/// cb: Callback that provides connection handle and error status of request
+///
+/// # Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_connection_create_with_public_did(command_handle: CommandHandle,
+                                                    source_id: *const c_char,
+                                                    public_did: *const c_char,
+                                                    cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, connection_handle: u32)>) -> u32 {
+    info!("vcx_connection_create_with_public_did >>>");
+
+    _vcx_connection_create_with_public_did(command_handle, None, source_id, public_did, cb)
+}
+
+/// Same as `vcx_connection_create_with_public_did`, the object is created within the profile and bound to it.
+/// Every operation on the object runs within the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_connection_create_with_public_did`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_connection_create_with_public_did_in_profile(command_handle: CommandHandle,
+                                                               profile_handle: u32,
+                                                               source_id: *const c_char,
+                                                               public_did: *const c_char,
+                                                               cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, connection_handle: u32)>) -> u32 {
+    info!("vcx_connection_create_with_public_did_in_profile >>>");
+
+    _vcx_connection_create_with_public_did(command_handle, Some(profile_handle), source_id, public_did, cb)
+}
+
+fn _vcx_connection_create_with_public_did(command_handle: CommandHandle,
+                                          profile_handle: Option<u32>,
+                                          source_id: *const c_char,
+                                          public_did: *const c_char,
+                                          cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, connection_handle: u32)>) -> u32 {
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(source_id, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(public_did, VcxErrorKind::InvalidDid);
+    trace!("vcx_connection_create_with_public_did(command_handle: {}, source_id: {}, public_did: {})", command_handle, source_id, public_did);
+    execute(move || {
+        match create_connection_with_public_did_in_profile(profile_handle, &source_id, &public_did) {
+            Ok(handle) => {
+                trace!("vcx_connection_create_with_public_did_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
+                       command_handle, error::SUCCESS.message, handle, source_id);
+                cb(command_handle, error::SUCCESS.code_num, handle);
+            }
+            Err(x) => {
+                warn!("vcx_connection_create_with_public_did_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
+                      command_handle, x, 0, source_id);
+                cb(command_handle, x.into(), 0);
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
 /// Establishes connection between institution and its user
 ///
 /// # Params
@@ -432,13 +661,40 @@ pub extern fn vcx_connection_deserialize(command_handle: CommandHandle,
                                          cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, connection_handle: u32)>) -> u32 {
     info!("vcx_connection_deserialize >>>");
 
+    _vcx_connection_deserialize(command_handle, None, connection_data, cb)
+}
+
+/// Same as `vcx_connection_deserialize`, the object is created within the profile and bound to it.
+/// Every operation on the object runs within the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_connection_deserialize`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_connection_deserialize_in_profile(command_handle: CommandHandle,
+                                                    profile_handle: u32,
+                                                    connection_data: *const c_char,
+                                                    cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, connection_handle: u32)>) -> u32 {
+    info!("vcx_connection_deserialize_in_profile >>>");
+
+    _vcx_connection_deserialize(command_handle, Some(profile_handle), connection_data, cb)
+}
+
+fn _vcx_connection_deserialize(command_handle: CommandHandle,
+                               profile_handle: Option<u32>,
+                               connection_data: *const c_char,
+                               cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, connection_handle: u32)>) -> u32 {
     check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
     check_useful_c_str!(connection_data, VcxErrorKind::InvalidOption);
 
     trace!("vcx_connection_deserialize(command_handle: {}, connection_data: {})", command_handle, connection_data);
 
     execute(move || {
-        let (rc, handle) = match from_string(&connection_data) {
+        let (rc, handle) = match from_string_in_profile(profile_handle, &connection_data) {
             Ok(x) => {
                 let source_id = get_source_id(x).unwrap_or_default();
                 trace!("vcx_connection_deserialize_cb(command_handle: {}, rc: {}, handle: {}), source_id: {:?}",
@@ -578,6 +834,56 @@ pub extern fn vcx_connection_update_state_with_message(command_handle: CommandHa
     error::SUCCESS.code_num
 }
 
+/// Unpack a message received directly from connection counterparty (without cloud agency)
+/// and resolve the connection it belongs to by recipient verkey.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// payload_raw: raw packed message buffer
+///
+/// payload_len: length of packed message buffer
+///
+/// cb: Callback that provides handle of the connection the message was sent to and the unpacked message.
+///     The message can be passed to `vcx_connection_update_state_with_message` or to update_state_with_message
+///     functions of other objects.
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_connection_process_packed_message(command_handle: CommandHandle,
+                                                    payload_raw: *const u8,
+                                                    payload_len: u32,
+                                                    cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, connection_handle: u32, message: *const c_char)>) -> u32 {
+    info!("vcx_connection_process_packed_message >>>");
+
+    check_useful_c_byte_array!(payload_raw, payload_len, VcxErrorKind::InvalidOption, VcxErrorKind::InvalidOption);
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_connection_process_packed_message(command_handle: {}, payload_len: {})", command_handle, payload_len);
+
+    execute(move || {
+        match process_packed_message(payload_raw) {
+            Ok((connection_handle, message)) => {
+                let message = json!(message).to_string();
+                trace!("vcx_connection_process_packed_message_cb(command_handle: {}, rc: {}, connection_handle: {}, message: {})",
+                       command_handle, error::SUCCESS.message, connection_handle, message);
+                let message = CStringUtils::string_to_cstring(message);
+                cb(command_handle, error::SUCCESS.code_num, connection_handle, message.as_ptr());
+            }
+            Err(x) => {
+                warn!("vcx_connection_process_packed_message_cb(command_handle: {}, rc: {}, connection_handle: {}, message: null)",
+                      command_handle, x, 0);
+                cb(command_handle, x.into(), 0, ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
 /// Returns the current internal state of the connection. Does NOT query agency for state updates.
 ///     Possible states:
 ///         1 - Initialized
@@ -844,8 +1150,13 @@ pub extern fn vcx_connection_sign_data(command_handle: CommandHandle,
         Err(e) => return e.into(),
     };
 
+    let wallet_handle = match connection::get_wallet_handle(connection_handle) {
+        Ok(x) => x,
+        Err(e) => return e.into(),
+    };
+
     execute(move || {
-        match libindy::utils::crypto::sign(&vk, &data_raw) {
+        match libindy::utils::crypto::sign(wallet_handle, &vk, &data_raw) {
             Ok(x) => {
                 trace!("vcx_connection_sign_data_cb(command_handle: {}, connection_handle: {}, rc: {}, signature: {:?})",
                        command_handle, connection_handle, error::SUCCESS.message, x);
@@ -1211,10 +1522,11 @@ mod tests {
     use serde_json::Value;
 
     use crate::agency_client::mocking::AgencyMockDecrypted;
-    use crate::api_lib::api_handle::connection::tests::{build_test_connection_inviter_invited, build_test_connection_inviter_null, build_test_connection_inviter_requested};
+    use crate::api_lib::api_handle::connection::tests::{build_test_connection_inviter_invited, build_test_connection_inviter_null, build_test_connection_inviter_requested, with_unique_pw_verkey};
     use crate::api_lib::utils_c::return_types_u32;
     use crate::api_lib::utils_c::timeout::TimeoutUtils;
     use crate::api_lib::VcxStateType;
+    use crate::aries::utils::encryption_envelope::tests::_packed_message;
     use crate::utils::constants::{DELETE_CONNECTION_DECRYPTED_RESPONSE, GET_MESSAGES_DECRYPTED_RESPONSE};
     use crate::utils::devsetup::*;
     use crate::utils::error;
@@ -1252,6 +1564,20 @@ mod tests {
         assert_eq!(rc, error::INVALID_OPTION.code_num);
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_connection_create_with_handshake_protocol() {
+        let _setup = SetupMocks::init();
+
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        let _rc = vcx_connection_create_with_handshake_protocol(cb.command_handle,
+                                                                CString::new("test_create").unwrap().into_raw(),
+                                                                CString::new("didexchange/1.0").unwrap().into_raw(),
+                                                                Some(cb.get_callback()));
+
+        assert!(cb.receive(TimeoutUtils::some_medium()).unwrap() > 0);
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_vcx_connection_connect() {
@@ -1330,6 +1656,27 @@ mod tests {
         assert_eq!(cb.receive(TimeoutUtils::some_medium()).unwrap(), VcxStateType::VcxStateAccepted as u32);
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_connection_process_packed_message() {
+        let _setup = SetupMocks::init();
+
+        let handle = with_unique_pw_verkey(build_test_connection_inviter_requested());
+        let pw_vk = get_pw_verkey(handle).unwrap();
+        let payload = _packed_message(vec![&pw_vk]);
+
+        AgencyMockDecrypted::set_next_decrypted_message(ARIES_CONNECTION_ACK);
+        let cb = return_types_u32::Return_U32_U32_STR::new().unwrap();
+        let rc = vcx_connection_process_packed_message(cb.command_handle, payload.as_ptr(), payload.len() as u32, Some(cb.get_callback()));
+        assert_eq!(rc, error::SUCCESS.code_num);
+        let (connection_handle, message) = cb.receive(TimeoutUtils::some_medium()).unwrap();
+        assert_eq!(pw_vk, get_pw_verkey(connection_handle).unwrap());
+        assert_eq!(serde_json::from_str::<A2AMessage>(ARIES_CONNECTION_ACK).unwrap(), serde_json::from_str::<A2AMessage>(&message.unwrap()).unwrap());
+
+        let rc = vcx_connection_process_packed_message(0, ptr::null(), 0, Some(cb.get_callback()));
+        assert_eq!(rc, error::INVALID_OPTION.code_num);
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_vcx_connection_update_state_fails() {
@@ -18,7 +18,12 @@ use crate::utils::error;
 
     The set of object states, messages and transitions depends on the communication method is used.
     The communication method can be specified as a config option on one of *_init functions.
-        VcxStateType::VcxStateRequestReceived - once `vcx_credential_create_with_offer` (create Credential object) is called.
+        VcxStateType::VcxStateInitialized - once `vcx_credential_create_with_proposal` (create Credential object) is called.
+
+        VcxStateType::VcxStateOfferSent - once `vcx_credential_send_proposal` (send `CredentialProposal` message) is called.
+
+        VcxStateType::VcxStateRequestReceived - once `vcx_credential_create_with_offer` (create Credential object) is called
+                                                or `CredentialOffer` message is received in response to `CredentialProposal`.
 
         VcxStateType::VcxStateOfferSent - once `vcx_credential_send_request` (send `CredentialRequest` message) is called.
 
@@ -29,6 +34,12 @@ use crate::utils::error;
     # Transitions
     RFC - https://github.com/hyperledger/aries-rfcs/tree/7b6b93acbaf9611d3c892c4bada142fe2613de6e/features/0036-issue-credential
         VcxStateType::None - `vcx_credential_create_with_offer` - VcxStateType::VcxStateRequestReceived
+        VcxStateType::None - `vcx_credential_create_with_proposal` - VcxStateType::VcxStateInitialized
+
+        VcxStateType::VcxStateInitialized - `vcx_credential_send_proposal` - VcxStateType::VcxStateOfferSent
+
+        VcxStateType::VcxStateOfferSent (proposal sent) - received `CredentialOffer` - VcxStateType::VcxStateRequestReceived
+        VcxStateType::VcxStateOfferSent (proposal sent) - received `ProblemReport` - VcxStateType::None
 
         VcxStateType::VcxStateRequestReceived - `vcx_issuer_send_credential_offer` - VcxStateType::VcxStateOfferSent
 
@@ -102,13 +113,42 @@ pub extern fn vcx_credential_get_payment_info(command_handle: CommandHandle,
 /// #Returns
 /// Error code as a u32
 #[no_mangle]
-#[allow(unused_variables, unused_mut)]
 pub extern fn vcx_credential_create_with_offer(command_handle: CommandHandle,
                                                source_id: *const c_char,
                                                offer: *const c_char,
                                                cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credential_handle: u32)>) -> u32 {
     info!("vcx_credential_create_with_offer >>>");
 
+    _vcx_credential_create_with_offer(command_handle, None, source_id, offer, cb)
+}
+
+/// Same as `vcx_credential_create_with_offer`, the object is created within the profile and bound to it.
+/// Every operation on the object runs within the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_credential_create_with_offer`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_credential_create_with_offer_in_profile(command_handle: CommandHandle,
+                                                          profile_handle: u32,
+                                                          source_id: *const c_char,
+                                                          offer: *const c_char,
+                                                          cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credential_handle: u32)>) -> u32 {
+    info!("vcx_credential_create_with_offer_in_profile >>>");
+
+    _vcx_credential_create_with_offer(command_handle, Some(profile_handle), source_id, offer, cb)
+}
+
+#[allow(unused_variables, unused_mut)]
+fn _vcx_credential_create_with_offer(command_handle: CommandHandle,
+                                     profile_handle: Option<u32>,
+                                     source_id: *const c_char,
+                                     offer: *const c_char,
+                                     cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credential_handle: u32)>) -> u32 {
     check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
     check_useful_c_str!(source_id, VcxErrorKind::InvalidOption);
     check_useful_c_str!(offer, VcxErrorKind::InvalidOption);
@@ -117,7 +157,7 @@ pub extern fn vcx_credential_create_with_offer(command_handle: CommandHandle,
            command_handle, source_id, secret!(&offer));
 
     execute(move || {
-        match credential::credential_create_with_offer(&source_id, &offer) {
+        match credential::credential_create_with_offer_in_profile(profile_handle, &source_id, &offer) {
             Ok(x) => {
                 trace!("vcx_credential_create_with_offer_cb(command_handle: {}, source_id: {}, rc: {}, handle: {})",
                        command_handle, source_id, error::SUCCESS.message, x);
@@ -136,6 +176,140 @@ pub extern fn vcx_credential_create_with_offer(command_handle: CommandHandle,
     error::SUCCESS.code_num
 }
 
+/// Create a Credential object that proposes a credential to the issuer (holder-initiated issuance).
+/// Issuer answers with a credential offer on the same thread.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// source_id: Institution's personal identification for the credential, should be unique.
+///
+/// proposal: credential proposal to be sent to the issuer
+///
+/// # Example
+/// proposal ->
+///     {"@type":"did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/issue-credential/1.0/propose-credential", "@id":"<uuid-of-proposal-message>", "comment":"somecomment", "credential_proposal":<json-ldobject>, "schema_id":"<schema-id>", "cred_def_id":"<cred-def-id>"}
+///
+/// cb: Callback that provides credential handle or error status
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_credential_create_with_proposal(command_handle: CommandHandle,
+                                                  source_id: *const c_char,
+                                                  proposal: *const c_char,
+                                                  cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credential_handle: u32)>) -> u32 {
+    info!("vcx_credential_create_with_proposal >>>");
+
+    _vcx_credential_create_with_proposal(command_handle, None, source_id, proposal, cb)
+}
+
+/// Same as `vcx_credential_create_with_proposal`, the object is created within the profile and bound to it.
+/// Every operation on the object runs within the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_credential_create_with_proposal`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_credential_create_with_proposal_in_profile(command_handle: CommandHandle,
+                                                             profile_handle: u32,
+                                                             source_id: *const c_char,
+                                                             proposal: *const c_char,
+                                                             cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credential_handle: u32)>) -> u32 {
+    info!("vcx_credential_create_with_proposal_in_profile >>>");
+
+    _vcx_credential_create_with_proposal(command_handle, Some(profile_handle), source_id, proposal, cb)
+}
+
+fn _vcx_credential_create_with_proposal(command_handle: CommandHandle,
+                                        profile_handle: Option<u32>,
+                                        source_id: *const c_char,
+                                        proposal: *const c_char,
+                                        cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credential_handle: u32)>) -> u32 {
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(source_id, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(proposal, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_credential_create_with_proposal(command_handle: {}, source_id: {}, proposal: {})",
+           command_handle, source_id, secret!(&proposal));
+
+    execute(move || {
+        match credential::credential_create_with_proposal_in_profile(profile_handle, &source_id, &proposal) {
+            Ok(x) => {
+                trace!("vcx_credential_create_with_proposal_cb(command_handle: {}, source_id: {}, rc: {}, handle: {})",
+                       command_handle, source_id, error::SUCCESS.message, x);
+                cb(command_handle, error::SUCCESS.code_num, x)
+            }
+            Err(x) => {
+                warn!("vcx_credential_create_with_proposal_cb(command_handle: {}, source_id: {}, rc: {}, handle: {})",
+                      command_handle, source_id, x, 0);
+                cb(command_handle, x.into(), 0);
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Send the credential proposal to the issuer.
+///
+/// #params
+/// command_handle: command handle to map callback to user context
+///
+/// credential_handle: credential handle that was provided during creation by `vcx_credential_create_with_proposal`
+///
+/// connection_handle: Connection handle that identifies pairwise connection
+///
+/// cb: Callback that provides error status of credential proposal
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_credential_send_proposal(command_handle: CommandHandle,
+                                           credential_handle: u32,
+                                           connection_handle: u32,
+                                           cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_credential_send_proposal >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    if !credential::is_valid_handle(credential_handle) {
+        return VcxError::from(VcxErrorKind::InvalidCredentialHandle).into();
+    }
+
+    if !connection::is_valid_handle(connection_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    let source_id = credential::get_source_id(credential_handle).unwrap_or_default();
+    trace!("vcx_credential_send_proposal(command_handle: {}, credential_handle: {}, connection_handle: {}), source_id: {:?}",
+           command_handle, credential_handle, connection_handle, source_id);
+
+    execute(move || {
+        match credential::send_credential_proposal(credential_handle, connection_handle) {
+            Ok(x) => {
+                trace!("vcx_credential_send_proposal_cb(command_handle: {}, rc: {}) source_id: {}",
+                       command_handle, x.to_string(), source_id);
+                cb(command_handle, x);
+            }
+            Err(e) => {
+                warn!("vcx_credential_send_proposal_cb(command_handle: {}, rc: {}) source_id: {}",
+                      command_handle, e, source_id);
+                cb(command_handle, e.into());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
 
 /// Retrieve information about a stored credential in user's wallet, including credential id and the credential itself.
 ///
@@ -913,6 +1087,33 @@ pub extern fn vcx_credential_deserialize(command_handle: CommandHandle,
                                          cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, handle: u32)>) -> u32 {
     info!("vcx_credential_deserialize >>>");
 
+    _vcx_credential_deserialize(command_handle, None, credential_data, cb)
+}
+
+/// Same as `vcx_credential_deserialize`, the object is created within the profile and bound to it.
+/// Every operation on the object runs within the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_credential_deserialize`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_credential_deserialize_in_profile(command_handle: CommandHandle,
+                                                    profile_handle: u32,
+                                                    credential_data: *const c_char,
+                                                    cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, handle: u32)>) -> u32 {
+    info!("vcx_credential_deserialize_in_profile >>>");
+
+    _vcx_credential_deserialize(command_handle, Some(profile_handle), credential_data, cb)
+}
+
+fn _vcx_credential_deserialize(command_handle: CommandHandle,
+                               profile_handle: Option<u32>,
+                               credential_data: *const c_char,
+                               cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, handle: u32)>) -> u32 {
     check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
     check_useful_c_str!(credential_data, VcxErrorKind::InvalidOption);
 
@@ -920,7 +1121,7 @@ pub extern fn vcx_credential_deserialize(command_handle: CommandHandle,
            command_handle, credential_data);
 
     execute(move || {
-        match credential::from_string(&credential_data) {
+        match credential::from_string_in_profile(profile_handle, &credential_data) {
             Ok(x) => {
                 trace!("vcx_credential_deserialize_cb(command_handle: {}, rc: {}, credential_handle: {}) source_id: {}",
                        command_handle, error::SUCCESS.message, x, credential::get_source_id(x).unwrap_or_default());
@@ -1003,7 +1204,7 @@ mod tests {
     use crate::api_lib::VcxStateType;
     use crate::utils::constants::{GET_MESSAGES_DECRYPTED_RESPONSE, V3_OBJECT_SERIALIZE_VERSION};
     use crate::utils::devsetup::*;
-    use crate::utils::mockdata::mockdata_credex::{ARIES_CREDENTIAL_OFFER, ARIES_CREDENTIAL_RESPONSE, CREDENTIAL_SM_FINISHED};
+    use crate::utils::mockdata::mockdata_credex::{ARIES_CREDENTIAL_OFFER, ARIES_CREDENTIAL_PROPOSAL, ARIES_CREDENTIAL_RESPONSE, CREDENTIAL_SM_FINISHED};
 
     use super::*;
 
@@ -1079,6 +1280,28 @@ mod tests {
         cb.receive(TimeoutUtils::some_medium()).unwrap();
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_credential_create_with_proposal_and_send_proposal() {
+        let _setup = SetupMocks::init();
+
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        assert_eq!(vcx_credential_create_with_proposal(cb.command_handle,
+                                                       CString::new("test_create_with_proposal").unwrap().into_raw(),
+                                                       CString::new(ARIES_CREDENTIAL_PROPOSAL).unwrap().into_raw(),
+                                                       Some(cb.get_callback())),
+                   error::SUCCESS.code_num);
+        let handle = cb.receive(TimeoutUtils::some_medium()).unwrap();
+        assert_eq!(credential::get_state(handle).unwrap(), VcxStateType::VcxStateInitialized as u32);
+
+        let connection_handle = connection::tests::build_test_connection_inviter_requested();
+
+        let cb = return_types_u32::Return_U32::new().unwrap();
+        assert_eq!(vcx_credential_send_proposal(cb.command_handle, handle, connection_handle, Some(cb.get_callback())), error::SUCCESS.code_num);
+        cb.receive(TimeoutUtils::some_medium()).unwrap();
+        assert_eq!(credential::get_state(handle).unwrap(), VcxStateType::VcxStateOfferSent as u32);
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_vcx_credential_get_new_offers() {
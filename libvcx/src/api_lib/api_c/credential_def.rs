@@ -8,7 +8,7 @@ use crate::api_lib::api_handle::credential_def;
 use crate::api_lib::utils_c::cstring::CStringUtils;
 use crate::api_lib::utils_c::runtime::execute;
 use crate::error::prelude::*;
-use crate::settings;
+use crate::profile::ProfileContext;
 use crate::utils::error;
 
 /// Create a new CredentialDef object and publish correspondent record on the ledger
@@ -36,6 +36,8 @@ use crate::utils::error;
 /// If tails_location is specified, the exact value is written to the ledger and obtainable via vcx_credential_get_tails_location.
 /// If tails_base_location in specified, the value written to the ledger and obtainable via vcx_credential_get_tails_location is "{tails_base_location}/{tails_hash}".
 /// It is not allowed to specify both tails_location and tails_base_location.
+/// If tails_base_location is specified, a new revocation registry is created, published and used for issuance
+/// once max_creds credentials have been issued from the active one (the CredentialDef has to be serialized again to persist it).
 /// # Examples config -> "{}" 
 ///    | "{"support_revocation":false}" 
 ///    | "{"support_revocation":true, "tails_file": "/tmp/tailsfile.txt", "max_creds": 1, "tails_url": "https://dummy.faber.org/DvVhi9j4a3RYdZoQxBerhUUHnyBf8k4j8a5Zp2vgLHpW"}"
@@ -58,6 +60,45 @@ pub extern fn vcx_credentialdef_create(command_handle: CommandHandle,
                                        cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credentialdef_handle: u32)>) -> u32 {
     info!("vcx_credentialdef_create >>>");
 
+    _vcx_credentialdef_create(command_handle, None, source_id, credentialdef_name, schema_id, issuer_did, tag, revocation_details, cb)
+}
+
+/// Same as `vcx_credentialdef_create`, the credential definition is created in the wallet of the profile
+/// (by its institution DID if `issuer_did` is not given) and bound to the profile.
+/// Every operation on the object runs within the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_credentialdef_create`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_credentialdef_create_in_profile(command_handle: CommandHandle,
+                                                  profile_handle: u32,
+                                                  source_id: *const c_char,
+                                                  credentialdef_name: *const c_char,
+                                                  schema_id: *const c_char,
+                                                  issuer_did: *const c_char,
+                                                  tag: *const c_char,
+                                                  revocation_details: *const c_char,
+                                                  _payment_handle: u32,
+                                                  cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credentialdef_handle: u32)>) -> u32 {
+    info!("vcx_credentialdef_create_in_profile >>>");
+
+    _vcx_credentialdef_create(command_handle, Some(profile_handle), source_id, credentialdef_name, schema_id, issuer_did, tag, revocation_details, cb)
+}
+
+fn _vcx_credentialdef_create(command_handle: CommandHandle,
+                             profile_handle: Option<u32>,
+                             source_id: *const c_char,
+                             credentialdef_name: *const c_char,
+                             schema_id: *const c_char,
+                             issuer_did: *const c_char,
+                             tag: *const c_char,
+                             revocation_details: *const c_char,
+                             cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credentialdef_handle: u32)>) -> u32 {
     check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
     check_useful_c_str!(credentialdef_name, VcxErrorKind::InvalidOption);
     check_useful_c_str!(source_id, VcxErrorKind::InvalidOption);
@@ -69,14 +110,15 @@ pub extern fn vcx_credentialdef_create(command_handle: CommandHandle,
         check_useful_c_str!(issuer_did, VcxErrorKind::InvalidOption);
         issuer_did.to_owned()
     } else {
-        match settings::get_config_value(settings::CONFIG_INSTITUTION_DID) {
+        match ProfileContext::from_handle(profile_handle).and_then(|profile| profile.institution_did()) {
             Ok(x) => x,
             Err(x) => return x.into(),
         }
     };
 
-    trace!("vcx_credential_def_create(command_handle: {}, source_id: {}, credentialdef_name: {} schema_id: {}, issuer_did: {}, tag: {}, revocation_details: {:?})",
+    trace!("vcx_credential_def_create(command_handle: {}, profile_handle: {:?}, source_id: {}, credentialdef_name: {} schema_id: {}, issuer_did: {}, tag: {}, revocation_details: {:?})",
            command_handle,
+           profile_handle,
            source_id,
            credentialdef_name,
            schema_id,
@@ -85,12 +127,13 @@ pub extern fn vcx_credentialdef_create(command_handle: CommandHandle,
            revocation_details);
 
     execute(move || {
-        let (rc, handle) = match credential_def::create_and_publish_credentialdef(source_id,
-                                                                                  credentialdef_name,
-                                                                                  issuer_did,
-                                                                                  schema_id,
-                                                                                  tag,
-                                                                                  revocation_details) {
+        let (rc, handle) = match credential_def::create_and_publish_credentialdef_in_profile(profile_handle,
+                                                                                             source_id,
+                                                                                             credentialdef_name,
+                                                                                             issuer_did,
+                                                                                             schema_id,
+                                                                                             tag,
+                                                                                             revocation_details) {
             Ok(x) => {
                 trace!("vcx_credential_def_create_cb(command_handle: {}, rc: {}, credentialdef_handle: {}), source_id: {:?}",
                        command_handle, error::SUCCESS.message, x, credential_def::get_source_id(x).unwrap_or_default());
@@ -175,13 +218,40 @@ pub extern fn vcx_credentialdef_deserialize(command_handle: CommandHandle,
                                             cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credentialdef_handle: u32)>) -> u32 {
     info!("vcx_credentialdef_deserialize >>>");
 
+    _vcx_credentialdef_deserialize(command_handle, None, credentialdef_data, cb)
+}
+
+/// Same as `vcx_credentialdef_deserialize`, the object is created within the profile and bound to it.
+/// Every operation on the object runs within the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_credentialdef_deserialize`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_credentialdef_deserialize_in_profile(command_handle: CommandHandle,
+                                                       profile_handle: u32,
+                                                       credentialdef_data: *const c_char,
+                                                       cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credentialdef_handle: u32)>) -> u32 {
+    info!("vcx_credentialdef_deserialize_in_profile >>>");
+
+    _vcx_credentialdef_deserialize(command_handle, Some(profile_handle), credentialdef_data, cb)
+}
+
+fn _vcx_credentialdef_deserialize(command_handle: CommandHandle,
+                                  profile_handle: Option<u32>,
+                                  credentialdef_data: *const c_char,
+                                  cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credentialdef_handle: u32)>) -> u32 {
     check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
     check_useful_c_str!(credentialdef_data, VcxErrorKind::InvalidOption);
 
     trace!("vcx_credentialdef_deserialize(command_handle: {}, credentialdef_data: {})", command_handle, credentialdef_data);
 
     execute(move || {
-        let (rc, handle) = match credential_def::from_string(&credentialdef_data) {
+        let (rc, handle) = match credential_def::from_string_in_profile(profile_handle, &credentialdef_data) {
             Ok(x) => {
                 trace!("vcx_credentialdef_deserialize_cb(command_handle: {}, rc: {}, handle: {}), source_id: {}",
                        command_handle, error::SUCCESS.message, x, credential_def::get_source_id(x).unwrap_or_default());
@@ -0,0 +1,293 @@
+use std::ptr;
+
+use indy_sys::CommandHandle;
+use libc::c_char;
+
+use crate::api_lib::utils_c::cstring::CStringUtils;
+use crate::api_lib::utils_c::runtime::execute;
+use crate::error::prelude::*;
+use crate::libindy::proofs::prover::credential_store;
+use crate::profile::ProfileContext;
+use crate::utils::error;
+
+/*
+    APIs in this module are called by a holder to manage credentials stored in the wallet,
+    regardless of whether Credential objects they have been received with still exist.
+
+    Every stored credential is described as
+    {
+        "cred_id": string,
+        "schema_id": string,
+        "cred_def_id": string,
+        "issuer_did": string,
+        "attrs": {"attribute name": "raw value"},
+        "rev_reg_id": Optional<string>,
+        "cred_rev_id": Optional<string>,
+        "metadata": Optional<{
+            "issued_at": u64, // time the credential has been stored (seconds since epoch)
+            "source_id": Optional<string>, // source id of the Credential object
+            "thread_id": Optional<string>, // thread of the issuance exchange
+            "pw_did": Optional<string> // pairwise DID of the connection the credential has been received on
+        }>
+    }
+*/
+
+/// Searches credentials stored in the wallet
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// query_json: (Optional) WQL query filtering credentials, all credentials are returned if not set.
+///     Supported tags:
+///     {
+///         "schema_id": <credential schema id>,
+///         "schema_issuer_did": <credential schema issuer did>,
+///         "schema_name": <credential schema name>,
+///         "schema_version": <credential schema version>,
+///         "issuer_did": <credential issuer did>,
+///         "cred_def_id": <credential definition id>,
+///         "rev_reg_id": <credential revocation registry id>,
+///         "attr::<attribute name>::value": <attribute raw value>,
+///         "attr::<attribute name>::marker": "1" - to filter based on existence of a specific attribute
+///     }
+///     e.g. {"issuer_did": "V4SGRU86Z58d6TV7PBUe6f", "attr::name::value": "Alice"}
+///
+/// cb: Callback that provides json array of stored credentials and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_credential_store_search(command_handle: CommandHandle,
+                                          query_json: *const c_char,
+                                          cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credentials: *const c_char)>) -> u32 {
+    info!("vcx_credential_store_search >>>");
+
+    _vcx_credential_store_search(command_handle, None, query_json, cb)
+}
+
+/// Same as `vcx_credential_store_search`, credentials are searched in the wallet of the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_credential_store_search`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_credential_store_search_in_profile(command_handle: CommandHandle,
+                                                     profile_handle: u32,
+                                                     query_json: *const c_char,
+                                                     cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credentials: *const c_char)>) -> u32 {
+    info!("vcx_credential_store_search_in_profile >>>");
+
+    _vcx_credential_store_search(command_handle, Some(profile_handle), query_json, cb)
+}
+
+fn _vcx_credential_store_search(command_handle: CommandHandle,
+                                profile_handle: Option<u32>,
+                                query_json: *const c_char,
+                                cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credentials: *const c_char)>) -> u32 {
+    check_useful_opt_c_str!(query_json, VcxErrorKind::InvalidOption);
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_credential_store_search(command_handle: {}, query_json: {:?})", command_handle, query_json);
+
+    execute(move || {
+        match ProfileContext::from_handle(profile_handle).and_then(|profile| credential_store::search_credentials(&profile, query_json.as_ref().map(String::as_str))) {
+            Ok(credentials) => {
+                let credentials = json!(credentials).to_string();
+                trace!("vcx_credential_store_search_cb(command_handle: {}, rc: {}, credentials: {})",
+                       command_handle, error::SUCCESS.message, secret!(&credentials));
+                let credentials = CStringUtils::string_to_cstring(credentials);
+                cb(command_handle, error::SUCCESS.code_num, credentials.as_ptr());
+            }
+            Err(x) => {
+                error!("vcx_credential_store_search_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Retrieves credential stored in the wallet
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// cred_id: id of the credential in the wallet
+///
+/// cb: Callback that provides the stored credential and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_credential_store_get(command_handle: CommandHandle,
+                                       cred_id: *const c_char,
+                                       cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credential: *const c_char)>) -> u32 {
+    info!("vcx_credential_store_get >>>");
+
+    _vcx_credential_store_get(command_handle, None, cred_id, cb)
+}
+
+/// Same as `vcx_credential_store_get`, the credential is read from the wallet of the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_credential_store_get`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_credential_store_get_in_profile(command_handle: CommandHandle,
+                                                  profile_handle: u32,
+                                                  cred_id: *const c_char,
+                                                  cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credential: *const c_char)>) -> u32 {
+    info!("vcx_credential_store_get_in_profile >>>");
+
+    _vcx_credential_store_get(command_handle, Some(profile_handle), cred_id, cb)
+}
+
+fn _vcx_credential_store_get(command_handle: CommandHandle,
+                             profile_handle: Option<u32>,
+                             cred_id: *const c_char,
+                             cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credential: *const c_char)>) -> u32 {
+    check_useful_c_str!(cred_id, VcxErrorKind::InvalidOption);
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_credential_store_get(command_handle: {}, cred_id: {})", command_handle, cred_id);
+
+    execute(move || {
+        match ProfileContext::from_handle(profile_handle).and_then(|profile| credential_store::get_credential(&profile, &cred_id)) {
+            Ok(credential) => {
+                let credential = json!(credential).to_string();
+                trace!("vcx_credential_store_get_cb(command_handle: {}, rc: {}, credential: {})",
+                       command_handle, error::SUCCESS.message, secret!(&credential));
+                let credential = CStringUtils::string_to_cstring(credential);
+                cb(command_handle, error::SUCCESS.code_num, credential.as_ptr());
+            }
+            Err(x) => {
+                error!("vcx_credential_store_get_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Deletes credential from the wallet together with data vcx keeps about it
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// cred_id: id of the credential in the wallet
+///
+/// cb: Callback that provides error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_credential_store_delete(command_handle: CommandHandle,
+                                          cred_id: *const c_char,
+                                          cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_credential_store_delete >>>");
+
+    _vcx_credential_store_delete(command_handle, None, cred_id, cb)
+}
+
+/// Same as `vcx_credential_store_delete`, the credential is deleted from the wallet of the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_credential_store_delete`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_credential_store_delete_in_profile(command_handle: CommandHandle,
+                                                     profile_handle: u32,
+                                                     cred_id: *const c_char,
+                                                     cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_credential_store_delete_in_profile >>>");
+
+    _vcx_credential_store_delete(command_handle, Some(profile_handle), cred_id, cb)
+}
+
+fn _vcx_credential_store_delete(command_handle: CommandHandle,
+                                profile_handle: Option<u32>,
+                                cred_id: *const c_char,
+                                cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    check_useful_c_str!(cred_id, VcxErrorKind::InvalidOption);
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_credential_store_delete(command_handle: {}, cred_id: {})", command_handle, cred_id);
+
+    execute(move || {
+        match ProfileContext::from_handle(profile_handle).and_then(|profile| credential_store::delete_credential(&profile, &cred_id)) {
+            Ok(()) => {
+                trace!("vcx_credential_store_delete_cb(command_handle: {}, rc: {})", command_handle, error::SUCCESS.message);
+                cb(command_handle, error::SUCCESS.code_num);
+            }
+            Err(x) => {
+                error!("vcx_credential_store_delete_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use crate::api_lib::utils_c::return_types_u32;
+    use crate::api_lib::utils_c::timeout::TimeoutUtils;
+    use crate::utils::devsetup::*;
+
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_credential_store_search_get_delete() {
+        let _setup = SetupMocks::init();
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        let query = CString::new(r#"{"attr::name::value": "Bob"}"#).unwrap().into_raw();
+        assert_eq!(error::SUCCESS.code_num, vcx_credential_store_search(cb.command_handle, query, Some(cb.get_callback())));
+        let credentials: serde_json::Value = serde_json::from_str(&cb.receive(TimeoutUtils::some_medium()).unwrap().unwrap()).unwrap();
+        let cred_id = credentials[0]["cred_id"].as_str().unwrap().to_string();
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(error::SUCCESS.code_num, vcx_credential_store_get(cb.command_handle, CString::new(cred_id.as_str()).unwrap().into_raw(), Some(cb.get_callback())));
+        let credential: serde_json::Value = serde_json::from_str(&cb.receive(TimeoutUtils::some_medium()).unwrap().unwrap()).unwrap();
+        assert_eq!(credentials[0], credential);
+
+        let cb = return_types_u32::Return_U32::new().unwrap();
+        assert_eq!(error::SUCCESS.code_num, vcx_credential_store_delete(cb.command_handle, CString::new(cred_id.as_str()).unwrap().into_raw(), Some(cb.get_callback())));
+        cb.receive(TimeoutUtils::some_medium()).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_credential_store_search_fails_for_invalid_query() {
+        let _setup = SetupMocks::init();
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        let query = CString::new("[]").unwrap().into_raw();
+        assert_eq!(error::SUCCESS.code_num, vcx_credential_store_search(cb.command_handle, query, Some(cb.get_callback())));
+        assert_eq!(error::INVALID_OPTION.code_num, cb.receive(TimeoutUtils::some_medium()).unwrap_err());
+    }
+}
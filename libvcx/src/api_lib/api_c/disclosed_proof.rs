@@ -68,13 +68,42 @@ use crate::utils::error;
 /// #Returns
 /// Error code as u32
 #[no_mangle]
-#[allow(unused_variables, unused_mut)]
 pub extern fn vcx_disclosed_proof_create_with_request(command_handle: CommandHandle,
                                                       source_id: *const c_char,
                                                       proof_req: *const c_char,
                                                       cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, handle: u32)>) -> u32 {
     info!("vcx_disclosed_proof_create_with_request >>>");
 
+    _vcx_disclosed_proof_create_with_request(command_handle, None, source_id, proof_req, cb)
+}
+
+/// Same as `vcx_disclosed_proof_create_with_request`, the object is created within the profile and bound to it.
+/// Every operation on the object runs within the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_disclosed_proof_create_with_request`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_disclosed_proof_create_with_request_in_profile(command_handle: CommandHandle,
+                                                                 profile_handle: u32,
+                                                                 source_id: *const c_char,
+                                                                 proof_req: *const c_char,
+                                                                 cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, handle: u32)>) -> u32 {
+    info!("vcx_disclosed_proof_create_with_request_in_profile >>>");
+
+    _vcx_disclosed_proof_create_with_request(command_handle, Some(profile_handle), source_id, proof_req, cb)
+}
+
+#[allow(unused_variables, unused_mut)]
+fn _vcx_disclosed_proof_create_with_request(command_handle: CommandHandle,
+                                            profile_handle: Option<u32>,
+                                            source_id: *const c_char,
+                                            proof_req: *const c_char,
+                                            cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, handle: u32)>) -> u32 {
     check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
     check_useful_c_str!(source_id, VcxErrorKind::InvalidOption);
     check_useful_c_str!(proof_req, VcxErrorKind::InvalidOption);
@@ -83,7 +112,7 @@ pub extern fn vcx_disclosed_proof_create_with_request(command_handle: CommandHan
            command_handle, source_id, proof_req);
 
     execute(move || {
-        match disclosed_proof::create_proof(&source_id, &proof_req) {
+        match disclosed_proof::create_proof_in_profile(profile_handle, &source_id, &proof_req) {
             Ok(x) => {
                 trace!("vcx_disclosed_proof_create_with_request_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
                        command_handle, error::SUCCESS.message, x, source_id);
@@ -653,6 +682,33 @@ pub extern fn vcx_disclosed_proof_deserialize(command_handle: CommandHandle,
                                               cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, handle: u32)>) -> u32 {
     info!("vcx_disclosed_proof_deserialize >>>");
 
+    _vcx_disclosed_proof_deserialize(command_handle, None, proof_data, cb)
+}
+
+/// Same as `vcx_disclosed_proof_deserialize`, the object is created within the profile and bound to it.
+/// Every operation on the object runs within the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_disclosed_proof_deserialize`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_disclosed_proof_deserialize_in_profile(command_handle: CommandHandle,
+                                                         profile_handle: u32,
+                                                         proof_data: *const c_char,
+                                                         cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, handle: u32)>) -> u32 {
+    info!("vcx_disclosed_proof_deserialize_in_profile >>>");
+
+    _vcx_disclosed_proof_deserialize(command_handle, Some(profile_handle), proof_data, cb)
+}
+
+fn _vcx_disclosed_proof_deserialize(command_handle: CommandHandle,
+                                    profile_handle: Option<u32>,
+                                    proof_data: *const c_char,
+                                    cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, handle: u32)>) -> u32 {
     check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
     check_useful_c_str!(proof_data, VcxErrorKind::InvalidOption);
 
@@ -660,7 +716,7 @@ pub extern fn vcx_disclosed_proof_deserialize(command_handle: CommandHandle,
            command_handle, proof_data);
 
     execute(move || {
-        match disclosed_proof::from_string(&proof_data) {
+        match disclosed_proof::from_string_in_profile(profile_handle, &proof_data) {
             Ok(x) => {
                 trace!("vcx_disclosed_proof_deserialize_cb(command_handle: {}, rc: {}, proof_handle: {}) source_id: {}",
                        command_handle, error::SUCCESS.message, x, disclosed_proof::get_source_id(x).unwrap_or_default());
@@ -812,6 +868,125 @@ pub extern fn vcx_disclosed_proof_generate_proof(command_handle: CommandHandle,
     error::SUCCESS.code_num
 }
 
+/// Selects a credential for every attribute and predicate of the proof request according to the policy
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// proof_handle: Proof handle that was provided during creation. Used to identify the disclosed proof object
+///
+/// policy: (Optional) json string with selection rules, all fields are optional
+///     {
+///         "preferred_issuers": [string], // issuer DIDs, credentials of issuers listed first are preferred
+///         "check_revocation": bool, // skip credentials revoked in the requested interval, true by default
+///         "tails_dir": string, // directory with tails files of revocable credentials
+///     }
+///     Among equally ranked credentials the one already selected for another referent and then
+///     the most recently issued one is picked.
+///
+/// cb: Callback that provides json string with the selection or error status
+///     {
+///         "credentials": {...}, // selected credentials accepted by vcx_disclosed_proof_generate_proof
+///         "unsatisfied": [string] // referents no credential has been found for
+///     }
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_disclosed_proof_select_credentials(command_handle: CommandHandle,
+                                                     proof_handle: u32,
+                                                     policy: *const c_char,
+                                                     cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, selection: *const c_char)>) -> u32 {
+    info!("vcx_disclosed_proof_select_credentials >>>");
+
+    check_useful_opt_c_str!(policy, VcxErrorKind::InvalidOption);
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    if !disclosed_proof::is_valid_handle(proof_handle) {
+        return VcxError::from(VcxErrorKind::InvalidDisclosedProofHandle).into();
+    }
+
+    let source_id = disclosed_proof::get_source_id(proof_handle).unwrap_or_default();
+    trace!("vcx_disclosed_proof_select_credentials(command_handle: {}, proof_handle: {}, policy: {:?}) source_id: {}",
+           command_handle, proof_handle, policy, source_id);
+
+    execute(move || {
+        match disclosed_proof::select_credentials(proof_handle, policy.as_deref()) {
+            Ok(selection) => {
+                trace!("vcx_disclosed_proof_select_credentials(command_handle: {}, rc: {}, selection: {}) source_id: {}",
+                       command_handle, error::SUCCESS.message, secret!(&selection), source_id);
+                let selection = CStringUtils::string_to_cstring(selection);
+                cb(command_handle, error::SUCCESS.code_num, selection.as_ptr());
+            }
+            Err(x) => {
+                error!("vcx_disclosed_proof_select_credentials(command_handle: {}, rc: {}) source_id: {}",
+                       command_handle, x, source_id);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Generates proof from credentials selected according to the policy, see `vcx_disclosed_proof_select_credentials`.
+/// Fails if some attribute or predicate can be satisfied neither by a credential nor by self attested attributes.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// proof_handle: Proof handle that was provided during creation. Used to identify the disclosed proof object
+///
+/// policy: (Optional) json string with selection rules, see `vcx_disclosed_proof_select_credentials`
+///
+/// self_attested_attrs: a json string with attributes self attested by user
+///
+/// cb: Callback that returns error status
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_disclosed_proof_generate_proof_with_policy(command_handle: CommandHandle,
+                                                             proof_handle: u32,
+                                                             policy: *const c_char,
+                                                             self_attested_attrs: *const c_char,
+                                                             cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_disclosed_proof_generate_proof_with_policy >>>");
+
+    check_useful_opt_c_str!(policy, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(self_attested_attrs, VcxErrorKind::InvalidOption);
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    if !disclosed_proof::is_valid_handle(proof_handle) {
+        return VcxError::from(VcxErrorKind::InvalidDisclosedProofHandle).into();
+    }
+
+    let source_id = disclosed_proof::get_source_id(proof_handle).unwrap_or_default();
+    trace!("vcx_disclosed_proof_generate_proof_with_policy(command_handle: {}, proof_handle: {}, policy: {:?}, self_attested_attrs: {}) source_id: {}",
+           command_handle, proof_handle, policy, self_attested_attrs, source_id);
+
+    execute(move || {
+        match disclosed_proof::generate_proof_with_policy(proof_handle, policy.as_deref(), self_attested_attrs) {
+            Ok(_) => {
+                trace!("vcx_disclosed_proof_generate_proof_with_policy(command_handle: {}, rc: {}) source_id: {}",
+                       command_handle, error::SUCCESS.message, source_id);
+                cb(command_handle, error::SUCCESS.code_num);
+            }
+            Err(x) => {
+                error!("vcx_disclosed_proof_generate_proof_with_policy(command_handle: {}, rc: {}) source_id: {}",
+                       command_handle, x, source_id);
+                cb(command_handle, x.into());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
 /// Declines presentation request.
 /// There are two ways of following interaction:
 ///     - Prover wants to propose using a different presentation - pass `proposal` parameter.
@@ -1180,4 +1355,30 @@ mod tests {
                                                       Some(cb.get_callback())), error::SUCCESS.code_num);
         cb.receive(TimeoutUtils::some_medium()).unwrap();
     }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_disclosed_proof_select_credentials() {
+        let _setup = SetupMocks::init();
+
+        let handle = _vcx_disclosed_proof_create_with_request_c_closure(ARIES_PROOF_REQUEST_PRESENTATION).unwrap();
+        let _mock_builder = MockBuilder::init().
+            set_mock_creds_retrieved_for_proof_request(CREDS_FROM_PROOF_REQ);
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_disclosed_proof_select_credentials(cb.command_handle,
+                                                          handle,
+                                                          ptr::null(),
+                                                          Some(cb.get_callback())), error::SUCCESS.code_num);
+        let selection: Value = serde_json::from_str(&cb.receive(TimeoutUtils::some_medium()).unwrap().unwrap()).unwrap();
+        assert_eq!(5, selection["unsatisfied"].as_array().unwrap().len());
+
+        let cb = return_types_u32::Return_U32::new().unwrap();
+        assert_eq!(vcx_disclosed_proof_generate_proof_with_policy(cb.command_handle,
+                                                                  handle,
+                                                                  ptr::null(),
+                                                                  CString::new("{}").unwrap().into_raw(),
+                                                                  Some(cb.get_callback())), error::SUCCESS.code_num);
+        assert_eq!(error::INVALID_PROOF_CREDENTIAL_DATA.code_num, cb.receive(TimeoutUtils::some_medium()).unwrap_err());
+    }
 }
@@ -0,0 +1,134 @@
+use std::ptr;
+
+use indy_sys::CommandHandle;
+use libc::c_char;
+
+use agency_client::get_message::parse_connection_handles;
+
+use crate::api_lib::api_handle::dispatcher;
+use crate::api_lib::utils_c::cstring::CStringUtils;
+use crate::api_lib::utils_c::runtime::execute;
+use crate::error::prelude::*;
+use crate::utils::error;
+
+/*
+    APIs in this module let the application process messages of many issuer credentials, credentials,
+    disclosed proofs and proofs at once instead of calling `update_state` of every object.
+
+    Messages are matched to the objects by their thread, so the objects to receive them must be loaded.
+    Only objects of the connection's profile receive its messages. Object is bound to the connection over which
+    it got its first dispatched message, messages of its thread arriving over other connections are ignored.
+    Credential offer or presentation request which does not belong to any loaded object creates a new
+    credential or disclosed proof in the connection's profile whose source id is the thread id of the message.
+*/
+
+/// Downloads messages of the connections and passes every message of the issue-credential and present-proof
+/// protocols to the object it belongs to. Processed messages are marked as reviewed, messages the object failed
+/// to process are left unreviewed. Messages of other protocols are left unreviewed too, as are messages of threads
+/// shared by several loaded objects of the same type. Connection whose messages cannot be downloaded is reported
+/// in `failed` and the remaining connections are dispatched anyway.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// connection_handles: comma separated connection handles
+///
+/// cb: Callback that provides json report of the dispatch and error status of request
+///     {
+///         "dispatched": [{
+///             "uid": string - uid of the message,
+///             "connection_handle": u32,
+///             "object_type": "issuer_credential" | "credential" | "disclosed_proof" | "proof",
+///             "handle": u32 - handle of the object, 0 if the object failed to be created,
+///             "created": bool - whether the object has been created from the message,
+///             "state": u32 - state of the object after processing the message, not set for created objects,
+///             "error": string - set if the object failed to process the message or the message failed to be marked as reviewed
+///         }],
+///         "failed": [{
+///             "connection_handle": u32 - connection whose messages could not be downloaded,
+///             "code": u32 - error code,
+///             "reason": string
+///         }],
+///         "duplicates": [{
+///             "object_type": "issuer_credential" | "credential" | "disclosed_proof" | "proof",
+///             "profile_handle": u32 - not set for objects outside of profiles,
+///             "thread_id": string,
+///             "handles": [u32] - objects sharing the thread, its messages are not dispatched
+///         }]
+///     }
+///
+/// # Example connection_handles -> 1251643351, 2361937153
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_dispatch_messages(command_handle: CommandHandle,
+                                    connection_handles: *const c_char,
+                                    cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, dispatched: *const c_char)>) -> u32 {
+    info!("vcx_dispatch_messages >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(connection_handles, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_dispatch_messages(command_handle: {}, connection_handles: {})", command_handle, connection_handles);
+
+    let connection_handles = connection_handles.split(',').map(|handle| handle.trim().to_string()).collect();
+    let connection_handles = match parse_connection_handles(connection_handles) {
+        Ok(handles) => handles,
+        Err(err) => return VcxError::from_msg(VcxErrorKind::InvalidOption, err.to_string()).into()
+    };
+
+    execute(move || {
+        let result = dispatcher::dispatch_messages(&connection_handles)
+            .and_then(|report| serde_json::to_string(&report)
+                .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Cannot serialize dispatch report: {:?}", err))));
+        match result {
+            Ok(dispatched) => {
+                trace!("vcx_dispatch_messages_cb(command_handle: {}, rc: {}, dispatched: {})",
+                       command_handle, error::SUCCESS.message, dispatched);
+                let dispatched = CStringUtils::string_to_cstring(dispatched);
+                cb(command_handle, error::SUCCESS.code_num, dispatched.as_ptr());
+            }
+            Err(x) => {
+                error!("vcx_dispatch_messages_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use crate::api_lib::utils_c::return_types_u32;
+    use crate::api_lib::utils_c::timeout::TimeoutUtils;
+    use crate::utils::devsetup::*;
+
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_dispatch_messages_fails_for_invalid_handles() {
+        let _setup = SetupMocks::init();
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        let connection_handles = CString::new("1,abc").unwrap().into_raw();
+        assert_eq!(error::INVALID_OPTION.code_num, vcx_dispatch_messages(cb.command_handle, connection_handles, Some(cb.get_callback())));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_dispatch_messages_fails_for_unknown_connection() {
+        let _setup = SetupMocks::init();
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        let connection_handles = CString::new("1").unwrap().into_raw();
+        assert_eq!(error::SUCCESS.code_num, vcx_dispatch_messages(cb.command_handle, connection_handles, Some(cb.get_callback())));
+        assert_eq!(error::INVALID_CONNECTION_HANDLE.code_num, cb.receive(TimeoutUtils::some_medium()).unwrap_err());
+    }
+}
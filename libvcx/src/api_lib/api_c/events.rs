@@ -0,0 +1,161 @@
+use indy_sys::CommandHandle;
+use libc::c_char;
+
+use crate::api_lib::api_handle::events::{self, Event};
+use crate::api_lib::utils_c::cstring::CStringUtils;
+use crate::api_lib::utils_c::runtime::execute;
+use crate::error::prelude::*;
+use crate::utils::error;
+
+/*
+    APIs in this module notify the application about changes of connections, credentials, proofs and mediations
+    as they happen, so it does not have to poll their states.
+
+    # Events
+
+    Every event is a json with following fields:
+    {
+        "kind": "state_changed" | "unhandled_message" | "problem_report",
+        "object_type": "connection" | "issuer_credential" | "credential" | "disclosed_proof" | "proof" | "mediation",
+        "handle": u32 - handle of the object,
+        "source_id": string,
+        "thread_id": string - not set for connections and mediations,
+        "old_state": u32,
+        "new_state": u32 - same as "old_state" unless the state has changed,
+        "message": object - received message, set for "unhandled_message" and "problem_report"
+    }
+
+    "state_changed" is emitted whenever state of the object changes.
+    "unhandled_message" is emitted when a message passed to `update_state_with_message` function of the object
+    is not expected by the object in its current state.
+    "problem_report" is emitted when the object receives a problem report.
+
+    Events are delivered one at a time on a dedicated thread in the order they were emitted.
+*/
+
+/// Subscribes to events of all objects
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// event_cb: Callback called with the same command handle and json of the event for every event, see above
+///
+/// cb: Callback that provides subscription handle and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_events_subscribe(command_handle: CommandHandle,
+                                   event_cb: Option<extern fn(xcommand_handle: CommandHandle, event: *const c_char)>,
+                                   cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, subscription_handle: u32)>) -> u32 {
+    info!("vcx_events_subscribe >>>");
+
+    check_useful_c_callback!(event_cb, VcxErrorKind::InvalidOption);
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_events_subscribe(command_handle: {})", command_handle);
+
+    execute(move || {
+        let listener = move |event: &Event| {
+            match event.to_string() {
+                Ok(event) => {
+                    let event = CStringUtils::string_to_cstring(event);
+                    event_cb(command_handle, event.as_ptr());
+                }
+                Err(err) => error!("vcx_events_subscribe_event_cb(command_handle: {}, err: {})", command_handle, err)
+            }
+        };
+        match events::subscribe(Box::new(listener)) {
+            Ok(subscription_handle) => {
+                trace!("vcx_events_subscribe_cb(command_handle: {}, rc: {}, subscription_handle: {})",
+                       command_handle, error::SUCCESS.message, subscription_handle);
+                cb(command_handle, error::SUCCESS.code_num, subscription_handle);
+            }
+            Err(x) => {
+                error!("vcx_events_subscribe_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), 0);
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Cancels the subscription, no more events are delivered to it
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// subscription_handle: subscription handle returned by vcx_events_subscribe
+///
+/// cb: Callback that provides error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_events_unsubscribe(command_handle: CommandHandle,
+                                     subscription_handle: u32,
+                                     cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_events_unsubscribe >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_events_unsubscribe(command_handle: {}, subscription_handle: {})", command_handle, subscription_handle);
+
+    execute(move || {
+        match events::unsubscribe(subscription_handle) {
+            Ok(()) => {
+                trace!("vcx_events_unsubscribe_cb(command_handle: {}, rc: {})", command_handle, error::SUCCESS.message);
+                cb(command_handle, error::SUCCESS.code_num);
+            }
+            Err(x) => {
+                error!("vcx_events_unsubscribe_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api_lib::utils_c::return_types_u32;
+    use crate::api_lib::utils_c::timeout::TimeoutUtils;
+    use crate::utils::devsetup::*;
+
+    use super::*;
+
+    extern fn _event_cb(_command_handle: CommandHandle, _event: *const c_char) {}
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_events_subscribe_and_unsubscribe() {
+        let _setup = SetupMocks::init();
+
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        assert_eq!(error::SUCCESS.code_num, vcx_events_subscribe(cb.command_handle, Some(_event_cb), Some(cb.get_callback())));
+        let subscription_handle = cb.receive(TimeoutUtils::some_medium()).unwrap();
+
+        let cb = return_types_u32::Return_U32::new().unwrap();
+        assert_eq!(error::SUCCESS.code_num, vcx_events_unsubscribe(cb.command_handle, subscription_handle, Some(cb.get_callback())));
+        cb.receive(TimeoutUtils::some_medium()).unwrap();
+
+        let cb = return_types_u32::Return_U32::new().unwrap();
+        assert_eq!(error::SUCCESS.code_num, vcx_events_unsubscribe(cb.command_handle, subscription_handle, Some(cb.get_callback())));
+        assert_eq!(error::INVALID_OBJ_HANDLE.code_num, cb.receive(TimeoutUtils::some_medium()).unwrap_err());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_events_subscribe_fails_without_event_callback() {
+        let _setup = SetupMocks::init();
+
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        assert_eq!(error::INVALID_OPTION.code_num, vcx_events_subscribe(cb.command_handle, None, Some(cb.get_callback())));
+    }
+}
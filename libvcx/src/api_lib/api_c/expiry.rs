@@ -0,0 +1,263 @@
+use std::ptr;
+
+use indy_sys::CommandHandle;
+use libc::c_char;
+
+use crate::api_lib::api_handle::expiry;
+use crate::api_lib::utils_c::cstring::CStringUtils;
+use crate::api_lib::utils_c::runtime::execute;
+use crate::error::prelude::*;
+use crate::utils::error;
+
+/*
+    APIs in this module let the application find credential and proof exchanges which stalled waiting
+    for the other party and finish them.
+
+    Every issuer credential, credential, disclosed proof and proof records when it entered its current state.
+    Exchange expires when it waits for the other party longer than set in the config:
+
+    "issue_credential_expiry": seconds, expiry of issuer credentials and credentials
+    "present_proof_expiry": seconds, expiry of disclosed proofs and proofs
+
+    Credential offers and presentation requests are sent with the `~timing.expires_time` decorator,
+    received offers and requests expire at their `~timing.expires_time` if it is set.
+
+    Expired exchanges are not finished automatically, the application decides when to abandon them.
+    Abandoned exchange is in the Finished state with failure. Issuer credential can be abandoned only until
+    the credential is sent.
+
+    # Object types
+
+    "issuer_credential", "credential", "disclosed_proof", "proof"
+*/
+
+/// Gets the time the object entered its current state and the time the exchange expires
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// object_type: type of the object, see the list above
+///
+/// handle: handle of the object
+///
+/// cb: Callback that provides json with times in RFC 3339 format and error status of request
+///     {"entered_time": string, "expires_time": string}, expires_time is not set if the exchange does not expire
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_expiry_get_state_timing(command_handle: CommandHandle,
+                                          object_type: *const c_char,
+                                          handle: u32,
+                                          cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, state_timing: *const c_char)>) -> u32 {
+    info!("vcx_expiry_get_state_timing >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(object_type, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_expiry_get_state_timing(command_handle: {}, object_type: {}, handle: {})", command_handle, object_type, handle);
+
+    execute(move || {
+        match expiry::get_state_timing(&object_type, handle) {
+            Ok(state_timing) => {
+                trace!("vcx_expiry_get_state_timing_cb(command_handle: {}, rc: {}, state_timing: {})",
+                       command_handle, error::SUCCESS.message, state_timing);
+                let state_timing = CStringUtils::string_to_cstring(state_timing);
+                cb(command_handle, error::SUCCESS.code_num, state_timing.as_ptr());
+            }
+            Err(x) => {
+                error!("vcx_expiry_get_state_timing_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Finds loaded objects of the type whose exchanges have expired
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// object_type: type of the object, see the list above
+///
+/// cb: Callback that provides json array of handles of the expired objects and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_expiry_get_expired(command_handle: CommandHandle,
+                                     object_type: *const c_char,
+                                     cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, handles: *const c_char)>) -> u32 {
+    info!("vcx_expiry_get_expired >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(object_type, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_expiry_get_expired(command_handle: {}, object_type: {})", command_handle, object_type);
+
+    execute(move || {
+        match expiry::get_expired(&object_type) {
+            Ok(handles) => {
+                let handles = json!(handles).to_string();
+                trace!("vcx_expiry_get_expired_cb(command_handle: {}, rc: {}, handles: {})",
+                       command_handle, error::SUCCESS.message, handles);
+                let handles = CStringUtils::string_to_cstring(handles);
+                cb(command_handle, error::SUCCESS.code_num, handles.as_ptr());
+            }
+            Err(x) => {
+                error!("vcx_expiry_get_expired_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Abandons the exchange whether it has expired or not
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// object_type: type of the object, see the list above
+///
+/// handle: handle of the object
+///
+/// connection_handle: connection to send the problem report to the other party over, 0 to abandon without notice
+///
+/// reason: (optional) comment of the problem report, "Exchange has expired" if not set
+///
+/// cb: Callback that provides the new state of the object and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_expiry_abandon(command_handle: CommandHandle,
+                                 object_type: *const c_char,
+                                 handle: u32,
+                                 connection_handle: u32,
+                                 reason: *const c_char,
+                                 cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, state: u32)>) -> u32 {
+    info!("vcx_expiry_abandon >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(object_type, VcxErrorKind::InvalidOption);
+    check_useful_opt_c_str!(reason, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_expiry_abandon(command_handle: {}, object_type: {}, handle: {}, connection_handle: {}, reason: {:?})",
+           command_handle, object_type, handle, connection_handle, reason);
+
+    let connection_handle = if connection_handle == 0 { None } else { Some(connection_handle) };
+
+    execute(move || {
+        match expiry::abandon(&object_type, handle, connection_handle, reason) {
+            Ok(state) => {
+                trace!("vcx_expiry_abandon_cb(command_handle: {}, rc: {}, state: {})",
+                       command_handle, error::SUCCESS.message, state);
+                cb(command_handle, error::SUCCESS.code_num, state);
+            }
+            Err(x) => {
+                error!("vcx_expiry_abandon_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), 0);
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Abandons all expired exchanges of the type without notifying the other parties
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// object_type: type of the object, see the list above
+///
+/// cb: Callback that provides json array of handles of the abandoned objects and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_expiry_abandon_expired(command_handle: CommandHandle,
+                                         object_type: *const c_char,
+                                         cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, handles: *const c_char)>) -> u32 {
+    info!("vcx_expiry_abandon_expired >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(object_type, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_expiry_abandon_expired(command_handle: {}, object_type: {})", command_handle, object_type);
+
+    execute(move || {
+        match expiry::abandon_expired(&object_type) {
+            Ok(handles) => {
+                let handles = json!(handles).to_string();
+                trace!("vcx_expiry_abandon_expired_cb(command_handle: {}, rc: {}, handles: {})",
+                       command_handle, error::SUCCESS.message, handles);
+                let handles = CStringUtils::string_to_cstring(handles);
+                cb(command_handle, error::SUCCESS.code_num, handles.as_ptr());
+            }
+            Err(x) => {
+                error!("vcx_expiry_abandon_expired_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use crate::api_lib::api_handle::credential;
+    use crate::api_lib::utils_c::return_types_u32;
+    use crate::api_lib::utils_c::timeout::TimeoutUtils;
+    use crate::api_lib::VcxStateType;
+    use crate::utils::devsetup::*;
+    use crate::utils::mockdata::mockdata_credex::ARIES_CREDENTIAL_OFFER;
+
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_expiry_abandon_credential() {
+        let _setup = SetupMocks::init();
+
+        let handle = credential::credential_create_with_offer("test_vcx_expiry_abandon_credential", ARIES_CREDENTIAL_OFFER).unwrap();
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        let object_type = CString::new("credential").unwrap().into_raw();
+        assert_eq!(error::SUCCESS.code_num, vcx_expiry_get_state_timing(cb.command_handle, object_type, handle, Some(cb.get_callback())));
+        let state_timing: serde_json::Value = serde_json::from_str(&cb.receive(TimeoutUtils::some_medium()).unwrap().unwrap()).unwrap();
+        assert!(state_timing["entered_time"].is_string());
+
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        let object_type = CString::new("credential").unwrap().into_raw();
+        let reason = CString::new("reason").unwrap().into_raw();
+        assert_eq!(error::SUCCESS.code_num, vcx_expiry_abandon(cb.command_handle, object_type, handle, 0, reason, Some(cb.get_callback())));
+        assert_eq!(VcxStateType::VcxStateNone as u32, cb.receive(TimeoutUtils::some_medium()).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_expiry_get_expired_fails_for_unknown_type() {
+        let _setup = SetupMocks::init();
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        let object_type = CString::new("connection").unwrap().into_raw();
+        assert_eq!(error::SUCCESS.code_num, vcx_expiry_get_expired(cb.command_handle, object_type, Some(cb.get_callback())));
+        assert_eq!(error::INVALID_OPTION.code_num, cb.receive(TimeoutUtils::some_medium()).unwrap_err());
+    }
+}
@@ -7,7 +7,7 @@ use crate::api_lib::api_handle::{connection, credential_def, issuer_credential};
 use crate::api_lib::utils_c::cstring::CStringUtils;
 use crate::api_lib::utils_c::runtime::execute;
 use crate::error::prelude::*;
-use crate::settings;
+use crate::profile::ProfileContext;
 use crate::utils::error;
 
 /*
@@ -19,9 +19,11 @@ use crate::utils::error;
     The set of object states, messages and transitions depends on the communication method is used.
     The communication method can be specified as a config option on one of *_init functions.
 
-        VcxStateType::VcxStateInitialized - once `vcx_issuer_create_credential` (create IssuerCredential object) is called.
+        VcxStateType::VcxStateInitialized - once `vcx_issuer_create_credential` or `vcx_issuer_create_credential_from_proposal`
+                                            (create IssuerCredential object) is called.
 
-        VcxStateType::VcxStateOfferSent - once `vcx_issuer_send_credential_offer` (send `CredentialOffer` message) is called.
+        VcxStateType::VcxStateOfferSent - once `vcx_issuer_send_credential_offer` or `vcx_issuer_send_credential_offer_for_proposal`
+                                          (send `CredentialOffer` message) is called.
 
         VcxStateType::VcxStateRequestReceived - once `CredentialRequest` messages is received.
         VcxStateType::None - once `ProblemReport` messages is received.
@@ -33,10 +35,14 @@ use crate::utils::error;
     RFC - https://github.com/hyperledger/aries-rfcs/tree/7b6b93acbaf9611d3c892c4bada142fe2613de6e/features/0036-issue-credential
         VcxStateType::None - `vcx_issuer_create_credential` - VcxStateType::VcxStateInitialized
 
+        VcxStateType::None - `vcx_issuer_create_credential_from_proposal` - VcxStateType::VcxStateInitialized
+
         VcxStateType::VcxStateInitialized - `vcx_issuer_send_credential_offer` - VcxStateType::VcxStateOfferSent
+        VcxStateType::VcxStateInitialized (proposal received) - `vcx_issuer_send_credential_offer_for_proposal` - VcxStateType::VcxStateOfferSent
 
         VcxStateType::VcxStateOfferSent - received `CredentialRequest` - VcxStateType::VcxStateRequestReceived
         VcxStateType::VcxStateOfferSent - received `ProblemReport` - VcxStateType::None
+        VcxStateType::VcxStateOfferSent - received `CredentialProposal` - VcxStateType::VcxStateInitialized (proposal received)
 
         VcxStateType::VcxStateRequestReceived - vcx_issuer_send_credential` - VcxStateType::VcxStateAccepted
 
@@ -78,7 +84,6 @@ use crate::utils::error;
 /// Note, that value can be empty: "{"middle_name":""}"
 /// # Example credential_data -> "{"state":["UT"]}"  please note: this format is deprecated
 #[no_mangle]
-#[allow(unused_variables, unused_mut)]
 pub extern fn vcx_issuer_create_credential(command_handle: CommandHandle,
                                            source_id: *const c_char,
                                            cred_def_handle: u32,
@@ -89,6 +94,44 @@ pub extern fn vcx_issuer_create_credential(command_handle: CommandHandle,
                                            cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credential_handle: u32)>) -> u32 {
     info!("vcx_issuer_create_credential >>>");
 
+    _vcx_issuer_create_credential(command_handle, None, source_id, cred_def_handle, issuer_did, credential_data, credential_name, price, cb)
+}
+
+/// Same as `vcx_issuer_create_credential`, the object is created within the profile and bound to it.
+/// Every operation on the object runs within the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_issuer_create_credential`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_issuer_create_credential_in_profile(command_handle: CommandHandle,
+                                                      profile_handle: u32,
+                                                      source_id: *const c_char,
+                                                      cred_def_handle: u32,
+                                                      issuer_did: *const c_char,
+                                                      credential_data: *const c_char,
+                                                      credential_name: *const c_char,
+                                                      price: *const c_char,
+                                                      cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credential_handle: u32)>) -> u32 {
+    info!("vcx_issuer_create_credential_in_profile >>>");
+
+    _vcx_issuer_create_credential(command_handle, Some(profile_handle), source_id, cred_def_handle, issuer_did, credential_data, credential_name, price, cb)
+}
+
+#[allow(unused_variables, unused_mut)]
+fn _vcx_issuer_create_credential(command_handle: CommandHandle,
+                                 profile_handle: Option<u32>,
+                                 source_id: *const c_char,
+                                 cred_def_handle: u32,
+                                 issuer_did: *const c_char,
+                                 credential_data: *const c_char,
+                                 credential_name: *const c_char,
+                                 price: *const c_char,
+                                 cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credential_handle: u32)>) -> u32 {
     check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
     check_useful_c_str!(credential_data, VcxErrorKind::InvalidOption);
     check_useful_c_str!(credential_name, VcxErrorKind::InvalidOption);
@@ -99,7 +142,7 @@ pub extern fn vcx_issuer_create_credential(command_handle: CommandHandle,
         check_useful_c_str!(issuer_did, VcxErrorKind::InvalidOption);
         issuer_did.to_owned()
     } else {
-        match settings::get_config_value(settings::CONFIG_INSTITUTION_DID) {
+        match ProfileContext::from_handle(profile_handle).and_then(|profile| profile.institution_did()) {
             Ok(x) => x,
             Err(x) => return x.into()
         }
@@ -127,7 +170,7 @@ pub extern fn vcx_issuer_create_credential(command_handle: CommandHandle,
            credential_name);
 
     execute(move || {
-        let (rc, handle) = match issuer_credential::issuer_credential_create(cred_def_handle, source_id, issuer_did, credential_name, credential_data, price) {
+        let (rc, handle) = match issuer_credential::issuer_credential_create_in_profile(profile_handle, cred_def_handle, source_id, issuer_did, credential_name, credential_data, price) {
             Ok(x) => {
                 trace!("vcx_issuer_create_credential_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
                        command_handle, error::SUCCESS.message, x, issuer_credential::get_source_id(x).unwrap_or_default());
@@ -252,6 +295,247 @@ pub extern fn vcx_issuer_get_credential_offer_msg(command_handle: CommandHandle,
     error::SUCCESS.code_num
 }
 
+/// Create a Issuer Credential object from a credential proposal received from the holder.
+/// Use `vcx_issuer_send_credential_offer_for_proposal` to answer the proposal with an offer.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// source_id: Enterprise's personal identification for the user.
+///
+/// proposal: received credential proposal (see `vcx_issuer_get_credential_proposals`)
+///
+/// cb: Callback that provides credential handle and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_issuer_create_credential_from_proposal(command_handle: CommandHandle,
+                                                         source_id: *const c_char,
+                                                         proposal: *const c_char,
+                                                         cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credential_handle: u32)>) -> u32 {
+    info!("vcx_issuer_create_credential_from_proposal >>>");
+
+    _vcx_issuer_create_credential_from_proposal(command_handle, None, source_id, proposal, cb)
+}
+
+/// Same as `vcx_issuer_create_credential_from_proposal`, the object is created within the profile and bound to it.
+/// Every operation on the object runs within the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_issuer_create_credential_from_proposal`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_issuer_create_credential_from_proposal_in_profile(command_handle: CommandHandle,
+                                                                    profile_handle: u32,
+                                                                    source_id: *const c_char,
+                                                                    proposal: *const c_char,
+                                                                    cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credential_handle: u32)>) -> u32 {
+    info!("vcx_issuer_create_credential_from_proposal_in_profile >>>");
+
+    _vcx_issuer_create_credential_from_proposal(command_handle, Some(profile_handle), source_id, proposal, cb)
+}
+
+fn _vcx_issuer_create_credential_from_proposal(command_handle: CommandHandle,
+                                               profile_handle: Option<u32>,
+                                               source_id: *const c_char,
+                                               proposal: *const c_char,
+                                               cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credential_handle: u32)>) -> u32 {
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(source_id, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(proposal, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_issuer_create_credential_from_proposal(command_handle: {}, source_id: {}, proposal: {})",
+           command_handle, source_id, secret!(&proposal));
+
+    execute(move || {
+        match issuer_credential::issuer_credential_create_with_proposal_in_profile(profile_handle, source_id.clone(), &proposal) {
+            Ok(x) => {
+                trace!("vcx_issuer_create_credential_from_proposal_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
+                       command_handle, error::SUCCESS.message, x, source_id);
+                cb(command_handle, error::SUCCESS.code_num, x);
+            }
+            Err(x) => {
+                warn!("vcx_issuer_create_credential_from_proposal_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
+                      command_handle, x, 0, source_id);
+                cb(command_handle, x.into(), 0);
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Get the credential proposal the Issuer Credential object was created from
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// credential_handle: Credential handle that was provided during creation. Used to identify credential object
+///
+/// cb: Callback that provides credential proposal as JSON string and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_issuer_get_credential_proposal(command_handle: CommandHandle,
+                                                 credential_handle: u32,
+                                                 cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, proposal: *const c_char)>) -> u32 {
+    info!("vcx_issuer_get_credential_proposal >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    let source_id = issuer_credential::get_source_id(credential_handle).unwrap_or_default();
+    trace!("vcx_issuer_get_credential_proposal(command_handle: {}, credential_handle: {}) source_id: {}",
+           command_handle, credential_handle, source_id);
+
+    if !issuer_credential::is_valid_handle(credential_handle) {
+        return VcxError::from(VcxErrorKind::InvalidIssuerCredentialHandle).into();
+    }
+
+    execute(move || {
+        match issuer_credential::get_credential_proposal(credential_handle) {
+            Ok(proposal) => {
+                let proposal = CStringUtils::string_to_cstring(proposal);
+                trace!("vcx_issuer_get_credential_proposal_cb(command_handle: {}, credential_handle: {}, rc: {}) source_id: {}",
+                       command_handle, credential_handle, error::SUCCESS.message, source_id);
+                cb(command_handle, error::SUCCESS.code_num, proposal.as_ptr());
+            }
+            Err(x) => {
+                warn!("vcx_issuer_get_credential_proposal_cb(command_handle: {}, credential_handle: {}, rc: {}) source_id: {}",
+                      command_handle, credential_handle, x, source_id);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Answer the received credential proposal with a credential offer.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// credential_handle: Credential handle that was provided by `vcx_issuer_create_credential_from_proposal`
+///
+/// cred_def_handle: handle of credential definition the offered credential is based on
+///
+/// credential_data: data attributes offered to person in the credential
+///
+/// connection_handle: Connection handle that identifies pairwise connection
+///
+/// cb: Callback that provides error status of credential offer
+///
+/// #Returns
+/// Error code as a u32
+///
+/// # Example credential_data -> "{"state":"UT"}"
+#[no_mangle]
+pub extern fn vcx_issuer_send_credential_offer_for_proposal(command_handle: CommandHandle,
+                                                            credential_handle: u32,
+                                                            cred_def_handle: u32,
+                                                            credential_data: *const c_char,
+                                                            connection_handle: u32,
+                                                            cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_issuer_send_credential_offer_for_proposal >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(credential_data, VcxErrorKind::InvalidOption);
+
+    let source_id = issuer_credential::get_source_id(credential_handle).unwrap_or_default();
+    trace!("vcx_issuer_send_credential_offer_for_proposal(command_handle: {}, credential_handle: {}, cred_def_handle: {}, credential_data: {}, connection_handle: {}) source_id: {}",
+           command_handle, credential_handle, cred_def_handle, secret!(&credential_data), connection_handle, source_id);
+
+    if !issuer_credential::is_valid_handle(credential_handle) {
+        return VcxError::from(VcxErrorKind::InvalidIssuerCredentialHandle).into();
+    }
+
+    if !credential_def::is_valid_handle(cred_def_handle) {
+        return VcxError::from(VcxErrorKind::InvalidCredDefHandle).into();
+    }
+
+    if !connection::is_valid_handle(connection_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    execute(move || {
+        let err = match issuer_credential::send_credential_offer_for_proposal(credential_handle, cred_def_handle, &credential_data, connection_handle, None) {
+            Ok(x) => {
+                trace!("vcx_issuer_send_credential_offer_for_proposal_cb(command_handle: {}, credential_handle: {}, rc: {}) source_id: {}",
+                       command_handle, credential_handle, error::SUCCESS.message, source_id);
+                x
+            }
+            Err(x) => {
+                warn!("vcx_issuer_send_credential_offer_for_proposal_cb(command_handle: {}, credential_handle: {}, rc: {}) source_id: {}",
+                      command_handle, credential_handle, x, source_id);
+                x.into()
+            }
+        };
+
+        cb(command_handle, err);
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Queries agency for credential proposals from the given connection.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// connection_handle: Connection to query for credential proposals.
+///
+/// cb: Callback that provides any credential proposals and error status of query
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_issuer_get_credential_proposals(command_handle: CommandHandle,
+                                                  connection_handle: u32,
+                                                  cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, proposals: *const c_char)>) -> u32 {
+    info!("vcx_issuer_get_credential_proposals >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    if !connection::is_valid_handle(connection_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    trace!("vcx_issuer_get_credential_proposals(command_handle: {}, connection_handle: {})",
+           command_handle, connection_handle);
+
+    execute(move || {
+        match issuer_credential::get_credential_proposal_messages(connection_handle) {
+            Ok(x) => {
+                trace!("vcx_issuer_get_credential_proposals_cb(command_handle: {}, rc: {}, msg: {})",
+                       command_handle, error::SUCCESS.message, x);
+                let msg = CStringUtils::string_to_cstring(x);
+                cb(command_handle, error::SUCCESS.code_num, msg.as_ptr());
+            }
+            Err(x) => {
+                warn!("vcx_issuer_get_credential_proposals_cb(command_handle: {}, rc: {}, msg: null)",
+                      command_handle, x);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
 /// Query the agency for the received messages.
 /// Checks for any messages changing state in the object and updates the state attribute.
 ///
@@ -686,13 +970,40 @@ pub extern fn vcx_issuer_credential_deserialize(command_handle: CommandHandle,
                                                 cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credential_handle: u32)>) -> u32 {
     info!("vcx_issuer_credential_deserialize >>>");
 
+    _vcx_issuer_credential_deserialize(command_handle, None, credential_data, cb)
+}
+
+/// Same as `vcx_issuer_credential_deserialize`, the object is created within the profile and bound to it.
+/// Every operation on the object runs within the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_issuer_credential_deserialize`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_issuer_credential_deserialize_in_profile(command_handle: CommandHandle,
+                                                           profile_handle: u32,
+                                                           credential_data: *const c_char,
+                                                           cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credential_handle: u32)>) -> u32 {
+    info!("vcx_issuer_credential_deserialize_in_profile >>>");
+
+    _vcx_issuer_credential_deserialize(command_handle, Some(profile_handle), credential_data, cb)
+}
+
+fn _vcx_issuer_credential_deserialize(command_handle: CommandHandle,
+                                      profile_handle: Option<u32>,
+                                      credential_data: *const c_char,
+                                      cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credential_handle: u32)>) -> u32 {
     check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
     check_useful_c_str!(credential_data, VcxErrorKind::InvalidOption);
 
     trace!("vcx_issuer_credential_deserialize(command_handle: {}, credential_data: {})", command_handle, credential_data);
 
     execute(move || {
-        let (rc, handle) = match issuer_credential::from_string(&credential_data) {
+        let (rc, handle) = match issuer_credential::from_string_in_profile(profile_handle, &credential_data) {
             Ok(x) => {
                 trace!("vcx_issuer_credential_deserialize_cb(command_handle: {}, rc: {}, handle: {}), source_id: {}",
                        command_handle, error::SUCCESS.message, x, issuer_credential::get_source_id(x).unwrap_or_default());
@@ -713,6 +1024,41 @@ pub extern fn vcx_issuer_credential_deserialize(command_handle: CommandHandle,
     error::SUCCESS.code_num
 }
 
+/// Sets version of the Issue Credential protocol used for the exchange.
+/// Has to be called before the credential offer is sent; by default version "1.0" is used.
+///
+/// #Params
+/// credential_handle: Credential handle that was provided during creation. Used to identify credential object
+///
+/// protocol_version: protocol version to use, either "1.0" or "2.0"
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_issuer_credential_set_protocol_version(credential_handle: u32, protocol_version: *const c_char) -> u32 {
+    info!("vcx_issuer_credential_set_protocol_version >>>");
+
+    check_useful_c_str!(protocol_version, VcxErrorKind::InvalidOption);
+
+    if !issuer_credential::is_valid_handle(credential_handle) {
+        return VcxError::from(VcxErrorKind::InvalidIssuerCredentialHandle).into();
+    }
+
+    let source_id = issuer_credential::get_source_id(credential_handle).unwrap_or_default();
+    match issuer_credential::set_protocol_version(credential_handle, &protocol_version) {
+        Ok(()) => {
+            trace!("(vcx_issuer_credential_set_protocol_version credential_handle: {}, protocol_version: {}, rc: {}), source_id: {}",
+                   credential_handle, protocol_version, error::SUCCESS.message, source_id);
+            error::SUCCESS.code_num
+        }
+        Err(e) => {
+            warn!("(vcx_issuer_credential_set_protocol_version credential_handle: {}, protocol_version: {}, rc: {}), source_id: {}",
+                  credential_handle, protocol_version, e, source_id);
+            e.into()
+        }
+    }
+}
+
 /// Releases the issuer credential object by deallocating memory
 ///
 /// #Params
@@ -841,6 +1187,145 @@ pub extern fn vcx_issuer_revoke_credential_local(command_handle: CommandHandle,
     error::SUCCESS.code_num
 }
 
+/// Revoke Credentials without publishing, revocations of each revocation registry are merged into one pending delta
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// credential_handles: json array of Credential handles, e.g. [1, 2]
+///
+/// cb: Callback that provides error status of revoking the credentials
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_issuer_revoke_credentials_local(command_handle: CommandHandle,
+                                                  credential_handles: *const c_char,
+                                                  cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_issuer_revoke_credentials_local >>>");
+
+    check_useful_c_str!(credential_handles, VcxErrorKind::InvalidOption);
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_issuer_revoke_credentials_local(command_handle: {}, credential_handles: {})", command_handle, credential_handles);
+
+    let credential_handles: Vec<u32> = match serde_json::from_str(&credential_handles) {
+        Ok(handles) => handles,
+        Err(err) => return VcxError::from_msg(VcxErrorKind::InvalidOption, format!("Cannot deserialize credential handles: {}", err)).into()
+    };
+
+    execute(move || {
+        let err = match issuer_credential::revoke_credentials_local(&credential_handles) {
+            Ok(()) => {
+                trace!("vcx_issuer_revoke_credentials_local_cb(command_handle: {}, rc: {})", command_handle, error::SUCCESS.message);
+                error::SUCCESS.code_num
+            }
+            Err(x) => {
+                warn!("vcx_issuer_revoke_credentials_local_cb(command_handle: {}, rc: {})", command_handle, x);
+                x.into()
+            }
+        };
+
+        cb(command_handle, err);
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Get number of revoked credentials which have not been published yet
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// cb: Callback that provides error status of request and json object mapping revocation registry ids to numbers of pending revocations
+///     e.g. {"V4SGRU86Z58d6TV7PBUe6f:4:V4SGRU86Z58d6TV7PBUe6f:3:CL:1281:tag1:CL_ACCUM:tag1": 2}
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_issuer_get_pending_revocations(command_handle: CommandHandle,
+                                                 cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, pending: *const c_char)>) -> u32 {
+    info!("vcx_issuer_get_pending_revocations >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_issuer_get_pending_revocations(command_handle: {})", command_handle);
+
+    execute(move || {
+        match issuer_credential::get_pending_revocations() {
+            Ok(pending) => {
+                let pending = json!(pending).to_string();
+                trace!("vcx_issuer_get_pending_revocations_cb(command_handle: {}, rc: {}, pending: {})",
+                       command_handle, error::SUCCESS.message, pending);
+                let pending = CStringUtils::string_to_cstring(pending);
+                cb(command_handle, error::SUCCESS.code_num, pending.as_ptr());
+            }
+            Err(x) => {
+                warn!("vcx_issuer_get_pending_revocations_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Publish revocations stored locally, one revocation registry delta is written to the ledger per revocation registry
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// rev_reg_ids: (Optional) json array of ids of revocation registries to publish, all registries with pending revocations are published if not set
+///
+/// cb: Callback that provides error status of request and the report of publishing
+///     {
+///         "published": [{"rev_reg_id": string, "revoked": [<credential revocation index>], "payment_txn": Optional<object>}],
+///         "failed": [{"rev_reg_id": string, "code": u32, "reason": string}] // revocations stay pending
+///     }
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_issuer_publish_pending_revocations(command_handle: CommandHandle,
+                                                     rev_reg_ids: *const c_char,
+                                                     cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, report: *const c_char)>) -> u32 {
+    info!("vcx_issuer_publish_pending_revocations >>>");
+
+    check_useful_opt_c_str!(rev_reg_ids, VcxErrorKind::InvalidOption);
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_issuer_publish_pending_revocations(command_handle: {}, rev_reg_ids: {:?})", command_handle, rev_reg_ids);
+
+    let rev_reg_ids: Option<Vec<String>> = match rev_reg_ids.as_ref().map(|rev_reg_ids| serde_json::from_str(rev_reg_ids)).transpose() {
+        Ok(rev_reg_ids) => rev_reg_ids,
+        Err(err) => return VcxError::from_msg(VcxErrorKind::InvalidOption, format!("Cannot deserialize revocation registry ids: {}", err)).into()
+    };
+
+    execute(move || {
+        match issuer_credential::publish_pending_revocations(rev_reg_ids) {
+            Ok(report) => {
+                let report = json!(report).to_string();
+                trace!("vcx_issuer_publish_pending_revocations_cb(command_handle: {}, rc: {}, report: {})",
+                       command_handle, error::SUCCESS.message, report);
+                let report = CStringUtils::string_to_cstring(report);
+                cb(command_handle, error::SUCCESS.code_num, report.as_ptr());
+            }
+            Err(x) => {
+                warn!("vcx_issuer_publish_pending_revocations_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
 #[cfg(test)]
 pub mod tests {
     extern crate serde_json;
@@ -857,7 +1342,7 @@ pub mod tests {
     use crate::utils::constants::*;
     use crate::utils::devsetup::*;
     use crate::utils::get_temp_dir_path;
-    use crate::utils::mockdata::mockdata_credex::{ARIES_CREDENTIAL_REQUEST, CREDENTIAL_ISSUER_SM_FINISHED};
+    use crate::utils::mockdata::mockdata_credex::{ARIES_CREDENTIAL_PROPOSAL, ARIES_CREDENTIAL_REQUEST, CREDENTIAL_ISSUER_SM_FINISHED};
 
     use super::*;
 
@@ -998,6 +1483,21 @@ pub mod tests {
         assert_ne!(handle, handle_2);
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_issuer_credential_set_protocol_version() {
+        let _setup = SetupMocks::init();
+
+        let credential_handle = _vcx_issuer_create_credential_c_closure().unwrap();
+
+        assert_eq!(vcx_issuer_credential_set_protocol_version(credential_handle, CString::new("2.0").unwrap().into_raw()),
+                   error::SUCCESS.code_num);
+        assert_eq!(vcx_issuer_credential_set_protocol_version(credential_handle, CString::new("3.0").unwrap().into_raw()),
+                   error::INVALID_OPTION.code_num);
+        assert_eq!(vcx_issuer_credential_set_protocol_version(0, CString::new("2.0").unwrap().into_raw()),
+                   error::INVALID_ISSUER_CREDENTIAL_HANDLE.code_num);
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_vcx_issuer_send_credential_offer() {
@@ -1025,6 +1525,37 @@ pub mod tests {
         assert_eq!(state, VcxStateType::VcxStateRequestReceived as u32);
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_issuer_send_credential_offer_for_proposal() {
+        let _setup = SetupMocks::init();
+
+        let connection_handle = connection::tests::build_test_connection_inviter_requested();
+
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        assert_eq!(vcx_issuer_create_credential_from_proposal(cb.command_handle,
+                                                              CString::new("test_create_from_proposal").unwrap().into_raw(),
+                                                              CString::new(ARIES_CREDENTIAL_PROPOSAL).unwrap().into_raw(),
+                                                              Some(cb.get_callback())),
+                   error::SUCCESS.code_num);
+        let credential_handle = cb.receive(TimeoutUtils::some_medium()).unwrap();
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_issuer_get_credential_proposal(cb.command_handle, credential_handle, Some(cb.get_callback())), error::SUCCESS.code_num);
+        cb.receive(TimeoutUtils::some_medium()).unwrap().unwrap();
+
+        let cb = return_types_u32::Return_U32::new().unwrap();
+        assert_eq!(vcx_issuer_send_credential_offer_for_proposal(cb.command_handle,
+                                                                 credential_handle,
+                                                                 credential_def::tests::create_cred_def_fake(),
+                                                                 CString::new("{\"degree\":\"maths\"}").unwrap().into_raw(),
+                                                                 connection_handle,
+                                                                 Some(cb.get_callback())),
+                   error::SUCCESS.code_num);
+        cb.receive(TimeoutUtils::some_medium()).unwrap();
+        assert_eq!(issuer_credential::get_state(credential_handle).unwrap(), VcxStateType::VcxStateOfferSent as u32);
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_vcx_issuer_update_state_v2() {
@@ -1172,6 +1703,33 @@ pub mod tests {
         cb.receive(TimeoutUtils::some_medium()).unwrap();
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_issuer_revoke_credentials_local_and_publish() {
+        let _setup = SetupMocks::init();
+
+        settings::set_config_value(settings::CONFIG_INSTITUTION_DID, DEFAULT_DID);
+        let handle = issuer_credential::from_string(CREDENTIAL_ISSUER_SM_FINISHED).unwrap();
+
+        let cb = return_types_u32::Return_U32::new().unwrap();
+        let handles = CString::new(json!([handle]).to_string()).unwrap().into_raw();
+        assert_eq!(vcx_issuer_revoke_credentials_local(cb.command_handle, handles, Some(cb.get_callback())), error::SUCCESS.code_num);
+        cb.receive(TimeoutUtils::some_medium()).unwrap();
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_issuer_get_pending_revocations(cb.command_handle, Some(cb.get_callback())), error::SUCCESS.code_num);
+        assert_eq!("{}", cb.receive(TimeoutUtils::some_medium()).unwrap().unwrap());
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_issuer_publish_pending_revocations(cb.command_handle, ptr::null(), Some(cb.get_callback())), error::SUCCESS.code_num);
+        let report: serde_json::Value = serde_json::from_str(&cb.receive(TimeoutUtils::some_medium()).unwrap().unwrap()).unwrap();
+        assert_eq!(json!({"published": [], "failed": []}), report);
+
+        let cb = return_types_u32::Return_U32::new().unwrap();
+        let handles = CString::new("[\"1\"]").unwrap().into_raw();
+        assert_eq!(vcx_issuer_revoke_credentials_local(cb.command_handle, handles, Some(cb.get_callback())), error::INVALID_OPTION.code_num);
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_vcx_issuer_credential_release() {
@@ -0,0 +1,955 @@
+use std::ptr;
+
+use indy_sys::CommandHandle;
+use libc::c_char;
+
+use crate::api_lib::api_handle::connection;
+use crate::api_lib::api_handle::mediation;
+use crate::api_lib::utils_c::cstring::CStringUtils;
+use crate::api_lib::utils_c::runtime::execute;
+use crate::error::prelude::*;
+use crate::utils::error;
+
+/*
+    APIs in this module are called by a recipient using a mediator (coordinate-mediation protocol, RFC 0211).
+    Assumes that pairwise connection between the recipient and the mediator is already established.
+
+    # States
+
+    0 - Initial - once `vcx_mediation_create` is called.
+    1 - Requested - once `vcx_mediation_send_request` (send `mediate-request` message) is called.
+    2 - Granted - once `mediate-grant` message is received. Endpoint and routing keys of the mediator
+        are used by all connections created afterwards.
+    3 - Denied - once `mediate-deny` or `problem-report` message is received.
+
+    # Message pickup
+
+    Once `vcx_mediation_enable_message_pickup` is called, connections of the profile of the connection with the mediator
+    pick up their messages from the mediator (messagepickup/1.0 or messagepickup/2.0 protocol) instead of downloading
+    them from the cloud agency, the connection with the mediator itself keeps using the cloud agency.
+    Picked up messages are kept in memory until they are handled by updating state of the object they are addressed to,
+    messagepickup/2.0 mediators are told to remove them only then. Messages which cannot be unpacked or are addressed
+    to no connection are discarded.
+*/
+
+/// Creates a mediation object used to request mediation from a mediator
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// source_id: Institution's personal identification for the mediation
+///
+/// cb: Callback that provides mediation handle and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_mediation_create(command_handle: CommandHandle,
+                                   source_id: *const c_char,
+                                   cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, mediation_handle: u32)>) -> u32 {
+    info!("vcx_mediation_create >>>");
+
+    _vcx_mediation_create(command_handle, None, source_id, cb)
+}
+
+/// Same as `vcx_mediation_create`, the object is created within the profile and bound to it.
+/// Every operation on the object runs within the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_mediation_create`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_mediation_create_in_profile(command_handle: CommandHandle,
+                                              profile_handle: u32,
+                                              source_id: *const c_char,
+                                              cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, mediation_handle: u32)>) -> u32 {
+    info!("vcx_mediation_create_in_profile >>>");
+
+    _vcx_mediation_create(command_handle, Some(profile_handle), source_id, cb)
+}
+
+fn _vcx_mediation_create(command_handle: CommandHandle,
+                         profile_handle: Option<u32>,
+                         source_id: *const c_char,
+                         cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, mediation_handle: u32)>) -> u32 {
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(source_id, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_mediation_create(command_handle: {}, source_id: {})", command_handle, source_id);
+
+    execute(move || {
+        match mediation::create_in_profile(profile_handle, &source_id) {
+            Ok(handle) => {
+                trace!("vcx_mediation_create_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
+                       command_handle, error::SUCCESS.message, handle, source_id);
+                cb(command_handle, error::SUCCESS.code_num, handle);
+            }
+            Err(x) => {
+                error!("vcx_mediation_create_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
+                       command_handle, x, 0, source_id);
+                cb(command_handle, x.into(), 0);
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Sends mediate-request message to the mediator
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// mediation_handle: mediation handle that was provided during creation.
+///
+/// connection_handle: Connection handle that identifies pairwise connection with the mediator
+///
+/// cb: Callback that provides error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_mediation_send_request(command_handle: CommandHandle,
+                                         mediation_handle: u32,
+                                         connection_handle: u32,
+                                         cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_mediation_send_request >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    if !mediation::is_valid_handle(mediation_handle) {
+        return VcxError::from(VcxErrorKind::InvalidMediationHandle).into();
+    }
+
+    if !connection::is_valid_handle(connection_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    trace!("vcx_mediation_send_request(command_handle: {}, mediation_handle: {}, connection_handle: {})",
+           command_handle, mediation_handle, connection_handle);
+
+    execute(move || {
+        match mediation::send_request(mediation_handle, connection_handle) {
+            Ok(_) => {
+                trace!("vcx_mediation_send_request_cb(command_handle: {}, rc: {})", command_handle, error::SUCCESS.message);
+                cb(command_handle, error::SUCCESS.code_num);
+            }
+            Err(x) => {
+                error!("vcx_mediation_send_request_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Query the mediator for messages and update the state of the mediation
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// mediation_handle: mediation handle that was provided during creation.
+///
+/// connection_handle: Connection handle that identifies pairwise connection with the mediator
+///
+/// cb: Callback that provides most current state of the mediation and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_mediation_update_state(command_handle: CommandHandle,
+                                         mediation_handle: u32,
+                                         connection_handle: u32,
+                                         cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, state: u32)>) -> u32 {
+    info!("vcx_mediation_update_state >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    if !mediation::is_valid_handle(mediation_handle) {
+        return VcxError::from(VcxErrorKind::InvalidMediationHandle).into();
+    }
+
+    if !connection::is_valid_handle(connection_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    trace!("vcx_mediation_update_state(command_handle: {}, mediation_handle: {}, connection_handle: {})",
+           command_handle, mediation_handle, connection_handle);
+
+    execute(move || {
+        match mediation::update_state(mediation_handle, None, connection_handle) {
+            Ok(state) => {
+                trace!("vcx_mediation_update_state_cb(command_handle: {}, rc: {}, state: {})",
+                       command_handle, error::SUCCESS.message, state);
+                cb(command_handle, error::SUCCESS.code_num, state);
+            }
+            Err(x) => {
+                error!("vcx_mediation_update_state_cb(command_handle: {}, rc: {}, state: {})",
+                       command_handle, x, 0);
+                cb(command_handle, x.into(), 0);
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Update the state of the mediation based on the given message
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// mediation_handle: mediation handle that was provided during creation.
+///
+/// connection_handle: Connection handle that identifies pairwise connection with the mediator
+///
+/// message: message to process for state changes
+///
+/// cb: Callback that provides most current state of the mediation and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_mediation_update_state_with_message(command_handle: CommandHandle,
+                                                      mediation_handle: u32,
+                                                      connection_handle: u32,
+                                                      message: *const c_char,
+                                                      cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, state: u32)>) -> u32 {
+    info!("vcx_mediation_update_state_with_message >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(message, VcxErrorKind::InvalidOption);
+
+    if !mediation::is_valid_handle(mediation_handle) {
+        return VcxError::from(VcxErrorKind::InvalidMediationHandle).into();
+    }
+
+    trace!("vcx_mediation_update_state_with_message(command_handle: {}, mediation_handle: {}, connection_handle: {})",
+           command_handle, mediation_handle, connection_handle);
+
+    execute(move || {
+        match mediation::update_state(mediation_handle, Some(&message), connection_handle) {
+            Ok(state) => {
+                trace!("vcx_mediation_update_state_with_message_cb(command_handle: {}, rc: {}, state: {})",
+                       command_handle, error::SUCCESS.message, state);
+                cb(command_handle, error::SUCCESS.code_num, state);
+            }
+            Err(x) => {
+                error!("vcx_mediation_update_state_with_message_cb(command_handle: {}, rc: {}, state: {})",
+                       command_handle, x, 0);
+                cb(command_handle, x.into(), 0);
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Get the current state of the mediation
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// mediation_handle: mediation handle that was provided during creation.
+///
+/// cb: Callback that provides most current state of the mediation and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_mediation_get_state(command_handle: CommandHandle,
+                                      mediation_handle: u32,
+                                      cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, state: u32)>) -> u32 {
+    info!("vcx_mediation_get_state >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    if !mediation::is_valid_handle(mediation_handle) {
+        return VcxError::from(VcxErrorKind::InvalidMediationHandle).into();
+    }
+
+    trace!("vcx_mediation_get_state(command_handle: {}, mediation_handle: {})", command_handle, mediation_handle);
+
+    execute(move || {
+        match mediation::get_state(mediation_handle) {
+            Ok(state) => {
+                trace!("vcx_mediation_get_state_cb(command_handle: {}, rc: {}, state: {})",
+                       command_handle, error::SUCCESS.message, state);
+                cb(command_handle, error::SUCCESS.code_num, state);
+            }
+            Err(x) => {
+                error!("vcx_mediation_get_state_cb(command_handle: {}, rc: {}, state: {})",
+                       command_handle, x, 0);
+                cb(command_handle, x.into(), 0);
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Sends keylist-update message asking the mediator to add and remove recipient keys it routes messages for
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// mediation_handle: mediation handle that was provided during creation.
+///
+/// connection_handle: Connection handle that identifies pairwise connection with the mediator
+///
+/// add_keys: json array of recipient keys to add (may be empty)
+///     ["GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL"]
+///
+/// remove_keys: json array of recipient keys to remove (may be empty)
+///
+/// cb: Callback that provides error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_mediation_update_keylist(command_handle: CommandHandle,
+                                           mediation_handle: u32,
+                                           connection_handle: u32,
+                                           add_keys: *const c_char,
+                                           remove_keys: *const c_char,
+                                           cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_mediation_update_keylist >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(add_keys, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(remove_keys, VcxErrorKind::InvalidOption);
+
+    if !mediation::is_valid_handle(mediation_handle) {
+        return VcxError::from(VcxErrorKind::InvalidMediationHandle).into();
+    }
+
+    if !connection::is_valid_handle(connection_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    trace!("vcx_mediation_update_keylist(command_handle: {}, mediation_handle: {}, connection_handle: {}, add_keys: {}, remove_keys: {})",
+           command_handle, mediation_handle, connection_handle, add_keys, remove_keys);
+
+    let add_keys: Vec<String> = match serde_json::from_str(&add_keys) {
+        Ok(keys) => keys,
+        Err(err) => return VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot parse keys to add: {}", err)).into()
+    };
+    let remove_keys: Vec<String> = match serde_json::from_str(&remove_keys) {
+        Ok(keys) => keys,
+        Err(err) => return VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot parse keys to remove: {}", err)).into()
+    };
+
+    execute(move || {
+        match mediation::update_keylist(mediation_handle, connection_handle, add_keys, remove_keys) {
+            Ok(_) => {
+                trace!("vcx_mediation_update_keylist_cb(command_handle: {}, rc: {})", command_handle, error::SUCCESS.message);
+                cb(command_handle, error::SUCCESS.code_num);
+            }
+            Err(x) => {
+                error!("vcx_mediation_update_keylist_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Asks the mediator to route messages for the given (mediated) connection
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// mediation_handle: mediation handle that was provided during creation.
+///
+/// connection_handle: Connection handle that identifies pairwise connection with the mediator
+///
+/// mediated_connection_handle: Connection handle of the connection which messages should be routed through the mediator
+///
+/// cb: Callback that provides error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_mediation_add_connection_key(command_handle: CommandHandle,
+                                               mediation_handle: u32,
+                                               connection_handle: u32,
+                                               mediated_connection_handle: u32,
+                                               cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_mediation_add_connection_key >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    if !mediation::is_valid_handle(mediation_handle) {
+        return VcxError::from(VcxErrorKind::InvalidMediationHandle).into();
+    }
+
+    if !connection::is_valid_handle(connection_handle) || !connection::is_valid_handle(mediated_connection_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    trace!("vcx_mediation_add_connection_key(command_handle: {}, mediation_handle: {}, connection_handle: {}, mediated_connection_handle: {})",
+           command_handle, mediation_handle, connection_handle, mediated_connection_handle);
+
+    execute(move || {
+        match mediation::add_connection_key(mediation_handle, connection_handle, mediated_connection_handle) {
+            Ok(_) => {
+                trace!("vcx_mediation_add_connection_key_cb(command_handle: {}, rc: {})", command_handle, error::SUCCESS.message);
+                cb(command_handle, error::SUCCESS.code_num);
+            }
+            Err(x) => {
+                error!("vcx_mediation_add_connection_key_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Sends keylist-query message, recipient keys of the mediation are updated once the keylist is received
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// mediation_handle: mediation handle that was provided during creation.
+///
+/// connection_handle: Connection handle that identifies pairwise connection with the mediator
+///
+/// cb: Callback that provides error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_mediation_query_keylist(command_handle: CommandHandle,
+                                          mediation_handle: u32,
+                                          connection_handle: u32,
+                                          cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_mediation_query_keylist >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    if !mediation::is_valid_handle(mediation_handle) {
+        return VcxError::from(VcxErrorKind::InvalidMediationHandle).into();
+    }
+
+    if !connection::is_valid_handle(connection_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    trace!("vcx_mediation_query_keylist(command_handle: {}, mediation_handle: {}, connection_handle: {})",
+           command_handle, mediation_handle, connection_handle);
+
+    execute(move || {
+        match mediation::query_keylist(mediation_handle, connection_handle) {
+            Ok(_) => {
+                trace!("vcx_mediation_query_keylist_cb(command_handle: {}, rc: {})", command_handle, error::SUCCESS.message);
+                cb(command_handle, error::SUCCESS.code_num);
+            }
+            Err(x) => {
+                error!("vcx_mediation_query_keylist_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Get routing granted by the mediator
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// mediation_handle: mediation handle that was provided during creation.
+///
+/// cb: Callback that provides routing info and error status of request
+///     {
+///         "endpoint": "http://localhost:8020/mediator",
+///         "routing_keys": ["3LYuxJBJkngDbvJj4zjx13DBUdZ2P96eNybwd2n9L9AU"],
+///         "recipient_keys": ["GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL"]
+///     }
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_mediation_get_routing_info(command_handle: CommandHandle,
+                                             mediation_handle: u32,
+                                             cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, routing_info: *const c_char)>) -> u32 {
+    info!("vcx_mediation_get_routing_info >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    if !mediation::is_valid_handle(mediation_handle) {
+        return VcxError::from(VcxErrorKind::InvalidMediationHandle).into();
+    }
+
+    trace!("vcx_mediation_get_routing_info(command_handle: {}, mediation_handle: {})", command_handle, mediation_handle);
+
+    execute(move || {
+        match mediation::get_routing_info(mediation_handle) {
+            Ok(routing_info) => {
+                trace!("vcx_mediation_get_routing_info_cb(command_handle: {}, rc: {}, routing_info: {})",
+                       command_handle, error::SUCCESS.message, routing_info);
+                let routing_info = CStringUtils::string_to_cstring(routing_info);
+                cb(command_handle, error::SUCCESS.code_num, routing_info.as_ptr());
+            }
+            Err(x) => {
+                error!("vcx_mediation_get_routing_info_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Makes connections of the profile of the connection with the mediator pick up their messages from the mediator
+/// instead of downloading them from the cloud agency
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// connection_handle: Connection handle that identifies pairwise connection with the mediator
+///
+/// version: version of the message pickup protocol supported by the mediator, "1.0" or "2.0"
+///
+/// cb: Callback that provides error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_mediation_enable_message_pickup(command_handle: CommandHandle,
+                                                  connection_handle: u32,
+                                                  version: *const c_char,
+                                                  cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_mediation_enable_message_pickup >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(version, VcxErrorKind::InvalidOption);
+
+    if !connection::is_valid_handle(connection_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    trace!("vcx_mediation_enable_message_pickup(command_handle: {}, connection_handle: {}, version: {})",
+           command_handle, connection_handle, version);
+
+    execute(move || {
+        match mediation::enable_message_pickup(connection_handle, &version) {
+            Ok(()) => {
+                trace!("vcx_mediation_enable_message_pickup_cb(command_handle: {}, rc: {})", command_handle, error::SUCCESS.message);
+                cb(command_handle, error::SUCCESS.code_num);
+            }
+            Err(x) => {
+                error!("vcx_mediation_enable_message_pickup_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Makes connections of the global context download their messages from the cloud agency again
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_mediation_disable_message_pickup() -> u32 {
+    info!("vcx_mediation_disable_message_pickup >>>");
+
+    match mediation::disable_message_pickup() {
+        Ok(()) => error::SUCCESS.code_num,
+        Err(x) => {
+            error!("vcx_mediation_disable_message_pickup(rc: {})", x);
+            x.into()
+        }
+    }
+}
+
+/// Get status of the queue of messages held by the mediator
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// cb: Callback that provides status of the queue and error status of request
+///     {
+///         "message_count": 3,
+///         // other fields are optional and depend on the version of the protocol, e.g.
+///         "total_size": 8096, // messagepickup/1.0
+///         "live_delivery": false // messagepickup/2.0
+///     }
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_mediation_get_pickup_status(command_handle: CommandHandle,
+                                              cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, status: *const c_char)>) -> u32 {
+    info!("vcx_mediation_get_pickup_status >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_mediation_get_pickup_status(command_handle: {})", command_handle);
+
+    execute(move || {
+        match mediation::get_pickup_status() {
+            Ok(status) => {
+                trace!("vcx_mediation_get_pickup_status_cb(command_handle: {}, rc: {}, status: {})",
+                       command_handle, error::SUCCESS.message, status);
+                let status = CStringUtils::string_to_cstring(status);
+                cb(command_handle, error::SUCCESS.code_num, status.as_ptr());
+            }
+            Err(x) => {
+                error!("vcx_mediation_get_pickup_status_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Switches live mode of the mediator (messagepickup/2.0 only). In live mode the mediator delivers
+/// messages to the endpoint of the recipient as they arrive, they can be processed by `vcx_connection_process_packed_message`.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// live_delivery: whether live mode should be on
+///
+/// cb: Callback that provides error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_mediation_set_live_delivery(command_handle: CommandHandle,
+                                              live_delivery: bool,
+                                              cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_mediation_set_live_delivery >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_mediation_set_live_delivery(command_handle: {}, live_delivery: {})", command_handle, live_delivery);
+
+    execute(move || {
+        match mediation::set_live_delivery(live_delivery) {
+            Ok(()) => {
+                trace!("vcx_mediation_set_live_delivery_cb(command_handle: {}, rc: {})", command_handle, error::SUCCESS.message);
+                cb(command_handle, error::SUCCESS.code_num);
+            }
+            Err(x) => {
+                error!("vcx_mediation_set_live_delivery_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Picks up all messages held by the mediator. Connections pick up messages when their messages are requested,
+/// this allows to do it ahead.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// cb: Callback that provides number of picked up messages and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_mediation_pickup_messages(command_handle: CommandHandle,
+                                            cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, count: u32)>) -> u32 {
+    info!("vcx_mediation_pickup_messages >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_mediation_pickup_messages(command_handle: {})", command_handle);
+
+    execute(move || {
+        match mediation::pickup_messages() {
+            Ok(count) => {
+                trace!("vcx_mediation_pickup_messages_cb(command_handle: {}, rc: {}, count: {})",
+                       command_handle, error::SUCCESS.message, count);
+                cb(command_handle, error::SUCCESS.code_num, count);
+            }
+            Err(x) => {
+                error!("vcx_mediation_pickup_messages_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), 0);
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Takes the mediation object and returns a json string of all its attributes
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// mediation_handle: mediation handle that was provided during creation.
+///
+/// cb: Callback that provides json string of the mediation's attributes and provides error status
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_mediation_serialize(command_handle: CommandHandle,
+                                      mediation_handle: u32,
+                                      cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, data: *const c_char)>) -> u32 {
+    info!("vcx_mediation_serialize >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    if !mediation::is_valid_handle(mediation_handle) {
+        return VcxError::from(VcxErrorKind::InvalidMediationHandle).into();
+    }
+
+    trace!("vcx_mediation_serialize(command_handle: {}, mediation_handle: {})", command_handle, mediation_handle);
+
+    execute(move || {
+        match mediation::to_string(mediation_handle) {
+            Ok(x) => {
+                trace!("vcx_mediation_serialize_cb(command_handle: {}, rc: {}, data: {})",
+                       command_handle, error::SUCCESS.message, x);
+                let msg = CStringUtils::string_to_cstring(x);
+                cb(command_handle, error::SUCCESS.code_num, msg.as_ptr());
+            }
+            Err(x) => {
+                error!("vcx_mediation_serialize_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Takes a json string representing a mediation object and recreates an object matching the json
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// data: json string representing a mediation object
+///
+/// cb: Callback that provides mediation handle and provides error status
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_mediation_deserialize(command_handle: CommandHandle,
+                                        data: *const c_char,
+                                        cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, mediation_handle: u32)>) -> u32 {
+    info!("vcx_mediation_deserialize >>>");
+
+    _vcx_mediation_deserialize(command_handle, None, data, cb)
+}
+
+/// Same as `vcx_mediation_deserialize`, the object is created within the profile and bound to it.
+/// Every operation on the object runs within the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_mediation_deserialize`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_mediation_deserialize_in_profile(command_handle: CommandHandle,
+                                                   profile_handle: u32,
+                                                   data: *const c_char,
+                                                   cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, mediation_handle: u32)>) -> u32 {
+    info!("vcx_mediation_deserialize_in_profile >>>");
+
+    _vcx_mediation_deserialize(command_handle, Some(profile_handle), data, cb)
+}
+
+fn _vcx_mediation_deserialize(command_handle: CommandHandle,
+                              profile_handle: Option<u32>,
+                              data: *const c_char,
+                              cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, mediation_handle: u32)>) -> u32 {
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(data, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_mediation_deserialize(command_handle: {}, data: {})", command_handle, data);
+
+    execute(move || {
+        match mediation::from_string_in_profile(profile_handle, &data) {
+            Ok(handle) => {
+                trace!("vcx_mediation_deserialize_cb(command_handle: {}, rc: {}, handle: {})",
+                       command_handle, error::SUCCESS.message, handle);
+                cb(command_handle, error::SUCCESS.code_num, handle);
+            }
+            Err(x) => {
+                error!("vcx_mediation_deserialize_cb(command_handle: {}, rc: {}, handle: {})",
+                       command_handle, x, 0);
+                cb(command_handle, x.into(), 0);
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Releases the mediation object by de-allocating memory
+///
+/// #Params
+/// mediation_handle: mediation handle that was provided during creation.
+///
+/// #Returns
+/// Success
+#[no_mangle]
+pub extern fn vcx_mediation_release(mediation_handle: u32) -> u32 {
+    info!("vcx_mediation_release >>>");
+
+    match mediation::release(mediation_handle) {
+        Ok(()) => {
+            trace!("vcx_mediation_release(mediation_handle: {}, rc: {})", mediation_handle, error::SUCCESS.message);
+            error::SUCCESS.code_num
+        }
+        Err(e) => {
+            error!("vcx_mediation_release(mediation_handle: {}, rc: {})", mediation_handle, e);
+            e.into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use crate::api_lib::api_handle::connection::tests::build_test_connection_invitee_completed;
+    use crate::api_lib::api_handle::mediation::tests::_grant_message;
+    use crate::api_lib::utils_c::return_types_u32;
+    use crate::api_lib::utils_c::timeout::TimeoutUtils;
+    use crate::profile::tests::_create_profile;
+    use crate::utils::devsetup::*;
+
+    use super::*;
+
+    fn _vcx_mediation_create() -> u32 {
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        let source_id = CString::new("test_mediation").unwrap().into_raw();
+        assert_eq!(error::SUCCESS.code_num, vcx_mediation_create(cb.command_handle, source_id, Some(cb.get_callback())));
+        cb.receive(TimeoutUtils::some_medium()).unwrap()
+    }
+
+    // granted mediation routes connections created within its profile, other tests are not affected
+    fn _vcx_mediation_create_in_profile() -> u32 {
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        let source_id = CString::new("test_mediation").unwrap().into_raw();
+        assert_eq!(error::SUCCESS.code_num, vcx_mediation_create_in_profile(cb.command_handle, _create_profile(), source_id, Some(cb.get_callback())));
+        cb.receive(TimeoutUtils::some_medium()).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_mediation_request_and_grant() {
+        let _setup = SetupMocks::init();
+
+        let connection_handle = build_test_connection_invitee_completed();
+        let handle = _vcx_mediation_create_in_profile();
+
+        let cb = return_types_u32::Return_U32::new().unwrap();
+        assert_eq!(error::SUCCESS.code_num, vcx_mediation_send_request(cb.command_handle, handle, connection_handle, Some(cb.get_callback())));
+        cb.receive(TimeoutUtils::some_medium()).unwrap();
+
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        let message = CString::new(_grant_message()).unwrap().into_raw();
+        assert_eq!(error::SUCCESS.code_num, vcx_mediation_update_state_with_message(cb.command_handle, handle, connection_handle, message, Some(cb.get_callback())));
+        assert_eq!(2, cb.receive(TimeoutUtils::some_medium()).unwrap());
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(error::SUCCESS.code_num, vcx_mediation_get_routing_info(cb.command_handle, handle, Some(cb.get_callback())));
+        assert!(cb.receive(TimeoutUtils::some_medium()).unwrap().is_some());
+
+        let cb = return_types_u32::Return_U32::new().unwrap();
+        let add_keys = CString::new(r#"["GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL"]"#).unwrap().into_raw();
+        let remove_keys = CString::new("[]").unwrap().into_raw();
+        assert_eq!(error::SUCCESS.code_num, vcx_mediation_update_keylist(cb.command_handle, handle, connection_handle, add_keys, remove_keys, Some(cb.get_callback())));
+        cb.receive(TimeoutUtils::some_medium()).unwrap();
+
+        assert_eq!(error::SUCCESS.code_num, vcx_mediation_release(handle));
+        assert_eq!(error::INVALID_MEDIATION_HANDLE.code_num, vcx_mediation_release(handle));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_mediation_update_keylist_fails_for_invalid_keys() {
+        let _setup = SetupMocks::init();
+
+        let connection_handle = build_test_connection_invitee_completed();
+        let handle = _vcx_mediation_create();
+
+        let cb = return_types_u32::Return_U32::new().unwrap();
+        let add_keys = CString::new("not json").unwrap().into_raw();
+        let remove_keys = CString::new("[]").unwrap().into_raw();
+        assert_eq!(error::INVALID_JSON.code_num, vcx_mediation_update_keylist(cb.command_handle, handle, connection_handle, add_keys, remove_keys, Some(cb.get_callback())));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_mediation_enable_message_pickup() {
+        let _setup = SetupMocks::init();
+
+        let connection_handle = build_test_connection_invitee_completed();
+
+        let cb = return_types_u32::Return_U32::new().unwrap();
+        let version = CString::new("3.0").unwrap().into_raw();
+        assert_eq!(error::SUCCESS.code_num, vcx_mediation_enable_message_pickup(cb.command_handle, connection_handle, version, Some(cb.get_callback())));
+        assert_eq!(error::INVALID_OPTION.code_num, cb.receive(TimeoutUtils::some_medium()).unwrap_err());
+
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        assert_eq!(error::SUCCESS.code_num, vcx_mediation_pickup_messages(cb.command_handle, Some(cb.get_callback())));
+        assert_eq!(error::NOT_READY.code_num, cb.receive(TimeoutUtils::some_medium()).unwrap_err());
+
+        let cb = return_types_u32::Return_U32::new().unwrap();
+        let version = CString::new("1.0").unwrap().into_raw();
+        assert_eq!(error::SUCCESS.code_num, vcx_mediation_enable_message_pickup(cb.command_handle, connection_handle, version, Some(cb.get_callback())));
+        cb.receive(TimeoutUtils::some_medium()).unwrap();
+
+        let cb = return_types_u32::Return_U32::new().unwrap();
+        assert_eq!(error::SUCCESS.code_num, vcx_mediation_set_live_delivery(cb.command_handle, true, Some(cb.get_callback())));
+        let err = cb.receive(TimeoutUtils::some_medium()).unwrap_err();
+        assert_eq!(error::SUCCESS.code_num, vcx_mediation_disable_message_pickup());
+        assert_eq!(error::ACTION_NOT_SUPPORTED.code_num, err);
+    }
+}
@@ -1,9 +1,17 @@
 pub mod connection;
 pub mod credential;
+pub mod credential_store;
 pub mod credential_def;
+pub mod dispatcher;
 pub mod disclosed_proof;
+pub mod events;
+pub mod expiry;
 pub mod issuer_credential;
 pub mod logger;
+pub mod mediation;
+pub mod multi_use_invitation;
+pub mod object_storage;
+pub mod profile;
 pub mod proof;
 pub mod schema;
 pub mod utils;
@@ -0,0 +1,659 @@
+use std::ptr;
+
+use indy_sys::CommandHandle;
+use libc::c_char;
+
+use crate::api_lib::api_handle::multi_use_invitation;
+use crate::api_lib::utils_c::cstring::CStringUtils;
+use crate::api_lib::utils_c::runtime::execute;
+use crate::aries::messages::a2a::A2AMessage;
+use crate::error::prelude::*;
+use crate::utils::error;
+
+/*
+    APIs in this module are called by an inviter which wants to share a single invitation with
+    many invitees (e.g. publish it as QR code).
+
+    Recipient key of the invitation stays the same. Every connection request received on the
+    invitation spawns a new Connection object with its own pairwise DID, the spawned connections
+    are then driven by APIs of the `connection` module.
+
+    Once revoked, the invitation does not accept requests anymore, connections spawned earlier
+    are not affected.
+
+    Public invitation accepts requests addressed to the public DID of the institution, invitees
+    resolve its verkey and endpoint from the ledger.
+*/
+
+/// Creates a multi-use invitation
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// source_id: Institution's personal identification for the invitation
+///
+/// handshake_protocol (optional): handshake protocol to be used: "connections/1.0" | "didexchange/1.0"
+///
+/// cb: Callback that provides invitation handle and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_multi_use_invitation_create(command_handle: CommandHandle,
+                                              source_id: *const c_char,
+                                              handshake_protocol: *const c_char,
+                                              cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, invitation_handle: u32)>) -> u32 {
+    info!("vcx_multi_use_invitation_create >>>");
+
+    _vcx_multi_use_invitation_create(command_handle, None, source_id, handshake_protocol, cb)
+}
+
+/// Same as `vcx_multi_use_invitation_create`, the object is created within the profile and bound to it.
+/// Every operation on the object runs within the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_multi_use_invitation_create`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_multi_use_invitation_create_in_profile(command_handle: CommandHandle,
+                                                         profile_handle: u32,
+                                                         source_id: *const c_char,
+                                                         handshake_protocol: *const c_char,
+                                                         cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, invitation_handle: u32)>) -> u32 {
+    info!("vcx_multi_use_invitation_create_in_profile >>>");
+
+    _vcx_multi_use_invitation_create(command_handle, Some(profile_handle), source_id, handshake_protocol, cb)
+}
+
+fn _vcx_multi_use_invitation_create(command_handle: CommandHandle,
+                                    profile_handle: Option<u32>,
+                                    source_id: *const c_char,
+                                    handshake_protocol: *const c_char,
+                                    cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, invitation_handle: u32)>) -> u32 {
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(source_id, VcxErrorKind::InvalidOption);
+    check_useful_opt_c_str!(handshake_protocol, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_multi_use_invitation_create(command_handle: {}, source_id: {}, handshake_protocol: {:?})",
+           command_handle, source_id, handshake_protocol);
+
+    execute(move || {
+        match multi_use_invitation::create_in_profile(profile_handle, &source_id, handshake_protocol.as_deref()) {
+            Ok(handle) => {
+                trace!("vcx_multi_use_invitation_create_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
+                       command_handle, error::SUCCESS.message, handle, source_id);
+                cb(command_handle, error::SUCCESS.code_num, handle);
+            }
+            Err(x) => {
+                error!("vcx_multi_use_invitation_create_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
+                       command_handle, x, 0, source_id);
+                cb(command_handle, x.into(), 0);
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Creates an invitation accepting requests addressed to the public DID of the institution
+/// (institution_did of the configuration). Endpoint of the agent receiving requests has to be
+/// written to the ledger by vcx_multi_use_invitation_publish_endpoint before invitees can use it.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// source_id: Institution's personal identification for the invitation
+///
+/// cb: Callback that provides invitation handle and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_multi_use_invitation_create_public(command_handle: CommandHandle,
+                                                     source_id: *const c_char,
+                                                     cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, invitation_handle: u32)>) -> u32 {
+    info!("vcx_multi_use_invitation_create_public >>>");
+
+    _vcx_multi_use_invitation_create_public(command_handle, None, source_id, cb)
+}
+
+/// Same as `vcx_multi_use_invitation_create_public`, the invitation accepts requests addressed to the public DID
+/// of the institution of the profile and is bound to the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_multi_use_invitation_create_public`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_multi_use_invitation_create_public_in_profile(command_handle: CommandHandle,
+                                                                profile_handle: u32,
+                                                                source_id: *const c_char,
+                                                                cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, invitation_handle: u32)>) -> u32 {
+    info!("vcx_multi_use_invitation_create_public_in_profile >>>");
+
+    _vcx_multi_use_invitation_create_public(command_handle, Some(profile_handle), source_id, cb)
+}
+
+fn _vcx_multi_use_invitation_create_public(command_handle: CommandHandle,
+                                           profile_handle: Option<u32>,
+                                           source_id: *const c_char,
+                                           cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, invitation_handle: u32)>) -> u32 {
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(source_id, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_multi_use_invitation_create_public(command_handle: {}, source_id: {})", command_handle, source_id);
+
+    execute(move || {
+        match multi_use_invitation::create_public_in_profile(profile_handle, &source_id) {
+            Ok(handle) => {
+                trace!("vcx_multi_use_invitation_create_public_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
+                       command_handle, error::SUCCESS.message, handle, source_id);
+                cb(command_handle, error::SUCCESS.code_num, handle);
+            }
+            Err(x) => {
+                error!("vcx_multi_use_invitation_create_public_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
+                       command_handle, x, 0, source_id);
+                cb(command_handle, x.into(), 0);
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Writes endpoint of the agent receiving requests on the public invitation to the ledger
+/// as "endpoint" ATTRIB of the public DID, the institution DID pays the fees if there are any.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// invitation_handle: handle of the public invitation
+///
+/// endorser: (Optional) DID of the endorser that will submit the transaction.
+///     If set, the transaction is not written but returned to be passed to the endorser (see vcx_endorse_transaction)
+///
+/// cb: Callback that provides the transaction for endorser (null if the transaction has been written) and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_multi_use_invitation_publish_endpoint(command_handle: CommandHandle,
+                                                        invitation_handle: u32,
+                                                        endorser: *const c_char,
+                                                        cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, transaction: *const c_char)>) -> u32 {
+    info!("vcx_multi_use_invitation_publish_endpoint >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_opt_c_str!(endorser, VcxErrorKind::InvalidOption);
+
+    if !multi_use_invitation::is_valid_handle(invitation_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    trace!("vcx_multi_use_invitation_publish_endpoint(command_handle: {}, invitation_handle: {}, endorser: {:?})",
+           command_handle, invitation_handle, endorser);
+
+    execute(move || {
+        match multi_use_invitation::publish_endpoint(invitation_handle, endorser.as_ref().map(String::as_str)) {
+            Ok(transaction) => {
+                trace!("vcx_multi_use_invitation_publish_endpoint_cb(command_handle: {}, rc: {}, transaction: {:?})",
+                       command_handle, error::SUCCESS.message, transaction);
+                let transaction = transaction.map(CStringUtils::string_to_cstring);
+                cb(command_handle, error::SUCCESS.code_num, transaction.as_ref().map_or(ptr::null(), |transaction| transaction.as_ptr()));
+            }
+            Err(x) => {
+                error!("vcx_multi_use_invitation_publish_endpoint_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), ptr::null());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Gets the invitation to be shared with invitees
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// invitation_handle: invitation handle that was provided during creation.
+///
+/// cb: Callback that provides json string of the invitation and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_multi_use_invitation_get_details(command_handle: CommandHandle,
+                                                   invitation_handle: u32,
+                                                   cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, details: *const c_char)>) -> u32 {
+    info!("vcx_multi_use_invitation_get_details >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    if !multi_use_invitation::is_valid_handle(invitation_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    trace!("vcx_multi_use_invitation_get_details(command_handle: {}, invitation_handle: {})", command_handle, invitation_handle);
+
+    execute(move || {
+        match multi_use_invitation::get_invite_details(invitation_handle) {
+            Ok(details) => {
+                trace!("vcx_multi_use_invitation_get_details_cb(command_handle: {}, rc: {}, details: {})",
+                       command_handle, error::SUCCESS.message, details);
+                let details = CStringUtils::string_to_cstring(details);
+                cb(command_handle, error::SUCCESS.code_num, details.as_ptr());
+            }
+            Err(x) => {
+                error!("vcx_multi_use_invitation_get_details_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Spawns a connection for the request received on the invitation by other means than the agency
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// invitation_handle: invitation handle that was provided during creation.
+///
+/// request: json string of the connection request message
+///
+/// cb: Callback that provides handle of the spawned connection and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_multi_use_invitation_handle_request(command_handle: CommandHandle,
+                                                      invitation_handle: u32,
+                                                      request: *const c_char,
+                                                      cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, connection_handle: u32)>) -> u32 {
+    info!("vcx_multi_use_invitation_handle_request >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(request, VcxErrorKind::InvalidOption);
+
+    if !multi_use_invitation::is_valid_handle(invitation_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    trace!("vcx_multi_use_invitation_handle_request(command_handle: {}, invitation_handle: {}, request: {})",
+           command_handle, invitation_handle, request);
+
+    let request: A2AMessage = match serde_json::from_str(&request) {
+        Ok(request) => request,
+        Err(err) => return VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize request: {:?}", err)).into()
+    };
+
+    execute(move || {
+        match multi_use_invitation::handle_request(invitation_handle, &request) {
+            Ok(handle) => {
+                trace!("vcx_multi_use_invitation_handle_request_cb(command_handle: {}, rc: {}, connection_handle: {})",
+                       command_handle, error::SUCCESS.message, handle);
+                cb(command_handle, error::SUCCESS.code_num, handle);
+            }
+            Err(x) => {
+                error!("vcx_multi_use_invitation_handle_request_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), 0);
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Downloads requests received on the invitation from the agency and spawns a connection for each
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// invitation_handle: invitation handle that was provided during creation.
+///
+/// cb: Callback that provides json array with result of every request and error status of request
+///     [{"uid": string, "connection_handle": int, "error": string}], connection_handle is set if a connection has been
+///     spawned for the request, error is set if the request could not be handled or marked as reviewed
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_multi_use_invitation_accept_requests(command_handle: CommandHandle,
+                                                       invitation_handle: u32,
+                                                       cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, requests: *const c_char)>) -> u32 {
+    info!("vcx_multi_use_invitation_accept_requests >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    if !multi_use_invitation::is_valid_handle(invitation_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    trace!("vcx_multi_use_invitation_accept_requests(command_handle: {}, invitation_handle: {})", command_handle, invitation_handle);
+
+    execute(move || {
+        match multi_use_invitation::accept_requests(invitation_handle) {
+            Ok(requests) => {
+                let requests = json!(requests).to_string();
+                trace!("vcx_multi_use_invitation_accept_requests_cb(command_handle: {}, rc: {}, requests: {})",
+                       command_handle, error::SUCCESS.message, requests);
+                let requests = CStringUtils::string_to_cstring(requests);
+                cb(command_handle, error::SUCCESS.code_num, requests.as_ptr());
+            }
+            Err(x) => {
+                error!("vcx_multi_use_invitation_accept_requests_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Lists multi-use invitations created within the profile
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// profile_handle: handle of the profile, 0 to list invitations created outside of profiles
+///
+/// cb: Callback that provides json array of invitations and error status of request
+///     [{"handle": u32, "source_id": string, "invitation": {...}, "connections": u32, "revoked": bool}]
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_multi_use_invitation_list(command_handle: CommandHandle,
+                                            profile_handle: u32,
+                                            cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, invitations: *const c_char)>) -> u32 {
+    info!("vcx_multi_use_invitation_list >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_multi_use_invitation_list(command_handle: {}, profile_handle: {})", command_handle, profile_handle);
+
+    let profile_handle = if profile_handle == 0 { None } else { Some(profile_handle) };
+
+    execute(move || {
+        match multi_use_invitation::list(profile_handle) {
+            Ok(invitations) => {
+                trace!("vcx_multi_use_invitation_list_cb(command_handle: {}, rc: {}, invitations: {})",
+                       command_handle, error::SUCCESS.message, invitations);
+                let invitations = CStringUtils::string_to_cstring(invitations);
+                cb(command_handle, error::SUCCESS.code_num, invitations.as_ptr());
+            }
+            Err(x) => {
+                error!("vcx_multi_use_invitation_list_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Revokes the invitation, so that no more connections are spawned from it
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// invitation_handle: invitation handle that was provided during creation.
+///
+/// cb: Callback that provides error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_multi_use_invitation_revoke(command_handle: CommandHandle,
+                                              invitation_handle: u32,
+                                              cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_multi_use_invitation_revoke >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    if !multi_use_invitation::is_valid_handle(invitation_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    trace!("vcx_multi_use_invitation_revoke(command_handle: {}, invitation_handle: {})", command_handle, invitation_handle);
+
+    execute(move || {
+        match multi_use_invitation::revoke(invitation_handle) {
+            Ok(_) => {
+                trace!("vcx_multi_use_invitation_revoke_cb(command_handle: {}, rc: {})", command_handle, error::SUCCESS.message);
+                cb(command_handle, error::SUCCESS.code_num);
+            }
+            Err(x) => {
+                error!("vcx_multi_use_invitation_revoke_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Takes the invitation object and returns a json string of all its attributes
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// invitation_handle: invitation handle that was provided during creation.
+///
+/// cb: Callback that provides json string of the invitation's attributes and provides error status
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_multi_use_invitation_serialize(command_handle: CommandHandle,
+                                                 invitation_handle: u32,
+                                                 cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, data: *const c_char)>) -> u32 {
+    info!("vcx_multi_use_invitation_serialize >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    if !multi_use_invitation::is_valid_handle(invitation_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    trace!("vcx_multi_use_invitation_serialize(command_handle: {}, invitation_handle: {})", command_handle, invitation_handle);
+
+    execute(move || {
+        match multi_use_invitation::to_string(invitation_handle) {
+            Ok(x) => {
+                trace!("vcx_multi_use_invitation_serialize_cb(command_handle: {}, rc: {}, data: {})",
+                       command_handle, error::SUCCESS.message, x);
+                let msg = CStringUtils::string_to_cstring(x);
+                cb(command_handle, error::SUCCESS.code_num, msg.as_ptr());
+            }
+            Err(x) => {
+                error!("vcx_multi_use_invitation_serialize_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Takes a json string representing a multi-use invitation object and recreates an object matching the json
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// data: json string representing a multi-use invitation object
+///
+/// cb: Callback that provides invitation handle and provides error status
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_multi_use_invitation_deserialize(command_handle: CommandHandle,
+                                                   data: *const c_char,
+                                                   cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, invitation_handle: u32)>) -> u32 {
+    info!("vcx_multi_use_invitation_deserialize >>>");
+
+    _vcx_multi_use_invitation_deserialize(command_handle, None, data, cb)
+}
+
+/// Same as `vcx_multi_use_invitation_deserialize`, the object is created within the profile and bound to it.
+/// Every operation on the object runs within the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_multi_use_invitation_deserialize`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_multi_use_invitation_deserialize_in_profile(command_handle: CommandHandle,
+                                                              profile_handle: u32,
+                                                              data: *const c_char,
+                                                              cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, invitation_handle: u32)>) -> u32 {
+    info!("vcx_multi_use_invitation_deserialize_in_profile >>>");
+
+    _vcx_multi_use_invitation_deserialize(command_handle, Some(profile_handle), data, cb)
+}
+
+fn _vcx_multi_use_invitation_deserialize(command_handle: CommandHandle,
+                                         profile_handle: Option<u32>,
+                                         data: *const c_char,
+                                         cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, invitation_handle: u32)>) -> u32 {
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(data, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_multi_use_invitation_deserialize(command_handle: {}, data: {})", command_handle, data);
+
+    execute(move || {
+        match multi_use_invitation::from_string_in_profile(profile_handle, &data) {
+            Ok(handle) => {
+                trace!("vcx_multi_use_invitation_deserialize_cb(command_handle: {}, rc: {}, handle: {})",
+                       command_handle, error::SUCCESS.message, handle);
+                cb(command_handle, error::SUCCESS.code_num, handle);
+            }
+            Err(x) => {
+                error!("vcx_multi_use_invitation_deserialize_cb(command_handle: {}, rc: {}, handle: {})",
+                       command_handle, x, 0);
+                cb(command_handle, x.into(), 0);
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Releases the multi-use invitation object by de-allocating memory
+///
+/// #Params
+/// invitation_handle: invitation handle that was provided during creation.
+///
+/// #Returns
+/// Success
+#[no_mangle]
+pub extern fn vcx_multi_use_invitation_release(invitation_handle: u32) -> u32 {
+    info!("vcx_multi_use_invitation_release >>>");
+
+    match multi_use_invitation::release(invitation_handle) {
+        Ok(()) => {
+            trace!("vcx_multi_use_invitation_release(invitation_handle: {}, rc: {})", invitation_handle, error::SUCCESS.message);
+            error::SUCCESS.code_num
+        }
+        Err(e) => {
+            error!("vcx_multi_use_invitation_release(invitation_handle: {}, rc: {})", invitation_handle, e);
+            e.into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use crate::api_lib::api_handle::connection;
+    use crate::api_lib::utils_c::return_types_u32;
+    use crate::api_lib::utils_c::timeout::TimeoutUtils;
+    use crate::utils::devsetup::*;
+    use crate::utils::mockdata::mockdata_connection::ARIES_CONNECTION_REQUEST;
+
+    use super::*;
+
+    fn _vcx_multi_use_invitation_create() -> u32 {
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        let source_id = CString::new("test_multi_use_invitation").unwrap().into_raw();
+        assert_eq!(error::SUCCESS.code_num, vcx_multi_use_invitation_create(cb.command_handle, source_id, ptr::null(), Some(cb.get_callback())));
+        cb.receive(TimeoutUtils::some_medium()).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_multi_use_invitation_handle_request() {
+        let _setup = SetupMocks::init();
+
+        let handle = _vcx_multi_use_invitation_create();
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(error::SUCCESS.code_num, vcx_multi_use_invitation_get_details(cb.command_handle, handle, Some(cb.get_callback())));
+        assert!(cb.receive(TimeoutUtils::some_medium()).unwrap().is_some());
+
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        let request = CString::new(ARIES_CONNECTION_REQUEST).unwrap().into_raw();
+        assert_eq!(error::SUCCESS.code_num, vcx_multi_use_invitation_handle_request(cb.command_handle, handle, request, Some(cb.get_callback())));
+        let connection_handle = cb.receive(TimeoutUtils::some_medium()).unwrap();
+        assert!(connection::is_valid_handle(connection_handle));
+
+        let cb = return_types_u32::Return_U32::new().unwrap();
+        assert_eq!(error::SUCCESS.code_num, vcx_multi_use_invitation_revoke(cb.command_handle, handle, Some(cb.get_callback())));
+        cb.receive(TimeoutUtils::some_medium()).unwrap();
+
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        let request = CString::new(ARIES_CONNECTION_REQUEST).unwrap().into_raw();
+        assert_eq!(error::SUCCESS.code_num, vcx_multi_use_invitation_handle_request(cb.command_handle, handle, request, Some(cb.get_callback())));
+        assert_eq!(error::INVALID_STATE.code_num, cb.receive(TimeoutUtils::some_medium()).unwrap_err());
+
+        assert_eq!(error::SUCCESS.code_num, vcx_multi_use_invitation_release(handle));
+        assert_eq!(error::INVALID_CONNECTION_HANDLE.code_num, vcx_multi_use_invitation_release(handle));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_multi_use_invitation_list() {
+        let _setup = SetupMocks::init();
+
+        let handle = _vcx_multi_use_invitation_create();
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(error::SUCCESS.code_num, vcx_multi_use_invitation_list(cb.command_handle, 0, Some(cb.get_callback())));
+        let invitations: serde_json::Value = serde_json::from_str(&cb.receive(TimeoutUtils::some_medium()).unwrap().unwrap()).unwrap();
+        assert!(invitations.as_array().unwrap().iter().any(|info| info["handle"] == handle));
+    }
+}
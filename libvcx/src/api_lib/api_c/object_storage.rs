@@ -0,0 +1,308 @@
+use std::ptr;
+
+use indy_sys::CommandHandle;
+use libc::c_char;
+
+use crate::api_lib::api_handle::object_storage;
+use crate::api_lib::utils_c::cstring::CStringUtils;
+use crate::api_lib::utils_c::runtime::execute;
+use crate::error::prelude::*;
+use crate::utils::error;
+
+/*
+    APIs in this module give access to objects persisted in the wallet.
+
+    Once `persist_objects` is set to "true" in the config, connections, issuer credentials, credentials,
+    disclosed proofs, proofs, credential definitions, schemas, mediations and multi-use invitations are stored
+    in wallet records and the records are updated on every change of the object, so the objects survive restart
+    of the application without calling their `serialize` functions. Records are tagged by the handle of the object, after restart objects
+    are loaded by `vcx_object_storage_load` or `vcx_object_storage_search` under the handles they had before.
+    Releasing the object (e.g. `vcx_connection_release`) deletes its record.
+
+    If the record fails to be written, the operation which changed the object fails with `ObjectNotPersisted`
+    (1112) although the change of the object has taken effect, the record is written again on the next change
+    of the object or by `vcx_object_storage_persist`.
+
+    # Object types
+
+    "connection", "issuer_credential", "credential", "disclosed_proof", "proof", "credential_def", "schema", "mediation",
+    "multi_use_invitation"
+
+    # Record tags
+
+    All records are tagged by `handle`, `source_id` and `state`.
+    "connection" records are also tagged by `pw_did` and `their_pw_did`.
+    "issuer_credential", "credential", "disclosed_proof" and "proof" records are also tagged by `thread_id`.
+    "credential_def" records are also tagged by `cred_def_id`, "schema" records by `schema_id`.
+    "multi_use_invitation" records are tagged by `recipient_key` and `revoked` instead of `state`.
+*/
+
+/// Loads the object stored in the wallet record
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// object_type: type of the object, see the list above
+///
+/// record_id: id of the wallet record, see vcx_object_storage_get_record_id
+///
+/// cb: Callback that provides handle of the loaded object and error status of request.
+///     Handle of the object is returned if it is already loaded.
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_object_storage_load(command_handle: CommandHandle,
+                                      object_type: *const c_char,
+                                      record_id: *const c_char,
+                                      cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, handle: u32)>) -> u32 {
+    info!("vcx_object_storage_load >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(object_type, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(record_id, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_object_storage_load(command_handle: {}, object_type: {}, record_id: {})", command_handle, object_type, record_id);
+
+    execute(move || {
+        match object_storage::load(&object_type, &record_id) {
+            Ok(handle) => {
+                trace!("vcx_object_storage_load_cb(command_handle: {}, rc: {}, handle: {})",
+                       command_handle, error::SUCCESS.message, handle);
+                cb(command_handle, error::SUCCESS.code_num, handle);
+            }
+            Err(x) => {
+                error!("vcx_object_storage_load_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), 0);
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Loads all stored objects of the type whose records match the query
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// object_type: type of the object, see the list above
+///
+/// query: (optional) WQL query over the record tags, all objects of the type are loaded if not set
+///     example: {"thread_id": "7e3f6a26-9d4d-4d2a-b8a6-3d4b5f8f4c1e"}
+///
+/// cb: Callback that provides json array of loaded objects and error status of request
+///     [{"record_id": string, "handle": u32, "tags": {"source_id": string, "state": string, ...}}]
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_object_storage_search(command_handle: CommandHandle,
+                                        object_type: *const c_char,
+                                        query: *const c_char,
+                                        cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, objects: *const c_char)>) -> u32 {
+    info!("vcx_object_storage_search >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(object_type, VcxErrorKind::InvalidOption);
+    check_useful_opt_c_str!(query, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_object_storage_search(command_handle: {}, object_type: {}, query: {:?})", command_handle, object_type, query);
+
+    execute(move || {
+        match object_storage::search(&object_type, query.as_deref()) {
+            Ok(objects) => {
+                trace!("vcx_object_storage_search_cb(command_handle: {}, rc: {}, objects: {})",
+                       command_handle, error::SUCCESS.message, objects);
+                let objects = CStringUtils::string_to_cstring(objects);
+                cb(command_handle, error::SUCCESS.code_num, objects.as_ptr());
+            }
+            Err(x) => {
+                error!("vcx_object_storage_search_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Gets id of the wallet record the object is stored in
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// object_type: type of the object, see the list above
+///
+/// handle: handle of the object
+///
+/// cb: Callback that provides id of the record and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_object_storage_get_record_id(command_handle: CommandHandle,
+                                               object_type: *const c_char,
+                                               handle: u32,
+                                               cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, record_id: *const c_char)>) -> u32 {
+    info!("vcx_object_storage_get_record_id >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(object_type, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_object_storage_get_record_id(command_handle: {}, object_type: {}, handle: {})", command_handle, object_type, handle);
+
+    execute(move || {
+        match object_storage::get_record_id(&object_type, handle) {
+            Ok(record_id) => {
+                trace!("vcx_object_storage_get_record_id_cb(command_handle: {}, rc: {}, record_id: {})",
+                       command_handle, error::SUCCESS.message, record_id);
+                let record_id = CStringUtils::string_to_cstring(record_id);
+                cb(command_handle, error::SUCCESS.code_num, record_id.as_ptr());
+            }
+            Err(x) => {
+                error!("vcx_object_storage_get_record_id_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Writes the object to its wallet record if the record is out of date
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// object_type: type of the object, see the list above
+///
+/// handle: handle of the object
+///
+/// cb: Callback that provides error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_object_storage_persist(command_handle: CommandHandle,
+                                         object_type: *const c_char,
+                                         handle: u32,
+                                         cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_object_storage_persist >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(object_type, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_object_storage_persist(command_handle: {}, object_type: {}, handle: {})", command_handle, object_type, handle);
+
+    execute(move || {
+        match object_storage::persist(&object_type, handle) {
+            Ok(()) => {
+                trace!("vcx_object_storage_persist_cb(command_handle: {}, rc: {})", command_handle, error::SUCCESS.message);
+                cb(command_handle, error::SUCCESS.code_num);
+            }
+            Err(x) => {
+                error!("vcx_object_storage_persist_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Deletes the wallet record of the object. Loaded object stays available until it is released.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// object_type: type of the object, see the list above
+///
+/// record_id: id of the wallet record
+///
+/// cb: Callback that provides error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_object_storage_delete(command_handle: CommandHandle,
+                                        object_type: *const c_char,
+                                        record_id: *const c_char,
+                                        cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_object_storage_delete >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(object_type, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(record_id, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_object_storage_delete(command_handle: {}, object_type: {}, record_id: {})", command_handle, object_type, record_id);
+
+    execute(move || {
+        match object_storage::delete(&object_type, &record_id) {
+            Ok(()) => {
+                trace!("vcx_object_storage_delete_cb(command_handle: {}, rc: {})", command_handle, error::SUCCESS.message);
+                cb(command_handle, error::SUCCESS.code_num);
+            }
+            Err(x) => {
+                error!("vcx_object_storage_delete_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use crate::api_lib::api_handle::connection::tests::build_test_connection_invitee_completed;
+    use crate::api_lib::utils_c::return_types_u32;
+    use crate::api_lib::utils_c::timeout::TimeoutUtils;
+    use crate::settings;
+    use crate::utils::devsetup::*;
+
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_object_storage_load_persisted_connection() {
+        let _setup = SetupMocks::init();
+        settings::set_config_value(settings::CONFIG_PERSIST_OBJECTS, "true");
+
+        let handle = build_test_connection_invitee_completed();
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        let object_type = CString::new("connection").unwrap().into_raw();
+        assert_eq!(error::SUCCESS.code_num, vcx_object_storage_get_record_id(cb.command_handle, object_type, handle, Some(cb.get_callback())));
+        let record_id = cb.receive(TimeoutUtils::some_medium()).unwrap().unwrap();
+
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        let object_type = CString::new("connection").unwrap().into_raw();
+        let record_id = CString::new(record_id).unwrap().into_raw();
+        assert_eq!(error::SUCCESS.code_num, vcx_object_storage_load(cb.command_handle, object_type, record_id, Some(cb.get_callback())));
+        assert_eq!(handle, cb.receive(TimeoutUtils::some_medium()).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_object_storage_search_fails_for_unknown_type() {
+        let _setup = SetupMocks::init();
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        let object_type = CString::new("mediation").unwrap().into_raw();
+        assert_eq!(error::SUCCESS.code_num, vcx_object_storage_search(cb.command_handle, object_type, ptr::null(), Some(cb.get_callback())));
+        assert_eq!(error::INVALID_OPTION.code_num, cb.receive(TimeoutUtils::some_medium()).unwrap_err());
+    }
+}
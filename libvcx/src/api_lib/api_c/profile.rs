@@ -0,0 +1,201 @@
+use indy::{CommandHandle, WalletHandle};
+use libc::c_char;
+
+use crate::api_lib::utils_c::cstring::CStringUtils;
+use crate::api_lib::utils_c::runtime::execute;
+use crate::error::prelude::*;
+use crate::profile;
+use crate::utils::error;
+
+/// Creates a profile - context of a single agent (tenant) served by libvcx.
+/// Allows one process to serve several agents, each with its own wallet, agency and institution DID.
+/// Objects are created within the profile by the `*_in_profile` variants of the create and deserialize functions.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// config: institution DID and agency configuration of the agent
+///
+/// wallet_handle: handle of the (already opened) wallet of the agent
+///
+/// pool_handle: handle of the (already opened) pool the agent should use,
+///              0 to use the pool opened by vcx_open_main_pool
+///
+/// cb: Callback that provides profile handle and error status of request
+///
+/// # Example config ->
+/// {
+///   "institution_did": "V4SGRU86Z58d6TV7PBUe6f",
+///   "agency_endpoint": "http://localhost:8080",
+///   "agency_did": "VsKV7grR1BUE29mG2Fm2kX",
+///   "agency_verkey": "Hezce2UWMZ3wUhVkh2LfKSs8nDzWwzs2Win7EzNN3YaR",
+///   "remote_to_sdk_did": "...",
+///   "remote_to_sdk_verkey": "...",
+///   "sdk_to_remote_did": "...",
+///   "sdk_to_remote_verkey": "..."
+/// }
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_profile_create(command_handle: CommandHandle,
+                                 config: *const c_char,
+                                 wallet_handle: WalletHandle,
+                                 pool_handle: i32,
+                                 cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, profile_handle: u32)>) -> u32 {
+    info!("vcx_profile_create >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(config, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_profile_create(command_handle: {}, wallet_handle: {:?}, pool_handle: {})",
+           command_handle, wallet_handle, pool_handle);
+
+    let pool_handle = if pool_handle > 0 { Some(pool_handle) } else { None };
+
+    execute(move || {
+        match profile::create_profile(&config, wallet_handle, pool_handle) {
+            Ok(handle) => {
+                trace!("vcx_profile_create_cb(command_handle: {}, rc: {}, handle: {})",
+                       command_handle, error::SUCCESS.message, handle);
+                cb(command_handle, error::SUCCESS.code_num, handle);
+            }
+            Err(x) => {
+                warn!("vcx_profile_create_cb(command_handle: {}, rc: {}, handle: {})",
+                      command_handle, x, 0);
+                cb(command_handle, x.into(), 0);
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Releases the profile. Objects bound to the profile can no longer be used.
+///
+/// #Params
+/// profile_handle: handle of the profile
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_profile_release(profile_handle: u32) -> u32 {
+    info!("vcx_profile_release >>>");
+
+    match profile::release(profile_handle) {
+        Ok(()) => {
+            trace!("vcx_profile_release(profile_handle: {}, rc: {})", profile_handle, error::SUCCESS.message);
+            error::SUCCESS.code_num
+        }
+        Err(e) => {
+            warn!("vcx_profile_release(profile_handle: {}, rc: {})", profile_handle, e);
+            e.into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use crate::api_lib::api_c::connection::{vcx_connection_create, vcx_connection_create_in_profile};
+    use crate::api_lib::api_c::credential_def::vcx_credentialdef_deserialize_in_profile;
+    use crate::api_lib::api_c::schema::vcx_schema_create_in_profile;
+    use crate::api_lib::api_handle::{connection, credential_def, schema};
+    use crate::api_lib::utils_c::return_types_u32;
+    use crate::api_lib::utils_c::timeout::TimeoutUtils;
+    use crate::profile::tests::_profile_config;
+    use crate::utils::devsetup::*;
+
+    use super::*;
+
+    fn _vcx_profile_create() -> u32 {
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        let config = CString::new(_profile_config("V4SGRU86Z58d6TV7PBUe6f")).unwrap().into_raw();
+        assert_eq!(error::SUCCESS.code_num, vcx_profile_create(cb.command_handle, config, WalletHandle(42), 0, Some(cb.get_callback())));
+        cb.receive(TimeoutUtils::some_medium()).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_profile_create_fails_for_invalid_config() {
+        let _setup = SetupMocks::init();
+
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        let config = CString::new("{}").unwrap().into_raw();
+        assert_eq!(error::SUCCESS.code_num, vcx_profile_create(cb.command_handle, config, WalletHandle(42), 0, Some(cb.get_callback())));
+        assert_eq!(error::INVALID_CONFIGURATION.code_num, cb.receive(TimeoutUtils::some_medium()).unwrap_err());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_profile_binds_created_objects() {
+        let _setup = SetupMocks::init();
+
+        let profile_handle = _vcx_profile_create();
+
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        let source_id = CString::new("test_vcx_profile_binds_created_objects").unwrap().into_raw();
+        assert_eq!(error::SUCCESS.code_num, vcx_connection_create_in_profile(cb.command_handle, profile_handle, source_id, Some(cb.get_callback())));
+        let connection_handle = cb.receive(TimeoutUtils::some_medium()).unwrap();
+        assert_eq!(Some(profile_handle), connection::get_profile_handle(connection_handle));
+
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        let source_id = CString::new("test_vcx_profile_binds_created_objects").unwrap().into_raw();
+        assert_eq!(error::SUCCESS.code_num, vcx_connection_create(cb.command_handle, source_id, Some(cb.get_callback())));
+        let connection_handle = cb.receive(TimeoutUtils::some_medium()).unwrap();
+        assert_eq!(None, connection::get_profile_handle(connection_handle));
+
+        assert_eq!(error::SUCCESS.code_num, vcx_profile_release(profile_handle));
+        assert_eq!(error::INVALID_PROFILE_HANDLE.code_num, vcx_profile_release(profile_handle));
+
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        let source_id = CString::new("test_vcx_profile_binds_created_objects").unwrap().into_raw();
+        assert_eq!(error::SUCCESS.code_num, vcx_connection_create_in_profile(cb.command_handle, profile_handle, source_id, Some(cb.get_callback())));
+        assert_eq!(error::INVALID_PROFILE_HANDLE.code_num, cb.receive(TimeoutUtils::some_medium()).unwrap_err());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_profile_release_invalidates_existing_objects() {
+        let _setup = SetupMocks::init();
+
+        let profile_handle = _vcx_profile_create();
+
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        let source_id = CString::new("test_vcx_profile_release_invalidates_existing_objects").unwrap().into_raw();
+        assert_eq!(error::SUCCESS.code_num, vcx_connection_create_in_profile(cb.command_handle, profile_handle, source_id, Some(cb.get_callback())));
+        let connection_handle = cb.receive(TimeoutUtils::some_medium()).unwrap();
+        assert_eq!(WalletHandle(42), connection::get_wallet_handle(connection_handle).unwrap());
+
+        assert_eq!(error::SUCCESS.code_num, vcx_profile_release(profile_handle));
+
+        assert_eq!(VcxErrorKind::InvalidProfileHandle, connection::get_wallet_handle(connection_handle).unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_profile_binds_schemas_and_cred_defs() {
+        let _setup = SetupMocks::init();
+
+        let profile_handle = _vcx_profile_create();
+
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        let source_id = CString::new("test_vcx_profile_binds_schemas_and_cred_defs").unwrap().into_raw();
+        let schema_name = CString::new("Test Schema").unwrap().into_raw();
+        let version = CString::new("1.0").unwrap().into_raw();
+        let schema_data = CString::new(r#"["name","age"]"#).unwrap().into_raw();
+        assert_eq!(error::SUCCESS.code_num, vcx_schema_create_in_profile(cb.command_handle, profile_handle, source_id, schema_name, version, schema_data, 0, Some(cb.get_callback())));
+        let schema_handle = cb.receive(TimeoutUtils::some_medium()).unwrap();
+        assert_eq!(Some(profile_handle), schema::get_profile_handle(schema_handle));
+
+        let cred_def_data = credential_def::to_string(credential_def::tests::create_cred_def_fake()).unwrap();
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        let cred_def_data = CString::new(cred_def_data).unwrap().into_raw();
+        assert_eq!(error::SUCCESS.code_num, vcx_credentialdef_deserialize_in_profile(cb.command_handle, profile_handle, cred_def_data, Some(cb.get_callback())));
+        let cred_def_handle = cb.receive(TimeoutUtils::some_medium()).unwrap();
+        assert_eq!(Some(profile_handle), credential_def::get_profile_handle(cred_def_handle));
+    }
+}
@@ -20,13 +20,16 @@ use crate::utils::error;
     The communication method can be specified as a config option on one of *_init functions.
 
     aries:
-        VcxStateType::VcxStateInitialized - once `vcx_proof_create` (create Proof object) is called.
+        VcxStateType::VcxStateInitialized - once `vcx_proof_create` or `vcx_proof_create_from_proposal` (create Proof object) is called.
 
         VcxStateType::VcxStateOfferSent - once `vcx_credential_send_request` (send `PresentationRequest` message) is called.
+                                          or `vcx_proof_accept_presentation_proposal` / `vcx_proof_counter_presentation_proposal`
+                                          (send `PresentationRequest` message in response to `PresentationProposal`) is called.
 
         VcxStateType::VcxStateAccepted - once `Presentation` messages is received.
         VcxStateType::None - once `ProblemReport` messages is received.
-        VcxStateType::None - once `PresentationProposal` messages is received.
+        VcxStateType::VcxStateInitialized - once `PresentationProposal` messages is received.
+        VcxStateType::None - once `vcx_proof_reject_presentation_proposal` (send `ProblemReport` message) is called.
         VcxStateType::None - on `Presentation` validation failed.
                                                 use `vcx_proof_update_state` or `vcx_proof_update_state_with_message` functions for state updates.
 
@@ -34,11 +37,17 @@ use crate::utils::error;
 
     aries: RFC - https://github.com/hyperledger/aries-rfcs/tree/7b6b93acbaf9611d3c892c4bada142fe2613de6e/features/0037-present-proof#propose-presentation
         VcxStateType::None - `vcx_proof_create` - VcxStateType::VcxStateInitialized
+        VcxStateType::None - `vcx_proof_create_from_proposal` - VcxStateType::VcxStateInitialized (proposal received)
+
+        VcxStateType::VcxStateInitialized (proposal received) - `vcx_proof_accept_presentation_proposal` - VcxStateType::VcxStateOfferSent
+        VcxStateType::VcxStateInitialized (proposal received) - `vcx_proof_counter_presentation_proposal` - VcxStateType::VcxStateOfferSent
+        VcxStateType::VcxStateInitialized (proposal received) - `vcx_proof_reject_presentation_proposal` - VcxStateType::None
+        VcxStateType::VcxStateInitialized (proposal received) - received `ProblemReport` - VcxStateType::None
 
         VcxStateType::VcxStateInitialized - `vcx_credential_send_request` - VcxStateType::VcxStateOfferSent
 
         VcxStateType::VcxStateOfferSent - received `Presentation` - VcxStateType::VcxStateAccepted
-        VcxStateType::VcxStateOfferSent - received `PresentationProposal` - VcxStateType::None
+        VcxStateType::VcxStateOfferSent - received `PresentationProposal` - VcxStateType::VcxStateInitialized (proposal received)
         VcxStateType::VcxStateOfferSent - received `ProblemReport` - VcxStateType::None
 
     # Messages
@@ -76,7 +85,7 @@ use crate::utils::error;
 /// requested_predicates: predicate specifications prover must provide claim for
 ///          { // set of requested predicates
 ///             "name": attribute name, (case insensitive and ignore spaces)
-///             "p_type": predicate type (Currently ">=" only)
+///             "p_type": predicate type, one of ">=", ">", "<=", "<" (or "GE", "GT", "LE", "LT")
 ///             "p_value": int predicate value
 ///             "restrictions":  Optional<wql query> -  set of restrictions applying to requested credentials. (see below)
 ///             "non_revoked": Optional<{
@@ -85,7 +94,7 @@ use crate::utils::error;
 ///             }>
 ///          },
 ///
-/// # Example requested_predicates -> "[{"name":"attrName","p_type":"GE","p_value":9,"restrictions":["issuer_did":"did","schema_id":"id","schema_issuer_did":"did","schema_name":"name","schema_version":"1.1.1","cred_def_id":"id"}]]"
+/// # Example requested_predicates -> "[{"name":"attrName","p_type":">=","p_value":9,"restrictions":["issuer_did":"did","schema_id":"id","schema_issuer_did":"did","schema_name":"name","schema_version":"1.1.1","cred_def_id":"id"}]]"
 ///
 /// revocation_interval:  Optional<<revocation_interval>>, // see below,
 ///                        // If specified, prover must proof non-revocation
@@ -108,8 +117,11 @@ use crate::utils::error;
 ///         // the following keys can be used for every `attribute name` in credential.
 ///         "attr::<attribute name>::marker": "1", - to filter based on existence of a specific attribute
 ///         "attr::<attribute name>::value": <attribute raw value>, - to filter based on value of a specific attribute
+///     Keys can be combined by "$or", "$and" and "$not", values can use "$neq", "$gt", "$gte", "$lt", "$lte", "$like" and "$in".
+///     Identifiers used in restrictions have to be either all fully qualified or all unqualified.
 ///
 /// cb: Callback that provides proof handle and error status of request.
+///     Fails with InvalidProofRequest error if the request does not satisfy rules above.
 ///
 /// #Returns
 /// Error code as a u32
@@ -123,6 +135,41 @@ pub extern fn vcx_proof_create(command_handle: CommandHandle,
                                cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, proof_handle: u32)>) -> u32 {
     info!("vcx_proof_create >>>");
 
+    _vcx_proof_create(command_handle, None, source_id, requested_attrs, requested_predicates, revocation_interval, name, cb)
+}
+
+/// Same as `vcx_proof_create`, the object is created within the profile and bound to it.
+/// Every operation on the object runs within the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_proof_create`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_proof_create_in_profile(command_handle: CommandHandle,
+                                          profile_handle: u32,
+                                          source_id: *const c_char,
+                                          requested_attrs: *const c_char,
+                                          requested_predicates: *const c_char,
+                                          revocation_interval: *const c_char,
+                                          name: *const c_char,
+                                          cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, proof_handle: u32)>) -> u32 {
+    info!("vcx_proof_create_in_profile >>>");
+
+    _vcx_proof_create(command_handle, Some(profile_handle), source_id, requested_attrs, requested_predicates, revocation_interval, name, cb)
+}
+
+fn _vcx_proof_create(command_handle: CommandHandle,
+                     profile_handle: Option<u32>,
+                     source_id: *const c_char,
+                     requested_attrs: *const c_char,
+                     requested_predicates: *const c_char,
+                     revocation_interval: *const c_char,
+                     name: *const c_char,
+                     cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, proof_handle: u32)>) -> u32 {
     check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
     check_useful_c_str!(requested_attrs, VcxErrorKind::InvalidOption);
     check_useful_c_str!(requested_predicates, VcxErrorKind::InvalidOption);
@@ -134,7 +181,7 @@ pub extern fn vcx_proof_create(command_handle: CommandHandle,
            command_handle, source_id, requested_attrs, requested_predicates, revocation_interval, name);
 
     execute(move || {
-        let (rc, handle) = match proof::create_proof(source_id, requested_attrs, requested_predicates, revocation_interval, name) {
+        let (rc, handle) = match proof::create_proof_in_profile(profile_handle, source_id, requested_attrs, requested_predicates, revocation_interval, name) {
             Ok(x) => {
                 trace!("vcx_proof_create_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
                        command_handle, error::SUCCESS.message, x, proof::get_source_id(x).unwrap_or_default());
@@ -393,6 +440,33 @@ pub extern fn vcx_proof_deserialize(command_handle: CommandHandle,
                                     cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, proof_handle: u32)>) -> u32 {
     info!("vcx_proof_deserialize >>>");
 
+    _vcx_proof_deserialize(command_handle, None, proof_data, cb)
+}
+
+/// Same as `vcx_proof_deserialize`, the object is created within the profile and bound to it.
+/// Every operation on the object runs within the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_proof_deserialize`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_proof_deserialize_in_profile(command_handle: CommandHandle,
+                                               profile_handle: u32,
+                                               proof_data: *const c_char,
+                                               cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, proof_handle: u32)>) -> u32 {
+    info!("vcx_proof_deserialize_in_profile >>>");
+
+    _vcx_proof_deserialize(command_handle, Some(profile_handle), proof_data, cb)
+}
+
+fn _vcx_proof_deserialize(command_handle: CommandHandle,
+                          profile_handle: Option<u32>,
+                          proof_data: *const c_char,
+                          cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, proof_handle: u32)>) -> u32 {
     check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
     check_useful_c_str!(proof_data, VcxErrorKind::InvalidOption);
 
@@ -400,7 +474,7 @@ pub extern fn vcx_proof_deserialize(command_handle: CommandHandle,
            command_handle, proof_data);
 
     execute(move || {
-        let (rc, handle) = match proof::from_string(&proof_data) {
+        let (rc, handle) = match proof::from_string_in_profile(profile_handle, &proof_data) {
             Ok(x) => {
                 trace!("vcx_proof_deserialize_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
                        command_handle, error::SUCCESS.message, x, proof::get_source_id(x).unwrap_or_default());
@@ -420,6 +494,41 @@ pub extern fn vcx_proof_deserialize(command_handle: CommandHandle,
     error::SUCCESS.code_num
 }
 
+/// Sets version of the Present Proof protocol used for the exchange.
+/// Has to be called before the proof request is sent; by default version "1.0" is used.
+///
+/// #Params
+/// proof_handle: Proof handle that was provided during creation. Used to identify proof object
+///
+/// protocol_version: protocol version to use, either "1.0" or "2.0"
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_proof_set_protocol_version(proof_handle: u32, protocol_version: *const c_char) -> u32 {
+    info!("vcx_proof_set_protocol_version >>>");
+
+    check_useful_c_str!(protocol_version, VcxErrorKind::InvalidOption);
+
+    if !proof::is_valid_handle(proof_handle) {
+        return VcxError::from(VcxErrorKind::InvalidProofHandle).into();
+    }
+
+    let source_id = proof::get_source_id(proof_handle).unwrap_or_default();
+    match proof::set_protocol_version(proof_handle, &protocol_version) {
+        Ok(()) => {
+            trace!("(vcx_proof_set_protocol_version proof_handle: {}, protocol_version: {}, rc: {}), source_id: {}",
+                   proof_handle, protocol_version, error::SUCCESS.message, source_id);
+            error::SUCCESS.code_num
+        }
+        Err(e) => {
+            warn!("(vcx_proof_set_protocol_version proof_handle: {}, protocol_version: {}, rc: {}), source_id: {}",
+                  proof_handle, protocol_version, e, source_id);
+            e.into()
+        }
+    }
+}
+
 /// Releases the proof object by de-allocating memory
 ///
 /// #Params
@@ -553,6 +662,410 @@ pub extern fn vcx_proof_get_request_msg(command_handle: CommandHandle,
 }
 
 
+/// Create a new Proof object from a presentation proposal received from the prover.
+/// Accept, counter or reject the proposal to continue the presentation.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// source_id: Enterprise's personal identification for the user.
+///
+/// presentation_proposal: received presentation proposal (see `vcx_proof_get_presentation_proposals`)
+///
+/// cb: Callback that provides proof handle and error status of request.
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_proof_create_from_proposal(command_handle: CommandHandle,
+                                             source_id: *const c_char,
+                                             presentation_proposal: *const c_char,
+                                             cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, proof_handle: u32)>) -> u32 {
+    info!("vcx_proof_create_from_proposal >>>");
+
+    _vcx_proof_create_from_proposal(command_handle, None, source_id, presentation_proposal, cb)
+}
+
+/// Same as `vcx_proof_create_from_proposal`, the object is created within the profile and bound to it.
+/// Every operation on the object runs within the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_proof_create_from_proposal`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_proof_create_from_proposal_in_profile(command_handle: CommandHandle,
+                                                        profile_handle: u32,
+                                                        source_id: *const c_char,
+                                                        presentation_proposal: *const c_char,
+                                                        cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, proof_handle: u32)>) -> u32 {
+    info!("vcx_proof_create_from_proposal_in_profile >>>");
+
+    _vcx_proof_create_from_proposal(command_handle, Some(profile_handle), source_id, presentation_proposal, cb)
+}
+
+fn _vcx_proof_create_from_proposal(command_handle: CommandHandle,
+                                   profile_handle: Option<u32>,
+                                   source_id: *const c_char,
+                                   presentation_proposal: *const c_char,
+                                   cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, proof_handle: u32)>) -> u32 {
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(source_id, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(presentation_proposal, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_proof_create_from_proposal(command_handle: {}, source_id: {}, presentation_proposal: {})",
+           command_handle, source_id, secret!(&presentation_proposal));
+
+    execute(move || {
+        match proof::create_proof_from_proposal_in_profile(profile_handle, source_id.clone(), &presentation_proposal) {
+            Ok(x) => {
+                trace!("vcx_proof_create_from_proposal_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
+                       command_handle, error::SUCCESS.message, x, source_id);
+                cb(command_handle, error::SUCCESS.code_num, x);
+            }
+            Err(x) => {
+                warn!("vcx_proof_create_from_proposal_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
+                      command_handle, x, 0, source_id);
+                cb(command_handle, x.into(), 0);
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Get the presentation proposal the Proof object was created from or countered with.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// proof_handle: Proof handle that was provided during creation. Used to access proof object
+///
+/// cb: Callback that provides presentation proposal as JSON string and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_proof_get_presentation_proposal(command_handle: CommandHandle,
+                                                  proof_handle: u32,
+                                                  cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, presentation_proposal: *const c_char)>) -> u32 {
+    info!("vcx_proof_get_presentation_proposal >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    let source_id = proof::get_source_id(proof_handle).unwrap_or_default();
+    trace!("vcx_proof_get_presentation_proposal(command_handle: {}, proof_handle: {}) source_id: {}",
+           command_handle, proof_handle, source_id);
+    if !proof::is_valid_handle(proof_handle) {
+        return VcxError::from(VcxErrorKind::InvalidProofHandle).into();
+    }
+
+    execute(move || {
+        match proof::get_presentation_proposal(proof_handle) {
+            Ok(presentation_proposal) => {
+                let presentation_proposal = CStringUtils::string_to_cstring(presentation_proposal);
+                trace!("vcx_proof_get_presentation_proposal_cb(command_handle: {}, rc: {}, proof_handle: {}) source_id: {}",
+                       command_handle, error::SUCCESS.code_num, proof_handle, source_id);
+                cb(command_handle, error::SUCCESS.code_num, presentation_proposal.as_ptr());
+            }
+            Err(x) => {
+                warn!("vcx_proof_get_presentation_proposal_cb(command_handle: {}, rc: {}, proof_handle: {}) source_id: {}",
+                      command_handle, x, proof_handle, source_id);
+                cb(command_handle, x.into(), ptr::null_mut())
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Get the proof request data matching the received presentation proposal.
+/// Proposed attributes and predicates are restricted to the proposed credential definitions.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// proof_handle: Proof handle that was provided during creation. Used to access proof object
+///
+/// cb: Callback that provides proof request data (requested attributes and predicates) as JSON string and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_proof_get_proposed_request_data(command_handle: CommandHandle,
+                                                  proof_handle: u32,
+                                                  cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, request_data: *const c_char)>) -> u32 {
+    info!("vcx_proof_get_proposed_request_data >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    let source_id = proof::get_source_id(proof_handle).unwrap_or_default();
+    trace!("vcx_proof_get_proposed_request_data(command_handle: {}, proof_handle: {}) source_id: {}",
+           command_handle, proof_handle, source_id);
+    if !proof::is_valid_handle(proof_handle) {
+        return VcxError::from(VcxErrorKind::InvalidProofHandle).into();
+    }
+
+    execute(move || {
+        match proof::get_proposed_proof_request_data(proof_handle) {
+            Ok(request_data) => {
+                let request_data = CStringUtils::string_to_cstring(request_data);
+                trace!("vcx_proof_get_proposed_request_data_cb(command_handle: {}, rc: {}, proof_handle: {}) source_id: {}",
+                       command_handle, error::SUCCESS.code_num, proof_handle, source_id);
+                cb(command_handle, error::SUCCESS.code_num, request_data.as_ptr());
+            }
+            Err(x) => {
+                warn!("vcx_proof_get_proposed_request_data_cb(command_handle: {}, rc: {}, proof_handle: {}) source_id: {}",
+                      command_handle, x, proof_handle, source_id);
+                cb(command_handle, x.into(), ptr::null_mut())
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Accept the received presentation proposal by sending a proof request built from it.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// proof_handle: Proof handle that was provided during creation. Used to access proof object
+///
+/// connection_handle: Connection handle that identifies pairwise connection
+///
+/// cb: provides any error status of the proof_request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_proof_accept_presentation_proposal(command_handle: CommandHandle,
+                                                     proof_handle: u32,
+                                                     connection_handle: u32,
+                                                     cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_proof_accept_presentation_proposal >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    let source_id = proof::get_source_id(proof_handle).unwrap_or_default();
+    trace!("vcx_proof_accept_presentation_proposal(command_handle: {}, proof_handle: {}, connection_handle: {}) source_id: {}",
+           command_handle, proof_handle, connection_handle, source_id);
+    if !proof::is_valid_handle(proof_handle) {
+        return VcxError::from(VcxErrorKind::InvalidProofHandle).into();
+    }
+
+    if !connection::is_valid_handle(connection_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    execute(move || {
+        let err = match proof::accept_presentation_proposal(proof_handle, connection_handle, None) {
+            Ok(x) => {
+                trace!("vcx_proof_accept_presentation_proposal_cb(command_handle: {}, rc: {}, proof_handle: {}) source_id: {}",
+                       command_handle, 0, proof_handle, source_id);
+                x
+            }
+            Err(x) => {
+                warn!("vcx_proof_accept_presentation_proposal_cb(command_handle: {}, rc: {}, proof_handle: {}) source_id: {}",
+                      command_handle, x, proof_handle, source_id);
+                x.into()
+            }
+        };
+
+        cb(command_handle, err);
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Answer the received presentation proposal with a different proof request.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// proof_handle: Proof handle that was provided during creation. Used to access proof object
+///
+/// connection_handle: Connection handle that identifies pairwise connection
+///
+/// requested_attrs: Describes requested attribute (see `vcx_proof_create`)
+///
+/// requested_predicates: predicate specifications prover must provide claim for (see `vcx_proof_create`)
+///
+/// revocation_interval: Optional timestamps to request revocation proof
+///
+/// name: Name of the proof request
+///
+/// cb: provides any error status of the proof_request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_proof_counter_presentation_proposal(command_handle: CommandHandle,
+                                                      proof_handle: u32,
+                                                      connection_handle: u32,
+                                                      requested_attrs: *const c_char,
+                                                      requested_predicates: *const c_char,
+                                                      revocation_interval: *const c_char,
+                                                      name: *const c_char,
+                                                      cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_proof_counter_presentation_proposal >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(requested_attrs, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(requested_predicates, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(name, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(revocation_interval, VcxErrorKind::InvalidOption);
+
+    let source_id = proof::get_source_id(proof_handle).unwrap_or_default();
+    trace!("vcx_proof_counter_presentation_proposal(command_handle: {}, proof_handle: {}, connection_handle: {}, requested_attrs: {}, requested_predicates: {}, revocation_interval: {}, name: {}) source_id: {}",
+           command_handle, proof_handle, connection_handle, requested_attrs, requested_predicates, revocation_interval, name, source_id);
+    if !proof::is_valid_handle(proof_handle) {
+        return VcxError::from(VcxErrorKind::InvalidProofHandle).into();
+    }
+
+    if !connection::is_valid_handle(connection_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    execute(move || {
+        let err = match proof::counter_presentation_proposal(proof_handle, connection_handle, requested_attrs, requested_predicates, revocation_interval, name, None) {
+            Ok(x) => {
+                trace!("vcx_proof_counter_presentation_proposal_cb(command_handle: {}, rc: {}, proof_handle: {}) source_id: {}",
+                       command_handle, 0, proof_handle, source_id);
+                x
+            }
+            Err(x) => {
+                warn!("vcx_proof_counter_presentation_proposal_cb(command_handle: {}, rc: {}, proof_handle: {}) source_id: {}",
+                      command_handle, x, proof_handle, source_id);
+                x.into()
+            }
+        };
+
+        cb(command_handle, err);
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Reject the received presentation proposal by sending a problem report.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// proof_handle: Proof handle that was provided during creation. Used to access proof object
+///
+/// connection_handle: Connection handle that identifies pairwise connection
+///
+/// reason: human-readable string that explain the reason of rejection
+///
+/// cb: provides any error status of the rejection
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_proof_reject_presentation_proposal(command_handle: CommandHandle,
+                                                     proof_handle: u32,
+                                                     connection_handle: u32,
+                                                     reason: *const c_char,
+                                                     cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_proof_reject_presentation_proposal >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(reason, VcxErrorKind::InvalidOption);
+
+    let source_id = proof::get_source_id(proof_handle).unwrap_or_default();
+    trace!("vcx_proof_reject_presentation_proposal(command_handle: {}, proof_handle: {}, connection_handle: {}, reason: {}) source_id: {}",
+           command_handle, proof_handle, connection_handle, reason, source_id);
+    if !proof::is_valid_handle(proof_handle) {
+        return VcxError::from(VcxErrorKind::InvalidProofHandle).into();
+    }
+
+    if !connection::is_valid_handle(connection_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    execute(move || {
+        let err = match proof::reject_presentation_proposal(proof_handle, connection_handle, reason) {
+            Ok(x) => {
+                trace!("vcx_proof_reject_presentation_proposal_cb(command_handle: {}, rc: {}, proof_handle: {}) source_id: {}",
+                       command_handle, 0, proof_handle, source_id);
+                x
+            }
+            Err(x) => {
+                warn!("vcx_proof_reject_presentation_proposal_cb(command_handle: {}, rc: {}, proof_handle: {}) source_id: {}",
+                      command_handle, x, proof_handle, source_id);
+                x.into()
+            }
+        };
+
+        cb(command_handle, err);
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Queries agency for presentation proposals from the given connection.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// connection_handle: Connection to query for presentation proposals.
+///
+/// cb: Callback that provides any presentation proposals and error status of query
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_proof_get_presentation_proposals(command_handle: CommandHandle,
+                                                   connection_handle: u32,
+                                                   cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, proposals: *const c_char)>) -> u32 {
+    info!("vcx_proof_get_presentation_proposals >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    if !connection::is_valid_handle(connection_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    trace!("vcx_proof_get_presentation_proposals(command_handle: {}, connection_handle: {})",
+           command_handle, connection_handle);
+
+    execute(move || {
+        match proof::get_presentation_proposal_messages(connection_handle) {
+            Ok(x) => {
+                trace!("vcx_proof_get_presentation_proposals_cb(command_handle: {}, rc: {}, msg: {})",
+                       command_handle, error::SUCCESS.message, x);
+                let msg = CStringUtils::string_to_cstring(x);
+                cb(command_handle, error::SUCCESS.code_num, msg.as_ptr());
+            }
+            Err(x) => {
+                warn!("vcx_proof_get_presentation_proposals_cb(command_handle: {}, rc: {}, msg: null)",
+                      command_handle, x);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
 /// Get Proof Msg
 ///
 /// *Note* This replaces vcx_get_proof. You no longer need a connection handle.
@@ -601,6 +1114,63 @@ pub extern fn vcx_get_proof_msg(command_handle: CommandHandle,
     error::SUCCESS.code_num
 }
 
+/// Get report of the received proof
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// proof_handle: Proof handle that was provided during creation. Used to identify proof object
+///
+/// cb: Callback that provides verification report and error status of request
+///     {
+///         "verified": bool,
+///         "failure": Optional<{"code": u32, "reason": string}>, // why the proof has been rejected
+///         "revealed_attrs": {"<referent>": {"name": Optional<string>, "raw": string, "encoded": string, "sub_proof_index": u32}},
+///         "revealed_attr_groups": {"<referent>": {"values": {"<name>": {"raw": string, "encoded": string}}, "sub_proof_index": u32}},
+///         "unrevealed_attrs": {"<referent>": {"name": Optional<string>, "sub_proof_index": u32}},
+///         "self_attested_attrs": {"<referent>": {"name": Optional<string>, "value": string}},
+///         "predicates": {"<referent>": {"name": string, "p_type": string, "p_value": i32, "sub_proof_index": Optional<u32>, "satisfied": bool}},
+///         "identifiers": [{"schema_id": string, "cred_def_id": string, "rev_reg_id": Optional<string>, "timestamp": Optional<u64>}] // indexed by sub_proof_index
+///     }
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_proof_get_verification_report(command_handle: CommandHandle,
+                                                proof_handle: u32,
+                                                cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, report: *const c_char)>) -> u32 {
+    info!("vcx_proof_get_verification_report >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    let source_id = proof::get_source_id(proof_handle).unwrap_or_default();
+    trace!("vcx_proof_get_verification_report(command_handle: {}, proof_handle: {}) source_id: {}",
+           command_handle, proof_handle, source_id);
+
+    if !proof::is_valid_handle(proof_handle) {
+        return VcxError::from(VcxErrorKind::InvalidProofHandle).into();
+    }
+
+    execute(move || {
+        match proof::get_verification_report(proof_handle) {
+            Ok(report) => {
+                trace!("vcx_proof_get_verification_report_cb(command_handle: {}, proof_handle: {}, rc: {}, report: {}) source_id: {}",
+                       command_handle, proof_handle, error::SUCCESS.message, report, source_id);
+                let report = CStringUtils::string_to_cstring(report);
+                cb(command_handle, error::SUCCESS.code_num, report.as_ptr());
+            }
+            Err(err) => {
+                warn!("vcx_proof_get_verification_report_cb(command_handle: {}, proof_handle: {}, rc: {}, report: {}) source_id: {}",
+                      command_handle, proof_handle, err, "null", source_id);
+                cb(command_handle, err.into(), ptr::null_mut());
+            }
+        };
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
 #[allow(unused_variables)]
 pub extern fn vcx_proof_accepted(proof_handle: u32, response_data: *const c_char) -> u32 {
     info!("vcx_proof_accepted >>>");
@@ -763,6 +1333,41 @@ mod tests {
         assert_eq!(proof::get_state(proof_handle).unwrap(), VcxStateType::VcxStateAccepted as u32);
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_proof_create_from_proposal_and_reject() {
+        let _setup = SetupMocks::init();
+
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        assert_eq!(vcx_proof_create_from_proposal(cb.command_handle,
+                                                  CString::new(DEFAULT_PROOF_NAME).unwrap().into_raw(),
+                                                  CString::new(mockdata_proof::ARIES_PRESENTATION_PROPOSAL).unwrap().into_raw(),
+                                                  Some(cb.get_callback())),
+                   error::SUCCESS.code_num);
+        let proof_handle = cb.receive(TimeoutUtils::some_medium()).unwrap();
+        assert_eq!(proof::get_state(proof_handle).unwrap(), VcxStateType::VcxStateInitialized as u32);
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_proof_get_presentation_proposal(cb.command_handle, proof_handle, Some(cb.get_callback())), error::SUCCESS.code_num);
+        cb.receive(TimeoutUtils::some_medium()).unwrap().unwrap();
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_proof_get_proposed_request_data(cb.command_handle, proof_handle, Some(cb.get_callback())), error::SUCCESS.code_num);
+        cb.receive(TimeoutUtils::some_medium()).unwrap().unwrap();
+
+        let connection_handle = build_test_connection_inviter_requested();
+
+        let cb = return_types_u32::Return_U32::new().unwrap();
+        assert_eq!(vcx_proof_reject_presentation_proposal(cb.command_handle,
+                                                          proof_handle,
+                                                          connection_handle,
+                                                          CString::new("reason").unwrap().into_raw(),
+                                                          Some(cb.get_callback())),
+                   error::SUCCESS.code_num);
+        cb.receive(TimeoutUtils::some_medium()).unwrap();
+        assert_eq!(proof::get_state(proof_handle).unwrap(), VcxStateType::VcxStateNone as u32);
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_get_proof_fails_when_not_ready_with_proof() {
@@ -797,6 +1402,23 @@ mod tests {
         assert_eq!(vcx_proof_release(proof_handle), error::INVALID_PROOF_HANDLE.code_num);
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_proof_get_verification_report_fails_without_report() {
+        let _setup = SetupMocks::init();
+
+        let proof_handle = proof::from_string(mockdata_proof::SERIALIZIED_PROOF_PRESENTATION_REQUEST_SENT).unwrap();
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_proof_get_verification_report(cb.command_handle, proof_handle, Some(cb.get_callback())), error::SUCCESS.code_num);
+        assert_eq!(error::NOT_READY.code_num, cb.receive(TimeoutUtils::some_medium()).unwrap_err());
+
+        // proof verified before reports have been introduced
+        let proof_handle = proof::from_string(mockdata_proof::SERIALIZIED_PROOF_REVOKED).unwrap();
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_proof_get_verification_report(cb.command_handle, proof_handle, Some(cb.get_callback())), error::SUCCESS.code_num);
+        assert_eq!(error::INVALID_STATE.code_num, cb.receive(TimeoutUtils::some_medium()).unwrap_err());
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_vcx_connection_get_state() {
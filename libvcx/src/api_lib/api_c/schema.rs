@@ -8,7 +8,7 @@ use crate::api_lib::api_handle::schema;
 use crate::api_lib::utils_c::cstring::CStringUtils;
 use crate::api_lib::utils_c::runtime::execute;
 use crate::error::prelude::*;
-use crate::settings;
+use crate::profile::ProfileContext;
 use crate::utils::error;
 
 /// Create a new Schema object and publish corresponding record on the ledger
@@ -45,25 +45,60 @@ pub extern fn vcx_schema_create(command_handle: CommandHandle,
                                 cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credentialdef_handle: u32)>) -> u32 {
     info!("vcx_schema_create >>>");
 
+    _vcx_schema_create(command_handle, None, source_id, schema_name, version, schema_data, cb)
+}
+
+/// Same as `vcx_schema_create`, the schema is created by the institution DID of the profile and bound to the profile.
+/// Every operation on the object runs within the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_schema_create`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_schema_create_in_profile(command_handle: CommandHandle,
+                                           profile_handle: u32,
+                                           source_id: *const c_char,
+                                           schema_name: *const c_char,
+                                           version: *const c_char,
+                                           schema_data: *const c_char,
+                                           _payment_handle: u32,
+                                           cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credentialdef_handle: u32)>) -> u32 {
+    info!("vcx_schema_create_in_profile >>>");
+
+    _vcx_schema_create(command_handle, Some(profile_handle), source_id, schema_name, version, schema_data, cb)
+}
+
+fn _vcx_schema_create(command_handle: CommandHandle,
+                      profile_handle: Option<u32>,
+                      source_id: *const c_char,
+                      schema_name: *const c_char,
+                      version: *const c_char,
+                      schema_data: *const c_char,
+                      cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credentialdef_handle: u32)>) -> u32 {
     check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
     check_useful_c_str!(schema_name, VcxErrorKind::InvalidOption);
     check_useful_c_str!(version, VcxErrorKind::InvalidOption);
     check_useful_c_str!(source_id, VcxErrorKind::InvalidOption);
     check_useful_c_str!(schema_data, VcxErrorKind::InvalidOption);
 
-    let issuer_did = match settings::get_config_value(settings::CONFIG_INSTITUTION_DID) {
+    let issuer_did = match ProfileContext::from_handle(profile_handle).and_then(|profile| profile.institution_did()) {
         Ok(x) => x,
         Err(x) => return x.into()
     };
-    trace!(target: "vcx", "vcx_schema_create(command_handle: {}, source_id: {}, schema_name: {},  schema_data: {})",
-           command_handle, source_id, schema_name, schema_data);
+    trace!(target: "vcx", "vcx_schema_create(command_handle: {}, profile_handle: {:?}, source_id: {}, schema_name: {},  schema_data: {})",
+           command_handle, profile_handle, source_id, schema_name, schema_data);
 
     execute(move || {
-        match schema::create_and_publish_schema(&source_id,
-                                                issuer_did,
-                                                schema_name,
-                                                version,
-                                                schema_data) {
+        match schema::create_and_publish_schema_in_profile(profile_handle,
+                                                           &source_id,
+                                                           issuer_did,
+                                                           schema_name,
+                                                           version,
+                                                           schema_data) {
             Ok(x) => {
                 trace!(target: "vcx", "vcx_schema_create_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
                        command_handle, error::SUCCESS.message, x, source_id);
@@ -117,6 +152,45 @@ pub extern fn vcx_schema_prepare_for_endorser(command_handle: CommandHandle,
                                                                    schema_transaction: *const c_char)>) -> u32 {
     info!("vcx_schema_prepare_for_endorser >>>");
 
+    _vcx_schema_prepare_for_endorser(command_handle, None, source_id, schema_name, version, schema_data, endorser, cb)
+}
+
+/// Same as `vcx_schema_prepare_for_endorser`, the schema is created by the institution DID of the profile and bound to the profile.
+/// Every operation on the object runs within the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_schema_prepare_for_endorser`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_schema_prepare_for_endorser_in_profile(command_handle: CommandHandle,
+                                                         profile_handle: u32,
+                                                         source_id: *const c_char,
+                                                         schema_name: *const c_char,
+                                                         version: *const c_char,
+                                                         schema_data: *const c_char,
+                                                         endorser: *const c_char,
+                                                         cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32,
+                                                                              schema_handle: u32,
+                                                                              schema_transaction: *const c_char)>) -> u32 {
+    info!("vcx_schema_prepare_for_endorser_in_profile >>>");
+
+    _vcx_schema_prepare_for_endorser(command_handle, Some(profile_handle), source_id, schema_name, version, schema_data, endorser, cb)
+}
+
+fn _vcx_schema_prepare_for_endorser(command_handle: CommandHandle,
+                                    profile_handle: Option<u32>,
+                                    source_id: *const c_char,
+                                    schema_name: *const c_char,
+                                    version: *const c_char,
+                                    schema_data: *const c_char,
+                                    endorser: *const c_char,
+                                    cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32,
+                                                         schema_handle: u32,
+                                                         schema_transaction: *const c_char)>) -> u32 {
     check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
     check_useful_c_str!(schema_name, VcxErrorKind::InvalidOption);
     check_useful_c_str!(version, VcxErrorKind::InvalidOption);
@@ -124,20 +198,21 @@ pub extern fn vcx_schema_prepare_for_endorser(command_handle: CommandHandle,
     check_useful_c_str!(schema_data, VcxErrorKind::InvalidOption);
     check_useful_c_str!(endorser, VcxErrorKind::InvalidOption);
 
-    let issuer_did = match settings::get_config_value(settings::CONFIG_INSTITUTION_DID) {
+    let issuer_did = match ProfileContext::from_handle(profile_handle).and_then(|profile| profile.institution_did()) {
         Ok(x) => x,
         Err(x) => return x.into()
     };
-    trace!(target: "vcx", "vcx_schema_prepare_for_endorser(command_handle: {}, source_id: {}, schema_name: {},  schema_data: {},  endorser: {})",
-           command_handle, source_id, schema_name, schema_data, endorser);
+    trace!(target: "vcx", "vcx_schema_prepare_for_endorser(command_handle: {}, profile_handle: {:?}, source_id: {}, schema_name: {},  schema_data: {},  endorser: {})",
+           command_handle, profile_handle, source_id, schema_name, schema_data, endorser);
 
     execute(move || {
-        match schema::prepare_schema_for_endorser(&source_id,
-                                                  issuer_did,
-                                                  schema_name,
-                                                  version,
-                                                  schema_data,
-                                                  endorser) {
+        match schema::prepare_schema_for_endorser_in_profile(profile_handle,
+                                                             &source_id,
+                                                             issuer_did,
+                                                             schema_name,
+                                                             version,
+                                                             schema_data,
+                                                             endorser) {
             Ok((handle, transaction)) => {
                 trace!(target: "vcx", "vcx_schema_prepare_for_endorser(command_handle: {}, rc: {}, handle: {}, transaction: {}) source_id: {}",
                        command_handle, error::SUCCESS.message, handle, transaction, source_id);
@@ -222,12 +297,39 @@ pub extern fn vcx_schema_deserialize(command_handle: CommandHandle,
                                      cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, schema_handle: u32)>) -> u32 {
     info!("vcx_schema_deserialize >>>");
 
+    _vcx_schema_deserialize(command_handle, None, schema_data, cb)
+}
+
+/// Same as `vcx_schema_deserialize`, the object is created within the profile and bound to it.
+/// Every operation on the object runs within the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_schema_deserialize`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_schema_deserialize_in_profile(command_handle: CommandHandle,
+                                                profile_handle: u32,
+                                                schema_data: *const c_char,
+                                                cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, schema_handle: u32)>) -> u32 {
+    info!("vcx_schema_deserialize_in_profile >>>");
+
+    _vcx_schema_deserialize(command_handle, Some(profile_handle), schema_data, cb)
+}
+
+fn _vcx_schema_deserialize(command_handle: CommandHandle,
+                           profile_handle: Option<u32>,
+                           schema_data: *const c_char,
+                           cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, schema_handle: u32)>) -> u32 {
     check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
     check_useful_c_str!(schema_data, VcxErrorKind::InvalidOption);
 
     trace!("vcx_schema_deserialize(command_handle: {}, schema_data: {})", command_handle, schema_data);
     execute(move || {
-        match schema::from_string(&schema_data) {
+        match schema::from_string_in_profile(profile_handle, &schema_data) {
             Ok(x) => {
                 trace!("vcx_schema_deserialize_cb(command_handle: {}, rc: {}, handle: {}), source_id: {}",
                        command_handle, error::SUCCESS.message, x, schema::get_source_id(x).unwrap_or_default());
@@ -338,6 +440,34 @@ pub extern fn vcx_schema_get_attributes(command_handle: CommandHandle,
                                         cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, s_handle: u32, schema_attrs: *const c_char)>) -> u32 {
     info!("vcx_schema_get_attributes >>>");
 
+    _vcx_schema_get_attributes(command_handle, None, source_id, schema_id, cb)
+}
+
+/// Same as `vcx_schema_get_attributes`, the schema is read from the pool of the profile and the object is bound to the profile.
+///
+/// #Params
+/// profile_handle: handle of the profile created by `vcx_profile_create`
+///
+/// other params are the same as in `vcx_schema_get_attributes`
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_schema_get_attributes_in_profile(command_handle: CommandHandle,
+                                                   profile_handle: u32,
+                                                   source_id: *const c_char,
+                                                   schema_id: *const c_char,
+                                                   cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, s_handle: u32, schema_attrs: *const c_char)>) -> u32 {
+    info!("vcx_schema_get_attributes_in_profile >>>");
+
+    _vcx_schema_get_attributes(command_handle, Some(profile_handle), source_id, schema_id, cb)
+}
+
+fn _vcx_schema_get_attributes(command_handle: CommandHandle,
+                              profile_handle: Option<u32>,
+                              source_id: *const c_char,
+                              schema_id: *const c_char,
+                              cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, s_handle: u32, schema_attrs: *const c_char)>) -> u32 {
     check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
     check_useful_c_str!(source_id, VcxErrorKind::InvalidOption);
     check_useful_c_str!(schema_id, VcxErrorKind::InvalidOption);
@@ -345,7 +475,7 @@ pub extern fn vcx_schema_get_attributes(command_handle: CommandHandle,
            command_handle, source_id, schema_id);
 
     execute(move || {
-        match schema::get_schema_attrs(source_id, schema_id) {
+        match schema::get_schema_attrs_in_profile(profile_handle, source_id, schema_id) {
             Ok((handle, data)) => {
                 let data: serde_json::Value = serde_json::from_str(&data).unwrap();
                 let data = data["data"].clone();
@@ -12,7 +12,8 @@ use crate::api_lib::api_handle::connection;
 use crate::api_lib::utils_c::cstring::CStringUtils;
 use crate::api_lib::utils_c::runtime::execute;
 use crate::error::prelude::*;
-use crate::libindy::utils::payments;
+use crate::libindy::utils::{ledger, payments};
+use crate::profile::ProfileContext;
 use crate::utils::constants::*;
 use crate::utils::error;
 use crate::utils::provision::AgentProvisionConfig;
@@ -112,7 +113,7 @@ pub extern fn vcx_ledger_get_fees(command_handle: CommandHandle,
            command_handle);
 
     execute(move || {
-        match crate::libindy::utils::payments::get_ledger_fees() {
+        match crate::libindy::utils::payments::get_ledger_fees(&ProfileContext::Global) {
             Ok(x) => {
                 trace!("vcx_ledger_get_fees_cb(command_handle: {}, rc: {}, fees: {})",
                        command_handle, error::SUCCESS.message, x);
@@ -478,7 +479,7 @@ pub extern fn vcx_get_request_price(command_handle: CommandHandle,
            command_handle, action_json, requester_info_json);
 
     execute(move || {
-        match payments::get_request_price(action_json, requester_info_json) {
+        match payments::get_request_price(&ProfileContext::Global, action_json, requester_info_json) {
             Ok(x) => {
                 trace!(target: "vcx", "vcx_get_request_price(command_handle: {}, rc: {}, handle: {})",
                        command_handle, error::SUCCESS.message, x);
@@ -519,7 +520,7 @@ pub extern fn vcx_endorse_transaction(command_handle: CommandHandle,
            command_handle, transaction);
 
     execute(move || {
-        match crate::libindy::utils::ledger::endorse_transaction(&transaction) {
+        match crate::libindy::utils::ledger::endorse_transaction(&ProfileContext::Global, &transaction) {
             Ok(()) => {
                 trace!("vcx_endorse_transaction(command_handle: {}, rc: {})",
                        command_handle, error::SUCCESS.message);
@@ -540,6 +541,253 @@ pub extern fn vcx_endorse_transaction(command_handle: CommandHandle,
     error::SUCCESS.code_num
 }
 
+/// Writes NYM transaction on behalf of the institution DID, which pays the fees if there are any.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// target_did: DID the NYM is written for
+/// verkey: (Optional) verkey of the target DID
+/// alias: (Optional) alias of the target DID
+/// role: (Optional) role of the target DID: "TRUSTEE", "STEWARD", "TRUST_ANCHOR", "ENDORSER", "NETWORK_MONITOR", "" to reset role
+/// endorser: (Optional) DID of the endorser that will submit the transaction.
+///     If set, the transaction is not written but returned to be passed to the endorser (see vcx_endorse_transaction)
+///
+/// cb: Callback that provides the transaction for endorser (null if the transaction has been written) and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_ledger_publish_nym(command_handle: CommandHandle,
+                                     target_did: *const c_char,
+                                     verkey: *const c_char,
+                                     alias: *const c_char,
+                                     role: *const c_char,
+                                     endorser: *const c_char,
+                                     cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, transaction: *const c_char)>) -> u32 {
+    info!("vcx_ledger_publish_nym >>>");
+
+    check_useful_c_str!(target_did, VcxErrorKind::InvalidDid);
+    check_useful_opt_c_str!(verkey, VcxErrorKind::InvalidOption);
+    check_useful_opt_c_str!(alias, VcxErrorKind::InvalidOption);
+    check_useful_opt_c_str!(role, VcxErrorKind::InvalidOption);
+    check_useful_opt_c_str!(endorser, VcxErrorKind::InvalidOption);
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_ledger_publish_nym(command_handle: {}, target_did: {}, verkey: {:?}, alias: {:?}, role: {:?}, endorser: {:?})",
+           command_handle, target_did, verkey, alias, role, endorser);
+
+    execute(move || {
+        let verkey = verkey.as_ref().map(String::as_str);
+        let alias = alias.as_ref().map(String::as_str);
+        let role = role.as_ref().map(String::as_str);
+        let result = match endorser {
+            Some(endorser) => ledger::prepare_nym_for_endorser(&ProfileContext::Global, &target_did, verkey, alias, role, &endorser).map(Some),
+            None => ledger::publish_nym(&ProfileContext::Global, &target_did, verkey, alias, role).map(|_| None)
+        };
+        match result {
+            Ok(transaction) => {
+                trace!("vcx_ledger_publish_nym_cb(command_handle: {}, rc: {}, transaction: {:?})",
+                       command_handle, error::SUCCESS.message, transaction);
+                let transaction = transaction.map(CStringUtils::string_to_cstring);
+                cb(command_handle, error::SUCCESS.code_num, transaction.as_ref().map_or(ptr::null(), |transaction| transaction.as_ptr()));
+            }
+            Err(x) => {
+                warn!("vcx_ledger_publish_nym_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), ptr::null());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Replaces verkey of the institution DID on the ledger and in the wallet.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// endorser: (Optional) DID of the endorser that will submit the transaction.
+///     If set, the transaction is not written but returned to be passed to the endorser (see vcx_endorse_transaction),
+///     the wallet keeps using the current verkey until vcx_ledger_apply_verkey_rotation is called
+///
+/// cb: Callback that provides the new verkey, the transaction for endorser (null if the transaction has been written)
+///     and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_ledger_rotate_verkey(command_handle: CommandHandle,
+                                       endorser: *const c_char,
+                                       cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, verkey: *const c_char, transaction: *const c_char)>) -> u32 {
+    info!("vcx_ledger_rotate_verkey >>>");
+
+    check_useful_opt_c_str!(endorser, VcxErrorKind::InvalidOption);
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_ledger_rotate_verkey(command_handle: {}, endorser: {:?})", command_handle, endorser);
+
+    execute(move || {
+        let result = match endorser {
+            Some(endorser) => ledger::prepare_verkey_rotation_for_endorser(&ProfileContext::Global, &endorser).map(|(verkey, transaction)| (verkey, Some(transaction))),
+            None => ledger::rotate_verkey(&ProfileContext::Global).map(|(verkey, _)| (verkey, None))
+        };
+        match result {
+            Ok((verkey, transaction)) => {
+                trace!("vcx_ledger_rotate_verkey_cb(command_handle: {}, rc: {}, verkey: {}, transaction: {:?})",
+                       command_handle, error::SUCCESS.message, verkey, transaction);
+                let verkey = CStringUtils::string_to_cstring(verkey);
+                let transaction = transaction.map(CStringUtils::string_to_cstring);
+                cb(command_handle, error::SUCCESS.code_num, verkey.as_ptr(), transaction.as_ref().map_or(ptr::null(), |transaction| transaction.as_ptr()));
+            }
+            Err(x) => {
+                warn!("vcx_ledger_rotate_verkey_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), ptr::null(), ptr::null());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Finishes verkey rotation started by vcx_ledger_rotate_verkey with endorser,
+/// to be called once the endorser has written the transaction to the ledger.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// cb: Callback that provides the new verkey and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_ledger_apply_verkey_rotation(command_handle: CommandHandle,
+                                               cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, verkey: *const c_char)>) -> u32 {
+    info!("vcx_ledger_apply_verkey_rotation >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_ledger_apply_verkey_rotation(command_handle: {})", command_handle);
+
+    execute(move || {
+        match ledger::apply_verkey_rotation(&ProfileContext::Global) {
+            Ok(verkey) => {
+                trace!("vcx_ledger_apply_verkey_rotation_cb(command_handle: {}, rc: {}, verkey: {})",
+                       command_handle, error::SUCCESS.message, verkey);
+                let verkey = CStringUtils::string_to_cstring(verkey);
+                cb(command_handle, error::SUCCESS.code_num, verkey.as_ptr());
+            }
+            Err(x) => {
+                warn!("vcx_ledger_apply_verkey_rotation_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), ptr::null());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Writes raw ATTRIB transaction on behalf of the institution DID, which pays the fees if there are any.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// target_did: DID the attribute is written for
+/// attrib_json: attribute as JSON object, e.g. {"endpoint":{"endpoint":"https://agent.example.com"}}
+/// endorser: (Optional) DID of the endorser that will submit the transaction.
+///     If set, the transaction is not written but returned to be passed to the endorser (see vcx_endorse_transaction)
+///
+/// cb: Callback that provides the transaction for endorser (null if the transaction has been written) and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_ledger_publish_attrib(command_handle: CommandHandle,
+                                        target_did: *const c_char,
+                                        attrib_json: *const c_char,
+                                        endorser: *const c_char,
+                                        cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, transaction: *const c_char)>) -> u32 {
+    info!("vcx_ledger_publish_attrib >>>");
+
+    check_useful_c_str!(target_did, VcxErrorKind::InvalidDid);
+    check_useful_c_str!(attrib_json, VcxErrorKind::InvalidOption);
+    check_useful_opt_c_str!(endorser, VcxErrorKind::InvalidOption);
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_ledger_publish_attrib(command_handle: {}, target_did: {}, attrib_json: {}, endorser: {:?})",
+           command_handle, target_did, attrib_json, endorser);
+
+    execute(move || {
+        let result = match endorser {
+            Some(endorser) => ledger::prepare_attrib_for_endorser(&ProfileContext::Global, &target_did, &attrib_json, &endorser).map(Some),
+            None => ledger::publish_attrib(&ProfileContext::Global, &target_did, &attrib_json).map(|_| None)
+        };
+        match result {
+            Ok(transaction) => {
+                trace!("vcx_ledger_publish_attrib_cb(command_handle: {}, rc: {}, transaction: {:?})",
+                       command_handle, error::SUCCESS.message, transaction);
+                let transaction = transaction.map(CStringUtils::string_to_cstring);
+                cb(command_handle, error::SUCCESS.code_num, transaction.as_ref().map_or(ptr::null(), |transaction| transaction.as_ptr()));
+            }
+            Err(x) => {
+                warn!("vcx_ledger_publish_attrib_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), ptr::null());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Reads raw ATTRIB of the DID from the ledger.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+/// target_did: DID the attribute has been written for
+/// attr_name: name of the attribute, e.g. "endpoint"
+///
+/// cb: Callback that provides the attribute JSON (null if it is not set) and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_ledger_get_attrib(command_handle: CommandHandle,
+                                    target_did: *const c_char,
+                                    attr_name: *const c_char,
+                                    cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, attrib: *const c_char)>) -> u32 {
+    info!("vcx_ledger_get_attrib >>>");
+
+    check_useful_c_str!(target_did, VcxErrorKind::InvalidDid);
+    check_useful_c_str!(attr_name, VcxErrorKind::InvalidOption);
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_ledger_get_attrib(command_handle: {}, target_did: {}, attr_name: {})", command_handle, target_did, attr_name);
+
+    execute(move || {
+        match ledger::get_attrib(&ProfileContext::Global, &target_did, &attr_name) {
+            Ok(attrib) => {
+                trace!("vcx_ledger_get_attrib_cb(command_handle: {}, rc: {}, attrib: {:?})",
+                       command_handle, error::SUCCESS.message, attrib);
+                let attrib = attrib.map(CStringUtils::string_to_cstring);
+                cb(command_handle, error::SUCCESS.code_num, attrib.as_ref().map_or(ptr::null(), |attrib| attrib.as_ptr()));
+            }
+            Err(x) => {
+                warn!("vcx_ledger_get_attrib_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), ptr::null());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
 #[cfg(test)]
 mod tests {
     use std::ffi::CString;
@@ -635,4 +883,75 @@ mod tests {
                    error::SUCCESS.code_num);
         cb.receive(TimeoutUtils::some_medium()).unwrap();
     }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_ledger_publish_nym() {
+        let _setup = SetupMocks::init();
+
+        let target_did = CString::new("V4SGRU86Z58d6TV7PBUe6f").unwrap();
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_ledger_publish_nym(cb.command_handle,
+                                          target_did.as_ptr(),
+                                          CString::new(constants::VERKEY).unwrap().into_raw(),
+                                          ptr::null(),
+                                          ptr::null(),
+                                          ptr::null(),
+                                          Some(cb.get_callback())),
+                   error::SUCCESS.code_num);
+        assert_eq!(None, cb.receive(TimeoutUtils::some_medium()).unwrap());
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_ledger_publish_nym(cb.command_handle,
+                                          target_did.as_ptr(),
+                                          ptr::null(),
+                                          ptr::null(),
+                                          ptr::null(),
+                                          CString::new("NcYxiDXkpYi6ov5FcYDi1e").unwrap().into_raw(),
+                                          Some(cb.get_callback())),
+                   error::SUCCESS.code_num);
+        assert_eq!(Some(constants::REQUEST_WITH_ENDORSER.to_string()), cb.receive(TimeoutUtils::some_medium()).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_ledger_rotate_verkey_for_endorser() {
+        let _setup = SetupMocks::init();
+
+        let cb = return_types_u32::Return_U32_STR_STR::new().unwrap();
+        assert_eq!(vcx_ledger_rotate_verkey(cb.command_handle,
+                                            CString::new("NcYxiDXkpYi6ov5FcYDi1e").unwrap().into_raw(),
+                                            Some(cb.get_callback())),
+                   error::SUCCESS.code_num);
+        assert_eq!((Some(constants::VERKEY.to_string()), Some(constants::REQUEST_WITH_ENDORSER.to_string())),
+                   cb.receive(TimeoutUtils::some_medium()).unwrap());
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_ledger_apply_verkey_rotation(cb.command_handle, Some(cb.get_callback())), error::SUCCESS.code_num);
+        assert_eq!(Some(constants::VERKEY.to_string()), cb.receive(TimeoutUtils::some_medium()).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_ledger_publish_and_get_attrib() {
+        let _setup = SetupMocks::init();
+
+        let target_did = CString::new("V4SGRU86Z58d6TV7PBUe6f").unwrap();
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_ledger_publish_attrib(cb.command_handle,
+                                             target_did.as_ptr(),
+                                             CString::new(constants::ATTRIB_DATA).unwrap().into_raw(),
+                                             ptr::null(),
+                                             Some(cb.get_callback())),
+                   error::SUCCESS.code_num);
+        assert_eq!(None, cb.receive(TimeoutUtils::some_medium()).unwrap());
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_ledger_get_attrib(cb.command_handle,
+                                         target_did.as_ptr(),
+                                         CString::new("endpoint").unwrap().into_raw(),
+                                         Some(cb.get_callback())),
+                   error::SUCCESS.code_num);
+        assert_eq!(Some(constants::ATTRIB_DATA.to_string()), cb.receive(TimeoutUtils::some_medium()).unwrap());
+    }
 }
@@ -276,6 +276,9 @@ pub extern fn vcx_shutdown(delete: bool) -> u32 {
     crate::api_lib::api_handle::proof::release_all();
     crate::api_lib::api_handle::disclosed_proof::release_all();
     crate::api_lib::api_handle::credential::release_all();
+    crate::api_lib::api_handle::mediation::release_all();
+    crate::api_lib::api_handle::multi_use_invitation::release_all();
+    crate::profile::release_all();
 
     if delete {
         let pool_name = settings::get_config_value(settings::CONFIG_POOL_NAME)
@@ -13,6 +13,7 @@ use crate::init::open_as_main_wallet;
 use crate::libindy::utils::payments::{create_address, get_wallet_token_info, pay_a_payee, sign_with_address, verify_with_address};
 use crate::libindy::utils::wallet;
 use crate::libindy::utils::wallet::{export_main_wallet, import, RestoreWalletConfigs, WalletConfig};
+use crate::profile::ProfileContext;
 use crate::utils;
 use crate::utils::error;
 
@@ -227,7 +228,7 @@ pub extern fn vcx_wallet_get_token_info(command_handle: CommandHandle,
            command_handle, payment_handle);
 
     execute(move || {
-        match get_wallet_token_info() {
+        match get_wallet_token_info(&ProfileContext::Global) {
             Ok(x) => {
                 trace!("vcx_wallet_get_token_info_cb(command_handle: {}, rc: {}, info: {})",
                        command_handle, 0, x);
@@ -278,7 +279,7 @@ pub extern fn vcx_wallet_create_payment_address(command_handle: CommandHandle,
            command_handle);
 
     execute(move || {
-        match create_address(seed) {
+        match create_address(&ProfileContext::Global, seed) {
             Ok(x) => {
                 trace!("vcx_wallet_create_payment_address_cb(command_handle: {}, rc: {}, address: {})",
                        command_handle, error::SUCCESS.message, x);
@@ -329,7 +330,7 @@ pub extern fn vcx_wallet_sign_with_address(command_handle: CommandHandle,
            command_handle, payment_address, message_raw);
 
     execute(move || {
-        match sign_with_address(&payment_address, message_raw.as_slice()) {
+        match sign_with_address(&ProfileContext::Global, &payment_address, message_raw.as_slice()) {
             Ok(signature) => {
                 trace!("vcx_wallet_sign_with_address_cb(command_handle: {}, rc: {}, signature: {:?})",
                        command_handle, error::SUCCESS.message, signature);
@@ -452,7 +453,7 @@ pub extern fn vcx_wallet_add_record(command_handle: CommandHandle,
            command_handle, secret!(&type_), secret!(&id), secret!(&value), secret!(&tags_json));
 
     execute(move || {
-        match wallet::add_record(&type_, &id, &value, Some(&tags_json)) {
+        match wallet::add_record(wallet::get_wallet_handle(), &type_, &id, &value, Some(&tags_json)) {
             Ok(()) => {
                 trace!("vcx_wallet_add_record(command_handle: {}, rc: {})",
                        command_handle, error::SUCCESS.message);
@@ -507,7 +508,7 @@ pub extern fn vcx_wallet_update_record_value(command_handle: CommandHandle,
            command_handle, secret!(&type_), secret!(&id), secret!(&value));
 
     execute(move || {
-        match wallet::update_record_value(&type_, &id, &value) {
+        match wallet::update_record_value(wallet::get_wallet_handle(), &type_, &id, &value) {
             Ok(()) => {
                 trace!("vcx_wallet_update_record_value(command_handle: {}, rc: {})",
                        command_handle, error::SUCCESS.message);
@@ -562,7 +563,7 @@ pub extern fn vcx_wallet_update_record_tags(command_handle: CommandHandle,
            command_handle, secret!(&type_), secret!(&id), secret!(&tags_json));
 
     execute(move || {
-        match wallet::update_record_tags(&type_, &id, &tags_json) {
+        match wallet::update_record_tags(wallet::get_wallet_handle(), &type_, &id, &tags_json) {
             Ok(()) => {
                 trace!("vcx_wallet_update_record_tags(command_handle: {}, rc: {})",
                        command_handle, error::SUCCESS.message);
@@ -617,7 +618,7 @@ pub extern fn vcx_wallet_add_record_tags(command_handle: CommandHandle,
            command_handle, secret!(&type_), secret!(&id), secret!(&tags_json));
 
     execute(move || {
-        match wallet::add_record_tags(&type_, &id, &tags_json) {
+        match wallet::add_record_tags(wallet::get_wallet_handle(), &type_, &id, &tags_json) {
             Ok(()) => {
                 trace!("vcx_wallet_add_record_tags(command_handle: {}, rc: {})",
                        command_handle, error::SUCCESS.message);
@@ -672,7 +673,7 @@ pub extern fn vcx_wallet_delete_record_tags(command_handle: CommandHandle,
            command_handle, secret!(&type_), secret!(&id), secret!(&tag_names_json));
 
     execute(move || {
-        match wallet::delete_record_tags(&type_, &id, &tag_names_json) {
+        match wallet::delete_record_tags(wallet::get_wallet_handle(), &type_, &id, &tag_names_json) {
             Ok(()) => {
                 trace!("vcx_wallet_delete_record_tags(command_handle: {}, rc: {})",
                        command_handle, error::SUCCESS.message);
@@ -726,7 +727,7 @@ pub extern fn vcx_wallet_get_record(command_handle: CommandHandle,
            command_handle, secret!(&type_), secret!(&id), options_json);
 
     execute(move || {
-        match wallet::get_record(&type_, &id, &options_json) {
+        match wallet::get_record(wallet::get_wallet_handle(), &type_, &id, &options_json) {
             Ok(x) => {
                 trace!("vcx_wallet_get_record(command_handle: {}, rc: {}, record_json: {})",
                        command_handle, error::SUCCESS.message, x);
@@ -781,7 +782,7 @@ pub extern fn vcx_wallet_delete_record(command_handle: CommandHandle,
            command_handle, secret!(&type_), secret!(&id));
 
     execute(move || {
-        match wallet::delete_record(&type_, &id) {
+        match wallet::delete_record(wallet::get_wallet_handle(), &type_, &id) {
             Ok(()) => {
                 trace!("vcx_wallet_delete_record(command_handle: {}, rc: {})",
                        command_handle, error::SUCCESS.message);
@@ -839,7 +840,7 @@ pub extern fn vcx_wallet_send_tokens(command_handle: CommandHandle,
            command_handle, payment_handle, tokens, recipient);
 
     execute(move || {
-        match pay_a_payee(tokens, &recipient) {
+        match pay_a_payee(&ProfileContext::Global, tokens, &recipient) {
             Ok((_payment, msg)) => {
                 trace!("vcx_wallet_send_tokens_cb(command_handle: {}, rc: {}, receipt: {})",
                        command_handle, error::SUCCESS.message, msg);
@@ -906,7 +907,7 @@ pub extern fn vcx_wallet_open_search(command_handle: CommandHandle,
            command_handle, secret!(&type_), secret!(&query_json), secret!(&options_json));
 
     execute(move || {
-        match wallet::open_search(&type_, &query_json, &options_json) {
+        match wallet::open_search(wallet::get_wallet_handle(), &type_, &query_json, &options_json) {
             Ok(x) => {
                 trace!("vcx_wallet_open_search(command_handle: {}, rc_: {}, search_handle: {})",
                        command_handle, error::SUCCESS.message, x);
@@ -961,7 +962,7 @@ pub extern fn vcx_wallet_search_next_records(command_handle: CommandHandle,
            command_handle, wallet_search_handle);
 
     execute(move || {
-        match wallet::fetch_next_records(wallet_search_handle, count) {
+        match wallet::fetch_next_records(wallet::get_wallet_handle(), wallet_search_handle, count) {
             Ok(x) => {
                 trace!("vcx_wallet_search_next_records(command_handle: {}, rc: {}, record_json: {})",
                        command_handle, error::SUCCESS.message, x);
@@ -1328,7 +1329,7 @@ pub mod tests {
 
         let recipient = CStringUtils::string_to_cstring(build_test_address("2ZrAm5Jc3sP4NAXMQbaWzDxEa12xxJW3VgWjbbPtMPQCoznJyS"));
         debug!("sending payment to {:?}", recipient);
-        let balance = libindy::utils::payments::get_wallet_token_info().unwrap().get_balance();
+        let balance = libindy::utils::payments::get_wallet_token_info(&ProfileContext::Global).unwrap().get_balance();
         let tokens = 5;
         let cb = return_types_u32::Return_U32_STR::new().unwrap();
         assert_eq!(vcx_wallet_send_tokens(cb.command_handle,
@@ -1338,7 +1339,7 @@ pub mod tests {
                                           Some(cb.get_callback())),
                    error::SUCCESS.code_num);
         cb.receive(TimeoutUtils::some_medium()).unwrap();
-        let new_balance = libindy::utils::payments::get_wallet_token_info().unwrap().get_balance();
+        let new_balance = libindy::utils::payments::get_wallet_token_info(&ProfileContext::Global).unwrap().get_balance();
         assert_eq!(balance - tokens, new_balance);
     }
 
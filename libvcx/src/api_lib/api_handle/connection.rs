@@ -1,28 +1,67 @@
 use std::collections::HashMap;
 
+use indy::WalletHandle;
 use serde_json;
 
 use agency_client;
 use agency_client::get_message::MessageByConnection;
 use agency_client::MessageStatusCode;
 
-use crate::api_lib::api_handle::object_cache::ObjectCache;
+use crate::api_lib::api_handle::events;
+use crate::api_lib::api_handle::multi_use_invitation;
+use crate::api_lib::api_handle::object_cache::{ObjectCache, ObjectEvents, ObjectStorage, PersistentCache};
 use crate::aries::handlers::connection::cloud_agent::CloudAgentInfo;
 use crate::aries::handlers::connection::connection::{Connection, SmConnectionState};
 use crate::aries::handlers::connection::pairwise_info::PairwiseInfo;
+use crate::aries::handlers::mediation::message_pickup;
+use crate::aries::handlers::mediation::message_pickup::MessagePickup;
 use crate::aries::messages::a2a::A2AMessage;
-use crate::aries::messages::connection::invite::Invitation as InvitationV3;
+use crate::aries::messages::a2a::protocol_version::ProtocolVersion;
+use crate::aries::messages::connection::handshake_protocol::HandshakeProtocol;
+use crate::aries::messages::connection::invite::{AnyInvitation, PublicInvitation};
+use crate::aries::utils::encryption_envelope::EncryptionEnvelope;
+use crate::aries::utils::transport::LOOPBACK_TRANSPORT;
 use crate::error::prelude::*;
+use crate::profile::ProfileContext;
 use crate::utils::error;
 
 lazy_static! {
-    static ref CONNECTION_MAP: ObjectCache<Connection> = ObjectCache::<Connection>::new("connections-cache");
+    static ref CONNECTION_MAP: ObjectCache<Connection> = ObjectCache::<Connection>::new_persistent("connections-cache", ObjectStorage {
+        record_type: "vcx_connection",
+        serialize: Connection::to_string,
+        deserialize: |profile, data| Connection::from_string(profile.clone(), data),
+        tags: _storage_tags,
+    }).with_events(ObjectEvents {
+        object_type: "connection",
+        source_id: Connection::get_source_id,
+        thread_id: |_| None,
+        state: |connection| connection.get_state().into(),
+    });
+}
+
+fn _storage_tags(connection: &Connection) -> HashMap<String, String> {
+    let mut tags = HashMap::new();
+    tags.insert("source_id".to_string(), connection.get_source_id());
+    tags.insert("state".to_string(), u32::from(connection.get_state()).to_string());
+    tags.insert("pw_did".to_string(), connection.pairwise_info().pw_did.to_string());
+    if let Ok(their_pw_did) = connection.remote_did() {
+        tags.insert("their_pw_did".to_string(), their_pw_did);
+    }
+    tags
+}
+
+pub fn persistent_cache() -> &'static dyn PersistentCache {
+    &*CONNECTION_MAP
 }
 
 pub fn is_valid_handle(handle: u32) -> bool {
     CONNECTION_MAP.has_handle(handle)
 }
 
+pub fn get_profile_handle(handle: u32) -> Option<u32> {
+    CONNECTION_MAP.get_profile_handle(handle)
+}
+
 pub fn get_agent_did(handle: u32) -> VcxResult<String> {
     CONNECTION_MAP.get(handle, |connection| {
         Ok(connection.cloud_agent_info().agent_did.to_string())
@@ -47,6 +86,12 @@ pub fn get_pw_verkey(handle: u32) -> VcxResult<String> {
     })
 }
 
+pub fn get_wallet_handle(handle: u32) -> VcxResult<WalletHandle> {
+    CONNECTION_MAP.get(handle, |connection| {
+        connection.profile().wallet_handle()
+    })
+}
+
 pub fn get_their_pw_did(handle: u32) -> VcxResult<String> {
     CONNECTION_MAP.get(handle, |connection| {
         connection.remote_did()
@@ -72,27 +117,66 @@ pub fn get_source_id(handle: u32) -> VcxResult<String> {
     })
 }
 
+/**
+Stores the connection, it stays bound to the profile it was created in.
+ */
 pub fn store_connection(connection: Connection) -> VcxResult<u32> {
-    CONNECTION_MAP.add(connection)
+    CONNECTION_MAP.add_in_profile(connection.profile().handle(), connection)
         .or(Err(VcxError::from(VcxErrorKind::CreateConnection)))
 }
 
 pub fn create_connection(source_id: &str) -> VcxResult<u32> {
-    trace!("create_connection >>> source_id: {}", source_id);
-    let connection = Connection::create(source_id, true)?;
+    create_connection_in_profile(None, source_id)
+}
+
+pub fn create_connection_in_profile(profile_handle: Option<u32>, source_id: &str) -> VcxResult<u32> {
+    trace!("create_connection_in_profile >>> profile_handle: {:?}, source_id: {}", profile_handle, source_id);
+    let connection = Connection::create(ProfileContext::from_handle(profile_handle)?, source_id, true)?;
     return store_connection(connection);
 }
 
+pub fn create_connection_with_handshake_protocol(source_id: &str, protocol: &str) -> VcxResult<u32> {
+    create_connection_with_handshake_protocol_in_profile(None, source_id, protocol)
+}
+
+pub fn create_connection_with_handshake_protocol_in_profile(profile_handle: Option<u32>, source_id: &str, protocol: &str) -> VcxResult<u32> {
+    trace!("create_connection_with_handshake_protocol >>> profile_handle: {:?}, source_id: {}, protocol: {}", profile_handle, source_id, protocol);
+    let protocol: HandshakeProtocol = serde_json::from_value(json!(protocol))
+        .map_err(|_| VcxError::from_msg(VcxErrorKind::InvalidOption, format!("Unsupported handshake protocol: {}", protocol)))?;
+    let connection = Connection::create_with_handshake_protocol(ProfileContext::from_handle(profile_handle)?, source_id, protocol, true)?;
+    store_connection(connection)
+}
+
 pub fn create_connection_with_invite(source_id: &str, details: &str) -> VcxResult<u32> {
-    debug!("create connection {} with invite {}", source_id, details);
-    if let Some(invitation) = serde_json::from_str::<InvitationV3>(details).ok() {
-        let connection = Connection::create_with_invite(source_id, invitation, true)?;
+    create_connection_with_invite_in_profile(None, source_id, details)
+}
+
+pub fn create_connection_with_invite_in_profile(profile_handle: Option<u32>, source_id: &str, details: &str) -> VcxResult<u32> {
+    debug!("create connection {} with invite {} in profile {:?}", source_id, details, profile_handle);
+    if let Some(invitation) = serde_json::from_str::<AnyInvitation>(details).ok() {
+        let connection = Connection::create_with_invite(ProfileContext::from_handle(profile_handle)?, source_id, invitation, true)?;
         store_connection(connection)
     } else {
         Err(VcxError::from_msg(VcxErrorKind::InvalidJson, "Used invite has invalid structure")) // TODO: Specific error type
     }
 }
 
+/**
+Creates invitee connection sending the request to the public DID, its verkey and endpoint are resolved from the ledger.
+ */
+pub fn create_connection_with_public_did(source_id: &str, did: &str) -> VcxResult<u32> {
+    create_connection_with_public_did_in_profile(None, source_id, did)
+}
+
+pub fn create_connection_with_public_did_in_profile(profile_handle: Option<u32>, source_id: &str, did: &str) -> VcxResult<u32> {
+    trace!("create_connection_with_public_did >>> profile_handle: {:?}, source_id: {}, did: {}", profile_handle, source_id, did);
+    let invitation = PublicInvitation::create()
+        .set_label(did.to_string())
+        .set_did(did.to_string());
+    let connection = Connection::create_with_invite(ProfileContext::from_handle(profile_handle)?, source_id, AnyInvitation::Public(invitation), true)?;
+    store_connection(connection)
+}
+
 pub fn send_generic_message(connection_handle: u32, msg: &str) -> VcxResult<String> {
     CONNECTION_MAP.get(connection_handle, |connection| {
         connection.send_generic_message(msg)
@@ -101,6 +185,7 @@ pub fn send_generic_message(connection_handle: u32, msg: &str) -> VcxResult<Stri
 
 pub fn update_state_with_message(handle: u32, message: A2AMessage) -> VcxResult<u32> {
     CONNECTION_MAP.get_mut(handle, |connection| {
+        CONNECTION_MAP.notify_message(handle, connection, &message, connection.find_message_to_handle(events::as_messages(&message)).is_some());
         connection.update_state_with_message(&message)?;
         Ok(error::SUCCESS.code_num)
     })
@@ -154,8 +239,12 @@ pub fn to_string(handle: u32) -> VcxResult<String> {
 }
 
 pub fn from_string(connection_data: &str) -> VcxResult<u32> {
-    let connection = Connection::from_string(connection_data)?;
-    let handle = CONNECTION_MAP.add(connection)?;
+    from_string_in_profile(None, connection_data)
+}
+
+pub fn from_string_in_profile(profile_handle: Option<u32>, connection_data: &str) -> VcxResult<u32> {
+    let connection = Connection::from_string(ProfileContext::from_handle(profile_handle)?, connection_data)?;
+    let handle = CONNECTION_MAP.add_in_profile(profile_handle, connection)?;
     Ok(handle)
 }
 
@@ -167,7 +256,7 @@ impl Into<(SmConnectionState, PairwiseInfo, CloudAgentInfo, String)> for Connect
 
 impl From<(SmConnectionState, PairwiseInfo, CloudAgentInfo, String)> for Connection {
     fn from((state, pairwise_info, cloud_agent_info, source_id): (SmConnectionState, PairwiseInfo, CloudAgentInfo, String)) -> Connection {
-        Connection::from_parts(source_id, pairwise_info, cloud_agent_info, state, true)
+        Connection::from_parts(ProfileContext::Global, source_id, pairwise_info, cloud_agent_info, state, true)
     }
 }
 
@@ -195,6 +284,21 @@ pub fn get_messages(handle: u32) -> VcxResult<HashMap<String, A2AMessage>> {
     })
 }
 
+/**
+Acknowledges and removes picked up messages addressed to no connection of the profile.
+ */
+pub fn discard_undeliverable_messages(profile: &ProfileContext) -> VcxResult<usize> {
+    if message_pickup::get_pickup_mediator(profile)?.is_none() {
+        return Ok(0);
+    }
+    let recipients = CONNECTION_MAP.index_by(|connection| connection.pairwise_info().pw_vk.clone())?;
+    message_pickup::discard_undeliverable_messages(profile, |recipient_vk| {
+        recipients.get(recipient_vk)
+            .map(|handle| CONNECTION_MAP.get_profile_handle(*handle) == profile.handle())
+            .unwrap_or(false)
+    })
+}
+
 pub fn update_message_status(handle: u32, uid: String) -> VcxResult<()> {
     CONNECTION_MAP.get_mut(handle, |connection| {
         connection.update_message_status(uid.clone())
@@ -207,6 +311,38 @@ pub fn get_message_by_id(handle: u32, msg_id: String) -> VcxResult<A2AMessage> {
     })
 }
 
+pub fn process_packed_message(payload: Vec<u8>) -> VcxResult<(u32, A2AMessage)> {
+    trace!("connection::process_packed_message >>> processing payload of {} bytes", payload.len());
+    let recipient_verkeys = EncryptionEnvelope::recipient_verkeys(&payload)?;
+    let handle = match CONNECTION_MAP.find_handle(|connection| recipient_verkeys.contains(&connection.pairwise_info().pw_vk))? {
+        Some(handle) => handle,
+        None => {
+            return multi_use_invitation::process_packed_request(&recipient_verkeys, payload)?
+                .ok_or(VcxError::from_msg(VcxErrorKind::InvalidConnectionHandle, format!("No connection found for recipient verkeys: {:?}", recipient_verkeys)));
+        }
+    };
+    let message = CONNECTION_MAP.get(handle, |connection| {
+        connection.unpack_message(payload.clone())
+    })?;
+    Ok((handle, message))
+}
+
+/**
+Feeds messages delivered by the loopback transport to the `endpoint` into `process_packed_message`.
+Messages which cannot be processed are logged and discarded, they are already taken from the inbox.
+ */
+pub fn process_loopback_messages(endpoint: &str) -> Vec<(u32, A2AMessage)> {
+    trace!("connection::process_loopback_messages >>> endpoint: {:?}", endpoint);
+    LOOPBACK_TRANSPORT.take_messages(endpoint)
+        .into_iter()
+        .filter_map(|payload| {
+            process_packed_message(payload)
+                .map_err(|err| warn!("connection::process_loopback_messages >>> discarding message for {}: {}", endpoint, err))
+                .ok()
+        })
+        .collect()
+}
+
 pub fn send_message(handle: u32, message: A2AMessage) -> VcxResult<()> {
     trace!("connection::send_message >>>");
     let send_message = send_message_closure(handle)?;
@@ -219,6 +355,12 @@ pub fn send_message_closure(handle: u32) -> VcxResult<impl Fn(&A2AMessage) -> Vc
     })
 }
 
+pub fn create_message_pickup(handle: u32, version: ProtocolVersion) -> VcxResult<MessagePickup> {
+    CONNECTION_MAP.get(handle, |connection| {
+        MessagePickup::create(connection, version)
+    })
+}
+
 pub fn is_v3_connection(connection_handle: u32) -> VcxResult<bool> {
     CONNECTION_MAP.get(connection_handle, |_| {
         Ok(true)
@@ -281,10 +423,13 @@ pub mod tests {
     use crate::aries::handlers::connection::connection::tests::create_connected_connections;
     use crate::aries::messages::ack::tests::_ack;
     use crate::aries::messages::connection::invite::tests::_invitation_json;
+    use crate::aries::utils::encryption_envelope::tests::_packed_message;
+    use crate::aries::utils::transport::send_packed_message;
     use crate::utils::constants;
     use crate::utils::devsetup::*;
     use crate::utils::devsetup_agent::test::{Alice, Faber, TestAgent};
     use crate::utils::mockdata::mockdata_connection::{ARIES_CONNECTION_ACK, ARIES_CONNECTION_INVITATION, ARIES_CONNECTION_REQUEST, CONNECTION_SM_INVITEE_COMPLETED, CONNECTION_SM_INVITEE_INVITED, CONNECTION_SM_INVITEE_REQUESTED, CONNECTION_SM_INVITER_COMPLETED};
+    use crate::utils::mockdata::mockdata_credex::ARIES_CREDENTIAL_OFFER;
 
     use super::*;
 
@@ -309,6 +454,17 @@ pub mod tests {
         assert_eq!(0, connection::get_state(connection_handle));
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_create_connection_with_handshake_protocol_works() {
+        let _setup = SetupMocks::init();
+        let connection_handle = connection::create_connection_with_handshake_protocol(_source_id(), "didexchange/1.0").unwrap();
+        assert!(connection::is_valid_handle(connection_handle));
+        assert_eq!(0, connection::get_state(connection_handle));
+
+        assert_eq!(VcxErrorKind::InvalidOption, connection::create_connection_with_handshake_protocol(_source_id(), "didexchange/2.0").unwrap_err().kind());
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_create_connection_with_invite_works() {
@@ -362,6 +518,18 @@ pub mod tests {
         handle
     }
 
+    /**
+    Connections created with mocked wallet share the same verkey. Replaces the connection by connection in the same
+    state with its own pairwise verkey, so messages packed for the verkey are not routed to connections of other tests.
+     */
+    pub fn with_unique_pw_verkey(handle: u32) -> u32 {
+        let connection = CONNECTION_MAP.get(handle, |connection| Ok(connection.clone())).unwrap();
+        release(handle).unwrap();
+        let (state, pairwise_info, cloud_agent_info, source_id) = connection.into();
+        let pairwise_info = PairwiseInfo { pw_vk: uuid::Uuid::new_v4().to_string(), ..pairwise_info };
+        store_connection(Connection::from((state, pairwise_info, cloud_agent_info, source_id))).unwrap()
+    }
+
     pub fn create_and_store_connected_connections(consumer: &mut Alice, institution: &mut Faber) -> (u32, u32) {
         let (consumer_to_institution, institution_to_consumer) = create_connected_connections(consumer, institution);
         let consumer_to_institution = store_connection(consumer_to_institution).unwrap();
@@ -507,6 +675,29 @@ pub mod tests {
         connect(handle_2).unwrap();
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_create_connection_with_public_did() {
+        let _setup = SetupMocks::init();
+
+        let handle = create_connection_with_public_did("alice", "V4SGRU86Z58d6TV7PBUe6f").unwrap();
+        assert_eq!(1, get_state(handle));
+        connect(handle).unwrap();
+        assert_eq!(2, get_state(handle));
+        assert_eq!(crate::utils::constants::VERKEY, get_their_pw_verkey(handle).unwrap());
+
+        let details = json!({
+            "@type": "did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/connections/1.0/invitation",
+            "@id": "69212a3a-d068-4f9d-a2dd-4741bca89af3",
+            "label": "Faber College",
+            "did": "did:sov:V4SGRU86Z58d6TV7PBUe6f"
+        }).to_string();
+        let handle = create_connection_with_invite("alice", &details).unwrap();
+        assert_eq!(1, get_state(handle));
+
+        assert_eq!(VcxErrorKind::NotBase58, create_connection_with_public_did("alice", "not a did").unwrap_err().kind());
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_process_acceptance_message() {
@@ -528,6 +719,59 @@ pub mod tests {
         }).unwrap();
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_process_packed_message_works() {
+        let _setup = SetupMocks::init();
+
+        let handle = with_unique_pw_verkey(create_connection("test_process_packed_message").unwrap());
+        let pw_vk = get_pw_verkey(handle).unwrap();
+
+        AgencyMockDecrypted::set_next_decrypted_message(ARIES_CONNECTION_REQUEST);
+        let (connection_handle, message) = process_packed_message(_packed_message(vec!["unknown_verkey", &pw_vk])).unwrap();
+        assert_eq!(pw_vk, get_pw_verkey(connection_handle).unwrap());
+        assert_eq!(serde_json::from_str::<A2AMessage>(ARIES_CONNECTION_REQUEST).unwrap(), message);
+
+        assert_eq!(error::SUCCESS.code_num, update_state_with_message(connection_handle, message).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_process_packed_message_fails_for_unauthenticated_message() {
+        let _setup = SetupMocks::init();
+
+        let handle = with_unique_pw_verkey(create_connection("test_process_packed_message_fails_for_unauthenticated_message").unwrap());
+        let pw_vk = get_pw_verkey(handle).unwrap();
+
+        AgencyMockDecrypted::set_next_decrypted_message(ARIES_CREDENTIAL_OFFER);
+        let err = process_packed_message(_packed_message(vec![&pw_vk])).unwrap_err();
+        assert_eq!(VcxErrorKind::NotReady, err.kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_process_packed_message_fails_for_unknown_recipient() {
+        let _setup = SetupMocks::init();
+
+        create_connection("test_process_packed_message").unwrap();
+
+        let err = process_packed_message(_packed_message(vec!["unknown_verkey"])).unwrap_err();
+        assert_eq!(VcxErrorKind::InvalidConnectionHandle, err.kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_process_loopback_messages_discards_unprocessable_messages() {
+        let _setup = SetupMocks::init();
+
+        let endpoint = "loopback://test_process_loopback_messages";
+        send_packed_message(&_packed_message(vec!["unknown_verkey"]), endpoint).unwrap();
+        send_packed_message(b"not a packed message", endpoint).unwrap();
+
+        assert!(process_loopback_messages(endpoint).is_empty());
+        assert!(LOOPBACK_TRANSPORT.take_messages(endpoint).is_empty());
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_send_generic_message_fails_with_invalid_connection() {
@@ -539,6 +783,33 @@ pub mod tests {
         assert_eq!(err.kind(), VcxErrorKind::NotReady);
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_connection_emits_events() {
+        let _setup = SetupMocks::init();
+
+        let handle = build_test_connection_inviter_invited();
+        let invited_state = get_state(handle);
+        let (subscription_handle, receiver) = events::tests::subscribe_channel();
+
+        update_state_with_message(handle, serde_json::from_str(ARIES_CONNECTION_REQUEST).unwrap()).unwrap();
+        let event = events::tests::next_event(&receiver, handle);
+        assert_eq!(events::EventKind::StateChanged, event.kind);
+        assert_eq!("connection", event.object_type);
+        assert_eq!("faber_to_alice", event.source_id);
+        assert_eq!(invited_state, event.old_state);
+        assert_eq!(get_state(handle), event.new_state);
+
+        let invitation: A2AMessage = serde_json::from_str(ARIES_CONNECTION_INVITATION).unwrap();
+        update_state_with_message(handle, invitation.clone()).unwrap();
+        let event = events::tests::next_event(&receiver, handle);
+        assert_eq!(events::EventKind::UnhandledMessage, event.kind);
+        assert_eq!(event.old_state, event.new_state);
+        assert_eq!(Some(invitation), event.message);
+
+        events::unsubscribe(subscription_handle).unwrap();
+    }
+
     #[cfg(feature = "agency_v2")]
     #[test]
     fn test_download_messages_from_multiple_connections() {
@@ -1,22 +1,38 @@
+use std::collections::HashMap;
+
 use serde_json;
 
 use agency_client::mocking::AgencyMockDecrypted;
 
 use crate::api_lib::api_handle::connection;
-use crate::api_lib::api_handle::object_cache::ObjectCache;
+use crate::api_lib::api_handle::events;
+use crate::api_lib::api_handle::object_cache::{ObjectCache, ObjectEvents, ObjectStorage, PersistentCache};
 use crate::aries::{
     handlers::issuance::holder::holder::Holder,
+    handlers::state_timing::StateTiming,
     messages::a2a::A2AMessage,
     messages::issuance::credential_offer::CredentialOffer,
+    messages::issuance::credential_proposal::CredentialProposal,
 };
 use crate::error::prelude::*;
+use crate::profile::ProfileContext;
 use crate::settings::indy_mocks_enabled;
 use crate::utils::constants::GET_MESSAGES_DECRYPTED_RESPONSE;
 use crate::utils::error;
 use crate::utils::mockdata::mockdata_credex::ARIES_CREDENTIAL_OFFER;
 
 lazy_static! {
-    static ref HANDLE_MAP: ObjectCache<Holder> = ObjectCache::<Holder>::new("credentials-cache");
+    static ref HANDLE_MAP: ObjectCache<Holder> = ObjectCache::<Holder>::new_persistent("credentials-cache", ObjectStorage {
+        record_type: "vcx_credential",
+        serialize: _serialize,
+        deserialize: _deserialize,
+        tags: _storage_tags,
+    }).with_events(ObjectEvents {
+        object_type: "credential",
+        source_id: Holder::get_source_id,
+        thread_id: |credential| Some(credential.get_thread_id()),
+        state: Holder::get_state,
+    });
 }
 
 // This enum is left only to avoid making breaking serialization changes
@@ -27,6 +43,35 @@ enum Credentials {
     V3(Holder)
 }
 
+fn _serialize(credential: &Holder) -> VcxResult<String> {
+    serde_json::to_string(&Credentials::V3(credential.clone()))
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidState, format!("cannot serialize Credential credentialect: {:?}", err)))
+}
+
+fn _deserialize(profile: &ProfileContext, credential_data: &str) -> VcxResult<Holder> {
+    let credential: Credentials = serde_json::from_str(credential_data)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize Credential: {:?}", err)))?;
+
+    match credential {
+        Credentials::V3(mut credential) => {
+            credential.set_profile(profile.clone());
+            Ok(credential)
+        }
+    }
+}
+
+fn _storage_tags(credential: &Holder) -> HashMap<String, String> {
+    let mut tags = HashMap::new();
+    tags.insert("source_id".to_string(), credential.get_source_id());
+    tags.insert("thread_id".to_string(), credential.get_thread_id());
+    tags.insert("state".to_string(), credential.get_state().to_string());
+    tags
+}
+
+pub fn persistent_cache() -> &'static dyn PersistentCache {
+    &*HANDLE_MAP
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 pub struct Credential {}
 
@@ -38,7 +83,7 @@ fn handle_err(err: VcxError) -> VcxError {
     }
 }
 
-fn create_credential(source_id: &str, offer: &str) -> VcxResult<Option<Holder>> {
+fn create_credential(profile: ProfileContext, source_id: &str, offer: &str) -> VcxResult<Option<Holder>> {
     trace!("create_credential >>> source_id: {}, offer: {}", source_id, secret!(&offer));
 
     let offer_message = serde_json::from_str::<serde_json::Value>(offer)
@@ -50,7 +95,7 @@ fn create_credential(source_id: &str, offer: &str) -> VcxResult<Option<Holder>>
     };
 
     if let Ok(cred_offer) = serde_json::from_value::<CredentialOffer>(offer_message) {
-        return Ok(Some(Holder::create(cred_offer, source_id)?));
+        return Ok(Some(Holder::create(profile, cred_offer, source_id)?));
     }
 
     // TODO: Return error in case of error
@@ -58,14 +103,33 @@ fn create_credential(source_id: &str, offer: &str) -> VcxResult<Option<Holder>>
 }
 
 pub fn credential_create_with_offer(source_id: &str, offer: &str) -> VcxResult<u32> {
-    trace!("credential_create_with_offer >>> source_id: {}, offer: {}", source_id, secret!(&offer));
+    credential_create_with_offer_in_profile(None, source_id, offer)
+}
+
+pub fn credential_create_with_offer_in_profile(profile_handle: Option<u32>, source_id: &str, offer: &str) -> VcxResult<u32> {
+    trace!("credential_create_with_offer >>> profile_handle: {:?}, source_id: {}, offer: {}", profile_handle, source_id, secret!(&offer));
 
     let cred_offer: CredentialOffer = serde_json::from_str(offer)
         .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson,
                                           format!("Strict `aries` protocol is enabled. Can not parse `aries` formatted Credential Offer: {}", err)))?;
 
-    let holder = Holder::create(cred_offer, source_id)?;
-    return HANDLE_MAP.add(holder);
+    let holder = Holder::create(ProfileContext::from_handle(profile_handle)?, cred_offer, source_id)?;
+    return HANDLE_MAP.add_in_profile(profile_handle, holder);
+}
+
+pub fn credential_create_with_proposal(source_id: &str, proposal: &str) -> VcxResult<u32> {
+    credential_create_with_proposal_in_profile(None, source_id, proposal)
+}
+
+pub fn credential_create_with_proposal_in_profile(profile_handle: Option<u32>, source_id: &str, proposal: &str) -> VcxResult<u32> {
+    trace!("credential_create_with_proposal >>> profile_handle: {:?}, source_id: {}, proposal: {}", profile_handle, source_id, secret!(&proposal));
+
+    let cred_proposal: CredentialProposal = serde_json::from_str(proposal)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize Credential Proposal: {:?}", err)))?;
+    cred_proposal.validate()?;
+
+    let holder = Holder::create_with_proposal(ProfileContext::from_handle(profile_handle)?, cred_proposal, source_id)?;
+    HANDLE_MAP.add_in_profile(profile_handle, holder)
 }
 
 pub fn credential_create_with_msgid(source_id: &str, connection_handle: u32, msg_id: &str) -> VcxResult<(u32, String)> {
@@ -74,10 +138,11 @@ pub fn credential_create_with_msgid(source_id: &str, connection_handle: u32, msg
     let offer = get_credential_offer_msg(connection_handle, &msg_id)?;
     trace!("credential_create_with_msgid ::: for msg_id {} found offer {}", msg_id, offer);
 
-    let credential = create_credential(source_id, &offer)?
+    let profile_handle = connection::get_profile_handle(connection_handle);
+    let credential = create_credential(ProfileContext::from_handle(profile_handle)?, source_id, &offer)?
         .ok_or(VcxError::from_msg(VcxErrorKind::InvalidConnectionHandle, format!("Connection can not be used for Proprietary Issuance protocol")))?;
 
-    let handle = HANDLE_MAP.add(credential)?;
+    let handle = HANDLE_MAP.add_in_profile(profile_handle, credential)?;
 
     debug!("inserting credential {} into handle map", source_id);
     Ok((handle, offer))
@@ -92,10 +157,12 @@ pub fn update_state(handle: u32, message: Option<&str>, connection_handle: u32)
         if let Some(message) = message {
             let message: A2AMessage = serde_json::from_str(&message)
                 .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidOption, format!("Cannot update state: Message deserialization failed: {:?}", err)))?;
+            HANDLE_MAP.notify_message(handle, credential, &message, credential.find_message_to_handle(events::as_messages(&message)).is_some());
             credential.step(message.into(), Some(&send_message))?;
         } else {
             let messages = connection::get_messages(connection_handle)?;
             if let Some((uid, msg)) = credential.find_message_to_handle(messages) {
+                HANDLE_MAP.notify_message(handle, credential, &msg, true);
                 credential.step(msg.into(), Some(&send_message))?;
                 connection::update_message_status(connection_handle, uid)?;
             }
@@ -194,6 +261,15 @@ pub fn send_credential_request(handle: u32, connection_handle: u32) -> VcxResult
     }).map_err(handle_err)
 }
 
+pub fn send_credential_proposal(handle: u32, connection_handle: u32) -> VcxResult<u32> {
+    trace!("Credential::send_credential_proposal >>> credential_handle: {}, connection_handle: {}", handle, connection_handle);
+    HANDLE_MAP.get_mut(handle, |credential| {
+        let send_message = connection::send_message_closure(connection_handle)?;
+        credential.send_proposal(send_message)?;
+        Ok(error::SUCCESS.code_num)
+    }).map_err(handle_err)
+}
+
 fn get_credential_offer_msg(connection_handle: u32, msg_id: &str) -> VcxResult<String> {
     trace!("get_credential_offer_msg >>> connection_handle: {}, msg_id: {}", connection_handle, msg_id);
 
@@ -249,13 +325,36 @@ pub fn is_valid_handle(handle: u32) -> bool {
     HANDLE_MAP.has_handle(handle)
 }
 
-pub fn to_string(handle: u32) -> VcxResult<String> {
-    HANDLE_MAP.get(handle, |credential| {
-        serde_json::to_string(&Credentials::V3(credential.clone()))
-            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidState, format!("cannot serialize Credential credentialect: {:?}", err)))
+pub fn get_state_timing(handle: u32) -> VcxResult<StateTiming> {
+    HANDLE_MAP.get(handle, |credential| Ok(credential.get_state_timing()))
+}
+
+pub fn expired_handles() -> VcxResult<Vec<u32>> {
+    HANDLE_MAP.find_handles(|credential| credential.is_expired())
+}
+
+pub fn abandon(handle: u32, connection_handle: Option<u32>, reason: Option<String>) -> VcxResult<u32> {
+    HANDLE_MAP.get_mut(handle, |credential| {
+        match connection_handle {
+            Some(connection_handle) => credential.abandon(reason.clone(), Some(&connection::send_message_closure(connection_handle)?))?,
+            None => credential.abandon(reason.clone(), None::<&fn(&A2AMessage) -> _>)?
+        };
+        Ok(credential.get_state())
     })
 }
 
+pub fn handles_by_thread_id() -> VcxResult<HashMap<String, Vec<u32>>> {
+    HANDLE_MAP.group_by(|credential| credential.get_thread_id())
+}
+
+pub fn get_profile_handle(handle: u32) -> Option<u32> {
+    HANDLE_MAP.get_profile_handle(handle)
+}
+
+pub fn to_string(handle: u32) -> VcxResult<String> {
+    HANDLE_MAP.get(handle, _serialize)
+}
+
 pub fn get_source_id(handle: u32) -> VcxResult<String> {
     HANDLE_MAP.get(handle, |credential| {
         Ok(credential.get_source_id())
@@ -263,12 +362,12 @@ pub fn get_source_id(handle: u32) -> VcxResult<String> {
 }
 
 pub fn from_string(credential_data: &str) -> VcxResult<u32> {
-    let credential: Credentials = serde_json::from_str(credential_data)
-        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize Credential: {:?}", err)))?;
+    from_string_in_profile(None, credential_data)
+}
 
-    match credential {
-        Credentials::V3(credential) => HANDLE_MAP.add(credential)
-    }
+pub fn from_string_in_profile(profile_handle: Option<u32>, credential_data: &str) -> VcxResult<u32> {
+    let credential = _deserialize(&ProfileContext::from_handle(profile_handle)?, credential_data)?;
+    HANDLE_MAP.add_in_profile(profile_handle, credential)
 }
 
 pub fn is_payment_required(handle: u32) -> VcxResult<bool> {
@@ -291,7 +390,7 @@ pub mod tests {
     use crate::aries::messages::issuance::credential::Credential;
     use crate::error::VcxErrorKind;
     use crate::utils::devsetup::*;
-    use crate::utils::mockdata::mockdata_credex::{ARIES_CREDENTIAL_OFFER, ARIES_CREDENTIAL_OFFER_JSON_FORMAT, ARIES_CREDENTIAL_RESPONSE, CREDENTIAL_SM_FINISHED, CREDENTIAL_SM_OFFER_RECEIVED};
+    use crate::utils::mockdata::mockdata_credex::{ARIES_CREDENTIAL_OFFER, ARIES_CREDENTIAL_OFFER_JSON_FORMAT, ARIES_CREDENTIAL_PROPOSAL, ARIES_CREDENTIAL_RESPONSE, CREDENTIAL_SM_FINISHED, CREDENTIAL_SM_OFFER_RECEIVED};
     use crate::utils::mockdata::mockdata_credex;
 
     use super::*;
@@ -332,6 +431,23 @@ pub mod tests {
         assert_eq!(err.kind(), VcxErrorKind::InvalidJson);
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_credential_create_with_proposal_and_send_proposal() {
+        let _setup = SetupMocks::init();
+
+        let handle_conn = connection::tests::build_test_connection_inviter_requested();
+
+        let handle_cred = credential_create_with_proposal("test_credential_create_with_proposal", ARIES_CREDENTIAL_PROPOSAL).unwrap();
+        assert_eq!(VcxStateType::VcxStateInitialized as u32, get_state(handle_cred).unwrap());
+
+        send_credential_proposal(handle_cred, handle_conn).unwrap();
+        assert_eq!(VcxStateType::VcxStateOfferSent as u32, get_state(handle_cred).unwrap());
+
+        let err = credential_create_with_proposal("test_credential_create_with_proposal", ARIES_CREDENTIAL_OFFER).unwrap_err();
+        assert_eq!(err.kind(), VcxErrorKind::InvalidJson);
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_credential_serialize_deserialize() {
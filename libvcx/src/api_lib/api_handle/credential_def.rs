@@ -1,16 +1,24 @@
+use std::collections::HashMap;
+
 use serde_json;
 
-use crate::api_lib::api_handle::object_cache::ObjectCache;
+use crate::api_lib::api_handle::object_cache::{ObjectCache, ObjectStorage, PersistentCache};
 use crate::api_lib::PublicEntityStateType;
 use crate::error::prelude::*;
-use crate::libindy::utils::{anoncreds, ledger};
+use crate::libindy::utils::{anoncreds, cache, ledger};
 use crate::libindy::utils::cache::update_rev_reg_ids_cache;
 use crate::libindy::utils::payments::PaymentTxn;
+use crate::profile::ProfileContext;
 use crate::utils::constants::DEFAULT_SERIALIZE_VERSION;
 use crate::utils::serialization::ObjectWithVersion;
 
 lazy_static! {
-    static ref CREDENTIALDEF_MAP: ObjectCache<CredentialDef> = ObjectCache::<CredentialDef>::new("credential-defs-cache");
+    static ref CREDENTIALDEF_MAP: ObjectCache<CredentialDef> = ObjectCache::<CredentialDef>::new_persistent("credential-defs-cache", ObjectStorage {
+        record_type: "vcx_credential_def",
+        serialize: CredentialDef::to_string,
+        deserialize: _deserialize,
+        tags: _storage_tags,
+    });
 }
 
 #[derive(Clone, Deserialize, Debug, Serialize, PartialEq)]
@@ -23,6 +31,8 @@ struct RevocationRegistry {
     tag: u32,
     rev_reg_def_payment_txn: Option<PaymentTxn>,
     rev_reg_delta_payment_txn: Option<PaymentTxn>,
+    #[serde(default)]
+    tails_base_url: Option<String>,
 }
 
 #[derive(Clone, Deserialize, Debug, Serialize, PartialEq)]
@@ -36,6 +46,8 @@ pub struct CredentialDef {
     rev_reg: Option<RevocationRegistry>,
     #[serde(default)]
     state: PublicEntityStateType,
+    #[serde(skip)]
+    profile: ProfileContext,
 }
 
 #[derive(Clone, Deserialize, Debug, Serialize)]
@@ -87,6 +99,24 @@ fn _replace_tails_location(new_rev_reg_def: &str, revocation_details: &Revocatio
         .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Failed to serialize new rev_reg_def: {:?}, error: {:?}", new_rev_reg_def, err)))
 }
 
+fn _deserialize(profile: &ProfileContext, data: &str) -> VcxResult<CredentialDef> {
+    let mut cred_def = CredentialDef::from_str(data)?;
+    cred_def.set_profile(profile.clone());
+    Ok(cred_def)
+}
+
+fn _storage_tags(cred_def: &CredentialDef) -> HashMap<String, String> {
+    let mut tags = HashMap::new();
+    tags.insert("source_id".to_string(), cred_def.get_source_id().to_string());
+    tags.insert("cred_def_id".to_string(), cred_def.get_cred_def_id().to_string());
+    tags.insert("state".to_string(), cred_def.get_state().to_string());
+    tags
+}
+
+pub fn persistent_cache() -> &'static dyn PersistentCache {
+    &*CREDENTIALDEF_MAP
+}
+
 impl CredentialDef {
     pub fn from_str(data: &str) -> VcxResult<CredentialDef> {
         ObjectWithVersion::deserialize(data)
@@ -138,6 +168,8 @@ impl CredentialDef {
 
     pub fn set_source_id(&mut self, source_id: String) { self.source_id = source_id.clone(); }
 
+    pub fn set_profile(&mut self, profile: ProfileContext) { self.profile = profile; }
+
     fn get_rev_reg_def_payment_txn(&self) -> Option<PaymentTxn> {
         match &self.rev_reg {
             Some(rev_reg) => rev_reg.rev_reg_def_payment_txn.clone(),
@@ -156,13 +188,13 @@ impl CredentialDef {
 
     fn update_state(&mut self) -> VcxResult<u32> {
         if let Some(ref rev_reg_id) = self.get_rev_reg_id() {
-            if let (Ok(_), Ok(_), Ok(_)) = (anoncreds::get_cred_def_json(&self.id),
-                                            anoncreds::get_rev_reg_def_json(rev_reg_id),
-                                            anoncreds::get_rev_reg(rev_reg_id, time::get_time().sec as u64)) {
+            if let (Ok(_), Ok(_), Ok(_)) = (anoncreds::get_cred_def_json(&self.profile, &self.id),
+                                            anoncreds::get_rev_reg_def_json(&self.profile, rev_reg_id),
+                                            anoncreds::get_rev_reg(&self.profile, rev_reg_id, time::get_time().sec as u64)) {
                 self.state = PublicEntityStateType::Published
             }
         } else {
-            if let Ok(_) = anoncreds::get_cred_def_json(&self.id) {
+            if let Ok(_) = anoncreds::get_cred_def_json(&self.profile, &self.id) {
                 self.state = PublicEntityStateType::Published
             }
         }
@@ -172,9 +204,8 @@ impl CredentialDef {
 
     fn get_state(&self) -> u32 { self.state as u32 }
 
-    fn rotate_rev_reg(&mut self, revocation_details: &str) -> VcxResult<RevocationRegistry> {
-        debug!("rotate_rev_reg >>> revocation_details: {}", revocation_details);
-        let revocation_details = _parse_revocation_details(revocation_details)?;
+    fn rotate_rev_reg(&mut self, revocation_details: &RevocationDetails) -> VcxResult<RevocationRegistry> {
+        debug!("rotate_rev_reg >>> revocation_details: {:?}", revocation_details);
         let (tails_file, max_creds, issuer_did) = (
             revocation_details.clone().tails_file.or(self.get_tails_file()),
             revocation_details.max_creds.or(self.get_max_creds()),
@@ -184,15 +215,15 @@ impl CredentialDef {
             (Some(rev_reg), Some(tails_file), Some(max_creds), Some(issuer_did)) => {
                 let tag = format!("tag{}", rev_reg.tag + 1);
                 let (rev_reg_id, rev_reg_def, rev_reg_entry) =
-                    anoncreds::generate_rev_reg(&issuer_did, &self.id, &tails_file, *max_creds, tag.as_str())
+                    anoncreds::generate_rev_reg(self.profile.wallet_handle()?, &issuer_did, &self.id, &tails_file, *max_creds, tag.as_str())
                         .map_err(|err| err.map(VcxErrorKind::CreateRevRegDef, "Cannot create revocation registry defintion"))?;
 
                 let new_rev_reg_def = _replace_tails_location(&rev_reg_def, &revocation_details)?;
 
-                let rev_reg_def_payment_txn = anoncreds::publish_rev_reg_def(&issuer_did, &new_rev_reg_def)
+                let rev_reg_def_payment_txn = anoncreds::publish_rev_reg_def(&self.profile, &issuer_did, &new_rev_reg_def)
                     .map_err(|err| err.map(VcxErrorKind::CreateCredDef, "Cannot publish revocation registry defintion"))?;
 
-                let (rev_reg_delta_payment_txn, _) = anoncreds::publish_rev_reg_delta(&issuer_did, &rev_reg_id, &rev_reg_entry)
+                let (rev_reg_delta_payment_txn, _) = anoncreds::publish_rev_reg_delta(&self.profile, &issuer_did, &rev_reg_id, &rev_reg_entry)
                     .map_err(|err| err.map(VcxErrorKind::InvalidRevocationEntry, "Cannot post RevocationEntry"))?;
 
                 let new_rev_reg = RevocationRegistry {
@@ -204,7 +235,9 @@ impl CredentialDef {
                     tag: rev_reg.tag + 1,
                     rev_reg_delta_payment_txn,
                     rev_reg_def_payment_txn,
+                    tails_base_url: revocation_details.tails_base_url.clone().or(rev_reg.tails_base_url.clone()),
                 };
+                update_rev_reg_ids_cache(self.profile.wallet_handle()?, &self.id, &new_rev_reg.rev_reg_id)?;
                 self.rev_reg = Some(new_rev_reg.clone());
 
                 trace!("rotate_rev_reg_def <<< new_rev_reg_def: {:?}", new_rev_reg);
@@ -213,6 +246,71 @@ impl CredentialDef {
             _ => Err(VcxError::from_msg(VcxErrorKind::RevRegDefNotFound, "No revocation registry definitions associated with this credential definition"))
         }
     }
+
+    /**
+    Active revocation registry to issue a new credential with, returns its id and tails file. When the active
+    registry is full, a new one is created, published and becomes active. Credentials are counted when libindy
+    issues them, see `cache::record_rev_reg_issuance`.
+     */
+    fn active_rev_reg(&mut self) -> VcxResult<Option<(String, String)>> {
+        let issued_count = match self.get_rev_reg_id() {
+            Some(rev_reg_id) => cache::get_rev_reg_issued_count(self.profile.wallet_handle()?, rev_reg_id),
+            None => return Ok(None)
+        };
+        self._active_rev_reg(issued_count)
+    }
+
+    fn _active_rev_reg(&mut self, issued_count: u32) -> VcxResult<Option<(String, String)>> {
+        if let Some(max_creds) = self.get_max_creds() {
+            if issued_count >= max_creds {
+                self._rotate_full_rev_reg()?;
+            }
+        }
+        Ok(self._rev_reg_info())
+    }
+
+    /**
+    Replaces the revocation registry libindy reported as full, unless it has been replaced already.
+    Registries used by earlier versions are not counted, so they are found full only by libindy.
+     */
+    fn replace_full_rev_reg(&mut self, rev_reg_id: &str) -> VcxResult<Option<(String, String)>> {
+        if self.get_rev_reg_id().map(String::as_str) == Some(rev_reg_id) {
+            self._rotate_full_rev_reg()?;
+        }
+        Ok(self._rev_reg_info())
+    }
+
+    fn _rotate_full_rev_reg(&mut self) -> VcxResult<()> {
+        let tails_base_url = self.rev_reg.as_ref()
+            .and_then(|rev_reg| rev_reg.tails_base_url.clone())
+            .ok_or(VcxError::from_msg(VcxErrorKind::InvalidRevocationDetails, "Revocation registry is full and cannot be rotated automatically without `tails_base_url`"))?;
+        let revocation_details = RevocationDetails {
+            support_revocation: Some(true),
+            tails_file: None,
+            tails_url: None,
+            tails_base_url: Some(tails_base_url),
+            max_creds: None,
+        };
+        self.rotate_rev_reg(&revocation_details)?;
+        Ok(())
+    }
+
+    fn _rev_reg_info(&self) -> Option<(String, String)> {
+        self.rev_reg.as_ref().map(|rev_reg| (rev_reg.rev_reg_id.clone(), rev_reg.tails_file.clone()))
+    }
+
+    /**
+    Ids of all revocation registries created for the credential definition, the active one is the last.
+     */
+    fn get_rev_reg_ids(&self) -> VcxResult<Vec<String>> {
+        let mut rev_reg_ids = cache::get_rev_reg_ids(self.profile.wallet_handle()?, &self.id);
+        if let Some(rev_reg_id) = self.get_rev_reg_id() {
+            if !rev_reg_ids.contains(rev_reg_id) {
+                rev_reg_ids.push(rev_reg_id.to_string());
+            }
+        }
+        Ok(rev_reg_ids)
+    }
 }
 
 fn _parse_revocation_details(revocation_details: &str) -> VcxResult<RevocationDetails> {
@@ -239,13 +337,15 @@ fn _maybe_set_url(rev_reg_def_json: &str, revocation_details: &RevocationDetails
         .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Failed to serialize RevocationRegistryDefinition: {:?}, err: {:?}", rev_reg_def, err)))
 }
 
-fn _create_credentialdef(issuer_did: &str,
+fn _create_credentialdef(profile: &ProfileContext,
+                         issuer_did: &str,
                          schema_id: &str,
                          tag: &str,
                          revocation_details: &RevocationDetails) -> VcxResult<(String, String, Option<String>, Option<String>, Option<String>)> {
-    let (_, schema_json) = anoncreds::get_schema_json(&schema_id)?;
+    let (_, schema_json) = anoncreds::get_schema_json(profile, &schema_id)?;
 
-    let (cred_def_id, cred_def_json) = anoncreds::generate_cred_def(issuer_did,
+    let (cred_def_id, cred_def_json) = anoncreds::generate_cred_def(profile.wallet_handle()?,
+                                                                    issuer_did,
                                                                     &schema_json,
                                                                     tag,
                                                                     None,
@@ -263,7 +363,7 @@ fn _create_credentialdef(issuer_did: &str,
                 .ok_or(VcxError::from_msg(VcxErrorKind::InvalidRevocationDetails, "Invalid RevocationDetails: `max_creds` field not found"))?;
 
             let (rev_reg_id, rev_reg_def, rev_reg_entry) =
-                anoncreds::generate_rev_reg(&issuer_did, &cred_def_id, &tails_file, max_creds, "tag1")
+                anoncreds::generate_rev_reg(profile.wallet_handle()?, &issuer_did, &cred_def_id, &tails_file, max_creds, "tag1")
                     .map_err(|err| err.map(VcxErrorKind::CreateCredDef, "Cannot create CredentialDefinition"))?;
 
             let rev_reg_def = _maybe_set_url(&rev_reg_def, revocation_details)?;
@@ -276,8 +376,8 @@ fn _create_credentialdef(issuer_did: &str,
     Ok((cred_def_id, cred_def_json, rev_reg_id, rev_reg_def, rev_reg_entry))
 }
 
-fn _try_get_cred_def_from_ledger(issuer_did: &str, cred_def_id: &str) -> VcxResult<Option<String>> {
-    match anoncreds::get_cred_def(Some(issuer_did), cred_def_id) {
+fn _try_get_cred_def_from_ledger(profile: &ProfileContext, issuer_did: &str, cred_def_id: &str) -> VcxResult<Option<String>> {
+    match anoncreds::get_cred_def(profile, Some(issuer_did), cred_def_id) {
         Ok((_, cred_def)) => Ok(Some(cred_def)),
         Err(err) if err.kind() == VcxErrorKind::LibndyError(309) => Ok(None),
         Err(err) => Err(VcxError::from_msg(VcxErrorKind::InvalidLedgerResponse, format!("Failed to check presence of credential definition id {} on the ledger\nError: {}", cred_def_id, err)))
@@ -290,26 +390,37 @@ pub fn create_and_publish_credentialdef(source_id: String,
                                         schema_id: String,
                                         tag: String,
                                         revocation_details: String) -> VcxResult<u32> {
-    trace!("create_and_publish_credentialdef >>> source_id: {}, name: {}, issuer_did: {}, schema_id: {}, revocation_details: {}",
-           source_id, name, issuer_did, schema_id, revocation_details);
+    create_and_publish_credentialdef_in_profile(None, source_id, name, issuer_did, schema_id, tag, revocation_details)
+}
 
+pub fn create_and_publish_credentialdef_in_profile(profile_handle: Option<u32>,
+                                                   source_id: String,
+                                                   name: String,
+                                                   issuer_did: String,
+                                                   schema_id: String,
+                                                   tag: String,
+                                                   revocation_details: String) -> VcxResult<u32> {
+    trace!("create_and_publish_credentialdef >>> profile_handle: {:?}, source_id: {}, name: {}, issuer_did: {}, schema_id: {}, revocation_details: {}",
+           profile_handle, source_id, name, issuer_did, schema_id, revocation_details);
+
+    let profile = ProfileContext::from_handle(profile_handle)?;
     let revocation_details: RevocationDetails = _parse_revocation_details(&revocation_details)?;
 
-    let (cred_def_id, cred_def_json, rev_reg_id, rev_reg_def, rev_reg_entry) = _create_credentialdef(&issuer_did, &schema_id, &tag, &revocation_details)?;
+    let (cred_def_id, cred_def_json, rev_reg_id, rev_reg_def, rev_reg_entry) = _create_credentialdef(&profile, &issuer_did, &schema_id, &tag, &revocation_details)?;
 
-    let (rev_def_payment, rev_delta_payment, cred_def_payment_txn) = match _try_get_cred_def_from_ledger(&issuer_did, &cred_def_id) {
+    let (rev_def_payment, rev_delta_payment, cred_def_payment_txn) = match _try_get_cred_def_from_ledger(&profile, &issuer_did, &cred_def_id) {
         Ok(Some(ledger_cred_def_json)) => {
             return Err(VcxError::from_msg(VcxErrorKind::CreateCredDef, format!("Credential definition with id {} already exists on the ledger: {}", cred_def_id, ledger_cred_def_json)));
         }
         Ok(None) => {
-            let cred_def_payment_txn = anoncreds::publish_cred_def(&issuer_did, &cred_def_json)?;
+            let cred_def_payment_txn = anoncreds::publish_cred_def(&profile, &issuer_did, &cred_def_json)?;
 
             match (&rev_reg_id, &rev_reg_def, &rev_reg_entry) {
                 (Some(ref rev_reg_id), Some(ref rev_reg_def), Some(ref rev_reg_entry)) => {
-                    let rev_def_payment = anoncreds::publish_rev_reg_def(&issuer_did, &rev_reg_def)
+                    let rev_def_payment = anoncreds::publish_rev_reg_def(&profile, &issuer_did, &rev_reg_def)
                         .map_err(|err| err.map(VcxErrorKind::CreateCredDef, "Cannot create CredentialDefinition"))?;
 
-                    let (rev_delta_payment, _) = anoncreds::publish_rev_reg_delta(&issuer_did, &rev_reg_id, &rev_reg_entry)
+                    let (rev_delta_payment, _) = anoncreds::publish_rev_reg_delta(&profile, &issuer_did, &rev_reg_id, &rev_reg_entry)
                         .map_err(|err| err.map(VcxErrorKind::InvalidRevocationEntry, "Cannot post RevocationEntry"))?;
 
                     (rev_def_payment, rev_delta_payment, cred_def_payment_txn)
@@ -331,11 +442,16 @@ pub fn create_and_publish_credentialdef(source_id: String,
                 tag: 1,
                 rev_reg_def_payment_txn: rev_def_payment,
                 rev_reg_delta_payment_txn: rev_delta_payment,
+                tails_base_url: revocation_details.tails_base_url,
             })
         }
         _ => None
     };
 
+    if let Some(rev_reg) = &rev_reg {
+        update_rev_reg_ids_cache(profile.wallet_handle()?, &cred_def_id, &rev_reg.rev_reg_id)?;
+    }
+
     let cred_def = CredentialDef {
         source_id,
         name,
@@ -345,20 +461,34 @@ pub fn create_and_publish_credentialdef(source_id: String,
         cred_def_payment_txn,
         rev_reg,
         state: PublicEntityStateType::Published,
+        profile,
     };
 
-    let handle = CREDENTIALDEF_MAP.add(cred_def).or(Err(VcxError::from(VcxErrorKind::CreateCredDef)))?;
+    let handle = CREDENTIALDEF_MAP.add_in_profile(profile_handle, cred_def).or(Err(VcxError::from(VcxErrorKind::CreateCredDef)))?;
 
     Ok(handle)
 }
 
+/**
+Publishes revocations stored locally for all revocation registries of the credential definition,
+fails with RevDeltaNotFound if there are none.
+ */
 pub fn publish_revocations(handle: u32) -> VcxResult<()> {
     CREDENTIALDEF_MAP.get(handle, |cd| {
-        if let Some(rev_reg_id) = cd.get_rev_reg_id() {
-            anoncreds::publish_local_revocations(rev_reg_id.as_str())?;
-            Ok(())
-        } else {
-            Err(VcxError::from(VcxErrorKind::InvalidCredDefHandle))
+        if cd.get_rev_reg_id().is_none() {
+            return Err(VcxError::from(VcxErrorKind::InvalidCredDefHandle));
+        }
+        let mut published = false;
+        for rev_reg_id in cd.get_rev_reg_ids()? {
+            match anoncreds::publish_local_revocations(&cd.profile, &rev_reg_id) {
+                Ok(_) => published = true,
+                Err(err) if err.kind() == VcxErrorKind::RevDeltaNotFound => {}
+                Err(err) => return Err(err)
+            }
+        }
+        match published {
+            true => Ok(()),
+            false => Err(VcxError::from(VcxErrorKind::RevDeltaNotFound))
         }
     })
 }
@@ -367,6 +497,10 @@ pub fn is_valid_handle(handle: u32) -> bool {
     CREDENTIALDEF_MAP.has_handle(handle)
 }
 
+pub fn get_profile_handle(handle: u32) -> Option<u32> {
+    CREDENTIALDEF_MAP.get_profile_handle(handle)
+}
+
 pub fn to_string(handle: u32) -> VcxResult<String> {
     CREDENTIALDEF_MAP.get(handle, |cd| {
         cd.to_string()
@@ -374,8 +508,12 @@ pub fn to_string(handle: u32) -> VcxResult<String> {
 }
 
 pub fn from_string(data: &str) -> VcxResult<u32> {
-    let cred_def: CredentialDef = CredentialDef::from_str(data)?;
-    CREDENTIALDEF_MAP.add(cred_def)
+    from_string_in_profile(None, data)
+}
+
+pub fn from_string_in_profile(profile_handle: Option<u32>, data: &str) -> VcxResult<u32> {
+    let cred_def = _deserialize(&ProfileContext::from_handle(profile_handle)?, data)?;
+    CREDENTIALDEF_MAP.add_in_profile(profile_handle, cred_def)
 }
 
 pub fn get_source_id(handle: u32) -> VcxResult<String> {
@@ -396,6 +534,40 @@ pub fn get_rev_reg_id(handle: u32) -> VcxResult<String> {
     })
 }
 
+/**
+Revocation registry id is `<issuer_did>:4:<cred_def_id>:CL_ACCUM:<tag>`.
+ */
+fn _owns_rev_reg(cred_def: &CredentialDef, rev_reg_id: &str) -> bool {
+    rev_reg_id.contains(&format!(":4:{}:", cred_def.id))
+}
+
+/**
+Active revocation registry of the loaded credential definition the registry belongs to, `None` if the credential
+definition is not loaded.
+ */
+pub fn get_active_rev_reg(rev_reg_id: &str) -> VcxResult<Option<(String, String)>> {
+    match CREDENTIALDEF_MAP.find_handle(|c| _owns_rev_reg(c, rev_reg_id))? {
+        Some(handle) => CREDENTIALDEF_MAP.get_mut(handle, |c| c.active_rev_reg()),
+        None => Ok(None)
+    }
+}
+
+/**
+Replaces full revocation registry of the loaded credential definition, returns the active registry.
+ */
+pub fn replace_full_rev_reg(rev_reg_id: &str) -> VcxResult<Option<(String, String)>> {
+    match CREDENTIALDEF_MAP.find_handle(|c| _owns_rev_reg(c, rev_reg_id))? {
+        Some(handle) => CREDENTIALDEF_MAP.get_mut(handle, |c| c.replace_full_rev_reg(rev_reg_id)),
+        None => Ok(None)
+    }
+}
+
+pub fn get_rev_reg_ids(handle: u32) -> VcxResult<Vec<String>> {
+    CREDENTIALDEF_MAP.get(handle, |c| {
+        c.get_rev_reg_ids()
+    })
+}
+
 pub fn get_tails_file(handle: u32) -> VcxResult<Option<String>> {
     CREDENTIALDEF_MAP.get(handle, |c| {
         Ok(c.get_tails_file())
@@ -452,11 +624,9 @@ pub fn rotate_rev_reg_def(handle: u32, revocation_details: &str) -> VcxResult<St
     CREDENTIALDEF_MAP.get_mut(handle, |s| {
         match &s.issuer_did {
             Some(_) => {
-                let new_rev_reg = s.rotate_rev_reg(revocation_details)?;
-                match update_rev_reg_ids_cache(&s.id, &new_rev_reg.rev_reg_id) {
-                    Ok(()) => s.to_string(),
-                    Err(err) => Err(err)
-                }
+                let revocation_details = _parse_revocation_details(revocation_details)?;
+                s.rotate_rev_reg(&revocation_details)?;
+                s.to_string()
             }
             // TODO: Better error
             None => Err(VcxError::from(VcxErrorKind::InvalidCredentialHandle))
@@ -569,7 +739,7 @@ pub mod tests {
         let _setup = SetupLibraryWalletPoolZeroFees::init();
         let (_, _, cred_def_id, cred_def_json, _, _) = libindy::utils::anoncreds::tests::create_and_store_credential_def(utils::constants::DEFAULT_SCHEMA_ATTRS, false);
 
-        let (id, r_cred_def_json) = libindy::utils::anoncreds::get_cred_def_json(&cred_def_id).unwrap();
+        let (id, r_cred_def_json) = libindy::utils::anoncreds::get_cred_def_json(&ProfileContext::Global, &cred_def_id).unwrap();
 
         assert_eq!(id, cred_def_id);
         let def1: serde_json::Value = serde_json::from_str(&cred_def_json).unwrap();
@@ -656,7 +826,7 @@ pub mod tests {
         assert!(get_rev_reg_def(handle).unwrap().is_some());
         assert!(get_rev_reg_id(handle).ok().is_some());
         let cred_id = get_cred_def_id(handle).unwrap();
-        libindy::utils::anoncreds::get_cred_def_json(&cred_id).unwrap();
+        libindy::utils::anoncreds::get_cred_def_json(&ProfileContext::Global, &cred_id).unwrap();
     }
 
     #[cfg(feature = "pool_tests")]
@@ -739,6 +909,73 @@ pub mod tests {
         assert_eq!(CredentialDef::from_str("{}").unwrap_err().kind(), VcxErrorKind::CreateCredDef);
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_rev_reg_rotated_when_full() {
+        let _setup = SetupMocks::init();
+
+        let rev_details = json!({"support_revocation": true, "tails_file": utils::constants::TEST_TAILS_FILE, "max_creds": 2, "tails_base_url": utils::constants::TEST_TAILS_URL}).to_string();
+        let handle = create_and_publish_credentialdef("SourceId".to_string(), CREDENTIAL_DEF_NAME.to_string(), ISSUER_DID.to_string(), SCHEMA_ID.to_string(), "tag".to_string(), rev_details).unwrap();
+        let rev_reg = || CREDENTIALDEF_MAP.get(handle, |c| Ok(c.rev_reg.clone().unwrap())).unwrap();
+        let active_rev_reg = |issued_count| CREDENTIALDEF_MAP.get_mut(handle, |c| c._active_rev_reg(issued_count)).unwrap().unwrap();
+
+        let (rev_reg_id, tails_file) = active_rev_reg(1);
+        assert_eq!(utils::constants::REV_REG_ID, rev_reg_id);
+        assert_eq!(utils::constants::TEST_TAILS_FILE, tails_file);
+        assert_eq!(1, rev_reg().tag);
+
+        active_rev_reg(2);
+        assert_eq!(2, rev_reg().tag);
+        assert_eq!(Some(utils::constants::TEST_TAILS_URL.to_string()), rev_reg().tails_base_url);
+        assert_eq!(vec![utils::constants::REV_REG_ID.to_string()], get_rev_reg_ids(handle).unwrap());
+
+        let restored_handle = from_string(&to_string(handle).unwrap()).unwrap();
+        assert_eq!(rev_reg(), CREDENTIALDEF_MAP.get(restored_handle, |c| Ok(c.rev_reg.clone().unwrap())).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_replace_full_rev_reg_only_once() {
+        let _setup = SetupMocks::init();
+
+        let rev_details = json!({"support_revocation": true, "tails_file": utils::constants::TEST_TAILS_FILE, "max_creds": 2, "tails_base_url": utils::constants::TEST_TAILS_URL}).to_string();
+        let handle = create_and_publish_credentialdef("SourceId".to_string(), CREDENTIAL_DEF_NAME.to_string(), ISSUER_DID.to_string(), SCHEMA_ID.to_string(), "tag".to_string(), rev_details).unwrap();
+        let tag = || CREDENTIALDEF_MAP.get(handle, |c| Ok(c.rev_reg.as_ref().unwrap().tag)).unwrap();
+
+        CREDENTIALDEF_MAP.get_mut(handle, |c| c.replace_full_rev_reg("other_rev_reg_id")).unwrap();
+        assert_eq!(1, tag());
+        CREDENTIALDEF_MAP.get_mut(handle, |c| c.replace_full_rev_reg(utils::constants::REV_REG_ID)).unwrap();
+        assert_eq!(2, tag());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_owns_rev_reg() {
+        let _setup = SetupMocks::init();
+
+        let handle = create_cred_def_fake();
+        let cred_def_id = get_cred_def_id(handle).unwrap();
+        CREDENTIALDEF_MAP.get(handle, |c| {
+            assert!(_owns_rev_reg(c, &format!("{}:4:{}:CL_ACCUM:tag1", ISSUER_DID, cred_def_id)));
+            assert!(!_owns_rev_reg(c, &format!("{}:4:{}1:CL_ACCUM:tag1", ISSUER_DID, cred_def_id)));
+            Ok(())
+        }).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_rev_reg_not_rotated_without_tails_base_url() {
+        let _setup = SetupMocks::init();
+
+        let handle = create_cred_def_fake();
+        CREDENTIALDEF_MAP.get_mut(handle, |c| c._active_rev_reg(1)).unwrap().unwrap();
+        let err = CREDENTIALDEF_MAP.get_mut(handle, |c| c._active_rev_reg(2)).unwrap_err();
+        assert_eq!(VcxErrorKind::InvalidRevocationDetails, err.kind());
+
+        let handle = create_and_publish_credentialdef("SourceId".to_string(), CREDENTIAL_DEF_NAME.to_string(), ISSUER_DID.to_string(), SCHEMA_ID.to_string(), "tag".to_string(), "{}".to_string()).unwrap();
+        assert_eq!(None, CREDENTIALDEF_MAP.get_mut(handle, |c| c.active_rev_reg()).unwrap());
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_release_all() {
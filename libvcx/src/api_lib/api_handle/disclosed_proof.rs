@@ -1,22 +1,38 @@
+use std::collections::HashMap;
+
 use serde_json;
 
 use agency_client::mocking::AgencyMockDecrypted;
 
 use crate::api_lib::api_handle::connection;
-use crate::api_lib::api_handle::object_cache::ObjectCache;
+use crate::api_lib::api_handle::events;
+use crate::api_lib::api_handle::object_cache::{ObjectCache, ObjectEvents, ObjectStorage, PersistentCache};
 use crate::aries::{
     handlers::proof_presentation::prover::prover::Prover,
+    handlers::state_timing::StateTiming,
     messages::proof_presentation::presentation_request::PresentationRequest,
 };
 use crate::aries::messages::a2a::A2AMessage;
 use crate::error::prelude::*;
+use crate::libindy::proofs::prover::credential_selection::SelectionPolicy;
+use crate::profile::ProfileContext;
 use crate::settings::indy_mocks_enabled;
 use crate::utils::constants::GET_MESSAGES_DECRYPTED_RESPONSE;
 use crate::utils::error;
 use crate::utils::mockdata::mockdata_proof::ARIES_PROOF_REQUEST_PRESENTATION;
 
 lazy_static! {
-    static ref HANDLE_MAP: ObjectCache<Prover> = ObjectCache::<Prover>::new("disclosed-proofs-cache");
+    static ref HANDLE_MAP: ObjectCache<Prover> = ObjectCache::<Prover>::new_persistent("disclosed-proofs-cache", ObjectStorage {
+        record_type: "vcx_disclosed_proof",
+        serialize: _serialize,
+        deserialize: _deserialize,
+        tags: _storage_tags,
+    }).with_events(ObjectEvents {
+        object_type: "disclosed_proof",
+        source_id: Prover::get_source_id,
+        thread_id: |proof| Some(proof.get_thread_id()),
+        state: Prover::state,
+    });
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -26,6 +42,35 @@ enum DisclosedProofs {
     V3(Prover),
 }
 
+fn _serialize(proof: &Prover) -> VcxResult<String> {
+    serde_json::to_string(&DisclosedProofs::V3(proof.clone()))
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidState, format!("cannot serialize DisclosedProof proofect: {:?}", err)))
+}
+
+fn _deserialize(profile: &ProfileContext, proof_data: &str) -> VcxResult<Prover> {
+    let proof: DisclosedProofs = serde_json::from_str(proof_data)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("cannot deserialize DisclosedProofs object: {:?}", err)))?;
+
+    match proof {
+        DisclosedProofs::V3(mut proof) => {
+            proof.set_profile(profile.clone());
+            Ok(proof)
+        }
+    }
+}
+
+fn _storage_tags(proof: &Prover) -> HashMap<String, String> {
+    let mut tags = HashMap::new();
+    tags.insert("source_id".to_string(), proof.get_source_id());
+    tags.insert("thread_id".to_string(), proof.get_thread_id());
+    tags.insert("state".to_string(), proof.state().to_string());
+    tags
+}
+
+pub fn persistent_cache() -> &'static dyn PersistentCache {
+    &*HANDLE_MAP
+}
+
 fn handle_err(err: VcxError) -> VcxError {
     if err.kind() == VcxErrorKind::InvalidHandle {
         VcxError::from(VcxErrorKind::InvalidDisclosedProofHandle)
@@ -35,15 +80,19 @@ fn handle_err(err: VcxError) -> VcxError {
 }
 
 pub fn create_proof(source_id: &str, proof_req: &str) -> VcxResult<u32> {
-    trace!("create_proof >>> source_id: {}, proof_req: {}", source_id, proof_req);
+    create_proof_in_profile(None, source_id, proof_req)
+}
+
+pub fn create_proof_in_profile(profile_handle: Option<u32>, source_id: &str, proof_req: &str) -> VcxResult<u32> {
+    trace!("create_proof >>> profile_handle: {:?}, source_id: {}, proof_req: {}", profile_handle, source_id, proof_req);
     debug!("creating disclosed proof with id: {}", source_id);
 
     let presentation_request: PresentationRequest = serde_json::from_str(proof_req)
         .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson,
                                           format!("Strict `aries` protocol is enabled. Can not parse `aries` formatted Presentation Request: {}\nError: {}", proof_req, err)))?;
 
-    let proof = Prover::create(source_id, presentation_request)?;
-    HANDLE_MAP.add(proof)
+    let proof = Prover::create(ProfileContext::from_handle(profile_handle)?, source_id, presentation_request)?;
+    HANDLE_MAP.add_in_profile(profile_handle, proof)
 }
 
 pub fn create_proof_with_msgid(source_id: &str, connection_handle: u32, msg_id: &str) -> VcxResult<(u32, String)> {
@@ -57,9 +106,10 @@ pub fn create_proof_with_msgid(source_id: &str, connection_handle: u32, msg_id:
         .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson,
                                           format!("Strict `aries` protocol is enabled. Can not parse `aries` formatted Presentation Request: {}\nError: {}", proof_request, err)))?;
 
-    let proof = Prover::create(source_id, presentation_request)?;
+    let profile_handle = connection::get_profile_handle(connection_handle);
+    let proof = Prover::create(ProfileContext::from_handle(profile_handle)?, source_id, presentation_request)?;
 
-    let handle = HANDLE_MAP.add(proof)?;
+    let handle = HANDLE_MAP.add_in_profile(profile_handle, proof)?;
 
     debug!("inserting disclosed proof {} into handle map", source_id);
     Ok((handle, proof_request))
@@ -84,11 +134,13 @@ pub fn update_state(handle: u32, message: Option<&str>, connection_handle: u32)
             let message: A2AMessage = serde_json::from_str(message)
                 .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidOption, format!("Can not updated state with message: Message deserialization failed: {:?}", err)))?;
             trace!("disclosed_proof::update_state >>> updating using message {:?}", message);
+            HANDLE_MAP.notify_message(handle, proof, &message, proof.find_message_to_handle(events::as_messages(&message)).is_some());
             proof.handle_message(message.into(), Some(&send_message))?;
         } else {
             let messages = connection::get_messages(connection_handle)?;
             trace!("disclosed_proof::update_state >>> found messages: {:?}", messages);
             if let Some((uid, message)) = proof.find_message_to_handle(messages) {
+                HANDLE_MAP.notify_message(handle, proof, &message, true);
                 proof.handle_message(message.into(), Some(&send_message))?;
                 connection::update_message_status(connection_handle, uid)?;
             };
@@ -98,19 +150,16 @@ pub fn update_state(handle: u32, message: Option<&str>, connection_handle: u32)
 }
 
 pub fn to_string(handle: u32) -> VcxResult<String> {
-    HANDLE_MAP.get(handle, |proof| {
-        serde_json::to_string(&DisclosedProofs::V3(proof.clone()))
-            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidState, format!("cannot serialize DisclosedProof proofect: {:?}", err)))
-    })
+    HANDLE_MAP.get(handle, _serialize)
 }
 
 pub fn from_string(proof_data: &str) -> VcxResult<u32> {
-    let proof: DisclosedProofs = serde_json::from_str(proof_data)
-        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("cannot deserialize DisclosedProofs object: {:?}", err)))?;
+    from_string_in_profile(None, proof_data)
+}
 
-    match proof {
-        DisclosedProofs::V3(proof) => HANDLE_MAP.add(proof)
-    }
+pub fn from_string_in_profile(profile_handle: Option<u32>, proof_data: &str) -> VcxResult<u32> {
+    let proof = _deserialize(&ProfileContext::from_handle(profile_handle)?, proof_data)?;
+    HANDLE_MAP.add_in_profile(profile_handle, proof)
 }
 
 pub fn release(handle: u32) -> VcxResult<()> {
@@ -161,6 +210,30 @@ pub fn generate_proof(handle: u32, credentials: String, self_attested_attrs: Str
     }).map(|_| error::SUCCESS.code_num)
 }
 
+fn _parse_selection_policy(policy: Option<&str>) -> VcxResult<SelectionPolicy> {
+    match policy {
+        Some(policy) => serde_json::from_str(policy)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize selection policy: {:?}", err))),
+        None => Ok(SelectionPolicy::default())
+    }
+}
+
+pub fn select_credentials(handle: u32, policy: Option<&str>) -> VcxResult<String> {
+    let policy = _parse_selection_policy(policy)?;
+    HANDLE_MAP.get(handle, |proof| {
+        let selection = proof.select_credentials(&policy)?;
+        Ok(json!(selection).to_string())
+    }).map_err(handle_err)
+}
+
+pub fn generate_proof_with_policy(handle: u32, policy: Option<&str>, self_attested_attrs: String) -> VcxResult<u32> {
+    let policy = _parse_selection_policy(policy)?;
+    HANDLE_MAP.get_mut(handle, |proof| {
+        proof.generate_presentation_with_policy(&policy, self_attested_attrs.clone())?;
+        Ok(error::SUCCESS.code_num)
+    }).map_err(handle_err)
+}
+
 pub fn decline_presentation_request(handle: u32, connection_handle: u32, reason: Option<String>, proposal: Option<String>) -> VcxResult<u32> {
     HANDLE_MAP.get_mut(handle, |proof| {
         let send_message = connection::send_message_closure(connection_handle)?;
@@ -193,6 +266,32 @@ pub fn is_valid_handle(handle: u32) -> bool {
     HANDLE_MAP.has_handle(handle)
 }
 
+pub fn get_state_timing(handle: u32) -> VcxResult<StateTiming> {
+    HANDLE_MAP.get(handle, |proof| Ok(proof.get_state_timing()))
+}
+
+pub fn expired_handles() -> VcxResult<Vec<u32>> {
+    HANDLE_MAP.find_handles(|proof| proof.is_expired())
+}
+
+pub fn abandon(handle: u32, connection_handle: Option<u32>, reason: Option<String>) -> VcxResult<u32> {
+    HANDLE_MAP.get_mut(handle, |proof| {
+        match connection_handle {
+            Some(connection_handle) => proof.abandon(reason.clone(), Some(&connection::send_message_closure(connection_handle)?))?,
+            None => proof.abandon(reason.clone(), None::<&fn(&A2AMessage) -> _>)?
+        };
+        Ok(proof.state())
+    })
+}
+
+pub fn handles_by_thread_id() -> VcxResult<HashMap<String, Vec<u32>>> {
+    HANDLE_MAP.group_by(|proof| proof.get_thread_id())
+}
+
+pub fn get_profile_handle(handle: u32) -> Option<u32> {
+    HANDLE_MAP.get_profile_handle(handle)
+}
+
 fn get_proof_request(connection_handle: u32, msg_id: &str) -> VcxResult<String> {
     if !connection::is_v3_connection(connection_handle)? {
         return Err(VcxError::from_msg(VcxErrorKind::InvalidConnectionHandle, format!("Connection can not be used for Proprietary Issuance protocol")));
@@ -441,4 +540,35 @@ mod tests {
         let attrs = get_proof_request_attachment(handle).unwrap();
         let _attrs: PresentationRequestData = serde_json::from_str(&attrs).unwrap();
     }
+
+    fn _retrieved_credentials() -> String {
+        let selected: Value = serde_json::from_str(ARIES_PROVER_CREDENTIALS).unwrap();
+        let mut retrieved = json!({"attrs": {}});
+        for (referent, credential) in selected["attrs"].as_object().unwrap() {
+            retrieved["attrs"][referent] = json!([credential["credential"]]);
+        }
+        retrieved.to_string()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_select_credentials_and_generate_proof() {
+        let _setup = SetupMocks::init();
+
+        let handle = create_proof("TEST_CREDENTIAL", ARIES_PROOF_REQUEST_PRESENTATION).unwrap();
+
+        let _mock_builder = MockBuilder::init()
+            .set_mock_creds_retrieved_for_proof_request(&_retrieved_credentials())
+            .set_mock_generate_indy_proof("{\"selected\":\"credentials\"}");
+
+        let selection: Value = serde_json::from_str(&select_credentials(handle, None).unwrap()).unwrap();
+        assert_eq!(json!(["attribute_3"]), selection["unsatisfied"]);
+        assert!(selection["credentials"]["attrs"]["predicate_0"]["credential"]["cred_info"].is_object());
+
+        assert_eq!(VcxErrorKind::InvalidProofCredentialData, generate_proof_with_policy(handle, None, "{}".to_string()).unwrap_err().kind());
+        assert_eq!(VcxErrorKind::InvalidJson, select_credentials(handle, Some("not json")).unwrap_err().kind());
+
+        generate_proof_with_policy(handle, Some(r#"{"check_revocation": false}"#), ARIES_PROVER_SELF_ATTESTED_ATTRS.to_string()).unwrap();
+        assert!(generate_proof_msg(handle).is_ok());
+    }
 }
@@ -0,0 +1,454 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde_json;
+
+use crate::api_lib::api_handle::{connection, credential, disclosed_proof, issuer_credential, proof};
+use crate::aries::messages::a2a::A2AMessage;
+use crate::error::prelude::*;
+
+lazy_static! {
+    // connection over which the dispatcher exchanges messages with the object
+    static ref CONNECTIONS: Mutex<HashMap<(ObjectType, u32), u32>> = Default::default();
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectType {
+    IssuerCredential,
+    Credential,
+    DisclosedProof,
+    Proof,
+}
+
+/**
+Result of dispatching a single message. `created` is set if the object has been created from an offer or a request
+which did not belong to any existing object, `error` is set if the object failed to process the message.
+`handle` is 0 only if creating the object failed.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DispatchedMessage {
+    pub uid: String,
+    pub connection_handle: u32,
+    pub object_type: ObjectType,
+    pub handle: u32,
+    pub created: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/**
+Connection whose messages could not be downloaded, messages of other connections are dispatched anyway.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FailedConnection {
+    pub connection_handle: u32,
+    pub code: u32,
+    pub reason: String,
+}
+
+/**
+Thread shared by several objects of the same type and profile, e.g. an object deserialized twice. Its messages
+are not dispatched as it is not known which of the objects they belong to.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DuplicateThread {
+    pub object_type: ObjectType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile_handle: Option<u32>,
+    pub thread_id: String,
+    pub handles: Vec<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct DispatchReport {
+    pub dispatched: Vec<DispatchedMessage>,
+    pub failed: Vec<FailedConnection>,
+    pub duplicates: Vec<DuplicateThread>,
+}
+
+/**
+Objects by their profile and thread id, message is dispatched only to objects of the profile of its connection.
+ */
+struct ThreadIndex(HashMap<ObjectType, HashMap<(Option<u32>, String), Vec<u32>>>);
+
+impl ThreadIndex {
+    fn build() -> VcxResult<ThreadIndex> {
+        let mut index = ThreadIndex(HashMap::new());
+        index._add(ObjectType::IssuerCredential, issuer_credential::handles_by_thread_id()?, issuer_credential::get_profile_handle);
+        index._add(ObjectType::Credential, credential::handles_by_thread_id()?, credential::get_profile_handle);
+        index._add(ObjectType::DisclosedProof, disclosed_proof::handles_by_thread_id()?, disclosed_proof::get_profile_handle);
+        index._add(ObjectType::Proof, proof::handles_by_thread_id()?, proof::get_profile_handle);
+        Ok(index)
+    }
+
+    fn _add(&mut self, object_type: ObjectType, handles: HashMap<String, Vec<u32>>, get_profile_handle: fn(u32) -> Option<u32>) {
+        for (thread_id, handles) in handles {
+            for handle in handles {
+                self.insert(object_type, get_profile_handle(handle), &thread_id, handle);
+            }
+        }
+    }
+
+    /**
+    Handles of the first of `object_types` having an object of the thread, more than one handle is returned if
+    the thread is duplicate.
+     */
+    fn find(&self, object_types: &[ObjectType], profile_handle: Option<u32>, thread_id: &str) -> Option<(ObjectType, &[u32])> {
+        let key = (profile_handle, thread_id.to_string());
+        object_types.iter()
+            .filter_map(|object_type| self.0.get(object_type)
+                .and_then(|handles| handles.get(&key))
+                .map(|handles| (*object_type, handles.as_slice())))
+            .next()
+    }
+
+    fn insert(&mut self, object_type: ObjectType, profile_handle: Option<u32>, thread_id: &str, handle: u32) {
+        self.0.entry(object_type).or_default().entry((profile_handle, thread_id.to_string())).or_default().push(handle);
+    }
+
+    fn contains(&self, object_type: ObjectType, handle: u32) -> bool {
+        self.0.get(&object_type).map_or(false, |handles| handles.values().any(|h| h.contains(&handle)))
+    }
+
+    fn duplicates(&self) -> Vec<DuplicateThread> {
+        self.0.iter()
+            .flat_map(|(object_type, handles)| handles.iter()
+                .filter(|(_, handles)| handles.len() > 1)
+                .map(move |((profile_handle, thread_id), handles)| DuplicateThread {
+                    object_type: *object_type,
+                    profile_handle: *profile_handle,
+                    thread_id: thread_id.clone(),
+                    handles: handles.clone(),
+                }))
+            .collect()
+    }
+}
+
+/**
+Object is bound to the connection over which it got its first dispatched message or from whose message it has been
+created, messages of its thread arriving over other connections are not dispatched to it.
+ */
+fn _bind_connection(object_type: ObjectType, handle: u32, connection_handle: u32) -> VcxResult<bool> {
+    let mut connections = CONNECTIONS.lock()?;
+    let bound = connections.entry((object_type, handle)).or_insert(connection_handle);
+    Ok(*bound == connection_handle)
+}
+
+fn _release_connections(index: &ThreadIndex) -> VcxResult<()> {
+    CONNECTIONS.lock()?.retain(|(object_type, handle), _| index.contains(*object_type, *handle));
+    Ok(())
+}
+
+/**
+Object types which can receive the message, messages of other protocols are not dispatched.
+ */
+fn _recipients(message: &A2AMessage) -> &'static [ObjectType] {
+    match message {
+        A2AMessage::CredentialProposal(_) |
+        A2AMessage::CredentialRequest(_) |
+        A2AMessage::CredentialAck(_) |
+        A2AMessage::CredentialAckV2(_) => &[ObjectType::IssuerCredential],
+        A2AMessage::CredentialOffer(_) |
+        A2AMessage::Credential(_) => &[ObjectType::Credential],
+        A2AMessage::PresentationRequest(_) |
+        A2AMessage::PresentationAck(_) |
+        A2AMessage::PresentationAckV2(_) => &[ObjectType::DisclosedProof],
+        A2AMessage::PresentationProposal(_) |
+        A2AMessage::Presentation(_) => &[ObjectType::Proof],
+        A2AMessage::Ack(_) |
+        A2AMessage::CommonProblemReport(_) => &[ObjectType::IssuerCredential, ObjectType::Credential, ObjectType::DisclosedProof, ObjectType::Proof],
+        _ => &[]
+    }
+}
+
+/**
+Thread of the message is identified by `~thread.thid`, message without it starts a new thread identified by its `@id`.
+ */
+fn _thread_id(message: &A2AMessage) -> Option<String> {
+    let message = serde_json::to_value(message).ok()?;
+    message["~thread"]["thid"].as_str()
+        .or(message["@id"].as_str())
+        .map(String::from)
+}
+
+fn _update_state(object_type: ObjectType, handle: u32, message: &str, connection_handle: u32) -> VcxResult<u32> {
+    match object_type {
+        ObjectType::IssuerCredential => issuer_credential::update_state(handle, Some(message), connection_handle),
+        ObjectType::Credential => credential::update_state(handle, Some(message), connection_handle),
+        ObjectType::DisclosedProof => disclosed_proof::update_state(handle, Some(message), connection_handle),
+        ObjectType::Proof => proof::update_state(handle, Some(message), connection_handle),
+    }
+}
+
+/**
+Creates holder for an unsolicited credential offer and prover for an unsolicited presentation request
+within the profile of the connection, source id of the object is the thread id.
+ */
+fn _create_recipient(message: &A2AMessage, profile_handle: Option<u32>, thread_id: &str, message_json: &str) -> Option<(ObjectType, VcxResult<u32>)> {
+    match message {
+        A2AMessage::CredentialOffer(_) => Some((ObjectType::Credential, credential::credential_create_with_offer_in_profile(profile_handle, thread_id, message_json))),
+        A2AMessage::PresentationRequest(_) => Some((ObjectType::DisclosedProof, disclosed_proof::create_proof_in_profile(profile_handle, thread_id, message_json))),
+        _ => None
+    }
+}
+
+fn _dispatch_message(index: &mut ThreadIndex, connection_handle: u32, uid: String, message: A2AMessage) -> VcxResult<Option<DispatchedMessage>> {
+    let recipients = _recipients(&message);
+    if recipients.is_empty() {
+        return Ok(None);
+    }
+    let thread_id = match _thread_id(&message) {
+        Some(thread_id) => thread_id,
+        None => return Ok(None)
+    };
+    let message_json = serde_json::to_string(&message)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Cannot serialize message: {:?}", err)))?;
+    let profile_handle = connection::get_profile_handle(connection_handle);
+
+    let (object_type, result, created) = match index.find(recipients, profile_handle, &thread_id) {
+        Some((_, handles)) if handles.len() > 1 => {
+            warn!("dispatcher >>> message uid: {}, thread_id: {} belongs to several objects, handles: {:?}", uid, thread_id, handles);
+            return Ok(None);
+        }
+        Some((object_type, handles)) => {
+            let handle = handles[0];
+            if !_bind_connection(object_type, handle, connection_handle)? {
+                warn!("dispatcher >>> message uid: {}, thread_id: {} arrived over other connection than the thread, connection_handle: {}", uid, thread_id, connection_handle);
+                return Ok(None);
+            }
+            let result = _update_state(object_type, handle, &message_json, connection_handle)
+                .map(|state| (handle, Some(state)))
+                .map_err(|err| (handle, err));
+            (object_type, result, false)
+        }
+        None => match _create_recipient(&message, profile_handle, &thread_id, &message_json) {
+            Some((object_type, result)) => {
+                if let Ok(handle) = result {
+                    index.insert(object_type, profile_handle, &thread_id, handle);
+                    _bind_connection(object_type, handle, connection_handle)?;
+                }
+                (object_type, result.map(|handle| (handle, None)).map_err(|err| (0, err)), true)
+            }
+            None => {
+                debug!("dispatcher >>> no object found for message uid: {}, thread_id: {}", uid, thread_id);
+                return Ok(None);
+            }
+        }
+    };
+
+    let dispatched = match result {
+        Ok((handle, state)) => {
+            let error = connection::update_message_status(connection_handle, uid.clone()).err().map(|err| {
+                warn!("dispatcher >>> failed to mark message uid: {} as reviewed, error: {}", uid, err);
+                err.to_string()
+            });
+            DispatchedMessage { uid, connection_handle, object_type, handle, created, state, error }
+        }
+        Err((handle, err)) => {
+            warn!("dispatcher >>> failed to dispatch message uid: {}, thread_id: {}, error: {}", uid, thread_id, err);
+            DispatchedMessage { uid, connection_handle, object_type, handle, created, state: None, error: Some(err.to_string()) }
+        }
+    };
+    Ok(Some(dispatched))
+}
+
+/**
+Downloads messages of every connection once and passes each of them to the issuer credential, credential,
+disclosed proof or proof of the connection's profile whose thread it belongs to. Dispatched messages are marked as
+reviewed, messages which failed to be processed are kept so they are dispatched again. Messages of other protocols,
+messages of duplicate threads and messages of threads bound to other connections are left untouched. Connection
+whose messages cannot be downloaded is reported and skipped.
+ */
+pub fn dispatch_messages(connection_handles: &[u32]) -> VcxResult<DispatchReport> {
+    trace!("dispatch_messages >>> connection_handles: {:?}", connection_handles);
+    if let Some(handle) = connection_handles.iter().find(|handle| !connection::is_valid_handle(**handle)) {
+        return Err(VcxError::from_msg(VcxErrorKind::InvalidConnectionHandle, format!("Invalid connection handle: {}", handle)));
+    }
+    let mut index = ThreadIndex::build()?;
+    _release_connections(&index)?;
+    let mut report = DispatchReport { duplicates: index.duplicates(), ..DispatchReport::default() };
+    for duplicate in report.duplicates.iter() {
+        warn!("dispatch_messages >>> thread_id: {} is shared by {:?} handles: {:?}", duplicate.thread_id, duplicate.object_type, duplicate.handles);
+    }
+    for connection_handle in connection_handles {
+        let messages = match connection::get_messages(*connection_handle) {
+            Ok(messages) => messages,
+            Err(err) => {
+                warn!("dispatch_messages >>> failed to download messages of connection_handle: {}, error: {}", connection_handle, err);
+                report.failed.push(FailedConnection { connection_handle: *connection_handle, code: err.kind().into(), reason: err.to_string().trim().to_string() });
+                continue;
+            }
+        };
+        for (uid, message) in messages {
+            if let Some(message) = _dispatch_message(&mut index, *connection_handle, uid, message)? {
+                report.dispatched.push(message);
+            }
+        }
+    }
+    trace!("dispatch_messages <<< report: {:?}", report);
+    Ok(report)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use agency_client::mocking::AgencyMockDecrypted;
+
+    use crate::api_lib::api_handle::connection::tests::{build_test_connection_invitee_completed, build_test_connection_inviter_null};
+    use crate::profile::tests::_create_profile;
+    use crate::utils::constants::GET_MESSAGES_DECRYPTED_RESPONSE;
+    use crate::utils::devsetup::SetupMocks;
+    use crate::utils::mockdata::mockdata_connection::CONNECTION_SM_INVITEE_COMPLETED;
+    use crate::utils::mockdata::mockdata_credex::ARIES_CREDENTIAL_OFFER;
+
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_thread_id_of_message() {
+        let _setup = SetupMocks::init();
+
+        let offer: A2AMessage = serde_json::from_str(ARIES_CREDENTIAL_OFFER).unwrap();
+        let expected = match offer {
+            A2AMessage::CredentialOffer(ref offer) => offer.thread_id(),
+            _ => panic!("Unexpected message")
+        };
+        assert_eq!(Some(expected), _thread_id(&offer));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_recipients_of_messages() {
+        let _setup = SetupMocks::init();
+
+        let offer: A2AMessage = serde_json::from_str(ARIES_CREDENTIAL_OFFER).unwrap();
+        assert_eq!(&[ObjectType::Credential], _recipients(&offer));
+        assert!(_recipients(&A2AMessage::Generic(json!({}))).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_dispatch_creates_credential_for_unsolicited_offer() {
+        let _setup = SetupMocks::init();
+
+        let profile_handle = _create_profile();
+        let connection_handle = connection::from_string_in_profile(Some(profile_handle), CONNECTION_SM_INVITEE_COMPLETED).unwrap();
+        AgencyMockDecrypted::set_next_decrypted_response(GET_MESSAGES_DECRYPTED_RESPONSE);
+        AgencyMockDecrypted::set_next_decrypted_message(ARIES_CREDENTIAL_OFFER);
+
+        let report = dispatch_messages(&[connection_handle]).unwrap();
+        assert!(report.failed.is_empty());
+        let dispatched = report.dispatched;
+        assert_eq!(1, dispatched.len());
+        assert!(dispatched[0].created);
+        assert_eq!(ObjectType::Credential, dispatched[0].object_type);
+        assert_eq!(None, dispatched[0].error);
+        assert_eq!(Some(profile_handle), credential::get_profile_handle(dispatched[0].handle));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_dispatch_message_creates_credential_for_unsolicited_offer() {
+        let _setup = SetupMocks::init();
+
+        let connection_handle = build_test_connection_invitee_completed();
+        let offer: A2AMessage = serde_json::from_str(ARIES_CREDENTIAL_OFFER).unwrap();
+        let thread_id = _thread_id(&offer).unwrap();
+        let mut index = ThreadIndex(HashMap::new());
+
+        let dispatched = _dispatch_message(&mut index, connection_handle, "uid".to_string(), offer).unwrap().unwrap();
+        assert!(dispatched.created);
+        assert_eq!(ObjectType::Credential, dispatched.object_type);
+        assert_eq!(None, dispatched.error);
+        assert_eq!(thread_id, credential::get_source_id(dispatched.handle).unwrap());
+        assert_eq!(Some((ObjectType::Credential, &[dispatched.handle][..])), index.find(&[ObjectType::Credential], None, &thread_id));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_dispatch_message_ignores_thread_of_other_connection() {
+        let _setup = SetupMocks::init();
+
+        let connection_handle = build_test_connection_invitee_completed();
+        let other_connection_handle = build_test_connection_invitee_completed();
+        let offer: A2AMessage = serde_json::from_str(ARIES_CREDENTIAL_OFFER).unwrap();
+        let mut index = ThreadIndex(HashMap::new());
+
+        let dispatched = _dispatch_message(&mut index, connection_handle, "uid".to_string(), offer.clone()).unwrap().unwrap();
+        assert!(dispatched.created);
+
+        assert_eq!(None, _dispatch_message(&mut index, other_connection_handle, "uid".to_string(), offer).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_dispatch_message_ignores_thread_of_other_profile() {
+        let _setup = SetupMocks::init();
+
+        let connection_handle = build_test_connection_invitee_completed();
+        let offer: A2AMessage = serde_json::from_str(ARIES_CREDENTIAL_OFFER).unwrap();
+        let thread_id = _thread_id(&offer).unwrap();
+        let mut index = ThreadIndex(HashMap::new());
+        index.insert(ObjectType::Credential, Some(1), &thread_id, 1);
+
+        let dispatched = _dispatch_message(&mut index, connection_handle, "uid".to_string(), offer).unwrap().unwrap();
+        assert!(dispatched.created);
+        assert_ne!(1, dispatched.handle);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_dispatch_message_keeps_handle_of_object_failing_to_update() {
+        let _setup = SetupMocks::init();
+
+        let connection_handle = build_test_connection_invitee_completed();
+        let offer: A2AMessage = serde_json::from_str(ARIES_CREDENTIAL_OFFER).unwrap();
+        let mut index = ThreadIndex(HashMap::new());
+        let created = _dispatch_message(&mut index, connection_handle, "uid".to_string(), offer.clone()).unwrap().unwrap();
+        assert!(created.created);
+
+        connection::release(connection_handle).unwrap();
+
+        let dispatched = _dispatch_message(&mut index, connection_handle, "uid".to_string(), offer).unwrap().unwrap();
+        assert!(!dispatched.created);
+        assert_eq!(created.handle, dispatched.handle);
+        assert_eq!(None, dispatched.state);
+        assert!(dispatched.error.is_some());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_dispatch_message_ignores_duplicate_thread() {
+        let _setup = SetupMocks::init();
+
+        let connection_handle = build_test_connection_invitee_completed();
+        let offer: A2AMessage = serde_json::from_str(ARIES_CREDENTIAL_OFFER).unwrap();
+        let thread_id = _thread_id(&offer).unwrap();
+        let mut index = ThreadIndex(HashMap::new());
+        index.insert(ObjectType::Credential, None, &thread_id, 1);
+        index.insert(ObjectType::Credential, None, &thread_id, 2);
+
+        assert_eq!(None, _dispatch_message(&mut index, connection_handle, "uid".to_string(), offer).unwrap());
+        assert_eq!(vec![DuplicateThread { object_type: ObjectType::Credential, profile_handle: None, thread_id, handles: vec![1, 2] }], index.duplicates());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_dispatch_continues_after_connection_fails() {
+        let _setup = SetupMocks::init();
+
+        let failing_connection_handle = build_test_connection_inviter_null();
+        let connection_handle = build_test_connection_invitee_completed();
+        AgencyMockDecrypted::set_next_decrypted_response(GET_MESSAGES_DECRYPTED_RESPONSE);
+        AgencyMockDecrypted::set_next_decrypted_message(ARIES_CREDENTIAL_OFFER);
+
+        let report = dispatch_messages(&[failing_connection_handle, connection_handle]).unwrap();
+        assert_eq!(1, report.failed.len());
+        assert_eq!(failing_connection_handle, report.failed[0].connection_handle);
+        assert_eq!(u32::from(VcxErrorKind::NotReady), report.failed[0].code);
+        assert_eq!(1, report.dispatched.len());
+        assert_eq!(connection_handle, report.dispatched[0].connection_handle);
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+
+use rand::Rng;
+
+use crate::aries::messages::a2a::A2AMessage;
+use crate::error::prelude::*;
+
+lazy_static! {
+    static ref LISTENERS: RwLock<HashMap<u32, Arc<dyn EventListener>>> = Default::default();
+    static ref DISPATCHER: Mutex<Option<Sender<Event>>> = Default::default();
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    StateChanged,
+    UnhandledMessage,
+    ProblemReport,
+}
+
+/**
+Notification about an object of the library. `old_state` and `new_state` are equal unless the state has changed,
+`message` is set for received messages. `thread_id` is not set for connections and mediations.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Event {
+    pub kind: EventKind,
+    pub object_type: String,
+    pub handle: u32,
+    pub source_id: String,
+    pub thread_id: Option<String>,
+    pub old_state: u32,
+    pub new_state: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<A2AMessage>,
+}
+
+impl Event {
+    pub fn to_string(&self) -> VcxResult<String> {
+        serde_json::to_string(self)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Cannot serialize Event: {:?}", err)))
+    }
+}
+
+/**
+Listeners are called one event at a time on a dedicated thread, in the order the events were emitted,
+so they can use the library without blocking the operation which emitted the event.
+ */
+pub trait EventListener: Send + Sync {
+    fn on_event(&self, event: &Event);
+}
+
+impl<F> EventListener for F where F: Fn(&Event) + Send + Sync {
+    fn on_event(&self, event: &Event) {
+        self(event)
+    }
+}
+
+pub fn subscribe(listener: Box<dyn EventListener>) -> VcxResult<u32> {
+    let mut listeners = LISTENERS.write()?;
+
+    let mut subscription_handle = rand::thread_rng().gen::<u32>();
+    while subscription_handle == 0 || listeners.contains_key(&subscription_handle) {
+        subscription_handle = rand::thread_rng().gen::<u32>();
+    }
+
+    listeners.insert(subscription_handle, Arc::from(listener));
+    Ok(subscription_handle)
+}
+
+pub fn unsubscribe(subscription_handle: u32) -> VcxResult<()> {
+    LISTENERS.write()?
+        .remove(&subscription_handle)
+        .map(|_| ())
+        .ok_or(VcxError::from_msg(VcxErrorKind::InvalidHandle, format!("Subscription not found for handle: {}", subscription_handle)))
+}
+
+pub fn has_listeners() -> bool {
+    LISTENERS.read()
+        .map(|listeners| !listeners.is_empty())
+        .unwrap_or(false)
+}
+
+pub fn emit(event: Event) {
+    trace!("events::emit >>> event: {:?}", event);
+    if !has_listeners() { return; }
+
+    let mut dispatcher = match DISPATCHER.lock() {
+        Ok(dispatcher) => dispatcher,
+        Err(_) => return warn!("events::emit >>> dispatcher is poisoned, event is dropped")
+    };
+    let sender = dispatcher.get_or_insert_with(_spawn_dispatcher);
+    if let Err(mpsc::SendError(event)) = sender.send(event) {
+        let sender = _spawn_dispatcher();
+        sender.send(event).ok();
+        *dispatcher = Some(sender);
+    }
+}
+
+fn _spawn_dispatcher() -> Sender<Event> {
+    let (sender, receiver) = mpsc::channel::<Event>();
+    let spawned = thread::Builder::new()
+        .name("vcx-events".to_string())
+        .spawn(move || {
+            for event in receiver {
+                let listeners: Vec<Arc<dyn EventListener>> = match LISTENERS.read() {
+                    Ok(listeners) => listeners.values().cloned().collect(),
+                    Err(_) => continue
+                };
+                for listener in listeners {
+                    if panic::catch_unwind(AssertUnwindSafe(|| listener.on_event(&event))).is_err() {
+                        error!("events >>> listener panicked on event: {:?}", event);
+                    }
+                }
+            }
+        });
+    if let Err(err) = spawned {
+        error!("events >>> cannot spawn dispatcher thread: {:?}", err);
+    }
+    sender
+}
+
+pub fn is_problem_report(message: &A2AMessage) -> bool {
+    match message {
+        A2AMessage::ConnectionProblemReport(_) |
+        A2AMessage::DidExchangeProblemReport(_) |
+        A2AMessage::CommonProblemReport(_) => true,
+        _ => false
+    }
+}
+
+/**
+Wraps a single message for `find_message_to_handle` of the handlers.
+ */
+pub fn as_messages(message: &A2AMessage) -> HashMap<String, A2AMessage> {
+    let mut messages = HashMap::new();
+    messages.insert(String::new(), message.clone());
+    messages
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::mpsc::Receiver;
+    use std::time::Duration;
+
+    use super::*;
+
+    pub fn subscribe_channel() -> (u32, Receiver<Event>) {
+        let (sender, receiver) = mpsc::channel();
+        let sender = Mutex::new(sender);
+        let subscription_handle = subscribe(Box::new(move |event: &Event| {
+            sender.lock().unwrap().send(event.clone()).ok();
+        })).unwrap();
+        (subscription_handle, receiver)
+    }
+
+    pub fn next_event(receiver: &Receiver<Event>, handle: u32) -> Event {
+        loop {
+            let event = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+            if event.handle == handle {
+                return event;
+            }
+        }
+    }
+
+    fn _event(handle: u32) -> Event {
+        Event {
+            kind: EventKind::StateChanged,
+            object_type: "proof".to_string(),
+            handle,
+            source_id: "test_events".to_string(),
+            thread_id: Some("thread_id".to_string()),
+            old_state: 1,
+            new_state: 2,
+            message: None,
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_events_are_delivered_in_order() {
+        let (subscription_handle, receiver) = subscribe_channel();
+
+        let handle = rand::thread_rng().gen::<u32>();
+        for new_state in 2..5 {
+            emit(Event { new_state, .._event(handle) });
+        }
+        for new_state in 2..5 {
+            assert_eq!(new_state, next_event(&receiver, handle).new_state);
+        }
+
+        unsubscribe(subscription_handle).unwrap();
+        assert_eq!(unsubscribe(subscription_handle).unwrap_err().kind(), VcxErrorKind::InvalidHandle);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_panicking_listener_does_not_stop_delivery() {
+        let panicking = subscribe(Box::new(|_: &Event| panic!("listener failure"))).unwrap();
+        let (subscription_handle, receiver) = subscribe_channel();
+
+        let handle = rand::thread_rng().gen::<u32>();
+        emit(_event(handle));
+        emit(_event(handle));
+        next_event(&receiver, handle);
+        next_event(&receiver, handle);
+
+        unsubscribe(panicking).unwrap();
+        unsubscribe(subscription_handle).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_event_serialization() {
+        let event = _event(1);
+        assert_eq!(json!({
+            "kind": "state_changed",
+            "object_type": "proof",
+            "handle": 1,
+            "source_id": "test_events",
+            "thread_id": "thread_id",
+            "old_state": 1,
+            "new_state": 2
+        }), serde_json::from_str::<serde_json::Value>(&event.to_string().unwrap()).unwrap());
+    }
+}
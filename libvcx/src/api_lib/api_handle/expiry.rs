@@ -0,0 +1,137 @@
+use serde_json;
+
+use crate::api_lib::api_handle::{credential, disclosed_proof, issuer_credential, proof};
+use crate::aries::handlers::state_timing::StateTiming;
+use crate::error::prelude::*;
+
+struct ExpiringObjects {
+    get_state_timing: fn(u32) -> VcxResult<StateTiming>,
+    expired_handles: fn() -> VcxResult<Vec<u32>>,
+    abandon: fn(u32, Option<u32>, Option<String>) -> VcxResult<u32>,
+}
+
+/**
+Exchange expires when it stays in a state waiting for the other party longer than `issue_credential_expiry`
+or `present_proof_expiry` seconds set in the config. Offers and requests sent by the library carry the expiration
+in their `~timing` decorator, received offers and requests expire at their `~timing.expires_time` if it is set.
+ */
+fn _expiring_objects(object_type: &str) -> VcxResult<ExpiringObjects> {
+    match object_type {
+        "issuer_credential" => Ok(ExpiringObjects {
+            get_state_timing: issuer_credential::get_state_timing,
+            expired_handles: issuer_credential::expired_handles,
+            abandon: issuer_credential::abandon,
+        }),
+        "credential" => Ok(ExpiringObjects {
+            get_state_timing: credential::get_state_timing,
+            expired_handles: credential::expired_handles,
+            abandon: credential::abandon,
+        }),
+        "disclosed_proof" => Ok(ExpiringObjects {
+            get_state_timing: disclosed_proof::get_state_timing,
+            expired_handles: disclosed_proof::expired_handles,
+            abandon: disclosed_proof::abandon,
+        }),
+        "proof" => Ok(ExpiringObjects {
+            get_state_timing: proof::get_state_timing,
+            expired_handles: proof::expired_handles,
+            abandon: proof::abandon,
+        }),
+        _ => Err(VcxError::from_msg(VcxErrorKind::InvalidOption, format!("Unknown object type: {}", object_type)))
+    }
+}
+
+/**
+Returns json `{"entered_time": string, "expires_time": string}` with the time the object entered its current state
+and the time the exchange expires, `expires_time` is not set if the exchange does not expire in the state.
+ */
+pub fn get_state_timing(object_type: &str, handle: u32) -> VcxResult<String> {
+    trace!("expiry::get_state_timing >>> object_type: {}, handle: {}", object_type, handle);
+    let state_timing = (_expiring_objects(object_type)?.get_state_timing)(handle)?;
+    serde_json::to_string(&state_timing)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Cannot serialize state timing: {}", err)))
+}
+
+pub fn get_expired(object_type: &str) -> VcxResult<Vec<u32>> {
+    trace!("expiry::get_expired >>> object_type: {}", object_type);
+    (_expiring_objects(object_type)?.expired_handles)()
+}
+
+/**
+Finishes the exchange with failure whether it has expired or not. The other party is notified by a problem report
+sent over the connection if `connection_handle` is set. Returns the new state of the object.
+ */
+pub fn abandon(object_type: &str, handle: u32, connection_handle: Option<u32>, reason: Option<String>) -> VcxResult<u32> {
+    trace!("expiry::abandon >>> object_type: {}, handle: {}, connection_handle: {:?}, reason: {:?}", object_type, handle, connection_handle, reason);
+    (_expiring_objects(object_type)?.abandon)(handle, connection_handle, reason)
+}
+
+/**
+Abandons all expired exchanges of the type without notifying the other parties, returns handles of the abandoned objects.
+ */
+pub fn abandon_expired(object_type: &str) -> VcxResult<Vec<u32>> {
+    trace!("expiry::abandon_expired >>> object_type: {}", object_type);
+    let objects = _expiring_objects(object_type)?;
+    let mut abandoned = Vec::new();
+    for handle in (objects.expired_handles)()? {
+        match (objects.abandon)(handle, None, None) {
+            Ok(_) => abandoned.push(handle),
+            Err(err) => warn!("expiry::abandon_expired >>> cannot abandon {} {}: {}", object_type, handle, err)
+        }
+    }
+    Ok(abandoned)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::api_lib::VcxStateType;
+    use crate::aries::messages::a2a::A2AMessage;
+    use crate::aries::messages::timing;
+    use crate::utils::devsetup::SetupMocks;
+    use crate::utils::mockdata::mockdata_credex::ARIES_CREDENTIAL_OFFER;
+
+    use super::*;
+
+    fn _expired_offer() -> String {
+        match serde_json::from_str(ARIES_CREDENTIAL_OFFER).unwrap() {
+            A2AMessage::CredentialOffer(offer) => json!(offer.set_expires_time(timing::expires_time(Some(0))).to_a2a_message()).to_string(),
+            _ => panic!("Unexpected message")
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_abandon_expired_credential() {
+        let _setup = SetupMocks::init();
+
+        let handle = credential::credential_create_with_offer("test_abandon_expired_credential", &_expired_offer()).unwrap();
+        let state_timing: StateTiming = serde_json::from_str(&get_state_timing("credential", handle).unwrap()).unwrap();
+        assert!(state_timing.entered_time.is_some());
+        assert!(state_timing.expires_time.is_some());
+        assert!(get_expired("credential").unwrap().contains(&handle));
+
+        assert!(abandon_expired("credential").unwrap().contains(&handle));
+        assert_eq!(VcxStateType::VcxStateNone as u32, credential::get_state(handle).unwrap());
+        assert!(!get_expired("credential").unwrap().contains(&handle));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_abandon_credential_not_expired() {
+        let _setup = SetupMocks::init();
+
+        let handle = credential::credential_create_with_offer("test_abandon_credential_not_expired", ARIES_CREDENTIAL_OFFER).unwrap();
+        assert!(!get_expired("credential").unwrap().contains(&handle));
+
+        assert_eq!(VcxStateType::VcxStateNone as u32, abandon("credential", handle, None, Some(String::from("reason"))).unwrap());
+        assert_eq!(VcxErrorKind::InvalidState, abandon("credential", handle, None, None).unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_expiry_fails_for_unknown_type() {
+        let _setup = SetupMocks::init();
+
+        assert_eq!(VcxErrorKind::InvalidOption, get_expired("connection").unwrap_err().kind());
+    }
+}
@@ -1,15 +1,35 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::str::FromStr;
+
 use serde_json;
 
 use crate::api_lib::api_handle::connection;
+use crate::api_lib::api_handle::events;
 use crate::api_lib::api_handle::credential_def;
-use crate::api_lib::api_handle::object_cache::ObjectCache;
+use crate::api_lib::api_handle::object_cache::{ObjectCache, ObjectEvents, ObjectStorage, PersistentCache};
 use crate::aries::handlers::issuance::issuer::issuer::{Issuer, IssuerConfig};
+use crate::aries::handlers::state_timing::StateTiming;
 use crate::aries::messages::a2a::A2AMessage;
+use crate::aries::messages::a2a::protocol_version::ProtocolVersion;
+use crate::aries::messages::issuance::credential_proposal::CredentialProposal;
 use crate::error::prelude::*;
+use crate::libindy::utils::revocation_manager::{self, CredentialRevocation, PublishReport};
+use crate::profile::ProfileContext;
 use crate::utils::error;
 
 lazy_static! {
-    static ref ISSUER_CREDENTIAL_MAP: ObjectCache<Issuer> = ObjectCache::<Issuer>::new("issuer-credentials-cache");
+    static ref ISSUER_CREDENTIAL_MAP: ObjectCache<Issuer> = ObjectCache::<Issuer>::new_persistent("issuer-credentials-cache", ObjectStorage {
+        record_type: "vcx_issuer_credential",
+        serialize: _serialize,
+        deserialize: _deserialize,
+        tags: _storage_tags,
+    }).with_events(ObjectEvents {
+        object_type: "issuer_credential",
+        source_id: |credential| credential.get_source_id().unwrap_or_default(),
+        thread_id: |credential| Some(credential.get_thread_id()),
+        state: |credential| credential.get_state().unwrap_or_default(),
+    });
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -19,21 +39,95 @@ enum IssuerCredentials {
     V3(Issuer),
 }
 
+fn _serialize(credential: &Issuer) -> VcxResult<String> {
+    serde_json::to_string(&IssuerCredentials::V3(credential.clone()))
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidState, format!("cannot serialize IssuerCredential credentialect: {:?}", err)))
+}
+
+fn _deserialize(profile: &ProfileContext, credential_data: &str) -> VcxResult<Issuer> {
+    let credential: IssuerCredentials = serde_json::from_str(credential_data)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize IssuerCredential: {:?}", err)))?;
+
+    match credential {
+        IssuerCredentials::V3(mut credential) => {
+            credential.set_profile(profile.clone());
+            Ok(credential)
+        }
+    }
+}
+
+fn _storage_tags(credential: &Issuer) -> HashMap<String, String> {
+    let mut tags = HashMap::new();
+    tags.insert("source_id".to_string(), credential.get_source_id().unwrap_or_default());
+    tags.insert("thread_id".to_string(), credential.get_thread_id());
+    tags.insert("state".to_string(), credential.get_state().unwrap_or_default().to_string());
+    tags
+}
+
+pub fn persistent_cache() -> &'static dyn PersistentCache {
+    &*ISSUER_CREDENTIAL_MAP
+}
+
+/**
+Binds the credential to the active revocation registry of the credential definition, taking one of its indexes.
+ */
+/**
+Revocation registry set here is the one active when the offer is made, revocable credential is bound
+to the registry active at the time it is issued.
+ */
+fn _issuer_config(cred_def_handle: u32) -> VcxResult<IssuerConfig> {
+    Ok(IssuerConfig {
+        cred_def_id: credential_def::get_cred_def_id(cred_def_handle)?,
+        rev_reg_id: credential_def::get_rev_reg_id(cred_def_handle).ok(),
+        tails_file: credential_def::get_tails_file(cred_def_handle)?,
+    })
+}
+
+fn _bind_rev_reg(credential: &mut Issuer, get_rev_reg: impl Fn(&str) -> VcxResult<Option<(String, String)>>) -> VcxResult<()> {
+    if let Some(rev_reg_id) = credential.get_pending_issuance_rev_reg_id() {
+        if let Some((new_rev_reg_id, tails_file)) = get_rev_reg(&rev_reg_id)? {
+            if new_rev_reg_id != rev_reg_id {
+                credential.set_rev_reg(new_rev_reg_id, tails_file)?;
+            }
+        }
+    }
+    Ok(())
+}
+
 pub fn issuer_credential_create(cred_def_handle: u32,
                                 source_id: String,
                                 issuer_did: String,
                                 credential_name: String,
                                 credential_data: String,
                                 price: u64) -> VcxResult<u32> {
-    trace!("issuer_credential_create >>> cred_def_handle: {}, source_id: {}, issuer_did: {}, credential_name: {}, credential_data: {}, price: {}",
-           cred_def_handle, source_id, issuer_did, credential_name, secret!(&credential_data), price);
-    let issuer_config = IssuerConfig {
-        cred_def_id: credential_def::get_cred_def_id(cred_def_handle)?,
-        rev_reg_id: credential_def::get_rev_reg_id(cred_def_handle).ok(),
-        tails_file: credential_def::get_tails_file(cred_def_handle)?,
-    };
-    let issuer = Issuer::create(&issuer_config, &credential_data, &source_id)?;
-    ISSUER_CREDENTIAL_MAP.add(issuer)
+    issuer_credential_create_in_profile(None, cred_def_handle, source_id, issuer_did, credential_name, credential_data, price)
+}
+
+pub fn issuer_credential_create_in_profile(profile_handle: Option<u32>,
+                                           cred_def_handle: u32,
+                                           source_id: String,
+                                           issuer_did: String,
+                                           credential_name: String,
+                                           credential_data: String,
+                                           price: u64) -> VcxResult<u32> {
+    trace!("issuer_credential_create >>> profile_handle: {:?}, cred_def_handle: {}, source_id: {}, issuer_did: {}, credential_name: {}, credential_data: {}, price: {}",
+           profile_handle, cred_def_handle, source_id, issuer_did, credential_name, secret!(&credential_data), price);
+    let issuer_config = _issuer_config(cred_def_handle)?;
+    let issuer = Issuer::create(ProfileContext::from_handle(profile_handle)?, &issuer_config, &credential_data, &source_id)?;
+    ISSUER_CREDENTIAL_MAP.add_in_profile(profile_handle, issuer)
+}
+
+pub fn issuer_credential_create_with_proposal(source_id: String, proposal: &str) -> VcxResult<u32> {
+    issuer_credential_create_with_proposal_in_profile(None, source_id, proposal)
+}
+
+pub fn issuer_credential_create_with_proposal_in_profile(profile_handle: Option<u32>, source_id: String, proposal: &str) -> VcxResult<u32> {
+    trace!("issuer_credential_create_with_proposal >>> profile_handle: {:?}, source_id: {}, proposal: {}", profile_handle, source_id, secret!(&proposal));
+    let proposal: CredentialProposal = serde_json::from_str(proposal)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize Credential Proposal: {:?}", err)))?;
+    proposal.validate()?;
+    let issuer = Issuer::create_from_proposal(ProfileContext::from_handle(profile_handle)?, proposal, &source_id)?;
+    ISSUER_CREDENTIAL_MAP.add_in_profile(profile_handle, issuer)
 }
 
 pub fn update_state(handle: u32, message: Option<&str>, connection_handle: u32) -> VcxResult<u32> {
@@ -45,10 +139,12 @@ pub fn update_state(handle: u32, message: Option<&str>, connection_handle: u32)
         if let Some(message) = message {
             let message: A2AMessage = serde_json::from_str(&message)
                 .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidOption, format!("Cannot update state: Message deserialization failed: {:?}", err)))?;
+            ISSUER_CREDENTIAL_MAP.notify_message(handle, credential, &message, credential.find_message_to_handle(events::as_messages(&message)).is_some());
             credential.step(message.into(), Some(&send_message))?;
         } else {
             let messages = connection::get_messages(connection_handle)?;
             if let Some((uid, msg)) = credential.find_message_to_handle(messages) {
+                ISSUER_CREDENTIAL_MAP.notify_message(handle, credential, &msg, true);
                 credential.step(msg.into(), Some(&send_message))?;
                 connection::update_message_status(connection_handle, uid)?;
             }
@@ -82,20 +178,43 @@ pub fn is_valid_handle(handle: u32) -> bool {
     ISSUER_CREDENTIAL_MAP.has_handle(handle)
 }
 
-pub fn to_string(handle: u32) -> VcxResult<String> {
-    ISSUER_CREDENTIAL_MAP.get(handle, |credential| {
-        serde_json::to_string(&IssuerCredentials::V3(credential.clone()))
-            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidState, format!("cannot serialize IssuerCredential credentialect: {:?}", err)))
+pub fn get_state_timing(handle: u32) -> VcxResult<StateTiming> {
+    ISSUER_CREDENTIAL_MAP.get(handle, |credential| Ok(credential.get_state_timing()))
+}
+
+pub fn expired_handles() -> VcxResult<Vec<u32>> {
+    ISSUER_CREDENTIAL_MAP.find_handles(|credential| credential.is_expired())
+}
+
+pub fn abandon(handle: u32, connection_handle: Option<u32>, reason: Option<String>) -> VcxResult<u32> {
+    ISSUER_CREDENTIAL_MAP.get_mut(handle, |credential| {
+        match connection_handle {
+            Some(connection_handle) => credential.abandon(reason.clone(), Some(&connection::send_message_closure(connection_handle)?))?,
+            None => credential.abandon(reason.clone(), None::<&fn(&A2AMessage) -> _>)?
+        };
+        Ok(credential.get_state()?)
     })
 }
 
+pub fn handles_by_thread_id() -> VcxResult<HashMap<String, Vec<u32>>> {
+    ISSUER_CREDENTIAL_MAP.group_by(|credential| credential.get_thread_id())
+}
+
+pub fn get_profile_handle(handle: u32) -> Option<u32> {
+    ISSUER_CREDENTIAL_MAP.get_profile_handle(handle)
+}
+
+pub fn to_string(handle: u32) -> VcxResult<String> {
+    ISSUER_CREDENTIAL_MAP.get(handle, _serialize)
+}
+
 pub fn from_string(credential_data: &str) -> VcxResult<u32> {
-    let issuer_credential: IssuerCredentials = serde_json::from_str(credential_data)
-        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize IssuerCredential: {:?}", err)))?;
+    from_string_in_profile(None, credential_data)
+}
 
-    match issuer_credential {
-        IssuerCredentials::V3(credential) => ISSUER_CREDENTIAL_MAP.add(credential)
-    }
+pub fn from_string_in_profile(profile_handle: Option<u32>, credential_data: &str) -> VcxResult<u32> {
+    let credential = _deserialize(&ProfileContext::from_handle(profile_handle)?, credential_data)?;
+    ISSUER_CREDENTIAL_MAP.add_in_profile(profile_handle, credential)
 }
 
 pub fn generate_credential_offer_msg(handle: u32) -> VcxResult<(String, String)> {
@@ -104,6 +223,13 @@ pub fn generate_credential_offer_msg(handle: u32) -> VcxResult<(String, String)>
     })
 }
 
+pub fn set_protocol_version(handle: u32, protocol_version: &str) -> VcxResult<()> {
+    let protocol_version = ProtocolVersion::from_str(protocol_version)?;
+    ISSUER_CREDENTIAL_MAP.get_mut(handle, |credential| {
+        credential.set_protocol_version(protocol_version)
+    })
+}
+
 pub fn send_credential_offer(handle: u32, connection_handle: u32, comment: Option<String>) -> VcxResult<u32> {
     ISSUER_CREDENTIAL_MAP.get_mut(handle, |credential| {
         credential.send_credential_offer(connection::send_message_closure(connection_handle)?, comment.clone())?;
@@ -113,6 +239,40 @@ pub fn send_credential_offer(handle: u32, connection_handle: u32, comment: Optio
     })
 }
 
+pub fn get_credential_proposal(handle: u32) -> VcxResult<String> {
+    ISSUER_CREDENTIAL_MAP.get(handle, |credential| {
+        let proposal = credential.get_credential_proposal()?;
+        serde_json::to_string(&proposal)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Cannot serialize Credential Proposal: {:?}", err)))
+    })
+}
+
+pub fn send_credential_offer_for_proposal(handle: u32, cred_def_handle: u32, credential_data: &str, connection_handle: u32, comment: Option<String>) -> VcxResult<u32> {
+    trace!("send_credential_offer_for_proposal >>> handle: {}, cred_def_handle: {}, credential_data: {}, connection_handle: {}",
+           handle, cred_def_handle, secret!(&credential_data), connection_handle);
+    let issuer_config = _issuer_config(cred_def_handle)?;
+    ISSUER_CREDENTIAL_MAP.get_mut(handle, |credential| {
+        credential.send_credential_offer_for_proposal(&issuer_config, credential_data, connection::send_message_closure(connection_handle)?, comment.clone())?;
+        Ok(error::SUCCESS.code_num)
+    })
+}
+
+pub fn get_credential_proposal_messages(connection_handle: u32) -> VcxResult<String> {
+    trace!("issuer_credential::get_credential_proposal_messages >>> connection_handle: {}", connection_handle);
+
+    let credential_proposals: Vec<A2AMessage> = connection::get_messages(connection_handle)?
+        .into_iter()
+        .filter_map(|(_, a2a_message)| {
+            match a2a_message {
+                A2AMessage::CredentialProposal(_) => Some(a2a_message),
+                _ => None
+            }
+        })
+        .collect();
+
+    Ok(json!(credential_proposals).to_string())
+}
+
 pub fn generate_credential_msg(handle: u32, _my_pw_did: &str) -> VcxResult<String> {
     ISSUER_CREDENTIAL_MAP.get_mut(handle, |_| {
         Err(VcxError::from_msg(VcxErrorKind::ActionNotSupported, "Not implemented yet")) // TODO: implement
@@ -121,7 +281,17 @@ pub fn generate_credential_msg(handle: u32, _my_pw_did: &str) -> VcxResult<Strin
 
 pub fn send_credential(handle: u32, connection_handle: u32) -> VcxResult<u32> {
     ISSUER_CREDENTIAL_MAP.get_mut(handle, |credential| {
-        credential.send_credential(connection::send_message_closure(connection_handle)?)?;
+        let send_message = connection::send_message_closure(connection_handle)?;
+        _bind_rev_reg(credential, credential_def::get_active_rev_reg)?;
+        match credential.send_credential(&send_message) {
+            // AnoncredsRevocationRegistryFullError
+            Err(err) if err.kind() == VcxErrorKind::LibndyError(401) => {
+                warn!("issuer_credential::send_credential >>> revocation registry is full, issuing from a new one");
+                _bind_rev_reg(credential, credential_def::replace_full_rev_reg)?;
+                credential.send_credential(&send_message)?;
+            }
+            result => result?
+        }
         Ok(error::SUCCESS.code_num)
     })
 }
@@ -139,6 +309,26 @@ pub fn revoke_credential_local(handle: u32) -> VcxResult<()> {
     })
 }
 
+/**
+Revokes the credentials without publishing, revocations are published by `publish_pending_revocations`.
+ */
+pub fn revoke_credentials_local(handles: &[u32]) -> VcxResult<()> {
+    trace!("revoke_credentials_local >>> handles: {:?}", handles);
+    let revocations = handles.iter()
+        .map(|handle| ISSUER_CREDENTIAL_MAP.get(*handle, |credential| credential.get_credential_revocation()))
+        .collect::<VcxResult<Vec<CredentialRevocation>>>()?;
+    revocation_manager::queue_revocations(&ProfileContext::Global, &revocations)
+}
+
+pub fn get_pending_revocations() -> VcxResult<BTreeMap<String, usize>> {
+    revocation_manager::get_pending_revocations(&ProfileContext::Global)
+}
+
+pub fn publish_pending_revocations(rev_reg_ids: Option<Vec<String>>) -> VcxResult<PublishReport> {
+    trace!("publish_pending_revocations >>> rev_reg_ids: {:?}", rev_reg_ids);
+    revocation_manager::publish_pending_revocations(&ProfileContext::Global, rev_reg_ids.as_ref().map(Vec::as_slice))
+}
+
 pub fn convert_to_map(s: &str) -> VcxResult<serde_json::Map<String, serde_json::Value>> {
     serde_json::from_str(s)
         .map_err(|_| {
@@ -181,12 +371,13 @@ pub mod tests {
     use crate::api_lib::VcxStateType;
     use crate::libindy::utils::anoncreds::libindy_create_and_store_credential_def;
     use crate::libindy::utils::LibindyMock;
+    use crate::libindy::utils::wallet::get_wallet_handle;
     use crate::settings;
     use crate::utils::constants::{REV_REG_ID, SCHEMAS_JSON, V3_OBJECT_SERIALIZE_VERSION};
     #[allow(unused_imports)]
     use crate::utils::devsetup::*;
     use crate::utils::mockdata::mockdata_connection::ARIES_CONNECTION_ACK;
-    use crate::utils::mockdata::mockdata_credex::ARIES_CREDENTIAL_REQUEST;
+    use crate::utils::mockdata::mockdata_credex::{ARIES_CREDENTIAL_PROPOSAL, ARIES_CREDENTIAL_REQUEST};
 
     use super::*;
 
@@ -195,7 +386,7 @@ pub mod tests {
         let tag = "test_tag";
         let config = "{support_revocation: false}";
 
-        libindy_create_and_store_credential_def(&issuer_did, SCHEMAS_JSON, tag, None, config).unwrap();
+        libindy_create_and_store_credential_def(get_wallet_handle(), &issuer_did, SCHEMAS_JSON, tag, None, config).unwrap();
     }
 
     fn _issuer_credential_create() -> u32 {
@@ -239,6 +430,40 @@ pub mod tests {
         assert_eq!(get_state(handle_cred).unwrap(), VcxStateType::VcxStateOfferSent as u32);
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_send_credential_offer_with_protocol_version() {
+        let _setup = SetupMocks::init();
+
+        let handle_conn = build_test_connection_inviter_requested();
+
+        let handle_cred = _issuer_credential_create();
+        assert_eq!(VcxErrorKind::InvalidOption, set_protocol_version(handle_cred, "3.0").unwrap_err().kind());
+        set_protocol_version(handle_cred, "2.0").unwrap();
+
+        assert_eq!(send_credential_offer(handle_cred, handle_conn, None).unwrap(), error::SUCCESS.code_num);
+        assert_eq!(get_state(handle_cred).unwrap(), VcxStateType::VcxStateOfferSent as u32);
+        assert_eq!(VcxErrorKind::InvalidState, set_protocol_version(handle_cred, "1.0").unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_send_credential_offer_for_proposal() {
+        let _setup = SetupMocks::init();
+
+        let handle_conn = build_test_connection_inviter_requested();
+
+        let handle_cred = issuer_credential_create_with_proposal("1".to_string(), ARIES_CREDENTIAL_PROPOSAL).unwrap();
+        assert_eq!(get_state(handle_cred).unwrap(), VcxStateType::VcxStateInitialized as u32);
+
+        let proposal: CredentialProposal = serde_json::from_str(&get_credential_proposal(handle_cred).unwrap()).unwrap();
+        assert_eq!(2, proposal.credential_proposal.attributes.len());
+
+        assert_eq!(send_credential_offer_for_proposal(handle_cred, create_cred_def_fake(), "{\"degree\":\"maths\"}", handle_conn, None).unwrap(), error::SUCCESS.code_num);
+        assert_eq!(get_state(handle_cred).unwrap(), VcxStateType::VcxStateOfferSent as u32);
+        assert_eq!(VcxErrorKind::InvalidState, get_credential_proposal(handle_cred).unwrap_err().kind());
+    }
+
     #[cfg(feature = "pool_tests")]
     #[cfg(feature = "to_restore")]
     #[test]
@@ -404,5 +629,23 @@ pub mod tests {
         assert_eq!(revoc_result.unwrap_err().kind(), VcxErrorKind::InvalidRevocationDetails)
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_revoke_credentials_local_requires_issued_credentials() {
+        let _setup = SetupMocks::init();
+
+        let handle_conn = build_test_connection_inviter_requested();
+        let handle_cred = _issuer_credential_create();
+        assert_eq!(VcxErrorKind::NotReady, revoke_credentials_local(&[handle_cred]).unwrap_err().kind());
+
+        send_credential_offer(handle_cred, handle_conn, None).unwrap();
+        issuer_credential::update_state(handle_cred, Some(ARIES_CREDENTIAL_REQUEST), handle_conn).unwrap();
+        issuer_credential::send_credential(handle_cred, handle_conn).unwrap();
+        assert_eq!(VcxErrorKind::InvalidRevocationDetails, revoke_credentials_local(&[handle_cred]).unwrap_err().kind());
+
+        revoke_credentials_local(&[]).unwrap();
+        assert!(get_pending_revocations().unwrap().is_empty());
+    }
+
     // todo: Write test which will use use credetial definition supporting revocation, then actually revoke credential
 }
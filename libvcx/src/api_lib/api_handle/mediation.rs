@@ -0,0 +1,515 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+use serde_json;
+
+use crate::api_lib::api_handle::connection;
+use crate::api_lib::api_handle::events;
+use crate::api_lib::api_handle::object_cache::{ObjectCache, ObjectEvents, ObjectStorage, PersistentCache, StoredObject};
+use crate::aries::handlers::mediation::mediation_client::{MediationClient, MediationState};
+use crate::aries::handlers::mediation::message_pickup;
+use crate::aries::handlers::mediation::message_pickup::MessagePickup;
+use crate::aries::messages::a2a::A2AMessage;
+use crate::aries::messages::a2a::protocol_version::ProtocolVersion;
+use crate::error::prelude::*;
+use crate::profile::ProfileContext;
+use crate::utils::error;
+
+lazy_static! {
+    static ref MEDIATION_MAP: ObjectCache<MediationClient> = ObjectCache::<MediationClient>::new_persistent("mediation-cache", ObjectStorage {
+        record_type: "vcx_mediation",
+        serialize: _serialize,
+        deserialize: |_, data| _deserialize(data),
+        tags: _storage_tags,
+    }).with_events(ObjectEvents {
+        object_type: "mediation",
+        source_id: MediationClient::get_source_id,
+        thread_id: |_| None,
+        state: MediationClient::state,
+    });
+    // mediation routing connections of the profile (`None` for the global context), the last granted one wins
+    static ref ROUTING_MEDIATIONS: RwLock<HashMap<Option<u32>, RoutingMediation>> = Default::default();
+}
+
+type MediatorSender = Arc<dyn Fn(&A2AMessage) -> VcxResult<()> + Send + Sync>;
+
+#[derive(Clone)]
+struct RoutingMediation {
+    handle: u32,
+    // sends messages over the connection the mediation has been granted on, `None` for deserialized mediation
+    // until its state is updated with the connection
+    send_message: Option<MediatorSender>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "version", content = "data")]
+enum Mediations {
+    #[serde(rename = "1.0")]
+    V1(MediationClient),
+}
+
+fn _serialize(mediation: &MediationClient) -> VcxResult<String> {
+    serde_json::to_string(&Mediations::V1(mediation.clone()))
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Cannot serialize Mediation object: {:?}", err)))
+}
+
+fn _deserialize(mediation_data: &str) -> VcxResult<MediationClient> {
+    let mediation: Mediations = serde_json::from_str(mediation_data)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize Mediation object: {:?}", err)))?;
+    match mediation {
+        Mediations::V1(mediation) => Ok(mediation)
+    }
+}
+
+fn _storage_tags(mediation: &MediationClient) -> HashMap<String, String> {
+    let mut tags = HashMap::new();
+    tags.insert("source_id".to_string(), mediation.get_source_id());
+    tags.insert("state".to_string(), mediation.state().to_string());
+    tags
+}
+
+/**
+Mediations loaded from the wallet route messages of their profile once granted, as deserialized mediations do.
+ */
+struct MediationCache;
+
+impl PersistentCache for MediationCache {
+    fn load(&self, profile_handle: Option<u32>, record_id: &str) -> VcxResult<u32> {
+        let handle = MEDIATION_MAP.load(profile_handle, record_id)?;
+        _restore_routing(handle)?;
+        Ok(handle)
+    }
+
+    fn search(&self, profile_handle: Option<u32>, query: &str) -> VcxResult<Vec<StoredObject>> {
+        let objects = MEDIATION_MAP.search(profile_handle, query)?;
+        for object in objects.iter() {
+            _restore_routing(object.handle)?;
+        }
+        Ok(objects)
+    }
+
+    fn get_record_id(&self, handle: u32) -> VcxResult<Option<String>> {
+        MEDIATION_MAP.get_record_id(handle).map_err(handle_err)
+    }
+
+    fn persist(&self, handle: u32) -> VcxResult<()> {
+        MEDIATION_MAP.persist(handle).map_err(handle_err)
+    }
+
+    fn delete_record(&self, profile_handle: Option<u32>, record_id: &str) -> VcxResult<()> {
+        MEDIATION_MAP.delete_record(profile_handle, record_id)
+    }
+}
+
+pub fn persistent_cache() -> &'static dyn PersistentCache {
+    &MediationCache
+}
+
+fn handle_err(err: VcxError) -> VcxError {
+    if err.kind() == VcxErrorKind::InvalidHandle {
+        VcxError::from(VcxErrorKind::InvalidMediationHandle)
+    } else {
+        err
+    }
+}
+
+pub fn is_valid_handle(handle: u32) -> bool {
+    MEDIATION_MAP.has_handle(handle)
+}
+
+pub fn get_profile_handle(handle: u32) -> Option<u32> {
+    MEDIATION_MAP.get_profile_handle(handle)
+}
+
+pub fn create(source_id: &str) -> VcxResult<u32> {
+    create_in_profile(None, source_id)
+}
+
+pub fn create_in_profile(profile_handle: Option<u32>, source_id: &str) -> VcxResult<u32> {
+    trace!("mediation::create_in_profile >>> profile_handle: {:?}, source_id: {}", profile_handle, source_id);
+    ProfileContext::from_handle(profile_handle)?;
+    MEDIATION_MAP.add_in_profile(profile_handle, MediationClient::create(source_id))
+}
+
+pub fn get_source_id(handle: u32) -> VcxResult<String> {
+    MEDIATION_MAP.get(handle, |mediation| {
+        Ok(mediation.get_source_id())
+    }).map_err(handle_err)
+}
+
+pub fn get_state(handle: u32) -> VcxResult<u32> {
+    MEDIATION_MAP.get(handle, |mediation| {
+        Ok(mediation.state())
+    }).map_err(handle_err)
+}
+
+pub fn send_request(handle: u32, connection_handle: u32) -> VcxResult<u32> {
+    MEDIATION_MAP.get_mut(handle, |mediation| {
+        mediation.send_request(connection::send_message_closure(connection_handle)?)?;
+        Ok(error::SUCCESS.code_num)
+    }).map_err(handle_err)
+}
+
+fn _mediator_sender(connection_handle: u32) -> VcxResult<MediatorSender> {
+    let send_message = connection::send_message_closure(connection_handle)?;
+    Ok(Arc::new(send_message))
+}
+
+fn _route_through(handle: u32, send_message: Option<MediatorSender>) -> VcxResult<()> {
+    ROUTING_MEDIATIONS.write()?.insert(get_profile_handle(handle), RoutingMediation { handle, send_message });
+    Ok(())
+}
+
+/**
+Routes through the granted mediation restored without its connection, unless it is routing already.
+ */
+fn _restore_routing(handle: u32) -> VcxResult<()> {
+    if get_state(handle)? != MediationState::Granted as u32 {
+        return Ok(());
+    }
+    let routing = ROUTING_MEDIATIONS.read()?.get(&get_profile_handle(handle)).map(|routing| routing.handle);
+    if routing != Some(handle) {
+        _route_through(handle, None)?;
+    }
+    Ok(())
+}
+
+/**
+Sets the connection with the mediator of the routing mediation which has been deserialized without it.
+ */
+fn _connect_routing(handle: u32, connection_handle: u32) -> VcxResult<()> {
+    let mut mediations = ROUTING_MEDIATIONS.write()?;
+    if let Some(routing) = mediations.get_mut(&get_profile_handle(handle)) {
+        if routing.handle == handle && routing.send_message.is_none() {
+            routing.send_message = Some(_mediator_sender(connection_handle)?);
+        }
+    }
+    Ok(())
+}
+
+fn _routing_mediation(profile: &ProfileContext) -> VcxResult<Option<RoutingMediation>> {
+    Ok(ROUTING_MEDIATIONS.read()?.get(&profile.handle()).cloned())
+}
+
+/**
+Endpoint and routing keys granted to the profile, DidDocs and invitations of new connections
+use them instead of the endpoint and keys of the cloud agency.
+ */
+pub fn get_mediator_routing(profile: &ProfileContext) -> VcxResult<Option<(String, Vec<String>)>> {
+    let handle = match _routing_mediation(profile)? {
+        Some(routing) => routing.handle,
+        None => return Ok(None)
+    };
+    MEDIATION_MAP.get(handle, |mediation| {
+        Ok(Some((mediation.endpoint()?, mediation.routing_keys()?)))
+    }).map_err(handle_err)
+}
+
+fn _update_routing_keylist(profile: &ProfileContext, add: Vec<String>, remove: Vec<String>) -> VcxResult<bool> {
+    let routing = match _routing_mediation(profile)? {
+        Some(routing) => routing,
+        None => return Ok(false)
+    };
+    let send_message = routing.send_message
+        .ok_or(VcxError::from_msg(VcxErrorKind::NotReady, "Connection with the mediator is not known, \
+        update state of the deserialized mediation with the connection first"))?;
+    MEDIATION_MAP.get_mut(routing.handle, |mediation| {
+        mediation.update_keylist(add.clone(), remove.clone(), |message| send_message(message))
+    }).map_err(handle_err)?;
+    Ok(true)
+}
+
+/**
+Registers the pairwise verkey of a new connection with the mediator routing connections of the profile.
+Returns `false` if connections of the profile are not routed through a mediator.
+ */
+pub fn register_connection_key(profile: &ProfileContext, recipient_key: &str) -> VcxResult<bool> {
+    trace!("mediation::register_connection_key >>> profile: {:?}, recipient_key: {}", profile, recipient_key);
+    _update_routing_keylist(profile, vec![recipient_key.to_string()], vec![])
+}
+
+/**
+Removes the pairwise verkey of a deleted connection from the mediator routing connections of the profile.
+ */
+pub fn unregister_connection_key(profile: &ProfileContext, recipient_key: &str) -> VcxResult<bool> {
+    trace!("mediation::unregister_connection_key >>> profile: {:?}, recipient_key: {}", profile, recipient_key);
+    _update_routing_keylist(profile, vec![], vec![recipient_key.to_string()])
+}
+
+pub fn update_state(handle: u32, message: Option<&str>, connection_handle: u32) -> VcxResult<u32> {
+    let was_granted = get_state(handle)? == MediationState::Granted as u32;
+    let state = MEDIATION_MAP.get_mut(handle, |mediation| {
+        trace!("mediation::update_state >>> connection_handle: {:?}, message: {:?}", connection_handle, message);
+        if !mediation.has_transitions() {
+            return Ok(mediation.state());
+        }
+
+        if let Some(message) = message {
+            let message: A2AMessage = serde_json::from_str(message)
+                .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidOption, format!("Can not updated state with message: Message deserialization failed: {:?}", err)))?;
+            MEDIATION_MAP.notify_message(handle, mediation, &message, mediation.find_message_to_handle(events::as_messages(&message)).is_some());
+            mediation.handle_message(message)?;
+        } else {
+            let messages = connection::get_messages(connection_handle)?;
+            if let Some((uid, message)) = mediation.find_message_to_handle(messages) {
+                MEDIATION_MAP.notify_message(handle, mediation, &message, true);
+                mediation.handle_message(message)?;
+                connection::update_message_status(connection_handle, uid)?;
+            }
+        }
+        Ok(mediation.state())
+    }).map_err(handle_err)?;
+    if !was_granted && state == MediationState::Granted as u32 {
+        _route_through(handle, Some(_mediator_sender(connection_handle)?))?;
+    } else if state == MediationState::Granted as u32 {
+        _connect_routing(handle, connection_handle)?;
+    }
+    Ok(state)
+}
+
+pub fn update_keylist(handle: u32, connection_handle: u32, add: Vec<String>, remove: Vec<String>) -> VcxResult<u32> {
+    MEDIATION_MAP.get_mut(handle, |mediation| {
+        mediation.update_keylist(add.clone(), remove.clone(), connection::send_message_closure(connection_handle)?)?;
+        Ok(error::SUCCESS.code_num)
+    }).map_err(handle_err)
+}
+
+/**
+Registers the pairwise verkey of the mediated connection with the mediator, so that the mediator
+accepts messages forwarded to the connection. Keys of connections created after the mediation has been granted
+are registered automatically, this is needed only for connections created before.
+ */
+pub fn add_connection_key(handle: u32, connection_handle: u32, mediated_connection_handle: u32) -> VcxResult<u32> {
+    let recipient_key = connection::get_pw_verkey(mediated_connection_handle)?;
+    update_keylist(handle, connection_handle, vec![recipient_key], vec![])
+}
+
+pub fn query_keylist(handle: u32, connection_handle: u32) -> VcxResult<u32> {
+    MEDIATION_MAP.get_mut(handle, |mediation| {
+        mediation.query_keylist(connection::send_message_closure(connection_handle)?)?;
+        Ok(error::SUCCESS.code_num)
+    }).map_err(handle_err)
+}
+
+pub fn get_routing_info(handle: u32) -> VcxResult<String> {
+    MEDIATION_MAP.get(handle, |mediation| {
+        Ok(json!({
+            "endpoint": mediation.endpoint()?,
+            "routing_keys": mediation.routing_keys()?,
+            "recipient_keys": mediation.recipient_keys()?,
+        }).to_string())
+    }).map_err(handle_err)
+}
+
+/**
+Makes connections of the profile of the connection with the mediator pick up their messages from the mediator
+(message pickup protocol of given version, "1.0" or "2.0") instead of downloading them from the cloud agency.
+ */
+pub fn enable_message_pickup(connection_handle: u32, version: &str) -> VcxResult<()> {
+    trace!("mediation::enable_message_pickup >>> connection_handle: {}, version: {}", connection_handle, version);
+    let version = ProtocolVersion::from_str(version)?;
+    let pickup = connection::create_message_pickup(connection_handle, version)?;
+    let profile = pickup.profile().clone();
+    message_pickup::set_pickup_mediator(&profile, Some(pickup))
+}
+
+/**
+Makes connections of the global context download their messages from the cloud agency again.
+ */
+pub fn disable_message_pickup() -> VcxResult<()> {
+    disable_message_pickup_in_profile(None)
+}
+
+pub fn disable_message_pickup_in_profile(profile_handle: Option<u32>) -> VcxResult<()> {
+    trace!("mediation::disable_message_pickup_in_profile >>> profile_handle: {:?}", profile_handle);
+    message_pickup::set_pickup_mediator(&ProfileContext::from_handle(profile_handle)?, None)
+}
+
+fn _pickup_mediator(profile: &ProfileContext) -> VcxResult<MessagePickup> {
+    message_pickup::get_pickup_mediator(profile)?
+        .ok_or(VcxError::from_msg(VcxErrorKind::NotReady, "Message pickup is not enabled"))
+}
+
+pub fn get_pickup_status() -> VcxResult<String> {
+    get_pickup_status_in_profile(None)
+}
+
+pub fn get_pickup_status_in_profile(profile_handle: Option<u32>) -> VcxResult<String> {
+    let pickup = _pickup_mediator(&ProfileContext::from_handle(profile_handle)?)?;
+    let status = pickup.get_status(pickup.exchange_closure())?;
+    serde_json::to_string(&status)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Cannot serialize pickup status: {:?}", err)))
+}
+
+pub fn set_live_delivery(live_delivery: bool) -> VcxResult<()> {
+    set_live_delivery_in_profile(None, live_delivery)
+}
+
+pub fn set_live_delivery_in_profile(profile_handle: Option<u32>, live_delivery: bool) -> VcxResult<()> {
+    let profile = ProfileContext::from_handle(profile_handle)?;
+    let mut pickup = _pickup_mediator(&profile)?;
+    pickup.set_live_delivery(live_delivery, pickup.exchange_closure())?;
+    message_pickup::set_pickup_mediator(&profile, Some(pickup))
+}
+
+/**
+Picks up messages held by the mediator for connections of the global context, messages addressed to none
+of them are discarded.
+ */
+pub fn pickup_messages() -> VcxResult<u32> {
+    pickup_messages_in_profile(None)
+}
+
+pub fn pickup_messages_in_profile(profile_handle: Option<u32>) -> VcxResult<u32> {
+    let profile = ProfileContext::from_handle(profile_handle)?;
+    _pickup_mediator(&profile)?;
+    let picked_up = message_pickup::pickup_messages(&profile)?;
+    connection::discard_undeliverable_messages(&profile)?;
+    Ok(picked_up as u32)
+}
+
+pub fn to_string(handle: u32) -> VcxResult<String> {
+    MEDIATION_MAP.get(handle, _serialize).map_err(handle_err)
+}
+
+pub fn from_string(mediation_data: &str) -> VcxResult<u32> {
+    from_string_in_profile(None, mediation_data)
+}
+
+pub fn from_string_in_profile(profile_handle: Option<u32>, mediation_data: &str) -> VcxResult<u32> {
+    ProfileContext::from_handle(profile_handle)?;
+    let handle = MEDIATION_MAP.add_in_profile(profile_handle, _deserialize(mediation_data)?)?;
+    _restore_routing(handle)?;
+    Ok(handle)
+}
+
+pub fn release(handle: u32) -> VcxResult<()> {
+    ROUTING_MEDIATIONS.write()?.retain(|_, routing| routing.handle != handle);
+    MEDIATION_MAP.release(handle).map_err(handle_err)
+}
+
+pub fn release_all() {
+    ROUTING_MEDIATIONS.write().map(|mut mediations| mediations.clear()).ok();
+    MEDIATION_MAP.drain().ok();
+}
+
+#[cfg(test)]
+pub mod tests {
+    use agency_client::mocking::{AgencyMockDecrypted, HttpClientMockResponse};
+
+    use crate::api_lib::api_handle::connection::tests::{build_test_connection_invitee_completed, with_unique_pw_verkey};
+    use crate::aries::messages::attachment::AttachmentData;
+    use crate::aries::messages::basic_message::message::BasicMessage;
+    use crate::aries::messages::pickup::delivery::{DeliveredMessage, Delivery};
+    use crate::aries::utils::encryption_envelope::tests::_packed_message;
+    use crate::aries::messages::a2a::MessageId;
+    use crate::aries::messages::mediation::mediate_grant::MediateGrant;
+    use crate::aries::messages::mediation::mediate_grant::tests::{_endpoint, _routing_keys};
+    use crate::profile::tests::_create_profile;
+    use crate::utils::devsetup::SetupMocks;
+
+    use super::*;
+
+    pub fn _grant_message() -> String {
+        json!(MediateGrant::create()
+            .set_endpoint(_endpoint())
+            .set_routing_keys(_routing_keys())
+            .set_thread_id(&MessageId::id().0)
+            .to_a2a_message()).to_string()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_mediation_grant_is_used_by_new_connections() {
+        let _setup = SetupMocks::init();
+
+        let profile_handle = _create_profile();
+        let connection_handle = build_test_connection_invitee_completed();
+        let profile = ProfileContext::from_handle(Some(profile_handle)).unwrap();
+        let handle = create_in_profile(Some(profile_handle), "test_mediation_grant_is_used_by_new_connections").unwrap();
+        assert_eq!(MediationState::Initial as u32, get_state(handle).unwrap());
+        assert_eq!(VcxErrorKind::NotReady, get_routing_info(handle).unwrap_err().kind());
+
+        send_request(handle, connection_handle).unwrap();
+        assert_eq!(MediationState::Requested as u32, get_state(handle).unwrap());
+
+        let state = update_state(handle, Some(&_grant_message()), connection_handle).unwrap();
+        assert_eq!(MediationState::Granted as u32, state);
+
+        let routing_info: serde_json::Value = serde_json::from_str(&get_routing_info(handle).unwrap()).unwrap();
+        assert_eq!(_endpoint(), routing_info["endpoint"]);
+        assert_eq!(json!(_routing_keys()), routing_info["routing_keys"]);
+
+        let new_connection = connection::create_connection_in_profile(Some(profile_handle), "new_connection").unwrap();
+        connection::connect(new_connection).unwrap();
+        let invitation: serde_json::Value = serde_json::from_str(&connection::get_invite_details(new_connection).unwrap()).unwrap();
+        assert_eq!(_endpoint(), invitation["serviceEndpoint"]);
+        assert_eq!(json!(_routing_keys()), invitation["routingKeys"]);
+
+        assert!(MEDIATION_MAP.get(handle, |mediation| Ok(mediation.has_transitions())).unwrap());
+
+        add_connection_key(handle, connection_handle, new_connection).unwrap();
+
+        let serialized = to_string(handle).unwrap();
+        release(handle).unwrap();
+        assert_eq!(None, get_mediator_routing(&profile).unwrap());
+
+        let handle = from_string_in_profile(Some(profile_handle), &serialized).unwrap();
+        assert_eq!(Some((_endpoint(), _routing_keys())), get_mediator_routing(&profile).unwrap());
+        assert_eq!(None, get_mediator_routing(&ProfileContext::Global).unwrap());
+        assert_eq!(VcxErrorKind::NotReady, connection::create_connection_in_profile(Some(profile_handle), "new_connection").unwrap_err().kind());
+
+        update_state(handle, Some(&_grant_message()), connection_handle).unwrap();
+        connection::create_connection_in_profile(Some(profile_handle), "new_connection").unwrap();
+        release(handle).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_mediation_serialization() {
+        let _setup = SetupMocks::init();
+
+        let handle = create("test_mediation_serialization").unwrap();
+        let serialized = to_string(handle).unwrap();
+        release(handle).unwrap();
+        assert_eq!(VcxErrorKind::InvalidMediationHandle, get_state(handle).unwrap_err().kind());
+
+        let handle = from_string(&serialized).unwrap();
+        assert_eq!("test_mediation_serialization", get_source_id(handle).unwrap());
+        assert_eq!(serialized, to_string(handle).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_connection_gets_messages_picked_up_from_mediator() {
+        let _setup = SetupMocks::init();
+
+        let mediator_handle = build_test_connection_invitee_completed();
+        let connection_handle = with_unique_pw_verkey(build_test_connection_invitee_completed());
+        assert_eq!(VcxErrorKind::NotReady, pickup_messages().unwrap_err().kind());
+        assert_eq!(VcxErrorKind::InvalidOption, enable_message_pickup(mediator_handle, "3.0").unwrap_err().kind());
+        enable_message_pickup(mediator_handle, "2.0").unwrap();
+
+        let pw_vk = connection::get_pw_verkey(connection_handle).unwrap();
+        let delivery = Delivery::create()
+            .set_attachments(vec![DeliveredMessage {
+                id: String::from("picked_up_id"),
+                data: AttachmentData::Base64(base64::encode(&_packed_message(vec![&pw_vk]))),
+            }]);
+        let basic_message = BasicMessage::create().set_content(String::from("picked up")).to_a2a_message();
+
+        // mocked messages are taken in reverse order: delivery is the reply of the mediator, basic message is the picked up one
+        AgencyMockDecrypted::set_next_decrypted_message(&json!(basic_message).to_string());
+        AgencyMockDecrypted::set_next_decrypted_message(&json!(delivery.to_a2a_message()).to_string());
+        HttpClientMockResponse::set_next_response(Ok(b"packed delivery".to_vec()));
+
+        let messages = connection::get_messages(connection_handle);
+        disable_message_pickup().unwrap();
+        let messages = messages.unwrap();
+        assert_eq!(Some(&basic_message), messages.get("picked_up_id"));
+
+        connection::update_message_status(connection_handle, String::from("picked_up_id")).unwrap();
+        assert!(message_pickup::get_inbox_messages(&ProfileContext::Global, &pw_vk).unwrap().is_empty());
+    }
+}
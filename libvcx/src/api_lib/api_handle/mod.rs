@@ -1,8 +1,14 @@
 pub mod connection;
 pub mod credential;
 pub mod credential_def;
+pub mod dispatcher;
+pub mod events;
+pub mod expiry;
 pub mod disclosed_proof;
 pub mod issuer_credential;
+pub mod mediation;
+pub mod multi_use_invitation;
+pub mod object_storage;
 pub mod proof;
 pub mod schema;
 pub mod object_cache;
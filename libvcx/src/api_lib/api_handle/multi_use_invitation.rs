@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+
+use serde_json;
+
+use crate::api_lib::api_handle::connection;
+use crate::api_lib::api_handle::object_cache::{ObjectCache, ObjectStorage, PersistentCache};
+use crate::aries::handlers::connection::multi_use_invitation::MultiUseInvitation;
+use crate::aries::messages::a2a::A2AMessage;
+use crate::aries::messages::connection::handshake_protocol::HandshakeProtocol;
+use crate::aries::utils::encryption_envelope::EncryptionEnvelope;
+use crate::error::prelude::*;
+use crate::profile::ProfileContext;
+use crate::utils::error;
+
+lazy_static! {
+    static ref INVITATION_MAP: ObjectCache<MultiUseInvitation> = ObjectCache::<MultiUseInvitation>::new_persistent("multi-use-invitations-cache", ObjectStorage {
+        record_type: "vcx_multi_use_invitation",
+        serialize: _serialize,
+        deserialize: _deserialize,
+        tags: _storage_tags,
+    });
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "version", content = "data")]
+enum MultiUseInvitations {
+    #[serde(rename = "1.0")]
+    V1(MultiUseInvitation),
+}
+
+fn _serialize(invitation: &MultiUseInvitation) -> VcxResult<String> {
+    serde_json::to_string(&MultiUseInvitations::V1(invitation.clone()))
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Cannot serialize MultiUseInvitation: {:?}", err)))
+}
+
+fn _deserialize(profile: &ProfileContext, invitation_data: &str) -> VcxResult<MultiUseInvitation> {
+    let invitation: MultiUseInvitations = serde_json::from_str(invitation_data)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize MultiUseInvitation: {:?}", err)))?;
+    match invitation {
+        MultiUseInvitations::V1(mut invitation) => {
+            invitation.set_profile(profile.clone());
+            Ok(invitation)
+        }
+    }
+}
+
+fn _storage_tags(invitation: &MultiUseInvitation) -> HashMap<String, String> {
+    let mut tags = HashMap::new();
+    tags.insert("source_id".to_string(), invitation.get_source_id());
+    tags.insert("recipient_key".to_string(), invitation.recipient_key().to_string());
+    tags.insert("revoked".to_string(), invitation.is_revoked().to_string());
+    tags
+}
+
+pub fn persistent_cache() -> &'static dyn PersistentCache {
+    &*INVITATION_MAP
+}
+
+fn handle_err(err: VcxError) -> VcxError {
+    if err.kind() == VcxErrorKind::InvalidHandle {
+        VcxError::from(VcxErrorKind::InvalidConnectionHandle)
+    } else {
+        err
+    }
+}
+
+pub fn is_valid_handle(handle: u32) -> bool {
+    INVITATION_MAP.has_handle(handle)
+}
+
+pub fn create(source_id: &str, protocol: Option<&str>) -> VcxResult<u32> {
+    create_in_profile(None, source_id, protocol)
+}
+
+pub fn create_in_profile(profile_handle: Option<u32>, source_id: &str, protocol: Option<&str>) -> VcxResult<u32> {
+    trace!("multi_use_invitation::create_in_profile >>> profile_handle: {:?}, source_id: {}, protocol: {:?}", profile_handle, source_id, protocol);
+    let profile = ProfileContext::from_handle(profile_handle)?;
+    let protocol = match protocol {
+        Some(protocol) => serde_json::from_value(json!(protocol))
+            .map_err(|_| VcxError::from_msg(VcxErrorKind::InvalidOption, format!("Unsupported handshake protocol: {}", protocol)))?,
+        None => HandshakeProtocol::default()
+    };
+    INVITATION_MAP.add_in_profile(profile_handle, MultiUseInvitation::create(profile, source_id, protocol)?)
+}
+
+pub fn create_public(source_id: &str) -> VcxResult<u32> {
+    create_public_in_profile(None, source_id)
+}
+
+pub fn create_public_in_profile(profile_handle: Option<u32>, source_id: &str) -> VcxResult<u32> {
+    trace!("multi_use_invitation::create_public_in_profile >>> profile_handle: {:?}, source_id: {}", profile_handle, source_id);
+    let profile = ProfileContext::from_handle(profile_handle)?;
+    INVITATION_MAP.add_in_profile(profile_handle, MultiUseInvitation::create_public(profile, source_id)?)
+}
+
+/**
+Publishes endpoint of the public invitation to the ledger, returns transaction for the `endorser` if it is set.
+ */
+pub fn publish_endpoint(handle: u32, endorser: Option<&str>) -> VcxResult<Option<String>> {
+    INVITATION_MAP.get(handle, |invitation| {
+        invitation.publish_endpoint(endorser)
+    }).map_err(handle_err)
+}
+
+pub fn get_source_id(handle: u32) -> VcxResult<String> {
+    INVITATION_MAP.get(handle, |invitation| {
+        Ok(invitation.get_source_id())
+    }).map_err(handle_err)
+}
+
+pub fn get_invite_details(handle: u32) -> VcxResult<String> {
+    INVITATION_MAP.get(handle, |invitation| {
+        Ok(json!(invitation.get_invitation().to_a2a_message()).to_string())
+    }).map_err(handle_err)
+}
+
+/**
+Spawns connection for the request received on the invitation, returns handle of the connection. The connection
+is kept even if the invitation fails to be persisted, its record is written again on its next change.
+ */
+pub fn handle_request(handle: u32, request: &A2AMessage) -> VcxResult<u32> {
+    let (connection, not_persisted) = INVITATION_MAP.get_mut_with_persist_error(handle, |invitation| {
+        invitation.handle_request(request)
+    }).map_err(handle_err)?;
+    if let Some(err) = not_persisted {
+        warn!("multi_use_invitation::handle_request >>> connection spawned but invitation {} not persisted: {}", handle, err);
+    }
+    connection::store_connection(connection)
+}
+
+/**
+Result of accepting a single request received on the invitation, `connection_handle` is set if a connection
+has been spawned, `error` is set if the request could not be handled or marked as reviewed, or if the invitation
+remembering the handled requests could not be persisted afterwards.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AcceptedRequest {
+    pub uid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connection_handle: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/**
+Spawns connections for all requests received on the invitation through the agency. Failure of a single request
+does not affect the others, connections spawned before it are kept.
+ */
+pub fn accept_requests(handle: u32) -> VcxResult<Vec<AcceptedRequest>> {
+    let (requests, not_persisted) = INVITATION_MAP.get_mut_with_persist_error(handle, |invitation| {
+        invitation.accept_requests()
+    }).map_err(handle_err)?;
+    Ok(requests.into_iter()
+        .map(|request| {
+            let (connection_handle, error) = match request.connection.map(connection::store_connection) {
+                Some(Ok(connection_handle)) => (Some(connection_handle), request.error),
+                Some(Err(err)) => (None, Some(err.to_string())),
+                None => (None, request.error)
+            };
+            let error = error.or_else(|| not_persisted.as_ref().map(|err| err.to_string()));
+            AcceptedRequest { uid: request.uid, connection_handle, error }
+        })
+        .collect())
+}
+
+/**
+Handles request packed for recipient key of one of the invitations, see `connection::process_packed_message`.
+ */
+pub fn process_packed_request(recipient_verkeys: &[String], payload: Vec<u8>) -> VcxResult<Option<(u32, A2AMessage)>> {
+    let handle = match INVITATION_MAP.find_handle(|invitation| recipient_verkeys.iter().any(|key| key == invitation.recipient_key()))? {
+        Some(handle) => handle,
+        None => return Ok(None)
+    };
+    let message = INVITATION_MAP.get(handle, |invitation| {
+        EncryptionEnvelope::anon_unpack(invitation.profile().wallet_handle()?, payload.clone())
+    })?;
+    let connection_handle = handle_request(handle, &message)?;
+    Ok(Some((connection_handle, message)))
+}
+
+pub fn revoke(handle: u32) -> VcxResult<u32> {
+    INVITATION_MAP.get_mut(handle, |invitation| {
+        invitation.revoke();
+        Ok(error::SUCCESS.code_num)
+    }).map_err(handle_err)
+}
+
+/**
+Lists invitations created within the profile, `None` lists invitations created outside of profiles.
+ */
+pub fn list(profile_handle: Option<u32>) -> VcxResult<String> {
+    let mut invitations = Vec::new();
+    for handle in INVITATION_MAP.handles()? {
+        if INVITATION_MAP.get_profile_handle(handle) != profile_handle {
+            continue;
+        }
+        let info = INVITATION_MAP.get(handle, |invitation| {
+            Ok(json!({
+                "handle": handle,
+                "source_id": invitation.get_source_id(),
+                "invitation": invitation.get_invitation().to_a2a_message(),
+                "connections": invitation.connections_count(),
+                "revoked": invitation.is_revoked(),
+            }))
+        });
+        // invitation might have been released in the meantime
+        if let Ok(info) = info {
+            invitations.push(info);
+        }
+    }
+    Ok(json!(invitations).to_string())
+}
+
+pub fn to_string(handle: u32) -> VcxResult<String> {
+    INVITATION_MAP.get(handle, _serialize).map_err(handle_err)
+}
+
+pub fn from_string(invitation_data: &str) -> VcxResult<u32> {
+    from_string_in_profile(None, invitation_data)
+}
+
+pub fn from_string_in_profile(profile_handle: Option<u32>, invitation_data: &str) -> VcxResult<u32> {
+    let profile = ProfileContext::from_handle(profile_handle)?;
+    INVITATION_MAP.add_in_profile(profile_handle, _deserialize(&profile, invitation_data)?)
+}
+
+pub fn release(handle: u32) -> VcxResult<()> {
+    INVITATION_MAP.release(handle).map_err(handle_err)
+}
+
+pub fn release_all() {
+    INVITATION_MAP.drain().ok();
+}
+
+#[cfg(test)]
+pub mod tests {
+    use serde_json::Value;
+
+    use crate::api_lib::VcxStateType;
+    use crate::api_lib::api_handle::object_storage;
+    use crate::aries::handlers::connection::multi_use_invitation::tests::_request;
+    use crate::settings;
+    use crate::utils::devsetup::SetupMocks;
+    use crate::utils::mockdata::mock_settings::MockBuilder;
+
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_multi_use_invitation_spawns_connections() {
+        let _setup = SetupMocks::init();
+
+        let handle = create("test_multi_use_invitation_spawns_connections", None).unwrap();
+        let details: Value = serde_json::from_str(&get_invite_details(handle).unwrap()).unwrap();
+        assert!(details["recipientKeys"].is_array());
+
+        let connection_1 = handle_request(handle, &_request()).unwrap();
+        let connection_2 = handle_request(handle, &_request()).unwrap();
+        assert_ne!(connection_1, connection_2);
+        assert_eq!(VcxStateType::VcxStateRequestReceived as u32, connection::get_state(connection_1));
+        assert_eq!(VcxStateType::VcxStateRequestReceived as u32, connection::get_state(connection_2));
+
+        let invitations: Value = serde_json::from_str(&list(None).unwrap()).unwrap();
+        let info = invitations.as_array().unwrap().iter().find(|info| info["handle"] == handle).unwrap();
+        assert_eq!(2, info["connections"]);
+        assert_eq!(false, info["revoked"]);
+
+        revoke(handle).unwrap();
+        assert_eq!(VcxErrorKind::InvalidState, handle_request(handle, &_request()).unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_multi_use_invitation_create_fails_for_unknown_protocol() {
+        let _setup = SetupMocks::init();
+
+        assert_eq!(VcxErrorKind::InvalidOption, create("test", Some("didexchange/2.0")).unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_multi_use_invitation_serialization() {
+        let _setup = SetupMocks::init();
+
+        let handle = create("test_multi_use_invitation_serialization", Some("didexchange/1.0")).unwrap();
+        let serialized = to_string(handle).unwrap();
+        release(handle).unwrap();
+        assert_eq!(VcxErrorKind::InvalidConnectionHandle, get_source_id(handle).unwrap_err().kind());
+
+        let handle = from_string(&serialized).unwrap();
+        assert_eq!(serialized, to_string(handle).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_persisted_invitation_keeps_handled_requests() {
+        let _setup = SetupMocks::init();
+        settings::set_config_value(settings::CONFIG_PERSIST_OBJECTS, "true");
+
+        let handle = create("test_persisted_invitation_keeps_handled_requests", None).unwrap();
+        let record_id = object_storage::get_record_id("multi_use_invitation", handle).unwrap();
+
+        let mut serialized: Value = serde_json::from_str(&to_string(handle).unwrap()).unwrap();
+        serialized["data"]["handled_request_uids"] = json!(["uid_1"]);
+        let record = json!({
+            "id": record_id,
+            "type": null,
+            "value": serialized.to_string(),
+            "tags": json!({"handle": handle.to_string()}).to_string()
+        }).to_string();
+        let _mock_builder = MockBuilder::init().set_mock_wallet_record("vcx_multi_use_invitation", &record_id, &record);
+
+        release_all();
+        assert_eq!(handle, object_storage::load("multi_use_invitation", &record_id).unwrap());
+        let loaded: Value = serde_json::from_str(&to_string(handle).unwrap()).unwrap();
+        assert_eq!(json!(["uid_1"]), loaded["data"]["handled_request_uids"]);
+    }
+}
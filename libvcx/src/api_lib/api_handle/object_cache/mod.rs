@@ -1,22 +1,327 @@
 use std::collections::HashMap;
+use std::hash::Hash;
 use std::ops::Deref;
 use std::ops::DerefMut;
-use std::sync::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::sync::{Arc, Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
+use indy::{SearchHandle, WalletHandle};
 use rand::Rng;
+use serde_json;
 
+use crate::api_lib::api_handle::events::{self, Event, EventKind};
+use crate::aries::messages::a2a::A2AMessage;
 use crate::error::prelude::*;
+use crate::libindy::utils::wallet;
+use crate::profile::ProfileContext;
+use crate::settings;
+
+const SEARCH_BATCH_SIZE: usize = 20;
+// every record is tagged by the handle of its object so the object is loaded under the same handle
+const HANDLE_TAG: &str = "handle";
+
+/**
+Describes how objects of a cache are stored in the wallet when `persist_objects` is enabled.
+ */
+pub struct ObjectStorage<T> {
+    pub record_type: &'static str,
+    pub serialize: fn(&T) -> VcxResult<String>,
+    // objects are deserialized in the profile the record is loaded from
+    pub deserialize: fn(&ProfileContext, &str) -> VcxResult<T>,
+    pub tags: fn(&T) -> HashMap<String, String>,
+}
+
+/**
+Describes objects of a cache in events emitted to subscribers, state changes are detected by the cache itself.
+ */
+pub struct ObjectEvents<T> {
+    pub object_type: &'static str,
+    pub source_id: fn(&T) -> String,
+    pub thread_id: fn(&T) -> Option<String>,
+    pub state: fn(&T) -> u32,
+}
+
+struct StoredRecord {
+    id: String,
+    // last persisted value, unchanged objects are not written again, `None` until the record is added
+    value: Option<String>,
+    // version of the object serialized last and version written to the record, older versions are not written
+    version: u64,
+    written: u64,
+    // held while the record is written, the records map itself is not locked during wallet I/O
+    writing: Arc<Mutex<()>>,
+}
+
+impl StoredRecord {
+    fn new(id: String, value: Option<String>) -> StoredRecord {
+        StoredRecord { id, value, version: 0, written: 0, writing: Default::default() }
+    }
+}
+
+/**
+Object serialized while it was locked, waiting to be written to its record after the object is unlocked.
+ */
+struct PendingWrite {
+    handle: u32,
+    version: u64,
+    writing: Arc<Mutex<()>>,
+    value: String,
+    tags: String,
+}
+
+/**
+Object loaded from the wallet by a search over the tags of its record.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StoredObject {
+    pub record_id: String,
+    pub handle: u32,
+    pub tags: HashMap<String, String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct SearchedRecord {
+    id: String,
+    value: Option<String>,
+    tags: Option<HashMap<String, String>>,
+}
+
+#[derive(Deserialize, Debug)]
+struct SearchedRecords {
+    records: Option<Vec<SearchedRecord>>,
+}
+
+/**
+Type-independent access to the wallet records of a persistent cache.
+ */
+pub trait PersistentCache {
+    fn load(&self, profile_handle: Option<u32>, record_id: &str) -> VcxResult<u32>;
+
+    fn search(&self, profile_handle: Option<u32>, query: &str) -> VcxResult<Vec<StoredObject>>;
+
+    fn get_record_id(&self, handle: u32) -> VcxResult<Option<String>>;
+
+    fn persist(&self, handle: u32) -> VcxResult<()>;
+
+    fn delete_record(&self, profile_handle: Option<u32>, record_id: &str) -> VcxResult<()>;
+}
 
 pub struct ObjectCache<T> {
     pub cache_name: String,
     pub store: RwLock<HashMap<u32, Mutex<T>>>,
+    // objects created within a profile, operations on them run within that profile
+    profiles: RwLock<HashMap<u32, u32>>,
+    storage: Option<ObjectStorage<T>>,
+    // wallet records of persisted objects
+    records: RwLock<HashMap<u32, StoredRecord>>,
+    events: Option<ObjectEvents<T>>,
 }
 
 impl<T> ObjectCache<T> {
     pub fn new(cache_name: &str) -> ObjectCache<T> {
         ObjectCache {
             store: Default::default(),
+            profiles: Default::default(),
             cache_name: cache_name.to_string(),
+            storage: None,
+            records: Default::default(),
+            events: None,
+        }
+    }
+
+    pub fn new_persistent(cache_name: &str, storage: ObjectStorage<T>) -> ObjectCache<T> {
+        ObjectCache {
+            storage: Some(storage),
+            ..ObjectCache::new(cache_name)
+        }
+    }
+
+    pub fn with_events(self, events: ObjectEvents<T>) -> ObjectCache<T> {
+        ObjectCache {
+            events: Some(events),
+            ..self
+        }
+    }
+
+    pub fn get_profile_handle(&self, handle: u32) -> Option<u32> {
+        self.profiles.read().ok()
+            .and_then(|profiles| profiles.get(&handle).cloned())
+    }
+
+    fn _bind_profile(&self, handle: u32, profile_handle: Option<u32>) -> VcxResult<()> {
+        let mut profiles = self.profiles.write()?;
+        match profile_handle {
+            Some(profile_handle) => profiles.insert(handle, profile_handle),
+            None => profiles.remove(&handle)
+        };
+        Ok(())
+    }
+
+    fn _state(&self, obj: &T) -> Option<u32> {
+        match self.events {
+            Some(ref object_events) if events::has_listeners() => Some((object_events.state)(obj)),
+            _ => None
+        }
+    }
+
+    fn _emit(&self, kind: EventKind, handle: u32, obj: &T, old_state: u32, message: Option<&A2AMessage>) {
+        if let Some(ref object_events) = self.events {
+            events::emit(Event {
+                kind,
+                object_type: object_events.object_type.to_string(),
+                handle,
+                source_id: (object_events.source_id)(obj),
+                thread_id: (object_events.thread_id)(obj),
+                old_state,
+                new_state: (object_events.state)(obj),
+                message: message.cloned(),
+            });
+        }
+    }
+
+    fn _emit_state_change(&self, handle: u32, obj: &T, old_state: Option<u32>) {
+        if let (Some(old_state), Some(ref object_events)) = (old_state, &self.events) {
+            if (object_events.state)(obj) != old_state {
+                self._emit(EventKind::StateChanged, handle, obj, old_state, None);
+            }
+        }
+    }
+
+    /**
+    Notifies subscribers about a problem report or a message the object cannot handle, other messages are not reported.
+     */
+    pub fn notify_message(&self, handle: u32, obj: &T, message: &A2AMessage, handled: bool) {
+        let kind = if events::is_problem_report(message) {
+            EventKind::ProblemReport
+        } else if !handled {
+            EventKind::UnhandledMessage
+        } else {
+            return;
+        };
+        if let Some(state) = self._state(obj) {
+            self._emit(kind, handle, obj, state, Some(message));
+        }
+    }
+
+    fn _storage(&self) -> VcxResult<&ObjectStorage<T>> {
+        self.storage.as_ref()
+            .ok_or(VcxError::from_msg(VcxErrorKind::ActionNotSupported, format!("[ObjectCache: {}] Objects are not persisted", self.cache_name)))
+    }
+
+    /**
+    Serializes the object into the value and tags of its record, `None` if objects are not persisted.
+    Must be called while the object is locked so versions of the record follow changes of the object.
+     */
+    fn _prepare_write(&self, handle: u32, obj: &T) -> VcxResult<Option<PendingWrite>> {
+        let storage = match self.storage {
+            Some(ref storage) if settings::persist_objects_enabled() => storage,
+            _ => return Ok(None)
+        };
+        let value = (storage.serialize)(obj)?;
+        let mut tags = (storage.tags)(obj);
+        tags.insert(HANDLE_TAG.to_string(), handle.to_string());
+        let mut records = self.records.write()?;
+        let record = records.entry(handle)
+            .or_insert_with(|| StoredRecord::new(uuid::Uuid::new_v4().to_string(), None));
+        record.version += 1;
+        Ok(Some(PendingWrite { handle, version: record.version, writing: record.writing.clone(), value, tags: json!(tags).to_string() }))
+    }
+
+    /**
+    Writes the record unless it is up to date, a newer version of the object has been written already
+    or the object has been released meanwhile. Only writes of the same object wait for each other.
+     */
+    fn _write_record(&self, pending: PendingWrite) -> VcxResult<()> {
+        let PendingWrite { handle, version, writing, value, tags } = pending;
+        let _writing = writing.lock()?;
+        let (id, stored) = match self.records.read()?.get(&handle) {
+            Some(record) if record.written < version => (record.id.clone(), record.value.clone()),
+            _ => return Ok(())
+        };
+        if stored.as_ref() != Some(&value) {
+            let record_type = self._storage()?.record_type;
+            let wallet_handle = ProfileContext::from_handle(self.get_profile_handle(handle))?.wallet_handle()?;
+            match stored {
+                Some(_) => {
+                    wallet::update_record_value(wallet_handle, record_type, &id, &value)?;
+                    wallet::update_record_tags(wallet_handle, record_type, &id, &tags)?;
+                }
+                None => wallet::add_record(wallet_handle, record_type, &id, &value, Some(&tags))?
+            }
+        }
+        if let Some(record) = self.records.write()?.get_mut(&handle) {
+            if record.id == id {
+                record.value = Some(value);
+                record.written = version;
+            }
+        }
+        Ok(())
+    }
+
+    /**
+    Writes the object to its wallet record unless the record is up to date.
+     */
+    fn _persist_stored(&self, handle: u32) -> VcxResult<()> {
+        let pending = {
+            let store = self._lock_store_read()?;
+            let obj = match store.get(&handle).map(|m| m.lock()) {
+                Some(Ok(obj)) => obj,
+                Some(Err(_)) => return Err(VcxError::from_msg(VcxErrorKind::Common(10), format!("[ObjectCache: {}] Unable to lock Object Store", self.cache_name))),
+                None => return Err(VcxError::from_msg(VcxErrorKind::InvalidHandle, format!("[ObjectCache: {}] Object not found for handle: {}", self.cache_name, handle)))
+            };
+            self._prepare_write(handle, obj.deref())?
+        };
+        match pending {
+            Some(pending) => self._write_record(pending),
+            None => Ok(())
+        }
+    }
+
+    fn _find_record_handle(&self, record_id: &str) -> VcxResult<Option<u32>> {
+        Ok(self.records.read()?.iter()
+            .find(|(_, record)| record.id == record_id && record.value.is_some())
+            .map(|(handle, _)| *handle))
+    }
+
+    /**
+    Adds object stored in the record under the handle it was stored with, unless the handle is taken.
+    The object is bound to the profile its record is loaded from.
+     */
+    fn _add_stored(&self, profile_handle: Option<u32>, record_id: &str, value: &str, tags: Option<&HashMap<String, String>>) -> VcxResult<u32> {
+        if let Some(handle) = self._find_record_handle(record_id)? {
+            return Ok(handle);
+        }
+        let obj = (self._storage()?.deserialize)(&ProfileContext::from_handle(profile_handle)?, value)?;
+        let stored_handle = tags
+            .and_then(|tags| tags.get(HANDLE_TAG))
+            .and_then(|handle| handle.parse::<u32>().ok());
+        let handle = match stored_handle {
+            Some(handle) => {
+                let mut store = self._lock_store_write()?;
+                if store.contains_key(&handle) {
+                    drop(store);
+                    self._add(profile_handle, obj)?
+                } else {
+                    self._bind_profile(handle, profile_handle)?;
+                    store.insert(handle, Mutex::new(obj));
+                    handle
+                }
+            }
+            None => self._add(profile_handle, obj)?
+        };
+        self.records.write()?.insert(handle, StoredRecord::new(record_id.to_string(), Some(value.to_string())));
+        Ok(handle)
+    }
+
+    fn _fetch_records(&self, wallet_handle: WalletHandle, search_handle: SearchHandle) -> VcxResult<Vec<SearchedRecord>> {
+        let mut records = Vec::new();
+        loop {
+            let batch = wallet::fetch_next_records(wallet_handle, search_handle, SEARCH_BATCH_SIZE)?;
+            let batch: SearchedRecords = serde_json::from_str(&batch)
+                .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("[ObjectCache: {}] Cannot deserialize wallet records: {}", self.cache_name, err)))?;
+            match batch.records {
+                Some(batch) if !batch.is_empty() => records.extend(batch),
+                _ => return Ok(records)
+            }
         }
     }
 
@@ -48,6 +353,57 @@ impl<T> ObjectCache<T> {
         store.contains_key(&handle)
     }
 
+    pub fn find_handle<F>(&self, predicate: F) -> VcxResult<Option<u32>>
+        where F: Fn(&T) -> bool {
+        let store = self._lock_store_read()?;
+        for (handle, m) in store.iter() {
+            match m.lock() {
+                Ok(obj) => if predicate(obj.deref()) { return Ok(Some(*handle)); },
+                Err(_) => return Err(VcxError::from_msg(VcxErrorKind::Common(10), format!("[ObjectCache: {}] Unable to lock Object Store", self.cache_name))) //TODO better error
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn index_by<F, K>(&self, key: F) -> VcxResult<HashMap<K, u32>>
+        where F: Fn(&T) -> K, K: Eq + Hash {
+        let store = self._lock_store_read()?;
+        let mut index = HashMap::new();
+        for (handle, m) in store.iter() {
+            match m.lock() {
+                Ok(obj) => { index.insert(key(obj.deref()), *handle); }
+                Err(_) => return Err(VcxError::from_msg(VcxErrorKind::Common(10), format!("[ObjectCache: {}] Unable to lock Object Store", self.cache_name))) //TODO better error
+            }
+        }
+        Ok(index)
+    }
+
+    pub fn group_by<F, K>(&self, key: F) -> VcxResult<HashMap<K, Vec<u32>>>
+        where F: Fn(&T) -> K, K: Eq + Hash {
+        let store = self._lock_store_read()?;
+        let mut groups: HashMap<K, Vec<u32>> = HashMap::new();
+        for (handle, m) in store.iter() {
+            match m.lock() {
+                Ok(obj) => groups.entry(key(obj.deref())).or_default().push(*handle),
+                Err(_) => return Err(VcxError::from_msg(VcxErrorKind::Common(10), format!("[ObjectCache: {}] Unable to lock Object Store", self.cache_name))) //TODO better error
+            }
+        }
+        Ok(groups)
+    }
+
+    pub fn find_handles<F>(&self, predicate: F) -> VcxResult<Vec<u32>>
+        where F: Fn(&T) -> bool {
+        let store = self._lock_store_read()?;
+        let mut handles = Vec::new();
+        for (handle, m) in store.iter() {
+            match m.lock() {
+                Ok(obj) => if predicate(obj.deref()) { handles.push(*handle); }
+                Err(_) => return Err(VcxError::from_msg(VcxErrorKind::Common(10), format!("[ObjectCache: {}] Unable to lock Object Store", self.cache_name))) //TODO better error
+            }
+        }
+        Ok(handles)
+    }
+
     pub fn get<F, R>(&self, handle: u32, closure: F) -> VcxResult<R>
         where F: Fn(&T) -> VcxResult<R> {
         let store = self._lock_store_read()?;
@@ -60,19 +416,70 @@ impl<T> ObjectCache<T> {
         }
     }
 
+    /**
+    Object is written to its wallet record after the closure runs, the object is unlocked by then. If writing
+    the record fails, `ObjectNotPersisted` is returned although the change of the object has taken effect,
+    the record is written again on the next change of the object or by `PersistentCache::persist`.
+     */
     pub fn get_mut<F, R>(&self, handle: u32, closure: F) -> VcxResult<R>
         where F: Fn(&mut T) -> VcxResult<R> {
-        let mut store = self._lock_store_write()?;
-        match store.get_mut(&handle) {
-            Some(m) => match m.lock() {
-                Ok(mut obj) => closure(obj.deref_mut()),
-                Err(_) => Err(VcxError::from_msg(VcxErrorKind::Common(10), format!("[ObjectCache: {}] Unable to lock Object Store", self.cache_name))) //TODO better error
-            },
-            None => Err(VcxError::from_msg(VcxErrorKind::InvalidHandle, format!("[ObjectCache: {}] Object not found for handle: {}", self.cache_name, handle)))
-        }
+        let (result, persisted) = self._get_mut(handle, closure)?;
+        result.and_then(|result| persisted.map(|_| result))
+    }
+
+    /**
+    Same as `get_mut`, but the result of the closure is kept if writing the record fails, the failure is returned
+    along with it. For changes whose result must not be lost, e.g. objects spawned by the closure.
+     */
+    pub fn get_mut_with_persist_error<F, R>(&self, handle: u32, closure: F) -> VcxResult<(R, Option<VcxError>)>
+        where F: Fn(&mut T) -> VcxResult<R> {
+        let (result, persisted) = self._get_mut(handle, closure)?;
+        result.map(|result| (result, persisted.err()))
+    }
+
+    fn _get_mut<F, R>(&self, handle: u32, closure: F) -> VcxResult<(VcxResult<R>, VcxResult<()>)>
+        where F: Fn(&mut T) -> VcxResult<R> {
+        let (result, pending) = {
+            let store = self._lock_store_read()?;
+            let mut obj = match store.get(&handle).map(|m| m.lock()) {
+                Some(Ok(obj)) => obj,
+                Some(Err(_)) => return Err(VcxError::from_msg(VcxErrorKind::Common(10), format!("[ObjectCache: {}] Unable to lock Object Store", self.cache_name))), //TODO better error
+                None => return Err(VcxError::from_msg(VcxErrorKind::InvalidHandle, format!("[ObjectCache: {}] Object not found for handle: {}", self.cache_name, handle)))
+            };
+            let old_state = self._state(obj.deref());
+            let result = closure(obj.deref_mut());
+            self._emit_state_change(handle, obj.deref(), old_state);
+            (result, self._prepare_write(handle, obj.deref()))
+        };
+        let persisted = match pending {
+            Ok(Some(pending)) => self._write_record(pending),
+            Ok(None) => Ok(()),
+            Err(err) => Err(err)
+        };
+        let persisted = persisted.map_err(|err| {
+            error!("[ObjectCache: {}] Cannot persist object {}, its record is out of date: {}", self.cache_name, handle, err);
+            VcxError::from_msg(VcxErrorKind::ObjectNotPersisted, format!("[ObjectCache: {}] Object {} has changed but its record could not be written: {}", self.cache_name, handle, err))
+        });
+        Ok((result, persisted))
     }
 
     pub fn add(&self, obj: T) -> VcxResult<u32> {
+        self.add_in_profile(None, obj)
+    }
+
+    /**
+    Adds object created in the given profile (or the global context for `None`), the object stays bound to it.
+     */
+    pub fn add_in_profile(&self, profile_handle: Option<u32>, obj: T) -> VcxResult<u32> {
+        let handle = self._add(profile_handle, obj)?;
+        if let Err(err) = self._persist_stored(handle) {
+            self.release(handle).ok();
+            return Err(err);
+        }
+        Ok(handle)
+    }
+
+    fn _add(&self, profile_handle: Option<u32>, obj: T) -> VcxResult<u32> {
         let mut store = self._lock_store_write()?;
 
         let mut new_handle = rand::thread_rng().gen::<u32>();
@@ -83,6 +490,7 @@ impl<T> ObjectCache<T> {
             new_handle = rand::thread_rng().gen::<u32>();
         }
 
+        self._bind_profile(new_handle, profile_handle)?;
         match store.insert(new_handle, Mutex::new(obj)) {
             Some(_) => Ok(new_handle),
             None => Ok(new_handle)
@@ -90,36 +498,147 @@ impl<T> ObjectCache<T> {
     }
 
     pub fn insert(&self, handle: u32, obj: T) -> VcxResult<()> {
-        let mut store = self._lock_store_write()?;
+        {
+            let mut store = self._lock_store_write()?;
 
-        match store.insert(handle, Mutex::new(obj)) {
-            _ => Ok(()),
+            self._bind_profile(handle, None)?;
+            store.insert(handle, Mutex::new(obj));
         }
+        self._persist_stored(handle)
     }
 
+    /**
+    Releases the object and deletes its wallet record, the record is kept if deleting it fails so it can be retried.
+     */
     pub fn release(&self, handle: u32) -> VcxResult<()> {
+        let writing = self.records.read()?.get(&handle).map(|record| record.writing.clone());
+        let _writing = match writing {
+            Some(ref writing) => Some(writing.lock()?),
+            None => None
+        };
+        let record_id = self.records.read()?.get(&handle)
+            .and_then(|record| record.value.as_ref().map(|_| record.id.clone()));
+        if let (Some(record_id), Some(storage)) = (record_id, self.storage.as_ref()) {
+            let wallet_handle = ProfileContext::from_handle(self.get_profile_handle(handle))?.wallet_handle()?;
+            wallet::delete_record(wallet_handle, storage.record_type, &record_id)?;
+        }
         let mut store = self._lock_store_write()?;
+        self.profiles.write()?.remove(&handle);
+        self.records.write()?.remove(&handle);
         match store.remove(&handle) {
             Some(_) => Ok(()),
             None => Err(VcxError::from_msg(VcxErrorKind::InvalidHandle, format!("[ObjectCache: {}] Object not found for handle: {}", self.cache_name, handle)))
         }
     }
 
+    /**
+    Releases all objects from memory, their wallet records are kept so the objects can be loaded after restart.
+     */
     pub fn drain(&self) -> VcxResult<()> {
         let mut store = self._lock_store_write()?;
+        self.profiles.write()?.clear();
+        self.records.write()?.clear();
         Ok(store.clear())
     }
 
+    pub fn handles(&self) -> VcxResult<Vec<u32>> {
+        let store = self._lock_store_read()?;
+        Ok(store.keys().cloned().collect())
+    }
+
     pub fn len(&self) -> VcxResult<usize> {
         let store = self._lock_store_read()?;
         Ok(store.len())
     }
 }
 
+impl<T> PersistentCache for ObjectCache<T> {
+    fn load(&self, profile_handle: Option<u32>, record_id: &str) -> VcxResult<u32> {
+        if let Some(handle) = self._find_record_handle(record_id)? {
+            return Ok(handle);
+        }
+        let wallet_handle = ProfileContext::from_handle(profile_handle)?.wallet_handle()?;
+        let options = json!({"retrieveType": false, "retrieveValue": true, "retrieveTags": true}).to_string();
+        let record = wallet::get_record(wallet_handle, self._storage()?.record_type, record_id, &options)?;
+        let record: wallet::WalletRecord = serde_json::from_str(&record)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("[ObjectCache: {}] Cannot deserialize wallet record: {}", self.cache_name, err)))?;
+        let value = record.value
+            .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("[ObjectCache: {}] Wallet record {} has no value", self.cache_name, record_id)))?;
+        let tags = record.tags
+            .and_then(|tags| serde_json::from_str::<HashMap<String, String>>(&tags).ok());
+        self._add_stored(profile_handle, record_id, &value, tags.as_ref())
+    }
+
+    fn search(&self, profile_handle: Option<u32>, query: &str) -> VcxResult<Vec<StoredObject>> {
+        let record_type = self._storage()?.record_type;
+        let wallet_handle = ProfileContext::from_handle(profile_handle)?.wallet_handle()?;
+        let options = json!({"retrieveRecords": true, "retrieveTotalCount": false, "retrieveType": false, "retrieveValue": true, "retrieveTags": true}).to_string();
+        let search_handle = wallet::open_search(wallet_handle, record_type, query, &options)?;
+
+        let fetched = self._fetch_records(wallet_handle, search_handle);
+        wallet::close_search(search_handle).ok();
+
+        fetched?.into_iter()
+            .map(|SearchedRecord { id, value, tags }| {
+                let handle = match value {
+                    Some(value) => self._add_stored(profile_handle, &id, &value, tags.as_ref())?,
+                    None => self.load(profile_handle, &id)?
+                };
+                Ok(StoredObject { record_id: id, handle, tags: tags.unwrap_or_default() })
+            })
+            .collect()
+    }
+
+    fn get_record_id(&self, handle: u32) -> VcxResult<Option<String>> {
+        if !self.has_handle(handle) {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidHandle, format!("[ObjectCache: {}] Object not found for handle: {}", self.cache_name, handle)));
+        }
+        Ok(self.records.read()?.get(&handle)
+            .and_then(|record| record.value.as_ref().map(|_| record.id.clone())))
+    }
+
+    fn persist(&self, handle: u32) -> VcxResult<()> {
+        self._storage()?;
+        if !settings::persist_objects_enabled() {
+            return Err(VcxError::from_msg(VcxErrorKind::ActionNotSupported, format!("[ObjectCache: {}] Objects are not persisted, is persist_objects enabled?", self.cache_name)));
+        }
+        self._persist_stored(handle)
+    }
+
+    fn delete_record(&self, profile_handle: Option<u32>, record_id: &str) -> VcxResult<()> {
+        let wallet_handle = ProfileContext::from_handle(profile_handle)?.wallet_handle()?;
+        wallet::delete_record(wallet_handle, self._storage()?.record_type, record_id)?;
+        if let Some(handle) = self._find_record_handle(record_id)? {
+            // the next change of the object adds a new record
+            if let Some(record) = self.records.write()?.get_mut(&handle) {
+                record.id = uuid::Uuid::new_v4().to_string();
+                record.value = None;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::api_lib::api_handle::object_cache::ObjectCache;
-    use crate::utils::devsetup::SetupDefaults;
+    use std::collections::HashMap;
+
+    use crate::api_lib::api_handle::object_cache::{ObjectCache, ObjectStorage};
+    use crate::error::VcxErrorKind;
+    use crate::profile;
+    use crate::profile::tests::_create_profile;
+    use crate::settings;
+    use crate::utils::devsetup::{SetupDefaults, SetupMocks};
+
+    fn _persistent_cache(cache_name: &str) -> ObjectCache<u32> {
+        settings::set_config_value(settings::CONFIG_PERSIST_OBJECTS, "true");
+        ObjectCache::new_persistent(cache_name, ObjectStorage {
+            record_type: "test",
+            serialize: |obj| Ok(obj.to_string()),
+            deserialize: |_, data| Ok(data.parse().unwrap()),
+            tags: |_| HashMap::new(),
+        })
+    }
 
     #[test]
     #[cfg(feature = "general_test")]
@@ -173,4 +692,88 @@ mod tests {
 
         assert_eq!("TEST", string);
     }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn find_handle_test() {
+        let _setup = SetupDefaults::init();
+
+        let test: ObjectCache<u32> = ObjectCache::new("cache4-u32");
+        test.add(1111).unwrap();
+        let handle = test.add(2222).unwrap();
+
+        assert_eq!(Some(handle), test.find_handle(|obj| *obj == 2222).unwrap());
+        assert_eq!(None, test.find_handle(|obj| *obj == 3333).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn group_by_keeps_handles_of_equal_keys() {
+        let _setup = SetupDefaults::init();
+
+        let test: ObjectCache<u32> = ObjectCache::new("cache6-u32");
+        let first = test.add(1111).unwrap();
+        let second = test.add(1111).unwrap();
+        let other = test.add(2222).unwrap();
+
+        let mut groups = test.group_by(|obj| *obj).unwrap();
+        groups.get_mut(&1111).unwrap().sort();
+        let mut expected = vec![first, second];
+        expected.sort();
+        assert_eq!(Some(&expected), groups.get(&1111));
+        assert_eq!(Some(&vec![other]), groups.get(&2222));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn objects_are_bound_to_profile() {
+        let _setup = SetupMocks::init();
+
+        let test: ObjectCache<u32> = ObjectCache::new("cache5-u32");
+        let profile_handle = _create_profile();
+
+        let global_handle = test.add(1111).unwrap();
+        let profile_object_handle = test.add_in_profile(Some(profile_handle), 2222).unwrap();
+
+        assert_eq!(None, test.get_profile_handle(global_handle));
+        assert_eq!(Some(profile_handle), test.get_profile_handle(profile_object_handle));
+
+        test.release(profile_object_handle).unwrap();
+        assert_eq!(None, test.get_profile_handle(profile_object_handle));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn older_version_is_not_written_over_newer_one() {
+        let _setup = SetupMocks::init();
+
+        let test = _persistent_cache("cache7-u32");
+        let handle = test.add(1111).unwrap();
+
+        let older = test._prepare_write(handle, &2222).unwrap().unwrap();
+        let newer = test._prepare_write(handle, &3333).unwrap().unwrap();
+        test._write_record(newer).unwrap();
+        test._write_record(older).unwrap();
+
+        assert_eq!(Some("3333".to_string()), test.records.read().unwrap().get(&handle).unwrap().value);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn get_mut_fails_if_record_is_not_written() {
+        let _setup = SetupMocks::init();
+
+        let test = _persistent_cache("cache8-u32");
+        let profile_handle = _create_profile();
+        let handle = test.add_in_profile(Some(profile_handle), 1111).unwrap();
+        profile::release(profile_handle).unwrap();
+
+        let err = test.get_mut(handle, |obj| {
+            *obj = 2222;
+            Ok(())
+        }).unwrap_err();
+        assert_eq!(VcxErrorKind::ObjectNotPersisted, err.kind());
+        assert_eq!(2222, test.get(handle, |obj| Ok(*obj)).unwrap());
+        assert_eq!(Some("1111".to_string()), test.records.read().unwrap().get(&handle).unwrap().value);
+    }
 }
@@ -0,0 +1,162 @@
+use serde_json;
+
+use crate::api_lib::api_handle::{connection, credential, credential_def, disclosed_proof, issuer_credential, mediation, multi_use_invitation, proof, schema};
+use crate::api_lib::api_handle::object_cache::PersistentCache;
+use crate::error::prelude::*;
+
+/**
+Objects are persisted only when `persist_objects` is enabled in the config. Every object is stored in its own
+wallet record and the record is updated whenever the object changes, releasing the object deletes its record.
+Records are tagged by the handle of the object, after restart of the application objects are loaded by `load`
+or `search` under the handles they had before. Records are looked up in the wallet of the global context.
+
+Records are tagged by `source_id` and `state`, connections also by `pw_did` and `their_pw_did`,
+credential exchanges and proof presentations by `thread_id`, credential definitions by `cred_def_id`,
+schemas by `schema_id`. Multi-use invitations are tagged by `source_id`, `recipient_key` and `revoked`.
+ */
+fn _persistent_cache(object_type: &str) -> VcxResult<&'static dyn PersistentCache> {
+    match object_type {
+        "connection" => Ok(connection::persistent_cache()),
+        "issuer_credential" => Ok(issuer_credential::persistent_cache()),
+        "credential" => Ok(credential::persistent_cache()),
+        "disclosed_proof" => Ok(disclosed_proof::persistent_cache()),
+        "proof" => Ok(proof::persistent_cache()),
+        "credential_def" => Ok(credential_def::persistent_cache()),
+        "schema" => Ok(schema::persistent_cache()),
+        "mediation" => Ok(mediation::persistent_cache()),
+        "multi_use_invitation" => Ok(multi_use_invitation::persistent_cache()),
+        _ => Err(VcxError::from_msg(VcxErrorKind::InvalidOption, format!("Unknown object type: {}", object_type)))
+    }
+}
+
+/**
+Loads the object stored in the wallet record, returns handle of the object if it is already loaded.
+ */
+pub fn load(object_type: &str, record_id: &str) -> VcxResult<u32> {
+    trace!("object_storage::load >>> object_type: {}, record_id: {}", object_type, record_id);
+    _persistent_cache(object_type)?.load(None, record_id)
+}
+
+/**
+Loads all objects whose records match WQL query over the record tags.
+Returns json array of `{"record_id": string, "handle": u32, "tags": object}`.
+ */
+pub fn search(object_type: &str, query: Option<&str>) -> VcxResult<String> {
+    trace!("object_storage::search >>> object_type: {}, query: {:?}", object_type, query);
+    let query = query.unwrap_or("{}");
+    let parsed: serde_json::Value = serde_json::from_str(query)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize WQL query: {}", err)))?;
+    if !parsed.is_object() {
+        return Err(VcxError::from_msg(VcxErrorKind::InvalidOption, "WQL query must be JSON object"));
+    }
+    let objects = _persistent_cache(object_type)?.search(None, query)?;
+    serde_json::to_string(&objects)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Cannot serialize stored objects: {}", err)))
+}
+
+/**
+Returns id of the wallet record the object is stored in.
+ */
+pub fn get_record_id(object_type: &str, handle: u32) -> VcxResult<String> {
+    trace!("object_storage::get_record_id >>> object_type: {}, handle: {}", object_type, handle);
+    _persistent_cache(object_type)?.get_record_id(handle)?
+        .ok_or(VcxError::from_msg(VcxErrorKind::NotReady, format!("Object {} has not been persisted, is persist_objects enabled?", handle)))
+}
+
+/**
+Writes the object to its wallet record if the record is out of date, e.g. because writing it failed on
+the last change of the object.
+ */
+pub fn persist(object_type: &str, handle: u32) -> VcxResult<()> {
+    trace!("object_storage::persist >>> object_type: {}, handle: {}", object_type, handle);
+    _persistent_cache(object_type)?.persist(handle)
+}
+
+/**
+Deletes the wallet record, loaded object stays available until it is released.
+ */
+pub fn delete(object_type: &str, record_id: &str) -> VcxResult<()> {
+    trace!("object_storage::delete >>> object_type: {}, record_id: {}", object_type, record_id);
+    _persistent_cache(object_type)?.delete_record(None, record_id)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::api_lib::api_handle::connection::tests::build_test_connection_inviter_requested;
+    use crate::settings;
+    use crate::utils::devsetup::SetupMocks;
+    use crate::utils::mockdata::mock_settings::MockBuilder;
+
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_objects_are_not_persisted_by_default() {
+        let _setup = SetupMocks::init();
+
+        let handle = build_test_connection_inviter_requested();
+        assert_eq!(get_record_id("connection", handle).unwrap_err().kind(), VcxErrorKind::NotReady);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_persisted_object_is_loaded_from_its_record() {
+        let _setup = SetupMocks::init();
+        settings::set_config_value(settings::CONFIG_PERSIST_OBJECTS, "true");
+
+        let handle = build_test_connection_inviter_requested();
+        let record_id = get_record_id("connection", handle).unwrap();
+        assert_eq!(handle, load("connection", &record_id).unwrap());
+        persist("connection", handle).unwrap();
+
+        let serialized = connection::to_string(handle).unwrap();
+        let record = json!({
+            "id": record_id,
+            "type": null,
+            "value": serialized,
+            "tags": json!({"handle": handle.to_string()}).to_string()
+        }).to_string();
+        let _mock_builder = MockBuilder::init().set_mock_wallet_record("vcx_connection", &record_id, &record);
+
+        connection::release_all();
+        assert!(!connection::is_valid_handle(handle));
+        assert_eq!(handle, load("connection", &record_id).unwrap());
+        assert_eq!(serialized, connection::to_string(handle).unwrap());
+        assert_eq!(record_id, get_record_id("connection", handle).unwrap());
+
+        delete("connection", &record_id).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_released_object_is_not_loaded_by_handle() {
+        let _setup = SetupMocks::init();
+        settings::set_config_value(settings::CONFIG_PERSIST_OBJECTS, "true");
+
+        let handle = build_test_connection_inviter_requested();
+        get_record_id("connection", handle).unwrap();
+
+        connection::release(handle).unwrap();
+        assert!(!connection::is_valid_handle(handle));
+        assert_eq!(get_record_id("connection", handle).unwrap_err().kind(), VcxErrorKind::InvalidHandle);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_load_fails_for_invalid_record() {
+        let _setup = SetupMocks::init();
+
+        assert_eq!(load("proof", "not_stored").unwrap_err().kind(), VcxErrorKind::InvalidJson);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_search_validates_input() {
+        let _setup = SetupMocks::init();
+
+        assert_eq!(search("connection", Some("[]")).unwrap_err().kind(), VcxErrorKind::InvalidOption);
+        assert_eq!(search("unknown", None).unwrap_err().kind(), VcxErrorKind::InvalidOption);
+        assert_eq!("[]", search("connection", None).unwrap());
+        assert_eq!("[]", search("mediation", None).unwrap());
+    }
+}
@@ -1,14 +1,32 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
 use serde_json;
 
 use crate::api_lib::api_handle::connection;
-use crate::api_lib::api_handle::object_cache::ObjectCache;
+use crate::api_lib::api_handle::events;
+use crate::api_lib::api_handle::object_cache::{ObjectCache, ObjectEvents, ObjectStorage, PersistentCache};
 use crate::aries::handlers::proof_presentation::verifier::verifier::Verifier;
+use crate::aries::handlers::state_timing::StateTiming;
 use crate::aries::messages::a2a::A2AMessage;
+use crate::aries::messages::a2a::protocol_version::ProtocolVersion;
+use crate::aries::messages::proof_presentation::presentation_proposal::PresentationProposal;
 use crate::error::prelude::*;
+use crate::profile::ProfileContext;
 use crate::utils::error;
 
 lazy_static! {
-    static ref PROOF_MAP: ObjectCache<Verifier> = ObjectCache::<Verifier>::new("proofs-cache");
+    static ref PROOF_MAP: ObjectCache<Verifier> = ObjectCache::<Verifier>::new_persistent("proofs-cache", ObjectStorage {
+        record_type: "vcx_proof",
+        serialize: _serialize,
+        deserialize: _deserialize,
+        tags: _storage_tags,
+    }).with_events(ObjectEvents {
+        object_type: "proof",
+        source_id: Verifier::get_source_id,
+        thread_id: |proof| Some(proof.get_thread_id()),
+        state: Verifier::state,
+    });
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -18,13 +36,64 @@ enum Proofs {
     V3(Verifier),
 }
 
+fn _serialize(proof: &Verifier) -> VcxResult<String> {
+    serde_json::to_string(&Proofs::V3(proof.clone()))
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidState, format!("cannot serialize Proof proofect: {:?}", err)))
+}
+
+fn _deserialize(profile: &ProfileContext, proof_data: &str) -> VcxResult<Verifier> {
+    let proof: Proofs = serde_json::from_str(proof_data)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("cannot deserialize Proofs proofect: {:?}", err)))?;
+
+    match proof {
+        Proofs::V3(mut proof) => {
+            proof.set_profile(profile.clone());
+            Ok(proof)
+        }
+    }
+}
+
+fn _storage_tags(proof: &Verifier) -> HashMap<String, String> {
+    let mut tags = HashMap::new();
+    tags.insert("source_id".to_string(), proof.get_source_id());
+    tags.insert("thread_id".to_string(), proof.get_thread_id());
+    tags.insert("state".to_string(), proof.state().to_string());
+    tags
+}
+
+pub fn persistent_cache() -> &'static dyn PersistentCache {
+    &*PROOF_MAP
+}
+
 pub fn create_proof(source_id: String,
                     requested_attrs: String,
                     requested_predicates: String,
                     revocation_details: String,
                     name: String) -> VcxResult<u32> {
-    let verifier = Verifier::create(source_id, requested_attrs, requested_predicates, revocation_details, name)?;
-    PROOF_MAP.add(verifier)
+    create_proof_in_profile(None, source_id, requested_attrs, requested_predicates, revocation_details, name)
+}
+
+pub fn create_proof_in_profile(profile_handle: Option<u32>,
+                               source_id: String,
+                               requested_attrs: String,
+                               requested_predicates: String,
+                               revocation_details: String,
+                               name: String) -> VcxResult<u32> {
+    let verifier = Verifier::create(ProfileContext::from_handle(profile_handle)?, source_id, requested_attrs, requested_predicates, revocation_details, name)?;
+    PROOF_MAP.add_in_profile(profile_handle, verifier)
+        .or(Err(VcxError::from(VcxErrorKind::CreateProof)))
+}
+
+pub fn create_proof_from_proposal(source_id: String, presentation_proposal: &str) -> VcxResult<u32> {
+    create_proof_from_proposal_in_profile(None, source_id, presentation_proposal)
+}
+
+pub fn create_proof_from_proposal_in_profile(profile_handle: Option<u32>, source_id: String, presentation_proposal: &str) -> VcxResult<u32> {
+    trace!("create_proof_from_proposal >>> profile_handle: {:?}, source_id: {}, presentation_proposal: {}", profile_handle, source_id, secret!(&presentation_proposal));
+    let presentation_proposal: PresentationProposal = serde_json::from_str(presentation_proposal)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize Presentation Proposal: {:?}", err)))?;
+    let verifier = Verifier::create_from_proposal(ProfileContext::from_handle(profile_handle)?, source_id, presentation_proposal)?;
+    PROOF_MAP.add_in_profile(profile_handle, verifier)
         .or(Err(VcxError::from(VcxErrorKind::CreateProof)))
 }
 
@@ -32,6 +101,32 @@ pub fn is_valid_handle(handle: u32) -> bool {
     PROOF_MAP.has_handle(handle)
 }
 
+pub fn get_state_timing(handle: u32) -> VcxResult<StateTiming> {
+    PROOF_MAP.get(handle, |proof| Ok(proof.get_state_timing()))
+}
+
+pub fn expired_handles() -> VcxResult<Vec<u32>> {
+    PROOF_MAP.find_handles(|proof| proof.is_expired())
+}
+
+pub fn abandon(handle: u32, connection_handle: Option<u32>, reason: Option<String>) -> VcxResult<u32> {
+    PROOF_MAP.get_mut(handle, |proof| {
+        match connection_handle {
+            Some(connection_handle) => proof.abandon(reason.clone(), Some(&connection::send_message_closure(connection_handle)?))?,
+            None => proof.abandon(reason.clone(), None::<&fn(&A2AMessage) -> _>)?
+        };
+        Ok(proof.state())
+    })
+}
+
+pub fn handles_by_thread_id() -> VcxResult<HashMap<String, Vec<u32>>> {
+    PROOF_MAP.group_by(|proof| proof.get_thread_id())
+}
+
+pub fn get_profile_handle(handle: u32) -> Option<u32> {
+    PROOF_MAP.get_profile_handle(handle)
+}
+
 pub fn update_state(handle: u32, message: Option<&str>, connection_handle: u32) -> VcxResult<u32> {
     PROOF_MAP.get_mut(handle, |proof| {
         trace!("proof::update_state >>> handle: {}, message: {:?}, connection_handle: {}", handle, message, connection_handle);
@@ -42,11 +137,13 @@ pub fn update_state(handle: u32, message: Option<&str>, connection_handle: u32)
             let message: A2AMessage = serde_json::from_str(message)
                 .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidOption, format!("Cannot updated state with message: Message deserialization failed: {:?}", err)))?;
             trace!("proof::update_state >>> updating using message {:?}", message);
+            PROOF_MAP.notify_message(handle, proof, &message, proof.find_message_to_handle(events::as_messages(&message)).is_some());
             proof.handle_message(message.into(), Some(&send_message))?;
         } else {
             let messages = connection::get_messages(connection_handle)?;
             trace!("proof::update_state >>> found messages: {:?}", messages);
             if let Some((uid, message)) = proof.find_message_to_handle(messages) {
+                PROOF_MAP.notify_message(handle, proof, &message, true);
                 proof.handle_message(message.into(), Some(&send_message))?;
                 connection::update_message_status(connection_handle, uid)?;
             };
@@ -76,10 +173,7 @@ pub fn release_all() {
 }
 
 pub fn to_string(handle: u32) -> VcxResult<String> {
-    PROOF_MAP.get(handle, |proof| {
-        serde_json::to_string(&Proofs::V3(proof.clone()))
-            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidState, format!("cannot serialize Proof proofect: {:?}", err)))
-    })
+    PROOF_MAP.get(handle, _serialize)
 }
 
 pub fn get_source_id(handle: u32) -> VcxResult<String> {
@@ -89,12 +183,12 @@ pub fn get_source_id(handle: u32) -> VcxResult<String> {
 }
 
 pub fn from_string(proof_data: &str) -> VcxResult<u32> {
-    let proof: Proofs = serde_json::from_str(proof_data)
-        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("cannot deserialize Proofs proofect: {:?}", err)))?;
+    from_string_in_profile(None, proof_data)
+}
 
-    match proof {
-        Proofs::V3(proof) => PROOF_MAP.add(proof)
-    }
+pub fn from_string_in_profile(profile_handle: Option<u32>, proof_data: &str) -> VcxResult<u32> {
+    let proof = _deserialize(&ProfileContext::from_handle(profile_handle)?, proof_data)?;
+    PROOF_MAP.add_in_profile(profile_handle, proof)
 }
 
 pub fn generate_proof_request_msg(handle: u32) -> VcxResult<String> {
@@ -103,6 +197,13 @@ pub fn generate_proof_request_msg(handle: u32) -> VcxResult<String> {
     })
 }
 
+pub fn set_protocol_version(handle: u32, protocol_version: &str) -> VcxResult<()> {
+    let protocol_version = ProtocolVersion::from_str(protocol_version)?;
+    PROOF_MAP.get_mut(handle, |proof| {
+        proof.set_protocol_version(protocol_version)
+    })
+}
+
 pub fn send_proof_request(handle: u32, connection_handle: u32, comment: Option<String>) -> VcxResult<u32> {
     PROOF_MAP.get_mut(handle, |proof| {
         proof.send_presentation_request(connection::send_message_closure(connection_handle)?, comment.clone())?;
@@ -116,6 +217,79 @@ pub fn get_proof(handle: u32) -> VcxResult<String> {
     })
 }
 
+/**
+Returns report describing what has been proven by the received proof and why it has been rejected, if it has been.
+ */
+pub fn get_verification_report(handle: u32) -> VcxResult<String> {
+    PROOF_MAP.get(handle, |proof| {
+        proof.get_verification_report()
+    })
+}
+
+pub fn get_presentation_proposal(handle: u32) -> VcxResult<String> {
+    PROOF_MAP.get(handle, |proof| {
+        let presentation_proposal = proof.get_presentation_proposal()?;
+        serde_json::to_string(&presentation_proposal)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Cannot serialize Presentation Proposal: {:?}", err)))
+    })
+}
+
+pub fn get_proposed_proof_request_data(handle: u32) -> VcxResult<String> {
+    PROOF_MAP.get(handle, |proof| {
+        let presentation_request_data = proof.get_proposed_presentation_request_data()?;
+        serde_json::to_string(&presentation_request_data)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Cannot serialize Presentation Request Data: {:?}", err)))
+    })
+}
+
+pub fn accept_presentation_proposal(handle: u32, connection_handle: u32, comment: Option<String>) -> VcxResult<u32> {
+    PROOF_MAP.get_mut(handle, |proof| {
+        proof.accept_presentation_proposal(connection::send_message_closure(connection_handle)?, comment.clone())?;
+        Ok(error::SUCCESS.code_num)
+    })
+}
+
+pub fn counter_presentation_proposal(handle: u32,
+                                     connection_handle: u32,
+                                     requested_attrs: String,
+                                     requested_predicates: String,
+                                     revocation_details: String,
+                                     name: String,
+                                     comment: Option<String>) -> VcxResult<u32> {
+    PROOF_MAP.get_mut(handle, |proof| {
+        proof.counter_presentation_proposal(connection::send_message_closure(connection_handle)?,
+                                            requested_attrs.clone(),
+                                            requested_predicates.clone(),
+                                            revocation_details.clone(),
+                                            name.clone(),
+                                            comment.clone())?;
+        Ok(error::SUCCESS.code_num)
+    })
+}
+
+pub fn reject_presentation_proposal(handle: u32, connection_handle: u32, reason: String) -> VcxResult<u32> {
+    PROOF_MAP.get_mut(handle, |proof| {
+        proof.reject_presentation_proposal(connection::send_message_closure(connection_handle)?, reason.clone())?;
+        Ok(error::SUCCESS.code_num)
+    })
+}
+
+pub fn get_presentation_proposal_messages(connection_handle: u32) -> VcxResult<String> {
+    trace!("proof::get_presentation_proposal_messages >>> connection_handle: {}", connection_handle);
+
+    let presentation_proposals: Vec<A2AMessage> = connection::get_messages(connection_handle)?
+        .into_iter()
+        .filter_map(|(_, a2a_message)| {
+            match a2a_message {
+                A2AMessage::PresentationProposal(_) => Some(a2a_message),
+                _ => None
+            }
+        })
+        .collect();
+
+    Ok(json!(presentation_proposals).to_string())
+}
+
 #[cfg(test)]
 pub mod tests {
     use serde_json::Value;
@@ -306,6 +480,65 @@ pub mod tests {
         assert_eq!(get_state(handle_proof).unwrap(), VcxStateType::VcxStateOfferSent as u32);
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_accept_presentation_proposal() {
+        let _setup = SetupMocks::init();
+
+        let handle_conn = build_test_connection_inviter_requested();
+        let handle_proof = create_proof_from_proposal("1".to_string(), mockdata_proof::ARIES_PRESENTATION_PROPOSAL).unwrap();
+        assert_eq!(get_state(handle_proof).unwrap(), VcxStateType::VcxStateInitialized as u32);
+
+        let presentation_proposal: PresentationProposal = serde_json::from_str(&get_presentation_proposal(handle_proof).unwrap()).unwrap();
+        assert_eq!(1, presentation_proposal.presentation_proposal.predicates.len());
+
+        let proof_request_data: Value = serde_json::from_str(&get_proposed_proof_request_data(handle_proof).unwrap()).unwrap();
+        assert_eq!("degree", proof_request_data["requested_attributes"]["attribute_0"]["name"]);
+        assert_eq!(">=", proof_request_data["requested_predicates"]["predicate_0"]["p_type"]);
+
+        accept_presentation_proposal(handle_proof, handle_conn, _comment()).unwrap();
+        assert_eq!(get_state(handle_proof).unwrap(), VcxStateType::VcxStateOfferSent as u32);
+        assert_eq!(VcxErrorKind::InvalidState, get_presentation_proposal(handle_proof).unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_counter_presentation_proposal() {
+        let _setup = SetupMocks::init();
+
+        let handle_conn = build_test_connection_inviter_requested();
+        let handle_proof = create_proof_from_proposal("1".to_string(), mockdata_proof::ARIES_PRESENTATION_PROPOSAL).unwrap();
+
+        counter_presentation_proposal(handle_proof,
+                                      handle_conn,
+                                      REQUESTED_ATTRS.to_owned(),
+                                      REQUESTED_PREDICATES.to_owned(),
+                                      r#"{"support_revocation":false}"#.to_string(),
+                                      "Optional".to_owned(),
+                                      _comment()).unwrap();
+        assert_eq!(get_state(handle_proof).unwrap(), VcxStateType::VcxStateOfferSent as u32);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_reject_presentation_proposal() {
+        let _setup = SetupMocks::init();
+
+        let handle_conn = build_test_connection_inviter_requested();
+        let handle_proof = create_proof_from_proposal("1".to_string(), mockdata_proof::ARIES_PRESENTATION_PROPOSAL).unwrap();
+
+        reject_presentation_proposal(handle_proof, handle_conn, "reason".to_string()).unwrap();
+        assert_eq!(get_state(handle_proof).unwrap(), VcxStateType::VcxStateNone as u32);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_create_proof_from_proposal_fails_for_invalid_proposal() {
+        let _setup = SetupMocks::init();
+
+        assert_eq!(VcxErrorKind::InvalidJson, create_proof_from_proposal("1".to_string(), "{}").unwrap_err().kind());
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_get_proof() {
@@ -324,6 +557,9 @@ pub mod tests {
 
         let proof_str = get_proof(handle_proof).unwrap();
         assert_eq!(proof_str, mockdata_proof::ARIES_PROOF_PRESENTATION.replace("\n", "").replace(" ", ""));
+
+        let report: Value = serde_json::from_str(&get_verification_report(handle_proof).unwrap()).unwrap();
+        assert_eq!(true, report["verified"]);
     }
 
     #[test]
@@ -1,18 +1,25 @@
+use std::collections::HashMap;
 use std::string::ToString;
 
 use serde_json;
 
-use crate::api_lib::api_handle::object_cache::ObjectCache;
+use crate::api_lib::api_handle::object_cache::{ObjectCache, ObjectStorage, PersistentCache};
 use crate::api_lib::PublicEntityStateType;
 use crate::error::prelude::*;
 use crate::libindy::utils::anoncreds;
 use crate::libindy::utils::ledger;
 use crate::libindy::utils::payments::PaymentTxn;
+use crate::profile::ProfileContext;
 use crate::utils::constants::DEFAULT_SERIALIZE_VERSION;
 use crate::utils::serialization::ObjectWithVersion;
 
 lazy_static! {
-    static ref SCHEMA_MAP: ObjectCache<CreateSchema> = ObjectCache::<CreateSchema>::new("schemas-cache");
+    static ref SCHEMA_MAP: ObjectCache<CreateSchema> = ObjectCache::<CreateSchema>::new_persistent("schemas-cache", ObjectStorage {
+        record_type: "vcx_schema",
+        serialize: CreateSchema::to_string,
+        deserialize: _deserialize,
+        tags: _storage_tags,
+    });
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -33,6 +40,26 @@ pub struct CreateSchema {
     payment_txn: Option<PaymentTxn>,
     #[serde(default)]
     state: PublicEntityStateType,
+    #[serde(skip)]
+    profile: ProfileContext,
+}
+
+fn _deserialize(profile: &ProfileContext, data: &str) -> VcxResult<CreateSchema> {
+    let mut schema = CreateSchema::from_str(data)?;
+    schema.profile = profile.clone();
+    Ok(schema)
+}
+
+fn _storage_tags(schema: &CreateSchema) -> HashMap<String, String> {
+    let mut tags = HashMap::new();
+    tags.insert("source_id".to_string(), schema.get_source_id().to_string());
+    tags.insert("schema_id".to_string(), schema.get_schema_id().to_string());
+    tags.insert("state".to_string(), schema.get_state().to_string());
+    tags
+}
+
+pub fn persistent_cache() -> &'static dyn PersistentCache {
+    &*SCHEMA_MAP
 }
 
 impl CreateSchema {
@@ -61,7 +88,7 @@ impl CreateSchema {
     }
 
     fn update_state(&mut self) -> VcxResult<u32> {
-        if anoncreds::get_schema_json(&self.schema_id).is_ok() {
+        if anoncreds::get_schema_json(&self.profile, &self.schema_id).is_ok() {
             self.state = PublicEntityStateType::Published
         }
         Ok(self.state as u32)
@@ -75,15 +102,25 @@ pub fn create_and_publish_schema(source_id: &str,
                                  name: String,
                                  version: String,
                                  data: String) -> VcxResult<u32> {
-    trace!("create_new_schema >>> source_id: {}, issuer_did: {}, name: {}, version: {}, data: {}", source_id, issuer_did, name, version, data);
+    create_and_publish_schema_in_profile(None, source_id, issuer_did, name, version, data)
+}
+
+pub fn create_and_publish_schema_in_profile(profile_handle: Option<u32>,
+                                            source_id: &str,
+                                            issuer_did: String,
+                                            name: String,
+                                            version: String,
+                                            data: String) -> VcxResult<u32> {
+    trace!("create_new_schema >>> profile_handle: {:?}, source_id: {}, issuer_did: {}, name: {}, version: {}, data: {}", profile_handle, source_id, issuer_did, name, version, data);
     debug!("creating schema with source_id: {}, name: {}, issuer_did: {}", source_id, name, issuer_did);
 
-    let (schema_id, schema) = anoncreds::create_schema(&name, &version, &data)?;
-    let payment_txn = anoncreds::publish_schema(&schema)?;
+    let profile = ProfileContext::from_handle(profile_handle)?;
+    let (schema_id, schema) = anoncreds::create_schema(&profile, &name, &version, &data)?;
+    let payment_txn = anoncreds::publish_schema(&profile, &schema)?;
 
     debug!("created schema on ledger with id: {}", schema_id);
 
-    let schema_handle = _store_schema(source_id, name, version, schema_id, data, payment_txn, PublicEntityStateType::Published)?;
+    let schema_handle = _store_schema(profile_handle, source_id, name, version, schema_id, data, payment_txn, PublicEntityStateType::Published)?;
 
     Ok(schema_handle)
 }
@@ -94,21 +131,33 @@ pub fn prepare_schema_for_endorser(source_id: &str,
                                    version: String,
                                    data: String,
                                    endorser: String) -> VcxResult<(u32, String)> {
-    trace!("create_schema_for_endorser >>> source_id: {}, issuer_did: {}, name: {}, version: {}, data: {}, endorser: {}", source_id, issuer_did, name, version, data, endorser);
+    prepare_schema_for_endorser_in_profile(None, source_id, issuer_did, name, version, data, endorser)
+}
+
+pub fn prepare_schema_for_endorser_in_profile(profile_handle: Option<u32>,
+                                              source_id: &str,
+                                              issuer_did: String,
+                                              name: String,
+                                              version: String,
+                                              data: String,
+                                              endorser: String) -> VcxResult<(u32, String)> {
+    trace!("create_schema_for_endorser >>> profile_handle: {:?}, source_id: {}, issuer_did: {}, name: {}, version: {}, data: {}, endorser: {}", profile_handle, source_id, issuer_did, name, version, data, endorser);
     debug!("preparing schema for endorser with source_id: {}, name: {}, issuer_did: {}", source_id, name, issuer_did);
 
-    let (schema_id, schema) = anoncreds::create_schema(&name, &version, &data)?;
-    let schema_request = anoncreds::build_schema_request(&schema)?;
-    let schema_request = ledger::set_endorser(&schema_request, &endorser)?;
+    let profile = ProfileContext::from_handle(profile_handle)?;
+    let (schema_id, schema) = anoncreds::create_schema(&profile, &name, &version, &data)?;
+    let schema_request = anoncreds::build_schema_request(&profile, &schema)?;
+    let schema_request = ledger::set_endorser(&profile, &schema_request, &endorser)?;
 
     debug!("prepared schema for endorser with id: {}", schema_id);
 
-    let schema_handle = _store_schema(source_id, name, version, schema_id, data, None, PublicEntityStateType::Built)?;
+    let schema_handle = _store_schema(profile_handle, source_id, name, version, schema_id, data, None, PublicEntityStateType::Built)?;
 
     Ok((schema_handle, schema_request))
 }
 
-fn _store_schema(source_id: &str,
+fn _store_schema(profile_handle: Option<u32>,
+                 source_id: &str,
                  name: String,
                  version: String,
                  schema_id: String,
@@ -123,16 +172,22 @@ fn _store_schema(source_id: &str,
         schema_id,
         payment_txn,
         state,
+        profile: ProfileContext::from_handle(profile_handle)?,
     };
 
-    SCHEMA_MAP.add(schema)
+    SCHEMA_MAP.add_in_profile(profile_handle, schema)
         .or(Err(VcxError::from(VcxErrorKind::CreateSchema)))
 }
 
 pub fn get_schema_attrs(source_id: String, schema_id: String) -> VcxResult<(u32, String)> {
-    trace!("get_schema_attrs >>> source_id: {}, schema_id: {}", source_id, schema_id);
+    get_schema_attrs_in_profile(None, source_id, schema_id)
+}
+
+pub fn get_schema_attrs_in_profile(profile_handle: Option<u32>, source_id: String, schema_id: String) -> VcxResult<(u32, String)> {
+    trace!("get_schema_attrs >>> profile_handle: {:?}, source_id: {}, schema_id: {}", profile_handle, source_id, schema_id);
 
-    let (schema_id, schema_data_json) = anoncreds::get_schema_json(&schema_id)
+    let profile = ProfileContext::from_handle(profile_handle)?;
+    let (schema_id, schema_data_json) = anoncreds::get_schema_json(&profile, &schema_id)
         .map_err(|err| err.map(VcxErrorKind::InvalidSchemaSeqNo, "Schema not found"))?;
 
     let schema_data: SchemaData = serde_json::from_str(&schema_data_json)
@@ -146,11 +201,12 @@ pub fn get_schema_attrs(source_id: String, schema_id: String) -> VcxResult<(u32,
         data: schema_data.attr_names,
         payment_txn: None,
         state: PublicEntityStateType::Published,
+        profile,
     };
 
     let schema_json = schema.to_string()?;
 
-    let handle = SCHEMA_MAP.add(schema)
+    let handle = SCHEMA_MAP.add_in_profile(profile_handle, schema)
         .or(Err(VcxError::from(VcxErrorKind::CreateSchema)))?;
 
     Ok((handle, schema_json))
@@ -160,6 +216,10 @@ pub fn is_valid_handle(handle: u32) -> bool {
     SCHEMA_MAP.has_handle(handle)
 }
 
+pub fn get_profile_handle(handle: u32) -> Option<u32> {
+    SCHEMA_MAP.get_profile_handle(handle)
+}
+
 pub fn to_string(handle: u32) -> VcxResult<String> {
     SCHEMA_MAP.get(handle, |s| {
         s.to_string()
@@ -185,8 +245,12 @@ pub fn get_payment_txn(handle: u32) -> VcxResult<PaymentTxn> {
 }
 
 pub fn from_string(schema_data: &str) -> VcxResult<u32> {
-    let schema: CreateSchema = CreateSchema::from_str(schema_data)?;
-    SCHEMA_MAP.add(schema)
+    from_string_in_profile(None, schema_data)
+}
+
+pub fn from_string_in_profile(profile_handle: Option<u32>, schema_data: &str) -> VcxResult<u32> {
+    let schema = _deserialize(&ProfileContext::from_handle(profile_handle)?, schema_data)?;
+    SCHEMA_MAP.add_in_profile(profile_handle, schema)
 }
 
 pub fn release(handle: u32) -> VcxResult<()> {
@@ -413,7 +477,7 @@ pub mod tests {
         assert_eq!(0, update_state(handle).unwrap());
 
         settings::set_config_value(settings::CONFIG_INSTITUTION_DID, &endorser_did);
-        ledger::endorse_transaction(&schema_request).unwrap();
+        ledger::endorse_transaction(&ProfileContext::Global, &schema_request).unwrap();
 
         std::thread::sleep(std::time::Duration::from_millis(1000));
 
@@ -109,7 +109,10 @@ impl From<VcxErrorKind> for u32 {
             VcxErrorKind::NoAgentInformation => error::NO_AGENT_INFO.code_num,
             VcxErrorKind::RevRegDefNotFound => error::REV_REG_DEF_NOT_FOUND.code_num,
             VcxErrorKind::RevDeltaNotFound => error::REV_DELTA_NOT_FOUND.code_num,
-            VcxErrorKind::PoisonedLock => error::POISONED_LOCK.code_num
+            VcxErrorKind::PoisonedLock => error::POISONED_LOCK.code_num,
+            VcxErrorKind::InvalidProfileHandle => error::INVALID_PROFILE_HANDLE.code_num,
+            VcxErrorKind::InvalidMediationHandle => error::INVALID_MEDIATION_HANDLE.code_num,
+            VcxErrorKind::ObjectNotPersisted => error::OBJECT_NOT_PERSISTED.code_num
         }
     }
 }
@@ -202,6 +205,9 @@ impl From<u32> for VcxErrorKind {
             _ if { error::NO_AGENT_INFO.code_num == code } => VcxErrorKind::NoAgentInformation,
             _ if { error::REV_REG_DEF_NOT_FOUND.code_num == code } => VcxErrorKind::RevRegDefNotFound,
             _ if { error::REV_DELTA_NOT_FOUND.code_num == code } => VcxErrorKind::RevDeltaNotFound,
+            _ if { error::INVALID_PROFILE_HANDLE.code_num == code } => VcxErrorKind::InvalidProfileHandle,
+            _ if { error::INVALID_MEDIATION_HANDLE.code_num == code } => VcxErrorKind::InvalidMediationHandle,
+            _ if { error::OBJECT_NOT_PERSISTED.code_num == code } => VcxErrorKind::ObjectNotPersisted,
             _ => VcxErrorKind::UnknownError,
         }
     }
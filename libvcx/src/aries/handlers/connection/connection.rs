@@ -5,6 +5,7 @@ use std::fmt::Formatter;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde::de::{EnumAccess, Error, MapAccess, SeqAccess, Unexpected, Visitor};
 use serde_json::Value;
+use futures::future::{FutureExt, LocalBoxFuture};
 
 use agency_client::get_message::{Message, MessageByConnection};
 use agency_client::MessageStatusCode;
@@ -14,17 +15,26 @@ use crate::aries::handlers::connection::invitee::state_machine::{InviteeFullStat
 use crate::aries::handlers::connection::inviter::state_machine::{InviterFullState, InviterState, SmConnectionInviter};
 use crate::aries::handlers::connection::legacy_agent_info::LegacyAgentInfo;
 use crate::aries::handlers::connection::pairwise_info::PairwiseInfo;
+use crate::aries::handlers::mediation::message_pickup;
 use crate::aries::messages::a2a::A2AMessage;
 use crate::aries::messages::basic_message::message::BasicMessage;
 use crate::aries::messages::connection::did_doc::DidDoc;
-use crate::aries::messages::connection::invite::Invitation;
+use crate::aries::messages::connection::handshake_protocol::HandshakeProtocol;
+use crate::aries::messages::connection::invite::AnyInvitation;
+use crate::aries::messages::connection::request::AnyRequest;
+use crate::aries::messages::connection::response::AnyResponse;
 use crate::aries::messages::discovery::disclose::ProtocolDescriptor;
-use crate::aries::utils::send_message;
+use crate::aries::utils::encryption_envelope::EncryptionEnvelope;
+use crate::aries::utils::task::block_on;
+use crate::aries::utils::{send_message, send_message_async, send_message_boxed};
+use crate::aries::utils::task::run_blocking;
 use crate::error::prelude::*;
+use crate::profile::ProfileContext;
 use crate::utils::serialization::SerializableObjectWithState;
 
 #[derive(Clone)]
 pub struct Connection {
+    profile: ProfileContext,
     connection_sm: SmConnection,
     cloud_agent_info: CloudAgentInfo,
     autohop_enabled: bool,
@@ -76,46 +86,72 @@ impl Connection {
     /**
     Create Inviter connection state machine
      */
-    pub fn create(source_id: &str, autohop: bool) -> VcxResult<Connection> {
-        trace!("Connection::create >>> source_id: {}", source_id);
-        let pairwise_info = PairwiseInfo::create()?;
-        let cloud_agent_info = CloudAgentInfo::create(&pairwise_info)?;
+    pub fn create(profile: ProfileContext, source_id: &str, autohop: bool) -> VcxResult<Connection> {
+        Connection::create_with_handshake_protocol(profile, source_id, HandshakeProtocol::Connections, autohop)
+    }
+
+    /**
+    Create Inviter connection state machine establishing connection with the given handshake protocol.
+    For didexchange/1.0 the invitation is generated as out-of-band/1.0 invitation.
+     */
+    pub fn create_with_handshake_protocol(profile: ProfileContext, source_id: &str, protocol: HandshakeProtocol, autohop: bool) -> VcxResult<Connection> {
+        trace!("Connection::create_with_handshake_protocol >>> profile: {:?}, source_id: {}, protocol: {:?}", profile, source_id, protocol);
+        let pairwise_info = PairwiseInfo::create(profile.wallet_handle()?)?;
+        let cloud_agent_info = CloudAgentInfo::create(&profile, &pairwise_info)?;
         Ok(Connection {
             cloud_agent_info,
-            connection_sm: SmConnection::Inviter(SmConnectionInviter::new(source_id, pairwise_info, send_message)),
+            connection_sm: SmConnection::Inviter(SmConnectionInviter::new(source_id, pairwise_info, protocol, profile.clone(), send_message_boxed)),
+            profile,
             autohop_enabled: autohop,
         })
     }
 
     /**
-    Create Invitee connection state machine
+    Create Invitee connection state machine. Accepts connections/1.0 as well as out-of-band/1.0 invitation.
      */
-    pub fn create_with_invite(source_id: &str, invitation: Invitation, autohop_enabled: bool) -> VcxResult<Connection> {
-        trace!("Connection::create_with_invite >>> source_id: {}", source_id);
-        let pairwise_info = PairwiseInfo::create()?;
-        let cloud_agent_info = CloudAgentInfo::create(&pairwise_info)?;
+    pub fn create_with_invite(profile: ProfileContext, source_id: &str, invitation: AnyInvitation, autohop_enabled: bool) -> VcxResult<Connection> {
+        trace!("Connection::create_with_invite >>> profile: {:?}, source_id: {}", profile, source_id);
+        let pairwise_info = PairwiseInfo::create(profile.wallet_handle()?)?;
+        let cloud_agent_info = CloudAgentInfo::create(&profile, &pairwise_info)?;
         let mut connection = Connection {
             cloud_agent_info,
-            connection_sm: SmConnection::Invitee(SmConnectionInvitee::new(source_id, pairwise_info, send_message)),
+            connection_sm: SmConnection::Invitee(SmConnectionInvitee::new(source_id, pairwise_info, profile.clone(), send_message_boxed)),
+            profile,
             autohop_enabled,
         };
         connection.process_invite(invitation)?;
         Ok(connection)
     }
 
-    pub fn from_parts(source_id: String, pairwise_info: PairwiseInfo, cloud_agent_info: CloudAgentInfo, state: SmConnectionState, autohop_enabled: bool) -> Connection {
+    /**
+    Create Inviter connection state machine waiting for a request on an invitation created earlier.
+    Used to spawn a separate connection for every request received on a multi-use invitation.
+     */
+    pub fn create_invited(profile: ProfileContext, source_id: &str, pairwise_info: PairwiseInfo, cloud_agent_info: CloudAgentInfo, invitation: AnyInvitation, autohop_enabled: bool) -> Connection {
+        trace!("Connection::create_invited >>> profile: {:?}, source_id: {}", profile, source_id);
+        Connection {
+            cloud_agent_info,
+            connection_sm: SmConnection::Inviter(SmConnectionInviter::invited(source_id, pairwise_info, invitation, profile.clone(), send_message_boxed)),
+            profile,
+            autohop_enabled,
+        }
+    }
+
+    pub fn from_parts(profile: ProfileContext, source_id: String, pairwise_info: PairwiseInfo, cloud_agent_info: CloudAgentInfo, state: SmConnectionState, autohop_enabled: bool) -> Connection {
         match state {
             SmConnectionState::Inviter(state) => {
                 Connection {
                     cloud_agent_info,
-                    connection_sm: SmConnection::Inviter(SmConnectionInviter::from(source_id, pairwise_info, state, send_message)),
+                    connection_sm: SmConnection::Inviter(SmConnectionInviter::from(source_id, pairwise_info, state, profile.clone(), send_message_boxed)),
+                    profile,
                     autohop_enabled,
                 }
             }
             SmConnectionState::Invitee(state) => {
                 Connection {
                     cloud_agent_info,
-                    connection_sm: SmConnection::Invitee(SmConnectionInvitee::from(source_id, pairwise_info, state, send_message)),
+                    connection_sm: SmConnection::Invitee(SmConnectionInvitee::from(source_id, pairwise_info, state, profile.clone(), send_message_boxed)),
+                    profile,
                     autohop_enabled,
                 }
             }
@@ -155,6 +191,10 @@ impl Connection {
         }
     }
 
+    pub fn profile(&self) -> &ProfileContext {
+        &self.profile
+    }
+
     pub fn cloud_agent_info(&self) -> CloudAgentInfo {
         self.cloud_agent_info.clone()
     }
@@ -259,24 +299,15 @@ impl Connection {
     /**
     Invitee operation
      */
-    pub fn process_invite(&mut self, invitation: Invitation) -> VcxResult<()> {
-        trace!("Connection::process_invite >>> invitation: {:?}", invitation);
-        self.connection_sm = match &self.connection_sm {
-            SmConnection::Inviter(_sm_inviter) => {
-                return Err(VcxError::from_msg(VcxErrorKind::NotReady, "Invalid action"));
-            }
-            SmConnection::Invitee(sm_invitee) => {
-                SmConnection::Invitee(sm_invitee.clone().handle_invitation(invitation)?)
-            }
-        };
-        Ok(())
+    pub fn process_invite(&mut self, invitation: AnyInvitation) -> VcxResult<()> {
+        block_on(self.process_invite_async(invitation))
     }
 
     /**
     If called on Inviter in Invited state returns invitation to connect with him. Returns error in other states.
     If called on Invitee, returns error
      */
-    pub fn get_invite_details(&self) -> Option<&Invitation> {
+    pub fn get_invite_details(&self) -> Option<&AnyInvitation> {
         trace!("Connection::get_invite_details >>>");
         match &self.connection_sm {
             SmConnection::Inviter(sm_inviter) => {
@@ -323,96 +354,63 @@ impl Connection {
     //     Ok(None)
     // }
 
-    fn _update_state(&mut self, message: Option<A2AMessage>) -> VcxResult<()> {
-        let (new_connection_sm, can_autohop) = match &self.connection_sm {
-            SmConnection::Inviter(_) => {
-                self._step_inviter(message)?
-            }
-            SmConnection::Invitee(_) => {
-                self._step_invitee(message)?
+    async fn _update_state(&mut self, mut message: Option<A2AMessage>) -> VcxResult<()> {
+        loop {
+            let (new_connection_sm, can_autohop) = match &self.connection_sm {
+                SmConnection::Inviter(_) => {
+                    self._step_inviter(message.take()).await?
+                }
+                SmConnection::Invitee(_) => {
+                    self._step_invitee(message.take()).await?
+                }
+            };
+            *self = new_connection_sm;
+            if !(can_autohop && self.autohop_enabled.clone()) {
+                return Ok(());
             }
-        };
-        *self = new_connection_sm;
-        if can_autohop && self.autohop_enabled.clone() {
-            self._update_state(None)
-        } else {
-            Ok(())
         }
     }
 
     pub fn update_state(&mut self) -> VcxResult<()> {
-        if self.is_in_null_state() {
-            warn!("Connection::update_state :: update state on connection in null state is ignored");
-            return Ok(());
-        }
-
-        let messages = self.get_messages_noauth()?;
-        trace!("Connection::update_state >>> retrieved messages {:?}", messages);
-
-        match self.find_message_to_handle(messages) {
-            Some((uid, message)) => {
-                trace!("Connection::update_state >>> handling message uid: {:?}", uid);
-                self._update_state(Some(message))?;
-                self.cloud_agent_info().clone().update_message_status(self.pairwise_info(), uid)?;
-            }
-            None => {
-                // Todo: Restore lookup into bootstrap cloud agent
-                // self.bootstrap_agent_info()
-                // if let Some((messages, bootstrap_agent_info)) = self._get_bootstrap_agent_messages(self.remote_vk(), )? {
-                //     if let Some((uid, message)) = self.find_message_to_handle(messages) {
-                //         trace!("Connection::update_state >>> handling message found on bootstrap agent uid: {:?}", uid);
-                //         self._update_state(Some(message))?;
-                //         bootstrap_agent_info.update_message_status(uid)?;
-                //     }
-                // } else {
-                trace!("Connection::update_state >>> trying to update state without message");
-                self._update_state(None)?;
-                // }
-            }
-        }
-        Ok(())
+        block_on(self.update_state_async())
     }
 
     /**
     Perform state machine transition using supplied message.
      */
     pub fn update_state_with_message(&mut self, message: &A2AMessage) -> VcxResult<()> {
-        trace!("Connection: update_state_with_message: {:?}", message);
-        if self.is_in_null_state() {
-            warn!("Connection::update_state_with_message :: update state on connection in null state is ignored");
-            return Ok(());
-        }
-        self._update_state(Some(message.clone()))?;
-        Ok(())
+        block_on(self.update_state_with_message_async(message.clone()))
     }
 
-    fn _step_inviter(&self, message: Option<A2AMessage>) -> VcxResult<(Connection, bool)> {
+    async fn _step_inviter(&self, message: Option<A2AMessage>) -> VcxResult<(Connection, bool)> {
         match self.connection_sm.clone() {
             SmConnection::Inviter(sm_inviter) => {
                 let (sm_inviter, new_cloud_agent_info, can_autohop) = match message {
                     Some(message) => match message {
                         A2AMessage::ConnectionRequest(request) => {
-                            let new_pairwise_info = PairwiseInfo::create()?;
-                            let new_cloud_agent = CloudAgentInfo::create(&new_pairwise_info)?;
-                            let new_routing_keys = new_cloud_agent.routing_keys()?;
-                            let new_service_endpoint = new_cloud_agent.service_endpoint()?;
-                            let sm_connection = sm_inviter.handle_connection_request(request, &new_pairwise_info, new_routing_keys, new_service_endpoint)?;
-                            (sm_connection, Some(new_cloud_agent), true)
+                            self._handle_connection_request(sm_inviter, AnyRequest::Connections(request)).await?
+                        }
+                        A2AMessage::DidExchangeRequest(request) => {
+                            self._handle_connection_request(sm_inviter, AnyRequest::DidExchange(request)).await?
                         }
                         A2AMessage::Ack(ack) => {
                             (sm_inviter.handle_ack(ack)?, None, false)
                         }
+                        A2AMessage::DidExchangeComplete(complete) => {
+                            (sm_inviter.handle_complete(complete)?, None, false)
+                        }
                         A2AMessage::Ping(ping) => {
-                            (sm_inviter.handle_ping(ping)?, None, false)
+                            (sm_inviter.handle_ping(ping).await?, None, false)
                         }
-                        A2AMessage::ConnectionProblemReport(problem_report) => {
+                        A2AMessage::ConnectionProblemReport(problem_report) |
+                        A2AMessage::DidExchangeProblemReport(problem_report) => {
                             (sm_inviter.handle_problem_report(problem_report)?, None, false)
                         }
                         A2AMessage::PingResponse(ping_response) => {
                             (sm_inviter.handle_ping_response(ping_response)?, None, false)
                         }
                         A2AMessage::Query(query) => {
-                            (sm_inviter.handle_discovery_query(query)?, None, false)
+                            (sm_inviter.handle_discovery_query(query).await?, None, false)
                         }
                         A2AMessage::Disclose(disclose) => {
                             (sm_inviter.handle_disclose(disclose)?, None, false)
@@ -423,7 +421,7 @@ impl Connection {
                     }
                     None => {
                         if let InviterFullState::Requested(_) = sm_inviter.state_object() {
-                            (sm_inviter.handle_send_response()?, None, false)
+                            (sm_inviter.handle_send_response().await?, None, false)
                         } else {
                             (sm_inviter.clone(), None, false)
                         }
@@ -431,6 +429,7 @@ impl Connection {
                 };
 
                 let connection = Connection {
+                    profile: self.profile.clone(),
                     cloud_agent_info: new_cloud_agent_info.unwrap_or(self.cloud_agent_info.clone()),
                     connection_sm: SmConnection::Inviter(sm_inviter),
                     autohop_enabled: self.autohop_enabled.clone(),
@@ -446,31 +445,51 @@ impl Connection {
     }
 
 
-    fn _step_invitee(&self, message: Option<A2AMessage>) -> VcxResult<(Connection, bool)> {
+    async fn _handle_connection_request(&self, sm_inviter: SmConnectionInviter, request: AnyRequest) -> VcxResult<(SmConnectionInviter, Option<CloudAgentInfo>, bool)> {
+        let new_pairwise_info = PairwiseInfo::create_async(self.profile.wallet_handle()?).await?;
+        let (profile, pairwise_info) = (self.profile.clone(), new_pairwise_info.clone());
+        let new_cloud_agent = run_blocking(move || CloudAgentInfo::create(&profile, &pairwise_info)).await?;
+        let new_routing_keys = new_cloud_agent.routing_keys(&self.profile)?;
+        let new_service_endpoint = new_cloud_agent.service_endpoint(&self.profile)?;
+        let sm_connection = sm_inviter.handle_connection_request(request, &new_pairwise_info, new_routing_keys, new_service_endpoint).await?;
+        Ok((sm_connection, Some(new_cloud_agent), true))
+    }
+
+    async fn _step_invitee(&self, message: Option<A2AMessage>) -> VcxResult<(Connection, bool)> {
         match self.connection_sm.clone() {
             SmConnection::Invitee(sm_invitee) => {
                 let (sm_invitee, can_autohop) = match message {
                     Some(message) => match message {
                         A2AMessage::ConnectionInvitation(invitation) => {
-                            (sm_invitee.handle_invitation(invitation)?, false)
+                            (sm_invitee.handle_invitation(AnyInvitation::Connections(invitation)).await?, false)
+                        }
+                        A2AMessage::ConnectionInvitationPublic(invitation) => {
+                            (sm_invitee.handle_invitation(AnyInvitation::Public(invitation)).await?, false)
+                        }
+                        A2AMessage::OutOfBandInvitation(invitation) => {
+                            (sm_invitee.handle_invitation(AnyInvitation::OutOfBand(invitation)).await?, false)
                         }
                         A2AMessage::ConnectionResponse(response) => {
-                            (sm_invitee.handle_connection_response(response)?, true)
+                            (sm_invitee.handle_connection_response(AnyResponse::Connections(response))?, true)
+                        }
+                        A2AMessage::DidExchangeResponse(response) => {
+                            (sm_invitee.handle_connection_response(AnyResponse::DidExchange(response))?, true)
                         }
                         A2AMessage::Ack(ack) => {
                             (sm_invitee.handle_ack(ack)?, false)
                         }
                         A2AMessage::Ping(ping) => {
-                            (sm_invitee.handle_ping(ping)?, false)
+                            (sm_invitee.handle_ping(ping).await?, false)
                         }
-                        A2AMessage::ConnectionProblemReport(problem_report) => {
+                        A2AMessage::ConnectionProblemReport(problem_report) |
+                        A2AMessage::DidExchangeProblemReport(problem_report) => {
                             (sm_invitee.handle_problem_report(problem_report)?, false)
                         }
                         A2AMessage::PingResponse(ping_response) => {
                             (sm_invitee.handle_ping_response(ping_response)?, false)
                         }
                         A2AMessage::Query(query) => {
-                            (sm_invitee.handle_discovery_query(query)?, false)
+                            (sm_invitee.handle_discovery_query(query).await?, false)
                         }
                         A2AMessage::Disclose(disclose) => {
                             (sm_invitee.handle_disclose(disclose)?, false)
@@ -480,10 +499,11 @@ impl Connection {
                         }
                     }
                     None => {
-                        (sm_invitee.handle_send_ack()?, false)
+                        (sm_invitee.handle_send_ack().await?, false)
                     }
                 };
                 let connection = Connection {
+                    profile: self.profile.clone(),
                     connection_sm: SmConnection::Invitee(sm_invitee),
                     cloud_agent_info: self.cloud_agent_info.clone(),
                     autohop_enabled: self.autohop_enabled.clone(),
@@ -502,37 +522,35 @@ impl Connection {
     If called on Invitee, creates connection agent and send connection request using info from connection invitation
      */
     pub fn connect(&mut self) -> VcxResult<()> {
-        trace!("Connection::connect >>> source_id: {}", self.source_id());
-        self.connection_sm = match &self.connection_sm {
-            SmConnection::Inviter(sm_inviter) => {
-                SmConnection::Inviter(sm_inviter.clone().handle_connect(self.cloud_agent_info.routing_keys()?, self.cloud_agent_info.service_endpoint()?)?)
-            }
-            SmConnection::Invitee(sm_invitee) => {
-                SmConnection::Invitee(sm_invitee.clone().handle_connect(self.cloud_agent_info.routing_keys()?, self.cloud_agent_info.service_endpoint()?)?)
-            }
-        };
-        Ok(())
+        block_on(self.connect_async())
     }
 
     /**
     Updates status of a message (received from connection counterparty) in agency.
+    Messages picked up from the mediator are acknowledged to the mediator and removed from the inbox instead.
      */
     pub fn update_message_status(&self, uid: String) -> VcxResult<()> {
         trace!("Connection::update_message_status >>> uid: {:?}", uid);
-        self.cloud_agent_info().update_message_status(self.pairwise_info(), uid)
+        if message_pickup::acknowledge_inbox_message(&self.profile, &uid)? {
+            return Ok(());
+        }
+        self.cloud_agent_info().update_message_status(&self.profile, self.pairwise_info(), uid)
     }
 
     /**
 Get messages received from connection counterparty.
  */
     pub fn get_messages_noauth(&self) -> VcxResult<HashMap<String, A2AMessage>> {
+        if message_pickup::picks_up_messages_for(&self.profile, &self.pairwise_info().pw_vk)? {
+            return self.get_picked_up_messages(None);
+        }
         match &self.connection_sm {
             SmConnection::Inviter(sm_inviter) => {
-                let messages = self.cloud_agent_info().get_messages_noauth(sm_inviter.pairwise_info())?;
+                let messages = self.cloud_agent_info().get_messages_noauth(&self.profile, sm_inviter.pairwise_info())?;
                 Ok(messages)
             }
             SmConnection::Invitee(sm_invitee) => {
-                let messages = self.cloud_agent_info().get_messages_noauth(sm_invitee.pairwise_info())?;
+                let messages = self.cloud_agent_info().get_messages_noauth(&self.profile, sm_invitee.pairwise_info())?;
                 Ok(messages)
             }
         }
@@ -543,18 +561,44 @@ Get messages received from connection counterparty.
      */
     pub fn get_messages(&self) -> VcxResult<HashMap<String, A2AMessage>> {
         let expected_sender_vk = self.get_expected_sender_vk()?;
+        if message_pickup::picks_up_messages_for(&self.profile, &self.pairwise_info().pw_vk)? {
+            return self.get_picked_up_messages(Some(&expected_sender_vk));
+        }
         match &self.connection_sm {
             SmConnection::Inviter(sm_inviter) => {
-                let messages = self.cloud_agent_info().get_messages(&expected_sender_vk, sm_inviter.pairwise_info())?;
+                let messages = self.cloud_agent_info().get_messages(&self.profile, &expected_sender_vk, sm_inviter.pairwise_info())?;
                 Ok(messages)
             }
             SmConnection::Invitee(sm_invitee) => {
-                let messages = self.cloud_agent_info().get_messages(&expected_sender_vk, sm_invitee.pairwise_info())?;
+                let messages = self.cloud_agent_info().get_messages(&self.profile, &expected_sender_vk, sm_invitee.pairwise_info())?;
                 Ok(messages)
             }
         }
     }
 
+    /**
+    Picks up messages from the mediator and returns those addressed to this connection,
+    messages which cannot be unpacked (or authenticated if `expected_sender_vk` is set) are discarded.
+     */
+    fn get_picked_up_messages(&self, expected_sender_vk: Option<&str>) -> VcxResult<HashMap<String, A2AMessage>> {
+        message_pickup::pickup_messages(&self.profile)?;
+        let mut messages = HashMap::new();
+        for message in message_pickup::get_inbox_messages(&self.profile, &self.pairwise_info().pw_vk)? {
+            let unpacked = match expected_sender_vk {
+                Some(expected_sender_vk) => EncryptionEnvelope::auth_unpack(self.profile.wallet_handle()?, message.payload, expected_sender_vk),
+                None => EncryptionEnvelope::anon_unpack(self.profile.wallet_handle()?, message.payload)
+            };
+            match unpacked {
+                Ok(unpacked) => { messages.insert(message.id, unpacked); }
+                Err(err) => {
+                    warn!("Connection::get_picked_up_messages >>> discarding message {}: {}", message.id, err);
+                    message_pickup::acknowledge_inbox_message(&self.profile, &message.id)?;
+                }
+            }
+        }
+        Ok(messages)
+    }
+
     fn get_expected_sender_vk(&self) -> VcxResult<String> {
         self.remote_vk()
             .map_err(|_err|
@@ -569,7 +613,63 @@ Get messages received from connection counterparty.
     pub fn get_message_by_id(&self, msg_id: &str) -> VcxResult<A2AMessage> {
         trace!("Connection: get_message_by_id >>> msg_id: {}", msg_id);
         let expected_sender_vk = self.get_expected_sender_vk()?;
-        self.cloud_agent_info().get_message_by_id(msg_id, &expected_sender_vk, self.pairwise_info())
+        self.cloud_agent_info().get_message_by_id(&self.profile, msg_id, &expected_sender_vk, self.pairwise_info())
+    }
+
+    /**
+    Unpacks message received directly from connection counterparty (without cloud agency).
+    Sender is authenticated once verkey of connection counterparty is known, before that only the request
+    starting the handshake is accepted as it is the message which tells the verkey.
+    Invitee waiting for the response knows only the invitation key, the response is authenticated by
+    the key of the DIDDoc it carries (signed by the invitation key), other messages by the invitation key.
+     */
+    pub fn unpack_message(&self, payload: Vec<u8>) -> VcxResult<A2AMessage> {
+        trace!("Connection::unpack_message >>> processing payload of {} bytes", payload.len());
+        if let SmConnection::Invitee(sm_invitee) = &self.connection_sm {
+            if let InviteeFullState::Invited(_) | InviteeFullState::Requested(_) = sm_invitee.state_object() {
+                return self._unpack_response(payload);
+            }
+        }
+        match self.remote_vk() {
+            Ok(expected_sender_vk) => EncryptionEnvelope::auth_unpack(self.profile.wallet_handle()?, payload, &expected_sender_vk),
+            Err(_) => {
+                match EncryptionEnvelope::anon_unpack(self.profile.wallet_handle()?, payload)? {
+                    message @ A2AMessage::ConnectionRequest(_) |
+                    message @ A2AMessage::DidExchangeRequest(_) => Ok(message),
+                    _ => Err(VcxError::from_msg(VcxErrorKind::NotReady, "Verkey of connection counterparty is not known, \
+                    only connection request can be accepted without authentication"))
+                }
+            }
+        }
+    }
+
+    fn _unpack_response(&self, payload: Vec<u8>) -> VcxResult<A2AMessage> {
+        let invitation_vk = self.remote_vk()?;
+        EncryptionEnvelope::auth_unpack_with(self.profile.wallet_handle()?, payload, |message, sender_vk| {
+            let expected_sender_vk = match Self::_response_recipient_vk(message, &invitation_vk)? {
+                Some(response_vk) => response_vk,
+                None => invitation_vk.clone()
+            };
+            if sender_vk != expected_sender_vk {
+                return Err(VcxError::from_msg(VcxErrorKind::InvalidJson,
+                                              format!("Message did not pass authentication check. Expected sender verkey was {}, but actually was {}", expected_sender_vk, sender_vk)));
+            }
+            Ok(())
+        })
+    }
+
+    /**
+    Verkey of the DIDDoc of connection response signed by the invitation key, `None` for other messages.
+     */
+    fn _response_recipient_vk(message: &A2AMessage, invitation_vk: &str) -> VcxResult<Option<String>> {
+        let response = match message {
+            A2AMessage::ConnectionResponse(response) => AnyResponse::Connections(response.clone()),
+            A2AMessage::DidExchangeResponse(response) => AnyResponse::DidExchange(response.clone()),
+            _ => return Ok(None)
+        };
+        response.decode(invitation_vk)?.connection.did_doc.recipient_keys().get(0).cloned()
+            .map(Some)
+            .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, "Connection response DIDDoc does not contain recipient key"))
     }
 
     pub fn send_message_closure(&self) -> VcxResult<impl Fn(&A2AMessage) -> VcxResult<()>> {
@@ -577,8 +677,26 @@ Get messages received from connection counterparty.
         let did_doc = self.their_did_doc()
             .ok_or(VcxError::from_msg(VcxErrorKind::NotReady, "Cannot send message: Remote Connection information is not set"))?;
         let sender_vk = self.pairwise_info().pw_vk.clone();
+        let wallet_handle = self.profile.wallet_handle()?;
         return Ok(move |a2a_message: &A2AMessage| {
-            send_message(&sender_vk, &did_doc, a2a_message)
+            send_message(wallet_handle, &sender_vk, &did_doc, a2a_message)
+        });
+    }
+
+    /**
+    Like `send_message_closure`, but the returned closure sends the message asynchronously.
+     */
+    pub fn send_message_closure_async(&self) -> VcxResult<impl Fn(A2AMessage) -> LocalBoxFuture<'static, VcxResult<()>>> {
+        trace!("send_message_closure_async >>>");
+        let did_doc = self.their_did_doc()
+            .ok_or(VcxError::from_msg(VcxErrorKind::NotReady, "Cannot send message: Remote Connection information is not set"))?;
+        let sender_vk = self.pairwise_info().pw_vk.clone();
+        let wallet_handle = self.profile.wallet_handle()?;
+        return Ok(move |a2a_message: A2AMessage| {
+            let (did_doc, sender_vk) = (did_doc.clone(), sender_vk.clone());
+            async move {
+                send_message_async(wallet_handle, &sender_vk, &did_doc, &a2a_message).await
+            }.boxed_local()
         });
     }
 
@@ -595,42 +713,20 @@ Get messages received from connection counterparty.
     }
 
     pub fn send_generic_message(&self, message: &str) -> VcxResult<String> {
-        trace!("Connection::send_generic_message >>> message: {:?}", message);
-
-        let message = Connection::parse_generic_message(message);
-        let send_message = self.send_message_closure()?;
-        send_message(&message).map(|_| String::new())
+        block_on(self.send_generic_message_async(message))
     }
 
     pub fn send_ping(&mut self, comment: Option<String>) -> VcxResult<()> {
-        trace!("Connection::send_ping >>> comment: {:?}", comment);
-        self.connection_sm = match &self.connection_sm {
-            SmConnection::Inviter(sm_inviter) => {
-                SmConnection::Inviter(sm_inviter.clone().handle_send_ping(comment)?)
-            }
-            SmConnection::Invitee(sm_invitee) => {
-                SmConnection::Invitee(sm_invitee.clone().handle_send_ping(comment)?)
-            }
-        };
-        Ok(())
+        block_on(self.send_ping_async(comment))
     }
 
     pub fn delete(&self) -> VcxResult<()> {
         trace!("Connection: delete >>> {:?}", self.source_id());
-        self.cloud_agent_info().destroy(self.pairwise_info())
+        self.cloud_agent_info().destroy(&self.profile, self.pairwise_info())
     }
 
     pub fn send_discovery_features(&mut self, query: Option<String>, comment: Option<String>) -> VcxResult<()> {
-        trace!("Connection::send_discovery_features_query >>> query: {:?}, comment: {:?}", query, comment);
-        self.connection_sm = match &self.connection_sm {
-            SmConnection::Inviter(sm_inviter) => {
-                SmConnection::Inviter(sm_inviter.clone().handle_discover_features(query, comment)?)
-            }
-            SmConnection::Invitee(sm_invitee) => {
-                SmConnection::Invitee(sm_invitee.clone().handle_discover_features(query, comment)?)
-            }
-        };
-        Ok(())
+        block_on(self.send_discovery_features_async(query, comment))
     }
 
     pub fn get_connection_info(&self) -> VcxResult<String> {
@@ -643,8 +739,8 @@ Get messages received from connection counterparty.
         let current = SideConnectionInfo {
             did: pairwise_info.pw_did.clone(),
             recipient_keys: recipient_keys.clone(),
-            routing_keys: agent_info.routing_keys()?,
-            service_endpoint: agent_info.service_endpoint()?,
+            routing_keys: agent_info.routing_keys(&self.profile)?,
+            service_endpoint: agent_info.service_endpoint(&self.profile)?,
             protocols: Some(self.get_protocols()),
         };
 
@@ -670,10 +766,11 @@ Get messages received from connection counterparty.
 
     pub fn download_messages(&self, status_codes: Option<Vec<MessageStatusCode>>, uids: Option<Vec<String>>) -> VcxResult<Vec<Message>> {
         let expected_sender_vk = self.remote_vk()?;
+        let agency_client = self.profile.agency_client()?;
         let msgs = self.cloud_agent_info()
-            .download_encrypted_messages(uids, status_codes, self.pairwise_info())?
+            .download_encrypted_messages(&self.profile, uids, status_codes, self.pairwise_info())?
             .iter()
-            .map(|msg| msg.decrypt_auth(&expected_sender_vk).map_err(|err| err.into()))
+            .map(|msg| msg.decrypt_auth(agency_client.as_ref(), &expected_sender_vk).map_err(|err| err.into()))
             .collect::<VcxResult<Vec<Message>>>()?;
         Ok(msgs)
     }
@@ -683,9 +780,147 @@ Get messages received from connection counterparty.
             .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Cannot serialize Connection: {:?}", err)))
     }
 
-    pub fn from_string(connection_data: &str) -> VcxResult<Connection> {
-        serde_json::from_str(connection_data)
-            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize Connection: {:?}", err)))
+    /**
+    Restores serialized connection, the profile is not part of the serialized data and has to be given by the caller.
+     */
+    pub fn from_string(profile: ProfileContext, connection_data: &str) -> VcxResult<Connection> {
+        let object: SerializableObjectWithState<LegacyAgentInfo, SmConnectionState> = serde_json::from_str(connection_data)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize Connection: {:?}", err)))?;
+        Ok(Connection::from_serialized(profile, object))
+    }
+
+    fn from_serialized(profile: ProfileContext, object: SerializableObjectWithState<LegacyAgentInfo, SmConnectionState>) -> Connection {
+        match object {
+            SerializableObjectWithState::V1 { data, state, source_id } => {
+                let pairwise_info = PairwiseInfo { pw_did: data.pw_did, pw_vk: data.pw_vk };
+                let cloud_agent_info = CloudAgentInfo { agent_did: data.agent_did, agent_vk: data.agent_vk };
+                Connection::from_parts(profile, source_id, pairwise_info, cloud_agent_info, state, true)
+            }
+        }
+    }
+}
+
+impl Connection {
+    pub async fn process_invite_async(&mut self, invitation: AnyInvitation) -> VcxResult<()> {
+        trace!("Connection::process_invite_async >>> invitation: {:?}", invitation);
+        self.connection_sm = match &self.connection_sm {
+            SmConnection::Inviter(_sm_inviter) => {
+                return Err(VcxError::from_msg(VcxErrorKind::NotReady, "Invalid action"));
+            }
+            SmConnection::Invitee(sm_invitee) => {
+                SmConnection::Invitee(sm_invitee.clone().handle_invitation(invitation).await?)
+            }
+        };
+        Ok(())
+    }
+
+    pub async fn connect_async(&mut self) -> VcxResult<()> {
+        trace!("Connection::connect_async >>> source_id: {}", self.source_id());
+        let routing_keys = self.cloud_agent_info.routing_keys(&self.profile)?;
+        let service_endpoint = self.cloud_agent_info.service_endpoint(&self.profile)?;
+        self.connection_sm = match &self.connection_sm {
+            SmConnection::Inviter(sm_inviter) => {
+                SmConnection::Inviter(sm_inviter.clone().handle_connect(routing_keys, service_endpoint)?)
+            }
+            SmConnection::Invitee(sm_invitee) => {
+                SmConnection::Invitee(sm_invitee.clone().handle_connect(routing_keys, service_endpoint).await?)
+            }
+        };
+        Ok(())
+    }
+
+    pub async fn update_state_async(&mut self) -> VcxResult<()> {
+        if self.is_in_null_state() {
+            warn!("Connection::update_state_async :: update state on connection in null state is ignored");
+            return Ok(());
+        }
+
+        let connection = self.clone();
+        let messages = run_blocking(move || connection.get_messages_noauth()).await?;
+        trace!("Connection::update_state_async >>> retrieved messages {:?}", messages);
+
+        match self.find_message_to_handle(messages) {
+            Some((uid, message)) => {
+                trace!("Connection::update_state_async >>> handling message uid: {:?}", uid);
+                self._update_state(Some(message)).await?;
+                let (profile, cloud_agent_info, pairwise_info) = (self.profile.clone(), self.cloud_agent_info(), self.pairwise_info().clone());
+                run_blocking(move || cloud_agent_info.update_message_status(&profile, &pairwise_info, uid)).await?;
+            }
+            None => {
+                // Todo: Restore lookup into bootstrap cloud agent
+                // self.bootstrap_agent_info()
+                // if let Some((messages, bootstrap_agent_info)) = self._get_bootstrap_agent_messages(self.remote_vk(), )? {
+                //     if let Some((uid, message)) = self.find_message_to_handle(messages) {
+                //         trace!("Connection::update_state >>> handling message found on bootstrap agent uid: {:?}", uid);
+                //         self._update_state(Some(message))?;
+                //         bootstrap_agent_info.update_message_status(uid)?;
+                //     }
+                // } else {
+                trace!("Connection::update_state_async >>> trying to update state without message");
+                self._update_state(None).await?;
+                // }
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn update_state_with_message_async(&mut self, message: A2AMessage) -> VcxResult<()> {
+        trace!("Connection: update_state_with_message_async: {:?}", message);
+        if self.is_in_null_state() {
+            warn!("Connection::update_state_with_message_async :: update state on connection in null state is ignored");
+            return Ok(());
+        }
+        self._update_state(Some(message)).await
+    }
+
+    pub async fn update_message_status_async(&self, uid: String) -> VcxResult<()> {
+        let connection = self.clone();
+        run_blocking(move || connection.update_message_status(uid)).await
+    }
+
+    pub async fn get_messages_async(&self) -> VcxResult<HashMap<String, A2AMessage>> {
+        let connection = self.clone();
+        run_blocking(move || connection.get_messages()).await
+    }
+
+    pub async fn send_generic_message_async(&self, message: &str) -> VcxResult<String> {
+        trace!("Connection::send_generic_message_async >>> message: {:?}", message);
+        let did_doc = self.their_did_doc()
+            .ok_or(VcxError::from_msg(VcxErrorKind::NotReady, "Cannot send message: Remote Connection information is not set"))?;
+        let message = Connection::parse_generic_message(message);
+        send_message_async(self.profile.wallet_handle()?, &self.pairwise_info().pw_vk, &did_doc, &message).await
+            .map(|_| String::new())
+    }
+
+    pub async fn send_ping_async(&mut self, comment: Option<String>) -> VcxResult<()> {
+        trace!("Connection::send_ping_async >>> comment: {:?}", comment);
+        self.connection_sm = match &self.connection_sm {
+            SmConnection::Inviter(sm_inviter) => {
+                SmConnection::Inviter(sm_inviter.clone().handle_send_ping(comment).await?)
+            }
+            SmConnection::Invitee(sm_invitee) => {
+                SmConnection::Invitee(sm_invitee.clone().handle_send_ping(comment).await?)
+            }
+        };
+        Ok(())
+    }
+
+    pub async fn send_discovery_features_async(&mut self, query: Option<String>, comment: Option<String>) -> VcxResult<()> {
+        trace!("Connection::send_discovery_features_async >>> query: {:?}, comment: {:?}", query, comment);
+        self.connection_sm = match &self.connection_sm {
+            SmConnection::Inviter(sm_inviter) => {
+                SmConnection::Inviter(sm_inviter.clone().handle_discover_features(query, comment).await?)
+            }
+            SmConnection::Invitee(sm_invitee) => {
+                SmConnection::Invitee(sm_invitee.clone().handle_discover_features(query, comment).await?)
+            }
+        };
+        Ok(())
+    }
+
+    pub async fn delete_async(&self) -> VcxResult<()> {
+        let connection = self.clone();
+        run_blocking(move || connection.delete()).await
     }
 }
 
@@ -727,13 +962,7 @@ impl<'de> Visitor<'de> for ConnectionVisitor {
         let obj = Value::from(map_value);
         let ver: SerializableObjectWithState<LegacyAgentInfo, SmConnectionState> = serde_json::from_value(obj)
             .map_err(|err| A::Error::custom(err.to_string()))?;
-        match ver {
-            SerializableObjectWithState::V1 { data, state, source_id } => {
-                let pairwise_info = PairwiseInfo { pw_did: data.pw_did, pw_vk: data.pw_vk };
-                let cloud_agent_info = CloudAgentInfo { agent_did: data.agent_did, agent_vk: data.agent_vk };
-                Ok((state, pairwise_info, cloud_agent_info, source_id).into())
-            }
-        }
+        Ok(Connection::from_serialized(ProfileContext::Global, ver))
     }
 }
 
@@ -751,6 +980,7 @@ pub mod tests {
     use std::thread;
     use std::time::Duration;
 
+    use futures::future;
     use serde_json::Value;
 
     use agency_client::get_message::download_messages_noauth;
@@ -760,6 +990,11 @@ pub mod tests {
 
     use crate::{aries, settings, utils};
     use crate::aries::messages::ack::tests::_ack;
+    use crate::aries::messages::connection::invite::Invitation;
+    use crate::aries::messages::connection::response::Response;
+    use crate::aries::utils::task::block_on;
+    use crate::libindy::utils::tests::test_setup;
+    use crate::libindy::utils::wallet::get_wallet_handle;
     use crate::utils::constants;
     use crate::utils::devsetup::*;
     use crate::utils::devsetup_agent::test::{Alice, Faber, TestAgent};
@@ -772,7 +1007,7 @@ pub mod tests {
     fn test_deserialize_connection_inviter_completed() {
         let _setup = SetupMocks::init();
 
-        let connection = Connection::from_string(CONNECTION_SM_INVITER_COMPLETED).unwrap();
+        let connection = Connection::from_string(ProfileContext::Global, CONNECTION_SM_INVITER_COMPLETED).unwrap();
         let _second_string = connection.to_string();
 
         assert_eq!(connection.pairwise_info().pw_did, "2ZHFFhzA2XtTD6hJqzL7ux");
@@ -784,7 +1019,7 @@ pub mod tests {
 
     fn test_deserialize_and_serialize(sm_serialized: &str) {
         let original_object: Value = serde_json::from_str(sm_serialized).unwrap();
-        let connection = Connection::from_string(sm_serialized).unwrap();
+        let connection = Connection::from_string(ProfileContext::Global, sm_serialized).unwrap();
         let reserialized = connection.to_string().unwrap();
         let reserialized_object: Value = serde_json::from_str(&reserialized).unwrap();
 
@@ -802,26 +1037,100 @@ pub mod tests {
         test_deserialize_and_serialize(CONNECTION_SM_INVITER_COMPLETED);
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_update_state_with_message_async() {
+        let _setup = SetupMocks::init();
+
+        let mut connection = Connection::create(ProfileContext::Global, "test_update_state_with_message_async", true).unwrap();
+        block_on(connection.connect_async()).unwrap();
+        assert_eq!(connection.get_state(), ConnectionState::Inviter(InviterState::Invited));
+
+        let request: A2AMessage = serde_json::from_str(ARIES_CONNECTION_REQUEST).unwrap();
+        block_on(connection.update_state_with_message_async(request)).unwrap();
+        assert_eq!(connection.get_state(), ConnectionState::Inviter(InviterState::Responded));
+
+        let ack: A2AMessage = serde_json::from_str(ARIES_CONNECTION_ACK).unwrap();
+        block_on(connection.update_state_with_message_async(ack)).unwrap();
+        assert_eq!(connection.get_state(), ConnectionState::Inviter(InviterState::Completed));
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_serialize_deserialize() {
         let _setup = SetupMocks::init();
 
-        let connection = Connection::create("test_serialize_deserialize", true).unwrap();
+        let connection = Connection::create(ProfileContext::Global, "test_serialize_deserialize", true).unwrap();
         let first_string = connection.to_string().unwrap();
 
-        let connection2 = Connection::from_string(&first_string).unwrap();
+        let connection2 = Connection::from_string(ProfileContext::Global, &first_string).unwrap();
         let second_string = connection2.to_string().unwrap();
 
         assert_eq!(first_string, second_string);
     }
 
+    fn _invitee_requested(invitation_vk: &str) -> Connection {
+        let pairwise_info = PairwiseInfo::create(get_wallet_handle()).unwrap();
+        let invitation = Invitation::create()
+            .set_recipient_keys(vec![invitation_vk.to_string()])
+            .set_service_endpoint(String::from("http://localhost:8080"));
+        let sm_invitee = SmConnectionInvitee::new("invitee", pairwise_info.clone(), ProfileContext::Global, |_, _, _, _| future::ready(Ok(())).boxed_local());
+        let sm_invitee = block_on(sm_invitee.handle_invitation(AnyInvitation::Connections(invitation))).unwrap();
+        let sm_invitee = block_on(sm_invitee.handle_connect(vec![], String::from("http://localhost:8080"))).unwrap();
+        let state = SmConnectionState::Invitee(sm_invitee.state_object().clone());
+        Connection::from_parts(ProfileContext::Global, String::from("invitee"), pairwise_info, CloudAgentInfo::default(), state, true)
+    }
+
+    fn _packed_response(invitee: &Connection, invitation_vk: &str, response_vk: &str, sender_vk: &str) -> Vec<u8> {
+        let response = Response::create()
+            .set_did(String::from("VsKV7grR1BUE29mG2Fm2kX"))
+            .set_keys(vec![response_vk.to_string()], vec![])
+            .set_service_endpoint(String::from("http://localhost:8080"))
+            .encode(get_wallet_handle(), invitation_vk).unwrap();
+        let mut invitee_did_doc = DidDoc::default();
+        invitee_did_doc.set_keys(vec![invitee.pairwise_info().pw_vk.clone()], vec![]);
+        EncryptionEnvelope::create(get_wallet_handle(), &response.to_a2a_message(), Some(sender_vk), &invitee_did_doc).unwrap().0
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_invitee_unpacks_response_sent_by_new_inviter_key() {
+        SetupEmpty::init();
+        let _wallet = test_setup::setup_wallet();
+        let invitation_vk = test_setup::create_key(_wallet.wh);
+        let inviter_pw_vk = test_setup::create_key(_wallet.wh);
+
+        let invitee = _invitee_requested(&invitation_vk);
+        assert_eq!(ConnectionState::Invitee(InviteeState::Requested), invitee.get_state());
+
+        let payload = _packed_response(&invitee, &invitation_vk, &inviter_pw_vk, &inviter_pw_vk);
+        assert_match!(A2AMessage::ConnectionResponse(_), invitee.unpack_message(payload).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_invitee_rejects_response_sent_by_other_key_than_its_diddoc_key() {
+        SetupEmpty::init();
+        let _wallet = test_setup::setup_wallet();
+        let invitation_vk = test_setup::create_key(_wallet.wh);
+        let inviter_pw_vk = test_setup::create_key(_wallet.wh);
+        let other_vk = test_setup::create_key(_wallet.wh);
+
+        let invitee = _invitee_requested(&invitation_vk);
+
+        let payload = _packed_response(&invitee, &invitation_vk, &inviter_pw_vk, &other_vk);
+        assert_eq!(VcxErrorKind::InvalidJson, invitee.unpack_message(payload).unwrap_err().kind());
+
+        let payload = _packed_response(&invitee, &other_vk, &inviter_pw_vk, &inviter_pw_vk);
+        assert_eq!(VcxErrorKind::InvalidJson, invitee.unpack_message(payload).unwrap_err().kind());
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_serialize_deserialize_serde() {
         let _setup = SetupMocks::init();
 
-        let connection = Connection::create("test_serialize_deserialize", true).unwrap();
+        let connection = Connection::create(ProfileContext::Global, "test_serialize_deserialize", true).unwrap();
         let first_string = serde_json::to_string(&connection).unwrap();
 
         let connection: Connection = serde_json::from_str(&first_string).unwrap();
@@ -833,14 +1142,14 @@ pub mod tests {
     pub fn create_connected_connections(consumer: &mut Alice, institution: &mut Faber) -> (Connection, Connection) {
         debug!("Institution is going to create connection.");
         institution.activate().unwrap();
-        let mut institution_to_consumer = Connection::create("consumer", true).unwrap();
+        let mut institution_to_consumer = Connection::create(ProfileContext::Global, "consumer", true).unwrap();
         let _my_public_did = settings::get_config_value(settings::CONFIG_INSTITUTION_DID).unwrap();
         institution_to_consumer.connect().unwrap();
         let details = institution_to_consumer.get_invite_details().unwrap();
 
         consumer.activate().unwrap();
         debug!("Consumer is going to accept connection invitation.");
-        let mut consumer_to_institution = Connection::create_with_invite("institution", details.clone(), true).unwrap();
+        let mut consumer_to_institution = Connection::create_with_invite(ProfileContext::Global, "institution", details.clone(), true).unwrap();
 
         consumer_to_institution.connect().unwrap();
         consumer_to_institution.update_state().unwrap();
@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 
+use indy::WalletHandle;
+
 use crate::aries::handlers::connection::invitee::states::complete::CompleteState;
 use crate::aries::handlers::connection::invitee::states::invited::InvitedState;
 use crate::aries::handlers::connection::invitee::states::null::NullState;
@@ -11,22 +13,28 @@ use crate::aries::messages::a2a::A2AMessage;
 use crate::aries::messages::a2a::protocol_registry::ProtocolRegistry;
 use crate::aries::messages::ack::Ack;
 use crate::aries::messages::connection::did_doc::DidDoc;
-use crate::aries::messages::connection::invite::Invitation;
+use crate::aries::messages::connection::handshake_protocol::HandshakeProtocol;
+use crate::aries::messages::connection::invite::AnyInvitation;
 use crate::aries::messages::connection::problem_report::{ProblemCode, ProblemReport};
-use crate::aries::messages::connection::request::Request;
-use crate::aries::messages::connection::response::{Response, SignedResponse};
+use crate::aries::messages::connection::request::{AnyRequest, Request};
+use crate::aries::messages::connection::response::{AnyResponse, Response};
+use crate::aries::messages::did_exchange::complete::Complete;
+use crate::aries::messages::did_exchange::request::Request as DidExchangeRequest;
 use crate::aries::messages::discovery::disclose::{Disclose, ProtocolDescriptor};
 use crate::aries::messages::discovery::query::Query;
 use crate::aries::messages::trust_ping::ping::Ping;
 use crate::aries::messages::trust_ping::ping_response::PingResponse;
+use crate::aries::utils::SendMessageFn;
 use crate::error::prelude::*;
+use crate::profile::ProfileContext;
 
 #[derive(Clone)]
 pub struct SmConnectionInvitee {
     source_id: String,
     pairwise_info: PairwiseInfo,
     state: InviteeFullState,
-    send_message: fn(&str, &DidDoc, &A2AMessage) -> VcxResult<()>,
+    profile: ProfileContext,
+    send_message: SendMessageFn,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,11 +68,12 @@ impl From<InviteeFullState> for InviteeState {
 }
 
 impl SmConnectionInvitee {
-    pub fn new(source_id: &str, pairwise_info: PairwiseInfo, send_message: fn(&str, &DidDoc, &A2AMessage) -> VcxResult<()>) -> Self {
+    pub fn new(source_id: &str, pairwise_info: PairwiseInfo, profile: ProfileContext, send_message: SendMessageFn) -> Self {
         SmConnectionInvitee {
             source_id: source_id.to_string(),
             state: InviteeFullState::Null(NullState {}),
             pairwise_info,
+            profile,
             send_message,
         }
     }
@@ -73,11 +82,12 @@ impl SmConnectionInvitee {
         return InviteeState::from(self.state.clone()) == InviteeState::Null;
     }
 
-    pub fn from(source_id: String, pairwise_info: PairwiseInfo, state: InviteeFullState, send_message: fn(&str, &DidDoc, &A2AMessage) -> VcxResult<()>) -> Self {
+    pub fn from(source_id: String, pairwise_info: PairwiseInfo, state: InviteeFullState, profile: ProfileContext, send_message: SendMessageFn) -> Self {
         SmConnectionInvitee {
             source_id,
             pairwise_info,
             state,
+            profile,
             send_message,
         }
     }
@@ -108,14 +118,14 @@ impl SmConnectionInvitee {
     pub fn their_did_doc(&self) -> Option<DidDoc> {
         match self.state {
             InviteeFullState::Null(_) => None,
-            InviteeFullState::Invited(ref state) => Some(DidDoc::from(state.invitation.clone())),
+            InviteeFullState::Invited(ref state) => state.did_doc(&self.profile).ok(),
             InviteeFullState::Requested(ref state) => Some(state.did_doc.clone()),
             InviteeFullState::Responded(ref state) => Some(state.did_doc.clone()),
             InviteeFullState::Completed(ref state) => Some(state.did_doc.clone()),
         }
     }
 
-    pub fn get_invitation(&self) -> Option<&Invitation> {
+    pub fn get_invitation(&self) -> Option<&AnyInvitation> {
         match self.state {
             InviteeFullState::Invited(ref state) => Some(&state.invitation),
             _ => None
@@ -156,13 +166,18 @@ impl SmConnectionInvitee {
 
     pub fn can_handle_message(&self, message: &A2AMessage) -> bool {
         match self.state {
-            InviteeFullState::Requested(_) => {
+            InviteeFullState::Requested(ref state) => {
                 match message {
-                    A2AMessage::ConnectionResponse(_) => {
+                    A2AMessage::ConnectionResponse(_) if state.request.protocol() == HandshakeProtocol::Connections => {
                         debug!("Invitee received ConnectionResponse message");
                         true
                     }
-                    A2AMessage::ConnectionProblemReport(_) => {
+                    A2AMessage::DidExchangeResponse(_) if state.request.protocol() == HandshakeProtocol::DidExchange => {
+                        debug!("Invitee received DidExchangeResponse message");
+                        true
+                    }
+                    A2AMessage::ConnectionProblemReport(_) |
+                    A2AMessage::DidExchangeProblemReport(_) => {
                         debug!("Invitee received ProblemReport message");
                         true
                     }
@@ -203,66 +218,108 @@ impl SmConnectionInvitee {
         }
     }
 
-    fn _send_ack(did_doc: &DidDoc,
-                 request: &Request,
-                 response: &SignedResponse,
-                 pairwise_info: &PairwiseInfo,
-                 send_message: fn(&str, &DidDoc, &A2AMessage) -> VcxResult<()>) -> VcxResult<Response> {
+    fn _build_request(invitation: &AnyInvitation,
+                      label: &str,
+                      pairwise_info: &PairwiseInfo,
+                      routing_keys: Vec<String>,
+                      service_endpoint: String) -> VcxResult<AnyRequest> {
+        let recipient_keys = vec!(pairwise_info.pw_vk.clone());
+        match invitation.handshake_protocol()? {
+            HandshakeProtocol::Connections => {
+                let request = Request::create()
+                    .set_label(label.to_string())
+                    .set_did(pairwise_info.pw_did.to_string())
+                    .set_service_endpoint(service_endpoint)
+                    .set_keys(recipient_keys, routing_keys);
+                let request = match invitation.out_of_band_id() {
+                    Some(pthid) => request.set_parent_thread_id(&pthid),
+                    None => request
+                };
+                Ok(AnyRequest::Connections(request))
+            }
+            HandshakeProtocol::DidExchange => {
+                let mut did_doc = DidDoc::default();
+                did_doc.set_id(pairwise_info.pw_did.to_string());
+                did_doc.set_service_endpoint(service_endpoint);
+                did_doc.set_keys(recipient_keys, routing_keys);
+
+                let request = DidExchangeRequest::create(&did_doc)?
+                    .set_label(label.to_string())
+                    .set_parent_thread_id(invitation.id());
+                Ok(AnyRequest::DidExchange(request))
+            }
+        }
+    }
+
+    async fn _send_ack(did_doc: &DidDoc,
+                       request: &AnyRequest,
+                       response: &AnyResponse,
+                       pairwise_info: &PairwiseInfo,
+                       wallet_handle: WalletHandle,
+                       send_message: SendMessageFn) -> VcxResult<Response> {
         let remote_vk: String = did_doc.recipient_keys().get(0).cloned()
             .ok_or(VcxError::from_msg(VcxErrorKind::InvalidState, "Cannot handle Response: Remote Verkey not found"))?;
 
-        let response = response.clone().decode(&remote_vk)?;
+        let response = response.clone().decode_async(&remote_vk).await?;
 
-        if !response.from_thread(&request.id.0) {
+        if !response.from_thread(&request.thread_id()) {
             return Err(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot handle Response: thread id does not match: {:?}", response.thread)));
         }
 
-        let message = Ack::create()
-            .set_thread_id(&response.thread.thid.clone().unwrap_or_default())
-            .to_a2a_message();
+        let thread_id = response.thread.thid.clone().unwrap_or_default();
+        let message = match request {
+            AnyRequest::Connections(_) => {
+                Ack::create()
+                    .set_thread_id(&thread_id)
+                    .to_a2a_message()
+            }
+            AnyRequest::DidExchange(_) => {
+                Complete::create()
+                    .set_thread_id(&thread_id)
+                    .set_parent_thread_id(request.parent_thread_id())
+                    .to_a2a_message()
+            }
+        };
 
-        send_message(&pairwise_info.pw_vk, &response.connection.did_doc, &message)?;
+        send_message(wallet_handle, &pairwise_info.pw_vk, &response.connection.did_doc, &message).await?;
         Ok(response)
     }
 
-    pub fn handle_invitation(self, invitation: Invitation) -> VcxResult<Self> {
-        let Self { source_id, pairwise_info, state, send_message } = self;
+    pub async fn handle_invitation(self, invitation: AnyInvitation) -> VcxResult<Self> {
+        let Self { source_id, pairwise_info, state, profile, send_message } = self;
         let state = match state {
             InviteeFullState::Null(state) => {
-                InviteeFullState::Invited((state, invitation).into())
+                invitation.validate()?;
+                let did_doc = invitation.did_doc_async(&profile).await?;
+                InviteeFullState::Invited((state, invitation, did_doc).into())
             }
             _ => {
                 state.clone()
             }
         };
-        Ok(Self { source_id, pairwise_info, state, send_message })
+        Ok(Self { source_id, pairwise_info, state, profile, send_message })
     }
 
-    pub fn handle_connect(self, routing_keys: Vec<String>, service_endpoint: String) -> VcxResult<Self> {
-        let Self { source_id, pairwise_info, state, send_message } = self;
+    pub async fn handle_connect(self, routing_keys: Vec<String>, service_endpoint: String) -> VcxResult<Self> {
+        let Self { source_id, pairwise_info, state, profile, send_message } = self;
         let state = match state {
             InviteeFullState::Invited(state) => {
-                let recipient_keys = vec!(pairwise_info.pw_vk.clone());
-                let request = Request::create()
-                    .set_label(source_id.to_string())
-                    .set_did(pairwise_info.pw_did.to_string())
-                    .set_service_endpoint(service_endpoint)
-                    .set_keys(recipient_keys, routing_keys);
+                let request = Self::_build_request(&state.invitation, &source_id, &pairwise_info, routing_keys, service_endpoint)?;
 
-                let ddo = DidDoc::from(state.invitation.clone());
-                send_message(&pairwise_info.pw_vk, &ddo, &request.to_a2a_message())?;
-                let new_state = InviteeFullState::Requested((state, request).into());
+                let ddo = state.did_doc_async(&profile).await?;
+                send_message(profile.wallet_handle()?, &pairwise_info.pw_vk, &ddo, &request.to_a2a_message()).await?;
+                let new_state = InviteeFullState::Requested((state, request, ddo).into());
                 new_state
             }
             _ => {
                 state.clone()
             }
         };
-        Ok(Self { source_id, pairwise_info, state, send_message })
+        Ok(Self { source_id, pairwise_info, state, profile, send_message })
     }
 
-    pub fn handle_connection_response(self, response: SignedResponse) -> VcxResult<Self> {
-        let Self { source_id, pairwise_info, state, send_message } = self;
+    pub fn handle_connection_response(self, response: AnyResponse) -> VcxResult<Self> {
+        let Self { source_id, pairwise_info, state, profile, send_message } = self;
         let state = match state {
             InviteeFullState::Requested(state) => {
                 InviteeFullState::Responded((state, response).into())
@@ -271,71 +328,71 @@ impl SmConnectionInvitee {
                 state.clone()
             }
         };
-        Ok(Self { source_id, pairwise_info, state, send_message })
+        Ok(Self { source_id, pairwise_info, state, profile, send_message })
     }
 
-    pub fn handle_ping(self, ping: Ping) -> VcxResult<Self> {
-        let Self { source_id, pairwise_info, state, send_message } = self;
+    pub async fn handle_ping(self, ping: Ping) -> VcxResult<Self> {
+        let Self { source_id, pairwise_info, state, profile, send_message } = self;
         let state = match state {
             InviteeFullState::Completed(state) => {
-                state.handle_ping(&ping, &pairwise_info.pw_vk, send_message)?;
+                state.handle_ping(&ping, profile.wallet_handle()?, &pairwise_info.pw_vk, send_message).await?;
                 InviteeFullState::Completed(state)
             }
             _ => {
                 state.clone()
             }
         };
-        Ok(Self { source_id, pairwise_info, state, send_message })
+        Ok(Self { source_id, pairwise_info, state, profile, send_message })
     }
 
-    pub fn handle_send_ping(self, comment: Option<String>) -> VcxResult<Self> {
-        let Self { source_id, pairwise_info, state, send_message } = self;
+    pub async fn handle_send_ping(self, comment: Option<String>) -> VcxResult<Self> {
+        let Self { source_id, pairwise_info, state, profile, send_message } = self;
         let state = match state {
             InviteeFullState::Completed(state) => {
-                state.handle_send_ping(comment, &pairwise_info.pw_vk, send_message)?;
+                state.handle_send_ping(comment, profile.wallet_handle()?, &pairwise_info.pw_vk, send_message).await?;
                 InviteeFullState::Completed(state)
             }
             _ => {
                 state.clone()
             }
         };
-        Ok(Self { source_id, pairwise_info, state, send_message })
+        Ok(Self { source_id, pairwise_info, state, profile, send_message })
     }
 
     pub fn handle_ping_response(self, _ping_response: PingResponse) -> VcxResult<Self> {
         Ok(self)
     }
 
-    pub fn handle_discover_features(self, query_: Option<String>, comment: Option<String>) -> VcxResult<Self> {
-        let Self { source_id, pairwise_info, state, send_message } = self;
+    pub async fn handle_discover_features(self, query_: Option<String>, comment: Option<String>) -> VcxResult<Self> {
+        let Self { source_id, pairwise_info, state, profile, send_message } = self;
         let state = match state {
             InviteeFullState::Completed(state) => {
-                state.handle_discover_features(query_, comment, &pairwise_info.pw_vk, send_message)?;
+                state.handle_discover_features(query_, comment, profile.wallet_handle()?, &pairwise_info.pw_vk, send_message).await?;
                 InviteeFullState::Completed(state)
             }
             _ => {
                 state.clone()
             }
         };
-        Ok(Self { source_id, pairwise_info, state, send_message })
+        Ok(Self { source_id, pairwise_info, state, profile, send_message })
     }
 
-    pub fn handle_discovery_query(self, query: Query) -> VcxResult<Self> {
-        let Self { source_id, pairwise_info, state, send_message } = self;
+    pub async fn handle_discovery_query(self, query: Query) -> VcxResult<Self> {
+        let Self { source_id, pairwise_info, state, profile, send_message } = self;
         let state = match state {
             InviteeFullState::Completed(state) => {
-                state.handle_discovery_query(query, &pairwise_info.pw_vk, send_message)?;
+                state.handle_discovery_query(query, profile.wallet_handle()?, &pairwise_info.pw_vk, send_message).await?;
                 InviteeFullState::Completed(state)
             }
             _ => {
                 state.clone()
             }
         };
-        Ok(Self { source_id, pairwise_info, state, send_message })
+        Ok(Self { source_id, pairwise_info, state, profile, send_message })
     }
 
     pub fn handle_disclose(self, disclose: Disclose) -> VcxResult<Self> {
-        let Self { source_id, pairwise_info, state, send_message } = self;
+        let Self { source_id, pairwise_info, state, profile, send_message } = self;
         let state = match state {
             InviteeFullState::Completed(state) => {
                 InviteeFullState::Completed((state.clone(), disclose.protocols).into())
@@ -344,33 +401,33 @@ impl SmConnectionInvitee {
                 state.clone()
             }
         };
-        Ok(Self { source_id, pairwise_info, state, send_message })
+        Ok(Self { source_id, pairwise_info, state, profile, send_message })
     }
 
 
-    pub fn handle_send_ack(self) -> VcxResult<Self> {
-        let Self { source_id, pairwise_info, state, send_message } = self;
+    pub async fn handle_send_ack(self) -> VcxResult<Self> {
+        let Self { source_id, pairwise_info, state, profile, send_message } = self;
         let state = match state {
             InviteeFullState::Responded(state) => {
-                match Self::_send_ack(&state.did_doc, &state.request, &state.response, &pairwise_info, send_message) {
+                match Self::_send_ack(&state.did_doc, &state.request, &state.response, &pairwise_info, profile.wallet_handle()?, send_message).await {
                     Ok(response) => InviteeFullState::Completed((state, response).into()),
                     Err(err) => {
                         let problem_report = ProblemReport::create()
                             .set_problem_code(ProblemCode::ResponseProcessingError)
                             .set_explain(err.to_string())
-                            .set_thread_id(&state.request.id.0);
-                        send_message(&pairwise_info.pw_vk, &state.did_doc, &problem_report.to_a2a_message()).ok();
+                            .set_thread_id(&state.request.thread_id());
+                        send_message(profile.wallet_handle()?, &pairwise_info.pw_vk, &state.did_doc, &problem_report.to_a2a_message_for(&state.request.protocol())).await.ok();
                         InviteeFullState::Null((state, problem_report).into())
                     }
                 }
             }
             _ => state.clone()
         };
-        Ok(Self { source_id, pairwise_info, state, send_message })
+        Ok(Self { source_id, pairwise_info, state, profile, send_message })
     }
 
     pub fn handle_problem_report(self, problem_report: ProblemReport) -> VcxResult<Self> {
-        let Self { source_id, pairwise_info, state, send_message } = self;
+        let Self { source_id, pairwise_info, state, profile, send_message } = self;
         let state = match state {
             InviteeFullState::Requested(state) => {
                 InviteeFullState::Null((state, problem_report).into())
@@ -382,7 +439,7 @@ impl SmConnectionInvitee {
                 state.clone()
             }
         };
-        Ok(Self { source_id, pairwise_info, state, send_message })
+        Ok(Self { source_id, pairwise_info, state, profile, send_message })
     }
 
     pub fn handle_ack(self, _ack: Ack) -> VcxResult<Self> {
@@ -392,16 +449,22 @@ impl SmConnectionInvitee {
 
 #[cfg(test)]
 pub mod test {
+    use futures::future::{self, LocalBoxFuture};
+
     use crate::aries::messages::ack::tests::_ack;
+    use crate::aries::messages::connection::invite::Invitation;
     use crate::aries::messages::connection::invite::tests::_invitation;
     use crate::aries::messages::connection::problem_report::tests::_problem_report;
     use crate::aries::messages::connection::request::tests::_request;
     use crate::aries::messages::connection::response::tests::_signed_response;
+    use crate::aries::messages::did_exchange::response::Response as DidExchangeResponse;
     use crate::aries::messages::discovery::disclose::tests::_disclose;
     use crate::aries::messages::discovery::query::tests::_query;
     use crate::aries::messages::trust_ping::ping::tests::_ping;
     use crate::aries::messages::trust_ping::ping_response::tests::_ping_response;
     use crate::aries::test::source_id;
+    use crate::aries::utils::task::block_on;
+    use crate::libindy::utils::wallet::get_wallet_handle;
     use crate::utils::devsetup::SetupMocks;
 
     use super::*;
@@ -409,29 +472,32 @@ pub mod test {
     pub mod invitee {
         use crate::aries::messages::connection::did_doc::tests::_service_endpoint;
         use crate::aries::messages::connection::response::{Response, SignedResponse};
+        use crate::aries::messages::a2a::message_family::MessageFamilies;
+        use crate::aries::messages::connection::invite::PublicInvitation;
+        use crate::aries::messages::out_of_band::invitation::tests::_out_of_band_invitation;
 
         use super::*;
 
-        fn _send_message(pv_wk: &str, did_doc: &DidDoc, a2a_message: &A2AMessage) -> VcxResult<()> {
-            VcxResult::Ok(())
+        fn _send_message<'a>(_wallet_handle: WalletHandle, pv_wk: &'a str, did_doc: &'a DidDoc, a2a_message: &'a A2AMessage) -> LocalBoxFuture<'a, VcxResult<()>> {
+            Box::pin(future::ready(VcxResult::Ok(())))
         }
 
         pub fn invitee_sm() -> SmConnectionInvitee {
-            let pairwise_info = PairwiseInfo::create().unwrap();
-            SmConnectionInvitee::new(&source_id(), pairwise_info, _send_message)
+            let pairwise_info = PairwiseInfo::create(get_wallet_handle()).unwrap();
+            SmConnectionInvitee::new(&source_id(), pairwise_info, ProfileContext::Global, _send_message)
         }
 
         impl SmConnectionInvitee {
             pub fn to_invitee_invited_state(mut self) -> SmConnectionInvitee {
-                self = self.handle_invitation(_invitation()).unwrap();
+                self = block_on(self.handle_invitation(AnyInvitation::Connections(_invitation()))).unwrap();
                 self
             }
 
             pub fn to_invitee_requested_state(mut self) -> SmConnectionInvitee {
-                self = self.handle_invitation(_invitation()).unwrap();
+                self = block_on(self.handle_invitation(AnyInvitation::Connections(_invitation()))).unwrap();
                 let routing_keys: Vec<String> = vec!("verkey123".into());
                 let service_endpoint = String::from("https://example.org/agent");
-                self = self.handle_connect(routing_keys, service_endpoint).unwrap();
+                self = block_on(self.handle_connect(routing_keys, service_endpoint)).unwrap();
                 self
             }
 
@@ -439,13 +505,13 @@ pub mod test {
                 let key = "GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL".to_string();
                 let invitation = Invitation::default().set_recipient_keys(vec![key.clone()]);
 
-                self = self.handle_invitation(invitation).unwrap();
+                self = block_on(self.handle_invitation(AnyInvitation::Connections(invitation))).unwrap();
 
                 let routing_keys: Vec<String> = vec!("verkey123".into());
                 let service_endpoint = String::from("https://example.org/agent");
-                self = self.handle_connect(routing_keys, service_endpoint).unwrap();
-                self = self.handle_connection_response(_response(&key)).unwrap();
-                self = self.handle_send_ack().unwrap();
+                self = block_on(self.handle_connect(routing_keys, service_endpoint)).unwrap();
+                self = self.handle_connection_response(AnyResponse::Connections(_response(&key))).unwrap();
+                self = block_on(self.handle_send_ack()).unwrap();
                 self = self.handle_ack(_ack()).unwrap();
                 self
             }
@@ -456,7 +522,17 @@ pub mod test {
                 .set_service_endpoint(_service_endpoint())
                 .set_keys(vec![key.to_string()], vec![])
                 .set_thread_id(&_request().id.0)
-                .encode(&key).unwrap()
+                .encode(get_wallet_handle(), &key).unwrap()
+        }
+
+        fn _did_exchange_invitation() -> AnyInvitation {
+            AnyInvitation::OutOfBand(_out_of_band_invitation().set_handshake_protocols(vec![MessageFamilies::DidExchange.id()]))
+        }
+
+        fn _did_exchange_response(key: &str, did_doc: &DidDoc) -> DidExchangeResponse {
+            DidExchangeResponse::create(did_doc).unwrap()
+                .set_thread_id("testid")
+                .sign(get_wallet_handle(), key).unwrap()
         }
 
         mod new {
@@ -475,6 +551,7 @@ pub mod test {
         }
 
         mod step {
+            use crate::aries::messages::connection::did_doc::tests::{_did_doc, _key_1, _key_2};
             use crate::utils::devsetup::SetupIndyMocks;
 
             use super::*;
@@ -496,9 +573,61 @@ pub mod test {
 
                 let mut did_exchange_sm = invitee_sm();
 
-                did_exchange_sm = did_exchange_sm.handle_invitation(_invitation()).unwrap();
+                did_exchange_sm = block_on(did_exchange_sm.handle_invitation(AnyInvitation::Connections(_invitation()))).unwrap();
+
+                assert_match!(InviteeFullState::Invited(_), did_exchange_sm.state);
+            }
+
+            #[test]
+            #[cfg(feature = "general_test")]
+            fn test_did_exchange_handle_out_of_band_invite_from_null_state() {
+                let _setup = SetupIndyMocks::init();
+
+                let mut did_exchange_sm = invitee_sm();
 
+                did_exchange_sm = block_on(did_exchange_sm.handle_invitation(AnyInvitation::OutOfBand(_out_of_band_invitation()))).unwrap();
                 assert_match!(InviteeFullState::Invited(_), did_exchange_sm.state);
+                assert_eq!(vec![_key_1()], did_exchange_sm.their_did_doc().unwrap().recipient_keys());
+
+                let routing_keys: Vec<String> = vec!("verkey123".into());
+                let service_endpoint = String::from("https://example.org/agent");
+                did_exchange_sm = block_on(did_exchange_sm.handle_connect(routing_keys, service_endpoint)).unwrap();
+
+                match did_exchange_sm.state {
+                    InviteeFullState::Requested(ref state) => {
+                        assert_eq!(Some(_out_of_band_invitation().id.0), state.request.parent_thread_id());
+                        assert_eq!(HandshakeProtocol::Connections, state.request.protocol());
+                    }
+                    _ => panic!("Invitee is expected to be in Requested state")
+                }
+            }
+
+            #[test]
+            #[cfg(feature = "general_test")]
+            fn test_did_exchange_handle_public_invite_resolves_did_doc_once() {
+                let _setup = SetupMocks::init();
+
+                let invitation = PublicInvitation::create().set_did(String::from("did:sov:V4SGRU86Z58d6TV7PBUe6f"));
+                let did_exchange_sm = block_on(invitee_sm().handle_invitation(AnyInvitation::Public(invitation))).unwrap();
+
+                match did_exchange_sm.state {
+                    InviteeFullState::Invited(ref state) => {
+                        let did_doc = state.did_doc.clone().unwrap();
+                        assert_eq!(vec![crate::utils::constants::VERKEY.to_string()], did_doc.recipient_keys());
+                        assert_eq!(Some(did_doc), did_exchange_sm.their_did_doc());
+                    }
+                    _ => panic!("Invitee is expected to be in Invited state")
+                }
+            }
+
+            #[test]
+            #[cfg(feature = "general_test")]
+            fn test_did_exchange_handle_unsupported_out_of_band_invite_from_null_state() {
+                let _setup = SetupIndyMocks::init();
+
+                let invitation = _out_of_band_invitation().set_handshake_protocols(vec![]);
+
+                assert!(block_on(invitee_sm().handle_invitation(AnyInvitation::OutOfBand(invitation))).is_err());
             }
 
             #[test]
@@ -510,7 +639,7 @@ pub mod test {
 
                 let routing_keys: Vec<String> = vec!("verkey123".into());
                 let service_endpoint = String::from("https://example.org/agent");
-                did_exchange_sm = did_exchange_sm.handle_connect(routing_keys, service_endpoint).unwrap();
+                did_exchange_sm = block_on(did_exchange_sm.handle_connect(routing_keys, service_endpoint)).unwrap();
                 assert_match!(InviteeFullState::Null(_), did_exchange_sm.state);
 
                 did_exchange_sm = did_exchange_sm.handle_ack(_ack()).unwrap();
@@ -526,7 +655,7 @@ pub mod test {
 
                 let routing_keys: Vec<String> = vec!("verkey123".into());
                 let service_endpoint = String::from("https://example.org/agent");
-                did_exchange_sm = did_exchange_sm.handle_connect(routing_keys, service_endpoint).unwrap();
+                did_exchange_sm = block_on(did_exchange_sm.handle_connect(routing_keys, service_endpoint)).unwrap();
 
                 assert_match!(InviteeFullState::Requested(_), did_exchange_sm.state);
             }
@@ -552,10 +681,56 @@ pub mod test {
 
                 let mut did_exchange_sm = invitee_sm().to_invitee_requested_state();
 
-                did_exchange_sm = did_exchange_sm.handle_connection_response(_response(&key)).unwrap();
-                did_exchange_sm = did_exchange_sm.handle_send_ack().unwrap();
+                did_exchange_sm = did_exchange_sm.handle_connection_response(AnyResponse::Connections(_response(&key))).unwrap();
+                did_exchange_sm = block_on(did_exchange_sm.handle_send_ack()).unwrap();
+
+                assert_match!(InviteeFullState::Completed(_), did_exchange_sm.state);
+            }
+
+            #[test]
+            #[cfg(feature = "general_test")]
+            fn test_did_exchange_protocol_from_out_of_band_invite_to_completed_state() {
+                let _setup = SetupIndyMocks::init();
+
+                let mut did_exchange_sm = block_on(invitee_sm().handle_invitation(_did_exchange_invitation())).unwrap();
+
+                let routing_keys: Vec<String> = vec!("verkey123".into());
+                let service_endpoint = String::from("https://example.org/agent");
+                did_exchange_sm = block_on(did_exchange_sm.handle_connect(routing_keys, service_endpoint)).unwrap();
+
+                match did_exchange_sm.state {
+                    InviteeFullState::Requested(ref state) => {
+                        assert_match!(AnyRequest::DidExchange(_), state.request);
+                        assert_eq!(Some(_out_of_band_invitation().id.0), state.request.parent_thread_id());
+                        assert_eq!(did_exchange_sm.pairwise_info.pw_did, state.request.did_doc().unwrap().id);
+                    }
+                    _ => panic!("Invitee is expected to be in Requested state")
+                }
+
+                assert!(did_exchange_sm.can_handle_message(&A2AMessage::DidExchangeResponse(_did_exchange_response(&_key_1(), &_did_doc()))));
+                assert!(!did_exchange_sm.can_handle_message(&A2AMessage::ConnectionResponse(_signed_response())));
+
+                did_exchange_sm = did_exchange_sm.handle_connection_response(AnyResponse::DidExchange(_did_exchange_response(&_key_1(), &_did_doc()))).unwrap();
+                did_exchange_sm = block_on(did_exchange_sm.handle_send_ack()).unwrap();
 
                 assert_match!(InviteeFullState::Completed(_), did_exchange_sm.state);
+                assert_eq!(_did_doc(), did_exchange_sm.their_did_doc().unwrap());
+            }
+
+            #[test]
+            #[cfg(feature = "general_test")]
+            fn test_did_exchange_protocol_handle_response_signed_by_other_key() {
+                let _setup = SetupIndyMocks::init();
+
+                let routing_keys: Vec<String> = vec!("verkey123".into());
+                let service_endpoint = String::from("https://example.org/agent");
+                let mut did_exchange_sm = block_on(invitee_sm().handle_invitation(_did_exchange_invitation())).unwrap();
+                did_exchange_sm = block_on(did_exchange_sm.handle_connect(routing_keys, service_endpoint)).unwrap();
+
+                did_exchange_sm = did_exchange_sm.handle_connection_response(AnyResponse::DidExchange(_did_exchange_response(&_key_2(), &_did_doc()))).unwrap();
+                did_exchange_sm = block_on(did_exchange_sm.handle_send_ack()).unwrap();
+
+                assert_match!(InviteeFullState::Null(_), did_exchange_sm.state);
             }
 
 
@@ -569,7 +744,7 @@ pub mod test {
                 did_exchange_sm = did_exchange_sm.handle_ack(_ack()).unwrap();
                 assert_match!(InviteeFullState::Invited(_), did_exchange_sm.state);
 
-                did_exchange_sm = did_exchange_sm.handle_discovery_query(_query()).unwrap();
+                did_exchange_sm = block_on(did_exchange_sm.handle_discovery_query(_query())).unwrap();
                 assert_match!(InviteeFullState::Invited(_), did_exchange_sm.state);
             }
 
@@ -583,8 +758,8 @@ pub mod test {
                 let mut signed_response = _signed_response();
                 signed_response.connection_sig.signature = String::from("other");
 
-                did_exchange_sm = did_exchange_sm.handle_connection_response(signed_response).unwrap();
-                did_exchange_sm = did_exchange_sm.handle_send_ack().unwrap();
+                did_exchange_sm = did_exchange_sm.handle_connection_response(AnyResponse::Connections(signed_response)).unwrap();
+                did_exchange_sm = block_on(did_exchange_sm.handle_send_ack()).unwrap();
 
                 assert_match!(InviteeFullState::Null(_), did_exchange_sm.state);
             }
@@ -611,7 +786,7 @@ pub mod test {
                 did_exchange_sm = did_exchange_sm.handle_ack(_ack()).unwrap();
                 assert_match!(InviteeFullState::Requested(_), did_exchange_sm.state);
 
-                did_exchange_sm = did_exchange_sm.handle_ping(_ping()).unwrap();
+                did_exchange_sm = block_on(did_exchange_sm.handle_ping(_ping())).unwrap();
                 assert_match!(InviteeFullState::Requested(_), did_exchange_sm.state);
             }
 
@@ -623,11 +798,11 @@ pub mod test {
                 let mut did_exchange_sm = invitee_sm().to_invitee_completed_state();
 
                 // Send Ping
-                did_exchange_sm = did_exchange_sm.handle_send_ping(None).unwrap();
+                did_exchange_sm = block_on(did_exchange_sm.handle_send_ping(None)).unwrap();
                 assert_match!(InviteeFullState::Completed(_), did_exchange_sm.state);
 
                 // Ping
-                did_exchange_sm = did_exchange_sm.handle_ping(_ping()).unwrap();
+                did_exchange_sm = block_on(did_exchange_sm.handle_ping(_ping())).unwrap();
                 assert_match!(InviteeFullState::Completed(_), did_exchange_sm.state);
 
                 // Ping Response
@@ -635,11 +810,11 @@ pub mod test {
                 assert_match!(InviteeFullState::Completed(_), did_exchange_sm.state);
 
                 // Discovery Features
-                did_exchange_sm = did_exchange_sm.handle_discover_features(None, None).unwrap();
+                did_exchange_sm = block_on(did_exchange_sm.handle_discover_features(None, None)).unwrap();
                 assert_match!(InviteeFullState::Completed(_), did_exchange_sm.state);
 
                 // Query
-                did_exchange_sm = did_exchange_sm.handle_discovery_query(_query()).unwrap();
+                did_exchange_sm = block_on(did_exchange_sm.handle_discovery_query(_query())).unwrap();
                 assert_match!(InviteeFullState::Completed(_), did_exchange_sm.state);
 
                 // Disclose
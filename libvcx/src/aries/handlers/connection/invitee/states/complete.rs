@@ -1,13 +1,15 @@
+use indy::WalletHandle;
+
 use crate::aries::handlers::connection::invitee::states::requested::RequestedState;
 use crate::aries::handlers::connection::invitee::states::responded::RespondedState;
 use crate::aries::handlers::connection::util::handle_ping;
-use crate::aries::messages::a2a::A2AMessage;
 use crate::aries::messages::a2a::protocol_registry::ProtocolRegistry;
 use crate::aries::messages::connection::did_doc::DidDoc;
 use crate::aries::messages::connection::response::Response;
 use crate::aries::messages::discovery::disclose::{Disclose, ProtocolDescriptor};
 use crate::aries::messages::discovery::query::Query;
 use crate::aries::messages::trust_ping::ping::Ping;
+use crate::aries::utils::SendMessageFn;
 use crate::error::VcxResult;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,45 +40,49 @@ impl From<(RespondedState, Response)> for CompleteState {
 }
 
 impl CompleteState {
-    pub fn handle_send_ping(&self,
-                            comment: Option<String>,
-                            pw_vk: &str,
-                            send_message: fn(&str, &DidDoc, &A2AMessage) -> VcxResult<()>,
+    pub async fn handle_send_ping(&self,
+                                  comment: Option<String>,
+                                  wallet_handle: WalletHandle,
+                                  pw_vk: &str,
+                                  send_message: SendMessageFn,
     ) -> VcxResult<()> {
         let ping =
             Ping::create()
                 .request_response()
                 .set_comment(comment);
 
-        send_message(pw_vk, &self.did_doc, &ping.to_a2a_message()).ok();
+        send_message(wallet_handle, pw_vk, &self.did_doc, &ping.to_a2a_message()).await.ok();
         Ok(())
     }
 
-    pub fn handle_ping(&self,
-                       ping: &Ping,
-                       pw_vk: &str,
-                       send_message: fn(&str, &DidDoc, &A2AMessage) -> VcxResult<()>,
+    pub async fn handle_ping(&self,
+                             ping: &Ping,
+                             wallet_handle: WalletHandle,
+                             pw_vk: &str,
+                             send_message: SendMessageFn,
     ) -> VcxResult<()> {
-        handle_ping(ping, pw_vk, &self.did_doc, send_message)
+        handle_ping(ping, wallet_handle, pw_vk, &self.did_doc, send_message).await
     }
 
-    pub fn handle_discover_features(&self,
-                                    query: Option<String>,
-                                    comment: Option<String>,
-                                    pw_vk: &str,
-                                    send_message: fn(&str, &DidDoc, &A2AMessage) -> VcxResult<()>,
+    pub async fn handle_discover_features(&self,
+                                          query: Option<String>,
+                                          comment: Option<String>,
+                                          wallet_handle: WalletHandle,
+                                          pw_vk: &str,
+                                          send_message: SendMessageFn,
     ) -> VcxResult<()> {
         let query_ =
             Query::create()
                 .set_query(query)
                 .set_comment(comment);
-        send_message(pw_vk, &self.did_doc, &query_.to_a2a_message())
+        send_message(wallet_handle, pw_vk, &self.did_doc, &query_.to_a2a_message()).await
     }
 
-    pub fn handle_discovery_query(&self,
-                                  query: Query,
-                                  pw_vk: &str,
-                                  send_message: fn(&str, &DidDoc, &A2AMessage) -> VcxResult<()>,
+    pub async fn handle_discovery_query(&self,
+                                        query: Query,
+                                        wallet_handle: WalletHandle,
+                                        pw_vk: &str,
+                                        send_message: SendMessageFn,
     ) -> VcxResult<()> {
         let protocols = ProtocolRegistry::init().get_protocols_for_query(query.query.as_ref().map(String::as_str));
 
@@ -84,6 +90,6 @@ impl CompleteState {
             .set_protocols(protocols)
             .set_thread_id(query.id.0.clone());
 
-        send_message(pw_vk, &self.did_doc, &disclose.to_a2a_message())
+        send_message(wallet_handle, pw_vk, &self.did_doc, &disclose.to_a2a_message()).await
     }
 }
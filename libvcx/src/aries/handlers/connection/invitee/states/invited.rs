@@ -1,13 +1,35 @@
 use crate::aries::handlers::connection::invitee::states::null::NullState;
 use crate::aries::handlers::connection::invitee::states::requested::RequestedState;
 use crate::aries::messages::connection::did_doc::DidDoc;
-use crate::aries::messages::connection::invite::Invitation;
+use crate::aries::messages::connection::invite::AnyInvitation;
 use crate::aries::messages::connection::problem_report::ProblemReport;
-use crate::aries::messages::connection::request::Request;
+use crate::aries::messages::connection::request::AnyRequest;
+use crate::aries::utils::task::block_on;
+use crate::error::prelude::*;
+use crate::profile::ProfileContext;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InvitedState {
-    pub invitation: Invitation,
+    pub invitation: AnyInvitation,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub did_doc: Option<DidDoc>,
+}
+
+impl InvitedState {
+    /**
+    DidDoc resolved when the invitation was received, states stored without it derive it from the invitation.
+     */
+    pub fn did_doc(&self, profile: &ProfileContext) -> VcxResult<DidDoc> {
+        block_on(self.did_doc_async(profile))
+    }
+
+    pub async fn did_doc_async(&self, profile: &ProfileContext) -> VcxResult<DidDoc> {
+        match self.did_doc {
+            Some(ref did_doc) => Ok(did_doc.clone()),
+            None => self.invitation.did_doc_async(profile).await
+        }
+    }
 }
 
 impl From<(InvitedState, ProblemReport)> for NullState {
@@ -17,9 +39,9 @@ impl From<(InvitedState, ProblemReport)> for NullState {
     }
 }
 
-impl From<(InvitedState, Request)> for RequestedState {
-    fn from((state, request): (InvitedState, Request)) -> RequestedState {
+impl From<(InvitedState, AnyRequest, DidDoc)> for RequestedState {
+    fn from((_state, request, did_doc): (InvitedState, AnyRequest, DidDoc)) -> RequestedState {
         trace!("ConnectionInvitee: transit state from InvitedState to RequestedState");
-        RequestedState { request, did_doc: DidDoc::from(state.invitation) }
+        RequestedState { request, did_doc }
     }
 }
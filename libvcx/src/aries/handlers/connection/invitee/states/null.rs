@@ -1,12 +1,13 @@
 use crate::aries::handlers::connection::invitee::states::invited::InvitedState;
-use crate::aries::messages::connection::invite::Invitation;
+use crate::aries::messages::connection::did_doc::DidDoc;
+use crate::aries::messages::connection::invite::AnyInvitation;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NullState {}
 
-impl From<(NullState, Invitation)> for InvitedState {
-    fn from((_state, invitation): (NullState, Invitation)) -> InvitedState {
+impl From<(NullState, AnyInvitation, DidDoc)> for InvitedState {
+    fn from((_state, invitation, did_doc): (NullState, AnyInvitation, DidDoc)) -> InvitedState {
         trace!("ConnectionInvitee: transit state from NullState to InvitedState");
-        InvitedState { invitation }
+        InvitedState { invitation, did_doc: Some(did_doc) }
     }
 }
@@ -2,12 +2,12 @@ use crate::aries::handlers::connection::invitee::states::null::NullState;
 use crate::aries::handlers::connection::invitee::states::responded::RespondedState;
 use crate::aries::messages::connection::did_doc::DidDoc;
 use crate::aries::messages::connection::problem_report::ProblemReport;
-use crate::aries::messages::connection::request::Request;
-use crate::aries::messages::connection::response::SignedResponse;
+use crate::aries::messages::connection::request::AnyRequest;
+use crate::aries::messages::connection::response::AnyResponse;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RequestedState {
-    pub request: Request,
+    pub request: AnyRequest,
     pub did_doc: DidDoc,
 }
 
@@ -18,8 +18,8 @@ impl From<(RequestedState, ProblemReport)> for NullState {
     }
 }
 
-impl From<(RequestedState, SignedResponse)> for RespondedState {
-    fn from((state, response): (RequestedState, SignedResponse)) -> RespondedState {
+impl From<(RequestedState, AnyResponse)> for RespondedState {
+    fn from((state, response): (RequestedState, AnyResponse)) -> RespondedState {
         trace!("ConnectionInvitee: transit state from RequestedState to RespondedState");
         RespondedState { response, did_doc: state.did_doc, request: state.request }
     }
@@ -1,13 +1,13 @@
 use crate::aries::handlers::connection::invitee::states::null::NullState;
 use crate::aries::messages::connection::did_doc::DidDoc;
 use crate::aries::messages::connection::problem_report::ProblemReport;
-use crate::aries::messages::connection::request::Request;
-use crate::aries::messages::connection::response::SignedResponse;
+use crate::aries::messages::connection::request::AnyRequest;
+use crate::aries::messages::connection::response::AnyResponse;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RespondedState {
-    pub response: SignedResponse,
-    pub request: Request,
+    pub response: AnyResponse,
+    pub request: AnyRequest,
     pub did_doc: DidDoc,
 }
 
@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 
+use indy::WalletHandle;
+
 use crate::aries::handlers::connection::inviter::states::complete::CompleteState;
 use crate::aries::handlers::connection::inviter::states::invited::InvitedState;
 use crate::aries::handlers::connection::inviter::states::null::NullState;
@@ -7,25 +9,33 @@ use crate::aries::handlers::connection::inviter::states::requested::RequestedSta
 use crate::aries::handlers::connection::inviter::states::responded::RespondedState;
 use crate::aries::handlers::connection::pairwise_info::PairwiseInfo;
 use crate::aries::messages::a2a::A2AMessage;
+use crate::aries::messages::a2a::message_family::MessageFamilies;
 use crate::aries::messages::a2a::protocol_registry::ProtocolRegistry;
 use crate::aries::messages::ack::Ack;
 use crate::aries::messages::connection::did_doc::DidDoc;
-use crate::aries::messages::connection::invite::Invitation;
+use crate::aries::messages::connection::handshake_protocol::HandshakeProtocol;
+use crate::aries::messages::connection::invite::{AnyInvitation, Invitation};
 use crate::aries::messages::connection::problem_report::{ProblemCode, ProblemReport};
-use crate::aries::messages::connection::request::Request;
-use crate::aries::messages::connection::response::{Response, SignedResponse};
+use crate::aries::messages::connection::request::AnyRequest;
+use crate::aries::messages::connection::response::{AnyResponse, Response};
+use crate::aries::messages::did_exchange::complete::Complete;
+use crate::aries::messages::did_exchange::response::Response as DidExchangeResponse;
 use crate::aries::messages::discovery::disclose::{Disclose, ProtocolDescriptor};
 use crate::aries::messages::discovery::query::Query;
+use crate::aries::messages::out_of_band::invitation::{InlineService, OutOfBandInvitation, ServiceResolvable, verkey_to_did_key};
 use crate::aries::messages::trust_ping::ping::Ping;
 use crate::aries::messages::trust_ping::ping_response::PingResponse;
+use crate::aries::utils::SendMessageFn;
 use crate::error::prelude::*;
+use crate::profile::ProfileContext;
 
 #[derive(Clone)]
 pub struct SmConnectionInviter {
     pub source_id: String,
     pub pairwise_info: PairwiseInfo,
     pub state: InviterFullState,
-    send_message: fn(&str, &DidDoc, &A2AMessage) -> VcxResult<()>,
+    profile: ProfileContext,
+    send_message: SendMessageFn,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,11 +69,22 @@ impl From<InviterFullState> for InviterState {
 }
 
 impl SmConnectionInviter {
-    pub fn new(source_id: &str, pairwise_info: PairwiseInfo, send_message: fn(&str, &DidDoc, &A2AMessage) -> VcxResult<()>) -> Self {
+    pub fn new(source_id: &str, pairwise_info: PairwiseInfo, protocol: HandshakeProtocol, profile: ProfileContext, send_message: SendMessageFn) -> Self {
         Self {
             source_id: source_id.to_string(),
-            state: InviterFullState::Null(NullState {}),
+            state: InviterFullState::Null(NullState { protocol }),
             pairwise_info,
+            profile,
+            send_message,
+        }
+    }
+
+    pub fn invited(source_id: &str, pairwise_info: PairwiseInfo, invitation: AnyInvitation, profile: ProfileContext, send_message: SendMessageFn) -> Self {
+        Self {
+            source_id: source_id.to_string(),
+            state: InviterFullState::Invited(InvitedState { invitation }),
+            pairwise_info,
+            profile,
             send_message,
         }
     }
@@ -72,11 +93,12 @@ impl SmConnectionInviter {
         return InviterState::from(self.state.clone()) == InviterState::Null;
     }
 
-    pub fn from(source_id: String, pairwise_info: PairwiseInfo, state: InviterFullState, send_message: fn(&str, &DidDoc, &A2AMessage) -> VcxResult<()>) -> Self {
+    pub fn from(source_id: String, pairwise_info: PairwiseInfo, state: InviterFullState, profile: ProfileContext, send_message: SendMessageFn) -> Self {
         Self {
             source_id,
             pairwise_info,
             state,
+            profile,
             send_message,
         }
     }
@@ -107,7 +129,7 @@ impl SmConnectionInviter {
         }
     }
 
-    pub fn get_invitation(&self) -> Option<&Invitation> {
+    pub fn get_invitation(&self) -> Option<&AnyInvitation> {
         match self.state {
             InviterFullState::Invited(ref state) => Some(&state.invitation),
             _ => None
@@ -155,13 +177,18 @@ impl SmConnectionInviter {
 
     pub fn can_handle_message(&self, message: &A2AMessage) -> bool {
         match self.state {
-            InviterFullState::Invited(_) => {
+            InviterFullState::Invited(ref state) => {
                 match message {
-                    A2AMessage::ConnectionRequest(_) => {
+                    A2AMessage::ConnectionRequest(_) if state.protocol() == HandshakeProtocol::Connections => {
                         debug!("Inviter received ConnectionRequest message");
                         true
                     }
-                    A2AMessage::ConnectionProblemReport(_) => {
+                    A2AMessage::DidExchangeRequest(_) if state.protocol() == HandshakeProtocol::DidExchange => {
+                        debug!("Inviter received DidExchangeRequest message");
+                        true
+                    }
+                    A2AMessage::ConnectionProblemReport(_) |
+                    A2AMessage::DidExchangeProblemReport(_) => {
                         debug!("Inviter received ProblemReport message");
                         true
                     }
@@ -177,6 +204,10 @@ impl SmConnectionInviter {
                         debug!("Ack message received");
                         true
                     }
+                    A2AMessage::DidExchangeComplete(_) => {
+                        debug!("DidExchangeComplete message received");
+                        true
+                    }
                     A2AMessage::Ping(_) => {
                         debug!("Ping message received");
                         true
@@ -185,7 +216,8 @@ impl SmConnectionInviter {
                         debug!("PingResponse message received");
                         true
                     }
-                    A2AMessage::ConnectionProblemReport(_) => {
+                    A2AMessage::ConnectionProblemReport(_) |
+                    A2AMessage::DidExchangeProblemReport(_) => {
                         debug!("ProblemReport message received");
                         true
                     }
@@ -226,42 +258,93 @@ impl SmConnectionInviter {
         }
     }
 
-    fn _build_response(
-        request: &Request,
+    fn _build_invitation(
+        protocol: HandshakeProtocol,
+        label: &str,
+        pairwise_info: &PairwiseInfo,
+        routing_keys: Vec<String>,
+        service_endpoint: String,
+    ) -> VcxResult<AnyInvitation> {
+        match protocol {
+            HandshakeProtocol::Connections => {
+                let invite: Invitation = Invitation::create()
+                    .set_label(label.to_string())
+                    .set_recipient_keys(vec!(pairwise_info.pw_vk.clone()))
+                    .set_routing_keys(routing_keys)
+                    .set_service_endpoint(service_endpoint);
+                Ok(AnyInvitation::Connections(invite))
+            }
+            HandshakeProtocol::DidExchange => {
+                let service = InlineService {
+                    id: String::from("#inline"),
+                    type_: String::from("did-communication"),
+                    recipient_keys: vec!(verkey_to_did_key(&pairwise_info.pw_vk)?),
+                    routing_keys: routing_keys.iter()
+                        .map(|key| verkey_to_did_key(key))
+                        .collect::<VcxResult<Vec<String>>>()?,
+                    service_endpoint,
+                };
+                let invite = OutOfBandInvitation::create()
+                    .set_label(label.to_string())
+                    .set_handshake_protocols(vec!(MessageFamilies::DidExchange.id()))
+                    .add_service(ServiceResolvable::Inline(service));
+                Ok(AnyInvitation::OutOfBand(invite))
+            }
+        }
+    }
+
+    async fn _build_response(
+        wallet_handle: WalletHandle,
+        request: &AnyRequest,
         bootstrap_pairwise_info: &PairwiseInfo,
         new_pairwise_info: &PairwiseInfo,
         new_routing_keys: Vec<String>,
         new_service_endpoint: String,
-    ) -> VcxResult<SignedResponse> {
-        request.connection.did_doc.validate()?;
+    ) -> VcxResult<(DidDoc, AnyResponse)> {
+        let did_doc = request.did_doc()?;
+        did_doc.validate()?;
         let new_recipient_keys = vec!(new_pairwise_info.pw_vk.clone());
-        Response::create()
-            .set_did(new_pairwise_info.pw_did.to_string())
-            .set_service_endpoint(new_service_endpoint)
-            .set_keys(new_recipient_keys, new_routing_keys)
-            .ask_for_ack()
-            .set_thread_id(&request.id.0)
-            .encode(&bootstrap_pairwise_info.pw_vk)
+        let response = match request {
+            AnyRequest::Connections(_) => {
+                let response = Response::create()
+                    .set_did(new_pairwise_info.pw_did.to_string())
+                    .set_service_endpoint(new_service_endpoint)
+                    .set_keys(new_recipient_keys, new_routing_keys)
+                    .ask_for_ack()
+                    .set_thread_id(&request.thread_id())
+                    .encode_async(wallet_handle, &bootstrap_pairwise_info.pw_vk).await?;
+                AnyResponse::Connections(response)
+            }
+            AnyRequest::DidExchange(_) => {
+                let mut new_did_doc = DidDoc::default();
+                new_did_doc.set_id(new_pairwise_info.pw_did.to_string());
+                new_did_doc.set_service_endpoint(new_service_endpoint);
+                new_did_doc.set_keys(new_recipient_keys, new_routing_keys);
+
+                let response = DidExchangeResponse::create(&new_did_doc)?
+                    .set_thread_id(&request.thread_id())
+                    .sign_async(wallet_handle, &bootstrap_pairwise_info.pw_vk).await?;
+                AnyResponse::DidExchange(response)
+            }
+        };
+        Ok((did_doc, response))
     }
 
-    fn _send_response(
+    async fn _send_response(
         state: &RequestedState,
+        wallet_handle: WalletHandle,
         new_pw_vk: &str,
-        send_message: fn(&str, &DidDoc, &A2AMessage) -> VcxResult<()>,
+        send_message: SendMessageFn,
     ) -> VcxResult<()> {
-        send_message(&new_pw_vk, &state.did_doc, &state.signed_response.to_a2a_message())?;
+        send_message(wallet_handle, &new_pw_vk, &state.did_doc, &state.signed_response.to_a2a_message()).await?;
         Ok(())
     }
 
     pub fn handle_connect(self, routing_keys: Vec<String>, service_endpoint: String) -> VcxResult<Self> {
-        let Self { source_id, pairwise_info, state, send_message } = self;
+        let Self { source_id, pairwise_info, state, profile, send_message } = self;
         let state = match state {
             InviterFullState::Null(state) => {
-                let invite: Invitation = Invitation::create()
-                    .set_label(source_id.to_string())
-                    .set_recipient_keys(vec!(pairwise_info.pw_vk.clone()))
-                    .set_routing_keys(routing_keys)
-                    .set_service_endpoint(service_endpoint);
+                let invite = Self::_build_invitation(state.protocol, &source_id, &pairwise_info, routing_keys, service_endpoint)?;
 
                 let new_state = InviterFullState::Invited((state, invite).into());
                 new_state
@@ -270,37 +353,41 @@ impl SmConnectionInviter {
                 state.clone()
             }
         };
-        Ok(Self { source_id, pairwise_info, state, send_message })
+        Ok(Self { source_id, pairwise_info, state, profile, send_message })
     }
 
 
-    pub fn handle_connection_request(self,
-                                     request: Request,
-                                     new_pairwise_info: &PairwiseInfo,
-                                     new_routing_keys: Vec<String>,
-                                     new_service_endpoint: String) -> VcxResult<Self> {
-        let Self { source_id, pairwise_info: bootstrap_pairwise_info, state, send_message } = self;
+    pub async fn handle_connection_request(self,
+                                           request: AnyRequest,
+                                           new_pairwise_info: &PairwiseInfo,
+                                           new_routing_keys: Vec<String>,
+                                           new_service_endpoint: String) -> VcxResult<Self> {
+        let Self { source_id, pairwise_info: bootstrap_pairwise_info, state, profile, send_message } = self;
         let state = match state {
             InviterFullState::Invited(state) => {
                 match Self::_build_response(
+                    profile.wallet_handle()?,
                     &request,
                     &bootstrap_pairwise_info,
                     &new_pairwise_info,
                     new_routing_keys,
-                    new_service_endpoint) {
-                    Ok(signed_response) => {
-                        InviterFullState::Requested((state, request, signed_response).into())
+                    new_service_endpoint).await {
+                    Ok((did_doc, signed_response)) => {
+                        InviterFullState::Requested((state, request, did_doc, signed_response).into())
                     }
                     Err(err) => {
                         let problem_report = ProblemReport::create()
                             .set_problem_code(ProblemCode::RequestProcessingError)
                             .set_explain(err.to_string())
-                            .set_thread_id(&request.id.0);
-
-                        send_message(
-                            &bootstrap_pairwise_info.pw_vk,
-                            &request.connection.did_doc,
-                            &problem_report.to_a2a_message()).ok();
+                            .set_thread_id(&request.thread_id());
+
+                        if let Ok(did_doc) = request.did_doc() {
+                            send_message(
+                                profile.wallet_handle()?,
+                                &bootstrap_pairwise_info.pw_vk,
+                                &did_doc,
+                                &problem_report.to_a2a_message_for(&request.protocol())).await.ok();
+                        }
                         InviterFullState::Null((state, problem_report).into())
                     }
                 }
@@ -309,29 +396,29 @@ impl SmConnectionInviter {
                 state.clone()
             }
         };
-        Ok(Self { source_id, pairwise_info: new_pairwise_info.clone(), state, send_message })
+        Ok(Self { source_id, pairwise_info: new_pairwise_info.clone(), state, profile, send_message })
     }
 
-    pub fn handle_ping(self, ping: Ping) -> VcxResult<Self> {
-        let Self { source_id, pairwise_info, state, send_message } = self;
+    pub async fn handle_ping(self, ping: Ping) -> VcxResult<Self> {
+        let Self { source_id, pairwise_info, state, profile, send_message } = self;
         let state = match state {
             InviterFullState::Responded(state) => {
-                state.handle_ping(&ping, &pairwise_info.pw_vk, send_message)?;
+                state.handle_ping(&ping, profile.wallet_handle()?, &pairwise_info.pw_vk, send_message).await?;
                 InviterFullState::Completed((state, ping).into())
             }
             InviterFullState::Completed(state) => {
-                state.handle_ping(&ping, &pairwise_info.pw_vk, send_message)?;
+                state.handle_ping(&ping, profile.wallet_handle()?, &pairwise_info.pw_vk, send_message).await?;
                 InviterFullState::Completed(state)
             }
             _ => {
                 state.clone()
             }
         };
-        Ok(Self { source_id, pairwise_info, state, send_message })
+        Ok(Self { source_id, pairwise_info, state, profile, send_message })
     }
 
-    pub fn handle_send_ping(self, comment: Option<String>) -> VcxResult<Self> {
-        let Self { source_id, pairwise_info, state, send_message } = self;
+    pub async fn handle_send_ping(self, comment: Option<String>) -> VcxResult<Self> {
+        let Self { source_id, pairwise_info, state, profile, send_message } = self;
         let state = match state {
             InviterFullState::Responded(state) => {
                 let ping =
@@ -339,22 +426,22 @@ impl SmConnectionInviter {
                         .request_response()
                         .set_comment(comment);
 
-                send_message(&pairwise_info.pw_vk, &state.did_doc, &ping.to_a2a_message()).ok();
+                send_message(profile.wallet_handle()?, &pairwise_info.pw_vk, &state.did_doc, &ping.to_a2a_message()).await.ok();
                 InviterFullState::Responded(state)
             }
             InviterFullState::Completed(state) => {
-                state.handle_send_ping(comment, &pairwise_info.pw_vk, send_message)?;
+                state.handle_send_ping(comment, profile.wallet_handle()?, &pairwise_info.pw_vk, send_message).await?;
                 InviterFullState::Completed(state)
             }
             _ => {
                 state.clone()
             }
         };
-        Ok(Self { source_id, pairwise_info, state, send_message })
+        Ok(Self { source_id, pairwise_info, state, profile, send_message })
     }
 
     pub fn handle_ping_response(self, ping_response: PingResponse) -> VcxResult<Self> {
-        let Self { source_id, pairwise_info, state, send_message } = self;
+        let Self { source_id, pairwise_info, state, profile, send_message } = self;
         let state = match state {
             InviterFullState::Responded(state) => {
                 InviterFullState::Completed((state, ping_response).into())
@@ -363,39 +450,39 @@ impl SmConnectionInviter {
                 state.clone()
             }
         };
-        Ok(Self { source_id, pairwise_info, state, send_message })
+        Ok(Self { source_id, pairwise_info, state, profile, send_message })
     }
 
-    pub fn handle_discover_features(self, query_: Option<String>, comment: Option<String>) -> VcxResult<Self> {
-        let Self { source_id, pairwise_info, state, send_message } = self;
+    pub async fn handle_discover_features(self, query_: Option<String>, comment: Option<String>) -> VcxResult<Self> {
+        let Self { source_id, pairwise_info, state, profile, send_message } = self;
         let state = match state {
             InviterFullState::Completed(state) => {
-                state.handle_discover_features(query_, comment, &pairwise_info.pw_vk, send_message)?;
+                state.handle_discover_features(query_, comment, profile.wallet_handle()?, &pairwise_info.pw_vk, send_message).await?;
                 InviterFullState::Completed(state)
             }
             _ => {
                 state.clone()
             }
         };
-        Ok(Self { source_id, pairwise_info, state, send_message })
+        Ok(Self { source_id, pairwise_info, state, profile, send_message })
     }
 
-    pub fn handle_discovery_query(self, query: Query) -> VcxResult<Self> {
-        let Self { source_id, pairwise_info, state, send_message } = self;
+    pub async fn handle_discovery_query(self, query: Query) -> VcxResult<Self> {
+        let Self { source_id, pairwise_info, state, profile, send_message } = self;
         let state = match state {
             InviterFullState::Completed(state) => {
-                state.handle_discovery_query(query, &pairwise_info.pw_vk, send_message)?;
+                state.handle_discovery_query(query, profile.wallet_handle()?, &pairwise_info.pw_vk, send_message).await?;
                 InviterFullState::Completed(state)
             }
             _ => {
                 state.clone()
             }
         };
-        Ok(Self { source_id, pairwise_info, state, send_message })
+        Ok(Self { source_id, pairwise_info, state, profile, send_message })
     }
 
     pub fn handle_disclose(self, disclose: Disclose) -> VcxResult<Self> {
-        let Self { source_id, pairwise_info, state, send_message } = self;
+        let Self { source_id, pairwise_info, state, profile, send_message } = self;
         let state = match state {
             InviterFullState::Completed(state) => {
                 InviterFullState::Completed((state.clone(), disclose.protocols).into())
@@ -404,11 +491,11 @@ impl SmConnectionInviter {
                 state.clone()
             }
         };
-        Ok(Self { source_id, pairwise_info, state, send_message })
+        Ok(Self { source_id, pairwise_info, state, profile, send_message })
     }
 
     pub fn handle_problem_report(self, problem_report: ProblemReport) -> VcxResult<Self> {
-        let Self { source_id, pairwise_info, state, send_message } = self;
+        let Self { source_id, pairwise_info, state, profile, send_message } = self;
         let state = match state {
             InviterFullState::Responded(state) => {
                 InviterFullState::Null((state, problem_report).into())
@@ -420,14 +507,14 @@ impl SmConnectionInviter {
                 state.clone()
             }
         };
-        Ok(Self { source_id, pairwise_info, state, send_message })
+        Ok(Self { source_id, pairwise_info, state, profile, send_message })
     }
 
-    pub fn handle_send_response(self) -> VcxResult<Self> {
-        let Self { source_id, pairwise_info, state, send_message } = self;
+    pub async fn handle_send_response(self) -> VcxResult<Self> {
+        let Self { source_id, pairwise_info, state, profile, send_message } = self;
         let state = match state {
             InviterFullState::Requested(state) => {
-                match Self::_send_response(&state, &pairwise_info.pw_vk.clone(), send_message) {
+                match Self::_send_response(&state, profile.wallet_handle()?, &pairwise_info.pw_vk.clone(), send_message).await {
                     Ok(_) => {
                         InviterFullState::Responded(state.into())
                     }
@@ -439,18 +526,18 @@ impl SmConnectionInviter {
                             .set_explain(err.to_string())
                             .set_thread_id(&state.thread_id);
 
-                        send_message(&pairwise_info.pw_vk, &state.did_doc, &problem_report.to_a2a_message()).ok();
+                        send_message(profile.wallet_handle()?, &pairwise_info.pw_vk, &state.did_doc, &problem_report.to_a2a_message_for(&state.signed_response.protocol())).await.ok();
                         InviterFullState::Null((state, problem_report).into())
                     }
                 }
             }
             _ => state.clone()
         };
-        Ok(Self { source_id, pairwise_info, state, send_message })
+        Ok(Self { source_id, pairwise_info, state, profile, send_message })
     }
 
     pub fn handle_ack(self, ack: Ack) -> VcxResult<Self> {
-        let Self { source_id, pairwise_info, state, send_message } = self;
+        let Self { source_id, pairwise_info, state, profile, send_message } = self;
         let state = match state {
             InviterFullState::Responded(state) => {
                 InviterFullState::Completed((state, ack).into())
@@ -459,21 +546,39 @@ impl SmConnectionInviter {
                 state.clone()
             }
         };
-        Ok(Self { source_id, pairwise_info, state, send_message })
+        Ok(Self { source_id, pairwise_info, state, profile, send_message })
+    }
+
+    pub fn handle_complete(self, complete: Complete) -> VcxResult<Self> {
+        let Self { source_id, pairwise_info, state, profile, send_message } = self;
+        let state = match state {
+            InviterFullState::Responded(state) => {
+                InviterFullState::Completed((state, complete).into())
+            }
+            _ => {
+                state.clone()
+            }
+        };
+        Ok(Self { source_id, pairwise_info, state, profile, send_message })
     }
 }
 
 #[cfg(test)]
 pub mod test {
+    use futures::future::{self, LocalBoxFuture};
+
     use crate::aries::messages::ack::tests::_ack;
     use crate::aries::messages::connection::problem_report::tests::_problem_report;
     use crate::aries::messages::connection::request::tests::_request;
     use crate::aries::messages::connection::response::tests::_signed_response;
+    use crate::aries::messages::did_exchange::request::tests::_did_exchange_request;
     use crate::aries::messages::discovery::disclose::tests::_disclose;
     use crate::aries::messages::discovery::query::tests::_query;
     use crate::aries::messages::trust_ping::ping::tests::_ping;
     use crate::aries::messages::trust_ping::ping_response::tests::_ping_response;
     use crate::aries::test::source_id;
+    use crate::aries::utils::task::block_on;
+    use crate::libindy::utils::wallet::get_wallet_handle;
     use crate::utils::devsetup::SetupMocks;
 
     use super::*;
@@ -481,13 +586,18 @@ pub mod test {
     pub mod inviter {
         use super::*;
 
-        fn _send_message(pv_wk: &str, did_doc: &DidDoc, a2a_message: &A2AMessage) -> VcxResult<()> {
-            VcxResult::Ok(())
+        fn _send_message<'a>(_wallet_handle: WalletHandle, pv_wk: &'a str, did_doc: &'a DidDoc, a2a_message: &'a A2AMessage) -> LocalBoxFuture<'a, VcxResult<()>> {
+            Box::pin(future::ready(VcxResult::Ok(())))
         }
 
         pub fn inviter_sm() -> SmConnectionInviter {
-            let pairwise_info = PairwiseInfo::create().unwrap();
-            SmConnectionInviter::new(&source_id(), pairwise_info, _send_message)
+            let pairwise_info = PairwiseInfo::create(get_wallet_handle()).unwrap();
+            SmConnectionInviter::new(&source_id(), pairwise_info, HandshakeProtocol::Connections, ProfileContext::Global, _send_message)
+        }
+
+        pub fn did_exchange_inviter_sm() -> SmConnectionInviter {
+            let pairwise_info = PairwiseInfo::create(get_wallet_handle()).unwrap();
+            SmConnectionInviter::new(&source_id(), pairwise_info, HandshakeProtocol::DidExchange, ProfileContext::Global, _send_message)
         }
 
         impl SmConnectionInviter {
@@ -503,11 +613,11 @@ pub mod test {
                 let service_endpoint = String::from("https://example.org/agent");
                 self = self.handle_connect(routing_keys, service_endpoint).unwrap();
 
-                let new_pairwise_info = PairwiseInfo::create().unwrap();
+                let new_pairwise_info = PairwiseInfo::create(get_wallet_handle()).unwrap();
                 let new_routing_keys: Vec<String> = vec!("verkey456".into());
                 let new_service_endpoint = String::from("https://example.org/agent");
-                self = self.handle_connection_request(_request(), &new_pairwise_info, new_routing_keys, new_service_endpoint).unwrap();
-                self = self.handle_send_response().unwrap();
+                self = block_on(self.handle_connection_request(AnyRequest::Connections(_request()), &new_pairwise_info, new_routing_keys, new_service_endpoint)).unwrap();
+                self = block_on(self.handle_send_response()).unwrap();
                 self
             }
 
@@ -519,8 +629,8 @@ pub mod test {
                 let new_pairwise_info = PairwiseInfo { pw_did: "AC3Gx1RoAz8iYVcfY47gjJ".to_string(), pw_vk: "verkey456".to_string() };
                 let new_routing_keys: Vec<String> = vec!("AC3Gx1RoAz8iYVcfY47gjJ".into());
                 let new_service_endpoint = String::from("https://example.org/agent");
-                self = self.handle_connection_request(_request(), &new_pairwise_info, new_routing_keys, new_service_endpoint).unwrap();
-                self = self.handle_send_response().unwrap();
+                self = block_on(self.handle_connection_request(AnyRequest::Connections(_request()), &new_pairwise_info, new_routing_keys, new_service_endpoint)).unwrap();
+                self = block_on(self.handle_send_response()).unwrap();
                 self = self.handle_ack(_ack()).unwrap();
                 self
             }
@@ -594,8 +704,8 @@ pub mod test {
                 let new_pairwise_info = PairwiseInfo { pw_did: "AC3Gx1RoAz8iYVcfY47gjJ".to_string(), pw_vk: "verkey456".to_string() };
                 let new_routing_keys: Vec<String> = vec!("AC3Gx1RoAz8iYVcfY47gjJ".into());
                 let new_service_endpoint = String::from("https://example.org/agent");
-                did_exchange_sm = did_exchange_sm.handle_connection_request(_request(), &new_pairwise_info, new_routing_keys, new_service_endpoint).unwrap();
-                did_exchange_sm = did_exchange_sm.handle_send_response().unwrap();
+                did_exchange_sm = block_on(did_exchange_sm.handle_connection_request(AnyRequest::Connections(_request()), &new_pairwise_info, new_routing_keys, new_service_endpoint)).unwrap();
+                did_exchange_sm = block_on(did_exchange_sm.handle_send_response()).unwrap();
                 assert_match!(InviterFullState::Responded(_), did_exchange_sm.state);
             }
 
@@ -612,11 +722,68 @@ pub mod test {
                 let new_pairwise_info = PairwiseInfo { pw_did: "AC3Gx1RoAz8iYVcfY47gjJ".to_string(), pw_vk: "verkey456".to_string() };
                 let new_routing_keys: Vec<String> = vec!("AC3Gx1RoAz8iYVcfY47gjJ".into());
                 let new_service_endpoint = String::from("https://example.org/agent");
-                did_exchange_sm = did_exchange_sm.handle_connection_request(request, &new_pairwise_info, new_routing_keys, new_service_endpoint).unwrap();
+                did_exchange_sm = block_on(did_exchange_sm.handle_connection_request(AnyRequest::Connections(request), &new_pairwise_info, new_routing_keys, new_service_endpoint)).unwrap();
 
                 assert_match!(InviterFullState::Null(_), did_exchange_sm.state);
             }
 
+            #[test]
+            #[cfg(feature = "general_test")]
+            fn test_did_exchange_protocol_from_null_to_completed_state() {
+                let _setup = SetupIndyMocks::init();
+
+                let mut did_exchange_sm = did_exchange_inviter_sm().to_inviter_invited_state();
+
+                match did_exchange_sm.get_invitation().unwrap() {
+                    AnyInvitation::OutOfBand(invitation) => {
+                        assert!(invitation.supports_handshake_protocol(&MessageFamilies::DidExchange));
+                        assert_eq!(vec![verkey_to_did_key(&did_exchange_sm.pairwise_info.pw_vk).unwrap()], invitation.inline_service().unwrap().recipient_keys);
+                    }
+                    invitation => panic!("Out-of-band invitation expected, got: {:?}", invitation)
+                }
+
+                assert!(did_exchange_sm.can_handle_message(&A2AMessage::DidExchangeRequest(_did_exchange_request())));
+                assert!(!did_exchange_sm.can_handle_message(&A2AMessage::ConnectionRequest(_request())));
+
+                let bootstrap_vk = did_exchange_sm.pairwise_info.pw_vk.clone();
+                let new_pairwise_info = PairwiseInfo::create(get_wallet_handle()).unwrap();
+                let new_routing_keys: Vec<String> = vec!("verkey456".into());
+                let new_service_endpoint = String::from("https://example.org/agent");
+                did_exchange_sm = block_on(did_exchange_sm.handle_connection_request(AnyRequest::DidExchange(_did_exchange_request()), &new_pairwise_info, new_routing_keys, new_service_endpoint)).unwrap();
+                did_exchange_sm = block_on(did_exchange_sm.handle_send_response()).unwrap();
+
+                match did_exchange_sm.state {
+                    InviterFullState::Responded(ref state) => {
+                        match state.signed_response {
+                            AnyResponse::DidExchange(ref response) => {
+                                assert!(response.from_thread(&_did_exchange_request().thread_id()));
+                                assert_eq!(new_pairwise_info.pw_did, response.verify(&bootstrap_vk).unwrap().id);
+                            }
+                            ref response => panic!("DID Exchange response expected, got: {:?}", response)
+                        }
+                    }
+                    _ => panic!("Inviter is expected to be in Responded state")
+                }
+
+                did_exchange_sm = did_exchange_sm.handle_complete(Complete::create().set_thread_id(&_did_exchange_request().thread_id())).unwrap();
+                assert_match!(InviterFullState::Completed(_), did_exchange_sm.state);
+            }
+
+            #[test]
+            #[cfg(feature = "general_test")]
+            fn test_did_exchange_protocol_is_kept_after_problem_report() {
+                let _setup = SetupIndyMocks::init();
+
+                let mut did_exchange_sm = did_exchange_inviter_sm().to_inviter_invited_state();
+
+                did_exchange_sm = did_exchange_sm.handle_problem_report(_problem_report()).unwrap();
+
+                match did_exchange_sm.state {
+                    InviterFullState::Null(ref state) => assert_eq!(HandshakeProtocol::DidExchange, state.protocol),
+                    _ => panic!("Inviter is expected to be in Null state")
+                }
+            }
+
             #[test]
             #[cfg(feature = "general_test")]
             fn test_did_exchange_handle_problem_report_message_from_invited_state() {
@@ -664,7 +831,7 @@ pub mod test {
 
                 let mut did_exchange_sm = inviter_sm().to_inviter_responded_state();
 
-                did_exchange_sm = did_exchange_sm.handle_ping(_ping()).unwrap();
+                did_exchange_sm = block_on(did_exchange_sm.handle_ping(_ping())).unwrap();
 
                 assert_match!(InviterFullState::Completed(_), did_exchange_sm.state);
             }
@@ -703,11 +870,11 @@ pub mod test {
                 let mut did_exchange_sm = inviter_sm().to_inviter_completed_state();
 
                 // Send Ping
-                did_exchange_sm = did_exchange_sm.handle_send_ping(None).unwrap();
+                did_exchange_sm = block_on(did_exchange_sm.handle_send_ping(None)).unwrap();
                 assert_match!(InviterFullState::Completed(_), did_exchange_sm.state);
 
                 // Ping
-                did_exchange_sm = did_exchange_sm.handle_ping(_ping()).unwrap();
+                did_exchange_sm = block_on(did_exchange_sm.handle_ping(_ping())).unwrap();
                 assert_match!(InviterFullState::Completed(_), did_exchange_sm.state);
 
                 // Ping Response
@@ -715,11 +882,11 @@ pub mod test {
                 assert_match!(InviterFullState::Completed(_), did_exchange_sm.state);
 
                 // Discovery Features
-                did_exchange_sm = did_exchange_sm.handle_discover_features(None, None).unwrap();
+                did_exchange_sm = block_on(did_exchange_sm.handle_discover_features(None, None)).unwrap();
                 assert_match!(InviterFullState::Completed(_), did_exchange_sm.state);
 
                 // Query
-                did_exchange_sm = did_exchange_sm.handle_discovery_query(_query()).unwrap();
+                did_exchange_sm = block_on(did_exchange_sm.handle_discovery_query(_query())).unwrap();
                 assert_match!(InviterFullState::Completed(_), did_exchange_sm.state);
 
                 // Disclose
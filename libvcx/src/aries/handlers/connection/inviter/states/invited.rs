@@ -1,29 +1,37 @@
 use crate::aries::handlers::connection::inviter::states::null::NullState;
 use crate::aries::handlers::connection::inviter::states::requested::RequestedState;
-use crate::aries::messages::connection::invite::Invitation;
+use crate::aries::messages::connection::did_doc::DidDoc;
+use crate::aries::messages::connection::handshake_protocol::HandshakeProtocol;
+use crate::aries::messages::connection::invite::AnyInvitation;
 use crate::aries::messages::connection::problem_report::ProblemReport;
-use crate::aries::messages::connection::request::Request;
-use crate::aries::messages::connection::response::SignedResponse;
+use crate::aries::messages::connection::request::AnyRequest;
+use crate::aries::messages::connection::response::AnyResponse;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InvitedState {
-    pub invitation: Invitation,
+    pub invitation: AnyInvitation,
+}
+
+impl InvitedState {
+    pub fn protocol(&self) -> HandshakeProtocol {
+        self.invitation.handshake_protocol().unwrap_or_default()
+    }
 }
 
 impl From<(InvitedState, ProblemReport)> for NullState {
-    fn from((_state, _error): (InvitedState, ProblemReport)) -> NullState {
+    fn from((state, _error): (InvitedState, ProblemReport)) -> NullState {
         trace!("ConnectionInviter: transit state from InvitedState to NullState");
-        NullState {}
+        NullState { protocol: state.protocol() }
     }
 }
 
-impl From<(InvitedState, Request, SignedResponse)> for RequestedState {
-    fn from((_state, request, signed_response): (InvitedState, Request, SignedResponse)) -> RequestedState {
+impl From<(InvitedState, AnyRequest, DidDoc, AnyResponse)> for RequestedState {
+    fn from((_state, request, did_doc, signed_response): (InvitedState, AnyRequest, DidDoc, AnyResponse)) -> RequestedState {
         trace!("ConnectionInviter: transit state from InvitedState to RespondedState");
         RequestedState {
             signed_response,
-            did_doc: request.connection.did_doc,
-            thread_id: request.id.0,
+            did_doc,
+            thread_id: request.thread_id(),
         }
     }
 }
\ No newline at end of file
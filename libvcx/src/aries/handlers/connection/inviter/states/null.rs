@@ -1,11 +1,15 @@
 use crate::aries::handlers::connection::inviter::states::invited::InvitedState;
-use crate::aries::messages::connection::invite::Invitation;
+use crate::aries::messages::connection::handshake_protocol::HandshakeProtocol;
+use crate::aries::messages::connection::invite::AnyInvitation;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct NullState {}
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct NullState {
+    #[serde(default)]
+    pub protocol: HandshakeProtocol,
+}
 
-impl From<(NullState, Invitation)> for InvitedState {
-    fn from((_state, invitation): (NullState, Invitation)) -> InvitedState {
+impl From<(NullState, AnyInvitation)> for InvitedState {
+    fn from((_state, invitation): (NullState, AnyInvitation)) -> InvitedState {
         trace!("ConnectionInviter: transit state from NullState to InvitedState");
         InvitedState { invitation }
     }
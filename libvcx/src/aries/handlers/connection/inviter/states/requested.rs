@@ -2,19 +2,19 @@ use crate::aries::handlers::connection::inviter::states::null::NullState;
 use crate::aries::handlers::connection::inviter::states::responded::RespondedState;
 use crate::aries::messages::connection::did_doc::DidDoc;
 use crate::aries::messages::connection::problem_report::ProblemReport;
-use crate::aries::messages::connection::response::SignedResponse;
+use crate::aries::messages::connection::response::AnyResponse;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RequestedState {
-    pub signed_response: SignedResponse,
+    pub signed_response: AnyResponse,
     pub did_doc: DidDoc,
     pub thread_id: String,
 }
 
 impl From<(RequestedState, ProblemReport)> for NullState {
-    fn from((_state, _error): (RequestedState, ProblemReport)) -> NullState {
+    fn from((state, _error): (RequestedState, ProblemReport)) -> NullState {
         trace!("ConnectionInviter: transit state from RequestedState to NullState");
-        NullState {}
+        NullState { protocol: state.signed_response.protocol() }
     }
 }
 
@@ -1,26 +1,29 @@
+use indy::WalletHandle;
+
 use crate::aries::handlers::connection::inviter::states::complete::CompleteState;
 use crate::aries::handlers::connection::inviter::states::null::NullState;
 use crate::aries::handlers::connection::util::handle_ping;
-use crate::aries::messages::a2a::A2AMessage;
 use crate::aries::messages::ack::Ack;
 use crate::aries::messages::connection::did_doc::DidDoc;
 use crate::aries::messages::connection::problem_report::ProblemReport;
-use crate::aries::messages::connection::response::SignedResponse;
+use crate::aries::messages::connection::response::AnyResponse;
+use crate::aries::messages::did_exchange::complete::Complete;
 use crate::aries::messages::trust_ping::ping::Ping;
 use crate::aries::messages::trust_ping::ping_response::PingResponse;
+use crate::aries::utils::SendMessageFn;
 use crate::error::prelude::*;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RespondedState {
-    pub signed_response: SignedResponse,
+    pub signed_response: AnyResponse,
     pub did_doc: DidDoc,
 }
 
 
 impl From<(RespondedState, ProblemReport)> for NullState {
-    fn from((_state, _error): (RespondedState, ProblemReport)) -> NullState {
+    fn from((state, _error): (RespondedState, ProblemReport)) -> NullState {
         trace!("ConnectionInviter: transit state from RespondedState to NullState");
-        NullState {}
+        NullState { protocol: state.signed_response.protocol() }
     }
 }
 
@@ -31,6 +34,13 @@ impl From<(RespondedState, Ack)> for CompleteState {
     }
 }
 
+impl From<(RespondedState, Complete)> for CompleteState {
+    fn from((state, _complete): (RespondedState, Complete)) -> CompleteState {
+        trace!("ConnectionInviter: transit state from RespondedState to CompleteState");
+        CompleteState { did_doc: state.did_doc, protocols: None }
+    }
+}
+
 impl From<(RespondedState, Ping)> for CompleteState {
     fn from((state, _ping): (RespondedState, Ping)) -> CompleteState {
         trace!("ConnectionInviter: transit state from RespondedState to CompleteState");
@@ -46,11 +56,12 @@ impl From<(RespondedState, PingResponse)> for CompleteState {
 }
 
 impl RespondedState {
-    pub fn handle_ping(&self,
-                       ping: &Ping,
-                       pw_vk: &str,
-                       send_message: fn(&str, &DidDoc, &A2AMessage) -> VcxResult<()>,
+    pub async fn handle_ping(&self,
+                             ping: &Ping,
+                             wallet_handle: WalletHandle,
+                             pw_vk: &str,
+                             send_message: SendMessageFn,
     ) -> VcxResult<()> {
-        handle_ping(ping, pw_vk, &self.did_doc, send_message)
+        handle_ping(ping, wallet_handle, pw_vk, &self.did_doc, send_message).await
     }
 }
@@ -4,5 +4,6 @@ pub mod legacy_agent_info;
 pub mod connection;
 pub mod invitee;
 pub mod inviter;
+pub mod multi_use_invitation;
 mod util;
 
@@ -0,0 +1,368 @@
+use std::collections::HashMap;
+
+use crate::aries::handlers::connection::cloud_agent::CloudAgentInfo;
+use crate::aries::handlers::connection::connection::{Connection, ConnectionState};
+use crate::aries::handlers::connection::inviter::state_machine::InviterState;
+use crate::aries::handlers::connection::pairwise_info::PairwiseInfo;
+use crate::aries::messages::a2a::A2AMessage;
+use crate::aries::messages::connection::handshake_protocol::HandshakeProtocol;
+use crate::aries::messages::connection::invite::{AnyInvitation, PublicInvitation};
+use crate::error::prelude::*;
+use crate::libindy::utils::ledger::{self, EndpointAttrib};
+use crate::profile::ProfileContext;
+
+/**
+Invitation which can be accepted by any number of invitees. Its recipient key stays the same,
+every request received on it spawns a new inviter connection with its own pairwise DID.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultiUseInvitation {
+    #[serde(skip)]
+    profile: ProfileContext,
+    source_id: String,
+    pairwise_info: PairwiseInfo,
+    cloud_agent_info: CloudAgentInfo,
+    invitation: AnyInvitation,
+    #[serde(default)]
+    connections_count: u32,
+    #[serde(default)]
+    revoked: bool,
+    #[serde(default)]
+    handled_request_uids: Vec<String>,
+}
+
+/**
+Outcome of accepting a single request. `connection` is set if a connection has been spawned for the request,
+`error` is set if the request could not be handled or marked as reviewed.
+ */
+pub struct AcceptedRequest {
+    pub uid: String,
+    pub connection: Option<Connection>,
+    pub error: Option<String>,
+}
+
+impl MultiUseInvitation {
+    pub fn create(profile: ProfileContext, source_id: &str, protocol: HandshakeProtocol) -> VcxResult<MultiUseInvitation> {
+        trace!("MultiUseInvitation::create >>> profile: {:?}, source_id: {}, protocol: {:?}", profile, source_id, protocol);
+        let mut connection = Connection::create_with_handshake_protocol(profile.clone(), source_id, protocol, true)?;
+        connection.connect()?;
+        let invitation = connection.get_invite_details()
+            .cloned()
+            .ok_or(VcxError::from_msg(VcxErrorKind::InvalidState, "Invitation has not been generated"))?;
+
+        Ok(MultiUseInvitation {
+            profile,
+            source_id: source_id.to_string(),
+            pairwise_info: connection.pairwise_info().clone(),
+            cloud_agent_info: connection.cloud_agent_info(),
+            invitation,
+            connections_count: 0,
+            revoked: false,
+            handled_request_uids: Vec::new(),
+        })
+    }
+
+    /**
+    Creates invitation accepting requests addressed to the public DID of the institution of the profile.
+    Invitees resolve the endpoint of the agent receiving the requests from the ledger, see `publish_endpoint`.
+     */
+    pub fn create_public(profile: ProfileContext, source_id: &str) -> VcxResult<MultiUseInvitation> {
+        trace!("MultiUseInvitation::create_public >>> profile: {:?}, source_id: {}", profile, source_id);
+        let pairwise_info = PairwiseInfo {
+            pw_did: profile.institution_did()?,
+            pw_vk: profile.institution_verkey()?,
+        };
+        let cloud_agent_info = CloudAgentInfo::create(&profile, &pairwise_info)?;
+
+        let invitation = PublicInvitation::create()
+            .set_label(source_id.to_string())
+            .set_did(pairwise_info.pw_did.clone());
+
+        Ok(MultiUseInvitation {
+            profile,
+            source_id: source_id.to_string(),
+            pairwise_info,
+            cloud_agent_info,
+            invitation: AnyInvitation::Public(invitation),
+            connections_count: 0,
+            revoked: false,
+            handled_request_uids: Vec::new(),
+        })
+    }
+
+    /**
+    Binds deserialized invitation to the profile, the profile is not part of the serialized data.
+     */
+    pub fn set_profile(&mut self, profile: ProfileContext) {
+        self.profile = profile;
+    }
+
+    pub fn profile(&self) -> &ProfileContext {
+        &self.profile
+    }
+
+    pub fn get_source_id(&self) -> String {
+        self.source_id.clone()
+    }
+
+    pub fn get_invitation(&self) -> &AnyInvitation {
+        &self.invitation
+    }
+
+    pub fn recipient_key(&self) -> &str {
+        &self.pairwise_info.pw_vk
+    }
+
+    pub fn connections_count(&self) -> u32 {
+        self.connections_count
+    }
+
+    pub fn is_revoked(&self) -> bool {
+        self.revoked
+    }
+
+    /**
+    Writes endpoint of the agent receiving requests on the public invitation to the ledger as `endpoint` ATTRIB
+    of the DID. With `endorser` set the transaction is not written but returned to be passed to the endorser.
+     */
+    pub fn publish_endpoint(&self, endorser: Option<&str>) -> VcxResult<Option<String>> {
+        trace!("MultiUseInvitation::publish_endpoint >>> source_id: {}, endorser: {:?}", self.source_id, endorser);
+        match self.invitation {
+            AnyInvitation::Public(_) => {}
+            _ => return Err(VcxError::from_msg(VcxErrorKind::InvalidState, format!("Invitation {} is not public", self.source_id)))
+        };
+        let endpoint = EndpointAttrib {
+            endpoint: self.cloud_agent_info.service_endpoint(&self.profile)?,
+            routing_keys: self.cloud_agent_info.routing_keys(&self.profile)?,
+        };
+        match endorser {
+            Some(endorser) => ledger::prepare_service_endpoint_for_endorser(&self.profile, &self.pairwise_info.pw_did, &endpoint, endorser).map(Some),
+            None => ledger::publish_service_endpoint(&self.profile, &self.pairwise_info.pw_did, &endpoint).map(|_| None)
+        }
+    }
+
+    /**
+    Stops accepting requests, connections spawned earlier are not affected.
+     */
+    pub fn revoke(&mut self) {
+        trace!("MultiUseInvitation::revoke >>> source_id: {}", self.source_id);
+        self.revoked = true;
+    }
+
+    pub fn is_request(&self, message: &A2AMessage) -> bool {
+        match (self.invitation.handshake_protocol().unwrap_or_default(), message) {
+            (HandshakeProtocol::Connections, A2AMessage::ConnectionRequest(_)) => true,
+            (HandshakeProtocol::DidExchange, A2AMessage::DidExchangeRequest(_)) => true,
+            _ => false
+        }
+    }
+
+    /**
+    Spawns new inviter connection handling the request. The connection gets fresh pairwise keys
+    and (with autohop) sends the response right away.
+     */
+    pub fn handle_request(&mut self, request: &A2AMessage) -> VcxResult<Connection> {
+        trace!("MultiUseInvitation::handle_request >>> source_id: {}", self.source_id);
+        if self.revoked {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidState, format!("Invitation {} has been revoked", self.source_id)));
+        }
+        if !self.is_request(request) {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidMessages, "Message is not a request for the invitation"));
+        }
+
+        let mut connection = Connection::create_invited(self.profile.clone(), &self.source_id, self.pairwise_info.clone(), self.cloud_agent_info.clone(), self.invitation.clone(), true);
+        connection.update_state_with_message(request)?;
+        if connection.get_state() == ConnectionState::Inviter(InviterState::Null) {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidMessages, "Request has been rejected, problem report sent to the invitee"));
+        }
+        self.connections_count += 1;
+        Ok(connection)
+    }
+
+    /**
+    Downloads requests received on the invitation from the agency, keyed by message uid.
+     */
+    pub fn get_requests(&self) -> VcxResult<HashMap<String, A2AMessage>> {
+        let messages = self.cloud_agent_info.get_messages_noauth(&self.profile, &self.pairwise_info)?;
+        Ok(messages.into_iter()
+            .filter(|(_, message)| self.is_request(message))
+            .collect())
+    }
+
+    pub fn update_message_status(&self, uid: String) -> VcxResult<()> {
+        self.cloud_agent_info.update_message_status(&self.profile, &self.pairwise_info, uid)
+    }
+
+    /**
+    Spawns connections for all pending requests received on the invitation.
+     */
+    pub fn accept_requests(&mut self) -> VcxResult<Vec<AcceptedRequest>> {
+        trace!("MultiUseInvitation::accept_requests >>> source_id: {}", self.source_id);
+        if self.revoked {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidState, format!("Invitation {} has been revoked", self.source_id)));
+        }
+        let requests = self.get_requests()?;
+        let cloud_agent_info = self.cloud_agent_info.clone();
+        let pairwise_info = self.pairwise_info.clone();
+        let profile = self.profile.clone();
+        Ok(self.handle_requests(requests, |uid| cloud_agent_info.update_message_status(&profile, &pairwise_info, uid)))
+    }
+
+    /**
+    Spawns connection for each request and marks the request as reviewed. Request which could not be marked
+    is remembered, so it does not spawn another connection when it is downloaded again.
+     */
+    pub fn handle_requests<F>(&mut self, requests: HashMap<String, A2AMessage>, update_message_status: F) -> Vec<AcceptedRequest>
+        where F: Fn(String) -> VcxResult<()> {
+        let mut accepted = Vec::new();
+        for (uid, request) in requests {
+            let (connection, error) = if self.handled_request_uids.contains(&uid) {
+                (None, None)
+            } else {
+                match self.handle_request(&request) {
+                    Ok(connection) => (Some(connection), None),
+                    Err(err) => {
+                        warn!("MultiUseInvitation::handle_requests >>> failed to handle request {}: {}", uid, err);
+                        (None, Some(err.to_string()))
+                    }
+                }
+            };
+            let error = match update_message_status(uid.clone()) {
+                Ok(()) => {
+                    self.handled_request_uids.retain(|handled| handled != &uid);
+                    error
+                }
+                Err(err) => {
+                    warn!("MultiUseInvitation::handle_requests >>> failed to update status of request {}: {}", uid, err);
+                    if !self.handled_request_uids.contains(&uid) {
+                        self.handled_request_uids.push(uid.clone());
+                    }
+                    error.or_else(|| Some(err.to_string()))
+                }
+            };
+            if connection.is_some() || error.is_some() {
+                accepted.push(AcceptedRequest { uid, connection, error });
+            }
+        }
+        accepted
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::aries::handlers::connection::inviter::state_machine::InviterState;
+    use crate::settings;
+    use crate::utils::devsetup::SetupMocks;
+    use crate::utils::mockdata::mockdata_connection::ARIES_CONNECTION_REQUEST;
+
+    use super::*;
+
+    pub fn _request() -> A2AMessage {
+        serde_json::from_str(ARIES_CONNECTION_REQUEST).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_multi_use_invitation_spawns_connection_per_request() {
+        let _setup = SetupMocks::init();
+
+        let mut invitation = MultiUseInvitation::create(ProfileContext::Global, "test", HandshakeProtocol::Connections).unwrap();
+        let recipient_key = invitation.recipient_key().to_string();
+
+        let connection_1 = invitation.handle_request(&_request()).unwrap();
+        let connection_2 = invitation.handle_request(&_request()).unwrap();
+
+        assert_eq!(ConnectionState::Inviter(InviterState::Responded), connection_1.get_state());
+        assert_eq!(ConnectionState::Inviter(InviterState::Responded), connection_2.get_state());
+        assert_eq!(2, invitation.connections_count());
+        assert_eq!(recipient_key, invitation.recipient_key());
+        assert!(connection_1.get_invite_details().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_multi_use_invitation_rejects_requests_once_revoked() {
+        let _setup = SetupMocks::init();
+
+        let mut invitation = MultiUseInvitation::create(ProfileContext::Global, "test", HandshakeProtocol::Connections).unwrap();
+        invitation.revoke();
+
+        assert_eq!(VcxErrorKind::InvalidState, invitation.handle_request(&_request()).err().unwrap().kind());
+        assert_eq!(0, invitation.connections_count());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_public_invitation_spawns_connection_per_request() {
+        let _setup = SetupMocks::init();
+        settings::set_config_value(settings::CONFIG_INSTITUTION_VERKEY, crate::utils::constants::VERKEY);
+
+        let mut invitation = MultiUseInvitation::create_public(ProfileContext::Global, "test").unwrap();
+        let did = settings::get_config_value(settings::CONFIG_INSTITUTION_DID).unwrap();
+        match invitation.get_invitation() {
+            AnyInvitation::Public(public_invitation) => assert_eq!(did, public_invitation.did),
+            other => panic!("Public invitation expected, got: {:?}", other)
+        }
+        assert_eq!(settings::get_config_value(settings::CONFIG_INSTITUTION_VERKEY).unwrap(), invitation.recipient_key());
+
+        let connection = invitation.handle_request(&_request()).unwrap();
+        assert_eq!(ConnectionState::Inviter(InviterState::Responded), connection.get_state());
+        assert_ne!(did, connection.pairwise_info().pw_did);
+        assert_eq!(1, invitation.connections_count());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_publish_endpoint_of_public_invitation() {
+        let _setup = SetupMocks::init();
+        settings::set_config_value(settings::CONFIG_INSTITUTION_VERKEY, crate::utils::constants::VERKEY);
+
+        let invitation = MultiUseInvitation::create_public(ProfileContext::Global, "test").unwrap();
+        assert_eq!(None, invitation.publish_endpoint(None).unwrap());
+        assert_eq!(Some(crate::utils::constants::REQUEST_WITH_ENDORSER.to_string()),
+                   invitation.publish_endpoint(Some("NcYxiDXkpYi6ov5FcYDi1e")).unwrap());
+
+        let invitation = MultiUseInvitation::create(ProfileContext::Global, "test", HandshakeProtocol::Connections).unwrap();
+        assert_eq!(VcxErrorKind::InvalidState, invitation.publish_endpoint(None).unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_multi_use_invitation_rejects_request_of_other_protocol() {
+        let _setup = SetupMocks::init();
+
+        let mut invitation = MultiUseInvitation::create(ProfileContext::Global, "test", HandshakeProtocol::DidExchange).unwrap();
+
+        assert!(!invitation.is_request(&_request()));
+        assert_eq!(VcxErrorKind::InvalidMessages, invitation.handle_request(&_request()).err().unwrap().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_multi_use_invitation_keeps_connection_if_status_update_fails() {
+        let _setup = SetupMocks::init();
+
+        let mut invitation = MultiUseInvitation::create(ProfileContext::Global, "test", HandshakeProtocol::Connections).unwrap();
+        let mut requests = HashMap::new();
+        requests.insert(String::from("uid_1"), _request());
+        requests.insert(String::from("uid_2"), _request());
+
+        let accepted = invitation.handle_requests(requests.clone(), |uid| match uid.as_str() {
+            "uid_1" => Err(VcxError::from_msg(VcxErrorKind::PostMessageFailed, "Agency unreachable")),
+            _ => Ok(())
+        });
+        assert_eq!(2, accepted.len());
+        assert!(accepted.iter().all(|request| request.connection.is_some()));
+        let failed = accepted.iter().find(|request| request.uid == "uid_1").unwrap();
+        assert!(failed.error.as_ref().unwrap().contains("Agency unreachable"));
+        assert_eq!(None, accepted.iter().find(|request| request.uid == "uid_2").unwrap().error);
+        assert_eq!(2, invitation.connections_count());
+
+        // request downloaded again only gets marked as reviewed
+        requests.remove("uid_2");
+        let accepted = invitation.handle_requests(requests, |_| Ok(()));
+        assert!(accepted.is_empty());
+        assert_eq!(2, invitation.connections_count());
+        assert!(invitation.handled_request_uids.is_empty());
+    }
+}
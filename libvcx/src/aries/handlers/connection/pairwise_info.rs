@@ -1,5 +1,8 @@
+use indy::WalletHandle;
+
+use crate::aries::utils::task::block_on;
 use crate::error::VcxResult;
-use crate::libindy::utils::signus::create_and_store_my_did;
+use crate::libindy::utils::signus::create_and_store_my_did_async;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PairwiseInfo {
@@ -17,8 +20,12 @@ impl Default for PairwiseInfo {
 }
 
 impl PairwiseInfo {
-    pub fn create() -> VcxResult<PairwiseInfo> {
-        let (pw_did, pw_vk) = create_and_store_my_did(None, None)?;
+    pub fn create(wallet_handle: WalletHandle) -> VcxResult<PairwiseInfo> {
+        block_on(Self::create_async(wallet_handle))
+    }
+
+    pub async fn create_async(wallet_handle: WalletHandle) -> VcxResult<PairwiseInfo> {
+        let (pw_did, pw_vk) = create_and_store_my_did_async(wallet_handle, None, None).await?;
         Ok(PairwiseInfo { pw_did, pw_vk })
     }
 }
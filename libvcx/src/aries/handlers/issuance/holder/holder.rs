@@ -1,24 +1,53 @@
 use std::collections::HashMap;
+use std::future::Future;
 
 use crate::aries::handlers::issuance::holder::state_machine::HolderSM;
 use crate::aries::handlers::issuance::messages::CredentialIssuanceMessage;
+use crate::aries::handlers::state_timing::StateTiming;
 use crate::aries::messages::a2a::A2AMessage;
 use crate::aries::handlers::connection::connection::Connection;
 use crate::aries::messages::issuance::credential_offer::CredentialOffer;
+use crate::aries::messages::issuance::credential_proposal::CredentialProposal;
 use crate::error::prelude::*;
+use crate::profile::ProfileContext;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Holder {
+    #[serde(skip)]
+    profile: ProfileContext,
     holder_sm: HolderSM
 }
 
 impl Holder {
-    pub fn create(credential_offer: CredentialOffer, source_id: &str) -> VcxResult<Holder> {
+    pub fn create(profile: ProfileContext, credential_offer: CredentialOffer, source_id: &str) -> VcxResult<Holder> {
         trace!("Holder::holder_create_credential >>> credential_offer: {:?}, source_id: {:?}", credential_offer, source_id);
 
         let holder_sm = HolderSM::new(credential_offer, source_id.to_string());
 
-        Ok(Holder { holder_sm })
+        Ok(Holder { profile, holder_sm })
+    }
+
+    pub fn create_with_proposal(profile: ProfileContext, credential_proposal: CredentialProposal, source_id: &str) -> VcxResult<Holder> {
+        trace!("Holder::create_with_proposal >>> credential_proposal: {:?}, source_id: {:?}", credential_proposal, source_id);
+
+        let holder_sm = HolderSM::new_with_proposal(credential_proposal, source_id.to_string());
+
+        Ok(Holder { profile, holder_sm })
+    }
+
+    /**
+    Binds deserialized holder to the profile, the profile is not part of the serialized data.
+     */
+    pub fn set_profile(&mut self, profile: ProfileContext) {
+        self.profile = profile;
+    }
+
+    pub fn profile(&self) -> &ProfileContext {
+        &self.profile
+    }
+
+    pub fn send_proposal(&mut self, send_message: impl Fn(&A2AMessage) -> VcxResult<()>) -> VcxResult<()> {
+        self.step(CredentialIssuanceMessage::CredentialProposalSend(), Some(&send_message))
     }
 
     pub fn send_request(&mut self, my_pw_did: String, send_message: impl Fn(&A2AMessage) -> VcxResult<()>) -> VcxResult<()> {
@@ -41,6 +70,10 @@ impl Holder {
         self.holder_sm.get_source_id()
     }
 
+    pub fn get_thread_id(&self) -> String {
+        self.holder_sm.thread_id()
+    }
+
     pub fn get_credential(&self) -> VcxResult<(String, A2AMessage)> {
         self.holder_sm.get_credential()
     }
@@ -66,19 +99,32 @@ impl Holder {
     }
 
     pub fn is_revokable(&self) -> VcxResult<bool> {
-        self.holder_sm.is_revokable()
+        self.holder_sm.is_revokable(&self.profile)
     }
 
     pub fn delete_credential(&self) -> VcxResult<()> {
-        self.holder_sm.delete_credential()
+        self.holder_sm.delete_credential(&self.profile)
     }
 
     pub fn get_credential_status(&self) -> VcxResult<u32> {
         Ok(self.holder_sm.credential_status())
     }
 
+    pub fn get_state_timing(&self) -> StateTiming {
+        self.holder_sm.get_state_timing()
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.holder_sm.is_expired()
+    }
+
+    pub fn abandon(&mut self, reason: Option<String>, send_message: Option<&impl Fn(&A2AMessage) -> VcxResult<()>>) -> VcxResult<()> {
+        self.holder_sm = self.holder_sm.clone().abandon(reason, send_message)?;
+        Ok(())
+    }
+
     pub fn step(&mut self, message: CredentialIssuanceMessage, send_message: Option<&impl Fn(&A2AMessage) -> VcxResult<()>>) -> VcxResult<()> {
-        self.holder_sm = self.holder_sm.clone().handle_message(message, send_message)?;
+        self.holder_sm = self.holder_sm.clone().handle_message(&self.profile, message, send_message)?;
         Ok(())
     }
 
@@ -95,3 +141,40 @@ impl Holder {
         Ok(self.get_state())
     }
 }
+
+impl Holder {
+    pub async fn send_proposal_async(&mut self, connection: &Connection) -> VcxResult<()> {
+        let send_message = connection.send_message_closure_async()?;
+        self.step_async(CredentialIssuanceMessage::CredentialProposalSend(), Some(&send_message)).await
+    }
+
+    pub async fn send_request_async(&mut self, connection: &Connection) -> VcxResult<()> {
+        let my_pw_did = connection.pairwise_info().pw_did.clone();
+        let send_message = connection.send_message_closure_async()?;
+        self.step_async(CredentialIssuanceMessage::CredentialRequestSend(my_pw_did), Some(&send_message)).await
+    }
+
+    pub async fn delete_credential_async(&self) -> VcxResult<()> {
+        self.holder_sm.delete_credential_async(&self.profile).await
+    }
+
+    pub async fn step_async<F, Fut>(&mut self, message: CredentialIssuanceMessage, send_message: Option<&F>) -> VcxResult<()>
+        where F: Fn(A2AMessage) -> Fut,
+              Fut: Future<Output=VcxResult<()>> {
+        self.holder_sm = self.holder_sm.clone().handle_message_async(&self.profile, message, send_message).await?;
+        Ok(())
+    }
+
+    pub async fn update_state_async(&mut self, connection: &Connection) -> VcxResult<u32> {
+        trace!("Holder::update_state_async >>> ");
+        if self.is_terminal_state() { return Ok(self.get_state()); }
+        let send_message = connection.send_message_closure_async()?;
+
+        let messages = connection.get_messages_async().await?;
+        if let Some((uid, msg)) = self.find_message_to_handle(messages) {
+            self.step_async(msg.into(), Some(&send_message)).await?;
+            connection.update_message_status_async(uid).await?;
+        }
+        Ok(self.get_state())
+    }
+}
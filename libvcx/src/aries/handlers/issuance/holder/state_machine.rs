@@ -1,22 +1,37 @@
 use std::collections::HashMap;
+use std::future::Future;
+use std::mem;
 
 use crate::api_lib::VcxStateType;
 use crate::aries::handlers::issuance::holder::states::finished::FinishedHolderState;
+use crate::aries::handlers::issuance::holder::states::initial::InitialHolderState;
 use crate::aries::handlers::issuance::holder::states::offer_received::OfferReceivedState;
+use crate::aries::handlers::issuance::holder::states::proposal_sent::ProposalSentState;
 use crate::aries::handlers::issuance::holder::states::request_sent::RequestSentState;
 use crate::aries::handlers::issuance::messages::CredentialIssuanceMessage;
+use crate::aries::handlers::state_timing::{self, StateTiming};
 use crate::aries::messages::a2a::A2AMessage;
+use crate::aries::messages::a2a::protocol_version::ProtocolVersion;
 use crate::aries::messages::error::ProblemReport;
 use crate::aries::messages::issuance::credential::Credential;
 use crate::aries::messages::issuance::credential_ack::CredentialAck;
 use crate::aries::messages::issuance::credential_offer::CredentialOffer;
+use crate::aries::messages::issuance::credential_proposal::CredentialProposal;
 use crate::aries::messages::issuance::credential_request::CredentialRequest;
 use crate::aries::messages::status::Status;
+use crate::aries::messages::timing;
+use crate::aries::utils::sync_sender;
+use crate::aries::utils::task::block_on;
 use crate::error::prelude::*;
-use crate::libindy::utils::anoncreds::{self, get_cred_def_json, libindy_prover_create_credential_req, libindy_prover_delete_credential, libindy_prover_store_credential};
+use crate::libindy::proofs::prover::credential_store::{self, CredentialMetadata};
+use crate::libindy::utils::anoncreds::{self, get_cred_def_json_async, libindy_prover_create_credential_req_async, libindy_prover_store_credential_async};
+use crate::profile::ProfileContext;
+use crate::settings;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum HolderState {
+    Initial(InitialHolderState),
+    ProposalSent(ProposalSentState),
     OfferReceived(OfferReceivedState),
     RequestSent(RequestSentState),
     Finished(FinishedHolderState),
@@ -27,23 +42,45 @@ pub struct HolderSM {
     state: HolderState,
     source_id: String,
     thread_id: String,
+    #[serde(default)]
+    timing: StateTiming,
 }
 
 impl HolderSM {
     pub fn new(offer: CredentialOffer, source_id: String) -> Self {
         HolderSM {
-            thread_id: offer.id.0.clone(),
+            thread_id: offer.thread_id(),
+            timing: StateTiming::entered(offer.expires_time()),
             state: HolderState::OfferReceived(OfferReceivedState::new(offer)),
             source_id,
         }
     }
 
+    pub fn new_with_proposal(proposal: CredentialProposal, source_id: String) -> Self {
+        HolderSM {
+            thread_id: proposal.thread_id(),
+            state: HolderState::Initial(InitialHolderState::new(proposal)),
+            source_id,
+            timing: StateTiming::entered(None),
+        }
+    }
+
     pub fn get_source_id(&self) -> String {
         self.source_id.clone()
     }
 
+    pub fn thread_id(&self) -> String {
+        self.thread_id.clone()
+    }
+
+    pub fn get_state_timing(&self) -> StateTiming {
+        self.timing.clone()
+    }
+
     pub fn state(&self) -> u32 {
         match self.state {
+            HolderState::Initial(_) => VcxStateType::VcxStateInitialized as u32,
+            HolderState::ProposalSent(_) => VcxStateType::VcxStateOfferSent as u32,
             HolderState::OfferReceived(_) => VcxStateType::VcxStateRequestReceived as u32,
             HolderState::RequestSent(_) => VcxStateType::VcxStateOfferSent as u32,
             HolderState::Finished(ref status) => {
@@ -60,6 +97,24 @@ impl HolderSM {
 
         for (uid, message) in messages {
             match self.state {
+                HolderState::Initial(_) => {
+                    // do not process messages
+                }
+                HolderState::ProposalSent(_) => {
+                    match message {
+                        A2AMessage::CredentialOffer(offer) => {
+                            if offer.thread_id() == self.thread_id {
+                                return Some((uid, A2AMessage::CredentialOffer(offer)));
+                            }
+                        }
+                        A2AMessage::CommonProblemReport(problem_report) => {
+                            if problem_report.from_thread(&self.thread_id) {
+                                return Some((uid, A2AMessage::CommonProblemReport(problem_report)));
+                            }
+                        }
+                        _ => {}
+                    }
+                }
                 HolderState::OfferReceived(_) => {
                     // do not process messages
                 }
@@ -88,25 +143,61 @@ impl HolderSM {
     }
 
     pub fn step(state: HolderState, source_id: String, thread_id: String) -> Self {
-        HolderSM { state, source_id, thread_id }
+        HolderSM::step_with_timing(state, source_id, thread_id, StateTiming::entered(None))
     }
 
-    pub fn handle_message(self, cim: CredentialIssuanceMessage, send_message: Option<&impl Fn(&A2AMessage) -> VcxResult<()>>) -> VcxResult<HolderSM> {
-        trace!("Holder::handle_message >>> cim: {:?}, state: {:?}", cim, self.state);
+    pub fn step_with_timing(state: HolderState, source_id: String, thread_id: String, timing: StateTiming) -> Self {
+        HolderSM { state, source_id, thread_id, timing }
+    }
 
-        let HolderSM { state, source_id, thread_id } = self;
+    pub fn handle_message(self, profile: &ProfileContext, cim: CredentialIssuanceMessage, send_message: Option<&impl Fn(&A2AMessage) -> VcxResult<()>>) -> VcxResult<HolderSM> {
+        block_on(self.handle_message_async(profile, cim, send_message.map(sync_sender).as_ref()))
+    }
+
+    pub async fn handle_message_async<F, Fut>(self, profile: &ProfileContext, cim: CredentialIssuanceMessage, send_message: Option<&F>) -> VcxResult<HolderSM>
+        where F: Fn(A2AMessage) -> Fut,
+              Fut: Future<Output=VcxResult<()>> {
+        trace!("Holder::handle_message_async >>> cim: {:?}, state: {:?}", cim, self.state);
+
+        let HolderSM { state, source_id, thread_id, timing } = self;
+        let previous_state = mem::discriminant(&state);
+        let expires_time = timing::expires_time(settings::get_expiry(settings::CONFIG_ISSUE_CREDENTIAL_EXPIRY));
         let state = match state {
+            HolderState::Initial(state_data) => match cim {
+                CredentialIssuanceMessage::CredentialProposalSend() => {
+                    send_message.ok_or(
+                        VcxError::from_msg(VcxErrorKind::InvalidState, "Attempted to call undefined send_message callback")
+                    )?(state_data.proposal.to_a2a_message()).await?;
+                    HolderState::ProposalSent(state_data.into())
+                }
+                _ => {
+                    warn!("Credential Issuance can only start on holder side with Credential Offer or by sending Credential Proposal");
+                    HolderState::Initial(state_data)
+                }
+            },
+            HolderState::ProposalSent(state_data) => match cim {
+                CredentialIssuanceMessage::CredentialOffer(offer) => {
+                    HolderState::OfferReceived((state_data, offer).into())
+                }
+                CredentialIssuanceMessage::ProblemReport(problem_report) => {
+                    HolderState::Finished((state_data, problem_report).into())
+                }
+                _ => {
+                    warn!("In this state Credential Issuance can accept only Offer and Problem Report");
+                    HolderState::ProposalSent(state_data)
+                }
+            },
             HolderState::OfferReceived(state_data) => match cim {
                 CredentialIssuanceMessage::CredentialRequestSend(my_pw_did) => {
-                    let request = _make_credential_request(my_pw_did, &state_data.offer);
+                    let request = _make_credential_request(profile, my_pw_did.clone(), &state_data.offer).await;
                     match request {
                         Ok((cred_request, req_meta, cred_def_json)) => {
                             let cred_request = cred_request
                                 .set_thread_id(&thread_id);
                             send_message.ok_or(
                                 VcxError::from_msg(VcxErrorKind::InvalidState, "Attempted to call undefined send_message callback")
-                            )?(&cred_request.to_a2a_message())?;
-                            HolderState::RequestSent((state_data, req_meta, cred_def_json).into())
+                            )?(cred_request.to_a2a_message()).await?;
+                            HolderState::RequestSent((state_data, req_meta, cred_def_json, my_pw_did).into())
                         }
                         Err(err) => {
                             let problem_report = ProblemReport::create()
@@ -114,7 +205,7 @@ impl HolderSM {
                                 .set_thread_id(&thread_id);
                             send_message.ok_or(
                                 VcxError::from_msg(VcxErrorKind::InvalidState, "Attempted to call undefined send_message callback")
-                            )?(&problem_report.to_a2a_message())?;
+                            )?(problem_report.to_a2a_message()).await?;
                             HolderState::Finished((state_data, problem_report).into())
                         }
                     }
@@ -126,14 +217,19 @@ impl HolderSM {
             },
             HolderState::RequestSent(state_data) => match cim {
                 CredentialIssuanceMessage::Credential(credential) => {
-                    let result = _store_credential(&credential, &state_data.req_meta, &state_data.cred_def_json);
+                    let metadata = CredentialMetadata::create(&source_id, &thread_id, state_data.my_pw_did.as_ref().map(String::as_str));
+                    let result = _store_credential(profile, &credential, &state_data.req_meta, &state_data.cred_def_json, &metadata).await;
                     match result {
                         Ok((cred_id, rev_reg_def_json)) => {
                             if credential.please_ack.is_some() {
                                 let ack = CredentialAck::create().set_thread_id(&thread_id);
+                                let ack = match credential.protocol_version() {
+                                    ProtocolVersion::V1 => A2AMessage::CredentialAck(ack),
+                                    ProtocolVersion::V2 => A2AMessage::CredentialAckV2(ack),
+                                };
                                 send_message.ok_or(
                                     VcxError::from_msg(VcxErrorKind::InvalidState, "Attempted to call undefined send_message callback")
-                                )?(&A2AMessage::CredentialAck(ack))?;
+                                )?(ack).await?;
                             }
 
                             HolderState::Finished((state_data, cred_id, credential, rev_reg_def_json).into())
@@ -144,7 +240,7 @@ impl HolderSM {
                                 .set_thread_id(&thread_id);
                             send_message.ok_or(
                                 VcxError::from_msg(VcxErrorKind::InvalidState, "Attempted to call undefined send_message callback")
-                            )?(&problem_report.to_a2a_message())?;
+                            )?(problem_report.to_a2a_message()).await?;
                             HolderState::Finished((state_data, problem_report).into())
                         }
                     }
@@ -162,6 +258,39 @@ impl HolderSM {
                 HolderState::Finished(state_data)
             }
         };
+        let timing = if mem::discriminant(&state) == previous_state {
+            timing
+        } else {
+            match state {
+                HolderState::ProposalSent(_) | HolderState::RequestSent(_) => StateTiming::entered(expires_time),
+                HolderState::OfferReceived(ref state_data) => StateTiming::entered(state_data.offer.expires_time()),
+                _ => StateTiming::entered(None)
+            }
+        };
+        Ok(HolderSM::step_with_timing(state, source_id, thread_id, timing))
+    }
+
+    /**
+    Finishes the exchange with failure, the issuer is notified by a problem report if `send_message` is set.
+     */
+    pub fn abandon(self, reason: Option<String>, send_message: Option<&impl Fn(&A2AMessage) -> VcxResult<()>>) -> VcxResult<HolderSM> {
+        trace!("Holder::abandon >>> reason: {:?}, state: {:?}", reason, self.state);
+
+        if let HolderState::Initial(_) | HolderState::Finished(_) = self.state {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidState, "Only credential exchange in progress can be abandoned"));
+        }
+        let problem_report = state_timing::expiration_problem_report(&self.thread_id, reason);
+        if let Some(send_message) = send_message {
+            send_message(&problem_report.to_a2a_message())?;
+        }
+
+        let HolderSM { state, source_id, thread_id, .. } = self;
+        let state = match state {
+            HolderState::ProposalSent(state_data) => HolderState::Finished((state_data, problem_report).into()),
+            HolderState::OfferReceived(state_data) => HolderState::Finished((state_data, problem_report).into()),
+            HolderState::RequestSent(state_data) => HolderState::Finished((state_data, problem_report).into()),
+            state => state
+        };
         Ok(HolderSM::step(state, source_id, thread_id))
     }
 
@@ -179,6 +308,10 @@ impl HolderSM {
         }
     }
 
+    pub fn is_expired(&self) -> bool {
+        !self.is_terminal_state() && self.timing.is_expired()
+    }
+
     pub fn get_credential(&self) -> VcxResult<(String, A2AMessage)> {
         match self.state {
             HolderState::Finished(ref state) => {
@@ -227,21 +360,26 @@ impl HolderSM {
         }
     }
 
-    pub fn is_revokable(&self) -> VcxResult<bool> {
+    pub fn is_revokable(&self, profile: &ProfileContext) -> VcxResult<bool> {
         match self.state {
-            HolderState::OfferReceived(ref state) => state.is_revokable(),
+            HolderState::Initial(_) | HolderState::ProposalSent(_) => Err(VcxError::from_msg(VcxErrorKind::NotReady, "Cannot get revocation info: credential offer must be receieved first")),
+            HolderState::OfferReceived(ref state) => state.is_revokable(profile),
             HolderState::RequestSent(ref state) => state.is_revokable(),
             HolderState::Finished(ref state) => state.is_revokable()
         }
     }
 
-    pub fn delete_credential(&self) -> VcxResult<()> {
-        trace!("Holder::delete_credential");
+    pub fn delete_credential(&self, profile: &ProfileContext) -> VcxResult<()> {
+        block_on(self.delete_credential_async(profile))
+    }
+
+    pub async fn delete_credential_async(&self, profile: &ProfileContext) -> VcxResult<()> {
+        trace!("Holder::delete_credential_async");
 
         match self.state {
             HolderState::Finished(ref state) => {
                 let cred_id = state.cred_id.clone().ok_or(VcxError::from_msg(VcxErrorKind::InvalidState, "Cannot get credential: credential id not found"))?;
-                _delete_credential(&cred_id)
+                _delete_credential(profile, &cred_id).await
             }
             _ => Err(VcxError::from_msg(VcxErrorKind::NotReady, "Cannot delete credential: credential issuance is not finished yet"))
         }
@@ -271,51 +409,60 @@ fn _parse_rev_reg_id_from_credential(credential: &str) -> VcxResult<Option<Strin
     Ok(rev_reg_id)
 }
 
-fn _store_credential(credential: &Credential,
-                     req_meta: &str, cred_def_json: &str) -> VcxResult<(String, Option<String>)> {
+async fn _store_credential(profile: &ProfileContext,
+                           credential: &Credential,
+                           req_meta: &str, cred_def_json: &str, metadata: &CredentialMetadata) -> VcxResult<(String, Option<String>)> {
     trace!("Holder::_store_credential >>> credential: {:?}, req_meta: {}, cred_def_json: {}", credential, req_meta, cred_def_json);
 
-    let credential_json = credential.credentials_attach.content()?;
+    let credential_json = credential.attachment_content()?;
     let rev_reg_id = _parse_rev_reg_id_from_credential(&credential_json)?;
     let rev_reg_def_json = if let Some(rev_reg_id) = rev_reg_id {
-        let (_, json) = anoncreds::get_rev_reg_def_json(&rev_reg_id)?;
+        let (_, json) = anoncreds::get_rev_reg_def_json_async(profile, &rev_reg_id).await?;
         Some(json)
     } else {
         None
     };
 
-    let cred_id = libindy_prover_store_credential(None,
-                                                  req_meta,
-                                                  &credential_json,
-                                                  cred_def_json,
-                                                  rev_reg_def_json.as_ref().map(String::as_str))?;
+    let cred_id = libindy_prover_store_credential_async(profile.wallet_handle()?,
+                                                        None,
+                                                        req_meta,
+                                                        &credential_json,
+                                                        cred_def_json,
+                                                        rev_reg_def_json.as_ref().map(String::as_str)).await?;
+    if let Err(err) = credential_store::record_credential_metadata_async(profile, &cred_id, metadata).await {
+        warn!("Holder::_store_credential >>> cannot record metadata of credential {}: {}", cred_id, err);
+    }
     Ok((cred_id, rev_reg_def_json))
 }
 
-fn _delete_credential(cred_id: &str) -> VcxResult<()> {
+async fn _delete_credential(profile: &ProfileContext, cred_id: &str) -> VcxResult<()> {
     trace!("Holder::_delete_credential >>> cred_id: {}", cred_id);
 
-    libindy_prover_delete_credential(cred_id)
+    credential_store::delete_credential_async(profile, cred_id).await
 }
 
-pub fn create_credential_request(cred_def_id: &str, prover_did: &str, cred_offer: &str) -> VcxResult<(String, String, String, String)> {
-    let (cred_def_id, cred_def_json) = get_cred_def_json(&cred_def_id)?;
+pub async fn create_credential_request(profile: &ProfileContext, cred_def_id: &str, prover_did: &str, cred_offer: &str) -> VcxResult<(String, String, String, String)> {
+    let (cred_def_id, cred_def_json) = get_cred_def_json_async(profile, &cred_def_id).await?;
 
-    libindy_prover_create_credential_req(&prover_did,
-                                         &cred_offer,
-                                         &cred_def_json)
+    libindy_prover_create_credential_req_async(profile.wallet_handle()?,
+                                               &prover_did,
+                                               &cred_offer,
+                                               &cred_def_json).await
         .map_err(|err| err.extend("Cannot create credential request")).map(|(s1, s2)| (s1, s2, cred_def_id, cred_def_json))
 }
 
-fn _make_credential_request(my_pw_did: String, offer: &CredentialOffer) -> VcxResult<(CredentialRequest, String, String)> {
+async fn _make_credential_request(profile: &ProfileContext, my_pw_did: String, offer: &CredentialOffer) -> VcxResult<(CredentialRequest, String, String)> {
     trace!("Holder::_make_credential_request >>> my_pw_did: {:?}, offer: {:?}", my_pw_did, offer);
 
-    let cred_offer = offer.offers_attach.content()?;
+    let cred_offer = offer.attachment_content()?;
     trace!("Parsed cred offer attachment: {}", cred_offer);
     let cred_def_id = parse_cred_def_id_from_cred_offer(&cred_offer)?;
-    let (req, req_meta, _cred_def_id, cred_def_json) = create_credential_request(&cred_def_id, &my_pw_did, &cred_offer)?;
+    let (req, req_meta, _cred_def_id, cred_def_json) = create_credential_request(profile, &cred_def_id, &my_pw_did, &cred_offer).await?;
     trace!("Created cred def json: {}", cred_def_json);
-    Ok((CredentialRequest::create().set_requests_attach(req)?, req_meta, cred_def_json))
+    let cred_request = CredentialRequest::create()
+        .set_requests_attach(req)?
+        .set_protocol_version(offer.protocol_version());
+    Ok((cred_request, req_meta, cred_def_json))
 }
 
 #[cfg(test)]
@@ -335,19 +482,28 @@ mod test {
         HolderSM::new(_credential_offer(), source_id())
     }
 
+    fn _holder_sm_with_proposal() -> HolderSM {
+        HolderSM::new_with_proposal(_credential_proposal(), source_id())
+    }
+
     fn _send_message() -> Option<&'static impl Fn(&A2AMessage) -> VcxResult<()>> {
         Some(&|_: &A2AMessage| VcxResult::Ok(()))
     }
 
     impl HolderSM {
+        fn to_proposal_sent_state(mut self) -> HolderSM {
+            self = self.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialProposalSend(), _send_message()).unwrap();
+            self
+        }
+
         fn to_request_sent_state(mut self) -> HolderSM {
-            self = self.handle_message(CredentialIssuanceMessage::CredentialRequestSend(_my_pw_did()), _send_message()).unwrap();
+            self = self.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialRequestSend(_my_pw_did()), _send_message()).unwrap();
             self
         }
 
         fn to_finished_state(mut self) -> HolderSM {
-            self = self.handle_message(CredentialIssuanceMessage::CredentialRequestSend(_my_pw_did()), _send_message()).unwrap();
-            self = self.handle_message(CredentialIssuanceMessage::Credential(_credential()), _send_message()).unwrap();
+            self = self.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialRequestSend(_my_pw_did()), _send_message()).unwrap();
+            self = self.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::Credential(_credential()), _send_message()).unwrap();
             self
         }
     }
@@ -365,6 +521,17 @@ mod test {
             assert_match!(HolderState::OfferReceived(_), holder_sm.state);
             assert_eq!(source_id(), holder_sm.get_source_id());
         }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_holder_new_with_proposal() {
+            let _setup = SetupMocks::init();
+
+            let holder_sm = _holder_sm_with_proposal();
+
+            assert_match!(HolderState::Initial(_), holder_sm.state);
+            assert_eq!(_credential_proposal().thread_id(), holder_sm.thread_id);
+        }
     }
 
     mod step {
@@ -385,7 +552,7 @@ mod test {
             let _setup = SetupMocks::init();
 
             let mut holder_sm = _holder_sm();
-            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::CredentialRequestSend(_my_pw_did()), _send_message()).unwrap();
+            holder_sm = holder_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialRequestSend(_my_pw_did()), _send_message()).unwrap();
 
             assert_match!(HolderState::RequestSent(_), holder_sm.state);
         }
@@ -398,7 +565,7 @@ mod test {
             let credential_offer = CredentialOffer::create().set_offers_attach(r#"{"credential offer": {}}"#).unwrap();
 
             let mut holder_sm = HolderSM::new(credential_offer, "test source".to_string());
-            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::CredentialRequestSend(_my_pw_did()), _send_message()).unwrap();
+            holder_sm = holder_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialRequestSend(_my_pw_did()), _send_message()).unwrap();
 
             assert_match!(HolderState::Finished(_), holder_sm.state);
             assert_eq!(Status::Failed(ProblemReport::default()).code(), holder_sm.credential_status());
@@ -410,10 +577,10 @@ mod test {
             let _setup = SetupMocks::init();
 
             let mut holder_sm = _holder_sm();
-            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::CredentialSend(), _send_message()).unwrap();
+            holder_sm = holder_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialSend(), _send_message()).unwrap();
             assert_match!(HolderState::OfferReceived(_), holder_sm.state);
 
-            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::ProblemReport(_problem_report()), _send_message()).unwrap();
+            holder_sm = holder_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::ProblemReport(_problem_report()), _send_message()).unwrap();
             assert_match!(HolderState::OfferReceived(_), holder_sm.state);
         }
 
@@ -423,8 +590,8 @@ mod test {
             let _setup = SetupMocks::init();
 
             let mut holder_sm = _holder_sm();
-            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::CredentialRequestSend(_my_pw_did()), _send_message()).unwrap();
-            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::Credential(_credential()), _send_message()).unwrap();
+            holder_sm = holder_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialRequestSend(_my_pw_did()), _send_message()).unwrap();
+            holder_sm = holder_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::Credential(_credential()), _send_message()).unwrap();
 
             assert_match!(HolderState::Finished(_), holder_sm.state);
             assert_eq!(Status::Success.code(), holder_sm.credential_status());
@@ -436,8 +603,8 @@ mod test {
             let _setup = SetupMocks::init();
 
             let mut holder_sm = _holder_sm();
-            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::CredentialRequestSend(_my_pw_did()), _send_message()).unwrap();
-            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::Credential(Credential::create()), _send_message()).unwrap();
+            holder_sm = holder_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialRequestSend(_my_pw_did()), _send_message()).unwrap();
+            holder_sm = holder_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::Credential(Credential::create()), _send_message()).unwrap();
 
             assert_match!(HolderState::Finished(_), holder_sm.state);
             assert_eq!(Status::Failed(ProblemReport::default()).code(), holder_sm.credential_status());
@@ -449,8 +616,8 @@ mod test {
             let _setup = SetupMocks::init();
 
             let mut holder_sm = _holder_sm();
-            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::CredentialRequestSend(_my_pw_did()), _send_message()).unwrap();
-            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::ProblemReport(_problem_report()), _send_message()).unwrap();
+            holder_sm = holder_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialRequestSend(_my_pw_did()), _send_message()).unwrap();
+            holder_sm = holder_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::ProblemReport(_problem_report()), _send_message()).unwrap();
 
             assert_match!(HolderState::Finished(_), holder_sm.state);
             assert_eq!(Status::Failed(ProblemReport::default()).code(), holder_sm.credential_status());
@@ -462,32 +629,115 @@ mod test {
             let _setup = SetupMocks::init();
 
             let mut holder_sm = _holder_sm();
-            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::CredentialRequestSend(_my_pw_did()), _send_message()).unwrap();
+            holder_sm = holder_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialRequestSend(_my_pw_did()), _send_message()).unwrap();
 
-            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::CredentialOffer(_credential_offer()), _send_message()).unwrap();
+            holder_sm = holder_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialOffer(_credential_offer()), _send_message()).unwrap();
             assert_match!(HolderState::RequestSent(_), holder_sm.state);
 
-            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::CredentialAck(_ack()), _send_message()).unwrap();
+            holder_sm = holder_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialAck(_ack()), _send_message()).unwrap();
             assert_match!(HolderState::RequestSent(_), holder_sm.state);
         }
 
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_holder_handle_credential_proposal_send_message_from_initial_state() {
+            let _setup = SetupMocks::init();
+
+            let mut holder_sm = _holder_sm_with_proposal();
+            holder_sm = holder_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialProposalSend(), _send_message()).unwrap();
+
+            assert_match!(HolderState::ProposalSent(_), holder_sm.state);
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_holder_handle_other_messages_from_initial_state() {
+            let _setup = SetupMocks::init();
+
+            let mut holder_sm = _holder_sm_with_proposal();
+
+            holder_sm = holder_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialOffer(_credential_offer()), _send_message()).unwrap();
+            assert_match!(HolderState::Initial(_), holder_sm.state);
+
+            holder_sm = holder_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialRequestSend(_my_pw_did()), _send_message()).unwrap();
+            assert_match!(HolderState::Initial(_), holder_sm.state);
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_holder_handle_credential_offer_message_from_proposal_sent_state() {
+            let _setup = SetupMocks::init();
+
+            let mut holder_sm = _holder_sm_with_proposal().to_proposal_sent_state();
+            holder_sm = holder_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialOffer(_credential_offer()), _send_message()).unwrap();
+            assert_match!(HolderState::OfferReceived(_), holder_sm.state);
+
+            holder_sm = holder_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialRequestSend(_my_pw_did()), _send_message()).unwrap();
+            holder_sm = holder_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::Credential(_credential()), _send_message()).unwrap();
+
+            assert_match!(HolderState::Finished(_), holder_sm.state);
+            assert_eq!(Status::Success.code(), holder_sm.credential_status());
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_holder_handle_problem_report_message_from_proposal_sent_state() {
+            let _setup = SetupMocks::init();
+
+            let mut holder_sm = _holder_sm_with_proposal().to_proposal_sent_state();
+            holder_sm = holder_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::ProblemReport(_problem_report()), _send_message()).unwrap();
+
+            assert_match!(HolderState::Finished(_), holder_sm.state);
+            assert_eq!(Status::Failed(ProblemReport::default()).code(), holder_sm.credential_status());
+        }
+
         #[test]
         #[cfg(feature = "general_test")]
         fn test_issuer_handle_message_from_finished_state() {
             let _setup = SetupMocks::init();
 
             let mut holder_sm = _holder_sm();
-            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::CredentialRequestSend(_my_pw_did()), _send_message()).unwrap();
-            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::Credential(_credential()), _send_message()).unwrap();
+            holder_sm = holder_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialRequestSend(_my_pw_did()), _send_message()).unwrap();
+            holder_sm = holder_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::Credential(_credential()), _send_message()).unwrap();
+
+            holder_sm = holder_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialOffer(_credential_offer()), _send_message()).unwrap();
+            assert_match!(HolderState::Finished(_), holder_sm.state);
 
-            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::CredentialOffer(_credential_offer()), _send_message()).unwrap();
+            holder_sm = holder_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::Credential(_credential()), _send_message()).unwrap();
             assert_match!(HolderState::Finished(_), holder_sm.state);
 
-            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::Credential(_credential()), _send_message()).unwrap();
+            holder_sm = holder_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialAck(_ack()), _send_message()).unwrap();
             assert_match!(HolderState::Finished(_), holder_sm.state);
+        }
+    }
+
+    mod abandon {
+        use super::*;
 
-            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::CredentialAck(_ack()), _send_message()).unwrap();
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_holder_expires_with_offer() {
+            let _setup = SetupMocks::init();
+
+            let offer = _credential_offer().set_expires_time(timing::expires_time(Some(0)));
+            let holder_sm = HolderSM::new(offer, source_id());
+            assert!(holder_sm.is_expired());
+
+            let holder_sm = holder_sm.abandon(None, _send_message()).unwrap();
             assert_match!(HolderState::Finished(_), holder_sm.state);
+            assert_eq!(VcxStateType::VcxStateNone as u32, holder_sm.state());
+            assert!(!holder_sm.is_expired());
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_holder_does_not_expire_without_expires_time() {
+            let _setup = SetupMocks::init();
+
+            let holder_sm = _holder_sm();
+            assert!(!holder_sm.is_expired());
+            assert_eq!(None, holder_sm.get_state_timing().expires_time);
+            assert_eq!(VcxErrorKind::InvalidState, _holder_sm_with_proposal().abandon(None, _send_message()).unwrap_err().kind());
         }
     }
 
@@ -517,6 +767,49 @@ mod test {
             }
         }
 
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_holder_find_message_to_handle_from_proposal_sent_state() {
+            let _setup = SetupMocks::init();
+
+            let holder = _holder_sm_with_proposal().to_proposal_sent_state();
+
+            // CredentialOffer
+            {
+                let messages = map!(
+                    "key_1".to_string() => A2AMessage::CredentialProposal(_credential_proposal()),
+                    "key_2".to_string() => A2AMessage::Credential(_credential()),
+                    "key_3".to_string() => A2AMessage::CredentialOffer(_credential_offer())
+                );
+
+                let (uid, message) = holder.find_message_to_handle(messages).unwrap();
+                assert_eq!("key_3", uid);
+                assert_match!(A2AMessage::CredentialOffer(_), message);
+            }
+
+            // Problem Report
+            {
+                let messages = map!(
+                    "key_1".to_string() => A2AMessage::CredentialProposal(_credential_proposal()),
+                    "key_2".to_string() => A2AMessage::CommonProblemReport(_problem_report())
+                );
+
+                let (uid, message) = holder.find_message_to_handle(messages).unwrap();
+                assert_eq!("key_2", uid);
+                assert_match!(A2AMessage::CommonProblemReport(_), message);
+            }
+
+            // No messages for different Thread ID
+            {
+                let messages = map!(
+                    "key_1".to_string() => A2AMessage::CredentialOffer(_credential_offer().set_thread_id("")),
+                    "key_2".to_string() => A2AMessage::CommonProblemReport(_problem_report().set_thread_id(""))
+                );
+
+                assert!(holder.find_message_to_handle(messages).is_none());
+            }
+        }
+
         #[test]
         #[cfg(feature = "general_test")]
         fn test_holder_find_message_to_handle_from_request_sent_state() {
@@ -610,6 +903,8 @@ mod test {
         fn test_get_state() {
             let _setup = SetupMocks::init();
 
+            assert_eq!(VcxStateType::VcxStateInitialized as u32, _holder_sm_with_proposal().state());
+            assert_eq!(VcxStateType::VcxStateOfferSent as u32, _holder_sm_with_proposal().to_proposal_sent_state().state());
             assert_eq!(VcxStateType::VcxStateRequestReceived as u32, _holder_sm().state());
             assert_eq!(VcxStateType::VcxStateOfferSent as u32, _holder_sm().to_request_sent_state().state());
             assert_eq!(VcxStateType::VcxStateAccepted as u32, _holder_sm().to_finished_state().state());
@@ -667,9 +962,9 @@ mod test {
         #[cfg(feature = "general_test")]
         fn test_is_revokable() {
             let _setup = SetupMocks::init();
-            assert_eq!(true, _holder_sm().is_revokable().unwrap());
-            assert_eq!(true, _holder_sm().to_request_sent_state().is_revokable().unwrap());
-            assert_eq!(true, _holder_sm().to_finished_state().is_revokable().unwrap());
+            assert_eq!(true, _holder_sm().is_revokable(&ProfileContext::Global).unwrap());
+            assert_eq!(true, _holder_sm().to_request_sent_state().is_revokable(&ProfileContext::Global).unwrap());
+            assert_eq!(true, _holder_sm().to_finished_state().is_revokable(&ProfileContext::Global).unwrap());
         }
     }
 }
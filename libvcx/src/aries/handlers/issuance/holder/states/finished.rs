@@ -34,7 +34,7 @@ impl FinishedHolderState {
     
     pub fn get_attachment(&self) -> VcxResult<String> {
         let credential = self.credential.as_ref().ok_or(VcxError::from_msg(VcxErrorKind::InvalidState, "No credential found"))?;
-        credential.credentials_attach.content()
+        credential.attachment_content()
     }
 
     // TODO: Avoid duplication
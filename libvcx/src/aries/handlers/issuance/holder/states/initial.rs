@@ -0,0 +1,24 @@
+use crate::aries::handlers::issuance::holder::states::proposal_sent::ProposalSentState;
+use crate::aries::messages::issuance::credential_proposal::CredentialProposal;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InitialHolderState {
+    pub proposal: CredentialProposal
+}
+
+impl InitialHolderState {
+    pub fn new(proposal: CredentialProposal) -> Self {
+        InitialHolderState {
+            proposal,
+        }
+    }
+}
+
+impl From<InitialHolderState> for ProposalSentState {
+    fn from(state: InitialHolderState) -> Self {
+        trace!("SM is now in ProposalSent state");
+        ProposalSentState {
+            proposal: state.proposal
+        }
+    }
+}
@@ -6,19 +6,21 @@ use crate::aries::messages::issuance::credential_offer::CredentialOffer;
 use crate::aries::messages::status::Status;
 use crate::aries::handlers::issuance::holder::state_machine::parse_cred_def_id_from_cred_offer;
 use crate::libindy::utils::anoncreds::get_cred_def_json;
+use crate::profile::ProfileContext;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct OfferReceivedState {
     pub offer: CredentialOffer
 }
 
-impl From<(OfferReceivedState, String, String)> for RequestSentState {
-    fn from((_state, req_meta, cred_def_json): (OfferReceivedState, String, String)) -> Self {
+impl From<(OfferReceivedState, String, String, String)> for RequestSentState {
+    fn from((_state, req_meta, cred_def_json, my_pw_did): (OfferReceivedState, String, String, String)) -> Self {
         trace!("SM is now in RequestSent state");
         trace!("cred_def_json={:?}", cred_def_json);
         RequestSentState {
             req_meta,
-            cred_def_json
+            cred_def_json,
+            my_pw_did: Some(my_pw_did),
         }
     }
 }
@@ -50,12 +52,12 @@ impl OfferReceivedState {
         Ok(serde_json::Value::Object(new_map).to_string())
     }
 
-    pub fn is_revokable(&self) -> VcxResult<bool> {
-        let offer = self.offer.offers_attach.content()
+    pub fn is_revokable(&self, profile: &ProfileContext) -> VcxResult<bool> {
+        let offer = self.offer.attachment_content()
             .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Failed to get credential offer attachment content: {}", err)))?;
         let cred_def_id = parse_cred_def_id_from_cred_offer(&offer)
             .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Failed to parse credential definition id from credential offer: {}", err)))?;
-        let (_, cred_def_json) = get_cred_def_json(&cred_def_id)
+        let (_, cred_def_json) = get_cred_def_json(profile, &cred_def_id)
             .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidLedgerResponse, format!("Failed to obtain credential definition from ledger or cache: {}", err)))?;
         let parsed_cred_def: serde_json::Value = serde_json::from_str(&cred_def_json)
             .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Failed deserialize credential definition json {}\nError: {}", cred_def_json, err)))?;
@@ -63,6 +65,6 @@ impl OfferReceivedState {
     }
 
     pub fn get_attachment(&self) -> VcxResult<String> {
-        self.offer.offers_attach.content()
+        self.offer.attachment_content()
     }
 }
@@ -0,0 +1,32 @@
+use crate::aries::handlers::issuance::holder::states::finished::FinishedHolderState;
+use crate::aries::handlers::issuance::holder::states::offer_received::OfferReceivedState;
+use crate::aries::messages::error::ProblemReport;
+use crate::aries::messages::issuance::credential_offer::CredentialOffer;
+use crate::aries::messages::issuance::credential_proposal::CredentialProposal;
+use crate::aries::messages::status::Status;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProposalSentState {
+    pub proposal: CredentialProposal
+}
+
+impl From<(ProposalSentState, CredentialOffer)> for OfferReceivedState {
+    fn from((_state, offer): (ProposalSentState, CredentialOffer)) -> Self {
+        trace!("SM is now in OfferReceived state");
+        OfferReceivedState {
+            offer
+        }
+    }
+}
+
+impl From<(ProposalSentState, ProblemReport)> for FinishedHolderState {
+    fn from((_state, problem_report): (ProposalSentState, ProblemReport)) -> Self {
+        trace!("SM is now in Finished state");
+        FinishedHolderState {
+            cred_id: None,
+            credential: None,
+            status: Status::Failed(problem_report),
+            rev_reg_def_json: None,
+        }
+    }
+}
@@ -7,7 +7,9 @@ use crate::aries::messages::status::Status;
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RequestSentState {
     pub req_meta: String,
-    pub cred_def_json: String
+    pub cred_def_json: String,
+    #[serde(default)]
+    pub my_pw_did: Option<String>,
 }
 
 impl From<(RequestSentState, String, Credential, Option<String>)> for FinishedHolderState {
@@ -1,13 +1,21 @@
 use std::collections::HashMap;
+use std::future::Future;
 
 use crate::aries::handlers::issuance::issuer::state_machine::IssuerSM;
 use crate::aries::handlers::issuance::messages::CredentialIssuanceMessage;
+use crate::aries::handlers::state_timing::StateTiming;
 use crate::aries::messages::a2a::A2AMessage;
+use crate::aries::messages::a2a::protocol_version::ProtocolVersion;
 use crate::aries::handlers::connection::connection::Connection;
+use crate::aries::messages::issuance::credential_proposal::CredentialProposal;
 use crate::error::prelude::*;
+use crate::libindy::utils::revocation_manager::CredentialRevocation;
+use crate::profile::ProfileContext;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Issuer {
+    #[serde(skip)]
+    profile: ProfileContext,
     issuer_sm: IssuerSM
 }
 
@@ -18,12 +26,52 @@ pub struct IssuerConfig {
     pub tails_file: Option<String>
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OfferInfo {
+    pub credential_json: String,
+    pub cred_def_id: String,
+    pub rev_reg_id: Option<String>,
+    pub tails_file: Option<String>,
+}
+
 impl Issuer {
-    pub fn create(issuer_config: &IssuerConfig, credential_data: &str, source_id: &str) -> VcxResult<Issuer> {
+    pub fn create(profile: ProfileContext, issuer_config: &IssuerConfig, credential_data: &str, source_id: &str) -> VcxResult<Issuer> {
         trace!("Issuer::issuer_create_credential >>> issuer_config: {:?}, credential_data: {:?}, source_id: {:?}", issuer_config, credential_data, source_id);
 
         let issuer_sm = IssuerSM::new(&issuer_config.cred_def_id.to_string(), credential_data, issuer_config.rev_reg_id.clone(), issuer_config.tails_file.clone(), source_id);
-        Ok(Issuer { issuer_sm })
+        Ok(Issuer { profile, issuer_sm })
+    }
+
+    pub fn create_from_proposal(profile: ProfileContext, credential_proposal: CredentialProposal, source_id: &str) -> VcxResult<Issuer> {
+        trace!("Issuer::create_from_proposal >>> credential_proposal: {:?}, source_id: {:?}", credential_proposal, source_id);
+
+        let issuer_sm = IssuerSM::from_proposal(credential_proposal, source_id);
+        Ok(Issuer { profile, issuer_sm })
+    }
+
+    /**
+    Binds deserialized issuer to the profile, the profile is not part of the serialized data.
+     */
+    pub fn set_profile(&mut self, profile: ProfileContext) {
+        self.profile = profile;
+    }
+
+    pub fn profile(&self) -> &ProfileContext {
+        &self.profile
+    }
+
+    pub fn get_credential_proposal(&self) -> VcxResult<CredentialProposal> {
+        self.issuer_sm.get_proposal()
+    }
+
+    pub fn send_credential_offer_for_proposal(&mut self, issuer_config: &IssuerConfig, credential_data: &str, send_message: impl Fn(&A2AMessage) -> VcxResult<()>, comment: Option<String>) -> VcxResult<()> {
+        let offer_info = OfferInfo {
+            credential_json: credential_data.to_string(),
+            cred_def_id: issuer_config.cred_def_id.clone(),
+            rev_reg_id: issuer_config.rev_reg_id.clone(),
+            tails_file: issuer_config.tails_file.clone(),
+        };
+        self.step(CredentialIssuanceMessage::CredentialOfferSend(offer_info, comment), Some(&send_message))
     }
 
     pub fn send_credential_offer(&mut self, send_message: impl Fn(&A2AMessage) -> VcxResult<()>, comment: Option<String>) -> VcxResult<()> {
@@ -34,6 +82,11 @@ impl Issuer {
         self.step(CredentialIssuanceMessage::CredentialSend(), Some(&send_message))
     }
 
+    pub fn set_protocol_version(&mut self, protocol_version: ProtocolVersion) -> VcxResult<()> {
+        self.issuer_sm = self.issuer_sm.clone().set_protocol_version(protocol_version)?;
+        Ok(())
+    }
+
     pub fn get_state(&self) -> VcxResult<u32> {
         Ok(self.issuer_sm.state())
     }
@@ -42,6 +95,10 @@ impl Issuer {
         Ok(self.issuer_sm.get_source_id())
     }
 
+    pub fn get_thread_id(&self) -> String {
+        self.issuer_sm.thread_id()
+    }
+
     pub fn is_terminal_state(&self) -> bool {
         self.issuer_sm.is_terminal_state()
     }
@@ -51,7 +108,11 @@ impl Issuer {
     }
 
     pub fn revoke_credential(&self, publish: bool) -> VcxResult<()> {
-        self.issuer_sm.revoke(publish)
+        self.issuer_sm.revoke(&self.profile, publish)
+    }
+
+    pub fn get_credential_revocation(&self) -> VcxResult<CredentialRevocation> {
+        self.issuer_sm.get_credential_revocation()
     }
 
     pub fn get_rev_reg_id(&self) -> VcxResult<String> {
@@ -62,12 +123,34 @@ impl Issuer {
         self.issuer_sm.is_revokable()
     }
 
+    pub fn get_pending_issuance_rev_reg_id(&self) -> Option<String> {
+        self.issuer_sm.get_pending_issuance_rev_reg_id()
+    }
+
+    pub fn set_rev_reg(&mut self, rev_reg_id: String, tails_file: String) -> VcxResult<()> {
+        self.issuer_sm = self.issuer_sm.clone().set_rev_reg(rev_reg_id, tails_file)?;
+        Ok(())
+    }
+
     pub fn get_credential_status(&self) -> VcxResult<u32> {
         Ok(self.issuer_sm.credential_status())
     }
 
+    pub fn get_state_timing(&self) -> StateTiming {
+        self.issuer_sm.get_state_timing()
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.issuer_sm.is_expired()
+    }
+
+    pub fn abandon(&mut self, reason: Option<String>, send_message: Option<&impl Fn(&A2AMessage) -> VcxResult<()>>) -> VcxResult<()> {
+        self.issuer_sm = self.issuer_sm.clone().abandon(reason, send_message)?;
+        Ok(())
+    }
+
     pub fn step(&mut self, message: CredentialIssuanceMessage, send_message: Option<&impl Fn(&A2AMessage) -> VcxResult<()>>) -> VcxResult<()> {
-        self.issuer_sm = self.issuer_sm.clone().handle_message(message, send_message)?;
+        self.issuer_sm = self.issuer_sm.clone().handle_message(&self.profile, message, send_message)?;
         Ok(())
     }
 
@@ -84,3 +167,39 @@ impl Issuer {
         self.get_state()
     }
 }
+
+impl Issuer {
+    pub async fn send_credential_offer_async(&mut self, connection: &Connection, comment: Option<String>) -> VcxResult<()> {
+        let send_message = connection.send_message_closure_async()?;
+        self.step_async(CredentialIssuanceMessage::CredentialInit(comment), Some(&send_message)).await
+    }
+
+    pub async fn send_credential_async(&mut self, connection: &Connection) -> VcxResult<()> {
+        let send_message = connection.send_message_closure_async()?;
+        self.step_async(CredentialIssuanceMessage::CredentialSend(), Some(&send_message)).await
+    }
+
+    pub async fn revoke_credential_async(&self, publish: bool) -> VcxResult<()> {
+        self.issuer_sm.revoke_async(&self.profile, publish).await
+    }
+
+    pub async fn step_async<F, Fut>(&mut self, message: CredentialIssuanceMessage, send_message: Option<&F>) -> VcxResult<()>
+        where F: Fn(A2AMessage) -> Fut,
+              Fut: Future<Output=VcxResult<()>> {
+        self.issuer_sm = self.issuer_sm.clone().handle_message_async(&self.profile, message, send_message).await?;
+        Ok(())
+    }
+
+    pub async fn update_state_async(&mut self, connection: &Connection) -> VcxResult<u32> {
+        trace!("Issuer::update_state_async >>> ");
+        if self.is_terminal_state() { return self.get_state(); }
+        let send_message = connection.send_message_closure_async()?;
+
+        let messages = connection.get_messages_async().await?;
+        if let Some((uid, msg)) = self.find_message_to_handle(messages) {
+            self.step_async(msg.into(), Some(&send_message)).await?;
+            connection.update_message_status_async(uid).await?;
+        }
+        self.get_state()
+    }
+}
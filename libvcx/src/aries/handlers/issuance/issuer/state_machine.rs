@@ -1,27 +1,43 @@
 use std::collections::HashMap;
+use std::future::Future;
+use std::mem;
 
-use crate::libindy::utils::anoncreds::{self, libindy_issuer_create_credential_offer};
+use crate::aries::utils::task::block_on;
+use crate::libindy::utils::anoncreds::{self, libindy_issuer_create_credential_offer_async};
+use crate::libindy::utils::revocation_manager::CredentialRevocation;
+use crate::profile::ProfileContext;
 
+use crate::aries::handlers::issuance::issuer::issuer::OfferInfo;
 use crate::aries::handlers::issuance::issuer::states::credential_sent::CredentialSentState;
 use crate::aries::handlers::issuance::issuer::states::finished::FinishedState;
 use crate::aries::handlers::issuance::issuer::states::initial::InitialState;
 use crate::aries::handlers::issuance::issuer::states::offer_sent::OfferSentState;
+use crate::aries::handlers::issuance::issuer::states::proposal_received::ProposalReceivedState;
 use crate::aries::handlers::issuance::issuer::states::requested_received::RequestReceivedState;
 use crate::aries::handlers::issuance::issuer::utils::encode_attributes;
 use crate::aries::handlers::issuance::messages::CredentialIssuanceMessage;
+use crate::aries::handlers::state_timing::{self, StateTiming};
 use crate::aries::messages::a2a::A2AMessage;
+use crate::aries::messages::a2a::protocol_version::ProtocolVersion;
 use crate::aries::messages::error::ProblemReport;
 use crate::aries::messages::issuance::credential::Credential;
 use crate::aries::messages::issuance::credential_offer::CredentialOffer;
+use crate::aries::messages::issuance::credential_proposal::CredentialProposal;
 use crate::aries::messages::issuance::credential_request::CredentialRequest;
+use crate::aries::messages::issuance::CredentialPreviewData;
 use crate::aries::messages::mime_type::MimeType;
 use crate::aries::messages::status::Status;
+use crate::aries::messages::timing;
+use crate::aries::utils::sync_sender;
+use crate::aries::utils::task::run_blocking;
 use crate::error::{VcxError, VcxErrorKind, VcxResult};
 use crate::api_lib::VcxStateType;
+use crate::settings;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum IssuerState {
     Initial(InitialState),
+    ProposalReceived(ProposalReceivedState),
     OfferSent(OfferSentState),
     RequestReceived(RequestReceivedState),
     CredentialSent(CredentialSentState),
@@ -39,6 +55,7 @@ impl IssuerState {
     pub fn thread_id(&self) -> String {
         match self {
             IssuerState::Initial(_) => String::new(),
+            IssuerState::ProposalReceived(state) => state.thread_id.clone(),
             IssuerState::OfferSent(state) => state.thread_id.clone(),
             IssuerState::RequestReceived(state) => state.thread_id.clone(),
             IssuerState::CredentialSent(state) => state.thread_id.clone(),
@@ -51,6 +68,8 @@ impl IssuerState {
 pub struct IssuerSM {
     state: IssuerState,
     source_id: String,
+    #[serde(default)]
+    timing: StateTiming,
 }
 
 impl IssuerSM {
@@ -58,6 +77,15 @@ impl IssuerSM {
         IssuerSM {
             state: IssuerState::Initial(InitialState::new(cred_def_id, credential_data, rev_reg_id, tails_file)),
             source_id: source_id.to_string(),
+            timing: StateTiming::entered(None),
+        }
+    }
+
+    pub fn from_proposal(proposal: CredentialProposal, source_id: &str) -> Self {
+        IssuerSM {
+            state: IssuerState::ProposalReceived(ProposalReceivedState::new(proposal)),
+            source_id: source_id.to_string(),
+            timing: StateTiming::entered(None),
         }
     }
 
@@ -65,26 +93,82 @@ impl IssuerSM {
         self.source_id.clone()
     }
 
+    pub fn thread_id(&self) -> String {
+        self.state.thread_id()
+    }
+
+    pub fn get_state_timing(&self) -> StateTiming {
+        self.timing.clone()
+    }
+
+    pub fn set_protocol_version(self, protocol_version: ProtocolVersion) -> VcxResult<Self> {
+        let IssuerSM { state, source_id, timing } = self;
+        let state = match state {
+            IssuerState::Initial(mut state_data) => {
+                state_data.protocol_version = protocol_version;
+                IssuerState::Initial(state_data)
+            }
+            _ => return Err(VcxError::from_msg(VcxErrorKind::InvalidState, "Protocol version can be set only before credential offer is sent"))
+        };
+        Ok(IssuerSM { state, source_id, timing })
+    }
+
     pub fn step(state: IssuerState, source_id: String) -> Self {
+        IssuerSM::step_with_timing(state, source_id, StateTiming::entered(None))
+    }
+
+    pub fn step_with_timing(state: IssuerState, source_id: String, timing: StateTiming) -> Self {
         IssuerSM {
             state,
             source_id,
+            timing,
+        }
+    }
+
+    pub fn get_proposal(&self) -> VcxResult<CredentialProposal> {
+        match &self.state {
+            IssuerState::ProposalReceived(state) => Ok(state.proposal.clone()),
+            _ => Err(VcxError::from_msg(VcxErrorKind::InvalidState, "Credential proposal is available only in ProposalReceived state"))
         }
     }
 
-    pub fn revoke(&self, publish: bool) -> VcxResult<()> {
+    pub fn revoke(&self, profile: &ProfileContext, publish: bool) -> VcxResult<()> {
         trace!("Issuer::revoke >>> publish={}", publish);
+        let revocation = self.get_credential_revocation()?;
+        if publish {
+            anoncreds::revoke_credential(profile, &revocation.tails_file, &revocation.rev_reg_id, &revocation.cred_rev_id)?;
+        } else {
+            anoncreds::revoke_credential_local(profile, &revocation.tails_file, &revocation.rev_reg_id, &revocation.cred_rev_id)?;
+        }
+        Ok(())
+    }
+
+    /**
+    Publishing the revocation pays the ledger fees with the blocking payment API, so it runs on the pool of blocking tasks.
+     */
+    pub async fn revoke_async(&self, profile: &ProfileContext, publish: bool) -> VcxResult<()> {
+        trace!("Issuer::revoke_async >>> publish={}", publish);
+        let revocation = self.get_credential_revocation()?;
+        if publish {
+            let profile = profile.clone();
+            run_blocking(move || anoncreds::revoke_credential(&profile, &revocation.tails_file, &revocation.rev_reg_id, &revocation.cred_rev_id)).await?;
+        } else {
+            anoncreds::revoke_credential_local_async(profile, &revocation.tails_file, &revocation.rev_reg_id, &revocation.cred_rev_id).await?;
+        }
+        Ok(())
+    }
+
+    pub fn get_credential_revocation(&self) -> VcxResult<CredentialRevocation> {
         match &self.state {
             IssuerState::Finished(state) => {
                 match &state.revocation_info_v1 {
                     Some(rev_info) => {
                         if let (Some(cred_rev_id), Some(rev_reg_id), Some(tails_file)) = (&rev_info.cred_rev_id, &rev_info.rev_reg_id, &rev_info.tails_file) {
-                            if publish {
-                                anoncreds::revoke_credential(tails_file, rev_reg_id, cred_rev_id)?;
-                            } else {
-                                anoncreds::revoke_credential_local(tails_file, rev_reg_id, cred_rev_id)?;
-                            }
-                            Ok(())
+                            Ok(CredentialRevocation {
+                                rev_reg_id: rev_reg_id.to_string(),
+                                tails_file: tails_file.to_string(),
+                                cred_rev_id: cred_rev_id.to_string(),
+                            })
                         } else {
                             warn!("Missing data to perform revocation. rev_info={:?}", rev_info);
                             Err(VcxError::from(VcxErrorKind::InvalidRevocationDetails))
@@ -100,6 +184,7 @@ impl IssuerSM {
     pub fn get_rev_reg_id(&self) -> VcxResult<String> {
         let rev_registry = match &self.state {
             IssuerState::Initial(state) => state.rev_reg_id.clone(),
+            IssuerState::ProposalReceived(_) => None,
             IssuerState::OfferSent(state) => state.rev_reg_id.clone(),
             IssuerState::RequestReceived(state) => state.rev_reg_id.clone(),
             IssuerState::CredentialSent(state) => state.revocation_info_v1.clone()
@@ -115,6 +200,7 @@ impl IssuerSM {
     pub fn is_revokable(&self) -> VcxResult<bool> {
         match &self.state {
             IssuerState::Initial(state) => Ok(state.rev_reg_id.is_some()),
+            IssuerState::ProposalReceived(_) => Err(VcxError::from_msg(VcxErrorKind::NotReady, "Revocation is not known until credential offer is sent")),
             IssuerState::OfferSent(state) => Ok(state.rev_reg_id.is_some()),
             IssuerState::RequestReceived(state) => Ok(state.rev_reg_id.is_some()),
             IssuerState::CredentialSent(state) => Ok(state.revocation_info_v1.is_some()),
@@ -122,6 +208,32 @@ impl IssuerSM {
         }
     }
 
+    /**
+    Revocation registry id of a revocable credential which is about to be issued.
+     */
+    pub fn get_pending_issuance_rev_reg_id(&self) -> Option<String> {
+        match &self.state {
+            IssuerState::RequestReceived(state) => state.rev_reg_id.clone(),
+            _ => None
+        }
+    }
+
+    /**
+    Binds revocable credential which has not been issued yet to another revocation registry of its credential definition.
+     */
+    pub fn set_rev_reg(self, rev_reg_id: String, tails_file: String) -> VcxResult<Self> {
+        let IssuerSM { state, source_id, timing } = self;
+        let state = match state {
+            IssuerState::RequestReceived(mut state_data) if state_data.rev_reg_id.is_some() => {
+                state_data.rev_reg_id = Some(rev_reg_id);
+                state_data.tails_file = Some(tails_file);
+                IssuerState::RequestReceived(state_data)
+            }
+            _ => return Err(VcxError::from_msg(VcxErrorKind::InvalidState, "Revocation registry can be set only for revocable credential which has not been issued yet"))
+        };
+        Ok(IssuerSM { state, source_id, timing })
+    }
+
     pub fn find_message_to_handle(&self, messages: HashMap<String, A2AMessage>) -> Option<(String, A2AMessage)> {
         trace!("Issuer::find_message_to_handle >>> messages: {:?}", messages);
 
@@ -130,6 +242,16 @@ impl IssuerSM {
                 IssuerState::Initial(_) => {
                     // do not process messages
                 }
+                IssuerState::ProposalReceived(_) => {
+                    match message {
+                        A2AMessage::CommonProblemReport(problem_report) => {
+                            if problem_report.from_thread(&self.state.thread_id()) {
+                                return Some((uid, A2AMessage::CommonProblemReport(problem_report)));
+                            }
+                        }
+                        _ => {}
+                    }
+                }
                 IssuerState::OfferSent(_) => {
                     match message {
                         A2AMessage::CredentialRequest(credential) => {
@@ -157,7 +279,7 @@ impl IssuerSM {
                 }
                 IssuerState::CredentialSent(_) => {
                     match message {
-                        A2AMessage::Ack(ack) | A2AMessage::CredentialAck(ack) => {
+                        A2AMessage::Ack(ack) | A2AMessage::CredentialAck(ack) | A2AMessage::CredentialAckV2(ack) => {
                             if ack.from_thread(&self.state.thread_id()) {
                                 return Some((uid, A2AMessage::CredentialAck(ack)));
                             }
@@ -182,6 +304,7 @@ impl IssuerSM {
     pub fn state(&self) -> u32 {
         match self.state {
             IssuerState::Initial(_) => VcxStateType::VcxStateInitialized as u32,
+            IssuerState::ProposalReceived(_) => VcxStateType::VcxStateInitialized as u32,
             IssuerState::OfferSent(_) => VcxStateType::VcxStateOfferSent as u32,
             IssuerState::RequestReceived(_) => VcxStateType::VcxStateRequestReceived as u32,
             IssuerState::CredentialSent(_) => VcxStateType::VcxStateAccepted as u32,
@@ -194,21 +317,26 @@ impl IssuerSM {
         }
     }
 
-    pub fn handle_message(self, cim: CredentialIssuanceMessage, send_message: Option<&impl Fn(&A2AMessage) -> VcxResult<()>>) -> VcxResult<IssuerSM> {
-        trace!("IssuerSM::handle_message >>> cim: {:?}, state: {:?}", cim, self.state);
+    pub fn handle_message(self, profile: &ProfileContext, cim: CredentialIssuanceMessage, send_message: Option<&impl Fn(&A2AMessage) -> VcxResult<()>>) -> VcxResult<IssuerSM> {
+        block_on(self.handle_message_async(profile, cim, send_message.map(sync_sender).as_ref()))
+    }
 
-        let IssuerSM { state, source_id } = self;
+    pub async fn handle_message_async<F, Fut>(self, profile: &ProfileContext, cim: CredentialIssuanceMessage, send_message: Option<&F>) -> VcxResult<IssuerSM>
+        where F: Fn(A2AMessage) -> Fut,
+              Fut: Future<Output=VcxResult<()>> {
+        trace!("IssuerSM::handle_message_async >>> cim: {:?}, state: {:?}", cim, self.state);
+
+        let IssuerSM { state, source_id, timing } = self;
+        let previous_state = mem::discriminant(&state);
+        let expires_time = timing::expires_time(settings::get_expiry(settings::CONFIG_ISSUE_CREDENTIAL_EXPIRY));
         let state = match state {
             IssuerState::Initial(state_data) => match cim {
                 CredentialIssuanceMessage::CredentialInit(comment) => {
-                    let cred_offer = libindy_issuer_create_credential_offer(&state_data.cred_def_id)?;
-                    let cred_offer_msg = CredentialOffer::create()
-                        .set_offers_attach(&cred_offer)?
-                        .set_comment(comment);
-                    let cred_offer_msg = _append_credential_preview(cred_offer_msg, &state_data.credential_json)?;
+                    let (cred_offer_msg, cred_offer) = _create_credential_offer(profile, &state_data.cred_def_id, &state_data.credential_json, comment, state_data.protocol_version).await?;
+                    let cred_offer_msg = cred_offer_msg.set_expires_time(expires_time.clone());
                     send_message.ok_or(
                         VcxError::from_msg(VcxErrorKind::InvalidState, "Attempted to call undefined send_message callback")
-                    )?(&cred_offer_msg.to_a2a_message())?;
+                    )?(cred_offer_msg.to_a2a_message()).await?;
                     IssuerState::OfferSent((state_data, cred_offer, cred_offer_msg.id).into())
                 }
                 _ => {
@@ -216,19 +344,43 @@ impl IssuerSM {
                     IssuerState::Initial(state_data)
                 }
             }
+            IssuerState::ProposalReceived(state_data) => match cim {
+                CredentialIssuanceMessage::CredentialOfferSend(offer_info, comment) => {
+                    let (cred_offer_msg, cred_offer) = _create_credential_offer(profile, &offer_info.cred_def_id, &offer_info.credential_json, comment, state_data.proposal.protocol_version()).await?;
+                    let cred_offer_msg = cred_offer_msg
+                        .set_thread_id(&state_data.thread_id)
+                        .set_expires_time(expires_time.clone());
+                    send_message.ok_or(
+                        VcxError::from_msg(VcxErrorKind::InvalidState, "Attempted to call undefined send_message callback")
+                    )?(cred_offer_msg.to_a2a_message()).await?;
+                    IssuerState::OfferSent((state_data, offer_info, cred_offer).into())
+                }
+                CredentialIssuanceMessage::ProblemReport(problem_report) => {
+                    IssuerState::Finished((state_data, problem_report).into())
+                }
+                _ => {
+                    warn!("In this state Credential Issuance can accept only Offer and Problem Report");
+                    IssuerState::ProposalReceived(state_data)
+                }
+            }
             IssuerState::OfferSent(state_data) => match cim {
                 CredentialIssuanceMessage::CredentialRequest(request) => {
                     IssuerState::RequestReceived((state_data, request).into())
                 }
-                CredentialIssuanceMessage::CredentialProposal(_) => {
-                    let problem_report = ProblemReport::create()
-                        .set_comment(String::from("CredentialProposal is not supported"))
-                        .set_thread_id(&state_data.thread_id);
-
-                    send_message.ok_or(
-                        VcxError::from_msg(VcxErrorKind::InvalidState, "Attempted to call undefined send_message callback")
-                    )?(&problem_report.to_a2a_message())?;
-                    IssuerState::Finished((state_data, problem_report).into())
+                CredentialIssuanceMessage::CredentialProposal(proposal) => {
+                    match proposal.validate() {
+                        Ok(()) => IssuerState::ProposalReceived((state_data, proposal).into()),
+                        Err(err) => {
+                            warn!("Rejecting Credential Proposal: {}", err);
+                            if let Some(send_message) = send_message {
+                                let problem_report = ProblemReport::create()
+                                    .set_comment(err.to_string())
+                                    .set_thread_id(&state_data.thread_id);
+                                send_message(problem_report.to_a2a_message()).await?;
+                            }
+                            IssuerState::OfferSent(state_data)
+                        }
+                    }
                 }
                 CredentialIssuanceMessage::ProblemReport(problem_report) => {
                     IssuerState::Finished((state_data, problem_report).into())
@@ -240,13 +392,15 @@ impl IssuerSM {
             },
             IssuerState::RequestReceived(state_data) => match cim {
                 CredentialIssuanceMessage::CredentialSend() => {
-                    let credential_msg = _create_credential(&state_data.request, &state_data.rev_reg_id, &state_data.tails_file, &state_data.offer, &state_data.cred_data);
+                    let credential_msg = _create_credential(profile, &state_data.request, &state_data.rev_reg_id, &state_data.tails_file, &state_data.offer, &state_data.cred_data).await;
                     match credential_msg {
+                        // AnoncredsRevocationRegistryFullError, credential can be issued with another registry
+                        Err(err) if err.kind() == VcxErrorKind::LibndyError(401) => return Err(err),
                         Ok((credential_msg, cred_rev_id)) => {
                             let credential_msg = credential_msg.set_thread_id(&state_data.thread_id);
                             send_message.ok_or(
                                 VcxError::from_msg(VcxErrorKind::InvalidState, "Attempted to call undefined send_message callback")
-                            )?(&credential_msg.to_a2a_message())?;
+                            )?(credential_msg.to_a2a_message()).await?;
                             IssuerState::Finished((state_data, cred_rev_id).into())
                         }
                         Err(err) => {
@@ -256,7 +410,7 @@ impl IssuerSM {
 
                             send_message.ok_or(
                                 VcxError::from_msg(VcxErrorKind::InvalidState, "Attempted to call undefined send_message callback")
-                            )?(&problem_report.to_a2a_message())?;
+                            )?(problem_report.to_a2a_message()).await?;
                             IssuerState::Finished((state_data, problem_report).into())
                         }
                     }
@@ -286,6 +440,45 @@ impl IssuerSM {
             }
         };
 
+        let timing = if mem::discriminant(&state) == previous_state {
+            timing
+        } else {
+            match state {
+                IssuerState::OfferSent(_) => StateTiming::entered(expires_time),
+                _ => StateTiming::entered(None)
+            }
+        };
+        Ok(IssuerSM::step_with_timing(state, source_id, timing))
+    }
+
+    /**
+    Finishes the exchange with failure, the holder is notified by a problem report if `send_message` is set.
+    Exchange can be abandoned only until the credential is sent.
+     */
+    pub fn abandon(self, reason: Option<String>, send_message: Option<&impl Fn(&A2AMessage) -> VcxResult<()>>) -> VcxResult<IssuerSM> {
+        trace!("IssuerSM::abandon >>> reason: {:?}, state: {:?}", reason, self.state);
+
+        match self.state {
+            IssuerState::Initial(_) | IssuerState::Finished(_) => {
+                return Err(VcxError::from_msg(VcxErrorKind::InvalidState, "Only credential exchange in progress can be abandoned"));
+            }
+            IssuerState::CredentialSent(_) => {
+                return Err(VcxError::from_msg(VcxErrorKind::InvalidState, "Credential has already been sent, exchange can not be abandoned"));
+            }
+            _ => {}
+        }
+        let problem_report = state_timing::expiration_problem_report(&self.thread_id(), reason);
+        if let Some(send_message) = send_message {
+            send_message(&problem_report.to_a2a_message())?;
+        }
+
+        let IssuerSM { state, source_id, .. } = self;
+        let state = match state {
+            IssuerState::ProposalReceived(state_data) => IssuerState::Finished((state_data, problem_report).into()),
+            IssuerState::OfferSent(state_data) => IssuerState::Finished((state_data, problem_report).into()),
+            IssuerState::RequestReceived(state_data) => IssuerState::Finished((state_data, problem_report).into()),
+            state => state
+        };
         Ok(IssuerSM::step(state, source_id))
     }
 
@@ -304,9 +497,26 @@ impl IssuerSM {
             _ => false
         }
     }
+
+    pub fn is_expired(&self) -> bool {
+        !self.is_terminal_state() && self.timing.is_expired()
+    }
 }
 
 
+async fn _create_credential_offer(profile: &ProfileContext, cred_def_id: &str, credential_json: &str, comment: Option<String>, protocol_version: ProtocolVersion) -> VcxResult<(CredentialOffer, String)> {
+    trace!("Issuer::_create_credential_offer >>> cred_def_id: {:?}, credential_json: {:?}, protocol_version: {:?}", cred_def_id, credential_json, protocol_version);
+
+    let cred_offer = libindy_issuer_create_credential_offer_async(profile.wallet_handle()?, cred_def_id).await?;
+    let cred_offer_msg = CredentialOffer::create()
+        .set_offers_attach(&cred_offer)?
+        .set_comment(comment)
+        .set_credential_preview_data(CredentialPreviewData::new().set_protocol_version(protocol_version))?
+        .set_protocol_version(protocol_version);
+    let cred_offer_msg = _append_credential_preview(cred_offer_msg, credential_json)?;
+    Ok((cred_offer_msg, cred_offer))
+}
+
 fn _append_credential_preview(cred_offer_msg: CredentialOffer, credential_json: &str) -> VcxResult<CredentialOffer> {
     trace!("Issuer::_append_credential_preview >>> cred_offer_msg: {:?}, credential_json: {:?}", cred_offer_msg, credential_json);
 
@@ -342,19 +552,23 @@ fn _append_credential_preview(cred_offer_msg: CredentialOffer, credential_json:
     Ok(new_offer)
 }
 
-fn _create_credential(request: &CredentialRequest, rev_reg_id: &Option<String>, tails_file: &Option<String>, offer: &str, cred_data: &str) -> VcxResult<(Credential, Option<String>)> {
+async fn _create_credential(profile: &ProfileContext, request: &CredentialRequest, rev_reg_id: &Option<String>, tails_file: &Option<String>, offer: &str, cred_data: &str) -> VcxResult<(Credential, Option<String>)> {
     trace!("Issuer::_create_credential >>> request: {:?}, rev_reg_id: {:?}, tails_file: {:?}, offer: {:?}, cred_data: {:?}", request, rev_reg_id, tails_file, offer, cred_data);
 
-    let request = &request.requests_attach.content()?;
+    let protocol_version = request.protocol_version();
+    let request = &request.attachment_content()?;
 
     let cred_data = encode_attributes(cred_data)?;
 
-    let (ser_credential, cred_rev_id, _) = anoncreds::libindy_issuer_create_credential(offer,
-                                                                                       &request,
-                                                                                       &cred_data,
-                                                                                       rev_reg_id.clone(),
-                                                                                       tails_file.clone())?;
-    let credential = Credential::create().set_credential(ser_credential)?;
+    let (ser_credential, cred_rev_id, _) = anoncreds::libindy_issuer_create_credential_async(profile.wallet_handle()?,
+                                                                                             offer,
+                                                                                             &request,
+                                                                                             &cred_data,
+                                                                                             rev_reg_id.clone(),
+                                                                                             tails_file.clone()).await?;
+    let credential = Credential::create()
+        .set_credential(ser_credential)?
+        .set_protocol_version(protocol_version);
 
     Ok((credential, cred_rev_id))
 }
@@ -363,7 +577,7 @@ fn _create_credential(request: &CredentialRequest, rev_reg_id: &Option<String>,
 pub mod test {
     use crate::aries::messages::issuance::credential::tests::_credential;
     use crate::aries::messages::issuance::credential_offer::tests::_credential_offer;
-    use crate::aries::messages::issuance::credential_proposal::tests::_credential_proposal;
+    use crate::aries::messages::issuance::credential_proposal::tests::{_credential_proposal, _filter_format};
     use crate::aries::messages::issuance::credential_request::tests::_credential_request;
     use crate::aries::messages::issuance::test::{_ack, _problem_report};
     use crate::aries::test::source_id;
@@ -387,22 +601,35 @@ pub mod test {
         IssuerSM::new("test", &json!({"name": "alice"}).to_string(), Some(_rev_reg_id()), Some(_tails_file()), &source_id())
     }
 
+    fn _issuer_sm_from_proposal() -> IssuerSM {
+        IssuerSM::from_proposal(_credential_proposal(), &source_id())
+    }
+
+    fn _offer_info() -> OfferInfo {
+        OfferInfo {
+            credential_json: json!({"name": "alice"}).to_string(),
+            cred_def_id: String::from("test"),
+            rev_reg_id: Some(_rev_reg_id()),
+            tails_file: Some(_tails_file()),
+        }
+    }
+
     impl IssuerSM {
         fn to_offer_sent_state(mut self) -> IssuerSM {
-            self = self.handle_message(CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
+            self = self.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
             self
         }
 
         fn to_request_received_state(mut self) -> IssuerSM {
-            self = self.handle_message(CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
-            self = self.handle_message(CredentialIssuanceMessage::CredentialRequest(_credential_request()), _send_message()).unwrap();
+            self = self.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
+            self = self.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialRequest(_credential_request()), _send_message()).unwrap();
             self
         }
 
         fn to_finished_state(mut self) -> IssuerSM {
-            self = self.handle_message(CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
-            self = self.handle_message(CredentialIssuanceMessage::CredentialRequest(_credential_request()), _send_message()).unwrap();
-            self = self.handle_message(CredentialIssuanceMessage::CredentialSend(), _send_message()).unwrap();
+            self = self.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
+            self = self.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialRequest(_credential_request()), _send_message()).unwrap();
+            self = self.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialSend(), _send_message()).unwrap();
             self
         }
     }
@@ -420,6 +647,18 @@ pub mod test {
             assert_match!(IssuerState::Initial(_), issuer_sm.state);
             assert_eq!(source_id(), issuer_sm.get_source_id());
         }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_issuer_from_proposal() {
+            let _setup = SetupMocks::init();
+
+            let issuer_sm = _issuer_sm_from_proposal();
+
+            assert_match!(IssuerState::ProposalReceived(_), issuer_sm.state);
+            assert_eq!(_credential_proposal(), issuer_sm.get_proposal().unwrap());
+            assert_eq!(_credential_proposal().thread_id(), issuer_sm.state.thread_id());
+        }
     }
 
     mod handle_message {
@@ -441,7 +680,7 @@ pub mod test {
             let _setup = SetupMocks::init();
 
             let mut issuer_sm = _issuer_sm();
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
 
             assert_match!(IssuerState::OfferSent(_), issuer_sm.state);
         }
@@ -453,10 +692,10 @@ pub mod test {
 
             let mut issuer_sm = _issuer_sm();
 
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::Credential(_credential()), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::Credential(_credential()), _send_message()).unwrap();
             assert_match!(IssuerState::Initial(_), issuer_sm.state);
 
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialRequest(_credential_request()), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialRequest(_credential_request()), _send_message()).unwrap();
             assert_match!(IssuerState::Initial(_), issuer_sm.state);
         }
 
@@ -466,8 +705,8 @@ pub mod test {
             let _setup = SetupMocks::init();
 
             let mut issuer_sm = _issuer_sm();
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialRequest(_credential_request()), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialRequest(_credential_request()), _send_message()).unwrap();
 
             assert_match!(IssuerState::RequestReceived(_), issuer_sm.state);
         }
@@ -478,21 +717,96 @@ pub mod test {
             let _setup = SetupMocks::init();
 
             let mut issuer_sm = _issuer_sm();
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialProposal(_credential_proposal()), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
+            let thread_id = issuer_sm.state.thread_id();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialProposal(_credential_proposal()), _send_message()).unwrap();
+
+            assert_match!(IssuerState::ProposalReceived(_), issuer_sm.state);
+            assert_eq!(thread_id, issuer_sm.state.thread_id());
+            assert_eq!(_credential_proposal(), issuer_sm.get_proposal().unwrap());
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_issuer_rejects_invalid_credential_proposal_from_offer_sent_state() {
+            let _setup = SetupMocks::init();
+
+            let mut issuer_sm = _issuer_sm();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
+
+            let send_message = Some(&|message: &A2AMessage| {
+                assert_match!(A2AMessage::CommonProblemReport(_), message);
+                VcxResult::Ok(())
+            });
+            let proposal = CredentialProposal { formats: vec![_filter_format()], .._credential_proposal() };
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialProposal(proposal), send_message).unwrap();
+
+            assert_match!(IssuerState::OfferSent(_), issuer_sm.state);
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_issuer_handle_credential_offer_send_message_from_proposal_received_state() {
+            let _setup = SetupMocks::init();
+
+            let send_message = Some(&|message: &A2AMessage| {
+                match message {
+                    A2AMessage::CredentialOffer(offer) => {
+                        assert_eq!(_credential_proposal().thread_id(), offer.thread_id());
+                        Ok(())
+                    }
+                    _ => Err(VcxError::from(VcxErrorKind::InvalidMessages))
+                }
+            });
+
+            let mut issuer_sm = _issuer_sm_from_proposal();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialOfferSend(_offer_info(), None), send_message).unwrap();
+
+            assert_match!(IssuerState::OfferSent(_), issuer_sm.state);
+            assert_eq!(_credential_proposal().thread_id(), issuer_sm.state.thread_id());
+            assert_eq!(_rev_reg_id(), issuer_sm.get_rev_reg_id().unwrap());
+
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialRequest(_credential_request()), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialSend(), _send_message()).unwrap();
+
+            assert_match!(IssuerState::Finished(_), issuer_sm.state);
+            assert_eq!(Status::Success.code(), issuer_sm.credential_status());
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_issuer_handle_problem_report_message_from_proposal_received_state() {
+            let _setup = SetupMocks::init();
+
+            let mut issuer_sm = _issuer_sm_from_proposal();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::ProblemReport(_problem_report()), _send_message()).unwrap();
 
             assert_match!(IssuerState::Finished(_), issuer_sm.state);
             assert_eq!(Status::Failed(ProblemReport::default()).code(), issuer_sm.credential_status());
         }
 
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_issuer_handle_other_messages_from_proposal_received_state() {
+            let _setup = SetupMocks::init();
+
+            let mut issuer_sm = _issuer_sm_from_proposal();
+
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
+            assert_match!(IssuerState::ProposalReceived(_), issuer_sm.state);
+
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialRequest(_credential_request()), _send_message()).unwrap();
+            assert_match!(IssuerState::ProposalReceived(_), issuer_sm.state);
+        }
+
         #[test]
         #[cfg(feature = "general_test")]
         fn test_issuer_handle_problem_report_message_from_offer_sent_state() {
             let _setup = SetupMocks::init();
 
             let mut issuer_sm = _issuer_sm();
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::ProblemReport(_problem_report()), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::ProblemReport(_problem_report()), _send_message()).unwrap();
 
             assert_match!(IssuerState::Finished(_), issuer_sm.state);
             assert_eq!(Status::Failed(ProblemReport::default()).code(), issuer_sm.credential_status());
@@ -504,8 +818,8 @@ pub mod test {
             let _setup = SetupMocks::init();
 
             let mut issuer_sm = _issuer_sm();
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::Credential(_credential()), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::Credential(_credential()), _send_message()).unwrap();
 
             assert_match!(IssuerState::OfferSent(_), issuer_sm.state);
         }
@@ -516,9 +830,9 @@ pub mod test {
             let _setup = SetupMocks::init();
 
             let mut issuer_sm = _issuer_sm();
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialRequest(_credential_request()), _send_message()).unwrap();
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialSend(), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialRequest(_credential_request()), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialSend(), _send_message()).unwrap();
 
             assert_match!(IssuerState::Finished(_), issuer_sm.state);
             assert_eq!(Status::Success.code(), issuer_sm.credential_status());
@@ -530,9 +844,9 @@ pub mod test {
             let _setup = SetupMocks::init();
 
             let mut issuer_sm = _issuer_sm();
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialRequest(CredentialRequest::create()), _send_message()).unwrap();
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialSend(), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialRequest(CredentialRequest::create()), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialSend(), _send_message()).unwrap();
 
             assert_match!(IssuerState::Finished(_), issuer_sm.state);
             assert_eq!(Status::Failed(ProblemReport::default()).code(), issuer_sm.credential_status());
@@ -544,14 +858,14 @@ pub mod test {
             let _setup = SetupMocks::init();
 
             let mut issuer_sm = _issuer_sm();
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialRequest(_credential_request()), _send_message()).unwrap();
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialSend(), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialRequest(_credential_request()), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialSend(), _send_message()).unwrap();
 
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialSend(), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialSend(), _send_message()).unwrap();
             assert_match!(IssuerState::Finished(_), issuer_sm.state);
 
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialAck(_ack()), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialAck(_ack()), _send_message()).unwrap();
             assert_match!(IssuerState::Finished(_), issuer_sm.state);
         }
 
@@ -563,21 +877,47 @@ pub mod test {
             let _setup = SetupMocks::init();
 
             let mut issuer_sm = _issuer_sm();
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialRequest(_credential_request()), _send_message()).unwrap();
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialSend(), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialRequest(_credential_request()), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialSend(), _send_message()).unwrap();
 
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialInit(None), _send_message()).unwrap();
             assert_match!(IssuerState::Finished(_), issuer_sm.state);
 
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialRequest(_credential_request()), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::CredentialRequest(_credential_request()), _send_message()).unwrap();
             assert_match!(IssuerState::Finished(_), issuer_sm.state);
 
-            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::Credential(_credential()), _send_message()).unwrap();
+            issuer_sm = issuer_sm.handle_message(&ProfileContext::Global, CredentialIssuanceMessage::Credential(_credential()), _send_message()).unwrap();
             assert_match!(IssuerState::Finished(_), issuer_sm.state);
         }
     }
 
+    mod abandon {
+        use super::*;
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_issuer_abandon_offer_sent() {
+            let _setup = SetupMocks::init();
+
+            let issuer_sm = _issuer_sm().to_offer_sent_state().abandon(None, _send_message()).unwrap();
+            assert_match!(IssuerState::Finished(_), issuer_sm.state);
+            assert_eq!(VcxStateType::VcxStateNone as u32, issuer_sm.state());
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_issuer_cannot_abandon_sent_credential() {
+            let _setup = SetupMocks::init();
+
+            let state = IssuerState::CredentialSent(CredentialSentState { revocation_info_v1: None, thread_id: String::from("thread_id") });
+            let issuer_sm = IssuerSM::step(state, source_id());
+            assert_eq!(VcxErrorKind::InvalidState, issuer_sm.abandon(None, _send_message()).unwrap_err().kind());
+
+            assert_eq!(VcxErrorKind::InvalidState, _issuer_sm().abandon(None, _send_message()).unwrap_err().kind());
+        }
+    }
+
     mod find_message_to_handle {
         use super::*;
 
@@ -729,6 +1069,7 @@ pub mod test {
             let _setup = SetupMocks::init();
 
             assert_eq!(VcxStateType::VcxStateInitialized as u32, _issuer_sm().state());
+            assert_eq!(VcxStateType::VcxStateInitialized as u32, _issuer_sm_from_proposal().state());
             assert_eq!(VcxStateType::VcxStateOfferSent as u32, _issuer_sm().to_offer_sent_state().state());
             assert_eq!(VcxStateType::VcxStateRequestReceived as u32, _issuer_sm().to_request_received_state().state());
             assert_eq!(VcxStateType::VcxStateAccepted as u32, _issuer_sm().to_finished_state().state());
@@ -750,6 +1091,26 @@ pub mod test {
         }
     }
 
+    mod set_rev_reg {
+        use super::*;
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_set_rev_reg_before_issuance() {
+            let _setup = SetupMocks::init();
+
+            let issuer_sm = _issuer_sm().to_request_received_state();
+            assert_eq!(Some(_rev_reg_id()), issuer_sm.get_pending_issuance_rev_reg_id());
+
+            let issuer_sm = issuer_sm.set_rev_reg(String::from("new_rev_reg_id"), String::from("new_tails_file")).unwrap();
+            assert_eq!("new_rev_reg_id", issuer_sm.get_rev_reg_id().unwrap());
+
+            let issuer_sm = _issuer_sm().to_finished_state();
+            assert_eq!(None, issuer_sm.get_pending_issuance_rev_reg_id());
+            assert_eq!(VcxErrorKind::InvalidState, issuer_sm.set_rev_reg(String::from("new_rev_reg_id"), String::from("new_tails_file")).unwrap_err().kind());
+        }
+    }
+
     mod is_revokable {
         use super::*;
 
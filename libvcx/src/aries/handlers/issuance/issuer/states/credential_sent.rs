@@ -19,4 +19,4 @@ impl From<CredentialSentState> for FinishedState {
             status: Status::Success,
         }
     }
-}
\ No newline at end of file
+}
@@ -1,6 +1,7 @@
 use crate::aries::handlers::issuance::issuer::states::finished::FinishedState;
 use crate::aries::handlers::issuance::issuer::states::offer_sent::OfferSentState;
 use crate::aries::messages::a2a::MessageId;
+use crate::aries::messages::a2a::protocol_version::ProtocolVersion;
 use crate::aries::messages::status::Status;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -9,6 +10,8 @@ pub struct InitialState {
     pub credential_json: String,
     pub rev_reg_id: Option<String>,
     pub tails_file: Option<String>,
+    #[serde(default)]
+    pub protocol_version: ProtocolVersion,
 }
 
 impl InitialState {
@@ -18,6 +21,7 @@ impl InitialState {
             credential_json: credential_json.to_string(),
             rev_reg_id,
             tails_file,
+            protocol_version: ProtocolVersion::default(),
         }
     }
 }
@@ -1,7 +1,9 @@
 use crate::aries::handlers::issuance::issuer::state_machine::RevocationInfoV1;
 use crate::aries::handlers::issuance::issuer::states::finished::FinishedState;
+use crate::aries::handlers::issuance::issuer::states::proposal_received::ProposalReceivedState;
 use crate::aries::handlers::issuance::issuer::states::requested_received::RequestReceivedState;
 use crate::aries::messages::error::ProblemReport;
+use crate::aries::messages::issuance::credential_proposal::CredentialProposal;
 use crate::aries::messages::issuance::credential_request::CredentialRequest;
 use crate::aries::messages::status::Status;
 
@@ -44,6 +46,16 @@ impl From<(OfferSentState, CredentialRequest)> for RequestReceivedState {
     }
 }
 
+impl From<(OfferSentState, CredentialProposal)> for ProposalReceivedState {
+    fn from((state, proposal): (OfferSentState, CredentialProposal)) -> Self {
+        trace!("SM is now in ProposalReceived state");
+        ProposalReceivedState {
+            proposal,
+            thread_id: state.thread_id,
+        }
+    }
+}
+
 impl From<(OfferSentState, ProblemReport)> for FinishedState {
     fn from((state, err): (OfferSentState, ProblemReport)) -> Self {
         trace!("SM is now in Finished state");
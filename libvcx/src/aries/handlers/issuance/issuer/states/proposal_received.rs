@@ -0,0 +1,46 @@
+use crate::aries::handlers::issuance::issuer::issuer::OfferInfo;
+use crate::aries::handlers::issuance::issuer::states::finished::FinishedState;
+use crate::aries::handlers::issuance::issuer::states::offer_sent::OfferSentState;
+use crate::aries::messages::error::ProblemReport;
+use crate::aries::messages::issuance::credential_proposal::CredentialProposal;
+use crate::aries::messages::status::Status;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProposalReceivedState {
+    pub proposal: CredentialProposal,
+    pub thread_id: String,
+}
+
+impl ProposalReceivedState {
+    pub fn new(proposal: CredentialProposal) -> Self {
+        ProposalReceivedState {
+            thread_id: proposal.thread_id(),
+            proposal,
+        }
+    }
+}
+
+impl From<(ProposalReceivedState, OfferInfo, String)> for OfferSentState {
+    fn from((state, offer_info, offer): (ProposalReceivedState, OfferInfo, String)) -> Self {
+        trace!("SM is now in OfferSent state");
+        OfferSentState {
+            offer,
+            cred_data: offer_info.credential_json,
+            rev_reg_id: offer_info.rev_reg_id,
+            tails_file: offer_info.tails_file,
+            thread_id: state.thread_id,
+        }
+    }
+}
+
+impl From<(ProposalReceivedState, ProblemReport)> for FinishedState {
+    fn from((state, err): (ProposalReceivedState, ProblemReport)) -> Self {
+        trace!("SM is now in Finished state");
+        FinishedState {
+            cred_id: None,
+            thread_id: state.thread_id,
+            revocation_info_v1: None,
+            status: Status::Failed(err),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::aries::handlers::issuance::issuer::issuer::OfferInfo;
 use crate::aries::messages::a2a::A2AMessage;
 use crate::aries::messages::error::ProblemReport;
 use crate::aries::messages::issuance::credential::Credential;
@@ -9,7 +10,9 @@ use crate::aries::messages::issuance::credential_request::CredentialRequest;
 #[derive(Debug, Clone)]
 pub enum CredentialIssuanceMessage {
     CredentialInit(Option<String>),
+    CredentialOfferSend(OfferInfo, Option<String>),
     CredentialSend(),
+    CredentialProposalSend(),
     CredentialProposal(CredentialProposal),
     CredentialOffer(CredentialOffer),
     CredentialRequestSend(String),
@@ -35,7 +38,7 @@ impl From<A2AMessage> for CredentialIssuanceMessage {
             A2AMessage::Credential(credential) => {
                 CredentialIssuanceMessage::Credential(credential)
             }
-            A2AMessage::Ack(ack) | A2AMessage::CredentialAck(ack) => {
+            A2AMessage::Ack(ack) | A2AMessage::CredentialAck(ack) | A2AMessage::CredentialAckV2(ack) => {
                 CredentialIssuanceMessage::CredentialAck(ack)
             }
             A2AMessage::CommonProblemReport(report) => {
@@ -0,0 +1,360 @@
+use std::collections::HashMap;
+
+use crate::aries::handlers::connection::connection::Connection;
+use crate::aries::messages::a2a::A2AMessage;
+use crate::aries::messages::mediation::keylist_query::KeylistQuery;
+use crate::aries::messages::mediation::keylist_update::{KeylistUpdate, KeylistUpdateAction};
+use crate::aries::messages::mediation::keylist_update_response::{KeylistUpdateResponse, KeylistUpdateResult};
+use crate::aries::messages::mediation::mediate_request::MediateRequest;
+use crate::error::prelude::*;
+
+/**
+Client (recipient) side of the coordinate-mediation protocol (RFC 0211).
+Once the mediator grants mediation, its endpoint and routing keys are kept in the granted state,
+DidDocs and invitations of connections created afterwards within the same profile use them.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MediationClient {
+    source_id: String,
+    state: MediationClientState,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum MediationClientState {
+    Initial,
+    Requested(RequestedState),
+    Granted(GrantedState),
+    Denied(DeniedState),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RequestedState {
+    pub thread_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GrantedState {
+    pub endpoint: String,
+    pub routing_keys: Vec<String>,
+    pub recipient_keys: Vec<String>,
+    #[serde(default)]
+    pub pending_updates: Vec<KeylistUpdate>,
+    #[serde(default)]
+    pub pending_queries: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeniedState {
+    pub thread_id: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum MediationState {
+    Initial = 0,
+    Requested = 1,
+    Granted = 2,
+    Denied = 3,
+}
+
+impl MediationClient {
+    pub fn create(source_id: &str) -> MediationClient {
+        trace!("MediationClient::create >>> source_id: {:?}", source_id);
+        MediationClient { source_id: source_id.to_string(), state: MediationClientState::Initial }
+    }
+
+    pub fn get_source_id(&self) -> String {
+        self.source_id.clone()
+    }
+
+    pub fn get_state(&self) -> MediationState {
+        match self.state {
+            MediationClientState::Initial => MediationState::Initial,
+            MediationClientState::Requested(_) => MediationState::Requested,
+            MediationClientState::Granted(_) => MediationState::Granted,
+            MediationClientState::Denied(_) => MediationState::Denied,
+        }
+    }
+
+    pub fn state(&self) -> u32 {
+        self.get_state() as u32
+    }
+
+    fn granted_state(&self) -> VcxResult<&GrantedState> {
+        match self.state {
+            MediationClientState::Granted(ref state) => Ok(state),
+            _ => Err(VcxError::from_msg(VcxErrorKind::NotReady, "Mediation has not been granted"))
+        }
+    }
+
+    fn granted_state_mut(&mut self) -> VcxResult<&mut GrantedState> {
+        match self.state {
+            MediationClientState::Granted(ref mut state) => Ok(state),
+            _ => Err(VcxError::from_msg(VcxErrorKind::NotReady, "Mediation has not been granted"))
+        }
+    }
+
+    pub fn endpoint(&self) -> VcxResult<String> {
+        self.granted_state().map(|state| state.endpoint.clone())
+    }
+
+    pub fn routing_keys(&self) -> VcxResult<Vec<String>> {
+        self.granted_state().map(|state| state.routing_keys.clone())
+    }
+
+    pub fn recipient_keys(&self) -> VcxResult<Vec<String>> {
+        self.granted_state().map(|state| state.recipient_keys.clone())
+    }
+
+    pub fn has_transitions(&self) -> bool {
+        match self.state {
+            MediationClientState::Requested(_) => true,
+            MediationClientState::Granted(ref state) => !state.pending_updates.is_empty() || !state.pending_queries.is_empty(),
+            _ => false
+        }
+    }
+
+    pub fn send_request(&mut self, send_message: impl Fn(&A2AMessage) -> VcxResult<()>) -> VcxResult<()> {
+        trace!("MediationClient::send_request >>>");
+        match self.state {
+            MediationClientState::Initial | MediationClientState::Denied(_) => {
+                let request = MediateRequest::create();
+                send_message(&request.to_a2a_message())?;
+                self.state = MediationClientState::Requested(RequestedState { thread_id: request.id.0 });
+                Ok(())
+            }
+            _ => Err(VcxError::from_msg(VcxErrorKind::NotReady, "Mediation has already been requested"))
+        }
+    }
+
+    pub fn update_keylist(&mut self, add: Vec<String>, remove: Vec<String>, send_message: impl Fn(&A2AMessage) -> VcxResult<()>) -> VcxResult<()> {
+        trace!("MediationClient::update_keylist >>> add: {:?}, remove: {:?}", add, remove);
+        if add.is_empty() && remove.is_empty() {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidOption, "Keylist update does not contain any key"));
+        }
+        let state = self.granted_state_mut()?;
+
+        let update = add.into_iter().fold(KeylistUpdate::create(), |update, key| update.add_key(key));
+        let update = remove.into_iter().fold(update, |update, key| update.remove_key(key));
+        send_message(&update.to_a2a_message())?;
+        state.pending_updates.push(update);
+        Ok(())
+    }
+
+    pub fn query_keylist(&mut self, send_message: impl Fn(&A2AMessage) -> VcxResult<()>) -> VcxResult<()> {
+        trace!("MediationClient::query_keylist >>>");
+        let state = self.granted_state_mut()?;
+
+        let query = KeylistQuery::create();
+        send_message(&query.to_a2a_message())?;
+        state.pending_queries.push(query.id.0);
+        Ok(())
+    }
+
+    pub fn find_message_to_handle(&self, messages: HashMap<String, A2AMessage>) -> Option<(String, A2AMessage)> {
+        trace!("MediationClient::find_message_to_handle >>> messages: {:?}", messages);
+
+        for (uid, message) in messages {
+            if self.is_expected_message(&message) {
+                return Some((uid, message));
+            }
+        }
+        None
+    }
+
+    fn is_expected_message(&self, message: &A2AMessage) -> bool {
+        match (&self.state, message) {
+            (MediationClientState::Requested(state), A2AMessage::MediateGrant(grant)) => grant.from_thread(&state.thread_id),
+            (MediationClientState::Requested(state), A2AMessage::MediateDeny(deny)) => deny.from_thread(&state.thread_id),
+            (MediationClientState::Requested(state), A2AMessage::CommonProblemReport(problem_report)) => problem_report.from_thread(&state.thread_id),
+            (MediationClientState::Granted(state), A2AMessage::KeylistUpdateResponse(response)) =>
+                state.pending_updates.iter().any(|update| response.from_thread(&update.id.0)),
+            (MediationClientState::Granted(state), A2AMessage::Keylist(keylist)) =>
+                state.pending_queries.iter().any(|query_id| keylist.from_thread(query_id)),
+            _ => false
+        }
+    }
+
+    pub fn handle_message(&mut self, message: A2AMessage) -> VcxResult<()> {
+        trace!("MediationClient::handle_message >>> message: {:?}", message);
+        if !self.is_expected_message(&message) {
+            warn!("MediationClient::handle_message >>> unexpected message in state {:?}", self.get_state());
+            return Ok(());
+        }
+
+        let state = match (self.state.clone(), message) {
+            (MediationClientState::Requested(_), A2AMessage::MediateGrant(grant)) => {
+                MediationClientState::Granted(GrantedState {
+                    endpoint: grant.endpoint,
+                    routing_keys: grant.routing_keys,
+                    recipient_keys: Vec::new(),
+                    pending_updates: Vec::new(),
+                    pending_queries: Vec::new(),
+                })
+            }
+            (MediationClientState::Requested(state), _) => {
+                MediationClientState::Denied(DeniedState { thread_id: state.thread_id })
+            }
+            (MediationClientState::Granted(mut state), A2AMessage::KeylistUpdateResponse(response)) => {
+                state.pending_updates.retain(|update| !response.from_thread(&update.id.0));
+                _apply_keylist_update_response(&mut state.recipient_keys, &response);
+                MediationClientState::Granted(state)
+            }
+            (MediationClientState::Granted(mut state), A2AMessage::Keylist(keylist)) => {
+                state.pending_queries.retain(|query_id| !keylist.from_thread(query_id));
+                state.recipient_keys = keylist.recipient_keys();
+                MediationClientState::Granted(state)
+            }
+            (state, _) => state
+        };
+        self.state = state;
+        Ok(())
+    }
+
+    pub fn update_state(&mut self, connection: &Connection) -> VcxResult<u32> {
+        trace!("MediationClient::update_state >>>");
+        if !self.has_transitions() { return Ok(self.state()); }
+
+        let messages = connection.get_messages()?;
+        if let Some((uid, message)) = self.find_message_to_handle(messages) {
+            self.handle_message(message)?;
+            connection.update_message_status(uid)?;
+        }
+        Ok(self.state())
+    }
+}
+
+fn _apply_keylist_update_response(recipient_keys: &mut Vec<String>, response: &KeylistUpdateResponse) {
+    for updated in response.updated.iter() {
+        match updated.result {
+            KeylistUpdateResult::Success | KeylistUpdateResult::NoChange => {}
+            _ => {
+                warn!("Mediator failed to {:?} recipient key {}: {:?}", updated.action, updated.recipient_key, updated.result);
+                continue;
+            }
+        }
+        recipient_keys.retain(|key| key != &updated.recipient_key);
+        if updated.action == KeylistUpdateAction::Add {
+            recipient_keys.push(updated.recipient_key.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::aries::messages::a2a::MessageId;
+    use crate::aries::messages::mediation::keylist::Keylist;
+    use crate::aries::messages::mediation::keylist_update::tests::*;
+    use crate::aries::messages::mediation::keylist_update_response::tests::*;
+    use crate::aries::messages::mediation::mediate_deny::MediateDeny;
+    use crate::aries::messages::mediation::mediate_grant::MediateGrant;
+    use crate::aries::messages::mediation::mediate_grant::tests::*;
+    use crate::utils::devsetup::SetupMocks;
+
+    use super::*;
+
+    fn _send_message(_: &A2AMessage) -> VcxResult<()> {
+        Ok(())
+    }
+
+    fn _grant() -> A2AMessage {
+        MediateGrant::create()
+            .set_endpoint(_endpoint())
+            .set_routing_keys(_routing_keys())
+            .set_thread_id(&MessageId::id().0)
+            .to_a2a_message()
+    }
+
+    pub fn _mediation_client_granted() -> MediationClient {
+        let mut client = MediationClient::create("test");
+        client.send_request(_send_message).unwrap();
+        client.handle_message(_grant()).unwrap();
+        client
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_mediation_client_grant() {
+        let _setup = SetupMocks::init();
+
+        let mut client = MediationClient::create("test");
+        assert_eq!(MediationState::Initial, client.get_state());
+        assert!(!client.has_transitions());
+
+        client.send_request(_send_message).unwrap();
+        assert_eq!(MediationState::Requested, client.get_state());
+        assert_eq!(VcxErrorKind::NotReady, client.send_request(_send_message).unwrap_err().kind());
+
+        client.handle_message(_grant()).unwrap();
+        assert_eq!(MediationState::Granted, client.get_state());
+        assert_eq!(_endpoint(), client.endpoint().unwrap());
+        assert_eq!(_routing_keys(), client.routing_keys().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_mediation_client_deny() {
+        let _setup = SetupMocks::init();
+
+        let mut client = MediationClient::create("test");
+        client.send_request(_send_message).unwrap();
+
+        client.handle_message(MediateDeny::create().set_thread_id("other").to_a2a_message()).unwrap();
+        assert_eq!(MediationState::Requested, client.get_state());
+
+        client.handle_message(MediateDeny::create().set_thread_id(&MessageId::id().0).to_a2a_message()).unwrap();
+        assert_eq!(MediationState::Denied, client.get_state());
+        assert_eq!(VcxErrorKind::NotReady, client.endpoint().unwrap_err().kind());
+        assert_eq!(VcxErrorKind::NotReady, client.update_keylist(vec![_recipient_key()], vec![], _send_message).unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_mediation_client_update_keylist() {
+        let _setup = SetupMocks::init();
+
+        let mut client = _mediation_client_granted();
+        assert_eq!(VcxErrorKind::InvalidOption, client.update_keylist(vec![], vec![], _send_message).unwrap_err().kind());
+
+        client.update_keylist(vec![_recipient_key()], vec![_other_recipient_key()], _send_message).unwrap();
+        assert!(client.has_transitions());
+
+        let mut messages = HashMap::new();
+        messages.insert(String::from("key_1"), _grant());
+        messages.insert(String::from("key_2"), _keylist_update_response().set_thread_id(&MessageId::id().0).to_a2a_message());
+        let (uid, message) = client.find_message_to_handle(messages).unwrap();
+        assert_eq!("key_2", uid);
+
+        client.handle_message(message).unwrap();
+        assert_eq!(vec![_recipient_key()], client.recipient_keys().unwrap());
+        assert!(!client.has_transitions());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_mediation_client_query_keylist() {
+        let _setup = SetupMocks::init();
+
+        let mut client = _mediation_client_granted();
+        client.query_keylist(_send_message).unwrap();
+
+        let keylist = Keylist::create()
+            .set_keys(vec![_recipient_key(), _other_recipient_key()])
+            .set_thread_id(&MessageId::id().0);
+        client.handle_message(keylist.to_a2a_message()).unwrap();
+        assert_eq!(vec![_recipient_key(), _other_recipient_key()], client.recipient_keys().unwrap());
+        assert!(!client.has_transitions());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_apply_keylist_update_response_skips_failed_updates() {
+        let _setup = SetupMocks::init();
+
+        let mut recipient_keys = vec![_other_recipient_key()];
+        let mut response = _keylist_update_response();
+        response.updated[0].result = KeylistUpdateResult::ServerError;
+        _apply_keylist_update_response(&mut recipient_keys, &response);
+
+        assert!(recipient_keys.is_empty());
+    }
+}
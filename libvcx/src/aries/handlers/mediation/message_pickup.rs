@@ -0,0 +1,513 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+
+use crate::aries::handlers::connection::connection::Connection;
+use crate::aries::messages::a2a::A2AMessage;
+use crate::aries::messages::a2a::protocol_version::ProtocolVersion;
+use crate::aries::messages::connection::did_doc::DidDoc;
+use crate::aries::messages::pickup::batch_pickup::BatchPickup;
+use crate::aries::messages::pickup::delivery_request::DeliveryRequest;
+use crate::aries::messages::pickup::live_delivery_change::LiveDeliveryChange;
+use crate::aries::messages::pickup::messages_received::MessagesReceived;
+use crate::aries::messages::pickup::status::Status;
+use crate::aries::messages::pickup::status_request::StatusRequest;
+use crate::aries::utils::encryption_envelope::EncryptionEnvelope;
+use crate::aries::utils::send_message_with_response;
+use crate::error::prelude::*;
+use crate::profile::ProfileContext;
+
+/**
+Client side of the message pickup protocol (messagepickup/1.0 of RFC 0212 and messagepickup/2.0 of RFC 0685).
+Requests are sent over the connection with the mediator, which replies over the same connection (return route).
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MessagePickup {
+    #[serde(skip)]
+    profile: ProfileContext,
+    version: ProtocolVersion,
+    sender_vk: String,
+    mediator_vk: String,
+    mediator_did_doc: DidDoc,
+    batch_size: u32,
+    #[serde(default)]
+    live_delivery: bool,
+}
+
+/**
+Packed message held by the mediator, `id` is assigned by the mediator.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PickedUpMessage {
+    pub id: String,
+    pub payload: Vec<u8>,
+}
+
+impl MessagePickup {
+    pub const DEFAULT_BATCH_SIZE: u32 = 10;
+
+    pub fn create(connection: &Connection, version: ProtocolVersion) -> VcxResult<MessagePickup> {
+        trace!("MessagePickup::create >>> version: {:?}", version);
+        let mediator_did_doc = connection.their_did_doc()
+            .ok_or(VcxError::from_msg(VcxErrorKind::NotReady, "Cannot pick up messages: connection with mediator is not established"))?;
+        Ok(MessagePickup {
+            profile: connection.profile().clone(),
+            version,
+            sender_vk: connection.pairwise_info().pw_vk.clone(),
+            mediator_vk: connection.remote_vk()?,
+            mediator_did_doc,
+            batch_size: Self::DEFAULT_BATCH_SIZE,
+            live_delivery: false,
+        })
+    }
+
+    pub fn set_batch_size(mut self, batch_size: u32) -> MessagePickup {
+        self.batch_size = batch_size;
+        self
+    }
+
+    pub fn profile(&self) -> &ProfileContext {
+        &self.profile
+    }
+
+    pub fn version(&self) -> ProtocolVersion {
+        self.version
+    }
+
+    pub fn live_delivery(&self) -> bool {
+        self.live_delivery
+    }
+
+    pub fn picks_up_for(&self, recipient_vk: &str) -> bool {
+        self.sender_vk != recipient_vk
+    }
+
+    /**
+    Returns closure sending message to the mediator and unpacking its reply, if there is any.
+     */
+    pub fn exchange_closure(&self) -> impl Fn(&A2AMessage) -> VcxResult<Option<A2AMessage>> {
+        let sender_vk = self.sender_vk.clone();
+        let mediator_vk = self.mediator_vk.clone();
+        let did_doc = self.mediator_did_doc.clone();
+        let profile = self.profile.clone();
+        move |message: &A2AMessage| {
+            let wallet_handle = profile.wallet_handle()?;
+            let response = send_message_with_response(wallet_handle, &sender_vk, &did_doc, message)?;
+            if response.is_empty() {
+                return Ok(None);
+            }
+            EncryptionEnvelope::auth_unpack(wallet_handle, response, &mediator_vk).map(Some)
+        }
+    }
+
+    pub fn get_status(&self, exchange: impl Fn(&A2AMessage) -> VcxResult<Option<A2AMessage>>) -> VcxResult<Status> {
+        trace!("MessagePickup::get_status >>>");
+        let request = StatusRequest::create();
+        let request = match self.version {
+            ProtocolVersion::V1 => A2AMessage::PickupStatusRequest(request),
+            ProtocolVersion::V2 => A2AMessage::PickupStatusRequestV2(request),
+        };
+        match exchange(&request)? {
+            Some(A2AMessage::PickupStatus(status)) | Some(A2AMessage::PickupStatusV2(status)) => Ok(status),
+            response => Err(_unexpected_response("status", response))
+        }
+    }
+
+    /**
+    Requests next batch of messages held by the mediator. Messages delivered by messagepickup/2.0 mediators
+    stay queued until they are acknowledged by `acknowledge`.
+     */
+    pub fn pickup(&self, exchange: impl Fn(&A2AMessage) -> VcxResult<Option<A2AMessage>>) -> VcxResult<Vec<PickedUpMessage>> {
+        trace!("MessagePickup::pickup >>> batch_size: {}", self.batch_size);
+        match self.version {
+            ProtocolVersion::V1 => {
+                let request = BatchPickup::create().set_batch_size(self.batch_size);
+                match exchange(&request.to_a2a_message())? {
+                    Some(A2AMessage::PickupBatch(batch)) => Ok(batch.messages.into_iter()
+                        .map(|message| PickedUpMessage { id: message.id, payload: message.message.to_string().into_bytes() })
+                        .collect()),
+                    response => Err(_unexpected_response("batch", response))
+                }
+            }
+            ProtocolVersion::V2 => {
+                let request = DeliveryRequest::create().set_limit(self.batch_size);
+                match exchange(&request.to_a2a_message())? {
+                    Some(A2AMessage::PickupDelivery(delivery)) => delivery.attachments.into_iter()
+                        .map(|message| Ok(PickedUpMessage { payload: message.data.get_bytes()?, id: message.id }))
+                        .collect(),
+                    // mediator replies with status if there are no messages to deliver
+                    Some(A2AMessage::PickupStatusV2(_)) => Ok(Vec::new()),
+                    response => Err(_unexpected_response("delivery", response))
+                }
+            }
+        }
+    }
+
+    /**
+    Lets the mediator remove delivered messages, messagepickup/1.0 mediators remove them once they are delivered.
+     */
+    pub fn acknowledge(&self, message_ids: Vec<String>, exchange: impl Fn(&A2AMessage) -> VcxResult<Option<A2AMessage>>) -> VcxResult<()> {
+        trace!("MessagePickup::acknowledge >>> message_ids: {:?}", message_ids);
+        if self.version == ProtocolVersion::V1 || message_ids.is_empty() {
+            return Ok(());
+        }
+        exchange(&MessagesReceived::create().set_message_id_list(message_ids).to_a2a_message())?;
+        Ok(())
+    }
+
+    /**
+    Switches live mode of messagepickup/2.0 mediators. In live mode the mediator delivers messages
+    to the endpoint of the recipient as they arrive instead of queueing them.
+     */
+    pub fn set_live_delivery(&mut self, live_delivery: bool, exchange: impl Fn(&A2AMessage) -> VcxResult<Option<A2AMessage>>) -> VcxResult<()> {
+        trace!("MessagePickup::set_live_delivery >>> live_delivery: {}", live_delivery);
+        if self.version == ProtocolVersion::V1 {
+            return Err(VcxError::from_msg(VcxErrorKind::ActionNotSupported, "Live delivery is not supported by messagepickup/1.0"));
+        }
+        let change = LiveDeliveryChange::create().set_live_delivery(live_delivery);
+        match exchange(&change.to_a2a_message())? {
+            Some(A2AMessage::CommonProblemReport(problem_report)) =>
+                Err(VcxError::from_msg(VcxErrorKind::ActionNotSupported, format!("Mediator refused to change live delivery: {:?}", problem_report.comment))),
+            _ => {
+                self.live_delivery = live_delivery;
+                Ok(())
+            }
+        }
+    }
+}
+
+fn _unexpected_response(expected: &str, response: Option<A2AMessage>) -> VcxError {
+    match response {
+        Some(A2AMessage::CommonProblemReport(problem_report)) =>
+            VcxError::from_msg(VcxErrorKind::PostMessageFailed, format!("Mediator replied with problem report: {:?}", problem_report.comment)),
+        Some(response) =>
+            VcxError::from_msg(VcxErrorKind::InvalidMessages, format!("Mediator replied with unexpected message, {} was expected: {:?}", expected, response)),
+        None =>
+            VcxError::from_msg(VcxErrorKind::InvalidMessages, format!("Mediator has not replied, {} was expected", expected)),
+    }
+}
+
+struct InboxMessage {
+    profile_handle: Option<u32>,
+    message: PickedUpMessage,
+    recipient_keys: Vec<String>,
+}
+
+lazy_static! {
+    // mediators connections pick up their messages from, per profile
+    static ref PICKUP_MEDIATORS: RwLock<HashMap<Option<u32>, MessagePickup>> = Default::default();
+    static ref INBOX: Mutex<Vec<InboxMessage>> = Default::default();
+}
+
+/**
+Sets mediator connections of the profile download their messages from, messages are downloaded
+from the cloud agency if it is not set.
+ */
+pub fn set_pickup_mediator(profile: &ProfileContext, pickup: Option<MessagePickup>) -> VcxResult<()> {
+    trace!("set_pickup_mediator >>> profile: {:?}, pickup: {:?}", profile, pickup);
+    let mut mediators = PICKUP_MEDIATORS.write()?;
+    match pickup {
+        Some(pickup) => mediators.insert(profile.handle(), pickup),
+        None => mediators.remove(&profile.handle())
+    };
+    Ok(())
+}
+
+pub fn get_pickup_mediator(profile: &ProfileContext) -> VcxResult<Option<MessagePickup>> {
+    Ok(PICKUP_MEDIATORS.read()?.get(&profile.handle()).cloned())
+}
+
+/**
+Connection picks up its messages if message pickup is enabled in its profile, the connection
+with the mediator itself keeps downloading its messages from the cloud agency.
+ */
+pub fn picks_up_messages_for(profile: &ProfileContext, recipient_vk: &str) -> VcxResult<bool> {
+    Ok(get_pickup_mediator(profile)?
+        .map(|pickup| pickup.picks_up_for(recipient_vk))
+        .unwrap_or(false))
+}
+
+/**
+Picks up messages held by the mediator until it delivers no new messages, messages are kept in the inbox
+until connections they are addressed to handle them. Messages delivered by messagepickup/2.0 mediators
+are acknowledged once they are handled, so they are delivered again if the process stops before.
+Returns number of picked up messages.
+ */
+pub fn pickup_messages(profile: &ProfileContext) -> VcxResult<usize> {
+    trace!("pickup_messages >>> profile: {:?}", profile);
+    let pickup = match get_pickup_mediator(profile)? {
+        Some(pickup) => pickup,
+        None => return Ok(0)
+    };
+    let exchange = pickup.exchange_closure();
+
+    let mut picked_up = 0;
+    loop {
+        let messages = pickup.pickup(&exchange)?;
+        let received = messages.len();
+        let stored = store_messages(profile, messages)?;
+        picked_up += stored;
+        // mediator keeps delivering messages which have not been acknowledged yet
+        if stored == 0 || received < pickup.batch_size as usize {
+            break;
+        }
+    }
+    trace!("pickup_messages <<< picked_up: {}", picked_up);
+    Ok(picked_up)
+}
+
+/**
+Adds messages to the inbox of the profile, skipping messages already in it. Returns number of added messages.
+ */
+pub fn store_messages(profile: &ProfileContext, messages: Vec<PickedUpMessage>) -> VcxResult<usize> {
+    let profile_handle = profile.handle();
+    let mut inbox = INBOX.lock()?;
+    let mut stored = 0;
+    for message in messages {
+        if inbox.iter().any(|stored| stored.profile_handle == profile_handle && stored.message.id == message.id) {
+            continue;
+        }
+        let recipient_keys = match EncryptionEnvelope::recipient_verkeys(&message.payload) {
+            Ok(recipient_keys) => recipient_keys,
+            Err(err) => {
+                warn!("store_messages >>> skipping message {} which is not a packed message: {}", message.id, err);
+                continue;
+            }
+        };
+        inbox.push(InboxMessage { profile_handle, message, recipient_keys });
+        stored += 1;
+    }
+    Ok(stored)
+}
+
+/**
+Returns messages from the inbox of the profile addressed to the recipient verkey.
+ */
+pub fn get_inbox_messages(profile: &ProfileContext, recipient_vk: &str) -> VcxResult<Vec<PickedUpMessage>> {
+    let profile_handle = profile.handle();
+    Ok(INBOX.lock()?.iter()
+        .filter(|stored| stored.profile_handle == profile_handle)
+        .filter(|stored| stored.recipient_keys.iter().any(|key| key == recipient_vk))
+        .map(|stored| stored.message.clone())
+        .collect())
+}
+
+/**
+Acknowledges handled message to the mediator and removes it from the inbox of the profile,
+returns false if there is no message with the id.
+ */
+pub fn acknowledge_inbox_message(profile: &ProfileContext, id: &str) -> VcxResult<bool> {
+    let profile_handle = profile.handle();
+    if !INBOX.lock()?.iter().any(|stored| stored.profile_handle == profile_handle && stored.message.id == id) {
+        return Ok(false);
+    }
+    if let Some(pickup) = get_pickup_mediator(profile)? {
+        pickup.acknowledge(vec![id.to_string()], pickup.exchange_closure())?;
+    }
+    remove_inbox_message(profile, id)
+}
+
+/**
+Acknowledges and removes messages of the profile which are addressed to none of the known recipients,
+no connection would ever take them. Returns number of removed messages.
+ */
+pub fn discard_undeliverable_messages(profile: &ProfileContext, known_recipient: impl Fn(&str) -> bool) -> VcxResult<usize> {
+    let profile_handle = profile.handle();
+    let undeliverable: Vec<String> = INBOX.lock()?.iter()
+        .filter(|stored| stored.profile_handle == profile_handle)
+        .filter(|stored| !stored.recipient_keys.iter().any(|key| known_recipient(key)))
+        .map(|stored| stored.message.id.clone())
+        .collect();
+    for id in undeliverable.iter() {
+        warn!("discard_undeliverable_messages >>> discarding message {} addressed to no known connection", id);
+        acknowledge_inbox_message(profile, id)?;
+    }
+    Ok(undeliverable.len())
+}
+
+/**
+Removes message from the inbox of the profile, returns false if there is no message with the id.
+ */
+pub fn remove_inbox_message(profile: &ProfileContext, id: &str) -> VcxResult<bool> {
+    let profile_handle = profile.handle();
+    let mut inbox = INBOX.lock()?;
+    let count = inbox.len();
+    inbox.retain(|stored| stored.profile_handle != profile_handle || stored.message.id != id);
+    Ok(inbox.len() != count)
+}
+
+pub fn clear_inbox() -> VcxResult<()> {
+    INBOX.lock()?.clear();
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::cell::RefCell;
+
+    use crate::aries::messages::attachment::AttachmentData;
+    use crate::aries::messages::connection::did_doc::tests::_did_doc_4;
+    use crate::aries::messages::pickup::batch::{Batch, BatchMessage};
+    use crate::aries::messages::pickup::delivery::{DeliveredMessage, Delivery};
+    use crate::aries::utils::encryption_envelope::tests::_packed_message;
+    use crate::utils::devsetup::SetupMocks;
+
+    use super::*;
+
+    fn _message_pickup(version: ProtocolVersion) -> MessagePickup {
+        MessagePickup {
+            profile: ProfileContext::Global,
+            version,
+            sender_vk: String::from("sender_vk"),
+            mediator_vk: String::from("mediator_vk"),
+            mediator_did_doc: _did_doc_4(),
+            batch_size: 2,
+            live_delivery: false,
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_get_status_works_for_both_versions() {
+        let _setup = SetupMocks::init();
+
+        let status = Status::create().set_message_count(3);
+
+        let pickup = _message_pickup(ProtocolVersion::V1);
+        let sent = RefCell::new(Vec::new());
+        let received = pickup.get_status(|message: &A2AMessage| {
+            sent.borrow_mut().push(message.clone());
+            Ok(Some(A2AMessage::PickupStatus(status.clone())))
+        }).unwrap();
+        assert_eq!(status, received);
+        assert_eq!(A2AMessage::PickupStatusRequest(StatusRequest::create()), sent.borrow()[0]);
+
+        let pickup = _message_pickup(ProtocolVersion::V2);
+        let sent = RefCell::new(Vec::new());
+        pickup.get_status(|message: &A2AMessage| {
+            sent.borrow_mut().push(message.clone());
+            Ok(Some(A2AMessage::PickupStatusV2(status.clone())))
+        }).unwrap();
+        assert_eq!(A2AMessage::PickupStatusRequestV2(StatusRequest::create()), sent.borrow()[0]);
+
+        let err = pickup.get_status(|_: &A2AMessage| Ok(None)).unwrap_err();
+        assert_eq!(VcxErrorKind::InvalidMessages, err.kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_pickup_works_for_batch() {
+        let _setup = SetupMocks::init();
+
+        let packed: serde_json::Value = serde_json::from_slice(&_packed_message(vec!["recipient_vk"])).unwrap();
+        let batch = Batch::create().set_messages(vec![BatchMessage { id: String::from("id_1"), message: packed.clone() }]);
+
+        let pickup = _message_pickup(ProtocolVersion::V1);
+        let messages = pickup.pickup(|message: &A2AMessage| {
+            assert_eq!(BatchPickup::create().set_batch_size(2).to_a2a_message(), *message);
+            Ok(Some(batch.to_a2a_message()))
+        }).unwrap();
+        assert_eq!(vec![PickedUpMessage { id: String::from("id_1"), payload: packed.to_string().into_bytes() }], messages);
+
+        pickup.acknowledge(vec![String::from("id_1")], |_: &A2AMessage| panic!("messagepickup/1.0 has no acknowledgement")).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_pickup_works_for_delivery() {
+        let _setup = SetupMocks::init();
+
+        let packed = _packed_message(vec!["recipient_vk"]);
+        let delivery = Delivery::create()
+            .set_attachments(vec![DeliveredMessage { id: String::from("id_1"), data: AttachmentData::Base64(base64::encode(&packed)) }]);
+
+        let pickup = _message_pickup(ProtocolVersion::V2);
+        let messages = pickup.pickup(|_: &A2AMessage| Ok(Some(delivery.to_a2a_message()))).unwrap();
+        assert_eq!(vec![PickedUpMessage { id: String::from("id_1"), payload: packed }], messages);
+
+        let messages = pickup.pickup(|_: &A2AMessage| Ok(Some(A2AMessage::PickupStatusV2(Status::create())))).unwrap();
+        assert!(messages.is_empty());
+
+        let sent = RefCell::new(Vec::new());
+        pickup.acknowledge(vec![String::from("id_1")], |message: &A2AMessage| {
+            sent.borrow_mut().push(message.clone());
+            Ok(None)
+        }).unwrap();
+        assert_eq!(vec![MessagesReceived::create().set_message_id_list(vec![String::from("id_1")]).to_a2a_message()], *sent.borrow());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_set_live_delivery() {
+        let _setup = SetupMocks::init();
+
+        let mut pickup = _message_pickup(ProtocolVersion::V1);
+        assert_eq!(VcxErrorKind::ActionNotSupported, pickup.set_live_delivery(true, |_: &A2AMessage| Ok(None)).unwrap_err().kind());
+
+        let mut pickup = _message_pickup(ProtocolVersion::V2);
+        pickup.set_live_delivery(true, |message: &A2AMessage| {
+            assert_eq!(LiveDeliveryChange::create().set_live_delivery(true).to_a2a_message(), *message);
+            Ok(Some(A2AMessage::PickupStatusV2(Status::create().set_live_delivery(true))))
+        }).unwrap();
+        assert!(pickup.live_delivery());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_inbox_keeps_messages_per_recipient() {
+        let _setup = SetupMocks::init();
+        clear_inbox().unwrap();
+
+        let message_1 = PickedUpMessage { id: String::from("inbox_id_1"), payload: _packed_message(vec!["inbox_vk_1"]) };
+        let message_2 = PickedUpMessage { id: String::from("inbox_id_2"), payload: _packed_message(vec!["inbox_vk_1", "inbox_vk_2"]) };
+        let invalid = PickedUpMessage { id: String::from("inbox_id_3"), payload: b"not a packed message".to_vec() };
+
+        assert_eq!(2, store_messages(&ProfileContext::Global, vec![message_1.clone(), message_2.clone(), invalid]).unwrap());
+        assert_eq!(0, store_messages(&ProfileContext::Global, vec![message_1.clone()]).unwrap());
+        assert_eq!(vec![message_1.clone(), message_2.clone()], get_inbox_messages(&ProfileContext::Global, "inbox_vk_1").unwrap());
+        assert_eq!(vec![message_2.clone()], get_inbox_messages(&ProfileContext::Global, "inbox_vk_2").unwrap());
+
+        assert!(remove_inbox_message(&ProfileContext::Global, "inbox_id_2").unwrap());
+        assert!(!remove_inbox_message(&ProfileContext::Global, "inbox_id_2").unwrap());
+        assert!(get_inbox_messages(&ProfileContext::Global, "inbox_vk_2").unwrap().is_empty());
+
+        clear_inbox().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_discard_undeliverable_messages() {
+        let _setup = SetupMocks::init();
+
+        let message_1 = PickedUpMessage { id: String::from("discard_id_1"), payload: _packed_message(vec!["discard_vk_1"]) };
+        let message_2 = PickedUpMessage { id: String::from("discard_id_2"), payload: _packed_message(vec!["discard_vk_2"]) };
+        store_messages(&ProfileContext::Global, vec![message_1.clone(), message_2]).unwrap();
+
+        let discarded = discard_undeliverable_messages(&ProfileContext::Global, |key| key.starts_with("discard_") && key != "discard_vk_2").unwrap();
+        assert_eq!(1, discarded);
+        assert_eq!(vec![message_1], get_inbox_messages(&ProfileContext::Global, "discard_vk_1").unwrap());
+        assert!(get_inbox_messages(&ProfileContext::Global, "discard_vk_2").unwrap().is_empty());
+
+        assert!(acknowledge_inbox_message(&ProfileContext::Global, "discard_id_1").unwrap());
+        assert!(!acknowledge_inbox_message(&ProfileContext::Global, "discard_id_1").unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_pickup_skips_connection_with_mediator() {
+        let _setup = SetupMocks::init();
+
+        let pickup = _message_pickup(ProtocolVersion::V2);
+        assert_eq!(false, pickup.picks_up_for("sender_vk"));
+        assert_eq!(true, pickup.picks_up_for("other_vk"));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_pickup_messages_without_mediator() {
+        let _setup = SetupMocks::init();
+
+        set_pickup_mediator(&ProfileContext::Global, None).unwrap();
+        assert!(get_pickup_mediator(&ProfileContext::Global).unwrap().is_none());
+        assert_eq!(0, pickup_messages(&ProfileContext::Global).unwrap());
+    }
+}
@@ -0,0 +1,2 @@
+pub mod mediation_client;
+pub mod message_pickup;
@@ -21,7 +21,7 @@ pub enum ProverMessages {
 impl From<A2AMessage> for ProverMessages {
     fn from(msg: A2AMessage) -> Self {
         match msg {
-            A2AMessage::Ack(ack) | A2AMessage::PresentationAck(ack) => {
+            A2AMessage::Ack(ack) | A2AMessage::PresentationAck(ack) | A2AMessage::PresentationAckV2(ack) => {
                 ProverMessages::PresentationAckReceived(ack)
             }
             A2AMessage::CommonProblemReport(report) => {
@@ -1,28 +1,48 @@
 use std::collections::HashMap;
+use std::future::Future;
+
+use futures::future;
 
 use crate::aries::handlers::proof_presentation::prover::messages::ProverMessages;
 use crate::aries::handlers::proof_presentation::prover::state_machine::ProverSM;
+use crate::aries::handlers::state_timing::StateTiming;
 use crate::aries::handlers::connection::connection::Connection;
 use crate::aries::messages::a2a::A2AMessage;
 use crate::aries::messages::proof_presentation::presentation::Presentation;
 use crate::aries::messages::proof_presentation::presentation_proposal::PresentationPreview;
 use crate::aries::messages::proof_presentation::presentation_request::PresentationRequest;
 use crate::error::prelude::*;
+use crate::libindy::proofs::prover::credential_selection::{self, CredentialSelection, SelectionPolicy};
 use crate::libindy::utils::anoncreds;
+use crate::profile::ProfileContext;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Prover {
+    #[serde(skip)]
+    profile: ProfileContext,
     prover_sm: ProverSM,
 }
 
 impl Prover {
-    pub fn create(source_id: &str, presentation_request: PresentationRequest) -> VcxResult<Prover> {
+    pub fn create(profile: ProfileContext, source_id: &str, presentation_request: PresentationRequest) -> VcxResult<Prover> {
         trace!("Prover::create >>> source_id: {}, presentation_request: {:?}", source_id, presentation_request);
         Ok(Prover {
+            profile,
             prover_sm: ProverSM::new(presentation_request, source_id.to_string()),
         })
     }
 
+    /**
+    Binds deserialized prover to the profile, the profile is not part of the serialized data.
+     */
+    pub fn set_profile(&mut self, profile: ProfileContext) {
+        self.profile = profile;
+    }
+
+    pub fn profile(&self) -> &ProfileContext {
+        &self.profile
+    }
+
     pub fn state(&self) -> u32 { self.prover_sm.state() }
 
     pub fn presentation_status(&self) -> u32 {
@@ -33,7 +53,34 @@ impl Prover {
     pub fn retrieve_credentials(&self) -> VcxResult<String> {
         trace!("Prover::retrieve_credentials >>>");
         let presentation_request = self.presentation_request_data()?;
-        anoncreds::libindy_prover_get_credentials_for_proof_req(&presentation_request)
+        anoncreds::libindy_prover_get_credentials_for_proof_req(self.profile.wallet_handle()?, &presentation_request)
+    }
+
+    /**
+    Picks a credential for every referent of the presentation request according to the policy.
+     */
+    pub fn select_credentials(&self, policy: &SelectionPolicy) -> VcxResult<CredentialSelection> {
+        trace!("Prover::select_credentials >>> policy: {:?}", policy);
+        let presentation_request = self.presentation_request_data()?;
+        credential_selection::select_credentials_for_proof_req(&self.profile, &presentation_request, policy)
+    }
+
+    /**
+    Generates presentation from credentials selected according to the policy. Fails if some referent
+    can be satisfied neither by a credential nor by the self attested attributes.
+     */
+    pub fn generate_presentation_with_policy(&mut self, policy: &SelectionPolicy, self_attested_attrs: String) -> VcxResult<()> {
+        trace!("Prover::generate_presentation_with_policy >>> policy: {:?}, self_attested_attrs: {:?}", policy, self_attested_attrs);
+        let selection = self.select_credentials(policy)?;
+        let self_attested: serde_json::Value = serde_json::from_str(&self_attested_attrs)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize self attested attributes: {}", err)))?;
+        let unsatisfied: Vec<&String> = selection.unsatisfied.iter()
+            .filter(|referent| self_attested.get(referent.as_str()).is_none())
+            .collect();
+        if !unsatisfied.is_empty() {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidProofCredentialData, format!("No credential satisfies referents: {:?}", unsatisfied)));
+        }
+        self.generate_presentation(selection.credentials_json(), self_attested_attrs)
     }
 
     pub fn generate_presentation(&mut self, credentials: String, self_attested_attrs: String) -> VcxResult<()> {
@@ -71,11 +118,11 @@ impl Prover {
     }
 
     pub fn presentation_request_data(&self) -> VcxResult<String> {
-        self.prover_sm.presentation_request().request_presentations_attach.content()
+        self.prover_sm.presentation_request().attachment_content()
     }
 
     pub fn get_proof_request_attachment(&self) -> VcxResult<String> {
-        let data = self.prover_sm.presentation_request().request_presentations_attach.content()?;
+        let data = self.prover_sm.presentation_request().attachment_content()?;
         let proof_request_data: serde_json::Value = serde_json::from_str(&data)
             .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize {:?} into PresentationRequestData: {:?}", data, err)))?;
         Ok(proof_request_data.to_string())
@@ -83,32 +130,52 @@ impl Prover {
 
     pub fn get_source_id(&self) -> String { self.prover_sm.source_id() }
 
+    pub fn get_thread_id(&self) -> String { self.prover_sm.thread_id() }
+
+    pub fn get_state_timing(&self) -> StateTiming {
+        self.prover_sm.get_state_timing()
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.prover_sm.is_expired()
+    }
+
+    pub fn abandon(&mut self, reason: Option<String>, send_message: Option<&impl Fn(&A2AMessage) -> VcxResult<()>>) -> VcxResult<()> {
+        self.prover_sm = self.prover_sm.clone().abandon(reason, send_message)?;
+        Ok(())
+    }
+
     pub fn step(&mut self,
                 message: ProverMessages,
                 send_message: Option<&impl Fn(&A2AMessage) -> VcxResult<()>>)
                 -> VcxResult<()>
     {
-        self.prover_sm = self.prover_sm.clone().step(message, send_message)?;
+        self.prover_sm = self.prover_sm.clone().step(&self.profile, message, send_message)?;
         Ok(())
     }
 
     pub fn decline_presentation_request(&mut self, send_message: &impl Fn(&A2AMessage) -> VcxResult<()>, reason: Option<String>, proposal: Option<String>) -> VcxResult<()> {
         trace!("Prover::decline_presentation_request >>> reason: {:?}, proposal: {:?}", reason, proposal);
+        let message = Self::_decline_message(reason, proposal)?;
+        self.step(message, Some(send_message))
+    }
+
+    fn _decline_message(reason: Option<String>, proposal: Option<String>) -> VcxResult<ProverMessages> {
         match (reason, proposal) {
             (Some(reason), None) => {
-                self.step(ProverMessages::RejectPresentationRequest(reason), Some(send_message))
+                Ok(ProverMessages::RejectPresentationRequest(reason))
             }
             (None, Some(proposal)) => {
                 let presentation_preview: PresentationPreview = serde_json::from_str(&proposal)
                     .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot serialize Presentation Preview: {:?}", err)))?;
 
-                self.step(ProverMessages::ProposePresentation(presentation_preview), Some(send_message))
+                Ok(ProverMessages::ProposePresentation(presentation_preview))
             }
             (None, None) => {
-                return Err(VcxError::from_msg(VcxErrorKind::InvalidOption, "Either `reason` or `proposal` parameter must be specified."));
+                Err(VcxError::from_msg(VcxErrorKind::InvalidOption, "Either `reason` or `proposal` parameter must be specified."))
             }
             (Some(_), Some(_)) => {
-                return Err(VcxError::from_msg(VcxErrorKind::InvalidOption, "Only one of `reason` or `proposal` parameters must be specified."));
+                Err(VcxError::from_msg(VcxErrorKind::InvalidOption, "Only one of `reason` or `proposal` parameters must be specified."))
             }
         }
     }
@@ -127,6 +194,52 @@ impl Prover {
     }
 }
 
+impl Prover {
+    pub async fn retrieve_credentials_async(&self) -> VcxResult<String> {
+        trace!("Prover::retrieve_credentials_async >>>");
+        let presentation_request = self.presentation_request_data()?;
+        anoncreds::libindy_prover_get_credentials_for_proof_req_async(self.profile.wallet_handle()?, &presentation_request).await
+    }
+
+    pub async fn generate_presentation_async(&mut self, credentials: String, self_attested_attrs: String) -> VcxResult<()> {
+        trace!("Prover::generate_presentation_async >>> credentials: {}, self_attested_attrs: {:?}", credentials, self_attested_attrs);
+        self.step_async(ProverMessages::PreparePresentation((credentials, self_attested_attrs)), None::<&fn(A2AMessage) -> future::Ready<VcxResult<()>>>).await
+    }
+
+    pub async fn send_presentation_async(&mut self, connection: &Connection) -> VcxResult<()> {
+        trace!("Prover::send_presentation_async >>>");
+        let send_message = connection.send_message_closure_async()?;
+        self.step_async(ProverMessages::SendPresentation, Some(&send_message)).await
+    }
+
+    pub async fn decline_presentation_request_async(&mut self, connection: &Connection, reason: Option<String>, proposal: Option<String>) -> VcxResult<()> {
+        trace!("Prover::decline_presentation_request_async >>> reason: {:?}, proposal: {:?}", reason, proposal);
+        let message = Self::_decline_message(reason, proposal)?;
+        let send_message = connection.send_message_closure_async()?;
+        self.step_async(message, Some(&send_message)).await
+    }
+
+    pub async fn step_async<F, Fut>(&mut self, message: ProverMessages, send_message: Option<&F>) -> VcxResult<()>
+        where F: Fn(A2AMessage) -> Fut,
+              Fut: Future<Output=VcxResult<()>> {
+        self.prover_sm = self.prover_sm.clone().step_async(&self.profile, message, send_message).await?;
+        Ok(())
+    }
+
+    pub async fn update_state_async(&mut self, connection: &Connection) -> VcxResult<u32> {
+        trace!("Prover::update_state_async >>> ");
+        if !self.has_transitions() { return Ok(self.state()); }
+        let send_message = connection.send_message_closure_async()?;
+
+        let messages = connection.get_messages_async().await?;
+        if let Some((uid, msg)) = self.find_message_to_handle(messages) {
+            self.step_async(msg.into(), Some(&send_message)).await?;
+            connection.update_message_status_async(uid).await?;
+        }
+        Ok(self.state())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{libindy, utils};
@@ -147,7 +260,7 @@ mod tests {
 
         let pres_req_data: PresentationRequestData = serde_json::from_str(&req).unwrap();
         let proof_req = PresentationRequest::create().set_request_presentations_attach(&pres_req_data).unwrap();
-        let proof: Prover = Prover::create("1", proof_req).unwrap();
+        let proof: Prover = Prover::create(ProfileContext::Global, "1", proof_req).unwrap();
 
         let retrieved_creds = proof.retrieve_credentials().unwrap();
         assert!(retrieved_creds.len() > 500);
@@ -168,7 +281,7 @@ mod tests {
 
         let pres_req_data: PresentationRequestData = serde_json::from_str(&req.to_string()).unwrap();
         let proof_req = PresentationRequest::create().set_request_presentations_attach(&pres_req_data).unwrap();
-        let proof: Prover = Prover::create("1", proof_req).unwrap();
+        let proof: Prover = Prover::create(ProfileContext::Global, "1", proof_req).unwrap();
 
         let retrieved_creds = proof.retrieve_credentials().unwrap();
         assert_eq!(retrieved_creds, "{}".to_string());
@@ -176,7 +289,7 @@ mod tests {
         req["requested_attributes"]["address1_1"] = json!({"name": "address1"});
         let pres_req_data: PresentationRequestData = serde_json::from_str(&req.to_string()).unwrap();
         let proof_req = PresentationRequest::create().set_request_presentations_attach(&pres_req_data).unwrap();
-        let proof: Prover = Prover::create("2", proof_req).unwrap();
+        let proof: Prover = Prover::create(ProfileContext::Global, "2", proof_req).unwrap();
 
         let retrieved_creds = proof.retrieve_credentials().unwrap();
         assert_eq!(retrieved_creds, json!({"attrs":{"address1_1":[]}}).to_string());
@@ -204,7 +317,7 @@ mod tests {
 
         let pres_req_data: PresentationRequestData = serde_json::from_str(&req.to_string()).unwrap();
         let proof_req = PresentationRequest::create().set_request_presentations_attach(&pres_req_data).unwrap();
-        let proof: Prover = Prover::create("1", proof_req).unwrap();
+        let proof: Prover = Prover::create(ProfileContext::Global, "1", proof_req).unwrap();
 
         // All lower case
         let retrieved_creds = proof.retrieve_credentials().unwrap();
@@ -216,7 +329,7 @@ mod tests {
         req["requested_attributes"]["zip_1"]["name"] = json!("Zip");
         let pres_req_data: PresentationRequestData = serde_json::from_str(&req.to_string()).unwrap();
         let proof_req = PresentationRequest::create().set_request_presentations_attach(&pres_req_data).unwrap();
-        let proof: Prover = Prover::create("2", proof_req).unwrap();
+        let proof: Prover = Prover::create(ProfileContext::Global, "2", proof_req).unwrap();
         let retrieved_creds2 = proof.retrieve_credentials().unwrap();
         assert!(retrieved_creds2.contains(r#""zip":"84000""#));
 
@@ -224,7 +337,7 @@ mod tests {
         req["requested_attributes"]["zip_1"]["name"] = json!("ZIP");
         let pres_req_data: PresentationRequestData = serde_json::from_str(&req.to_string()).unwrap();
         let proof_req = PresentationRequest::create().set_request_presentations_attach(&pres_req_data).unwrap();
-        let proof: Prover = Prover::create("1", proof_req).unwrap();
+        let proof: Prover = Prover::create(ProfileContext::Global, "1", proof_req).unwrap();
         let retrieved_creds3 = proof.retrieve_credentials().unwrap();
         assert!(retrieved_creds3.contains(r#""zip":"84000""#));
     }
@@ -235,7 +348,7 @@ mod tests {
         let _setup = SetupLibraryWallet::init();
 
         let proof_req = PresentationRequest::create();
-        let proof = Prover::create("1", proof_req).unwrap();
+        let proof = Prover::create(ProfileContext::Global, "1", proof_req).unwrap();
         assert_eq!(proof.retrieve_credentials().unwrap_err().kind(), VcxErrorKind::InvalidJson);
     }
 
@@ -268,7 +381,7 @@ mod tests {
 
         let pres_req_data: PresentationRequestData = serde_json::from_str(&indy_proof_req).unwrap();
         let proof_req = PresentationRequest::create().set_request_presentations_attach(&pres_req_data).unwrap();
-        let mut proof: Prover = Prover::create("1", proof_req).unwrap();
+        let mut proof: Prover = Prover::create(ProfileContext::Global, "1", proof_req).unwrap();
 
         let all_creds: serde_json::Value = serde_json::from_str(&proof.retrieve_credentials().unwrap()).unwrap();
         let selected_credentials: serde_json::Value = json!({
@@ -315,7 +428,7 @@ mod tests {
 
         let pres_req_data: PresentationRequestData = serde_json::from_str(&indy_proof_req).unwrap();
         let proof_req = PresentationRequest::create().set_request_presentations_attach(&pres_req_data).unwrap();
-        let mut proof: Prover = Prover::create("1", proof_req).unwrap();
+        let mut proof: Prover = Prover::create(ProfileContext::Global, "1", proof_req).unwrap();
 
         let selected_credentials: serde_json::Value = json!({});
         let self_attested: serde_json::Value = json!({
@@ -357,7 +470,7 @@ mod tests {
 
         let pres_req_data: PresentationRequestData = serde_json::from_str(&indy_proof_req).unwrap();
         let proof_req = PresentationRequest::create().set_request_presentations_attach(&pres_req_data).unwrap();
-        let mut proof: Prover = Prover::create("1", proof_req).unwrap();
+        let mut proof: Prover = Prover::create(ProfileContext::Global, "1", proof_req).unwrap();
 
         let all_creds: serde_json::Value = serde_json::from_str(&proof.retrieve_credentials().unwrap()).unwrap();
         let selected_credentials: serde_json::Value = json!({
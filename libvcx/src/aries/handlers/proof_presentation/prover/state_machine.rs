@@ -1,4 +1,6 @@
 use std::collections::HashMap;
+use std::future::Future;
+use std::mem;
 
 use crate::api_lib::VcxStateType;
 use crate::aries::handlers::proof_presentation::prover::messages::ProverMessages;
@@ -7,13 +9,20 @@ use crate::aries::handlers::proof_presentation::prover::states::initial::Initial
 use crate::aries::handlers::proof_presentation::prover::states::presentation_prepared::PresentationPreparedState;
 use crate::aries::handlers::proof_presentation::prover::states::presentation_prepared_failed::PresentationPreparationFailedState;
 use crate::aries::handlers::proof_presentation::prover::states::presentation_sent::PresentationSentState;
+use crate::aries::handlers::state_timing::{self, StateTiming};
 use crate::aries::messages::a2a::A2AMessage;
+use crate::aries::messages::a2a::protocol_version::ProtocolVersion;
 use crate::aries::messages::error::ProblemReport;
 use crate::aries::messages::proof_presentation::presentation::Presentation;
 use crate::aries::messages::proof_presentation::presentation_proposal::{PresentationPreview, PresentationProposal};
 use crate::aries::messages::proof_presentation::presentation_request::PresentationRequest;
 use crate::aries::messages::status::Status;
+use crate::aries::messages::timing;
+use crate::aries::utils::sync_sender;
+use crate::aries::utils::task::block_on;
 use crate::error::prelude::*;
+use crate::profile::ProfileContext;
+use crate::settings;
 
 /// A state machine that tracks the evolution of states for a Prover during
 /// the Present Proof protocol.
@@ -22,11 +31,18 @@ pub struct ProverSM {
     source_id: String,
     thread_id: String,
     state: ProverState,
+    #[serde(default)]
+    timing: StateTiming,
 }
 
 impl ProverSM {
     pub fn new(presentation_request: PresentationRequest, source_id: String) -> ProverSM {
-        ProverSM { source_id, thread_id: presentation_request.id.0.clone(), state: ProverState::Initiated(InitialState { presentation_request }) }
+        ProverSM {
+            source_id,
+            thread_id: presentation_request.thread_id(),
+            timing: StateTiming::entered(presentation_request.expires_time()),
+            state: ProverState::Initiated(InitialState { presentation_request }),
+        }
     }
 }
 
@@ -67,7 +83,7 @@ impl ProverSM {
                 }
                 ProverState::PresentationSent(_) => {
                     match message {
-                        A2AMessage::Ack(ack) | A2AMessage::PresentationAck(ack) => {
+                        A2AMessage::Ack(ack) | A2AMessage::PresentationAck(ack) | A2AMessage::PresentationAckV2(ack) => {
                             if ack.from_thread(&self.thread_id) {
                                 return Some((uid, A2AMessage::PresentationAck(ack)));
                             }
@@ -90,27 +106,42 @@ impl ProverSM {
     }
 
     pub fn step(self,
+                profile: &ProfileContext,
                 message: ProverMessages,
                 send_message: Option<&impl Fn(&A2AMessage) -> VcxResult<()>>,
     ) -> VcxResult<ProverSM> {
-        trace!("ProverSM::step >>> message: {:?}", message);
+        block_on(self.step_async(profile, message, send_message.map(sync_sender).as_ref()))
+    }
+
+    pub async fn step_async<F, Fut>(self,
+                                    profile: &ProfileContext,
+                                    message: ProverMessages,
+                                    send_message: Option<&F>,
+    ) -> VcxResult<ProverSM>
+        where F: Fn(A2AMessage) -> Fut,
+              Fut: Future<Output=VcxResult<()>> {
+        trace!("ProverSM::step_async >>> message: {:?}", message);
 
-        let ProverSM { source_id, state, thread_id } = self;
+        let ProverSM { source_id, state, thread_id, timing } = self;
+        let previous_state = mem::discriminant(&state);
 
         let state = match state {
             ProverState::Initiated(state) => {
                 match message {
                     ProverMessages::SetPresentation(presentation) => {
-                        let presentation = presentation.set_thread_id(&thread_id);
+                        let presentation = presentation
+                            .set_thread_id(&thread_id)
+                            .set_protocol_version(state.presentation_request.protocol_version());
                         ProverState::PresentationPrepared((state, presentation).into())
                     }
                     ProverMessages::PreparePresentation((credentials, self_attested_attrs)) => {
-                        match state.build_presentation(&credentials, &self_attested_attrs) {
+                        match state.build_presentation(profile, &credentials, &self_attested_attrs).await {
                             Ok(presentation) => {
                                 let presentation = Presentation::create()
                                     .ask_for_ack()
                                     .set_thread_id(&thread_id)
-                                    .set_presentations_attach(presentation)?;
+                                    .set_presentations_attach(presentation)?
+                                    .set_protocol_version(state.presentation_request.protocol_version());
 
                                 ProverState::PresentationPrepared((state, presentation).into())
                             }
@@ -126,7 +157,7 @@ impl ProverSM {
                     }
                     ProverMessages::RejectPresentationRequest(reason) => {
                         if let Some(send_message) = send_message {
-                            Self::_handle_reject_presentation_request(send_message, &reason, &thread_id)?;
+                            Self::_handle_reject_presentation_request(send_message, &reason, &thread_id).await?;
                             ProverState::Finished(state.into())
                         } else {
                             return Err(VcxError::from_msg(VcxErrorKind::ActionNotSupported, "Send message closure is required."));
@@ -134,7 +165,7 @@ impl ProverSM {
                     }
                     ProverMessages::ProposePresentation(preview) => {
                         if let Some(send_message) = send_message {
-                            Self::_handle_presentation_proposal(send_message, preview, &thread_id)?;
+                            Self::_handle_presentation_proposal(send_message, preview, &thread_id, state.presentation_request.protocol_version()).await?;
                             ProverState::Finished(state.into())
                         } else {
                             return Err(VcxError::from_msg(VcxErrorKind::ActionNotSupported, "Send message closure is required."));
@@ -149,7 +180,7 @@ impl ProverSM {
                 match message {
                     ProverMessages::SendPresentation => {
                         if let Some(send_message) = send_message {
-                            send_message(&state.presentation.to_a2a_message())?;
+                            send_message(state.presentation.to_a2a_message()).await?;
                             ProverState::PresentationSent((state).into())
                         } else {
                             return Err(VcxError::from_msg(VcxErrorKind::ActionNotSupported, "Send message closure is required."));
@@ -157,7 +188,7 @@ impl ProverSM {
                     }
                     ProverMessages::RejectPresentationRequest(reason) => {
                         if let Some(send_message) = send_message {
-                            Self::_handle_reject_presentation_request(send_message, &reason, &thread_id)?;
+                            Self::_handle_reject_presentation_request(send_message, &reason, &thread_id).await?;
                             ProverState::Finished(state.into())
                         } else {
                             return Err(VcxError::from_msg(VcxErrorKind::ActionNotSupported, "Send message closure is required."));
@@ -165,7 +196,7 @@ impl ProverSM {
                     }
                     ProverMessages::ProposePresentation(preview) => {
                         if let Some(send_message) = send_message {
-                            Self::_handle_presentation_proposal(send_message, preview, &thread_id)?;
+                            Self::_handle_presentation_proposal(send_message, preview, &thread_id, state.presentation_request.protocol_version()).await?;
                             ProverState::Finished(state.into())
                         } else {
                             return Err(VcxError::from_msg(VcxErrorKind::ActionNotSupported, "Send message closure is required."));
@@ -180,7 +211,7 @@ impl ProverSM {
                 match message {
                     ProverMessages::SendPresentation => {
                         if let Some(send_message) = send_message {
-                            send_message(&state.problem_report.to_a2a_message())?;
+                            send_message(state.problem_report.to_a2a_message()).await?;
                             ProverState::Finished((state).into())
                         } else {
                             return Err(VcxError::from_msg(VcxErrorKind::ActionNotSupported, "Send message closure is required."));
@@ -210,35 +241,94 @@ impl ProverSM {
             ProverState::Finished(state) => ProverState::Finished(state)
         };
 
-        Ok(ProverSM { source_id, state, thread_id })
+        let timing = if mem::discriminant(&state) == previous_state {
+            timing
+        } else {
+            match state {
+                ProverState::PresentationPrepared(ref state) => StateTiming::entered(state.presentation_request.expires_time()),
+                ProverState::PresentationPreparationFailed(ref state) => StateTiming::entered(state.presentation_request.expires_time()),
+                ProverState::PresentationSent(_) => StateTiming::entered(timing::expires_time(settings::get_expiry(settings::CONFIG_PRESENT_PROOF_EXPIRY))),
+                _ => StateTiming::entered(None)
+            }
+        };
+        Ok(ProverSM { source_id, state, thread_id, timing })
     }
 
-    fn _handle_reject_presentation_request(
-        send_message: &impl Fn(&A2AMessage) -> VcxResult<()>,
+    /**
+    Finishes the presentation with failure, the verifier is notified by a problem report if `send_message` is set.
+     */
+    pub fn abandon(self, reason: Option<String>, send_message: Option<&impl Fn(&A2AMessage) -> VcxResult<()>>) -> VcxResult<ProverSM> {
+        trace!("ProverSM::abandon >>> reason: {:?}, state: {:?}", reason, self.state);
+
+        if let ProverState::Finished(_) = self.state {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidState, "Presentation is already finished"));
+        }
+        let problem_report = state_timing::expiration_problem_report(&self.thread_id, reason);
+        if let Some(send_message) = send_message {
+            send_message(&problem_report.to_a2a_message())?;
+        }
+
+        let ProverSM { source_id, state, thread_id, .. } = self;
+        let state = match state {
+            ProverState::Initiated(state) => ProverState::Finished((state, problem_report).into()),
+            ProverState::PresentationPrepared(state) => ProverState::Finished((state, problem_report).into()),
+            ProverState::PresentationPreparationFailed(state) => ProverState::Finished((state, problem_report).into()),
+            ProverState::PresentationSent(state) => ProverState::Finished((state, problem_report).into()),
+            ProverState::Finished(state) => ProverState::Finished(state)
+        };
+        Ok(ProverSM { source_id, state, thread_id, timing: StateTiming::entered(None) })
+    }
+
+    async fn _handle_reject_presentation_request<F, Fut>(
+        send_message: &F,
         reason: &str,
         thread_id: &str,
-    ) -> VcxResult<()> {
+    ) -> VcxResult<()>
+        where F: Fn(A2AMessage) -> Fut,
+              Fut: Future<Output=VcxResult<()>> {
         let problem_report = ProblemReport::create()
             .set_comment(reason.to_string())
             .set_thread_id(thread_id);
 
-        send_message(&problem_report.to_a2a_message())
+        send_message(problem_report.to_a2a_message()).await
     }
 
-    fn _handle_presentation_proposal(
-        send_message: &impl Fn(&A2AMessage) -> VcxResult<()>,
+    async fn _handle_presentation_proposal<F, Fut>(
+        send_message: &F,
         preview: PresentationPreview,
         thread_id: &str,
-    ) -> VcxResult<()> {
+        protocol_version: ProtocolVersion,
+    ) -> VcxResult<()>
+        where F: Fn(A2AMessage) -> Fut,
+              Fut: Future<Output=VcxResult<()>> {
         let proposal = PresentationProposal::create()
             .set_presentation_preview(preview)
             .set_thread_id(thread_id);
+        let proposal = match protocol_version {
+            ProtocolVersion::V1 => proposal,
+            ProtocolVersion::V2 => {
+                let proof_request_data = proposal.presentation_proposal.to_proof_request_data()?;
+                proposal.set_proposals_attach(&proof_request_data)?
+                    .set_protocol_version(protocol_version)
+            }
+        };
 
-        send_message(&proposal.to_a2a_message())
+        send_message(proposal.to_a2a_message()).await
     }
 
     pub fn source_id(&self) -> String { self.source_id.clone() }
 
+    pub fn thread_id(&self) -> String { self.thread_id.clone() }
+
+    pub fn get_state_timing(&self) -> StateTiming { self.timing.clone() }
+
+    pub fn is_expired(&self) -> bool {
+        match self.state {
+            ProverState::Finished(_) => false,
+            _ => self.timing.is_expired()
+        }
+    }
+
     pub fn state(&self) -> u32 {
         match self.state {
             ProverState::Initiated(_) => VcxStateType::VcxStateRequestReceived as u32,
@@ -310,22 +400,22 @@ pub mod test {
 
     impl ProverSM {
         fn to_presentation_prepared_state(mut self) -> ProverSM {
-            self = self.step(ProverMessages::PreparePresentation((_credentials(), _self_attested())), None::<&fn(&A2AMessage) -> _>).unwrap();
+            self = self.step(&ProfileContext::Global, ProverMessages::PreparePresentation((_credentials(), _self_attested())), None::<&fn(&A2AMessage) -> _>).unwrap();
             self
         }
 
         fn to_presentation_sent_state(mut self) -> ProverSM {
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
-            self = self.step(ProverMessages::PreparePresentation((_credentials(), _self_attested())), send_message).unwrap();
-            self = self.step(ProverMessages::SendPresentation, send_message).unwrap();
+            self = self.step(&ProfileContext::Global, ProverMessages::PreparePresentation((_credentials(), _self_attested())), send_message).unwrap();
+            self = self.step(&ProfileContext::Global, ProverMessages::SendPresentation, send_message).unwrap();
             self
         }
 
         fn to_finished_state(mut self) -> ProverSM {
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
-            self = self.step(ProverMessages::PreparePresentation((_credentials(), _self_attested())), None::<&fn(&A2AMessage) -> _>).unwrap();
-            self = self.step(ProverMessages::SendPresentation, send_message).unwrap();
-            self = self.step(ProverMessages::PresentationAckReceived(_ack()), send_message).unwrap();
+            self = self.step(&ProfileContext::Global, ProverMessages::PreparePresentation((_credentials(), _self_attested())), None::<&fn(&A2AMessage) -> _>).unwrap();
+            self = self.step(&ProfileContext::Global, ProverMessages::SendPresentation, send_message).unwrap();
+            self = self.step(&ProfileContext::Global, ProverMessages::PresentationAckReceived(_ack()), send_message).unwrap();
             self
         }
     }
@@ -388,7 +478,7 @@ pub mod test {
 
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
             let mut prover_sm = _prover_sm();
-            prover_sm = prover_sm.step(ProverMessages::PreparePresentation((_credentials(), _self_attested())), send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::PreparePresentation((_credentials(), _self_attested())), send_message).unwrap();
 
             assert_match!(ProverState::PresentationPrepared(_), prover_sm.state);
         }
@@ -402,7 +492,7 @@ pub mod test {
 
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
             let mut prover_sm = _prover_sm();
-            prover_sm = prover_sm.step(ProverMessages::PreparePresentation(("invalid".to_string(), _self_attested())), send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::PreparePresentation(("invalid".to_string(), _self_attested())), send_message).unwrap();
 
             assert_match!(ProverState::PresentationPreparationFailed(_), prover_sm.state);
         }
@@ -414,7 +504,7 @@ pub mod test {
 
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
             let mut prover_sm = _prover_sm();
-            prover_sm = prover_sm.step(ProverMessages::RejectPresentationRequest(String::from("reject request")), send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::RejectPresentationRequest(String::from("reject request")), send_message).unwrap();
 
             assert_match!(ProverState::Finished(_), prover_sm.state);
         }
@@ -426,7 +516,7 @@ pub mod test {
 
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
             let mut prover_sm = _prover_sm();
-            prover_sm = prover_sm.step(ProverMessages::ProposePresentation((_presentation_preview())), send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::ProposePresentation((_presentation_preview())), send_message).unwrap();
 
             assert_match!(ProverState::Finished(_), prover_sm.state);
         }
@@ -439,10 +529,10 @@ pub mod test {
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
             let mut prover_sm = _prover_sm();
 
-            prover_sm = prover_sm.step(ProverMessages::SendPresentation, send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::SendPresentation, send_message).unwrap();
             assert_match!(ProverState::Initiated(_), prover_sm.state);
 
-            prover_sm = prover_sm.step(ProverMessages::PresentationAckReceived(_ack()), send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::PresentationAckReceived(_ack()), send_message).unwrap();
             assert_match!(ProverState::Initiated(_), prover_sm.state);
         }
 
@@ -454,8 +544,8 @@ pub mod test {
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
             let mut prover_sm = _prover_sm();
 
-            prover_sm = prover_sm.step(ProverMessages::PreparePresentation((_credentials(), _self_attested())), send_message).unwrap();
-            prover_sm = prover_sm.step(ProverMessages::SendPresentation, send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::PreparePresentation((_credentials(), _self_attested())), send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::SendPresentation, send_message).unwrap();
 
             assert_match!(ProverState::PresentationSent(_), prover_sm.state);
         }
@@ -468,10 +558,10 @@ pub mod test {
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
             let mut prover_sm = _prover_sm().to_presentation_prepared_state();
 
-            prover_sm = prover_sm.step(ProverMessages::PresentationRejectReceived(_problem_report()), send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::PresentationRejectReceived(_problem_report()), send_message).unwrap();
             assert_match!(ProverState::PresentationPrepared(_), prover_sm.state);
 
-            prover_sm = prover_sm.step(ProverMessages::PresentationAckReceived(_ack()), send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::PresentationAckReceived(_ack()), send_message).unwrap();
             assert_match!(ProverState::PresentationPrepared(_), prover_sm.state);
         }
 
@@ -483,7 +573,7 @@ pub mod test {
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
             let mut prover_sm = _prover_sm().to_presentation_prepared_state();
 
-            prover_sm = prover_sm.step(ProverMessages::RejectPresentationRequest(String::from("reject request")), send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::RejectPresentationRequest(String::from("reject request")), send_message).unwrap();
 
             assert_match!(ProverState::Finished(_), prover_sm.state);
         }
@@ -495,7 +585,7 @@ pub mod test {
 
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
             let mut prover_sm = _prover_sm().to_presentation_prepared_state();
-            prover_sm = prover_sm.step(ProverMessages::ProposePresentation(_presentation_preview()), send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::ProposePresentation(_presentation_preview()), send_message).unwrap();
 
             assert_match!(ProverState::Finished(_), prover_sm.state);
         }
@@ -509,10 +599,10 @@ pub mod test {
 
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
             let mut prover_sm = _prover_sm();
-            prover_sm = prover_sm.step(ProverMessages::PreparePresentation(("invalid".to_string(), _self_attested())), send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::PreparePresentation(("invalid".to_string(), _self_attested())), send_message).unwrap();
             assert_match!(ProverState::PresentationPreparationFailed(_), prover_sm.state);
 
-            prover_sm = prover_sm.step(ProverMessages::SendPresentation, send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::SendPresentation, send_message).unwrap();
             assert_match!(ProverState::Finished(_), prover_sm.state);
             assert_eq!(Status::Failed(ProblemReport::default()).code(), prover_sm.presentation_status());
         }
@@ -526,12 +616,12 @@ pub mod test {
 
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
             let mut prover_sm = _prover_sm();
-            prover_sm = prover_sm.step(ProverMessages::PreparePresentation(("invalid".to_string(), _self_attested())), send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::PreparePresentation(("invalid".to_string(), _self_attested())), send_message).unwrap();
 
-            prover_sm = prover_sm.step(ProverMessages::PresentationRejectReceived(_problem_report()), send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::PresentationRejectReceived(_problem_report()), send_message).unwrap();
             assert_match!(ProverState::PresentationPreparationFailed(_), prover_sm.state);
 
-            prover_sm = prover_sm.step(ProverMessages::PresentationAckReceived(_ack()), send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::PresentationAckReceived(_ack()), send_message).unwrap();
             assert_match!(ProverState::PresentationPreparationFailed(_), prover_sm.state);
         }
 
@@ -542,9 +632,9 @@ pub mod test {
 
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
             let mut prover_sm = _prover_sm();
-            prover_sm = prover_sm.step(ProverMessages::PreparePresentation((_credentials(), _self_attested())), send_message).unwrap();
-            prover_sm = prover_sm.step(ProverMessages::SendPresentation, send_message).unwrap();
-            prover_sm = prover_sm.step(ProverMessages::PresentationAckReceived(_ack()), send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::PreparePresentation((_credentials(), _self_attested())), send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::SendPresentation, send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::PresentationAckReceived(_ack()), send_message).unwrap();
 
             assert_match!(ProverState::Finished(_), prover_sm.state);
             assert_eq!(Status::Success.code(), prover_sm.presentation_status());
@@ -557,7 +647,7 @@ pub mod test {
 
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
             let prover_sm = _prover_sm().to_presentation_sent_state();
-            let err = prover_sm.step(ProverMessages::RejectPresentationRequest(String::from("reject")), send_message).unwrap_err();
+            let err = prover_sm.step(&ProfileContext::Global, ProverMessages::RejectPresentationRequest(String::from("reject")), send_message).unwrap_err();
             assert_eq!(VcxErrorKind::ActionNotSupported, err.kind());
         }
 
@@ -568,9 +658,9 @@ pub mod test {
 
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
             let mut prover_sm = _prover_sm();
-            prover_sm = prover_sm.step(ProverMessages::PreparePresentation((_credentials(), _self_attested())), send_message).unwrap();
-            prover_sm = prover_sm.step(ProverMessages::SendPresentation, send_message).unwrap();
-            prover_sm = prover_sm.step(ProverMessages::PresentationRejectReceived(_problem_report()), send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::PreparePresentation((_credentials(), _self_attested())), send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::SendPresentation, send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::PresentationRejectReceived(_problem_report()), send_message).unwrap();
 
             assert_match!(ProverState::Finished(_), prover_sm.state);
             assert_eq!(Status::Failed(ProblemReport::create()).code(), prover_sm.presentation_status());
@@ -583,13 +673,13 @@ pub mod test {
 
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
             let mut prover_sm = _prover_sm();
-            prover_sm = prover_sm.step(ProverMessages::PreparePresentation((_credentials(), _self_attested())), send_message).unwrap();
-            prover_sm = prover_sm.step(ProverMessages::SendPresentation, send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::PreparePresentation((_credentials(), _self_attested())), send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::SendPresentation, send_message).unwrap();
 
-            prover_sm = prover_sm.step(ProverMessages::PreparePresentation((_credentials(), _self_attested())), send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::PreparePresentation((_credentials(), _self_attested())), send_message).unwrap();
             assert_match!(ProverState::PresentationSent(_), prover_sm.state);
 
-            prover_sm = prover_sm.step(ProverMessages::SendPresentation, send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::SendPresentation, send_message).unwrap();
             assert_match!(ProverState::PresentationSent(_), prover_sm.state);
         }
 
@@ -600,14 +690,14 @@ pub mod test {
 
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
             let mut prover_sm = _prover_sm();
-            prover_sm = prover_sm.step(ProverMessages::PreparePresentation((_credentials(), _self_attested())), send_message).unwrap();
-            prover_sm = prover_sm.step(ProverMessages::SendPresentation, send_message).unwrap();
-            prover_sm = prover_sm.step(ProverMessages::PresentationAckReceived(_ack()), send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::PreparePresentation((_credentials(), _self_attested())), send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::SendPresentation, send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::PresentationAckReceived(_ack()), send_message).unwrap();
 
-            prover_sm = prover_sm.step(ProverMessages::PresentationAckReceived(_ack()), send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::PresentationAckReceived(_ack()), send_message).unwrap();
             assert_match!(ProverState::Finished(_), prover_sm.state);
 
-            prover_sm = prover_sm.step(ProverMessages::PresentationRejectReceived(_problem_report()), send_message).unwrap();
+            prover_sm = prover_sm.step(&ProfileContext::Global, ProverMessages::PresentationRejectReceived(_problem_report()), send_message).unwrap();
             assert_match!(ProverState::Finished(_), prover_sm.state);
         }
     }
@@ -1,10 +1,13 @@
 use crate::error::prelude::*;
+use crate::aries::handlers::proof_presentation::prover::states::finished::FinishedState;
 use crate::aries::handlers::proof_presentation::prover::states::presentation_prepared::PresentationPreparedState;
 use crate::aries::handlers::proof_presentation::prover::states::presentation_prepared_failed::PresentationPreparationFailedState;
 use crate::aries::messages::error::ProblemReport;
 use crate::aries::messages::proof_presentation::presentation::Presentation;
 use crate::aries::messages::proof_presentation::presentation_request::PresentationRequest;
-use crate::libindy::proofs::prover::prover::generate_indy_proof;
+use crate::aries::messages::status::Status;
+use crate::libindy::proofs::prover::prover::generate_indy_proof_async;
+use crate::profile::ProfileContext;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct InitialState {
@@ -13,10 +16,11 @@ pub struct InitialState {
 
 
 impl InitialState {
-    pub fn build_presentation(&self, credentials: &str, self_attested_attrs: &str) -> VcxResult<String> {
-        generate_indy_proof(credentials,
-                                            self_attested_attrs,
-                                            &self.presentation_request.request_presentations_attach.content()?)
+    pub async fn build_presentation(&self, profile: &ProfileContext, credentials: &str, self_attested_attrs: &str) -> VcxResult<String> {
+        generate_indy_proof_async(profile,
+                                  credentials,
+                                  self_attested_attrs,
+                                  &self.presentation_request.attachment_content()?).await
     }
 }
 
@@ -39,3 +43,14 @@ impl From<(InitialState, Presentation)> for PresentationPreparedState {
         }
     }
 }
+
+impl From<(InitialState, ProblemReport)> for FinishedState {
+    fn from((state, problem_report): (InitialState, ProblemReport)) -> Self {
+        trace!("transit state from InitialState to FinishedState");
+        FinishedState {
+            presentation_request: state.presentation_request,
+            presentation: Default::default(),
+            status: Status::Failed(problem_report),
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::aries::handlers::proof_presentation::prover::states::finished::FinishedState;
 use crate::aries::handlers::proof_presentation::prover::states::presentation_sent::PresentationSentState;
+use crate::aries::messages::error::ProblemReport;
 use crate::aries::messages::proof_presentation::presentation::Presentation;
 use crate::aries::messages::proof_presentation::presentation_request::PresentationRequest;
 use crate::aries::messages::status::Status;
@@ -30,3 +31,14 @@ impl From<PresentationPreparedState> for FinishedState {
         }
     }
 }
+
+impl From<(PresentationPreparedState, ProblemReport)> for FinishedState {
+    fn from((state, problem_report): (PresentationPreparedState, ProblemReport)) -> Self {
+        trace!("transit state from PresentationPreparedState to FinishedState");
+        FinishedState {
+            presentation_request: state.presentation_request,
+            presentation: Default::default(),
+            status: Status::Failed(problem_report),
+        }
+    }
+}
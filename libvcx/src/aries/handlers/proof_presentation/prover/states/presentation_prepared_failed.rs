@@ -20,3 +20,14 @@ impl From<PresentationPreparationFailedState> for FinishedState {
         }
     }
 }
+
+impl From<(PresentationPreparationFailedState, ProblemReport)> for FinishedState {
+    fn from((state, problem_report): (PresentationPreparationFailedState, ProblemReport)) -> Self {
+        trace!("transit state from PresentationPreparationFailedState to FinishedState");
+        FinishedState {
+            presentation_request: state.presentation_request,
+            presentation: Default::default(),
+            status: Status::Failed(problem_report),
+        }
+    }
+}
@@ -2,12 +2,16 @@ use crate::aries::messages::a2a::A2AMessage;
 use crate::aries::messages::error::ProblemReport;
 use crate::aries::messages::proof_presentation::presentation::Presentation;
 use crate::aries::messages::proof_presentation::presentation_proposal::PresentationProposal;
+use crate::aries::messages::proof_presentation::presentation_request::PresentationRequestData;
 
 type Comment = Option<String>;
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub enum VerifierMessages {
     SendPresentationRequest(Comment),
+    AcceptPresentationProposal(Comment),
+    CounterPresentationProposal(PresentationRequestData, Comment),
+    RejectPresentationProposal(String),
     VerifyPresentation(Presentation),
     PresentationProposalReceived(PresentationProposal),
     PresentationRejectReceived(ProblemReport),
@@ -1,32 +1,51 @@
 use std::collections::HashMap;
+use std::future::Future;
+use std::mem;
 
 use crate::api_lib::VcxStateType;
+use crate::aries::utils::task::block_on;
 use crate::error::prelude::*;
+use crate::profile::ProfileContext;
 use crate::aries::handlers::proof_presentation::verifier::messages::VerifierMessages;
 use crate::aries::messages::a2a::A2AMessage;
+use crate::aries::messages::a2a::protocol_version::ProtocolVersion;
 use crate::aries::messages::error::ProblemReport;
 use crate::aries::messages::proof_presentation::presentation::Presentation;
+use crate::aries::messages::proof_presentation::presentation_proposal::PresentationProposal;
 use crate::aries::messages::proof_presentation::presentation_request::{PresentationRequest, PresentationRequestData};
 use crate::aries::messages::status::Status;
+use crate::aries::messages::timing;
+use crate::aries::utils::sync_sender;
 use crate::aries::handlers::proof_presentation::verifier::states::initial::InitialState;
+use crate::aries::handlers::proof_presentation::verifier::states::presentation_proposal_received::PresentationProposalReceivedState;
 use crate::aries::handlers::proof_presentation::verifier::states::presentation_request_sent::PresentationRequestSentState;
 use crate::aries::handlers::proof_presentation::verifier::states::finished::FinishedState;
+use crate::aries::handlers::state_timing::{self, StateTiming};
+use crate::libindy::proofs::verifier::verification_report::VerificationReport;
+use crate::settings;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct VerifierSM {
     source_id: String,
     state: VerifierState,
+    #[serde(default)]
+    timing: StateTiming,
 }
 
 impl VerifierSM {
     pub fn new(presentation_request: PresentationRequestData, source_id: String) -> VerifierSM {
-        VerifierSM { source_id, state: VerifierState::Initiated(InitialState { presentation_request_data: presentation_request }) }
+        VerifierSM { source_id, state: VerifierState::Initiated(InitialState { presentation_request_data: presentation_request, protocol_version: ProtocolVersion::default() }), timing: StateTiming::entered(None) }
+    }
+
+    pub fn from_proposal(presentation_proposal: PresentationProposal, source_id: String) -> VerifierSM {
+        VerifierSM { source_id, state: VerifierState::PresentationProposalReceived(PresentationProposalReceivedState::new(presentation_proposal)), timing: StateTiming::entered(None) }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum VerifierState {
     Initiated(InitialState),
+    PresentationProposalReceived(PresentationProposalReceivedState),
     PresentationRequestSent(PresentationRequestSentState),
     Finished(FinishedState),
 }
@@ -47,6 +66,16 @@ impl VerifierSM {
                 VerifierState::Initiated(_) => {
                     // do not process message
                 }
+                VerifierState::PresentationProposalReceived(_) => {
+                    match message {
+                        A2AMessage::CommonProblemReport(problem_report) => {
+                            if problem_report.from_thread(&self.thread_id()) {
+                                return Some((uid, A2AMessage::CommonProblemReport(problem_report)));
+                            }
+                        }
+                        _ => {}
+                    }
+                }
                 VerifierState::PresentationRequestSent(_) => {
                     match message {
                         A2AMessage::Presentation(presentation) => {
@@ -76,10 +105,18 @@ impl VerifierSM {
         None
     }
 
-    pub fn step(self, message: VerifierMessages, send_message: Option<&impl Fn(&A2AMessage) -> VcxResult<()>>) -> VcxResult<VerifierSM> {
-        trace!("VerifierSM::step >>> message: {:?}", message);
+    pub fn step(self, profile: &ProfileContext, message: VerifierMessages, send_message: Option<&impl Fn(&A2AMessage) -> VcxResult<()>>) -> VcxResult<VerifierSM> {
+        block_on(self.step_async(profile, message, send_message.map(sync_sender).as_ref()))
+    }
+
+    pub async fn step_async<F, Fut>(self, profile: &ProfileContext, message: VerifierMessages, send_message: Option<&F>) -> VcxResult<VerifierSM>
+        where F: Fn(A2AMessage) -> Fut,
+              Fut: Future<Output=VcxResult<()>> {
+        trace!("VerifierSM::step_async >>> message: {:?}", message);
 
-        let VerifierSM { source_id, state } = self;
+        let VerifierSM { source_id, state, timing } = self;
+        let previous_state = mem::discriminant(&state);
+        let expires_time = timing::expires_time(settings::get_expiry(settings::CONFIG_PRESENT_PROOF_EXPIRY));
 
         let state = match state {
             VerifierState::Initiated(state) => {
@@ -88,10 +125,12 @@ impl VerifierSM {
                         let presentation_request =
                             PresentationRequest::create()
                                 .set_comment(comment)
-                                .set_request_presentations_attach(&state.presentation_request_data)?;
+                                .set_request_presentations_attach(&state.presentation_request_data)?
+                                .set_protocol_version(state.protocol_version)
+                                .set_expires_time(expires_time.clone());
                         send_message.ok_or(
                             VcxError::from_msg(VcxErrorKind::InvalidState, "Attempted to call undefined send_message callback")
-                        )?(&presentation_request.to_a2a_message())?;
+                        )?(presentation_request.to_a2a_message()).await?;
                         VerifierState::PresentationRequestSent((state, presentation_request).into())
                     }
                     _ => {
@@ -99,26 +138,60 @@ impl VerifierSM {
                     }
                 }
             }
+            VerifierState::PresentationProposalReceived(state) => {
+                match message {
+                    VerifierMessages::AcceptPresentationProposal(comment) => {
+                        let presentation_request_data =
+                            state.presentation_proposal.proof_request_data()?
+                                .set_name(source_id.clone())
+                                .set_nonce()?;
+                        let presentation_request = Self::_send_presentation_request_for_proposal(&presentation_request_data, comment, &state.thread_id, state.presentation_proposal.protocol_version(), expires_time.clone(), send_message).await?;
+                        VerifierState::PresentationRequestSent((state, presentation_request).into())
+                    }
+                    VerifierMessages::CounterPresentationProposal(presentation_request_data, comment) => {
+                        let presentation_request = Self::_send_presentation_request_for_proposal(&presentation_request_data, comment, &state.thread_id, state.presentation_proposal.protocol_version(), expires_time.clone(), send_message).await?;
+                        VerifierState::PresentationRequestSent((state, presentation_request).into())
+                    }
+                    VerifierMessages::RejectPresentationProposal(reason) => {
+                        let problem_report =
+                            ProblemReport::create()
+                                .set_comment(reason)
+                                .set_thread_id(&state.thread_id);
+                        send_message.ok_or(
+                            VcxError::from_msg(VcxErrorKind::InvalidState, "Attempted to call undefined send_message callback")
+                        )?(problem_report.to_a2a_message()).await?;
+                        VerifierState::Finished((state, problem_report).into())
+                    }
+                    VerifierMessages::PresentationRejectReceived(problem_report) => {
+                        VerifierState::Finished((state, problem_report).into())
+                    }
+                    _ => {
+                        VerifierState::PresentationProposalReceived(state)
+                    }
+                }
+            }
             VerifierState::PresentationRequestSent(state) => {
                 match message {
                     VerifierMessages::VerifyPresentation(presentation) => {
-                        match state.verify_presentation(&presentation, send_message) {
+                        let result = state.verify_presentation(profile, &presentation, send_message).await;
+                        let verification_report = state.verification_report(&presentation, result.as_ref().map(|_| ()));
+                        match result {
                             Ok(()) => {
-                                VerifierState::Finished((state, presentation, RevocationStatus::NonRevoked).into())
+                                VerifierState::Finished((state, presentation, RevocationStatus::NonRevoked, verification_report).into())
                             }
                             Err(err) => {
                                 let problem_report =
                                     ProblemReport::create()
                                         .set_comment(err.to_string())
-                                        .set_thread_id(&state.presentation_request.id.0);
+                                        .set_thread_id(&state.presentation_request.thread_id());
                                 send_message.ok_or(
                                     VcxError::from_msg(VcxErrorKind::InvalidState, "Attempted to call undefined send_message callback")
-                                )?(&problem_report.to_a2a_message())?;
+                                )?(problem_report.to_a2a_message()).await?;
                                 match err.kind() {
                                     VcxErrorKind::InvalidProof => {
-                                        VerifierState::Finished((state, presentation, RevocationStatus::Revoked).into())
+                                        VerifierState::Finished((state, presentation, RevocationStatus::Revoked, verification_report).into())
                                     }
-                                    _ => VerifierState::Finished((state, problem_report).into())
+                                    _ => VerifierState::Finished((state, problem_report, verification_report).into())
                                 }
                             }
                         }
@@ -126,15 +199,8 @@ impl VerifierSM {
                     VerifierMessages::PresentationRejectReceived(problem_report) => {
                         VerifierState::Finished((state, problem_report).into())
                     }
-                    VerifierMessages::PresentationProposalReceived(_) => { // TODO: handle Presentation Proposal
-                        let problem_report =
-                            ProblemReport::create()
-                                .set_comment(String::from("PresentationProposal is not supported"))
-                                .set_thread_id(&state.presentation_request.id.0);
-                        send_message.ok_or(
-                            VcxError::from_msg(VcxErrorKind::InvalidState, "Attempted to call undefined send_message callback")
-                        )?(&problem_report.to_a2a_message())?;
-                        VerifierState::Finished((state, problem_report).into())
+                    VerifierMessages::PresentationProposalReceived(presentation_proposal) => {
+                        VerifierState::PresentationProposalReceived((state, presentation_proposal).into())
                     }
                     _ => {
                         VerifierState::PresentationRequestSent(state)
@@ -144,16 +210,86 @@ impl VerifierSM {
             VerifierState::Finished(state) => VerifierState::Finished(state)
         };
 
-        Ok(VerifierSM { source_id, state })
+        let timing = if mem::discriminant(&state) == previous_state {
+            timing
+        } else {
+            match state {
+                VerifierState::PresentationRequestSent(_) => StateTiming::entered(expires_time),
+                _ => StateTiming::entered(None)
+            }
+        };
+        Ok(VerifierSM { source_id, state, timing })
+    }
+
+    /**
+    Finishes the presentation with failure, the prover is notified by a problem report if `send_message` is set.
+     */
+    pub fn abandon(self, reason: Option<String>, send_message: Option<&impl Fn(&A2AMessage) -> VcxResult<()>>) -> VcxResult<VerifierSM> {
+        trace!("VerifierSM::abandon >>> reason: {:?}, state: {:?}", reason, self.state);
+
+        if let VerifierState::Initiated(_) | VerifierState::Finished(_) = self.state {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidState, "Only presentation in progress can be abandoned"));
+        }
+        let problem_report = state_timing::expiration_problem_report(&self.thread_id(), reason);
+        if let Some(send_message) = send_message {
+            send_message(&problem_report.to_a2a_message())?;
+        }
+
+        let VerifierSM { source_id, state, .. } = self;
+        let state = match state {
+            VerifierState::PresentationProposalReceived(state) => VerifierState::Finished((state, problem_report).into()),
+            VerifierState::PresentationRequestSent(state) => VerifierState::Finished((state, problem_report).into()),
+            state => state
+        };
+        Ok(VerifierSM { source_id, state, timing: StateTiming::entered(None) })
+    }
+
+    async fn _send_presentation_request_for_proposal<F, Fut>(presentation_request_data: &PresentationRequestData,
+                                                             comment: Option<String>,
+                                                             thread_id: &str,
+                                                             protocol_version: ProtocolVersion,
+                                                             expires_time: Option<String>,
+                                                             send_message: Option<&F>) -> VcxResult<PresentationRequest>
+        where F: Fn(A2AMessage) -> Fut,
+              Fut: Future<Output=VcxResult<()>> {
+        let presentation_request =
+            PresentationRequest::create()
+                .set_comment(comment)
+                .set_thread_id(thread_id)
+                .set_request_presentations_attach(presentation_request_data)?
+                .set_protocol_version(protocol_version)
+                .set_expires_time(expires_time);
+        send_message.ok_or(
+            VcxError::from_msg(VcxErrorKind::InvalidState, "Attempted to call undefined send_message callback")
+        )?(presentation_request.to_a2a_message()).await?;
+        Ok(presentation_request)
+    }
+
+    pub fn set_protocol_version(self, protocol_version: ProtocolVersion) -> VcxResult<VerifierSM> {
+        let VerifierSM { source_id, state, timing } = self;
+        let state = match state {
+            VerifierState::Initiated(mut state) => {
+                state.protocol_version = protocol_version;
+                VerifierState::Initiated(state)
+            }
+            _ => return Err(VcxError::from_msg(VcxErrorKind::InvalidState, "Protocol version can be set only before presentation request is sent"))
+        };
+        Ok(VerifierSM { source_id, state, timing })
     }
 
     pub fn source_id(&self) -> String { self.source_id.clone() }
 
-    pub fn thread_id(&self) -> String { self.presentation_request().map(|request| request.id.0.clone()).unwrap_or_default() }
+    pub fn thread_id(&self) -> String {
+        match self.state {
+            VerifierState::PresentationProposalReceived(ref state) => state.thread_id.clone(),
+            _ => self.presentation_request().map(|request| request.thread_id()).unwrap_or_default()
+        }
+    }
 
     pub fn state(&self) -> u32 {
         match self.state {
             VerifierState::Initiated(_) => VcxStateType::VcxStateInitialized as u32,
+            VerifierState::PresentationProposalReceived(_) => VcxStateType::VcxStateInitialized as u32,
             VerifierState::PresentationRequestSent(_) => VcxStateType::VcxStateOfferSent as u32,
             VerifierState::Finished(ref status) => {
                 match status.status {
@@ -164,9 +300,19 @@ impl VerifierSM {
         }
     }
 
+    pub fn get_state_timing(&self) -> StateTiming { self.timing.clone() }
+
+    pub fn is_expired(&self) -> bool {
+        match self.state {
+            VerifierState::Finished(_) => false,
+            _ => self.timing.is_expired()
+        }
+    }
+
     pub fn has_transitions(&self) -> bool {
         match self.state {
             VerifierState::Initiated(_) => false,
+            VerifierState::PresentationProposalReceived(_) => true,
             VerifierState::PresentationRequestSent(_) => true,
             VerifierState::Finished(_) => false,
         }
@@ -198,11 +344,32 @@ impl VerifierSM {
             VerifierState::Initiated(ref state) => {
                 PresentationRequest::create().set_request_presentations_attach(&state.presentation_request_data)
             }
+            VerifierState::PresentationProposalReceived(ref state) => {
+                state.presentation_request.clone()
+                    .ok_or(VcxError::from_msg(VcxErrorKind::NotReady, "Presentation request is not sent yet"))
+            }
             VerifierState::PresentationRequestSent(ref state) => Ok(state.presentation_request.clone()),
             VerifierState::Finished(ref state) => Ok(state.presentation_request.clone()),
         }
     }
 
+    pub fn presentation_proposal(&self) -> VcxResult<PresentationProposal> {
+        match self.state {
+            VerifierState::PresentationProposalReceived(ref state) => Ok(state.presentation_proposal.clone()),
+            _ => Err(VcxError::from_msg(VcxErrorKind::InvalidState, "Presentation proposal is available only in PresentationProposalReceived state"))
+        }
+    }
+
+    pub fn verification_report(&self) -> VcxResult<VerificationReport> {
+        match self.state {
+            VerifierState::Finished(ref state) => {
+                state.verification_report.clone()
+                    .ok_or(VcxError::from_msg(VcxErrorKind::InvalidState, "Verification report is not available, presentation has not been verified"))
+            }
+            _ => Err(VcxError::from_msg(VcxErrorKind::NotReady, "Presentation is not received yet"))
+        }
+    }
+
     pub fn presentation(&self) -> VcxResult<Presentation> {
         match self.state {
             VerifierState::Finished(ref state) => {
@@ -230,17 +397,21 @@ pub mod test {
         VerifierSM::new(_presentation_request_data(), source_id())
     }
 
+    pub fn _verifier_sm_from_proposal() -> VerifierSM {
+        VerifierSM::from_proposal(_presentation_proposal(), source_id())
+    }
+
     impl VerifierSM {
         fn to_presentation_request_sent_state(mut self) -> VerifierSM {
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
-            self = self.step(VerifierMessages::SendPresentationRequest(_comment()), send_message).unwrap();
+            self = self.step(&ProfileContext::Global, VerifierMessages::SendPresentationRequest(_comment()), send_message).unwrap();
             self
         }
 
         fn to_finished_state(mut self) -> VerifierSM {
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
-            self = self.step(VerifierMessages::SendPresentationRequest(_comment()), send_message).unwrap();
-            self = self.step(VerifierMessages::VerifyPresentation(_presentation()), send_message).unwrap();
+            self = self.step(&ProfileContext::Global, VerifierMessages::SendPresentationRequest(_comment()), send_message).unwrap();
+            self = self.step(&ProfileContext::Global, VerifierMessages::VerifyPresentation(_presentation()), send_message).unwrap();
             self
         }
     }
@@ -258,6 +429,19 @@ pub mod test {
             assert_match!(VerifierState::Initiated(_), verifier_sm.state);
             assert_eq!(source_id(), verifier_sm.source_id());
         }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_verifier_from_proposal() {
+            let _setup = SetupMocks::init();
+
+            let verifier_sm = _verifier_sm_from_proposal();
+
+            assert_match!(VerifierState::PresentationProposalReceived(_), verifier_sm.state);
+            assert_eq!(_presentation_proposal(), verifier_sm.presentation_proposal().unwrap());
+            assert_eq!(_presentation_proposal().thread_id(), verifier_sm.thread_id());
+            assert_eq!(VcxErrorKind::NotReady, verifier_sm.presentation_request().unwrap_err().kind());
+        }
     }
 
     mod step {
@@ -280,7 +464,7 @@ pub mod test {
 
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
             let mut verifier_sm = _verifier_sm();
-            verifier_sm = verifier_sm.step(VerifierMessages::SendPresentationRequest(_comment()), send_message).unwrap();
+            verifier_sm = verifier_sm.step(&ProfileContext::Global, VerifierMessages::SendPresentationRequest(_comment()), send_message).unwrap();
 
             assert_match!(VerifierState::PresentationRequestSent(_), verifier_sm.state);
         }
@@ -293,10 +477,10 @@ pub mod test {
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
             let mut verifier_sm = _verifier_sm();
 
-            verifier_sm = verifier_sm.step(VerifierMessages::PresentationRejectReceived(_problem_report()), send_message).unwrap();
+            verifier_sm = verifier_sm.step(&ProfileContext::Global, VerifierMessages::PresentationRejectReceived(_problem_report()), send_message).unwrap();
             assert_match!(VerifierState::Initiated(_), verifier_sm.state);
 
-            verifier_sm = verifier_sm.step(VerifierMessages::VerifyPresentation(_presentation()), send_message).unwrap();
+            verifier_sm = verifier_sm.step(&ProfileContext::Global, VerifierMessages::VerifyPresentation(_presentation()), send_message).unwrap();
             assert_match!(VerifierState::Initiated(_), verifier_sm.state);
         }
 
@@ -309,11 +493,15 @@ pub mod test {
 
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
             let mut verifier_sm = _verifier_sm();
-            verifier_sm = verifier_sm.step(VerifierMessages::SendPresentationRequest(_comment()), send_message).unwrap();
-            verifier_sm = verifier_sm.step(VerifierMessages::VerifyPresentation(_presentation()), send_message).unwrap();
+            verifier_sm = verifier_sm.step(&ProfileContext::Global, VerifierMessages::SendPresentationRequest(_comment()), send_message).unwrap();
+            verifier_sm = verifier_sm.step(&ProfileContext::Global, VerifierMessages::VerifyPresentation(_presentation()), send_message).unwrap();
 
             assert_match!(VerifierState::Finished(_), verifier_sm.state);
             assert_eq!(Status::Success.code(), verifier_sm.presentation_status());
+
+            let report = verifier_sm.verification_report().unwrap();
+            assert!(report.verified);
+            assert_eq!(None, report.failure);
         }
 
         #[test]
@@ -325,12 +513,16 @@ pub mod test {
 
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
             let mut verifier_sm = _verifier_sm();
-            verifier_sm = verifier_sm.step(VerifierMessages::SendPresentationRequest(_comment()), send_message).unwrap();
-            verifier_sm = verifier_sm.step(VerifierMessages::VerifyPresentation(_presentation()), send_message).unwrap();
+            verifier_sm = verifier_sm.step(&ProfileContext::Global, VerifierMessages::SendPresentationRequest(_comment()), send_message).unwrap();
+            verifier_sm = verifier_sm.step(&ProfileContext::Global, VerifierMessages::VerifyPresentation(_presentation()), send_message).unwrap();
 
             assert_match!(VerifierState::Finished(_), verifier_sm.state);
             assert_eq!(VcxStateType::VcxStateAccepted as u32, verifier_sm.state());
             assert_eq!(Status::Failed(ProblemReport::create()).code(), verifier_sm.presentation_status());
+
+            let report = verifier_sm.verification_report().unwrap();
+            assert!(!report.verified);
+            assert_eq!(u32::from(VcxErrorKind::InvalidProof), report.failure.unwrap().code);
         }
 
         //    #[test]
@@ -338,8 +530,8 @@ pub mod test {
         //        let _setup = Setup::init();
         //
         //        let mut verifier_sm = _verifier_sm();
-        //        verifier_sm = verifier_sm.step(VerifierMessages::SendPresentationRequest(_comment())(mock_connection())).unwrap();
-        //        verifier_sm = verifier_sm.step(VerifierMessages::VerifyPresentation(_presentation())).unwrap();
+        //        verifier_sm = verifier_sm.step(&ProfileContext::Global, VerifierMessages::SendPresentationRequest(_comment())(mock_connection())).unwrap();
+        //        verifier_sm = verifier_sm.step(&ProfileContext::Global, VerifierMessages::VerifyPresentation(_presentation())).unwrap();
         //
         //        assert_match!(VerifierState::Finished(_), verifier_sm.state);
         //        assert_eq!(Status::Failed(_problem_report()).code(), verifier_sm.presentation_status());
@@ -352,13 +544,88 @@ pub mod test {
 
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
             let mut verifier_sm = _verifier_sm();
-            verifier_sm = verifier_sm.step(VerifierMessages::SendPresentationRequest(_comment()), send_message).unwrap();
-            verifier_sm = verifier_sm.step(VerifierMessages::PresentationProposalReceived(_presentation_proposal()), send_message).unwrap();
+            verifier_sm = verifier_sm.step(&ProfileContext::Global, VerifierMessages::SendPresentationRequest(_comment()), send_message).unwrap();
+            let thread_id = verifier_sm.thread_id();
+            verifier_sm = verifier_sm.step(&ProfileContext::Global, VerifierMessages::PresentationProposalReceived(_presentation_proposal()), send_message).unwrap();
+
+            assert_match!(VerifierState::PresentationProposalReceived(_), verifier_sm.state);
+            assert_eq!(thread_id, verifier_sm.thread_id());
+            assert!(verifier_sm.presentation_request().is_ok());
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_verifier_handle_accept_presentation_proposal_message_from_presentation_proposal_received_state() {
+            let _setup = SetupMocks::init();
+
+            let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
+            let mut verifier_sm = _verifier_sm_from_proposal();
+            verifier_sm = verifier_sm.step(&ProfileContext::Global, VerifierMessages::AcceptPresentationProposal(_comment()), send_message).unwrap();
+
+            assert_match!(VerifierState::PresentationRequestSent(_), verifier_sm.state);
+            assert_eq!(_presentation_proposal().thread_id(), verifier_sm.thread_id());
+
+            let presentation_request_data = verifier_sm.presentation_request().unwrap().get_presentation_request_data().unwrap();
+            assert_eq!(source_id(), presentation_request_data.name);
+            assert_eq!(Some(String::from("name")), presentation_request_data.requested_attributes["attribute_0"].name);
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_verifier_handle_counter_presentation_proposal_message_from_presentation_proposal_received_state() {
+            let _setup = SetupMocks::init();
+
+            let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
+            let mut verifier_sm = _verifier_sm_from_proposal();
+            verifier_sm = verifier_sm.step(&ProfileContext::Global, VerifierMessages::CounterPresentationProposal(_presentation_request_data(), _comment()), send_message).unwrap();
+
+            assert_match!(VerifierState::PresentationRequestSent(_), verifier_sm.state);
+            assert_eq!(_presentation_proposal().thread_id(), verifier_sm.thread_id());
+            assert_eq!(_presentation_request_data(), verifier_sm.presentation_request().unwrap().get_presentation_request_data().unwrap());
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_verifier_handle_reject_presentation_proposal_message_from_presentation_proposal_received_state() {
+            let _setup = SetupMocks::init();
+
+            let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
+            let mut verifier_sm = _verifier_sm_from_proposal();
+            verifier_sm = verifier_sm.step(&ProfileContext::Global, VerifierMessages::RejectPresentationProposal(String::from("reason")), send_message).unwrap();
+
+            assert_match!(VerifierState::Finished(_), verifier_sm.state);
+            assert_eq!(Status::Failed(_problem_report()).code(), verifier_sm.presentation_status());
+            assert_eq!(_presentation_proposal().thread_id(), verifier_sm.thread_id());
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_verifier_handle_problem_report_message_from_presentation_proposal_received_state() {
+            let _setup = SetupMocks::init();
+
+            let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
+            let mut verifier_sm = _verifier_sm_from_proposal();
+            verifier_sm = verifier_sm.step(&ProfileContext::Global, VerifierMessages::PresentationRejectReceived(_problem_report()), send_message).unwrap();
 
             assert_match!(VerifierState::Finished(_), verifier_sm.state);
             assert_eq!(Status::Failed(_problem_report()).code(), verifier_sm.presentation_status());
         }
 
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_verifier_handle_other_messages_from_presentation_proposal_received_state() {
+            let _setup = SetupMocks::init();
+
+            let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
+            let mut verifier_sm = _verifier_sm_from_proposal();
+
+            verifier_sm = verifier_sm.step(&ProfileContext::Global, VerifierMessages::SendPresentationRequest(_comment()), send_message).unwrap();
+            assert_match!(VerifierState::PresentationProposalReceived(_), verifier_sm.state);
+
+            verifier_sm = verifier_sm.step(&ProfileContext::Global, VerifierMessages::VerifyPresentation(_presentation()), send_message).unwrap();
+            assert_match!(VerifierState::PresentationProposalReceived(_), verifier_sm.state);
+        }
+
         #[test]
         #[cfg(feature = "general_test")]
         fn test_prover_handle_presentation_reject_message_from_presentation_request_sent_state() {
@@ -366,8 +633,8 @@ pub mod test {
 
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
             let mut verifier_sm = _verifier_sm();
-            verifier_sm = verifier_sm.step(VerifierMessages::SendPresentationRequest(_comment()), send_message).unwrap();
-            verifier_sm = verifier_sm.step(VerifierMessages::PresentationRejectReceived(_problem_report()), send_message).unwrap();
+            verifier_sm = verifier_sm.step(&ProfileContext::Global, VerifierMessages::SendPresentationRequest(_comment()), send_message).unwrap();
+            verifier_sm = verifier_sm.step(&ProfileContext::Global, VerifierMessages::PresentationRejectReceived(_problem_report()), send_message).unwrap();
 
             assert_match!(VerifierState::Finished(_), verifier_sm.state);
             assert_eq!(Status::Failed(_problem_report()).code(), verifier_sm.presentation_status());
@@ -380,8 +647,8 @@ pub mod test {
 
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
             let mut verifier_sm = _verifier_sm();
-            verifier_sm = verifier_sm.step(VerifierMessages::SendPresentationRequest(_comment()), send_message).unwrap();
-            verifier_sm = verifier_sm.step(VerifierMessages::SendPresentationRequest(_comment()), send_message).unwrap();
+            verifier_sm = verifier_sm.step(&ProfileContext::Global, VerifierMessages::SendPresentationRequest(_comment()), send_message).unwrap();
+            verifier_sm = verifier_sm.step(&ProfileContext::Global, VerifierMessages::SendPresentationRequest(_comment()), send_message).unwrap();
 
             assert_match!(VerifierState::PresentationRequestSent(_), verifier_sm.state);
         }
@@ -393,13 +660,13 @@ pub mod test {
 
             let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
             let mut verifier_sm = _verifier_sm();
-            verifier_sm = verifier_sm.step(VerifierMessages::SendPresentationRequest(_comment()), send_message).unwrap();
-            verifier_sm = verifier_sm.step(VerifierMessages::VerifyPresentation(_presentation()), send_message).unwrap();
+            verifier_sm = verifier_sm.step(&ProfileContext::Global, VerifierMessages::SendPresentationRequest(_comment()), send_message).unwrap();
+            verifier_sm = verifier_sm.step(&ProfileContext::Global, VerifierMessages::VerifyPresentation(_presentation()), send_message).unwrap();
 
-            verifier_sm = verifier_sm.step(VerifierMessages::PresentationRejectReceived(_problem_report()), send_message).unwrap();
+            verifier_sm = verifier_sm.step(&ProfileContext::Global, VerifierMessages::PresentationRejectReceived(_problem_report()), send_message).unwrap();
             assert_match!(VerifierState::Finished(_), verifier_sm.state);
 
-            verifier_sm = verifier_sm.step(VerifierMessages::PresentationProposalReceived(_presentation_proposal()), send_message).unwrap();
+            verifier_sm = verifier_sm.step(&ProfileContext::Global, VerifierMessages::PresentationProposalReceived(_presentation_proposal()), send_message).unwrap();
             assert_match!(VerifierState::Finished(_), verifier_sm.state);
         }
     }
@@ -496,6 +763,36 @@ pub mod test {
             }
         }
 
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_verifier_find_message_to_handle_from_presentation_proposal_received_state() {
+            let _setup = SetupMocks::init();
+
+            let verifier = _verifier_sm_from_proposal();
+
+            // Problem Report
+            {
+                let messages = map!(
+                    "key_1".to_string() => A2AMessage::PresentationProposal(_presentation_proposal()),
+                    "key_2".to_string() => A2AMessage::Presentation(_presentation()),
+                    "key_3".to_string() => A2AMessage::CommonProblemReport(_problem_report())
+                );
+
+                let (uid, message) = verifier.find_message_to_handle(messages).unwrap();
+                assert_eq!("key_3", uid);
+                assert_match!(A2AMessage::CommonProblemReport(_), message);
+            }
+
+            // No messages for different Thread ID
+            {
+                let messages = map!(
+                    "key_1".to_string() => A2AMessage::CommonProblemReport(_problem_report().set_thread_id(""))
+                );
+
+                assert!(verifier.find_message_to_handle(messages).is_none());
+            }
+        }
+
         #[test]
         #[cfg(feature = "general_test")]
         fn test_verifier_find_message_to_handle_from_finished_state() {
@@ -518,6 +815,72 @@ pub mod test {
         }
     }
 
+    mod abandon {
+        use std::sync::Mutex;
+
+        use super::*;
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_verifier_records_state_timing() {
+            let _setup = SetupMocks::init();
+
+            let verifier_sm = _verifier_sm();
+            assert!(verifier_sm.get_state_timing().entered_time.is_some());
+            assert!(!verifier_sm.is_expired());
+
+            let verifier_sm = verifier_sm.to_presentation_request_sent_state();
+            assert!(verifier_sm.get_state_timing().entered_time.is_some());
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_verifier_sends_presentation_request_with_expires_time() {
+            let _setup = SetupMocks::init();
+
+            let expires_time = timing::expires_time(Some(60));
+            let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
+            let presentation_request = VerifierSM::_send_presentation_request_for_proposal(&_presentation_request_data(), None, "thread_id", ProtocolVersion::V1, expires_time.clone(), send_message).unwrap();
+            assert_eq!(expires_time, presentation_request.expires_time());
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_verifier_abandon_from_presentation_request_sent_state() {
+            let _setup = SetupMocks::init();
+
+            let sent = Mutex::new(Vec::new());
+            let send_message = |message: &A2AMessage| {
+                sent.lock().unwrap().push(message.clone());
+                VcxResult::Ok(())
+            };
+            let verifier_sm = _verifier_sm().to_presentation_request_sent_state();
+            let thread_id = verifier_sm.thread_id();
+            let verifier_sm = verifier_sm.abandon(None, Some(&send_message)).unwrap();
+
+            assert_match!(VerifierState::Finished(_), verifier_sm.state);
+            assert_eq!(VcxStateType::VcxStateNone as u32, verifier_sm.state());
+            assert!(!verifier_sm.is_expired());
+            let sent = sent.into_inner().unwrap();
+            match sent.as_slice() {
+                [A2AMessage::CommonProblemReport(problem_report)] => assert!(problem_report.from_thread(&thread_id)),
+                messages => panic!("Unexpected messages: {:?}", messages)
+            }
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_verifier_abandon_fails_for_exchange_not_in_progress() {
+            let _setup = SetupMocks::init();
+
+            let send_message = Some(&|_: &A2AMessage| VcxResult::Ok(()));
+            assert_eq!(VcxErrorKind::InvalidState, _verifier_sm().abandon(None, send_message).unwrap_err().kind());
+
+            let verifier_sm = _verifier_sm_from_proposal().abandon(Some(String::from("reason")), send_message).unwrap();
+            assert_eq!(VcxErrorKind::InvalidState, verifier_sm.abandon(None, send_message).unwrap_err().kind());
+        }
+    }
+
     mod get_state {
         use super::*;
         use crate::utils::mockdata::mock_settings::MockBuilder;
@@ -530,6 +893,7 @@ pub mod test {
                 set_mock_result_for_validate_indy_proof(Ok(true));
 
             assert_eq!(VcxStateType::VcxStateInitialized as u32, _verifier_sm().state());
+            assert_eq!(VcxStateType::VcxStateInitialized as u32, _verifier_sm_from_proposal().state());
             assert_eq!(VcxStateType::VcxStateOfferSent as u32, _verifier_sm().to_presentation_request_sent_state().state());
             assert_eq!(VcxStateType::VcxStateAccepted as u32, _verifier_sm().to_finished_state().state());
         }
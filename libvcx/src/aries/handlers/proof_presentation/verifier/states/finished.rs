@@ -2,6 +2,7 @@ use crate::aries::handlers::proof_presentation::verifier::state_machine::Revocat
 use crate::aries::messages::proof_presentation::presentation::Presentation;
 use crate::aries::messages::proof_presentation::presentation_request::PresentationRequest;
 use crate::aries::messages::status::Status;
+use crate::libindy::proofs::verifier::verification_report::VerificationReport;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct FinishedState {
@@ -9,4 +10,6 @@ pub struct FinishedState {
     pub presentation: Option<Presentation>,
     pub status: Status,
     pub revocation_status: Option<RevocationStatus>,
+    #[serde(default)]
+    pub verification_report: Option<VerificationReport>,
 }
\ No newline at end of file
@@ -1,9 +1,12 @@
 use crate::aries::handlers::proof_presentation::verifier::states::presentation_request_sent::PresentationRequestSentState;
+use crate::aries::messages::a2a::protocol_version::ProtocolVersion;
 use crate::aries::messages::proof_presentation::presentation_request::{PresentationRequest, PresentationRequestData};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct InitialState {
-    pub presentation_request_data: PresentationRequestData
+    pub presentation_request_data: PresentationRequestData,
+    #[serde(default)]
+    pub protocol_version: ProtocolVersion,
 }
 
 impl From<(InitialState, PresentationRequest)> for PresentationRequestSentState {
@@ -0,0 +1,45 @@
+use crate::aries::handlers::proof_presentation::verifier::states::finished::FinishedState;
+use crate::aries::handlers::proof_presentation::verifier::states::presentation_request_sent::PresentationRequestSentState;
+use crate::aries::messages::error::ProblemReport;
+use crate::aries::messages::proof_presentation::presentation_proposal::PresentationProposal;
+use crate::aries::messages::proof_presentation::presentation_request::PresentationRequest;
+use crate::aries::messages::status::Status;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PresentationProposalReceivedState {
+    pub presentation_proposal: PresentationProposal,
+    pub thread_id: String,
+    pub presentation_request: Option<PresentationRequest>,
+}
+
+impl PresentationProposalReceivedState {
+    pub fn new(presentation_proposal: PresentationProposal) -> Self {
+        PresentationProposalReceivedState {
+            thread_id: presentation_proposal.thread_id(),
+            presentation_proposal,
+            presentation_request: None,
+        }
+    }
+}
+
+impl From<(PresentationProposalReceivedState, PresentationRequest)> for PresentationRequestSentState {
+    fn from((_state, presentation_request): (PresentationProposalReceivedState, PresentationRequest)) -> Self {
+        trace!("transit state from PresentationProposalReceivedState to PresentationRequestSentState");
+        PresentationRequestSentState { presentation_request }
+    }
+}
+
+impl From<(PresentationProposalReceivedState, ProblemReport)> for FinishedState {
+    fn from((state, problem_report): (PresentationProposalReceivedState, ProblemReport)) -> Self {
+        trace!("transit state from PresentationProposalReceivedState to FinishedState");
+        let thread_id = state.thread_id;
+        FinishedState {
+            presentation_request: state.presentation_request
+                .unwrap_or_else(|| PresentationRequest::create().set_thread_id(&thread_id)),
+            presentation: None,
+            status: Status::Failed(problem_report),
+            revocation_status: None,
+            verification_report: None,
+        }
+    }
+}
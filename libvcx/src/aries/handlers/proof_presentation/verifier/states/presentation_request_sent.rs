@@ -1,13 +1,20 @@
+use std::future::Future;
+
 use crate::error::{VcxError, VcxErrorKind, VcxResult};
 use crate::aries::handlers::proof_presentation::verifier::states::finished::FinishedState;
+use crate::aries::handlers::proof_presentation::verifier::states::presentation_proposal_received::PresentationProposalReceivedState;
 use crate::aries::handlers::proof_presentation::verifier::state_machine::RevocationStatus;
 use crate::aries::messages::a2a::A2AMessage;
+use crate::aries::messages::a2a::protocol_version::ProtocolVersion;
 use crate::aries::messages::error::ProblemReport;
 use crate::aries::messages::proof_presentation::presentation::Presentation;
 use crate::aries::messages::proof_presentation::presentation_ack::PresentationAck;
+use crate::aries::messages::proof_presentation::presentation_proposal::PresentationProposal;
 use crate::aries::messages::proof_presentation::presentation_request::PresentationRequest;
 use crate::aries::messages::status::Status;
-use crate::libindy::proofs::verifier::verifier::validate_indy_proof;
+use crate::libindy::proofs::verifier::verification_report::VerificationReport;
+use crate::libindy::proofs::verifier::verifier::validate_indy_proof_async;
+use crate::profile::ProfileContext;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PresentationRequestSentState {
@@ -15,46 +22,88 @@ pub struct PresentationRequestSentState {
 }
 
 impl PresentationRequestSentState {
-    pub fn verify_presentation(&self, presentation: &Presentation, send_message: Option<&impl Fn(&A2AMessage) -> VcxResult<()>>) -> VcxResult<()> {
-        let valid = validate_indy_proof(&presentation.presentations_attach.content()?,
-                                               &self.presentation_request.request_presentations_attach.content()?)?;
+    pub async fn verify_presentation<F, Fut>(&self, profile: &ProfileContext, presentation: &Presentation, send_message: Option<&F>) -> VcxResult<()>
+        where F: Fn(A2AMessage) -> Fut,
+              Fut: Future<Output=VcxResult<()>> {
+        let valid = validate_indy_proof_async(profile,
+                                              &presentation.attachment_content()?,
+                                              &self.presentation_request.attachment_content()?).await?;
 
         if !valid {
-            return Err(VcxError::from_msg(VcxErrorKind::InvalidProof, "Presentation verification failed"));
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidProof, "Presentation verification failed: proof has been rejected by libindy verifier (proof does not match the request, its signature is invalid or a revoked credential has been used)"));
         }
 
         if presentation.please_ack.is_some() {
-            let ack = PresentationAck::create().set_thread_id(&self.presentation_request.id.0);
+            let ack = PresentationAck::create().set_thread_id(&self.presentation_request.thread_id());
+            let ack = match self.presentation_request.protocol_version() {
+                ProtocolVersion::V1 => A2AMessage::PresentationAck(ack),
+                ProtocolVersion::V2 => A2AMessage::PresentationAckV2(ack),
+            };
             send_message.ok_or(
                 VcxError::from_msg(VcxErrorKind::InvalidState, "Attempted to call undefined send_message callback")
-            )?(&A2AMessage::PresentationAck(ack))?;
+            )?(ack).await?;
         }
 
         Ok(())
     }
+
+    /**
+    Builds report of the presentation for the outcome of `verify_presentation`, returns None if the presentation is malformed.
+     */
+    pub fn verification_report(&self, presentation: &Presentation, result: Result<(), &VcxError>) -> Option<VerificationReport> {
+        let report = VerificationReport::build(&presentation.attachment_content().ok()?,
+                                               &self.presentation_request.attachment_content().ok()?,
+                                               result);
+        match report {
+            Ok(report) => Some(report),
+            Err(err) => {
+                warn!("PresentationRequestSentState::verification_report >>> cannot build verification report: {}", err);
+                None
+            }
+        }
+    }
 }
 
 
-impl From<(PresentationRequestSentState, Presentation, RevocationStatus)> for FinishedState {
-    fn from((state, presentation, was_revoked): (PresentationRequestSentState, Presentation, RevocationStatus)) -> Self {
+impl From<(PresentationRequestSentState, Presentation, RevocationStatus, Option<VerificationReport>)> for FinishedState {
+    fn from((state, presentation, was_revoked, verification_report): (PresentationRequestSentState, Presentation, RevocationStatus, Option<VerificationReport>)) -> Self {
         trace!("transit state from PresentationRequestSentState to FinishedState");
         FinishedState {
             presentation_request: state.presentation_request,
             presentation: Some(presentation),
             status: Status::Success,
             revocation_status: Some(was_revoked),
+            verification_report,
         }
     }
 }
 
 impl From<(PresentationRequestSentState, ProblemReport)> for FinishedState {
     fn from((state, problem_report): (PresentationRequestSentState, ProblemReport)) -> Self {
+        (state, problem_report, None).into()
+    }
+}
+
+impl From<(PresentationRequestSentState, ProblemReport, Option<VerificationReport>)> for FinishedState {
+    fn from((state, problem_report, verification_report): (PresentationRequestSentState, ProblemReport, Option<VerificationReport>)) -> Self {
         trace!("transit state from PresentationRequestSentState to FinishedState");
         FinishedState {
             presentation_request: state.presentation_request,
             presentation: None,
             status: Status::Failed(problem_report),
             revocation_status: None,
+            verification_report,
+        }
+    }
+}
+
+impl From<(PresentationRequestSentState, PresentationProposal)> for PresentationProposalReceivedState {
+    fn from((state, presentation_proposal): (PresentationRequestSentState, PresentationProposal)) -> Self {
+        trace!("transit state from PresentationRequestSentState to PresentationProposalReceivedState");
+        PresentationProposalReceivedState {
+            thread_id: state.presentation_request.thread_id(),
+            presentation_proposal,
+            presentation_request: Some(state.presentation_request),
         }
     }
 }
@@ -1,19 +1,27 @@
 use crate::error::prelude::*;
 use crate::aries::handlers::proof_presentation::verifier::messages::VerifierMessages;
 use crate::aries::handlers::proof_presentation::verifier::state_machine::VerifierSM;
+use crate::aries::handlers::state_timing::StateTiming;
 use crate::aries::handlers::connection::connection::Connection;
 use crate::aries::messages::a2a::A2AMessage;
+use crate::aries::messages::a2a::protocol_version::ProtocolVersion;
 use crate::aries::messages::proof_presentation::presentation::Presentation;
+use crate::aries::messages::proof_presentation::presentation_proposal::PresentationProposal;
 use crate::aries::messages::proof_presentation::presentation_request::*;
+use crate::profile::ProfileContext;
 use std::collections::HashMap;
+use std::future::Future;
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Verifier {
+    #[serde(skip)]
+    profile: ProfileContext,
     verifier_sm: VerifierSM
 }
 
 impl Verifier {
-    pub fn create(source_id: String,
+    pub fn create(profile: ProfileContext,
+                  source_id: String,
                   requested_attrs: String,
                   requested_predicates: String,
                   revocation_details: String,
@@ -30,12 +38,41 @@ impl Verifier {
                 .set_nonce()?;
 
         Ok(Verifier {
+            profile,
             verifier_sm: VerifierSM::new(presentation_request, source_id),
         })
     }
 
+    pub fn create_from_proposal(profile: ProfileContext, source_id: String, presentation_proposal: PresentationProposal) -> VcxResult<Verifier> {
+        trace!("Verifier::create_from_proposal >>> source_id: {:?}, presentation_proposal: {:?}", source_id, presentation_proposal);
+
+        Ok(Verifier {
+            profile,
+            verifier_sm: VerifierSM::from_proposal(presentation_proposal, source_id),
+        })
+    }
+
+    /**
+    Binds deserialized verifier to the profile, the profile is not part of the serialized data.
+     */
+    pub fn set_profile(&mut self, profile: ProfileContext) {
+        self.profile = profile;
+    }
+
+    pub fn profile(&self) -> &ProfileContext {
+        &self.profile
+    }
+
     pub fn get_source_id(&self) -> String { self.verifier_sm.source_id() }
 
+    pub fn get_thread_id(&self) -> String { self.verifier_sm.thread_id() }
+
+    pub fn set_protocol_version(&mut self, protocol_version: ProtocolVersion) -> VcxResult<()> {
+        trace!("Verifier::set_protocol_version >>> protocol_version: {:?}", protocol_version);
+        self.verifier_sm = self.verifier_sm.clone().set_protocol_version(protocol_version)?;
+        Ok(())
+    }
+
     pub fn state(&self) -> u32 {
         trace!("Verifier::state >>>");
         self.verifier_sm.state()
@@ -56,6 +93,47 @@ impl Verifier {
         self.step(VerifierMessages::SendPresentationRequest(comment), Some(&send_message))
     }
 
+    pub fn get_presentation_proposal(&self) -> VcxResult<PresentationProposal> {
+        trace!("Verifier::get_presentation_proposal >>>");
+        self.verifier_sm.presentation_proposal()
+    }
+
+    pub fn get_proposed_presentation_request_data(&self) -> VcxResult<PresentationRequestData> {
+        trace!("Verifier::get_proposed_presentation_request_data >>>");
+        self.verifier_sm.presentation_proposal()?.proof_request_data()
+    }
+
+    pub fn accept_presentation_proposal(&mut self, send_message: impl Fn(&A2AMessage) -> VcxResult<()>, comment: Option<String>) -> VcxResult<()> {
+        trace!("Verifier::accept_presentation_proposal >>>");
+        self.step(VerifierMessages::AcceptPresentationProposal(comment), Some(&send_message))
+    }
+
+    pub fn counter_presentation_proposal(&mut self,
+                                         send_message: impl Fn(&A2AMessage) -> VcxResult<()>,
+                                         requested_attrs: String,
+                                         requested_predicates: String,
+                                         revocation_details: String,
+                                         name: String,
+                                         comment: Option<String>) -> VcxResult<()> {
+        trace!("Verifier::counter_presentation_proposal >>> requested_attrs: {:?}, requested_predicates: {:?}, revocation_details: {:?}, name: {:?}",
+               requested_attrs, requested_predicates, revocation_details, name);
+
+        let presentation_request =
+            PresentationRequestData::create()
+                .set_name(name)
+                .set_requested_attributes(requested_attrs)?
+                .set_requested_predicates(requested_predicates)?
+                .set_not_revoked_interval(revocation_details)?
+                .set_nonce()?;
+
+        self.step(VerifierMessages::CounterPresentationProposal(presentation_request, comment), Some(&send_message))
+    }
+
+    pub fn reject_presentation_proposal(&mut self, send_message: impl Fn(&A2AMessage) -> VcxResult<()>, reason: String) -> VcxResult<()> {
+        trace!("Verifier::reject_presentation_proposal >>> reason: {:?}", reason);
+        self.step(VerifierMessages::RejectPresentationProposal(reason), Some(&send_message))
+    }
+
     pub fn generate_presentation_request_msg(&self) -> VcxResult<String> {
         trace!("Verifier::generate_presentation_request_msg >>>");
 
@@ -71,10 +149,30 @@ impl Verifier {
         Ok(json!(proof).to_string())
     }
 
+    pub fn get_verification_report(&self) -> VcxResult<String> {
+        trace!("Verifier::get_verification_report >>>");
+
+        let report = self.verifier_sm.verification_report()?;
+        Ok(json!(report).to_string())
+    }
+
+    pub fn get_state_timing(&self) -> StateTiming {
+        self.verifier_sm.get_state_timing()
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.verifier_sm.is_expired()
+    }
+
+    pub fn abandon(&mut self, reason: Option<String>, send_message: Option<&impl Fn(&A2AMessage) -> VcxResult<()>>) -> VcxResult<()> {
+        self.verifier_sm = self.verifier_sm.clone().abandon(reason, send_message)?;
+        Ok(())
+    }
+
     pub fn step(&mut self, message: VerifierMessages, send_message: Option<&impl Fn(&A2AMessage) -> VcxResult<()>>)
         -> VcxResult<()> 
     {
-        self.verifier_sm = self.verifier_sm.clone().step(message, send_message)?;
+        self.verifier_sm = self.verifier_sm.clone().step(&self.profile, message, send_message)?;
         Ok(())
     }
 
@@ -99,3 +197,43 @@ impl Verifier {
         Ok(self.state())
     }
 }
+
+impl Verifier {
+    pub async fn send_presentation_request_async(&mut self, connection: &Connection, comment: Option<String>) -> VcxResult<()> {
+        trace!("Verifier::send_presentation_request_async >>>");
+        let send_message = connection.send_message_closure_async()?;
+        self.step_async(VerifierMessages::SendPresentationRequest(comment), Some(&send_message)).await
+    }
+
+    pub async fn accept_presentation_proposal_async(&mut self, connection: &Connection, comment: Option<String>) -> VcxResult<()> {
+        trace!("Verifier::accept_presentation_proposal_async >>>");
+        let send_message = connection.send_message_closure_async()?;
+        self.step_async(VerifierMessages::AcceptPresentationProposal(comment), Some(&send_message)).await
+    }
+
+    pub async fn reject_presentation_proposal_async(&mut self, connection: &Connection, reason: String) -> VcxResult<()> {
+        trace!("Verifier::reject_presentation_proposal_async >>> reason: {:?}", reason);
+        let send_message = connection.send_message_closure_async()?;
+        self.step_async(VerifierMessages::RejectPresentationProposal(reason), Some(&send_message)).await
+    }
+
+    pub async fn step_async<F, Fut>(&mut self, message: VerifierMessages, send_message: Option<&F>) -> VcxResult<()>
+        where F: Fn(A2AMessage) -> Fut,
+              Fut: Future<Output=VcxResult<()>> {
+        self.verifier_sm = self.verifier_sm.clone().step_async(&self.profile, message, send_message).await?;
+        Ok(())
+    }
+
+    pub async fn update_state_async(&mut self, connection: &Connection) -> VcxResult<u32> {
+        trace!("Verifier::update_state_async >>> ");
+        if !self.has_transitions() { return Ok(self.state()); }
+        let send_message = connection.send_message_closure_async()?;
+
+        let messages = connection.get_messages_async().await?;
+        if let Some((uid, msg)) = self.find_message_to_handle(messages) {
+            self.step_async(msg.into(), Some(&send_message)).await?;
+            connection.update_message_status_async(uid).await?;
+        }
+        Ok(self.state())
+    }
+}
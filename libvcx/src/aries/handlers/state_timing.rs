@@ -0,0 +1,52 @@
+use crate::aries::messages::error::ProblemReport;
+use crate::aries::messages::timing;
+
+/**
+When the state machine entered its current state and when the exchange expires if it stays in the state.
+Objects created before the timing was recorded have no times and never expire.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct StateTiming {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entered_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_time: Option<String>,
+}
+
+impl StateTiming {
+    pub fn entered(expires_time: Option<String>) -> Self {
+        StateTiming {
+            entered_time: Some(timing::now()),
+            expires_time,
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_time.as_ref()
+            .map(|expires_time| timing::is_past(expires_time))
+            .unwrap_or(false)
+    }
+}
+
+/**
+Problem report (RFC 0035) informing the other party that the exchange has been abandoned.
+ */
+pub fn expiration_problem_report(thread_id: &str, reason: Option<String>) -> ProblemReport {
+    ProblemReport::create()
+        .set_comment(reason.unwrap_or(String::from("Exchange has expired")))
+        .set_thread_id(thread_id)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_state_timing_expiration() {
+        assert!(!StateTiming::default().is_expired());
+        assert!(!StateTiming::entered(None).is_expired());
+        assert!(!StateTiming::entered(timing::expires_time(Some(60))).is_expired());
+        assert!(StateTiming::entered(timing::expires_time(Some(0))).is_expired());
+    }
+}
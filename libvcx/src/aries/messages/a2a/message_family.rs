@@ -1,35 +1,51 @@
+use crate::aries::messages::a2a::protocol_version::ProtocolVersion;
 use crate::settings::Actors;
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, EnumIter)]
 pub enum MessageFamilies {
     Routing,
     Connections,
+    DidExchange,
+    OutOfBand,
     Notification,
     Signature,
     CredentialIssuance,
+    CredentialIssuanceV2,
     ReportProblem,
     PresentProof,
+    PresentProofV2,
     TrustPing,
     DiscoveryFeatures,
     Basicmessage,
+    CoordinateMediation,
+    MessagePickup,
+    MessagePickupV2,
     Unknown(String),
 }
 
 impl MessageFamilies {
     pub const DID: &'static str = "did:sov:BzCbsNYhMrjHiqZDTUASHg";
+    pub const DIDCOMM_ORG: &'static str = "https://didcomm.org";
 
     pub fn version(&self) -> &'static str {
         match self {
             MessageFamilies::Routing => "1.0",
             MessageFamilies::Connections => "1.0",
+            MessageFamilies::DidExchange => "1.0",
+            MessageFamilies::OutOfBand => "1.0",
             MessageFamilies::Notification => "1.0",
             MessageFamilies::Signature => "1.0",
             MessageFamilies::CredentialIssuance => "1.0",
+            MessageFamilies::CredentialIssuanceV2 => "2.0",
             MessageFamilies::ReportProblem => "1.0",
             MessageFamilies::PresentProof => "1.0",
+            MessageFamilies::PresentProofV2 => "2.0",
             MessageFamilies::TrustPing => "1.0",
             MessageFamilies::DiscoveryFeatures => "1.0",
             MessageFamilies::Basicmessage => "1.0",
+            MessageFamilies::CoordinateMediation => "1.0",
+            MessageFamilies::MessagePickup => "1.0",
+            MessageFamilies::MessagePickupV2 => "2.0",
             MessageFamilies::Unknown(_) => "1.0"
         }
     }
@@ -38,21 +54,48 @@ impl MessageFamilies {
         format!("{};spec/{}/{}", Self::DID, self.to_string(), self.version().to_string())
     }
 
+    /**
+    Whether `pid` identifies this family and version, either as `did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/{family}/{version}`
+    or as `https://didcomm.org/{family}/{version}`.
+     */
+    pub fn is_protocol_id(&self, pid: &str) -> bool {
+        pid == self.id() || pid == format!("{}/{}/{}", Self::DIDCOMM_ORG, self.to_string(), self.version())
+    }
+
     pub fn actors(&self) -> Option<(Actors, Actors)> {
         match self {
             MessageFamilies::Routing => None,
             MessageFamilies::Connections => Some((Actors::Inviter, Actors::Invitee)),
+            MessageFamilies::DidExchange => Some((Actors::Inviter, Actors::Invitee)),
+            MessageFamilies::OutOfBand => Some((Actors::Sender, Actors::Receiver)),
             MessageFamilies::Notification => None,
             MessageFamilies::Signature => None,
             MessageFamilies::CredentialIssuance => Some((Actors::Issuer, Actors::Holder)),
+            MessageFamilies::CredentialIssuanceV2 => Some((Actors::Issuer, Actors::Holder)),
             MessageFamilies::ReportProblem => None,
             MessageFamilies::PresentProof => Some((Actors::Prover, Actors::Verifier)),
+            MessageFamilies::PresentProofV2 => Some((Actors::Prover, Actors::Verifier)),
             MessageFamilies::TrustPing => Some((Actors::Sender, Actors::Receiver)),
             MessageFamilies::DiscoveryFeatures => Some((Actors::Sender, Actors::Receiver)),
             MessageFamilies::Basicmessage => Some((Actors::Sender, Actors::Receiver)),
+            MessageFamilies::CoordinateMediation => None,
+            MessageFamilies::MessagePickup => None,
+            MessageFamilies::MessagePickupV2 => None,
             MessageFamilies::Unknown(_) => None
         }
     }
+
+    pub fn for_version(self, version: ProtocolVersion) -> MessageFamilies {
+        match (self, version) {
+            (MessageFamilies::CredentialIssuance, ProtocolVersion::V2) => MessageFamilies::CredentialIssuanceV2,
+            (MessageFamilies::CredentialIssuanceV2, ProtocolVersion::V1) => MessageFamilies::CredentialIssuance,
+            (MessageFamilies::PresentProof, ProtocolVersion::V2) => MessageFamilies::PresentProofV2,
+            (MessageFamilies::PresentProofV2, ProtocolVersion::V1) => MessageFamilies::PresentProof,
+            (MessageFamilies::MessagePickup, ProtocolVersion::V2) => MessageFamilies::MessagePickupV2,
+            (MessageFamilies::MessagePickupV2, ProtocolVersion::V1) => MessageFamilies::MessagePickup,
+            (family, _) => family
+        }
+    }
 }
 
 impl From<String> for MessageFamilies {
@@ -60,6 +103,8 @@ impl From<String> for MessageFamilies {
         match family.as_str() {
             "routing" => MessageFamilies::Routing,
             "connections" => MessageFamilies::Connections,
+            "didexchange" => MessageFamilies::DidExchange,
+            "out-of-band" => MessageFamilies::OutOfBand,
             "signature" => MessageFamilies::Signature,
             "notification" => MessageFamilies::Notification,
             "issue-credential" => MessageFamilies::CredentialIssuance,
@@ -68,6 +113,8 @@ impl From<String> for MessageFamilies {
             "trust_ping" => MessageFamilies::TrustPing,
             "discover-features" => MessageFamilies::DiscoveryFeatures,
             "basicmessage" => MessageFamilies::Basicmessage,
+            "coordinate-mediation" => MessageFamilies::CoordinateMediation,
+            "messagepickup" => MessageFamilies::MessagePickup,
             family @ _ => MessageFamilies::Unknown(family.to_string())
         }
     }
@@ -78,14 +125,21 @@ impl ::std::string::ToString for MessageFamilies {
         match self {
             MessageFamilies::Routing => "routing".to_string(),
             MessageFamilies::Connections => "connections".to_string(),
+            MessageFamilies::DidExchange => "didexchange".to_string(),
+            MessageFamilies::OutOfBand => "out-of-band".to_string(),
             MessageFamilies::Notification => "notification".to_string(),
             MessageFamilies::Signature => "signature".to_string(),
             MessageFamilies::CredentialIssuance => "issue-credential".to_string(),
+            MessageFamilies::CredentialIssuanceV2 => "issue-credential".to_string(),
             MessageFamilies::ReportProblem => "report-problem".to_string(),
             MessageFamilies::PresentProof => "present-proof".to_string(),
+            MessageFamilies::PresentProofV2 => "present-proof".to_string(),
             MessageFamilies::TrustPing => "trust_ping".to_string(),
             MessageFamilies::DiscoveryFeatures => "discover-features".to_string(),
             MessageFamilies::Basicmessage => "basicmessage".to_string(),
+            MessageFamilies::CoordinateMediation => "coordinate-mediation".to_string(),
+            MessageFamilies::MessagePickup => "messagepickup".to_string(),
+            MessageFamilies::MessagePickupV2 => "messagepickup".to_string(),
             MessageFamilies::Unknown(family) => family.to_string()
         }
     }
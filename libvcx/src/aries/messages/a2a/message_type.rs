@@ -3,6 +3,7 @@ use serde_json::Value;
 
 use agency_client::message_type::parse_message_type;
 use crate::aries::messages::a2a::message_family::MessageFamilies;
+use crate::aries::messages::a2a::protocol_version::ProtocolVersion;
 
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct MessageType {
@@ -33,7 +34,7 @@ impl<'de> Deserialize<'de> for MessageType {
                 let (did, family, version, type_) = parse_message_type(type_).map_err(de::Error::custom)?;
                 Ok(MessageType {
                     did,
-                    family: MessageFamilies::from(family),
+                    family: MessageFamilies::from(family).for_version(ProtocolVersion::from_version(&version)),
                     version,
                     type_,
                 })
@@ -1,6 +1,7 @@
 pub mod message_family;
 pub mod message_type;
 pub mod protocol_registry;
+pub mod protocol_version;
 
 use log;
 use self::message_type::MessageType;
@@ -9,10 +10,14 @@ use self::message_family::MessageFamilies;
 use serde::{de, Deserialize, Deserializer, ser, Serialize, Serializer};
 use serde_json::Value;
 
-use crate::aries::messages::connection::invite::Invitation;
+use crate::aries::messages::connection::invite::{Invitation, PublicInvitation};
 use crate::aries::messages::connection::request::Request;
 use crate::aries::messages::connection::response::SignedResponse;
 use crate::aries::messages::connection::problem_report::ProblemReport as ConnectionProblemReport;
+use crate::aries::messages::did_exchange::complete::Complete as DidExchangeComplete;
+use crate::aries::messages::did_exchange::request::Request as DidExchangeRequest;
+use crate::aries::messages::did_exchange::response::Response as DidExchangeResponse;
+use crate::aries::messages::out_of_band::invitation::OutOfBandInvitation;
 use crate::aries::messages::trust_ping::ping::Ping;
 use crate::aries::messages::trust_ping::ping_response::PingResponse;
 use crate::aries::messages::forward::Forward;
@@ -33,6 +38,22 @@ use crate::aries::messages::discovery::disclose::Disclose;
 
 use crate::aries::messages::basic_message::message::BasicMessage;
 
+use crate::aries::messages::mediation::mediate_request::MediateRequest;
+use crate::aries::messages::mediation::mediate_grant::MediateGrant;
+use crate::aries::messages::mediation::mediate_deny::MediateDeny;
+use crate::aries::messages::mediation::keylist_update::KeylistUpdate;
+use crate::aries::messages::mediation::keylist_update_response::KeylistUpdateResponse;
+use crate::aries::messages::mediation::keylist_query::KeylistQuery;
+use crate::aries::messages::mediation::keylist::Keylist;
+use crate::aries::messages::pickup::status_request::StatusRequest;
+use crate::aries::messages::pickup::status::Status;
+use crate::aries::messages::pickup::batch_pickup::BatchPickup;
+use crate::aries::messages::pickup::batch::Batch;
+use crate::aries::messages::pickup::delivery_request::DeliveryRequest;
+use crate::aries::messages::pickup::delivery::Delivery;
+use crate::aries::messages::pickup::messages_received::MessagesReceived;
+use crate::aries::messages::pickup::live_delivery_change::LiveDeliveryChange;
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum A2AMessage {
     /// routing
@@ -40,10 +61,20 @@ pub enum A2AMessage {
 
     /// DID Exchange
     ConnectionInvitation(Invitation),
+    ConnectionInvitationPublic(PublicInvitation),
     ConnectionRequest(Request),
     ConnectionResponse(SignedResponse),
     ConnectionProblemReport(ConnectionProblemReport),
 
+    /// didexchange
+    DidExchangeRequest(DidExchangeRequest),
+    DidExchangeResponse(DidExchangeResponse),
+    DidExchangeComplete(DidExchangeComplete),
+    DidExchangeProblemReport(ConnectionProblemReport),
+
+    /// out-of-band
+    OutOfBandInvitation(OutOfBandInvitation),
+
     /// trust ping
     Ping(Ping),
     PingResponse(PingResponse),
@@ -58,12 +89,14 @@ pub enum A2AMessage {
     CredentialRequest(CredentialRequest),
     Credential(Credential),
     CredentialAck(Ack),
+    CredentialAckV2(Ack),
 
     /// proof presentation
     PresentationProposal(PresentationProposal),
     PresentationRequest(PresentationRequest),
     Presentation(Presentation),
     PresentationAck(Ack),
+    PresentationAckV2(Ack),
 
     /// discovery features
     Query(Query),
@@ -72,6 +105,27 @@ pub enum A2AMessage {
     /// basic message
     BasicMessage(BasicMessage),
 
+    /// coordinate mediation
+    MediateRequest(MediateRequest),
+    MediateGrant(MediateGrant),
+    MediateDeny(MediateDeny),
+    KeylistUpdate(KeylistUpdate),
+    KeylistUpdateResponse(KeylistUpdateResponse),
+    KeylistQuery(KeylistQuery),
+    Keylist(Keylist),
+
+    /// message pickup
+    PickupStatusRequest(StatusRequest),
+    PickupStatus(Status),
+    PickupBatchPickup(BatchPickup),
+    PickupBatch(Batch),
+    PickupStatusRequestV2(StatusRequest),
+    PickupStatusV2(Status),
+    PickupDeliveryRequest(DeliveryRequest),
+    PickupDelivery(Delivery),
+    PickupMessagesReceived(MessagesReceived),
+    PickupLiveDeliveryChange(LiveDeliveryChange),
+
     /// Any Raw Message
     Generic(Value),
 }
@@ -99,6 +153,11 @@ impl<'de> Deserialize<'de> for A2AMessage {
                     .map(|msg| A2AMessage::Forward(msg))
                     .map_err(de::Error::custom)
             }
+            (MessageFamilies::Connections, A2AMessage::CONNECTION_INVITATION) if value.get("did").is_some() => {
+                PublicInvitation::deserialize(value)
+                    .map(|msg| A2AMessage::ConnectionInvitationPublic(msg))
+                    .map_err(de::Error::custom)
+            }
             (MessageFamilies::Connections, A2AMessage::CONNECTION_INVITATION) => {
                 Invitation::deserialize(value)
                     .map(|msg| A2AMessage::ConnectionInvitation(msg))
@@ -114,6 +173,31 @@ impl<'de> Deserialize<'de> for A2AMessage {
                     .map(|msg| A2AMessage::ConnectionResponse(msg))
                     .map_err(de::Error::custom)
             }
+            (MessageFamilies::DidExchange, A2AMessage::DID_EXCHANGE_REQUEST) => {
+                DidExchangeRequest::deserialize(value)
+                    .map(|msg| A2AMessage::DidExchangeRequest(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::DidExchange, A2AMessage::DID_EXCHANGE_RESPONSE) => {
+                DidExchangeResponse::deserialize(value)
+                    .map(|msg| A2AMessage::DidExchangeResponse(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::DidExchange, A2AMessage::DID_EXCHANGE_COMPLETE) => {
+                DidExchangeComplete::deserialize(value)
+                    .map(|msg| A2AMessage::DidExchangeComplete(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::DidExchange, A2AMessage::DID_EXCHANGE_PROBLEM_REPORT) => {
+                ConnectionProblemReport::deserialize(value)
+                    .map(|msg| A2AMessage::DidExchangeProblemReport(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::OutOfBand, A2AMessage::OUT_OF_BAND_INVITATION) => {
+                OutOfBandInvitation::deserialize(value)
+                    .map(|msg| A2AMessage::OutOfBandInvitation(msg))
+                    .map_err(de::Error::custom)
+            }
             (MessageFamilies::TrustPing, A2AMessage::PING) => {
                 Ping::deserialize(value)
                     .map(|msg| A2AMessage::Ping(msg))
@@ -139,22 +223,22 @@ impl<'de> Deserialize<'de> for A2AMessage {
                     .map(|msg| A2AMessage::CommonProblemReport(msg))
                     .map_err(de::Error::custom)
             }
-            (MessageFamilies::CredentialIssuance, A2AMessage::CREDENTIAL) => {
+            (MessageFamilies::CredentialIssuance | MessageFamilies::CredentialIssuanceV2, A2AMessage::CREDENTIAL) => {
                 Credential::deserialize(value)
                     .map(|msg| A2AMessage::Credential(msg))
                     .map_err(de::Error::custom)
             }
-            (MessageFamilies::CredentialIssuance, A2AMessage::PROPOSE_CREDENTIAL) => {
+            (MessageFamilies::CredentialIssuance | MessageFamilies::CredentialIssuanceV2, A2AMessage::PROPOSE_CREDENTIAL) => {
                 CredentialProposal::deserialize(value)
                     .map(|msg| A2AMessage::CredentialProposal(msg))
                     .map_err(de::Error::custom)
             }
-            (MessageFamilies::CredentialIssuance, A2AMessage::CREDENTIAL_OFFER) => {
+            (MessageFamilies::CredentialIssuance | MessageFamilies::CredentialIssuanceV2, A2AMessage::CREDENTIAL_OFFER) => {
                 CredentialOffer::deserialize(value)
                     .map(|msg| A2AMessage::CredentialOffer(msg))
                     .map_err(de::Error::custom)
             }
-            (MessageFamilies::CredentialIssuance, A2AMessage::REQUEST_CREDENTIAL) => {
+            (MessageFamilies::CredentialIssuance | MessageFamilies::CredentialIssuanceV2, A2AMessage::REQUEST_CREDENTIAL) => {
                 CredentialRequest::deserialize(value)
                     .map(|msg| A2AMessage::CredentialRequest(msg))
                     .map_err(de::Error::custom)
@@ -164,17 +248,22 @@ impl<'de> Deserialize<'de> for A2AMessage {
                     .map(|msg| A2AMessage::CredentialAck(msg))
                     .map_err(de::Error::custom)
             }
-            (MessageFamilies::PresentProof, A2AMessage::PROPOSE_PRESENTATION) => {
+            (MessageFamilies::CredentialIssuanceV2, A2AMessage::ACK) => {
+                Ack::deserialize(value)
+                    .map(|msg| A2AMessage::CredentialAckV2(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::PresentProof | MessageFamilies::PresentProofV2, A2AMessage::PROPOSE_PRESENTATION) => {
                 PresentationProposal::deserialize(value)
                     .map(|msg| A2AMessage::PresentationProposal(msg))
                     .map_err(de::Error::custom)
             }
-            (MessageFamilies::PresentProof, A2AMessage::REQUEST_PRESENTATION) => {
+            (MessageFamilies::PresentProof | MessageFamilies::PresentProofV2, A2AMessage::REQUEST_PRESENTATION) => {
                 PresentationRequest::deserialize(value)
                     .map(|msg| A2AMessage::PresentationRequest(msg))
                     .map_err(de::Error::custom)
             }
-            (MessageFamilies::PresentProof, A2AMessage::PRESENTATION) => {
+            (MessageFamilies::PresentProof | MessageFamilies::PresentProofV2, A2AMessage::PRESENTATION) => {
                 Presentation::deserialize(value)
                     .map(|msg| A2AMessage::Presentation(msg))
                     .map_err(de::Error::custom)
@@ -184,6 +273,11 @@ impl<'de> Deserialize<'de> for A2AMessage {
                     .map(|msg| A2AMessage::PresentationAck(msg))
                     .map_err(de::Error::custom)
             }
+            (MessageFamilies::PresentProofV2, A2AMessage::ACK) => {
+                Ack::deserialize(value)
+                    .map(|msg| A2AMessage::PresentationAckV2(msg))
+                    .map_err(de::Error::custom)
+            }
             (MessageFamilies::DiscoveryFeatures, A2AMessage::QUERY) => {
                 Query::deserialize(value)
                     .map(|msg| A2AMessage::Query(msg))
@@ -199,6 +293,91 @@ impl<'de> Deserialize<'de> for A2AMessage {
                     .map(|msg| A2AMessage::BasicMessage(msg))
                     .map_err(de::Error::custom)
             }
+            (MessageFamilies::CoordinateMediation, A2AMessage::MEDIATE_REQUEST) => {
+                MediateRequest::deserialize(value)
+                    .map(|msg| A2AMessage::MediateRequest(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::CoordinateMediation, A2AMessage::MEDIATE_GRANT) => {
+                MediateGrant::deserialize(value)
+                    .map(|msg| A2AMessage::MediateGrant(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::CoordinateMediation, A2AMessage::MEDIATE_DENY) => {
+                MediateDeny::deserialize(value)
+                    .map(|msg| A2AMessage::MediateDeny(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::CoordinateMediation, A2AMessage::KEYLIST_UPDATE) => {
+                KeylistUpdate::deserialize(value)
+                    .map(|msg| A2AMessage::KeylistUpdate(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::CoordinateMediation, A2AMessage::KEYLIST_UPDATE_RESPONSE) => {
+                KeylistUpdateResponse::deserialize(value)
+                    .map(|msg| A2AMessage::KeylistUpdateResponse(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::CoordinateMediation, A2AMessage::KEYLIST_QUERY) => {
+                KeylistQuery::deserialize(value)
+                    .map(|msg| A2AMessage::KeylistQuery(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::CoordinateMediation, A2AMessage::KEYLIST) => {
+                Keylist::deserialize(value)
+                    .map(|msg| A2AMessage::Keylist(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::MessagePickup, A2AMessage::PICKUP_STATUS_REQUEST) => {
+                StatusRequest::deserialize(value)
+                    .map(|msg| A2AMessage::PickupStatusRequest(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::MessagePickup, A2AMessage::PICKUP_STATUS) => {
+                Status::deserialize(value)
+                    .map(|msg| A2AMessage::PickupStatus(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::MessagePickup, A2AMessage::PICKUP_BATCH_PICKUP) => {
+                BatchPickup::deserialize(value)
+                    .map(|msg| A2AMessage::PickupBatchPickup(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::MessagePickup, A2AMessage::PICKUP_BATCH) => {
+                Batch::deserialize(value)
+                    .map(|msg| A2AMessage::PickupBatch(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::MessagePickupV2, A2AMessage::PICKUP_STATUS_REQUEST) => {
+                StatusRequest::deserialize(value)
+                    .map(|msg| A2AMessage::PickupStatusRequestV2(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::MessagePickupV2, A2AMessage::PICKUP_STATUS) => {
+                Status::deserialize(value)
+                    .map(|msg| A2AMessage::PickupStatusV2(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::MessagePickupV2, A2AMessage::PICKUP_DELIVERY_REQUEST) => {
+                DeliveryRequest::deserialize(value)
+                    .map(|msg| A2AMessage::PickupDeliveryRequest(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::MessagePickupV2, A2AMessage::PICKUP_DELIVERY) => {
+                Delivery::deserialize(value)
+                    .map(|msg| A2AMessage::PickupDelivery(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::MessagePickupV2, A2AMessage::PICKUP_MESSAGES_RECEIVED) => {
+                MessagesReceived::deserialize(value)
+                    .map(|msg| A2AMessage::PickupMessagesReceived(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::MessagePickupV2, A2AMessage::PICKUP_LIVE_DELIVERY_CHANGE) => {
+                LiveDeliveryChange::deserialize(value)
+                    .map(|msg| A2AMessage::PickupLiveDeliveryChange(msg))
+                    .map_err(de::Error::custom)
+            }
             (_, other_type) => {
                 warn!("Unexpected @type field structure: {}", other_type);
                 Ok(A2AMessage::Generic(value))
@@ -219,25 +398,50 @@ impl Serialize for A2AMessage {
         let value = match self {
             A2AMessage::Forward(msg) => set_a2a_message_type(msg, MessageFamilies::Routing, A2AMessage::FORWARD),
             A2AMessage::ConnectionInvitation(msg) => set_a2a_message_type(msg, MessageFamilies::Connections, A2AMessage::CONNECTION_INVITATION),
+            A2AMessage::ConnectionInvitationPublic(msg) => set_a2a_message_type(msg, MessageFamilies::Connections, A2AMessage::CONNECTION_INVITATION),
             A2AMessage::ConnectionRequest(msg) => set_a2a_message_type(msg, MessageFamilies::Connections, A2AMessage::CONNECTION_REQUEST),
             A2AMessage::ConnectionResponse(msg) => set_a2a_message_type(msg, MessageFamilies::Connections, A2AMessage::CONNECTION_RESPONSE),
             A2AMessage::ConnectionProblemReport(msg) => set_a2a_message_type(msg, MessageFamilies::Connections, A2AMessage::CONNECTION_PROBLEM_REPORT),
+            A2AMessage::DidExchangeRequest(msg) => set_a2a_message_type(msg, MessageFamilies::DidExchange, A2AMessage::DID_EXCHANGE_REQUEST),
+            A2AMessage::DidExchangeResponse(msg) => set_a2a_message_type(msg, MessageFamilies::DidExchange, A2AMessage::DID_EXCHANGE_RESPONSE),
+            A2AMessage::DidExchangeComplete(msg) => set_a2a_message_type(msg, MessageFamilies::DidExchange, A2AMessage::DID_EXCHANGE_COMPLETE),
+            A2AMessage::DidExchangeProblemReport(msg) => set_a2a_message_type(msg, MessageFamilies::DidExchange, A2AMessage::DID_EXCHANGE_PROBLEM_REPORT),
+            A2AMessage::OutOfBandInvitation(msg) => set_a2a_message_type(msg, MessageFamilies::OutOfBand, A2AMessage::OUT_OF_BAND_INVITATION),
             A2AMessage::Ping(msg) => set_a2a_message_type(msg, MessageFamilies::TrustPing, A2AMessage::PING),
             A2AMessage::PingResponse(msg) => set_a2a_message_type(msg, MessageFamilies::TrustPing, A2AMessage::PING_RESPONSE),
             A2AMessage::Ack(msg) => set_a2a_message_type(msg, MessageFamilies::Notification, A2AMessage::ACK),
             A2AMessage::CommonProblemReport(msg) => set_a2a_message_type(msg, MessageFamilies::ReportProblem, A2AMessage::PROBLEM_REPORT),
-            A2AMessage::CredentialOffer(msg) => set_a2a_message_type(msg, MessageFamilies::CredentialIssuance, A2AMessage::CREDENTIAL_OFFER),
-            A2AMessage::Credential(msg) => set_a2a_message_type(msg, MessageFamilies::CredentialIssuance, A2AMessage::CREDENTIAL),
-            A2AMessage::CredentialProposal(msg) => set_a2a_message_type(msg, MessageFamilies::CredentialIssuance, A2AMessage::PROPOSE_CREDENTIAL),
-            A2AMessage::CredentialRequest(msg) => set_a2a_message_type(msg, MessageFamilies::CredentialIssuance, A2AMessage::REQUEST_CREDENTIAL),
+            A2AMessage::CredentialOffer(msg) => set_a2a_message_type(msg, MessageFamilies::CredentialIssuance.for_version(msg.protocol_version()), A2AMessage::CREDENTIAL_OFFER),
+            A2AMessage::Credential(msg) => set_a2a_message_type(msg, MessageFamilies::CredentialIssuance.for_version(msg.protocol_version()), A2AMessage::CREDENTIAL),
+            A2AMessage::CredentialProposal(msg) => set_a2a_message_type(msg, MessageFamilies::CredentialIssuance.for_version(msg.protocol_version()), A2AMessage::PROPOSE_CREDENTIAL),
+            A2AMessage::CredentialRequest(msg) => set_a2a_message_type(msg, MessageFamilies::CredentialIssuance.for_version(msg.protocol_version()), A2AMessage::REQUEST_CREDENTIAL),
             A2AMessage::CredentialAck(msg) => set_a2a_message_type(msg, MessageFamilies::CredentialIssuance, A2AMessage::ACK),
-            A2AMessage::PresentationProposal(msg) => set_a2a_message_type(msg, MessageFamilies::PresentProof, A2AMessage::PROPOSE_PRESENTATION),
-            A2AMessage::PresentationRequest(msg) => set_a2a_message_type(msg, MessageFamilies::PresentProof, A2AMessage::REQUEST_PRESENTATION),
-            A2AMessage::Presentation(msg) => set_a2a_message_type(msg, MessageFamilies::PresentProof, A2AMessage::PRESENTATION),
+            A2AMessage::CredentialAckV2(msg) => set_a2a_message_type(msg, MessageFamilies::CredentialIssuanceV2, A2AMessage::ACK),
+            A2AMessage::PresentationProposal(msg) => set_a2a_message_type(msg, MessageFamilies::PresentProof.for_version(msg.protocol_version()), A2AMessage::PROPOSE_PRESENTATION),
+            A2AMessage::PresentationRequest(msg) => set_a2a_message_type(msg, MessageFamilies::PresentProof.for_version(msg.protocol_version()), A2AMessage::REQUEST_PRESENTATION),
+            A2AMessage::Presentation(msg) => set_a2a_message_type(msg, MessageFamilies::PresentProof.for_version(msg.protocol_version()), A2AMessage::PRESENTATION),
             A2AMessage::PresentationAck(msg) => set_a2a_message_type(msg, MessageFamilies::PresentProof, A2AMessage::ACK),
+            A2AMessage::PresentationAckV2(msg) => set_a2a_message_type(msg, MessageFamilies::PresentProofV2, A2AMessage::ACK),
             A2AMessage::Query(msg) => set_a2a_message_type(msg, MessageFamilies::DiscoveryFeatures, A2AMessage::QUERY),
             A2AMessage::Disclose(msg) => set_a2a_message_type(msg, MessageFamilies::DiscoveryFeatures, A2AMessage::DISCLOSE),
             A2AMessage::BasicMessage(msg) => set_a2a_message_type(msg, MessageFamilies::Basicmessage, A2AMessage::BASIC_MESSAGE),
+            A2AMessage::MediateRequest(msg) => set_a2a_message_type(msg, MessageFamilies::CoordinateMediation, A2AMessage::MEDIATE_REQUEST),
+            A2AMessage::MediateGrant(msg) => set_a2a_message_type(msg, MessageFamilies::CoordinateMediation, A2AMessage::MEDIATE_GRANT),
+            A2AMessage::MediateDeny(msg) => set_a2a_message_type(msg, MessageFamilies::CoordinateMediation, A2AMessage::MEDIATE_DENY),
+            A2AMessage::KeylistUpdate(msg) => set_a2a_message_type(msg, MessageFamilies::CoordinateMediation, A2AMessage::KEYLIST_UPDATE),
+            A2AMessage::KeylistUpdateResponse(msg) => set_a2a_message_type(msg, MessageFamilies::CoordinateMediation, A2AMessage::KEYLIST_UPDATE_RESPONSE),
+            A2AMessage::KeylistQuery(msg) => set_a2a_message_type(msg, MessageFamilies::CoordinateMediation, A2AMessage::KEYLIST_QUERY),
+            A2AMessage::Keylist(msg) => set_a2a_message_type(msg, MessageFamilies::CoordinateMediation, A2AMessage::KEYLIST),
+            A2AMessage::PickupStatusRequest(msg) => set_a2a_message_type(msg, MessageFamilies::MessagePickup, A2AMessage::PICKUP_STATUS_REQUEST),
+            A2AMessage::PickupStatus(msg) => set_a2a_message_type(msg, MessageFamilies::MessagePickup, A2AMessage::PICKUP_STATUS),
+            A2AMessage::PickupBatchPickup(msg) => set_a2a_message_type(msg, MessageFamilies::MessagePickup, A2AMessage::PICKUP_BATCH_PICKUP),
+            A2AMessage::PickupBatch(msg) => set_a2a_message_type(msg, MessageFamilies::MessagePickup, A2AMessage::PICKUP_BATCH),
+            A2AMessage::PickupStatusRequestV2(msg) => set_a2a_message_type(msg, MessageFamilies::MessagePickupV2, A2AMessage::PICKUP_STATUS_REQUEST),
+            A2AMessage::PickupStatusV2(msg) => set_a2a_message_type(msg, MessageFamilies::MessagePickupV2, A2AMessage::PICKUP_STATUS),
+            A2AMessage::PickupDeliveryRequest(msg) => set_a2a_message_type(msg, MessageFamilies::MessagePickupV2, A2AMessage::PICKUP_DELIVERY_REQUEST),
+            A2AMessage::PickupDelivery(msg) => set_a2a_message_type(msg, MessageFamilies::MessagePickupV2, A2AMessage::PICKUP_DELIVERY),
+            A2AMessage::PickupMessagesReceived(msg) => set_a2a_message_type(msg, MessageFamilies::MessagePickupV2, A2AMessage::PICKUP_MESSAGES_RECEIVED),
+            A2AMessage::PickupLiveDeliveryChange(msg) => set_a2a_message_type(msg, MessageFamilies::MessagePickupV2, A2AMessage::PICKUP_LIVE_DELIVERY_CHANGE),
             A2AMessage::Generic(msg) => Ok(msg.clone())
         }.map_err(ser::Error::custom)?;
 
@@ -278,6 +482,11 @@ impl A2AMessage {
     const CONNECTION_REQUEST: &'static str = "request";
     const CONNECTION_RESPONSE: &'static str = "response";
     const CONNECTION_PROBLEM_REPORT: &'static str = "problem_report";
+    const DID_EXCHANGE_REQUEST: &'static str = "request";
+    const DID_EXCHANGE_RESPONSE: &'static str = "response";
+    const DID_EXCHANGE_COMPLETE: &'static str = "complete";
+    const DID_EXCHANGE_PROBLEM_REPORT: &'static str = "problem_report";
+    const OUT_OF_BAND_INVITATION: &'static str = "invitation";
     const PING: &'static str = "ping";
     const PING_RESPONSE: &'static str = "ping_response";
     const ACK: &'static str = "ack";
@@ -292,6 +501,21 @@ impl A2AMessage {
     const QUERY: &'static str = "query";
     const DISCLOSE: &'static str = "disclose";
     const BASIC_MESSAGE: &'static str = "message";
+    const MEDIATE_REQUEST: &'static str = "mediate-request";
+    const MEDIATE_GRANT: &'static str = "mediate-grant";
+    const MEDIATE_DENY: &'static str = "mediate-deny";
+    const KEYLIST_UPDATE: &'static str = "keylist-update";
+    const KEYLIST_UPDATE_RESPONSE: &'static str = "keylist-update-response";
+    const KEYLIST_QUERY: &'static str = "keylist-query";
+    const KEYLIST: &'static str = "keylist";
+    const PICKUP_STATUS_REQUEST: &'static str = "status-request";
+    const PICKUP_STATUS: &'static str = "status";
+    const PICKUP_BATCH_PICKUP: &'static str = "batch-pickup";
+    const PICKUP_BATCH: &'static str = "batch";
+    const PICKUP_DELIVERY_REQUEST: &'static str = "delivery-request";
+    const PICKUP_DELIVERY: &'static str = "delivery";
+    const PICKUP_MESSAGES_RECEIVED: &'static str = "messages-received";
+    const PICKUP_LIVE_DELIVERY_CHANGE: &'static str = "live-delivery-change";
 }
 
 #[macro_export]
@@ -21,12 +21,19 @@ impl ProtocolRegistry {
                 family @ MessageFamilies::ReportProblem |
                 family @ MessageFamilies::Notification |
                 family @ MessageFamilies::Connections |
+                family @ MessageFamilies::DidExchange |
+                family @ MessageFamilies::OutOfBand |
                 family @ MessageFamilies::CredentialIssuance |
+                family @ MessageFamilies::CredentialIssuanceV2 |
                 family @ MessageFamilies::PresentProof |
+                family @ MessageFamilies::PresentProofV2 |
                 family @ MessageFamilies::TrustPing |
                 family @ MessageFamilies::Basicmessage |
                 family @ MessageFamilies::DiscoveryFeatures => registry.add_protocol(&actors, family),
                 MessageFamilies::Signature => {}
+                MessageFamilies::CoordinateMediation => {}
+                MessageFamilies::MessagePickup => {}
+                MessageFamilies::MessagePickupV2 => {}
                 MessageFamilies::Unknown(_) => {}
             }
         }
@@ -189,6 +196,28 @@ pub mod tests {
         assert_eq!(expected_protocols, protocols);
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_get_protocols_for_query_works_for_protocol_versions() {
+        let _setup = SetupEmpty::init();
+
+        let registry: ProtocolRegistry = ProtocolRegistry::init();
+
+        let protocols = registry.get_protocols_for_query(Some("did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/issue-credential"));
+        let expected_protocols = vec![
+            ProtocolDescriptor { pid: MessageFamilies::CredentialIssuance.id(), roles: None },
+            ProtocolDescriptor { pid: MessageFamilies::CredentialIssuanceV2.id(), roles: None },
+        ];
+        assert_eq!(expected_protocols, protocols);
+        assert_eq!("did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/issue-credential/2.0", MessageFamilies::CredentialIssuanceV2.id());
+
+        let protocols = registry.get_protocols_for_query(Some("did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/present-proof/2.0"));
+        let expected_protocols = vec![
+            ProtocolDescriptor { pid: MessageFamilies::PresentProofV2.id(), roles: None },
+        ];
+        assert_eq!(expected_protocols, protocols);
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_get_protocols_for_query_works_for_limited_actors() {
@@ -0,0 +1,67 @@
+use std::str::FromStr;
+
+use crate::error::prelude::*;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub enum ProtocolVersion {
+    V1,
+    V2,
+}
+
+impl ProtocolVersion {
+    pub fn from_version(version: &str) -> ProtocolVersion {
+        match version.split('.').next() {
+            Some("2") => ProtocolVersion::V2,
+            _ => ProtocolVersion::V1
+        }
+    }
+}
+
+impl FromStr for ProtocolVersion {
+    type Err = VcxError;
+
+    fn from_str(version: &str) -> VcxResult<ProtocolVersion> {
+        match version {
+            "1.0" => Ok(ProtocolVersion::V1),
+            "2.0" => Ok(ProtocolVersion::V2),
+            _ => Err(VcxError::from_msg(VcxErrorKind::InvalidOption, format!("Unsupported protocol version: {}", version)))
+        }
+    }
+}
+
+impl Default for ProtocolVersion {
+    fn default() -> ProtocolVersion {
+        ProtocolVersion::V1
+    }
+}
+
+impl ::std::string::ToString for ProtocolVersion {
+    fn to_string(&self) -> String {
+        match self {
+            ProtocolVersion::V1 => "1.0".to_string(),
+            ProtocolVersion::V2 => "2.0".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_protocol_version_from_version_works() {
+        assert_eq!(ProtocolVersion::V1, ProtocolVersion::from_version("1.0"));
+        assert_eq!(ProtocolVersion::V1, ProtocolVersion::from_version("1.1"));
+        assert_eq!(ProtocolVersion::V2, ProtocolVersion::from_version("2.0"));
+        assert_eq!(ProtocolVersion::V1, ProtocolVersion::from_version("unknown"));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_protocol_version_from_str_works() {
+        assert_eq!(ProtocolVersion::V1, ProtocolVersion::from_str("1.0").unwrap());
+        assert_eq!(ProtocolVersion::V2, ProtocolVersion::from_str("2.0").unwrap());
+        assert_eq!(VcxErrorKind::InvalidOption, ProtocolVersion::from_str("3.0").unwrap_err().kind());
+    }
+}
@@ -1,5 +1,6 @@
 use std::str::from_utf8;
 
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use serde_json;
 
 use crate::error::{VcxError, VcxErrorKind, VcxResult};
@@ -12,6 +13,10 @@ impl Attachments {
         Attachments::default()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
     pub fn get(&self) -> Option<&Attachment> {
         self.0.get(0)
     }
@@ -36,6 +41,33 @@ impl Attachments {
             _ => return Err(VcxError::from_msg(VcxErrorKind::InvalidJson, "Unsupported Attachment type"))
         }
     }
+
+    pub fn ids(&self) -> Vec<AttachmentId> {
+        self.0.iter()
+            .filter_map(|attachment| match attachment {
+                Attachment::JSON(ref attach) => Some(attach.id.clone()),
+                Attachment::Blank => None
+            })
+            .collect()
+    }
+
+    pub fn content_for_format(&self, formats: &[AttachmentFormat], format: &str) -> VcxResult<String> {
+        if formats.is_empty() {
+            return self.content();
+        }
+
+        let attach_id = formats.iter()
+            .find(|attachment_format| attachment_format.format == format)
+            .map(|attachment_format| AttachmentId::from(attachment_format.attach_id.as_str()))
+            .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("No attachment of supported format found: {}", format)))?;
+
+        self.0.iter()
+            .find_map(|attachment| match attachment {
+                Attachment::JSON(ref attach) if attach.id == attach_id => Some(attach.get_data()),
+                _ => None
+            })
+            .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Attachment referenced by format not found: {}", format)))?
+    }
 }
 
 #[serde(tag = "mime-type")]
@@ -53,20 +85,101 @@ pub struct Json {
     data: AttachmentData,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum AttachmentId {
-    #[serde(rename = "libindy-cred-offer-0")]
     CredentialOffer,
-    #[serde(rename = "libindy-cred-request-0")]
     CredentialRequest,
-    #[serde(rename = "libindy-cred-0")]
     Credential,
-    #[serde(rename = "libindy-request-presentation-0")]
     PresentationRequest,
-    #[serde(rename = "libindy-presentation-0")]
     Presentation,
+    Other(String),
+}
+
+impl From<&str> for AttachmentId {
+    fn from(id: &str) -> Self {
+        match id {
+            "libindy-cred-offer-0" => AttachmentId::CredentialOffer,
+            "libindy-cred-request-0" => AttachmentId::CredentialRequest,
+            "libindy-cred-0" => AttachmentId::Credential,
+            "libindy-request-presentation-0" => AttachmentId::PresentationRequest,
+            "libindy-presentation-0" => AttachmentId::Presentation,
+            other => AttachmentId::Other(other.to_string())
+        }
+    }
+}
+
+impl ::std::string::ToString for AttachmentId {
+    fn to_string(&self) -> String {
+        match self {
+            AttachmentId::CredentialOffer => "libindy-cred-offer-0".to_string(),
+            AttachmentId::CredentialRequest => "libindy-cred-request-0".to_string(),
+            AttachmentId::Credential => "libindy-cred-0".to_string(),
+            AttachmentId::PresentationRequest => "libindy-request-presentation-0".to_string(),
+            AttachmentId::Presentation => "libindy-presentation-0".to_string(),
+            AttachmentId::Other(id) => id.to_string()
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for AttachmentId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        let value = String::deserialize(deserializer).map_err(de::Error::custom)?;
+        Ok(AttachmentId::from(value.as_str()))
+    }
+}
+
+impl Serialize for AttachmentId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        self.to_string().serialize(serializer)
+    }
 }
 
+/// Entry of the `formats` array of 2.0 protocol messages binding an attachment to its format.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AttachmentFormat {
+    pub attach_id: String,
+    pub format: String,
+}
+
+impl AttachmentFormat {
+    pub const HLINDY_CRED_FILTER: &'static str = "hlindy/cred-filter@v2.0";
+    pub const HLINDY_CRED_ABSTRACT: &'static str = "hlindy/cred-abstract@v2.0";
+    pub const HLINDY_CRED_REQUEST: &'static str = "hlindy/cred-req@v2.0";
+    pub const HLINDY_CRED: &'static str = "hlindy/cred@v2.0";
+    pub const HLINDY_PROOF_REQUEST: &'static str = "hlindy/proof-req@v2.0";
+    pub const HLINDY_PROOF: &'static str = "hlindy/proof@v2.0";
+
+    pub fn new(attach_id: &AttachmentId, format: &str) -> AttachmentFormat {
+        AttachmentFormat {
+            attach_id: attach_id.to_string(),
+            format: format.to_string(),
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! attachment_formats (($type:ident, $attach:ident, $format:expr) => (
+    impl $type {
+        pub fn set_protocol_version(mut self, version: ProtocolVersion) -> $type {
+            self.formats = match version {
+                ProtocolVersion::V1 => Vec::new(),
+                ProtocolVersion::V2 => self.$attach.ids().iter()
+                    .map(|id| AttachmentFormat::new(id, $format))
+                    .collect()
+            };
+            self
+        }
+
+        pub fn protocol_version(&self) -> ProtocolVersion {
+            if self.formats.is_empty() { ProtocolVersion::V1 } else { ProtocolVersion::V2 }
+        }
+
+        pub fn attachment_content(&self) -> VcxResult<String> {
+            self.$attach.content_for_format(&self.formats, $format)
+        }
+    }
+));
+
 impl Json {
     pub fn new(id: AttachmentId, json: serde_json::Value, encoding: AttachmentEncoding) -> VcxResult<Json> {
         let data: AttachmentData = match encoding {
@@ -198,4 +311,29 @@ pub mod tests {
             assert_eq!(_json().to_string(), attachments.content().unwrap());
         }
     }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_attachment_id_serialization_works() {
+        assert_eq!(json!("libindy-cred-0"), serde_json::to_value(AttachmentId::Credential).unwrap());
+        assert_eq!(json!("indy-cred-0"), serde_json::to_value(AttachmentId::Other("indy-cred-0".to_string())).unwrap());
+        assert_eq!(AttachmentId::Credential, serde_json::from_value(json!("libindy-cred-0")).unwrap());
+        assert_eq!(AttachmentId::Other("indy-cred-0".to_string()), serde_json::from_value(json!("indy-cred-0")).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_attachments_content_for_format_works() {
+        let mut attachments = Attachments::new();
+        attachments.add_json_attachment(AttachmentId::Other("other-0".to_string()), json!({"field": "other"}), AttachmentEncoding::Json).unwrap();
+        attachments.add_json_attachment(AttachmentId::Credential, _json(), AttachmentEncoding::Base64).unwrap();
+
+        let formats = vec![
+            AttachmentFormat::new(&AttachmentId::Other("other-0".to_string()), "other/format@v1.0"),
+            AttachmentFormat::new(&AttachmentId::Credential, AttachmentFormat::HLINDY_CRED),
+        ];
+        assert_eq!(_json().to_string(), attachments.content_for_format(&formats, AttachmentFormat::HLINDY_CRED).unwrap());
+        assert_eq!(json!({"field": "other"}).to_string(), attachments.content_for_format(&[], AttachmentFormat::HLINDY_CRED).unwrap());
+        assert!(attachments.content_for_format(&formats, AttachmentFormat::HLINDY_PROOF).is_err());
+    }
 }
@@ -0,0 +1,58 @@
+use crate::aries::messages::a2a::message_family::MessageFamilies;
+
+/**
+Protocol used to establish pairwise connection: RFC 0160 connections/1.0 or RFC 0023 didexchange/1.0.
+ */
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+pub enum HandshakeProtocol {
+    #[serde(rename = "connections/1.0")]
+    Connections,
+    #[serde(rename = "didexchange/1.0")]
+    DidExchange,
+}
+
+impl HandshakeProtocol {
+    pub fn family(&self) -> MessageFamilies {
+        match self {
+            HandshakeProtocol::Connections => MessageFamilies::Connections,
+            HandshakeProtocol::DidExchange => MessageFamilies::DidExchange
+        }
+    }
+
+    /**
+    Resolves handshake protocol from protocol identifier as used in out-of-band `handshake_protocols`.
+     */
+    pub fn from_protocol_id(pid: &str) -> Option<HandshakeProtocol> {
+        [HandshakeProtocol::Connections, HandshakeProtocol::DidExchange].iter()
+            .find(|protocol| protocol.family().is_protocol_id(pid))
+            .cloned()
+    }
+}
+
+impl Default for HandshakeProtocol {
+    fn default() -> HandshakeProtocol {
+        HandshakeProtocol::Connections
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_handshake_protocol_from_protocol_id_works() {
+        assert_eq!(Some(HandshakeProtocol::Connections), HandshakeProtocol::from_protocol_id("https://didcomm.org/connections/1.0"));
+        assert_eq!(Some(HandshakeProtocol::DidExchange), HandshakeProtocol::from_protocol_id(&MessageFamilies::DidExchange.id()));
+        assert_eq!(None, HandshakeProtocol::from_protocol_id("https://didcomm.org/didexchange/2.0"));
+        assert_eq!(None, HandshakeProtocol::from_protocol_id("https://example.org/connections/1.0"));
+        assert_eq!(None, HandshakeProtocol::from_protocol_id("https://didcomm.org/legacy-connections/1.0"));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_handshake_protocol_serialization_works() {
+        assert_eq!(json!("didexchange/1.0"), serde_json::to_value(HandshakeProtocol::DidExchange).unwrap());
+        assert_eq!(HandshakeProtocol::Connections, serde_json::from_value(json!("connections/1.0")).unwrap());
+    }
+}
@@ -1,4 +1,13 @@
 use crate::aries::messages::a2a::{A2AMessage, MessageId};
+use crate::aries::messages::connection::did_doc::DidDoc;
+use crate::aries::messages::connection::handshake_protocol::HandshakeProtocol;
+use crate::aries::messages::out_of_band::invitation::OutOfBandInvitation;
+use crate::aries::utils::task::block_on;
+use crate::error::prelude::*;
+use crate::libindy::utils::ledger::{self, EndpointAttrib};
+use crate::profile::ProfileContext;
+use crate::utils::qualifier::is_fully_qualified;
+use crate::utils::validation::validate_did;
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
 pub struct Invitation {
@@ -47,10 +56,161 @@ impl Invitation {
 
 a2a_message!(Invitation, ConnectionInvitation);
 
+/**
+Implicit connections/1.0 invitation referencing public DID of the inviter. Its verkey and
+endpoint are resolved from the ledger (NYM and `endpoint` ATTRIB).
+ */
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
+pub struct PublicInvitation {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    pub label: String,
+    pub did: String,
+}
+
+impl PublicInvitation {
+    pub fn create() -> PublicInvitation {
+        PublicInvitation::default()
+    }
+
+    pub fn set_id(mut self, id: String) -> PublicInvitation {
+        self.id = MessageId(id);
+        self
+    }
+
+    pub fn set_label(mut self, label: String) -> PublicInvitation {
+        self.label = label;
+        self
+    }
+
+    pub fn set_did(mut self, did: String) -> PublicInvitation {
+        self.did = did;
+        self
+    }
+
+    pub fn validate(&self) -> VcxResult<()> {
+        unqualified_sov_did(&self.did).map(|_| ())
+    }
+
+    pub fn resolve_did_doc(&self, profile: &ProfileContext) -> VcxResult<DidDoc> {
+        block_on(self.resolve_did_doc_async(profile))
+    }
+
+    pub async fn resolve_did_doc_async(&self, profile: &ProfileContext) -> VcxResult<DidDoc> {
+        resolve_public_did_doc_async(profile, &self.did).await
+    }
+}
+
+/**
+Strips `did:sov:` prefix of the DID, only unqualified DIDs and `did:sov` DIDs can be resolved from the ledger.
+ */
+pub fn unqualified_sov_did(did: &str) -> VcxResult<&str> {
+    let unqualified_did = did.trim_start_matches("did:sov:");
+    if is_fully_qualified(unqualified_did) {
+        return Err(VcxError::from_msg(VcxErrorKind::InvalidDid, format!("DID {} cannot be resolved, only did:sov method is supported", did)));
+    }
+    validate_did(unqualified_did)?;
+    Ok(unqualified_did)
+}
+
+/**
+Builds DidDoc of public DID from its verkey (NYM) and `endpoint` ATTRIB on the ledger.
+ */
+pub fn resolve_public_did_doc(profile: &ProfileContext, did: &str) -> VcxResult<DidDoc> {
+    block_on(resolve_public_did_doc_async(profile, did))
+}
+
+pub async fn resolve_public_did_doc_async(profile: &ProfileContext, did: &str) -> VcxResult<DidDoc> {
+    let did = unqualified_sov_did(did)?;
+    let verkey = ledger::get_verkey_from_ledger_async(profile, did).await?;
+    let endpoint = ledger::get_service_endpoint_async(profile, did).await?;
+    Ok(_public_did_doc(did, verkey, endpoint))
+}
+
+fn _public_did_doc(did: &str, verkey: String, endpoint: EndpointAttrib) -> DidDoc {
+    let mut did_doc = DidDoc::default();
+    did_doc.set_id(did.to_string());
+    did_doc.set_service_endpoint(endpoint.endpoint);
+    did_doc.set_keys(vec![verkey], endpoint.routing_keys);
+    did_doc
+}
+
+a2a_message!(PublicInvitation, ConnectionInvitationPublic);
+
+/**
+Invitation to connect: either connections/1.0 invitation, out-of-band/1.0 invitation
+offering connections/1.0 or didexchange/1.0 as handshake protocol or public DID of the inviter.
+ */
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum AnyInvitation {
+    Connections(Invitation),
+    OutOfBand(OutOfBandInvitation),
+    Public(PublicInvitation),
+}
+
+impl AnyInvitation {
+    pub fn id(&self) -> &str {
+        match self {
+            AnyInvitation::Connections(invitation) => &invitation.id.0,
+            AnyInvitation::OutOfBand(invitation) => &invitation.id.0,
+            AnyInvitation::Public(invitation) => &invitation.id.0
+        }
+    }
+
+    pub fn out_of_band_id(&self) -> Option<String> {
+        match self {
+            AnyInvitation::Connections(_) | AnyInvitation::Public(_) => None,
+            AnyInvitation::OutOfBand(invitation) => Some(invitation.id.0.clone())
+        }
+    }
+
+    pub fn handshake_protocol(&self) -> VcxResult<HandshakeProtocol> {
+        match self {
+            AnyInvitation::Connections(_) | AnyInvitation::Public(_) => Ok(HandshakeProtocol::Connections),
+            AnyInvitation::OutOfBand(invitation) => invitation.handshake_protocol()
+                .ok_or(VcxError::from_msg(VcxErrorKind::InvalidInviteDetail, "Out-of-band invitation does not offer a supported handshake protocol"))
+        }
+    }
+
+    pub fn validate(&self) -> VcxResult<()> {
+        match self {
+            AnyInvitation::Connections(_) => Ok(()),
+            AnyInvitation::OutOfBand(invitation) => invitation.validate_for_connection(),
+            AnyInvitation::Public(invitation) => invitation.validate()
+        }
+    }
+
+    pub fn to_a2a_message(&self) -> A2AMessage {
+        match self {
+            AnyInvitation::Connections(invitation) => invitation.to_a2a_message(),
+            AnyInvitation::OutOfBand(invitation) => invitation.to_a2a_message(),
+            AnyInvitation::Public(invitation) => invitation.to_a2a_message()
+        }
+    }
+
+    /**
+    DidDoc of the inviter the request is sent to, for public invitation it is resolved from the ledger.
+     */
+    pub fn did_doc(&self, profile: &ProfileContext) -> VcxResult<DidDoc> {
+        block_on(self.did_doc_async(profile))
+    }
+
+    pub async fn did_doc_async(&self, profile: &ProfileContext) -> VcxResult<DidDoc> {
+        match self {
+            AnyInvitation::Connections(invitation) => Ok(DidDoc::from(invitation.clone())),
+            AnyInvitation::OutOfBand(invitation) => invitation.resolve_did_doc_async(profile).await,
+            AnyInvitation::Public(invitation) => invitation.resolve_did_doc_async(profile).await
+        }
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
     use crate::aries::messages::connection::did_doc::tests::*;
+    use crate::aries::messages::out_of_band::invitation::tests::{_out_of_band_invitation, _out_of_band_invitation_json};
+    use crate::utils::devsetup::SetupMocks;
 
     pub fn _invitation() -> Invitation {
         Invitation {
@@ -77,4 +237,60 @@ pub mod tests {
 
         assert_eq!(_invitation(), invitation);
     }
-}
\ No newline at end of file
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_any_invitation_deserialize_works() {
+        let invitation: AnyInvitation = serde_json::from_str(&_invitation_json()).unwrap();
+        assert_eq!(AnyInvitation::Connections(_invitation()), invitation);
+        assert_eq!(None, invitation.out_of_band_id());
+
+        let invitation: AnyInvitation = serde_json::from_str(&_out_of_band_invitation_json()).unwrap();
+        assert_match!(AnyInvitation::OutOfBand(_), invitation);
+        assert_eq!(Some(_out_of_band_invitation().id.0), invitation.out_of_band_id());
+        assert_eq!(HandshakeProtocol::Connections, invitation.handshake_protocol().unwrap());
+        invitation.validate().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_any_invitation_serialize_works_for_connections_invitation() {
+        let invitation = AnyInvitation::Connections(_invitation());
+        assert_eq!(serde_json::to_value(&_invitation()).unwrap(), serde_json::to_value(&invitation).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_any_invitation_works_for_public_invitation() {
+        let _setup = SetupMocks::init();
+
+        let invitation = PublicInvitation::create().set_label(_label()).set_did(String::from("did:sov:V4SGRU86Z58d6TV7PBUe6f"));
+        let message = json!(invitation.to_a2a_message());
+        assert_eq!("did:sov:V4SGRU86Z58d6TV7PBUe6f", message["did"]);
+
+        let invitation: AnyInvitation = serde_json::from_value(message.clone()).unwrap();
+        assert_match!(AnyInvitation::Public(_), invitation);
+        assert_match!(A2AMessage::ConnectionInvitationPublic(_), serde_json::from_value::<A2AMessage>(message).unwrap());
+        assert_eq!(HandshakeProtocol::Connections, invitation.handshake_protocol().unwrap());
+        invitation.validate().unwrap();
+
+        let did_doc = invitation.did_doc(&ProfileContext::Global).unwrap();
+        assert_eq!("V4SGRU86Z58d6TV7PBUe6f", did_doc.id);
+        assert_eq!(vec![crate::utils::constants::VERKEY.to_string()], did_doc.recipient_keys());
+        assert_eq!("http://localhost:8080", did_doc.get_endpoint());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_public_invitation_validate_rejects_other_did_methods() {
+        let _setup = SetupMocks::init();
+
+        PublicInvitation::create().set_did(String::from("V4SGRU86Z58d6TV7PBUe6f")).validate().unwrap();
+
+        let invitation = AnyInvitation::Public(PublicInvitation::create().set_did(String::from("did:peer:V4SGRU86Z58d6TV7PBUe6f")));
+        assert_eq!(VcxErrorKind::InvalidDid, invitation.validate().unwrap_err().kind());
+
+        let invitation = AnyInvitation::Public(PublicInvitation::create().set_did(String::from("did:sov:V4SGRU86Z58d")));
+        assert_eq!(VcxErrorKind::InvalidDid, invitation.validate().unwrap_err().kind());
+    }
+}
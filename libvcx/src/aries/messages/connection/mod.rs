@@ -1,4 +1,5 @@
 pub mod did_doc;
+pub mod handshake_protocol;
 pub mod invite;
 pub mod problem_report;
 pub mod request;
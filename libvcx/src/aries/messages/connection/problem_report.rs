@@ -1,5 +1,6 @@
 use crate::aries::messages::thread::Thread;
 use crate::aries::messages::a2a::{A2AMessage, MessageId};
+use crate::aries::messages::connection::handshake_protocol::HandshakeProtocol;
 use crate::aries::messages::localization::Localization;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
@@ -47,6 +48,13 @@ impl ProblemReport {
         self.explain = Some(explain);
         self
     }
+
+    pub fn to_a2a_message_for(&self, protocol: &HandshakeProtocol) -> A2AMessage {
+        match protocol {
+            HandshakeProtocol::Connections => A2AMessage::ConnectionProblemReport(self.clone()),
+            HandshakeProtocol::DidExchange => A2AMessage::DidExchangeProblemReport(self.clone())
+        }
+    }
 }
 
 threadlike!(ProblemReport);
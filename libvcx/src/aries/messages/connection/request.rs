@@ -1,5 +1,9 @@
 use crate::aries::messages::a2a::{A2AMessage, MessageId};
 use crate::aries::messages::connection::did_doc::*;
+use crate::aries::messages::connection::handshake_protocol::HandshakeProtocol;
+use crate::aries::messages::did_exchange::request::Request as DidExchangeRequest;
+use crate::aries::messages::thread::Thread;
+use crate::error::prelude::*;
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
 pub struct Request {
@@ -7,6 +11,9 @@ pub struct Request {
     pub id: MessageId,
     pub label: String,
     pub connection: ConnectionData,
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
@@ -42,13 +49,66 @@ impl Request {
         self.connection.did_doc.set_keys(recipient_keys, routing_keys);
         self
     }
+
+    pub fn set_parent_thread_id(mut self, pthid: &str) -> Request {
+        self.thread = Some(self.thread.unwrap_or_default().set_pthid(pthid.to_string()));
+        self
+    }
 }
 
 a2a_message!(Request, ConnectionRequest);
 
+/**
+Request received by Inviter: either connections/1.0 or didexchange/1.0 request.
+ */
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum AnyRequest {
+    Connections(Request),
+    DidExchange(DidExchangeRequest),
+}
+
+impl AnyRequest {
+    pub fn protocol(&self) -> HandshakeProtocol {
+        match self {
+            AnyRequest::Connections(_) => HandshakeProtocol::Connections,
+            AnyRequest::DidExchange(_) => HandshakeProtocol::DidExchange
+        }
+    }
+
+    pub fn thread_id(&self) -> String {
+        match self {
+            AnyRequest::Connections(request) => request.id.0.clone(),
+            AnyRequest::DidExchange(request) => request.thread_id()
+        }
+    }
+
+    pub fn parent_thread_id(&self) -> Option<String> {
+        match self {
+            AnyRequest::Connections(request) => request.thread.as_ref().and_then(|thread| thread.pthid.clone()),
+            AnyRequest::DidExchange(request) => request.thread.pthid.clone()
+        }
+    }
+
+    pub fn did_doc(&self) -> VcxResult<DidDoc> {
+        match self {
+            AnyRequest::Connections(request) => Ok(request.connection.did_doc.clone()),
+            AnyRequest::DidExchange(request) => request.did_doc()
+        }
+    }
+
+    pub fn to_a2a_message(&self) -> A2AMessage {
+        match self {
+            AnyRequest::Connections(request) => request.to_a2a_message(),
+            AnyRequest::DidExchange(request) => request.to_a2a_message()
+        }
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use crate::aries::messages::connection::did_doc::tests::*;
+    use crate::aries::messages::did_exchange::request::tests::_did_exchange_request;
 
     use super::*;
 
@@ -64,6 +124,7 @@ pub mod tests {
                 did: _did(),
                 did_doc: _did_doc(),
             },
+            thread: None,
         }
     }
 
@@ -78,4 +139,28 @@ pub mod tests {
 
         assert_eq!(_request(), request);
     }
-}
\ No newline at end of file
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_request_with_parent_thread_id_works() {
+        let request: Request = _request().set_parent_thread_id("oob-id");
+
+        assert_eq!(Some(String::from("oob-id")), request.thread.unwrap().pthid);
+        assert!(serde_json::to_value(&_request()).unwrap().get("~thread").is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_any_request_deserialize_works() {
+        let request: AnyRequest = serde_json::from_value(serde_json::to_value(&_request()).unwrap()).unwrap();
+        assert_eq!(AnyRequest::Connections(_request()), request);
+        assert_eq!(_request().id.0, request.thread_id());
+
+        let did_exchange_request = _did_exchange_request().set_parent_thread_id("oob-id");
+        let request: AnyRequest = serde_json::from_value(serde_json::to_value(&did_exchange_request).unwrap()).unwrap();
+        assert_eq!(AnyRequest::DidExchange(did_exchange_request), request);
+        assert_eq!(HandshakeProtocol::DidExchange, request.protocol());
+        assert_eq!(Some(String::from("oob-id")), request.parent_thread_id());
+        assert_eq!(_did_doc(), request.did_doc().unwrap());
+    }
+}
@@ -1,4 +1,5 @@
 use base64;
+use indy::WalletHandle;
 use time;
 
 use crate::aries::messages::a2a::{A2AMessage, MessageId};
@@ -6,7 +7,10 @@ use crate::aries::messages::a2a::message_family::MessageFamilies;
 use crate::aries::messages::a2a::message_type::MessageType;
 use crate::aries::messages::ack::PleaseAck;
 use crate::aries::messages::connection::did_doc::*;
+use crate::aries::messages::connection::handshake_protocol::HandshakeProtocol;
+use crate::aries::messages::did_exchange::response::Response as DidExchangeResponse;
 use crate::aries::messages::thread::Thread;
+use crate::aries::utils::task::block_on;
 use crate::error::prelude::*;
 use crate::libindy::utils::crypto;
 
@@ -73,7 +77,19 @@ impl Response {
         self
     }
 
-    pub fn encode(&self, key: &str) -> VcxResult<SignedResponse> {
+    pub fn encode(&self, wallet_handle: WalletHandle, key: &str) -> VcxResult<SignedResponse> {
+        block_on(self.encode_async(wallet_handle, key))
+    }
+
+    pub async fn encode_async(&self, wallet_handle: WalletHandle, key: &str) -> VcxResult<SignedResponse> {
+        let sig_data = self._sig_data();
+
+        let signature = crypto::sign_async(wallet_handle, key, &sig_data).await?;
+
+        Ok(self._signed_response(key, &sig_data, &signature))
+    }
+
+    fn _sig_data(&self) -> Vec<u8> {
         let connection_data = json!(self.connection).to_string();
 
         let now: u64 = time::get_time().sec as u64;
@@ -82,8 +98,10 @@ impl Response {
 
         sig_data.extend(connection_data.as_bytes());
 
-        let signature = crypto::sign(key, &sig_data)?;
+        sig_data
+    }
 
+    fn _signed_response(&self, key: &str, sig_data: &[u8], signature: &[u8]) -> SignedResponse {
         let sig_data = base64::encode_config(&sig_data, base64::URL_SAFE);
 
         let signature = base64::encode_config(&signature, base64::URL_SAFE);
@@ -95,14 +113,12 @@ impl Response {
             ..Default::default()
         };
 
-        let signed_response = SignedResponse {
+        SignedResponse {
             id: self.id.clone(),
             thread: self.thread.clone(),
             connection_sig,
             please_ack: self.please_ack.clone(),
-        };
-
-        Ok(signed_response)
+        }
     }
 }
 
@@ -111,16 +127,30 @@ threadlike!(Response);
 
 impl SignedResponse {
     pub fn decode(self, key: &str) -> VcxResult<Response> {
+        block_on(self.decode_async(key))
+    }
+
+    pub async fn decode_async(self, key: &str) -> VcxResult<Response> {
+        let (signature, sig_data) = self._signature_and_data()?;
+
+        if !crypto::verify_async(&key, &sig_data, &signature).await? {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidJson, "ConnectionResponse signature is invalid for original Invite recipient key"));
+        }
+
+        self._into_response(&sig_data)
+    }
+
+    fn _signature_and_data(&self) -> VcxResult<(Vec<u8>, Vec<u8>)> {
         let signature = base64::decode_config(&self.connection_sig.signature.as_bytes(), base64::URL_SAFE)
             .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot decode ConnectionResponse: {:?}", err)))?;
 
         let sig_data = base64::decode_config(&self.connection_sig.sig_data.as_bytes(), base64::URL_SAFE)
             .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot decode ConnectionResponse: {:?}", err)))?;
 
-        if !crypto::verify(&key, &sig_data, &signature)? {
-            return Err(VcxError::from_msg(VcxErrorKind::InvalidJson, "ConnectionResponse signature is invalid for original Invite recipient key"));
-        }
+        Ok((signature, sig_data))
+    }
 
+    fn _into_response(self, sig_data: &[u8]) -> VcxResult<Response> {
         //TODO check sig_data.signer
 
         let sig_data = &sig_data[8..];
@@ -139,6 +169,65 @@ impl SignedResponse {
 
 a2a_message!(SignedResponse, ConnectionResponse);
 
+/**
+Response received by Invitee: either signed connections/1.0 or didexchange/1.0 response.
+ */
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum AnyResponse {
+    Connections(SignedResponse),
+    DidExchange(DidExchangeResponse),
+}
+
+impl AnyResponse {
+    pub fn protocol(&self) -> HandshakeProtocol {
+        match self {
+            AnyResponse::Connections(_) => HandshakeProtocol::Connections,
+            AnyResponse::DidExchange(_) => HandshakeProtocol::DidExchange
+        }
+    }
+
+    pub fn thread(&self) -> &Thread {
+        match self {
+            AnyResponse::Connections(response) => &response.thread,
+            AnyResponse::DidExchange(response) => &response.thread
+        }
+    }
+
+    /**
+    Verifies response signature with the key used in the invitation and returns its content.
+     */
+    pub fn decode(self, key: &str) -> VcxResult<Response> {
+        block_on(self.decode_async(key))
+    }
+
+    pub async fn decode_async(self, key: &str) -> VcxResult<Response> {
+        match self {
+            AnyResponse::Connections(response) => response.decode_async(key).await,
+            AnyResponse::DidExchange(response) => {
+                let did_doc = response.verify_async(key).await?;
+                Ok(Self::_did_exchange_response(response, did_doc))
+            }
+        }
+    }
+
+    fn _did_exchange_response(response: DidExchangeResponse, did_doc: DidDoc) -> Response {
+        Response {
+            id: response.id,
+            thread: response.thread,
+            connection: ConnectionData { did: response.did, did_doc },
+            please_ack: None,
+        }
+    }
+
+    pub fn to_a2a_message(&self) -> A2AMessage {
+        match self {
+            AnyResponse::Connections(response) => response.to_a2a_message(),
+            AnyResponse::DidExchange(response) => response.to_a2a_message()
+        }
+    }
+}
+
 impl Default for ConnectionSignature {
     fn default() -> ConnectionSignature {
         ConnectionSignature {
@@ -153,7 +242,9 @@ impl Default for ConnectionSignature {
 #[cfg(test)]
 pub mod tests {
     use crate::aries::messages::connection::did_doc::tests::*;
+    use crate::aries::messages::did_exchange::response::tests::_did_exchange_response;
     use crate::libindy::utils::tests::test_setup;
+    use crate::utils::devsetup::SetupMocks;
     use crate::libindy::utils::tests::test_setup::create_trustee_key;
 
     use super::*;
@@ -217,7 +308,23 @@ pub mod tests {
     fn test_response_encode_works() {
         let setup = test_setup::setup_wallet();
         let trustee_key = create_trustee_key(setup.wh);
-        let signed_response: SignedResponse = _response().encode(&trustee_key).unwrap();
+        let signed_response: SignedResponse = _response().encode(setup.wh, &trustee_key).unwrap();
         assert_eq!(_response(), signed_response.decode(&trustee_key).unwrap());
     }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_any_response_deserialize_works() {
+        let response: AnyResponse = serde_json::from_value(serde_json::to_value(&_signed_response()).unwrap()).unwrap();
+        assert_eq!(AnyResponse::Connections(_signed_response()), response);
+
+        let _setup = SetupMocks::init();
+        let response: AnyResponse = serde_json::from_value(serde_json::to_value(&_did_exchange_response()).unwrap()).unwrap();
+        assert_eq!(AnyResponse::DidExchange(_did_exchange_response()), response);
+        assert_eq!(HandshakeProtocol::DidExchange, response.protocol());
+
+        let response = response.decode(&_key_1()).unwrap();
+        assert!(response.from_thread("testid"));
+        assert_eq!(_did_doc(), response.connection.did_doc);
+    }
 }
@@ -0,0 +1,44 @@
+use crate::aries::messages::a2a::{A2AMessage, MessageId};
+use crate::aries::messages::thread::Thread;
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
+pub struct Complete {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+}
+
+impl Complete {
+    pub fn create() -> Complete {
+        Complete::default()
+    }
+
+    pub fn set_parent_thread_id(mut self, pthid: Option<String>) -> Complete {
+        self.thread.pthid = pthid;
+        self
+    }
+}
+
+threadlike!(Complete);
+a2a_message!(Complete, DidExchangeComplete);
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    pub fn _complete() -> Complete {
+        Complete::create()
+            .set_thread_id("testid")
+            .set_parent_thread_id(Some(String::from("oob-id")))
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_complete_serialize_works() {
+        let message = serde_json::to_value(&_complete().to_a2a_message()).unwrap();
+
+        assert_eq!(json!("did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/didexchange/1.0/complete"), message["@type"]);
+        assert_eq!(json!({"thid": "testid", "pthid": "oob-id", "sender_order": 0, "received_orders": {}}), message["~thread"]);
+    }
+}
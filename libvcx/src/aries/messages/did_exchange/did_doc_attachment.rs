@@ -0,0 +1,198 @@
+use base64;
+use indy::WalletHandle;
+use rust_base58::FromBase58;
+
+use crate::aries::messages::connection::did_doc::DidDoc;
+use crate::aries::messages::out_of_band::invitation::{key_to_verkey, verkey_to_did_key};
+use crate::aries::utils::task::block_on;
+use crate::error::prelude::*;
+use crate::libindy::utils::crypto;
+
+pub const DID_DOC_ATTACHMENT_ID: &str = "did-doc";
+const MIME_TYPE_JSON: &str = "application/json";
+
+/**
+DIDDoc attached to DID Exchange request/response as `did_doc~attach`.
+Response attachment is signed by the key used in the invitation (detached JWS with EdDSA algorithm).
+ */
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct DidDocAttachment {
+    #[serde(rename = "@id")]
+    pub id: String,
+    #[serde(rename = "mime-type")]
+    pub mime_type: String,
+    pub data: DidDocAttachmentData,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
+pub struct DidDocAttachmentData {
+    pub base64: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jws: Option<Jws>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
+pub struct Jws {
+    pub header: JwsHeader,
+    pub protected: String,
+    pub signature: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
+pub struct JwsHeader {
+    pub kid: String,
+}
+
+impl DidDocAttachment {
+    pub fn create(did_doc: &DidDoc) -> VcxResult<DidDocAttachment> {
+        let did_doc = serde_json::to_string(did_doc)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Cannot serialize DIDDoc: {:?}", err)))?;
+
+        Ok(DidDocAttachment {
+            id: DID_DOC_ATTACHMENT_ID.to_string(),
+            mime_type: MIME_TYPE_JSON.to_string(),
+            data: DidDocAttachmentData {
+                base64: base64::encode(did_doc.as_bytes()),
+                jws: None,
+            },
+        })
+    }
+
+    pub fn did_doc(&self) -> VcxResult<DidDoc> {
+        let did_doc = self._payload()?;
+        serde_json::from_slice(&did_doc)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize attached DIDDoc: {:?}", err)))
+    }
+
+    pub fn sign(self, wallet_handle: WalletHandle, verkey: &str) -> VcxResult<DidDocAttachment> {
+        block_on(self.sign_async(wallet_handle, verkey))
+    }
+
+    pub async fn sign_async(self, wallet_handle: WalletHandle, verkey: &str) -> VcxResult<DidDocAttachment> {
+        let (did_key, protected, signing_input) = self._prepare_signing(verkey)?;
+        let signature = crypto::sign_async(wallet_handle, verkey, signing_input.as_bytes()).await?;
+        Ok(self._signed(did_key, protected, &signature))
+    }
+
+    fn _prepare_signing(&self, verkey: &str) -> VcxResult<(String, String, String)> {
+        let did_key = verkey_to_did_key(verkey)?;
+        let protected = json!({
+            "alg": "EdDSA",
+            "kid": did_key,
+            "jwk": {
+                "kty": "OKP",
+                "crv": "Ed25519",
+                "x": _encode_url_safe(&verkey.from_base58()
+                    .map_err(|err| VcxError::from_msg(VcxErrorKind::NotBase58, format!("Invalid verkey {}, err: {:?}", verkey, err)))?),
+                "kid": did_key
+            }
+        }).to_string();
+        let protected = _encode_url_safe(protected.as_bytes());
+        let signing_input = self._signing_input(&protected)?;
+        Ok((did_key, protected, signing_input))
+    }
+
+    fn _signed(mut self, did_key: String, protected: String, signature: &[u8]) -> DidDocAttachment {
+        self.data.jws = Some(Jws {
+            header: JwsHeader { kid: did_key },
+            protected,
+            signature: _encode_url_safe(signature),
+        });
+        self
+    }
+
+    /**
+    Verifies that the attachment is signed by the expected key.
+     */
+    pub fn verify(&self, verkey: &str) -> VcxResult<()> {
+        block_on(self.verify_async(verkey))
+    }
+
+    pub async fn verify_async(&self, verkey: &str) -> VcxResult<()> {
+        let (signing_input, signature) = self._prepare_verification(verkey)?;
+
+        if !crypto::verify_async(verkey, signing_input.as_bytes(), &signature).await? {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidJson, "DIDDoc attachment signature is invalid for original Invite recipient key"));
+        }
+        Ok(())
+    }
+
+    fn _prepare_verification(&self, verkey: &str) -> VcxResult<(String, Vec<u8>)> {
+        let jws = self.data.jws.as_ref()
+            .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, "DIDDoc attachment is not signed"))?;
+
+        if key_to_verkey(&jws.header.kid)? != verkey {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("DIDDoc attachment is signed by unexpected key: {}", jws.header.kid)));
+        }
+
+        let signature = _decode_url_safe(&jws.signature)?;
+
+        Ok((self._signing_input(&jws.protected)?, signature))
+    }
+
+    fn _payload(&self) -> VcxResult<Vec<u8>> {
+        base64::decode(&self.data.base64)
+            .or_else(|_| _decode_url_safe(&self.data.base64))
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot decode DIDDoc attachment: {:?}", err)))
+    }
+
+    fn _signing_input(&self, protected: &str) -> VcxResult<String> {
+        Ok(format!("{}.{}", protected, _encode_url_safe(&self._payload()?)))
+    }
+}
+
+fn _encode_url_safe(data: &[u8]) -> String {
+    base64::encode_config(data, base64::URL_SAFE_NO_PAD)
+}
+
+fn _decode_url_safe(data: &str) -> VcxResult<Vec<u8>> {
+    base64::decode_config(data.trim_end_matches('='), base64::URL_SAFE_NO_PAD)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot decode base64url value: {:?}", err)))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::aries::messages::connection::did_doc::tests::*;
+    use crate::libindy::utils::wallet::get_wallet_handle;
+    use crate::utils::devsetup::SetupMocks;
+
+    use super::*;
+
+    pub fn _did_doc_attachment() -> DidDocAttachment {
+        DidDocAttachment::create(&_did_doc()).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_did_doc_attachment_create_works() {
+        let attachment = _did_doc_attachment();
+
+        assert_eq!(DID_DOC_ATTACHMENT_ID, attachment.id);
+        assert_eq!(_did_doc(), attachment.did_doc().unwrap());
+        assert!(attachment.data.jws.is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_did_doc_attachment_sign_and_verify_works() {
+        let _setup = SetupMocks::init();
+
+        let attachment = _did_doc_attachment().sign(get_wallet_handle(), &_key_1()).unwrap();
+
+        let jws = attachment.data.jws.clone().unwrap();
+        assert_eq!(verkey_to_did_key(&_key_1()).unwrap(), jws.header.kid);
+        attachment.verify(&_key_1()).unwrap();
+        assert_eq!(_did_doc(), attachment.did_doc().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_did_doc_attachment_verify_fails_for_unsigned_or_other_key() {
+        let _setup = SetupMocks::init();
+
+        assert_eq!(VcxErrorKind::InvalidJson, _did_doc_attachment().verify(&_key_1()).unwrap_err().kind());
+
+        let attachment = _did_doc_attachment().sign(get_wallet_handle(), &_key_1()).unwrap();
+        assert_eq!(VcxErrorKind::InvalidJson, attachment.verify(&_key_2()).unwrap_err().kind());
+    }
+}
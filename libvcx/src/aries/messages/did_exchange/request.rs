@@ -0,0 +1,116 @@
+use crate::aries::messages::a2a::{A2AMessage, MessageId};
+use crate::aries::messages::connection::did_doc::DidDoc;
+use crate::aries::messages::did_exchange::did_doc_attachment::DidDocAttachment;
+use crate::aries::messages::thread::Thread;
+use crate::error::prelude::*;
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Request {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(default)]
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goal_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goal: Option<String>,
+    pub did: String,
+    #[serde(rename = "did_doc~attach")]
+    pub did_doc_attach: DidDocAttachment,
+    #[serde(rename = "~thread")]
+    #[serde(default)]
+    pub thread: Thread,
+}
+
+impl Request {
+    pub fn create(did_doc: &DidDoc) -> VcxResult<Request> {
+        let id = MessageId::new();
+        Ok(Request {
+            thread: Thread::new().set_thid(id.0.clone()),
+            id,
+            label: String::new(),
+            goal_code: None,
+            goal: None,
+            did: did_doc.id.clone(),
+            did_doc_attach: DidDocAttachment::create(did_doc)?,
+        })
+    }
+
+    pub fn set_label(mut self, label: String) -> Request {
+        self.label = label;
+        self
+    }
+
+    pub fn set_goal_code(mut self, goal_code: Option<String>) -> Request {
+        self.goal_code = goal_code;
+        self
+    }
+
+    pub fn set_goal(mut self, goal: Option<String>) -> Request {
+        self.goal = goal;
+        self
+    }
+
+    pub fn set_parent_thread_id(mut self, pthid: &str) -> Request {
+        self.thread = self.thread.set_pthid(pthid.to_string());
+        self
+    }
+
+    pub fn thread_id(&self) -> String {
+        self.thread.thid.clone().unwrap_or(self.id.0.clone())
+    }
+
+    pub fn did_doc(&self) -> VcxResult<DidDoc> {
+        self.did_doc_attach.did_doc()
+    }
+}
+
+a2a_message!(Request, DidExchangeRequest);
+
+#[cfg(test)]
+pub mod tests {
+    use crate::aries::messages::connection::did_doc::tests::*;
+
+    use super::*;
+
+    pub fn _did_exchange_request() -> Request {
+        Request::create(&_did_doc()).unwrap()
+            .set_label(_label())
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_did_exchange_request_build_works() {
+        let request: Request = _did_exchange_request().set_parent_thread_id("oob-id");
+
+        assert_eq!(_id(), request.did);
+        assert_eq!(_did_doc(), request.did_doc().unwrap());
+        assert_eq!(request.id.0, request.thread_id());
+        assert_eq!(Some(String::from("oob-id")), request.thread.pthid);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_did_exchange_request_deserialize_works() {
+        let message = json!({
+            "@type": "did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/didexchange/1.0/request",
+            "@id": "testid",
+            "~thread": {"pthid": "oob-id"},
+            "label": _label(),
+            "did": _id(),
+            "did_doc~attach": {
+                "@id": "did-doc",
+                "mime-type": "application/json",
+                "data": {"base64": base64::encode(&serde_json::to_vec(&_did_doc()).unwrap())}
+            }
+        });
+
+        let message: A2AMessage = serde_json::from_value(message).unwrap();
+        let request = match message {
+            A2AMessage::DidExchangeRequest(request) => request,
+            message => panic!("Unexpected message: {:?}", message)
+        };
+        assert_eq!(String::from("testid"), request.thread_id());
+        assert_eq!(_did_doc(), request.did_doc().unwrap());
+    }
+}
@@ -0,0 +1,92 @@
+use indy::WalletHandle;
+
+use crate::aries::messages::a2a::{A2AMessage, MessageId};
+use crate::aries::messages::connection::did_doc::DidDoc;
+use crate::aries::messages::did_exchange::did_doc_attachment::DidDocAttachment;
+use crate::aries::messages::thread::Thread;
+use crate::aries::utils::task::block_on;
+use crate::error::prelude::*;
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Response {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+    pub did: String,
+    #[serde(rename = "did_doc~attach")]
+    pub did_doc_attach: DidDocAttachment,
+}
+
+impl Response {
+    pub fn create(did_doc: &DidDoc) -> VcxResult<Response> {
+        Ok(Response {
+            id: MessageId::new(),
+            thread: Thread::new(),
+            did: did_doc.id.clone(),
+            did_doc_attach: DidDocAttachment::create(did_doc)?,
+        })
+    }
+
+    /**
+    Signs attached DIDDoc with the key the invitation was sent with.
+     */
+    pub fn sign(mut self, wallet_handle: WalletHandle, verkey: &str) -> VcxResult<Response> {
+        block_on(self.sign_async(wallet_handle, verkey))
+    }
+
+    pub async fn sign_async(mut self, wallet_handle: WalletHandle, verkey: &str) -> VcxResult<Response> {
+        self.did_doc_attach = self.did_doc_attach.sign_async(wallet_handle, verkey).await?;
+        Ok(self)
+    }
+
+    /**
+    Verifies signature of attached DIDDoc and returns it.
+     */
+    pub fn verify(&self, verkey: &str) -> VcxResult<DidDoc> {
+        block_on(self.verify_async(verkey))
+    }
+
+    pub async fn verify_async(&self, verkey: &str) -> VcxResult<DidDoc> {
+        self.did_doc_attach.verify_async(verkey).await?;
+        self.did_doc_attach.did_doc()
+    }
+}
+
+threadlike!(Response);
+a2a_message!(Response, DidExchangeResponse);
+
+#[cfg(test)]
+pub mod tests {
+    use crate::aries::messages::connection::did_doc::tests::*;
+    use crate::libindy::utils::wallet::get_wallet_handle;
+    use crate::utils::devsetup::SetupMocks;
+
+    use super::*;
+
+    pub fn _did_exchange_response() -> Response {
+        Response::create(&_did_doc()).unwrap()
+            .set_thread_id("testid")
+            .sign(get_wallet_handle(), &_key_1()).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_did_exchange_response_build_works() {
+        let _setup = SetupMocks::init();
+
+        let response = _did_exchange_response();
+
+        assert!(response.from_thread("testid"));
+        assert_eq!(_id(), response.did);
+        assert_eq!(_did_doc(), response.verify(&_key_1()).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_did_exchange_response_verify_fails_for_other_key() {
+        let _setup = SetupMocks::init();
+
+        assert_eq!(VcxErrorKind::InvalidJson, _did_exchange_response().verify(&_key_2()).unwrap_err().kind());
+    }
+}
@@ -1,6 +1,7 @@
 use crate::aries::messages::a2a::{A2AMessage, MessageId};
 use crate::aries::messages::ack::PleaseAck;
-use crate::aries::messages::attachment::{AttachmentId, Attachments};
+use crate::aries::messages::a2a::protocol_version::ProtocolVersion;
+use crate::aries::messages::attachment::{AttachmentFormat, AttachmentId, Attachments};
 use crate::aries::messages::thread::Thread;
 use crate::error::VcxResult;
 
@@ -10,6 +11,8 @@ pub struct Credential {
     pub id: MessageId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub formats: Vec<AttachmentFormat>,
     #[serde(rename = "credentials~attach")]
     pub credentials_attach: Attachments,
     #[serde(rename = "~thread")]
@@ -52,6 +55,7 @@ impl Credential {
 
 please_ack!(Credential);
 threadlike!(Credential);
+attachment_formats!(Credential, credentials_attach, AttachmentFormat::HLINDY_CRED);
 a2a_message!(Credential);
 
 #[cfg(test)]
@@ -82,6 +86,7 @@ pub mod tests {
             id: MessageId::id(),
             comment: Some(_comment()),
             thread: thread(),
+            formats: vec![],
             credentials_attach: attachment,
             please_ack: None,
         }
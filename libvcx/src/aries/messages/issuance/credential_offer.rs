@@ -1,8 +1,10 @@
 use crate::aries::messages::a2a::{A2AMessage, MessageId};
-use crate::aries::messages::attachment::{AttachmentId, Attachments};
+use crate::aries::messages::a2a::protocol_version::ProtocolVersion;
+use crate::aries::messages::attachment::{AttachmentFormat, AttachmentId, Attachments};
 use crate::aries::messages::issuance::CredentialPreviewData;
 use crate::aries::messages::mime_type::MimeType;
 use crate::aries::messages::thread::Thread;
+use crate::aries::messages::timing::Timing;
 use crate::error::VcxResult;
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
@@ -12,11 +14,16 @@ pub struct CredentialOffer {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub comment: Option<String>,
     pub credential_preview: CredentialPreviewData,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub formats: Vec<AttachmentFormat>,
     #[serde(rename = "offers~attach")]
     pub offers_attach: Attachments,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "~thread")]
     pub thread: Option<Thread>,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
 }
 
 impl CredentialOffer {
@@ -53,14 +60,23 @@ impl CredentialOffer {
         self.thread = Some(Thread::new().set_thid(id.to_string()));
         self
     }
+
+    /**
+    Offer sent in response to a proposal continues the proposal thread, otherwise it starts a new one.
+     */
+    pub fn thread_id(&self) -> String {
+        self.thread.as_ref()
+            .and_then(|thread| thread.thid.clone())
+            .unwrap_or(self.id.0.clone())
+    }
 }
 
+attachment_formats!(CredentialOffer, offers_attach, AttachmentFormat::HLINDY_CRED_ABSTRACT);
+timing!(CredentialOffer);
 a2a_message!(CredentialOffer);
 
 #[cfg(test)]
 pub mod tests {
-    use crate::aries::messages::connection::response::tests::*;
-
     use super::*;
 
     fn _attachment() -> ::serde_json::Value {
@@ -100,8 +116,10 @@ pub mod tests {
             id: MessageId::id(),
             comment: _comment(),
             credential_preview: _preview_data(),
+            formats: vec![],
             offers_attach: attachment,
-            thread: Some(_thread()),
+            thread: Some(Thread::new().set_thid(MessageId::id().0)),
+            timing: None,
         }
     }
 
@@ -110,10 +128,47 @@ pub mod tests {
     fn test_credential_offer_build_works() {
         let credential_offer: CredentialOffer = CredentialOffer::create()
             .set_comment(_comment())
-            .set_thread_id(&_thread_id())
+            .set_thread_id(&MessageId::id().0)
             .set_credential_preview_data(_preview_data()).unwrap()
             .set_offers_attach(&_attachment().to_string()).unwrap();
 
         assert_eq!(_credential_offer(), credential_offer);
     }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_credential_offer_v2_serialization_works() {
+        let credential_offer = _credential_offer().set_protocol_version(ProtocolVersion::V2);
+
+        let message = json!(credential_offer.to_a2a_message());
+        assert_eq!("did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/issue-credential/2.0/offer-credential", message["@type"]);
+        assert_eq!(json!([{"attach_id": "libindy-cred-offer-0", "format": "hlindy/cred-abstract@v2.0"}]), message["formats"]);
+
+        let received: A2AMessage = serde_json::from_value(message).unwrap();
+        assert_eq!(A2AMessage::CredentialOffer(credential_offer), received);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_credential_offer_v2_attachment_content_works() {
+        let message = json!({
+            "@id": "offer_id",
+            "@type": "did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/issue-credential/2.0/offer-credential",
+            "credential_preview": _preview_data(),
+            "formats": [{"attach_id": "indy", "format": "hlindy/cred-abstract@v2.0"}],
+            "offers~attach": [{
+                "@id": "indy",
+                "mime-type": "application/json",
+                "data": {"base64": base64::encode(&_attachment().to_string())}
+            }]
+        });
+
+        match serde_json::from_value(message).unwrap() {
+            A2AMessage::CredentialOffer(offer) => {
+                assert_eq!(ProtocolVersion::V2, offer.protocol_version());
+                assert_eq!(_attachment().to_string(), offer.attachment_content().unwrap());
+            }
+            other => panic!("Unexpected message: {:?}", other)
+        }
+    }
 }
@@ -1,8 +1,10 @@
 use crate::aries::messages::a2a::{A2AMessage, MessageId};
+use crate::aries::messages::a2a::protocol_version::ProtocolVersion;
+use crate::aries::messages::attachment::{AttachmentFormat, Attachments};
 use crate::aries::messages::issuance::CredentialPreviewData;
 use crate::aries::messages::mime_type::MimeType;
 use crate::aries::messages::thread::Thread;
-use crate::error::VcxResult;
+use crate::error::{VcxError, VcxErrorKind, VcxResult};
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
 pub struct CredentialProposal {
@@ -11,8 +13,15 @@ pub struct CredentialProposal {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub comment: Option<String>,
     pub credential_proposal: CredentialPreviewData,
+    #[serde(default)]
     pub schema_id: String,
+    #[serde(default)]
     pub cred_def_id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub formats: Vec<AttachmentFormat>,
+    #[serde(rename = "filters~attach")]
+    #[serde(default, skip_serializing_if = "Attachments::is_empty")]
+    pub filters_attach: Attachments,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "~thread")]
     pub thread: Option<Thread>,
@@ -38,6 +47,11 @@ impl CredentialProposal {
         self
     }
 
+    pub fn set_credential_preview_data(mut self, credential_preview: CredentialPreviewData) -> CredentialProposal {
+        self.credential_proposal = credential_preview;
+        self
+    }
+
     pub fn add_credential_preview_data(mut self, name: &str, value: &str, mime_type: MimeType) -> VcxResult<CredentialProposal> {
         self.credential_proposal = self.credential_proposal.add_value(name, value, mime_type)?;
         Ok(self)
@@ -47,8 +61,29 @@ impl CredentialProposal {
         self.thread = Some(Thread::new().set_thid(id.to_string()));
         self
     }
+
+    /**
+    Schema and credential definition ids are optional in 1.0 proposals (RFC 0036),
+    2.0 proposals have to attach the filter their formats refer to.
+     */
+    pub fn validate(&self) -> VcxResult<()> {
+        if self.protocol_version() == ProtocolVersion::V2 && self.filters_attach.is_empty() {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidOption, "Credential Proposal does not attach credential filter"));
+        }
+        Ok(())
+    }
+
+    /**
+    Proposal either starts new issuance thread or continues the existing one as counter-proposal.
+     */
+    pub fn thread_id(&self) -> String {
+        self.thread.as_ref()
+            .and_then(|thread| thread.thid.clone())
+            .unwrap_or(self.id.0.clone())
+    }
 }
 
+attachment_formats!(CredentialProposal, filters_attach, AttachmentFormat::HLINDY_CRED_FILTER);
 a2a_message!(CredentialProposal);
 
 #[cfg(test)]
@@ -76,6 +111,13 @@ pub mod tests {
             .add_value(name, value, MimeType::Plain).unwrap()
     }
 
+    pub fn _filter_format() -> AttachmentFormat {
+        AttachmentFormat {
+            attach_id: String::from("libindy-cred-filter-0"),
+            format: AttachmentFormat::HLINDY_CRED_FILTER.to_string(),
+        }
+    }
+
     pub fn _credential_proposal() -> CredentialProposal {
         CredentialProposal {
             id: MessageId::id(),
@@ -84,9 +126,34 @@ pub mod tests {
             schema_id: _schema_id(),
             thread: Some(thread()),
             cred_def_id: _cred_def_id(),
+            formats: vec![],
+            filters_attach: Attachments::new(),
         }
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_credential_proposal_validate_works() {
+        _credential_proposal().validate().unwrap();
+
+        let proposal = CredentialProposal { schema_id: String::new(), cred_def_id: String::new(), .._credential_proposal() };
+        proposal.validate().unwrap();
+
+        let proposal = CredentialProposal { formats: vec![_filter_format()], .._credential_proposal() };
+        assert_eq!(VcxErrorKind::InvalidOption, proposal.validate().unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_credential_offer_is_not_deserialized_as_proposal() {
+        let offer = json!({
+            "@id": "offer_id",
+            "credential_preview": _credential_preview_data(),
+            "offers~attach": []
+        });
+        assert!(serde_json::from_value::<CredentialProposal>(offer).is_err());
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_credential_proposal_build_works() {
@@ -101,4 +168,13 @@ pub mod tests {
 
         assert_eq!(_credential_proposal(), credential_proposal);
     }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_credential_proposal_thread_id_works() {
+        assert_eq!(thread_id(), _credential_proposal().thread_id());
+
+        let credential_proposal = CredentialProposal::create();
+        assert_eq!(credential_proposal.id.0, credential_proposal.thread_id());
+    }
 }
@@ -1,7 +1,8 @@
 use crate::error::VcxResult;
 use crate::aries::messages::thread::Thread;
 use crate::aries::messages::a2a::{A2AMessage, MessageId};
-use crate::aries::messages::attachment::{AttachmentId, Attachments};
+use crate::aries::messages::a2a::protocol_version::ProtocolVersion;
+use crate::aries::messages::attachment::{AttachmentFormat, AttachmentId, Attachments};
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
 pub struct CredentialRequest {
@@ -9,6 +10,8 @@ pub struct CredentialRequest {
     pub id: MessageId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub formats: Vec<AttachmentFormat>,
     #[serde(rename = "requests~attach")]
     pub requests_attach: Attachments,
     #[serde(rename = "~thread")]
@@ -32,6 +35,7 @@ impl CredentialRequest {
 }
 
 threadlike!(CredentialRequest);
+attachment_formats!(CredentialRequest, requests_attach, AttachmentFormat::HLINDY_CRED_REQUEST);
 a2a_message!(CredentialRequest);
 
 #[cfg(test)]
@@ -62,6 +66,7 @@ pub mod tests {
         CredentialRequest {
             id: MessageId::id(),
             comment: Some(_comment()),
+            formats: vec![],
             requests_attach: attachment,
             thread: thread(),
         }
@@ -1,5 +1,6 @@
 use crate::aries::messages::a2a::message_type::MessageType;
 use crate::aries::messages::a2a::message_family::MessageFamilies;
+use crate::aries::messages::a2a::protocol_version::ProtocolVersion;
 use crate::aries::messages::mime_type::MimeType;
 use crate::error::VcxResult;
 
@@ -21,6 +22,11 @@ impl CredentialPreviewData {
         CredentialPreviewData::default()
     }
 
+    pub fn set_protocol_version(mut self, version: ProtocolVersion) -> CredentialPreviewData {
+        self._type = MessageType::build(MessageFamilies::CredentialIssuance.for_version(version), "credential-preview");
+        self
+    }
+
     pub fn add_value(mut self, name: &str, value: &str, mime_type: MimeType) -> VcxResult<CredentialPreviewData> {
         let data_value = match mime_type {
             MimeType::Plain => {
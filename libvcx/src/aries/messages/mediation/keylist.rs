@@ -0,0 +1,71 @@
+use crate::aries::messages::a2a::{A2AMessage, MessageId};
+use crate::aries::messages::thread::Thread;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Keylist {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    pub keys: Vec<KeylistKey>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pagination: Option<KeylistPagination>,
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KeylistKey {
+    pub recipient_key: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KeylistPagination {
+    pub count: u32,
+    pub offset: u32,
+    pub remaining: u32,
+}
+
+impl Keylist {
+    pub fn create() -> Keylist {
+        Keylist::default()
+    }
+
+    pub fn set_keys(mut self, keys: Vec<String>) -> Self {
+        self.keys = keys.into_iter().map(|recipient_key| KeylistKey { recipient_key }).collect();
+        self
+    }
+
+    pub fn recipient_keys(&self) -> Vec<String> {
+        self.keys.iter().map(|key| key.recipient_key.clone()).collect()
+    }
+}
+
+a2a_message!(Keylist);
+threadlike!(Keylist);
+
+#[cfg(test)]
+pub mod tests {
+    use crate::aries::messages::connection::response::tests::*;
+    use crate::aries::messages::mediation::keylist_update::tests::*;
+
+    use super::*;
+
+    pub fn _keylist() -> Keylist {
+        Keylist {
+            id: MessageId::id(),
+            keys: vec![KeylistKey { recipient_key: _recipient_key() }],
+            pagination: None,
+            thread: _thread(),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_keylist_build_works() {
+        let keylist: Keylist = Keylist::create()
+            .set_keys(vec![_recipient_key()])
+            .set_thread_id(&_thread_id());
+
+        assert_eq!(_keylist(), keylist);
+        assert_eq!(vec![_recipient_key()], keylist.recipient_keys());
+    }
+}
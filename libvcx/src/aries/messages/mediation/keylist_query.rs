@@ -0,0 +1,59 @@
+use serde_json::Value;
+
+use crate::aries::messages::a2a::{A2AMessage, MessageId};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct KeylistQuery {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paginate: Option<KeylistQueryPaginate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KeylistQueryPaginate {
+    pub limit: u32,
+    pub offset: u32,
+}
+
+impl KeylistQuery {
+    pub fn create() -> KeylistQuery {
+        KeylistQuery::default()
+    }
+
+    pub fn set_filter(mut self, filter: Option<Value>) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn set_paginate(mut self, limit: u32, offset: u32) -> Self {
+        self.paginate = Some(KeylistQueryPaginate { limit, offset });
+        self
+    }
+}
+
+a2a_message!(KeylistQuery);
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    pub fn _keylist_query() -> KeylistQuery {
+        KeylistQuery {
+            id: MessageId::id(),
+            filter: None,
+            paginate: Some(KeylistQueryPaginate { limit: 30, offset: 0 }),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_keylist_query_build_works() {
+        let query: KeylistQuery = KeylistQuery::create()
+            .set_paginate(30, 0);
+
+        assert_eq!(_keylist_query(), query);
+    }
+}
@@ -0,0 +1,82 @@
+use crate::aries::messages::a2a::{A2AMessage, MessageId};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct KeylistUpdate {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    pub updates: Vec<KeylistUpdateItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KeylistUpdateItem {
+    pub recipient_key: String,
+    pub action: KeylistUpdateAction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum KeylistUpdateAction {
+    Add,
+    Remove,
+}
+
+impl KeylistUpdate {
+    pub fn create() -> KeylistUpdate {
+        KeylistUpdate::default()
+    }
+
+    pub fn add_key(mut self, recipient_key: String) -> Self {
+        self.updates.push(KeylistUpdateItem { recipient_key, action: KeylistUpdateAction::Add });
+        self
+    }
+
+    pub fn remove_key(mut self, recipient_key: String) -> Self {
+        self.updates.push(KeylistUpdateItem { recipient_key, action: KeylistUpdateAction::Remove });
+        self
+    }
+}
+
+a2a_message!(KeylistUpdate);
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    pub fn _recipient_key() -> String {
+        String::from("GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL")
+    }
+
+    pub fn _other_recipient_key() -> String {
+        String::from("8HH5gYEeNc3z7PYXmd54d4x6qAfCNrqQqEB3nS7Zfu7K")
+    }
+
+    pub fn _keylist_update() -> KeylistUpdate {
+        KeylistUpdate {
+            id: MessageId::id(),
+            updates: vec![
+                KeylistUpdateItem { recipient_key: _recipient_key(), action: KeylistUpdateAction::Add },
+                KeylistUpdateItem { recipient_key: _other_recipient_key(), action: KeylistUpdateAction::Remove },
+            ],
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_keylist_update_build_works() {
+        let keylist_update: KeylistUpdate = KeylistUpdate::create()
+            .add_key(_recipient_key())
+            .remove_key(_other_recipient_key());
+
+        assert_eq!(_keylist_update(), keylist_update);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_keylist_update_serialization_works() {
+        let value = serde_json::to_value(_keylist_update().to_a2a_message()).unwrap();
+
+        assert_eq!("did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/coordinate-mediation/1.0/keylist-update", value["@type"]);
+        assert_eq!("add", value["updates"][0]["action"]);
+        assert_eq!("remove", value["updates"][1]["action"]);
+    }
+}
@@ -0,0 +1,92 @@
+use crate::aries::messages::a2a::{A2AMessage, MessageId};
+use crate::aries::messages::mediation::keylist_update::KeylistUpdateAction;
+use crate::aries::messages::thread::Thread;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct KeylistUpdateResponse {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    pub updated: Vec<KeylistUpdated>,
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KeylistUpdated {
+    pub recipient_key: String,
+    pub action: KeylistUpdateAction,
+    pub result: KeylistUpdateResult,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeylistUpdateResult {
+    ClientError,
+    ServerError,
+    NoChange,
+    Success,
+}
+
+impl KeylistUpdateResponse {
+    pub fn create() -> KeylistUpdateResponse {
+        KeylistUpdateResponse::default()
+    }
+
+    pub fn set_updated(mut self, updated: Vec<KeylistUpdated>) -> Self {
+        self.updated = updated;
+        self
+    }
+}
+
+a2a_message!(KeylistUpdateResponse);
+threadlike!(KeylistUpdateResponse);
+
+#[cfg(test)]
+pub mod tests {
+    use crate::aries::messages::connection::response::tests::*;
+    use crate::aries::messages::mediation::keylist_update::tests::*;
+
+    use super::*;
+
+    pub fn _updated() -> Vec<KeylistUpdated> {
+        vec![
+            KeylistUpdated { recipient_key: _recipient_key(), action: KeylistUpdateAction::Add, result: KeylistUpdateResult::Success },
+            KeylistUpdated { recipient_key: _other_recipient_key(), action: KeylistUpdateAction::Remove, result: KeylistUpdateResult::NoChange },
+        ]
+    }
+
+    pub fn _keylist_update_response() -> KeylistUpdateResponse {
+        KeylistUpdateResponse {
+            id: MessageId::id(),
+            updated: _updated(),
+            thread: _thread(),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_keylist_update_response_build_works() {
+        let response: KeylistUpdateResponse = KeylistUpdateResponse::create()
+            .set_updated(_updated())
+            .set_thread_id(&_thread_id());
+
+        assert_eq!(_keylist_update_response(), response);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_keylist_update_response_deserialization_works() {
+        let message = json!({
+            "@type": "did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/coordinate-mediation/1.0/keylist-update-response",
+            "@id": "testid",
+            "updated": [
+                {"recipient_key": _recipient_key(), "action": "add", "result": "success"},
+                {"recipient_key": _other_recipient_key(), "action": "remove", "result": "no_change"}
+            ],
+            "~thread": {"thid": _thread_id()}
+        });
+
+        let message: A2AMessage = serde_json::from_value(message).unwrap();
+        assert_eq!(A2AMessage::KeylistUpdateResponse(_keylist_update_response()), message);
+    }
+}
@@ -0,0 +1,66 @@
+use crate::aries::messages::a2a::{A2AMessage, MessageId};
+use crate::aries::messages::thread::Thread;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct MediateGrant {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    pub endpoint: String,
+    pub routing_keys: Vec<String>,
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+}
+
+impl MediateGrant {
+    pub fn create() -> MediateGrant {
+        MediateGrant::default()
+    }
+
+    pub fn set_endpoint(mut self, endpoint: String) -> Self {
+        self.endpoint = endpoint;
+        self
+    }
+
+    pub fn set_routing_keys(mut self, routing_keys: Vec<String>) -> Self {
+        self.routing_keys = routing_keys;
+        self
+    }
+}
+
+a2a_message!(MediateGrant);
+threadlike!(MediateGrant);
+
+#[cfg(test)]
+pub mod tests {
+    use crate::aries::messages::connection::response::tests::*;
+
+    use super::*;
+
+    pub fn _endpoint() -> String {
+        String::from("http://localhost:8020/mediator")
+    }
+
+    pub fn _routing_keys() -> Vec<String> {
+        vec![String::from("3LYuxJBJkngDbvJj4zjx13DBUdZ2P96eNybwd2n9L9AU")]
+    }
+
+    pub fn _mediate_grant() -> MediateGrant {
+        MediateGrant {
+            id: MessageId::id(),
+            endpoint: _endpoint(),
+            routing_keys: _routing_keys(),
+            thread: _thread(),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_mediate_grant_build_works() {
+        let grant: MediateGrant = MediateGrant::create()
+            .set_endpoint(_endpoint())
+            .set_routing_keys(_routing_keys())
+            .set_thread_id(&_thread_id());
+
+        assert_eq!(_mediate_grant(), grant);
+    }
+}
@@ -0,0 +1,32 @@
+use crate::aries::messages::a2a::{A2AMessage, MessageId};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct MediateRequest {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+}
+
+impl MediateRequest {
+    pub fn create() -> MediateRequest {
+        MediateRequest::default()
+    }
+}
+
+a2a_message!(MediateRequest);
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    pub fn _mediate_request() -> MediateRequest {
+        MediateRequest {
+            id: MessageId::id(),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_mediate_request_build_works() {
+        assert_eq!(_mediate_request(), MediateRequest::create());
+    }
+}
@@ -4,9 +4,15 @@ pub mod thread;
 pub mod a2a;
 #[macro_use]
 pub mod ack;
+#[macro_use]
+pub mod timing;
 pub mod connection;
+pub mod did_exchange;
+pub mod out_of_band;
 pub mod error;
 pub mod forward;
+pub mod transport;
+#[macro_use]
 pub mod attachment;
 pub mod mime_type;
 pub mod status;
@@ -15,4 +21,6 @@ pub mod proof_presentation;
 pub mod discovery;
 pub mod trust_ping;
 pub mod basic_message;
+pub mod mediation;
+pub mod pickup;
 pub mod localization;
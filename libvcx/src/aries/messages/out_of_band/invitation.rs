@@ -0,0 +1,332 @@
+use rust_base58::{FromBase58, ToBase58};
+
+use crate::aries::messages::a2a::{A2AMessage, MessageId};
+use crate::aries::messages::a2a::message_family::MessageFamilies;
+use crate::aries::messages::connection::did_doc::DidDoc;
+use crate::aries::messages::connection::handshake_protocol::HandshakeProtocol;
+use crate::aries::messages::connection::invite::{resolve_public_did_doc_async, unqualified_sov_did};
+use crate::aries::utils::task::block_on;
+use crate::error::prelude::*;
+use crate::profile::ProfileContext;
+
+pub const DID_KEY_PREFIX: &str = "did:key:z";
+const ED25519_MULTICODEC_PREFIX: [u8; 2] = [0xed, 0x01];
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
+pub struct OutOfBandInvitation {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(default)]
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goal_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goal: Option<String>,
+    #[serde(default)]
+    pub handshake_protocols: Vec<String>,
+    // Attachments are kept raw as their ids are chosen by the sender
+    #[serde(default)]
+    #[serde(rename = "requests~attach")]
+    pub requests_attach: Vec<serde_json::Value>,
+    #[serde(alias = "service")]
+    pub services: Vec<ServiceResolvable>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum ServiceResolvable {
+    Inline(InlineService),
+    Did(String),
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct InlineService {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    #[serde(default)]
+    pub recipient_keys: Vec<String>,
+    #[serde(default)]
+    pub routing_keys: Vec<String>,
+    pub service_endpoint: String,
+}
+
+impl OutOfBandInvitation {
+    pub fn create() -> OutOfBandInvitation {
+        OutOfBandInvitation::default()
+    }
+
+    pub fn set_id(mut self, id: String) -> OutOfBandInvitation {
+        self.id = MessageId(id);
+        self
+    }
+
+    pub fn set_label(mut self, label: String) -> OutOfBandInvitation {
+        self.label = label;
+        self
+    }
+
+    pub fn set_goal_code(mut self, goal_code: Option<String>) -> OutOfBandInvitation {
+        self.goal_code = goal_code;
+        self
+    }
+
+    pub fn set_goal(mut self, goal: Option<String>) -> OutOfBandInvitation {
+        self.goal = goal;
+        self
+    }
+
+    pub fn set_handshake_protocols(mut self, handshake_protocols: Vec<String>) -> OutOfBandInvitation {
+        self.handshake_protocols = handshake_protocols;
+        self
+    }
+
+    pub fn add_service(mut self, service: ServiceResolvable) -> OutOfBandInvitation {
+        self.services.push(service);
+        self
+    }
+
+    pub fn supports_handshake_protocol(&self, family: &MessageFamilies) -> bool {
+        self.handshake_protocols.iter().any(|pid| family.is_protocol_id(pid))
+    }
+
+    /**
+    Returns the first handshake protocol offered by invitation which is supported, keeping the sender's preference order.
+     */
+    pub fn handshake_protocol(&self) -> Option<HandshakeProtocol> {
+        self.handshake_protocols.iter()
+            .find_map(|pid| HandshakeProtocol::from_protocol_id(pid))
+    }
+
+    pub fn inline_service(&self) -> Option<&InlineService> {
+        self.services.iter()
+            .find_map(|service| match service {
+                ServiceResolvable::Inline(service) => Some(service),
+                ServiceResolvable::Did(_) => None
+            })
+    }
+
+    /**
+    Returns the first service the connection can be established through: inline service or service
+    referenced by `did:sov` DID, keeping the sender's preference order.
+     */
+    pub fn connection_service(&self) -> Option<&ServiceResolvable> {
+        self.services.iter()
+            .find(|service| match service {
+                ServiceResolvable::Inline(_) => true,
+                ServiceResolvable::Did(did) => unqualified_sov_did(did).is_ok()
+            })
+    }
+
+    pub fn validate_for_connection(&self) -> VcxResult<()> {
+        if self.handshake_protocol().is_none() {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidInviteDetail,
+                                          format!("Out-of-band invitation does not offer a supported handshake protocol: {:?}", self.handshake_protocols)));
+        }
+
+        let service = match self.connection_service() {
+            Some(ServiceResolvable::Inline(service)) => service,
+            Some(ServiceResolvable::Did(_)) => return Ok(()),
+            None => return Err(VcxError::from_msg(VcxErrorKind::InvalidInviteDetail, "Out-of-band invitation contains neither inline service nor service referenced by did:sov DID"))
+        };
+
+        if service.recipient_keys.is_empty() {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidInviteDetail, "Out-of-band invitation service does not contain recipient keys"));
+        }
+
+        service.recipient_keys.iter()
+            .chain(service.routing_keys.iter())
+            .map(|key| key_to_verkey(key).map(|_| ()))
+            .collect::<VcxResult<()>>()
+    }
+
+    /**
+    DidDoc of the inviter, service referenced by DID is resolved from the ledger.
+     */
+    pub fn resolve_did_doc(&self, profile: &ProfileContext) -> VcxResult<DidDoc> {
+        block_on(self.resolve_did_doc_async(profile))
+    }
+
+    pub async fn resolve_did_doc_async(&self, profile: &ProfileContext) -> VcxResult<DidDoc> {
+        match self.connection_service() {
+            Some(ServiceResolvable::Did(did)) => resolve_public_did_doc_async(profile, did).await,
+            _ => Ok(DidDoc::from(self.clone()))
+        }
+    }
+}
+
+/**
+Converts Ed25519 `did:key` (as used in out-of-band services) into plain base58 verkey. Plain verkeys are returned as is.
+ */
+pub fn key_to_verkey(key: &str) -> VcxResult<String> {
+    if !key.starts_with(DID_KEY_PREFIX) {
+        return Ok(key.to_string());
+    }
+    let bytes = key[DID_KEY_PREFIX.len()..].from_base58()
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::NotBase58, format!("Invalid did:key {}, err: {:?}", key, err)))?;
+    if bytes.len() != ED25519_MULTICODEC_PREFIX.len() + 32 || bytes[..2] != ED25519_MULTICODEC_PREFIX {
+        return Err(VcxError::from_msg(VcxErrorKind::InvalidVerkey, format!("Unsupported did:key {}, only Ed25519 keys are supported", key)));
+    }
+    Ok(bytes[2..].to_base58())
+}
+
+pub fn verkey_to_did_key(verkey: &str) -> VcxResult<String> {
+    let mut bytes = ED25519_MULTICODEC_PREFIX.to_vec();
+    bytes.extend(verkey.from_base58()
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::NotBase58, format!("Invalid verkey {}, err: {:?}", verkey, err)))?);
+    Ok(format!("{}{}", DID_KEY_PREFIX, bytes.to_base58()))
+}
+
+impl From<OutOfBandInvitation> for DidDoc {
+    fn from(invitation: OutOfBandInvitation) -> DidDoc {
+        let mut did_doc: DidDoc = DidDoc::default();
+        did_doc.set_id(invitation.id.0.clone()); // TODO: FIXME DIDDoc id always MUST be a valid DID
+        if let Some(service) = invitation.inline_service() {
+            let to_verkeys = |keys: &Vec<String>| keys.iter()
+                .map(|key| key_to_verkey(key).unwrap_or(key.to_string()))
+                .collect::<Vec<String>>();
+            did_doc.set_service_endpoint(service.service_endpoint.clone());
+            did_doc.set_keys(to_verkeys(&service.recipient_keys), to_verkeys(&service.routing_keys));
+        }
+        did_doc
+    }
+}
+
+a2a_message!(OutOfBandInvitation);
+
+#[cfg(test)]
+pub mod tests {
+    use crate::aries::messages::connection::did_doc::tests::*;
+    use crate::utils::devsetup::SetupMocks;
+
+    use super::*;
+
+    pub fn _did_key() -> String {
+        String::from("did:key:z6MkukGVb3mRvTu1msArDKY9UwxeZFGjmwnCKtdQttr4Fk6i")
+    }
+
+    pub fn _inline_service() -> InlineService {
+        InlineService {
+            id: String::from("#inline"),
+            type_: String::from("did-communication"),
+            recipient_keys: vec![_did_key()],
+            routing_keys: vec![],
+            service_endpoint: _service_endpoint(),
+        }
+    }
+
+    pub fn _out_of_band_invitation() -> OutOfBandInvitation {
+        OutOfBandInvitation {
+            id: MessageId::id(),
+            label: _label(),
+            goal_code: None,
+            goal: None,
+            handshake_protocols: vec![MessageFamilies::Connections.id()],
+            requests_attach: vec![],
+            services: vec![ServiceResolvable::Inline(_inline_service())],
+        }
+    }
+
+    pub fn _out_of_band_invitation_json() -> String {
+        json!({
+            "@type": "https://didcomm.org/out-of-band/1.0/invitation",
+            "@id": "testid",
+            "label": _label(),
+            "handshake_protocols": ["https://didcomm.org/connections/1.0"],
+            "services": [
+                {
+                    "id": "#inline",
+                    "type": "did-communication",
+                    "recipientKeys": [_did_key()],
+                    "serviceEndpoint": _service_endpoint()
+                },
+                "did:sov:LjgpST2rjsoxYegQDRm7EL"
+            ]
+        }).to_string()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_out_of_band_invitation_build_works() {
+        let invitation: OutOfBandInvitation = OutOfBandInvitation::create()
+            .set_label(_label())
+            .set_handshake_protocols(vec![MessageFamilies::Connections.id()])
+            .add_service(ServiceResolvable::Inline(_inline_service()));
+
+        assert_eq!(_out_of_band_invitation(), invitation);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_out_of_band_invitation_deserialize_works_for_inline_and_did_services() {
+        let invitation: OutOfBandInvitation = serde_json::from_str(&_out_of_band_invitation_json()).unwrap();
+
+        assert_eq!(2, invitation.services.len());
+        assert_eq!(ServiceResolvable::Inline(_inline_service()), invitation.services[0]);
+        assert_eq!(ServiceResolvable::Did(String::from("did:sov:LjgpST2rjsoxYegQDRm7EL")), invitation.services[1]);
+        assert!(invitation.supports_handshake_protocol(&MessageFamilies::Connections));
+        assert!(!invitation.supports_handshake_protocol(&MessageFamilies::DidExchange));
+        invitation.validate_for_connection().unwrap();
+
+        let invitation = invitation.set_handshake_protocols(vec![String::from("https://example.org/didcomm/connections/1.0")]);
+        assert!(!invitation.supports_handshake_protocol(&MessageFamilies::Connections));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_out_of_band_invitation_handshake_protocol_respects_sender_preference() {
+        let invitation = _out_of_band_invitation()
+            .set_handshake_protocols(vec![String::from("https://didcomm.org/didexchange/1.0"), MessageFamilies::Connections.id()]);
+        assert_eq!(Some(HandshakeProtocol::DidExchange), invitation.handshake_protocol());
+
+        let invitation = _out_of_band_invitation()
+            .set_handshake_protocols(vec![String::from("https://didcomm.org/didexchange/2.0"), MessageFamilies::Connections.id()]);
+        assert_eq!(Some(HandshakeProtocol::Connections), invitation.handshake_protocol());
+        invitation.validate_for_connection().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_out_of_band_invitation_validate_fails_for_unsupported_invitations() {
+        let invitation = _out_of_band_invitation().set_handshake_protocols(vec![]);
+        assert_eq!(VcxErrorKind::InvalidInviteDetail, invitation.validate_for_connection().unwrap_err().kind());
+
+        let mut invitation = _out_of_band_invitation();
+        invitation.services = vec![ServiceResolvable::Did(String::from("did:peer:LjgpST2rjsoxYegQDRm7EL"))];
+        assert_eq!(VcxErrorKind::InvalidInviteDetail, invitation.validate_for_connection().unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_out_of_band_invitation_resolves_did_service() {
+        let _setup = SetupMocks::init();
+
+        let mut invitation = _out_of_band_invitation();
+        invitation.services = vec![ServiceResolvable::Did(String::from("did:peer:LjgpST2rjsoxYegQDRm7EL")),
+                                   ServiceResolvable::Did(String::from("did:sov:V4SGRU86Z58d6TV7PBUe6f"))];
+        invitation.validate_for_connection().unwrap();
+
+        let did_doc = invitation.resolve_did_doc(&ProfileContext::Global).unwrap();
+        assert_eq!("V4SGRU86Z58d6TV7PBUe6f", did_doc.id);
+        assert_eq!(vec![crate::utils::constants::VERKEY.to_string()], did_doc.recipient_keys());
+        assert_eq!("http://localhost:8080", did_doc.get_endpoint());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_did_key_conversion_works() {
+        assert_eq!(_key_1(), key_to_verkey(&_did_key()).unwrap());
+        assert_eq!(_key_1(), key_to_verkey(&_key_1()).unwrap());
+        assert_eq!(_did_key(), verkey_to_did_key(&_key_1()).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_did_doc_from_out_of_band_invitation_works() {
+        let did_doc: DidDoc = _out_of_band_invitation().into();
+
+        assert_eq!(vec![_key_1()], did_doc.recipient_keys());
+        assert_eq!(_service_endpoint(), did_doc.get_endpoint());
+    }
+}
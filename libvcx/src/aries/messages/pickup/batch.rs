@@ -0,0 +1,68 @@
+use serde_json::Value;
+
+use crate::aries::messages::a2a::{A2AMessage, MessageId};
+use crate::aries::messages::thread::Thread;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Batch {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(rename = "messages~attach")]
+    pub messages: Vec<BatchMessage>,
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+}
+
+/**
+Message held by the mediator, `message` is the packed message as it has been forwarded.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BatchMessage {
+    #[serde(rename = "@id")]
+    pub id: String,
+    pub message: Value,
+}
+
+impl Batch {
+    pub fn create() -> Batch {
+        Batch::default()
+    }
+
+    pub fn set_messages(mut self, messages: Vec<BatchMessage>) -> Self {
+        self.messages = messages;
+        self
+    }
+}
+
+a2a_message!(Batch, PickupBatch);
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    pub fn _batch_message() -> BatchMessage {
+        BatchMessage {
+            id: String::from("06ca25f6-d3c5-48ac-8eee-1a9e29120c31"),
+            message: json!({"protected": "eyJlbmMiOiJ4Y2hhY2hhMjBwb2x5MTMwNV9pZXRmIn0=", "iv": "iv", "ciphertext": "ciphertext", "tag": "tag"}),
+        }
+    }
+
+    pub fn _batch() -> Batch {
+        Batch {
+            id: MessageId::id(),
+            messages: vec![_batch_message()],
+            thread: None,
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_batch_build_works() {
+        let batch: Batch = Batch::create()
+            .set_messages(vec![_batch_message()]);
+
+        assert_eq!(_batch(), batch);
+        assert_eq!(_batch_message().id, serde_json::to_value(&batch).unwrap()["messages~attach"][0]["@id"]);
+    }
+}
@@ -0,0 +1,70 @@
+use crate::aries::messages::a2a::{A2AMessage, MessageId};
+use crate::aries::messages::attachment::AttachmentData;
+use crate::aries::messages::thread::Thread;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Delivery {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipient_key: Option<String>,
+    #[serde(rename = "~attach")]
+    pub attachments: Vec<DeliveredMessage>,
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+}
+
+/**
+Message held by the mediator, its `@id` has to be acknowledged by `messages-received` before the mediator removes it.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeliveredMessage {
+    #[serde(rename = "@id")]
+    pub id: String,
+    pub data: AttachmentData,
+}
+
+impl Delivery {
+    pub fn create() -> Delivery {
+        Delivery::default()
+    }
+
+    pub fn set_attachments(mut self, attachments: Vec<DeliveredMessage>) -> Self {
+        self.attachments = attachments;
+        self
+    }
+}
+
+a2a_message!(Delivery, PickupDelivery);
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    pub fn _delivered_message() -> DeliveredMessage {
+        DeliveredMessage {
+            id: String::from("123456781"),
+            data: AttachmentData::Base64(base64::encode(r#"{"protected":"protected","iv":"iv","ciphertext":"ciphertext","tag":"tag"}"#)),
+        }
+    }
+
+    pub fn _delivery() -> Delivery {
+        Delivery {
+            id: MessageId::id(),
+            recipient_key: None,
+            attachments: vec![_delivered_message()],
+            thread: None,
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_delivery_build_works() {
+        let delivery: Delivery = Delivery::create()
+            .set_attachments(vec![_delivered_message()]);
+
+        assert_eq!(_delivery(), delivery);
+        assert_eq!(json!("123456781"), serde_json::to_value(&delivery).unwrap()["~attach"][0]["@id"]);
+    }
+}
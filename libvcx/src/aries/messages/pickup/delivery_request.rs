@@ -0,0 +1,66 @@
+use crate::aries::messages::a2a::{A2AMessage, MessageId};
+use crate::aries::messages::transport::Transport;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeliveryRequest {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    pub limit: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipient_key: Option<String>,
+    #[serde(rename = "~transport")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transport: Option<Transport>,
+}
+
+impl Default for DeliveryRequest {
+    fn default() -> DeliveryRequest {
+        DeliveryRequest {
+            id: MessageId::default(),
+            limit: 0,
+            recipient_key: None,
+            transport: Some(Transport::return_route_all()),
+        }
+    }
+}
+
+impl DeliveryRequest {
+    pub fn create() -> DeliveryRequest {
+        DeliveryRequest::default()
+    }
+
+    pub fn set_limit(mut self, limit: u32) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    pub fn set_recipient_key(mut self, recipient_key: Option<String>) -> Self {
+        self.recipient_key = recipient_key;
+        self
+    }
+}
+
+a2a_message!(DeliveryRequest, PickupDeliveryRequest);
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    pub fn _delivery_request() -> DeliveryRequest {
+        DeliveryRequest {
+            id: MessageId::id(),
+            limit: 10,
+            recipient_key: None,
+            transport: Some(Transport::return_route_all()),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_delivery_request_build_works() {
+        let request: DeliveryRequest = DeliveryRequest::create()
+            .set_limit(10);
+
+        assert_eq!(_delivery_request(), request);
+    }
+}
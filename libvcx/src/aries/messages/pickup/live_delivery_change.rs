@@ -0,0 +1,57 @@
+use crate::aries::messages::a2a::{A2AMessage, MessageId};
+use crate::aries::messages::transport::Transport;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LiveDeliveryChange {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    pub live_delivery: bool,
+    #[serde(rename = "~transport")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transport: Option<Transport>,
+}
+
+impl Default for LiveDeliveryChange {
+    fn default() -> LiveDeliveryChange {
+        LiveDeliveryChange {
+            id: MessageId::default(),
+            live_delivery: false,
+            transport: Some(Transport::return_route_all()),
+        }
+    }
+}
+
+impl LiveDeliveryChange {
+    pub fn create() -> LiveDeliveryChange {
+        LiveDeliveryChange::default()
+    }
+
+    pub fn set_live_delivery(mut self, live_delivery: bool) -> Self {
+        self.live_delivery = live_delivery;
+        self
+    }
+}
+
+a2a_message!(LiveDeliveryChange, PickupLiveDeliveryChange);
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    pub fn _live_delivery_change() -> LiveDeliveryChange {
+        LiveDeliveryChange {
+            id: MessageId::id(),
+            live_delivery: true,
+            transport: Some(Transport::return_route_all()),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_live_delivery_change_build_works() {
+        let change: LiveDeliveryChange = LiveDeliveryChange::create()
+            .set_live_delivery(true);
+
+        assert_eq!(_live_delivery_change(), change);
+    }
+}
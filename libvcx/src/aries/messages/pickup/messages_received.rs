@@ -0,0 +1,57 @@
+use crate::aries::messages::a2a::{A2AMessage, MessageId};
+use crate::aries::messages::transport::Transport;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MessagesReceived {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    pub message_id_list: Vec<String>,
+    #[serde(rename = "~transport")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transport: Option<Transport>,
+}
+
+impl Default for MessagesReceived {
+    fn default() -> MessagesReceived {
+        MessagesReceived {
+            id: MessageId::default(),
+            message_id_list: Vec::new(),
+            transport: Some(Transport::return_route_all()),
+        }
+    }
+}
+
+impl MessagesReceived {
+    pub fn create() -> MessagesReceived {
+        MessagesReceived::default()
+    }
+
+    pub fn set_message_id_list(mut self, message_id_list: Vec<String>) -> Self {
+        self.message_id_list = message_id_list;
+        self
+    }
+}
+
+a2a_message!(MessagesReceived, PickupMessagesReceived);
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    pub fn _messages_received() -> MessagesReceived {
+        MessagesReceived {
+            id: MessageId::id(),
+            message_id_list: vec![String::from("123456781")],
+            transport: Some(Transport::return_route_all()),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_messages_received_build_works() {
+        let received: MessagesReceived = MessagesReceived::create()
+            .set_message_id_list(vec![String::from("123456781")]);
+
+        assert_eq!(_messages_received(), received);
+    }
+}
@@ -0,0 +1,8 @@
+pub mod status_request;
+pub mod status;
+pub mod batch_pickup;
+pub mod batch;
+pub mod delivery_request;
+pub mod delivery;
+pub mod messages_received;
+pub mod live_delivery_change;
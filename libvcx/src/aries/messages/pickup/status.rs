@@ -0,0 +1,104 @@
+use crate::aries::messages::a2a::{A2AMessage, MessageId};
+use crate::aries::messages::thread::Thread;
+
+/**
+Status of the queue of messages held by the mediator. Fields following `message_count` are optional,
+`duration_waited`, `last_added_time`, `last_delivered_time`, `last_removed_time` and `total_size` are sent
+by messagepickup/1.0 mediators, the rest by messagepickup/2.0 mediators.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Status {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    pub message_count: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipient_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_waited: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_added_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_delivered_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_removed_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub longest_waited_seconds: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub newest_received_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oldest_received_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub live_delivery: Option<bool>,
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+}
+
+impl Status {
+    pub fn create() -> Status {
+        Status::default()
+    }
+
+    pub fn set_message_count(mut self, message_count: u32) -> Self {
+        self.message_count = message_count;
+        self
+    }
+
+    pub fn set_live_delivery(mut self, live_delivery: bool) -> Self {
+        self.live_delivery = Some(live_delivery);
+        self
+    }
+}
+
+a2a_message!(Status, PickupStatus);
+
+#[cfg(test)]
+pub mod tests {
+    use crate::aries::messages::a2a::message_family::MessageFamilies;
+    use crate::aries::messages::a2a::message_type::MessageType;
+
+    use super::*;
+
+    pub fn _status() -> Status {
+        Status {
+            id: MessageId::id(),
+            message_count: 3,
+            ..Status::default()
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_status_build_works() {
+        let status: Status = Status::create()
+            .set_message_count(3);
+
+        assert_eq!(_status(), status);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_status_is_deserialized_for_both_versions() {
+        let status_v1 = json!({
+            "@id": "testid",
+            "@type": MessageType::build(MessageFamilies::MessagePickup, "status"),
+            "message_count": 3,
+            "total_size": 8096
+        });
+        assert_eq!(A2AMessage::PickupStatus(Status { total_size: Some(8096), .._status() }),
+                   serde_json::from_value(status_v1).unwrap());
+
+        let status_v2 = json!({
+            "@id": "testid",
+            "@type": MessageType::build(MessageFamilies::MessagePickupV2, "status"),
+            "message_count": 3,
+            "live_delivery": true
+        });
+        assert_eq!(A2AMessage::PickupStatusV2(_status().set_live_delivery(true)),
+                   serde_json::from_value(status_v2).unwrap());
+    }
+}
@@ -0,0 +1,58 @@
+use crate::aries::messages::a2a::{A2AMessage, MessageId};
+use crate::aries::messages::transport::Transport;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StatusRequest {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipient_key: Option<String>,
+    #[serde(rename = "~transport")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transport: Option<Transport>,
+}
+
+impl Default for StatusRequest {
+    fn default() -> StatusRequest {
+        StatusRequest {
+            id: MessageId::default(),
+            recipient_key: None,
+            transport: Some(Transport::return_route_all()),
+        }
+    }
+}
+
+impl StatusRequest {
+    pub fn create() -> StatusRequest {
+        StatusRequest::default()
+    }
+
+    pub fn set_recipient_key(mut self, recipient_key: Option<String>) -> Self {
+        self.recipient_key = recipient_key;
+        self
+    }
+}
+
+a2a_message!(StatusRequest, PickupStatusRequest);
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    pub fn _status_request() -> StatusRequest {
+        StatusRequest {
+            id: MessageId::id(),
+            recipient_key: None,
+            transport: Some(Transport::return_route_all()),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_status_request_build_works() {
+        let request: StatusRequest = StatusRequest::create();
+
+        assert_eq!(_status_request(), request);
+        assert_eq!(json!("all"), serde_json::to_value(&request).unwrap()["~transport"]["return_route"]);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::aries::messages::a2a::{A2AMessage, MessageId};
 use crate::aries::messages::ack::PleaseAck;
-use crate::aries::messages::attachment::{AttachmentId, Attachments};
+use crate::aries::messages::a2a::protocol_version::ProtocolVersion;
+use crate::aries::messages::attachment::{AttachmentFormat, AttachmentId, Attachments};
 use crate::aries::messages::thread::Thread;
 use crate::error::prelude::*;
 
@@ -10,6 +11,8 @@ pub struct Presentation {
     pub id: MessageId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub formats: Vec<AttachmentFormat>,
     #[serde(rename = "presentations~attach")]
     pub presentations_attach: Attachments,
     #[serde(rename = "~thread")]
@@ -37,6 +40,7 @@ impl Presentation {
 
 please_ack!(Presentation);
 threadlike!(Presentation);
+attachment_formats!(Presentation, presentations_attach, AttachmentFormat::HLINDY_PROOF);
 a2a_message!(Presentation);
 
 
@@ -61,6 +65,7 @@ pub mod tests {
         Presentation {
             id: MessageId::id(),
             comment: _comment(),
+            formats: vec![],
             presentations_attach: attachment,
             thread: thread(),
             please_ack: Some(PleaseAck {}),
@@ -1,8 +1,16 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
 use crate::aries::messages::thread::Thread;
 use crate::aries::messages::a2a::{A2AMessage, MessageId};
 use crate::aries::messages::a2a::message_family::MessageFamilies;
 use crate::aries::messages::a2a::message_type::MessageType;
+use crate::aries::messages::a2a::protocol_version::ProtocolVersion;
+use crate::aries::messages::attachment::{AttachmentFormat, AttachmentId, Attachments};
 use crate::aries::messages::mime_type::MimeType;
+use crate::error::prelude::*;
+use crate::libindy::proofs::proof_request::ProofRequestData;
+use crate::libindy::proofs::proof_request_internal::{AttrInfo, PredicateInfo, Restrictions};
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
 pub struct PresentationProposal {
@@ -10,8 +18,15 @@ pub struct PresentationProposal {
     pub id: MessageId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub comment: Option<String>,
+    #[serde(default)]
     pub presentation_proposal: PresentationPreview,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub formats: Vec<AttachmentFormat>,
+    #[serde(rename = "proposals~attach")]
+    #[serde(default, skip_serializing_if = "Attachments::is_empty")]
+    pub proposals_attach: Attachments,
     #[serde(rename = "~thread")]
+    #[serde(default)]
     pub thread: Thread,
 }
 
@@ -61,9 +76,94 @@ impl PresentationProposal {
         self.presentation_proposal = presentation_preview;
         self
     }
+
+    pub fn set_proposals_attach(mut self, proof_request_data: &ProofRequestData) -> VcxResult<PresentationProposal> {
+        self.proposals_attach.add_base64_encoded_json_attachment(AttachmentId::PresentationRequest, json!(proof_request_data))?;
+        Ok(self)
+    }
+
+    /**
+    Proposed proof request is attached to 2.0 proposals, 1.0 proposals carry the presentation preview only.
+     */
+    pub fn proof_request_data(&self) -> VcxResult<ProofRequestData> {
+        match self.protocol_version() {
+            ProtocolVersion::V1 => self.presentation_proposal.to_proof_request_data(),
+            ProtocolVersion::V2 => {
+                let content = self.attachment_content()?;
+                serde_json::from_str(&content)
+                    .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize proposed ProofRequestData: {}, error: {}", content, err)))
+            }
+        }
+    }
+
+    /**
+    Proposal can either start a new thread or counter a presentation request on its thread.
+     */
+    pub fn thread_id(&self) -> String {
+        self.thread.thid.clone().unwrap_or(self.id.0.clone())
+    }
+}
+
+impl PresentationPreview {
+    /**
+    Converts the preview into requested attributes and predicates of a proof request.
+    Attributes and predicates bound to a credential definition get restricted to it.
+    Name and nonce are left to be set by the caller.
+     */
+    pub fn to_proof_request_data(&self) -> VcxResult<ProofRequestData> {
+        let requested_attributes: HashMap<String, AttrInfo> = self.attributes.iter()
+            .enumerate()
+            .map(|(index, attribute)| (format!("attribute_{}", index), AttrInfo {
+                name: Some(attribute.name.clone()),
+                names: None,
+                restrictions: _cred_def_restrictions(&attribute.cred_def_id),
+                non_revoked: None,
+                self_attest_allowed: None,
+            }))
+            .collect();
+
+        let requested_predicates = self.predicates.iter()
+            .enumerate()
+            .map(|(index, predicate)| Ok((format!("predicate_{}", index), PredicateInfo {
+                name: predicate.name.clone(),
+                p_type: _predicate_type(&predicate.predicate)?,
+                p_value: i32::try_from(predicate.threshold)
+                    .map_err(|_| VcxError::from_msg(VcxErrorKind::InvalidProofRequest, format!("Predicate threshold is out of range: {}", predicate.threshold)))?,
+                restrictions: _cred_def_restrictions(&predicate.cred_def_id),
+                non_revoked: None,
+            })))
+            .collect::<VcxResult<HashMap<String, PredicateInfo>>>()?;
+
+        let proof_request_data = ProofRequestData {
+            requested_attributes,
+            requested_predicates,
+            ..ProofRequestData::default()
+        };
+        proof_request_data.validate()?;
+        Ok(proof_request_data)
+    }
+}
+
+fn _cred_def_restrictions(cred_def_id: &Option<String>) -> Option<Restrictions> {
+    cred_def_id.as_ref().map(|cred_def_id| Restrictions::V2(json!([{"cred_def_id": cred_def_id}])))
+}
+
+// predicate operators of presentation preview (RFC 0037), indy proof requests use the same ones
+const PREVIEW_PREDICATES: [&str; 4] = [">=", ">", "<=", "<"];
+
+/**
+Predicate operators of the preview are passed on to the proof request unchanged.
+ */
+fn _predicate_type(predicate: &str) -> VcxResult<String> {
+    if PREVIEW_PREDICATES.contains(&predicate) {
+        Ok(predicate.to_string())
+    } else {
+        Err(VcxError::from_msg(VcxErrorKind::InvalidProofRequest, format!("Unsupported predicate: {}", predicate)))
+    }
 }
 
 threadlike!(PresentationProposal);
+attachment_formats!(PresentationProposal, proposals_attach, AttachmentFormat::HLINDY_PROOF_REQUEST);
 a2a_message!(PresentationProposal);
 
 #[cfg(test)]
@@ -100,6 +200,8 @@ pub mod tests {
             comment: Some(_comment()),
             thread: thread(),
             presentation_proposal: _presentation_preview(),
+            formats: vec![],
+            proposals_attach: Attachments::new(),
         }
     }
 
@@ -113,4 +215,86 @@ pub mod tests {
 
         assert_eq!(_presentation_proposal(), presentation_proposal);
     }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_presentation_proposal_thread_id_works() {
+        assert_eq!(thread_id(), _presentation_proposal().thread_id());
+
+        let proposal: PresentationProposal = serde_json::from_value(json!({
+            "@id": "proposal_id",
+            "presentation_proposal": _presentation_preview()
+        })).unwrap();
+        assert_eq!("proposal_id", proposal.thread_id());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_presentation_preview_to_proof_request_data_works() {
+        let preview = PresentationPreview {
+            attributes: vec![Attribute {
+                name: String::from("name"),
+                cred_def_id: Some(String::from("cred_def_id")),
+                mime_type: None,
+                value: None,
+                filter: None,
+            }],
+            predicates: vec![Predicate {
+                name: String::from("age"),
+                cred_def_id: None,
+                predicate: String::from(">="),
+                threshold: 18,
+                filter: None,
+            }],
+            ..Default::default()
+        };
+
+        let proof_request_data = preview.to_proof_request_data().unwrap();
+
+        let attribute = &proof_request_data.requested_attributes["attribute_0"];
+        assert_eq!(Some(String::from("name")), attribute.name);
+        assert_eq!(Some(Restrictions::V2(json!([{"cred_def_id": "cred_def_id"}]))), attribute.restrictions);
+
+        let predicate = &proof_request_data.requested_predicates["predicate_0"];
+        assert_eq!("age", predicate.name);
+        assert_eq!(">=", predicate.p_type);
+        assert_eq!(18, predicate.p_value);
+        assert_eq!(None, predicate.restrictions);
+        assert_eq!(">=", json!(proof_request_data)["requested_predicates"]["predicate_0"]["p_type"]);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_presentation_preview_to_proof_request_data_keeps_predicate_operators() {
+        for operator in PREVIEW_PREDICATES.iter() {
+            let mut preview = _presentation_preview();
+            preview.predicates = vec![Predicate {
+                name: String::from("age"),
+                cred_def_id: None,
+                predicate: operator.to_string(),
+                threshold: 18,
+                filter: None,
+            }];
+
+            let proof_request_data = preview.to_proof_request_data().unwrap();
+            assert_eq!(*operator, proof_request_data.requested_predicates["predicate_0"].p_type);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_presentation_preview_to_proof_request_data_fails_for_unknown_predicate() {
+        let mut preview = _presentation_preview();
+        preview.predicates.push(Predicate {
+            name: String::from("age"),
+            cred_def_id: None,
+            predicate: String::from("!="),
+            threshold: 18,
+            filter: None,
+        });
+        assert_eq!(VcxErrorKind::InvalidProofRequest, preview.to_proof_request_data().unwrap_err().kind());
+
+        preview.predicates.last_mut().unwrap().predicate = String::from("GE");
+        assert_eq!(VcxErrorKind::InvalidProofRequest, preview.to_proof_request_data().unwrap_err().kind());
+    }
 }
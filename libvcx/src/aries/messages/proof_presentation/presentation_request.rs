@@ -1,6 +1,9 @@
 use crate::error::prelude::*;
 use crate::aries::messages::a2a::{A2AMessage, MessageId};
-use crate::aries::messages::attachment::{AttachmentId, Attachments};
+use crate::aries::messages::a2a::protocol_version::ProtocolVersion;
+use crate::aries::messages::attachment::{AttachmentFormat, AttachmentId, Attachments};
+use crate::aries::messages::thread::Thread;
+use crate::aries::messages::timing::Timing;
 use crate::libindy::proofs::proof_request::ProofRequestData;
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
@@ -9,8 +12,16 @@ pub struct PresentationRequest {
     pub id: MessageId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub formats: Vec<AttachmentFormat>,
     #[serde(rename = "request_presentations~attach")]
     pub request_presentations_attach: Attachments,
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
 }
 
 impl PresentationRequest {
@@ -34,8 +45,22 @@ impl PresentationRequest {
         Ok(self)
     }
 
+    pub fn set_thread_id(mut self, id: &str) -> Self {
+        self.thread = Some(Thread::new().set_thid(id.to_string()));
+        self
+    }
+
+    /**
+    Request sent in response to a presentation proposal continues the proposal thread, otherwise it starts a new one.
+     */
+    pub fn thread_id(&self) -> String {
+        self.thread.as_ref()
+            .and_then(|thread| thread.thid.clone())
+            .unwrap_or(self.id.0.clone())
+    }
+
     pub fn get_presentation_request_data(self) -> VcxResult<ProofRequestData> {
-        let content = &self.request_presentations_attach.content()?;
+        let content = &self.attachment_content()?;
         Ok(serde_json::from_str(&content)
             .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize PresentationRequestData: {}, error: {}", content, err)))?)
     }
@@ -46,6 +71,8 @@ impl PresentationRequest {
     }
 }
 
+attachment_formats!(PresentationRequest, request_presentations_attach, AttachmentFormat::HLINDY_PROOF_REQUEST);
+timing!(PresentationRequest);
 a2a_message!(PresentationRequest);
 
 
@@ -85,7 +112,10 @@ pub mod tests {
         PresentationRequest {
             id: MessageId::id(),
             comment: _comment(),
+            formats: vec![],
             request_presentations_attach: _attachment(),
+            thread: None,
+            timing: None,
         }
     }
 
@@ -98,4 +128,25 @@ pub mod tests {
 
         assert_eq!(_presentation_request(), presentation_request);
     }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_presentation_request_thread_id_works() {
+        assert_eq!(_presentation_request().id.0, _presentation_request().thread_id());
+        assert_eq!("proposal_thread", _presentation_request().set_thread_id("proposal_thread").thread_id());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_presentation_request_timing_works() {
+        let expires_time = String::from("2021-03-15T10:00:00.000Z");
+        let presentation_request = _presentation_request().set_expires_time(Some(expires_time.clone()));
+        assert_eq!(Some(expires_time.clone()), presentation_request.expires_time());
+
+        let message = json!(presentation_request.to_a2a_message());
+        assert_eq!(json!({"expires_time": expires_time}), message["~timing"]);
+
+        assert_eq!(None, presentation_request.set_expires_time(None).expires_time());
+        assert!(json!(_presentation_request().to_a2a_message()).get("~timing").is_none());
+    }
 }
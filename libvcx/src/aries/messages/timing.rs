@@ -0,0 +1,87 @@
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+
+/**
+The `~timing` decorator (RFC 0032), times are in UTC and in RFC 3339 format.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Timing {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub out_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stale_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay_milli: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wait_until_time: Option<String>,
+}
+
+pub fn format_time(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+pub fn parse_time(time: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(time)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|err| warn!("Cannot parse time {}: {}", time, err))
+        .ok()
+}
+
+pub fn now() -> String {
+    format_time(Utc::now())
+}
+
+/**
+Time `expiry` seconds from now, none if `expiry` is not set.
+ */
+pub fn expires_time(expiry: Option<u32>) -> Option<String> {
+    expiry.map(|expiry| format_time(Utc::now() + Duration::seconds(i64::from(expiry))))
+}
+
+pub fn is_past(time: &str) -> bool {
+    parse_time(time)
+        .map(|time| time <= Utc::now())
+        .unwrap_or(false)
+}
+
+#[macro_export]
+macro_rules! timing (($type:ident) => (
+    impl $type {
+        pub fn set_expires_time(mut self, expires_time: Option<String>) -> $type {
+            self.timing = match (self.timing, expires_time) {
+                (timing, None) => timing.map(|timing| Timing { expires_time: None, ..timing }),
+                (timing, expires_time) => Some(Timing { expires_time, ..timing.unwrap_or_default() })
+            };
+            self
+        }
+
+        pub fn expires_time(&self) -> Option<String> {
+            self.timing.as_ref().and_then(|timing| timing.expires_time.clone())
+        }
+    }
+));
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_timing_serialization() {
+        let timing = Timing { expires_time: Some("2021-03-15T10:00:00.000Z".to_string()), ..Timing::default() };
+        assert_eq!(json!({"expires_time": "2021-03-15T10:00:00.000Z"}), json!(timing));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_expires_time() {
+        assert_eq!(None, expires_time(None));
+        assert!(!is_past(&expires_time(Some(60)).unwrap()));
+        assert!(is_past(&expires_time(Some(0)).unwrap()));
+        assert!(is_past(&format_time(Utc::now() - Duration::seconds(1))));
+        assert!(!is_past("not a time"));
+    }
+}
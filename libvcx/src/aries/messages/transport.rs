@@ -0,0 +1,22 @@
+/**
+`~transport` decorator, `return_route` asks the recipient to send its replies back over the
+connection the message has been received on.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Transport {
+    pub return_route: ReturnRoute,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReturnRoute {
+    None,
+    All,
+    Thread,
+}
+
+impl Transport {
+    pub fn return_route_all() -> Transport {
+        Transport { return_route: ReturnRoute::All }
+    }
+}
@@ -1,8 +1,10 @@
 use agency_client::mocking::AgencyMockDecrypted;
+use indy::WalletHandle;
 
 use crate::aries::messages::a2a::A2AMessage;
 use crate::aries::messages::connection::did_doc::DidDoc;
 use crate::aries::messages::forward::Forward;
+use crate::aries::utils::task::block_on;
 use crate::error::prelude::*;
 use crate::libindy::utils::crypto;
 use crate::settings;
@@ -11,21 +13,30 @@ use crate::settings;
 pub struct EncryptionEnvelope(pub Vec<u8>);
 
 impl EncryptionEnvelope {
-    pub fn create(message: &A2AMessage,
+    pub fn create(wallet_handle: WalletHandle,
+                  message: &A2AMessage,
                   pw_verkey: Option<&str>,
                   did_doc: &DidDoc) -> VcxResult<EncryptionEnvelope> {
-        trace!("EncryptionEnvelope::create >>> message: {:?}, pw_verkey: {:?}, did_doc: {:?}", message, pw_verkey, did_doc);
+        block_on(Self::create_async(wallet_handle, message, pw_verkey, did_doc))
+    }
+
+    pub async fn create_async(wallet_handle: WalletHandle,
+                              message: &A2AMessage,
+                              pw_verkey: Option<&str>,
+                              did_doc: &DidDoc) -> VcxResult<EncryptionEnvelope> {
+        trace!("EncryptionEnvelope::create_async >>> wallet_handle: {:?}, message: {:?}, pw_verkey: {:?}, did_doc: {:?}", wallet_handle, message, pw_verkey, did_doc);
 
         if settings::indy_mocks_enabled() { return Ok(EncryptionEnvelope(vec![])); }
 
-        EncryptionEnvelope::encrypt_for_pairwise(message, pw_verkey, did_doc)
-            .and_then(|message| EncryptionEnvelope::wrap_into_forward_messages(message, did_doc))
-            .map(|message| EncryptionEnvelope(message))
+        let message = EncryptionEnvelope::encrypt_for_pairwise_async(wallet_handle, message, pw_verkey, did_doc).await?;
+        let message = EncryptionEnvelope::wrap_into_forward_messages_async(wallet_handle, message, did_doc).await?;
+        Ok(EncryptionEnvelope(message))
     }
 
-    fn encrypt_for_pairwise(message: &A2AMessage,
-                            pw_verkey: Option<&str>,
-                            did_doc: &DidDoc) -> VcxResult<Vec<u8>> {
+    async fn encrypt_for_pairwise_async(wallet_handle: WalletHandle,
+                                        message: &A2AMessage,
+                                        pw_verkey: Option<&str>,
+                                        did_doc: &DidDoc) -> VcxResult<Vec<u8>> {
         let message = match message {
             A2AMessage::Generic(message_) => message_.to_string(),
             message => json!(message).to_string()
@@ -34,11 +45,12 @@ impl EncryptionEnvelope {
         let receiver_keys = json!(did_doc.recipient_keys()).to_string();
 
         warn!("Encrypting for pairwise; pw_verkey={:?}, receiver_keys={:?}", pw_verkey, receiver_keys);
-        crypto::pack_message(pw_verkey, &receiver_keys, message.as_bytes())
+        crypto::pack_message_async(wallet_handle, pw_verkey, &receiver_keys, message.as_bytes()).await
     }
 
-    fn wrap_into_forward_messages(mut message: Vec<u8>,
-                                  did_doc: &DidDoc) -> VcxResult<Vec<u8>> {
+    async fn wrap_into_forward_messages_async(wallet_handle: WalletHandle,
+                                              mut message: Vec<u8>,
+                                              did_doc: &DidDoc) -> VcxResult<Vec<u8>> {
         let (recipient_keys, routing_keys) = did_doc.resolve_keys();
 
         let mut to = recipient_keys.get(0)
@@ -46,28 +58,29 @@ impl EncryptionEnvelope {
             .ok_or(VcxError::from_msg(VcxErrorKind::InvalidConnectionHandle, format!("Recipient Key not found in DIDDoc: {:?}", did_doc)))?;
 
         for routing_key in routing_keys.iter() {
-            message = EncryptionEnvelope::wrap_into_forward(message, &to, &routing_key)?;
+            message = EncryptionEnvelope::wrap_into_forward_async(wallet_handle, message, &to, &routing_key).await?;
             to = routing_key.clone();
         }
 
         Ok(message)
     }
 
-    fn wrap_into_forward(message: Vec<u8>,
-                         to: &str,
-                         routing_key: &str) -> VcxResult<Vec<u8>> {
+    async fn wrap_into_forward_async(wallet_handle: WalletHandle,
+                                     message: Vec<u8>,
+                                     to: &str,
+                                     routing_key: &str) -> VcxResult<Vec<u8>> {
         let message = A2AMessage::Forward(Forward::new(to.to_string(), message)?);
 
         let message = json!(message).to_string();
         let receiver_keys = json!(vec![routing_key]).to_string();
 
-        crypto::pack_message(None, &receiver_keys, message.as_bytes())
+        crypto::pack_message_async(wallet_handle, None, &receiver_keys, message.as_bytes()).await
     }
 
-    fn _unpack_a2a_message(payload: Vec<u8>) -> VcxResult<(String, Option<String>)> {
+    fn _unpack_a2a_message(wallet_handle: WalletHandle, payload: Vec<u8>) -> VcxResult<(String, Option<String>)> {
         trace!("EncryptionEnvelope::_unpack_a2a_message >>> processing payload of {} bytes", payload.len());
 
-        let unpacked_msg = crypto::unpack_message(&payload)?;
+        let unpacked_msg = crypto::unpack_message(wallet_handle, &payload)?;
 
         let msg_value: serde_json::Value = serde_json::from_slice(unpacked_msg.as_slice())
             .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize message: {}", err)))?;
@@ -80,14 +93,47 @@ impl EncryptionEnvelope {
         Ok((msg_string, sender_vk))
     }
 
+    /**
+    Reads recipient verkeys from the protected header of packed message without decrypting it.
+     */
+    pub fn recipient_verkeys(payload: &[u8]) -> VcxResult<Vec<String>> {
+        trace!("EncryptionEnvelope::recipient_verkeys >>> processing payload of {} bytes", payload.len());
+
+        let jwe: serde_json::Value = serde_json::from_slice(payload)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize packed message: {}", err)))?;
+
+        let protected = jwe["protected"].as_str()
+            .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, "Cannot find `protected` field of packed message"))?;
+        let protected = Self::_decode_base64_url(protected)?;
+
+        let protected: serde_json::Value = serde_json::from_slice(&protected)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize protected header of packed message: {}", err)))?;
+
+        let recipients = protected["recipients"].as_array()
+            .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, "Cannot find `recipients` of packed message"))?;
+
+        Ok(recipients.iter()
+            .filter_map(|recipient| recipient["header"]["kid"].as_str().map(String::from))
+            .collect())
+    }
+
+    fn _decode_base64_url(value: &str) -> VcxResult<Vec<u8>> {
+        let mut value = value.trim_end_matches('=').to_string();
+        while value.len() % 4 != 0 {
+            value.push('=');
+        }
+        base64::decode_config(&value, base64::URL_SAFE)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot decode protected header of packed message: {}", err)))
+    }
+
     // todo: we should use auth_unpack wherever possible
-    pub fn anon_unpack(payload: Vec<u8>) -> VcxResult<A2AMessage> {
+    pub fn anon_unpack(wallet_handle: WalletHandle, payload: Vec<u8>) -> VcxResult<A2AMessage> {
         trace!("EncryptionEnvelope::anon_unpack >>> processing payload of {} bytes", payload.len());
         let message = if AgencyMockDecrypted::has_decrypted_mock_messages() {
             trace!("EncryptionEnvelope::anon_unpack >>> returning decrypted mock message");
             AgencyMockDecrypted::get_next_decrypted_message()
         } else {
-            let (a2a_message, _sender_vk) = Self::_unpack_a2a_message(payload)?;
+            let (a2a_message, _sender_vk) = Self::_unpack_a2a_message(wallet_handle, payload)?;
             a2a_message
         };
         let a2a_message = serde_json::from_str(&message)
@@ -95,36 +141,50 @@ impl EncryptionEnvelope {
         Ok(a2a_message)
     }
 
-    pub fn auth_unpack(payload: Vec<u8>, expected_vk: &str) -> VcxResult<A2AMessage> {
+    pub fn auth_unpack(wallet_handle: WalletHandle, payload: Vec<u8>, expected_vk: &str) -> VcxResult<A2AMessage> {
         trace!("EncryptionEnvelope::auth_unpack >>> processing payload of {} bytes, expected_vk: {}", payload.len(), expected_vk);
+        Self::auth_unpack_with(wallet_handle, payload, |_message, sender_vk| {
+            if sender_vk != expected_vk {
+                error!("auth_unpack  sender_vk != expected_vk.... sender_vk: {}, expected_vk: {}", sender_vk, expected_vk);
+                return Err(VcxError::from_msg(VcxErrorKind::InvalidJson,
+                                              format!("Message did not pass authentication check. Expected sender verkey was {}, but actually was {}", expected_vk, sender_vk))
+                );
+            }
+            Ok(())
+        })
+    }
 
-        let message = if AgencyMockDecrypted::has_decrypted_mock_messages() {
-            trace!("EncryptionEnvelope::auth_unpack >>> returning decrypted mock message");
-            AgencyMockDecrypted::get_next_decrypted_message()
-        } else {
-            let (a2a_message, sender_vk) = Self::_unpack_a2a_message(payload)?;
-            trace!("anon_unpack >> a2a_msg: {:?}, sender_vk: {:?}", a2a_message, sender_vk);
-
-            match sender_vk {
-                Some(sender_vk) => {
-                    if sender_vk != expected_vk {
-                        error!("auth_unpack  sender_vk != expected_vk.... sender_vk: {}, expected_vk: {}", sender_vk, expected_vk);
-                        return Err(VcxError::from_msg(VcxErrorKind::InvalidJson,
-                                                      format!("Message did not pass authentication check. Expected sender verkey was {}, but actually was {}", expected_vk, sender_vk))
-                        );
-                    }
-                }
-                None => {
-                    error!("auth_unpack  message was authcrypted");
-                    return Err(VcxError::from_msg(VcxErrorKind::InvalidJson, "Can't authenticate message because it was anoncrypted."));
-                }
+    /**
+    Unpacks authcrypted message and lets `authenticate` check its sender verkey against the message content,
+    e.g. against the DIDDoc of the connection response which tells the verkey.
+     */
+    pub fn auth_unpack_with<F>(wallet_handle: WalletHandle, payload: Vec<u8>, authenticate: F) -> VcxResult<A2AMessage>
+        where F: FnOnce(&A2AMessage, &str) -> VcxResult<()> {
+        trace!("EncryptionEnvelope::auth_unpack_with >>> processing payload of {} bytes", payload.len());
+
+        if AgencyMockDecrypted::has_decrypted_mock_messages() {
+            trace!("EncryptionEnvelope::auth_unpack_with >>> returning decrypted mock message");
+            return Self::_deserialize_a2a_message(&AgencyMockDecrypted::get_next_decrypted_message());
+        }
+
+        let (a2a_message, sender_vk) = Self::_unpack_a2a_message(wallet_handle, payload)?;
+        trace!("auth_unpack_with >> a2a_msg: {:?}, sender_vk: {:?}", a2a_message, sender_vk);
+
+        let a2a_message = Self::_deserialize_a2a_message(&a2a_message)?;
+        match sender_vk {
+            Some(sender_vk) => authenticate(&a2a_message, &sender_vk)?,
+            None => {
+                error!("auth_unpack  message was authcrypted");
+                return Err(VcxError::from_msg(VcxErrorKind::InvalidJson, "Can't authenticate message because it was anoncrypted."));
             }
-            a2a_message
-        };
-        let a2a_message = serde_json::from_str(&message)
-            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize A2A message: {}", err)))?;
+        }
         Ok(a2a_message)
     }
+
+    fn _deserialize_a2a_message(message: &str) -> VcxResult<A2AMessage> {
+        serde_json::from_str(message)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize A2A message: {}", err)))
+    }
 }
 
 #[cfg(test)]
@@ -134,7 +194,6 @@ pub mod tests {
     use crate::libindy::utils::crypto::create_key;
     use crate::libindy::utils::tests::test_setup;
     use crate::libindy::utils::tests::test_setup::create_trustee_key;
-    use crate::libindy::utils::wallet;
     use crate::utils::devsetup::SetupEmpty;
 
     use super::*;
@@ -143,6 +202,30 @@ pub mod tests {
         settings::set_config_value(settings::CONFIG_ENABLE_TEST_MODE, "false");
     }
 
+    pub fn _packed_message(recipient_verkeys: Vec<&str>) -> Vec<u8> {
+        let recipients: Vec<serde_json::Value> = recipient_verkeys.iter()
+            .map(|verkey| json!({"encrypted_key": "key", "header": {"kid": verkey}}))
+            .collect();
+        let protected = json!({"enc": "xchacha20poly1305_ietf", "typ": "JWM/1.0", "alg": "Authcrypt", "recipients": recipients});
+        let protected = base64::encode_config(&protected.to_string(), base64::URL_SAFE);
+        json!({
+            "protected": protected.trim_end_matches('='),
+            "iv": "iv",
+            "ciphertext": "ciphertext",
+            "tag": "tag"
+        }).to_string().into_bytes()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_recipient_verkeys_works() {
+        let verkeys = EncryptionEnvelope::recipient_verkeys(&_packed_message(vec!["verkey_1", "verkey_2"])).unwrap();
+        assert_eq!(vec!["verkey_1".to_string(), "verkey_2".to_string()], verkeys);
+
+        let err = EncryptionEnvelope::recipient_verkeys(b"not a packed message").unwrap_err();
+        assert_eq!(VcxErrorKind::InvalidJson, err.kind());
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_encryption_envelope_works_for_no_keys() {
@@ -152,7 +235,7 @@ pub mod tests {
 
         let message = A2AMessage::Ack(_ack());
 
-        let res = EncryptionEnvelope::create(&message, Some(&trustee_key), &DidDoc::default());
+        let res = EncryptionEnvelope::create(setup.wh, &message, Some(&trustee_key), &DidDoc::default());
         assert_eq!(res.unwrap_err().kind(), VcxErrorKind::InvalidLibindyParam);
     }
 
@@ -165,8 +248,8 @@ pub mod tests {
 
         let message = A2AMessage::Ack(_ack());
 
-        let envelope = EncryptionEnvelope::create(&message, Some(&trustee_key), &_did_doc_4()).unwrap();
-        assert_eq!(message, EncryptionEnvelope::anon_unpack(envelope.0).unwrap());
+        let envelope = EncryptionEnvelope::create(setup.wh, &message, Some(&trustee_key), &_did_doc_4()).unwrap();
+        assert_eq!(message, EncryptionEnvelope::anon_unpack(setup.wh, envelope.0).unwrap());
     }
 
     #[test]
@@ -176,8 +259,8 @@ pub mod tests {
         let setup = test_setup::setup_wallet();
         let trustee_key = create_trustee_key(setup.wh);
 
-        let key_1 = create_key(None).unwrap();
-        let key_2 = create_key(None).unwrap();
+        let key_1 = create_key(setup.wh, None).unwrap();
+        let key_2 = create_key(setup.wh, None).unwrap();
 
         let mut did_doc = DidDoc::default();
         did_doc.set_service_endpoint(_service_endpoint());
@@ -185,9 +268,9 @@ pub mod tests {
 
         let ack = A2AMessage::Ack(_ack());
 
-        let envelope = EncryptionEnvelope::create(&ack, Some(&trustee_key), &did_doc).unwrap();
+        let envelope = EncryptionEnvelope::create(setup.wh, &ack, Some(&trustee_key), &did_doc).unwrap();
 
-        let message_1 = EncryptionEnvelope::anon_unpack(envelope.0).unwrap();
+        let message_1 = EncryptionEnvelope::anon_unpack(setup.wh, envelope.0).unwrap();
 
         let message_1 = match message_1 {
             A2AMessage::Forward(forward) => {
@@ -197,7 +280,7 @@ pub mod tests {
             _ => return assert!(false)
         };
 
-        let message_2 = EncryptionEnvelope::anon_unpack(message_1).unwrap();
+        let message_2 = EncryptionEnvelope::anon_unpack(setup.wh, message_1).unwrap();
 
         let message_2 = match message_2 {
             A2AMessage::Forward(forward) => {
@@ -207,7 +290,7 @@ pub mod tests {
             _ => return assert!(false)
         };
 
-        assert_eq!(ack, EncryptionEnvelope::anon_unpack(message_2).unwrap());
+        assert_eq!(ack, EncryptionEnvelope::anon_unpack(setup.wh, message_2).unwrap());
     }
 
     #[test]
@@ -226,11 +309,9 @@ pub mod tests {
 
         let ack = A2AMessage::Ack(_ack());
 
-        wallet::set_wallet_handle(sender_wallet.wh);
-        let envelope = EncryptionEnvelope::create(&ack, Some(&sender_key), &did_doc).unwrap();
+        let envelope = EncryptionEnvelope::create(sender_wallet.wh, &ack, Some(&sender_key), &did_doc).unwrap();
 
-        wallet::set_wallet_handle(recipient_wallet.wh);
-        let _message_1 = EncryptionEnvelope::auth_unpack(envelope.0, &sender_key).unwrap();
+        let _message_1 = EncryptionEnvelope::auth_unpack(recipient_wallet.wh, envelope.0, &sender_key).unwrap();
     }
 
     #[test]
@@ -250,11 +331,9 @@ pub mod tests {
 
         let ack = A2AMessage::Ack(_ack());
 
-        wallet::set_wallet_handle(sender_wallet.wh);
-        let envelope = EncryptionEnvelope::create(&ack, Some(&sender_key_2), &did_doc).unwrap();
+        let envelope = EncryptionEnvelope::create(sender_wallet.wh, &ack, Some(&sender_key_2), &did_doc).unwrap();
 
-        wallet::set_wallet_handle(recipient_wallet.wh);
-        let result = EncryptionEnvelope::auth_unpack(envelope.0, &sender_key_1);
+        let result = EncryptionEnvelope::auth_unpack(recipient_wallet.wh, envelope.0, &sender_key_1);
         assert!(result.is_err());
     }
 }
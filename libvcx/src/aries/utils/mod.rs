@@ -1,20 +1,61 @@
+use futures::future::{self, LocalBoxFuture};
+use indy::WalletHandle;
+
 use crate::aries::messages::connection::did_doc::DidDoc;
 use crate::aries::messages::a2a::A2AMessage;
 use crate::aries::utils::encryption_envelope::EncryptionEnvelope;
+use crate::aries::utils::task::run_blocking;
+use crate::aries::utils::transport::{send_packed_message, send_packed_message_with_response};
 use crate::error::VcxResult;
 
 pub mod encryption_envelope;
+pub mod task;
+pub mod transport;
 
-pub fn send_message(sender_verkey: &str, did_doc: &DidDoc, message: &A2AMessage) -> VcxResult<()> {
+pub fn send_message(wallet_handle: WalletHandle, sender_verkey: &str, did_doc: &DidDoc, message: &A2AMessage) -> VcxResult<()> {
     trace!("send_message >>> message: {:?}, did_doc: {:?}", message, &did_doc);
-    let envelope = EncryptionEnvelope::create(&message, Some(sender_verkey), &did_doc)?;
-    agency_client::httpclient::post_message(&envelope.0, &did_doc.get_endpoint())?;
-    Ok(())
+    let envelope = EncryptionEnvelope::create(wallet_handle, &message, Some(sender_verkey), &did_doc)?;
+    send_packed_message(&envelope.0, &did_doc.get_endpoint())
+}
+
+/**
+Packs the message awaiting libindy, the packed message is delivered by the blocking transport on the pool of blocking tasks.
+ */
+pub async fn send_message_async(wallet_handle: WalletHandle, sender_verkey: &str, did_doc: &DidDoc, message: &A2AMessage) -> VcxResult<()> {
+    trace!("send_message_async >>> message: {:?}, did_doc: {:?}", message, &did_doc);
+    let envelope = EncryptionEnvelope::create_async(wallet_handle, &message, Some(sender_verkey), &did_doc).await?;
+    let endpoint = did_doc.get_endpoint();
+    run_blocking(move || send_packed_message(&envelope.0, &endpoint)).await
+}
+
+/**
+Function the connection state machines send their messages with, see `send_message_boxed`.
+ */
+pub type SendMessageFn = for<'a> fn(WalletHandle, &'a str, &'a DidDoc, &'a A2AMessage) -> LocalBoxFuture<'a, VcxResult<()>>;
+
+pub fn send_message_boxed<'a>(wallet_handle: WalletHandle, sender_verkey: &'a str, did_doc: &'a DidDoc, message: &'a A2AMessage) -> LocalBoxFuture<'a, VcxResult<()>> {
+    Box::pin(send_message_async(wallet_handle, sender_verkey, did_doc, message))
+}
+
+/**
+Adapts blocking send closure (e.g. `Connection::send_message_closure`) to the protocol state machines,
+which take the message by value and await the sending.
+ */
+pub fn sync_sender<'a>(send_message: &'a impl Fn(&A2AMessage) -> VcxResult<()>) -> impl Fn(A2AMessage) -> future::Ready<VcxResult<()>> + 'a {
+    move |message| future::ready(send_message(&message))
+}
+
+/**
+Sends message asking for the reply over the same connection, returns the packed reply (empty if there is none).
+ */
+pub fn send_message_with_response(wallet_handle: WalletHandle, sender_verkey: &str, did_doc: &DidDoc, message: &A2AMessage) -> VcxResult<Vec<u8>> {
+    trace!("send_message_with_response >>> message: {:?}, did_doc: {:?}", message, &did_doc);
+    let envelope = EncryptionEnvelope::create(wallet_handle, &message, Some(sender_verkey), &did_doc)?;
+    send_packed_message_with_response(&envelope.0, &did_doc.get_endpoint())
 }
 
-pub fn send_message_anonymously(did_doc: &DidDoc, message: &A2AMessage) -> VcxResult<()> {
+pub fn send_message_anonymously(wallet_handle: WalletHandle, did_doc: &DidDoc, message: &A2AMessage) -> VcxResult<()> {
     trace!("send_message_anonymously >>> message: {:?}, did_doc: {:?}", message, &did_doc);
-    let envelope = EncryptionEnvelope::create(&message, None, &did_doc)?;
-    agency_client::httpclient::post_message(&envelope.0, &did_doc.get_endpoint())?;
-    Ok(())
+    let envelope = EncryptionEnvelope::create(wallet_handle, &message, None, &did_doc)?;
+    send_packed_message(&envelope.0, &did_doc.get_endpoint())
 }
\ No newline at end of file
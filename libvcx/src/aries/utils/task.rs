@@ -0,0 +1,117 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::task::{Context, Poll};
+use std::thread::{self, Thread};
+
+use futures::task::{ArcWake, waker};
+use tokio::runtime::{Builder, Runtime};
+
+use crate::error::prelude::*;
+use crate::utils::error;
+
+const MAX_BLOCKING_THREADS: usize = 16;
+
+lazy_static! {
+    // only the blocking pool of the runtime is used, tasks over the limit wait in its queue
+    static ref BLOCKING_POOL: Runtime = Builder::new_current_thread()
+        .thread_name("vcx-blocking")
+        .max_blocking_threads(MAX_BLOCKING_THREADS)
+        .build()
+        .expect("Cannot create pool of blocking tasks");
+}
+
+/**
+Offloads blocking work the async handler API cannot await: agency requests, message transport and
+ledger writes paying fees (other libindy calls are awaited directly). The closure runs on a shared pool of
+at most `MAX_BLOCKING_THREADS` threads, so the returned future does not need any particular runtime, does not
+block the executor polling it and can be waited for from the threads of the library pool as well.
+The closure has to capture everything it needs, the profile included.
+ */
+pub async fn run_blocking<F, R>(closure: F) -> VcxResult<R>
+    where F: FnOnce() -> VcxResult<R> + Send + 'static,
+          R: Send + 'static {
+    BLOCKING_POOL.spawn_blocking(closure).await
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::Common(error::THREAD_ERROR.code_num), format!("Blocking task failed before returning a result: {}", err)))?
+}
+
+struct ThreadWaker {
+    thread: Thread,
+    woken: AtomicBool,
+}
+
+impl ArcWake for ThreadWaker {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.woken.store(true, Ordering::SeqCst);
+        arc_self.thread.unpark();
+    }
+}
+
+/**
+Runs the future to completion on the calling thread, sync functions are thin wrappers of their async variants
+using it. Unlike `futures::executor::block_on` it can be nested, as sync functions are still called from
+async code too.
+ */
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let thread_waker = Arc::new(ThreadWaker { thread: thread::current(), woken: AtomicBool::new(false) });
+    let waker = waker(thread_waker.clone());
+    let mut context = Context::from_waker(&waker);
+    futures::pin_mut!(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+        // a nested block_on may consume the unpark, the flag tells whether this future has been woken
+        while !thread_waker.woken.swap(false, Ordering::SeqCst) {
+            thread::park();
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::utils::devsetup::SetupMocks;
+
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_run_blocking_returns_result() {
+        let _setup = SetupMocks::init();
+
+        let value = 1;
+        assert_eq!(2, block_on(run_blocking(move || Ok(value + 1))).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_run_blocking_reports_panicked_closure() {
+        let _setup = SetupMocks::init();
+
+        let err = block_on(run_blocking(|| -> VcxResult<()> { panic!("closure failed") })).unwrap_err();
+        assert_eq!(VcxErrorKind::Common(error::THREAD_ERROR.code_num), err.kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_run_blocking_bounds_number_of_threads() {
+        let _setup = SetupMocks::init();
+
+        let tasks = (0..MAX_BLOCKING_THREADS * 4)
+            .map(|_| run_blocking(|| Ok(std::thread::current().id())));
+        let thread_ids: std::collections::HashSet<_> = block_on(futures::future::join_all(tasks))
+            .into_iter()
+            .map(|thread_id| thread_id.unwrap())
+            .collect();
+        assert!(thread_ids.len() <= MAX_BLOCKING_THREADS);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_block_on_can_be_nested() {
+        let _setup = SetupMocks::init();
+
+        let value = block_on(async { block_on(async { 1 }) + 1 });
+        assert_eq!(2, value);
+    }
+}
@@ -0,0 +1,305 @@
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::sync::{Arc, Mutex, RwLock};
+
+use agency_client::mocking::agency_mocks_enabled;
+use tungstenite::{Message, WebSocket};
+use url::Url;
+
+use crate::error::prelude::*;
+
+/**
+Delivers packed (encrypted) messages to the endpoint of the counterparty.
+Transport is chosen by the scheme of the endpoint, see `register_transport`.
+ */
+pub trait Transport: Send + Sync {
+    fn send_message(&self, message: &[u8], endpoint: &str) -> VcxResult<()>;
+
+    /**
+    Sends message and returns packed message the endpoint replied with over the same connection
+    (return route), empty if transport does not support replies or the endpoint has not replied.
+     */
+    fn send_message_with_response(&self, message: &[u8], endpoint: &str) -> VcxResult<Vec<u8>> {
+        self.send_message(message, endpoint)?;
+        Ok(Vec::new())
+    }
+}
+
+pub struct HttpTransport;
+
+impl Transport for HttpTransport {
+    fn send_message(&self, message: &[u8], endpoint: &str) -> VcxResult<()> {
+        trace!("HttpTransport::send_message >>> endpoint: {:?}", endpoint);
+        agency_client::httpclient::post_message(&message.to_vec(), endpoint)?;
+        Ok(())
+    }
+
+    fn send_message_with_response(&self, message: &[u8], endpoint: &str) -> VcxResult<Vec<u8>> {
+        trace!("HttpTransport::send_message_with_response >>> endpoint: {:?}", endpoint);
+        Ok(agency_client::httpclient::post_message(&message.to_vec(), endpoint)?)
+    }
+}
+
+pub struct WsTransport;
+
+impl Transport for WsTransport {
+    fn send_message(&self, message: &[u8], endpoint: &str) -> VcxResult<()> {
+        trace!("WsTransport::send_message >>> endpoint: {:?}", endpoint);
+        if agency_mocks_enabled() {
+            warn!("WsTransport::send_message >>> mocks enabled, message is not sent");
+            return Ok(());
+        }
+
+        let (mut socket, _) = tungstenite::connect(endpoint)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::PostMessageFailed, format!("Could not connect to {}: {:?}", endpoint, err)))?;
+        socket.write_message(Message::Binary(message.to_vec()))
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::PostMessageFailed, format!("Could not send message to {}: {:?}", endpoint, err)))?;
+        socket.close(None).ok();
+        Ok(())
+    }
+
+    fn send_message_with_response(&self, message: &[u8], endpoint: &str) -> VcxResult<Vec<u8>> {
+        trace!("WsTransport::send_message_with_response >>> endpoint: {:?}", endpoint);
+        if agency_mocks_enabled() {
+            warn!("WsTransport::send_message_with_response >>> mocks enabled, message is not sent");
+            return Ok(Vec::new());
+        }
+
+        let (mut socket, _) = tungstenite::connect(endpoint)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::PostMessageFailed, format!("Could not connect to {}: {:?}", endpoint, err)))?;
+        socket.write_message(Message::Binary(message.to_vec()))
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::PostMessageFailed, format!("Could not send message to {}: {:?}", endpoint, err)))?;
+        let response = _read_response(&mut socket, endpoint)?;
+        socket.close(None).ok();
+        Ok(response)
+    }
+}
+
+/**
+Reads frames until the endpoint replies with a text or binary frame. Pings are answered (tungstenite queues
+the pong, it is flushed here) and pongs are skipped, connection closed before the reply is an error.
+ */
+fn _read_response<S: Read + Write>(socket: &mut WebSocket<S>, endpoint: &str) -> VcxResult<Vec<u8>> {
+    loop {
+        match socket.read_message() {
+            Ok(Message::Binary(response)) => return Ok(response),
+            Ok(Message::Text(response)) => return Ok(response.into_bytes()),
+            Ok(Message::Ping(_)) => {
+                socket.write_pending()
+                    .map_err(|err| VcxError::from_msg(VcxErrorKind::PostMessageFailed, format!("Could not answer ping of {}: {:?}", endpoint, err)))?;
+            }
+            Ok(Message::Pong(_)) => {}
+            Ok(Message::Close(frame)) => return Err(VcxError::from_msg(VcxErrorKind::PostMessageFailed, format!("Connection to {} closed before response: {:?}", endpoint, frame))),
+            Err(err) => return Err(VcxError::from_msg(VcxErrorKind::PostMessageFailed, format!("Could not receive response from {}: {:?}", endpoint, err)))
+        }
+    }
+}
+
+/**
+Keeps messages in process memory, the receiving agent takes them by its `loopback://` endpoint.
+Allows to run several agents in one process without any network involved, the messages are fed into
+inbound processing by `connection::process_loopback_messages`.
+ */
+pub struct LoopbackTransport {
+    inboxes: Mutex<HashMap<String, Vec<Vec<u8>>>>,
+}
+
+impl LoopbackTransport {
+    pub const SCHEME: &'static str = "loopback";
+
+    pub fn new() -> LoopbackTransport {
+        LoopbackTransport { inboxes: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn take_messages(&self, endpoint: &str) -> Vec<Vec<u8>> {
+        self.inboxes.lock().unwrap()
+            .remove(endpoint)
+            .unwrap_or_default()
+    }
+}
+
+impl Transport for LoopbackTransport {
+    fn send_message(&self, message: &[u8], endpoint: &str) -> VcxResult<()> {
+        trace!("LoopbackTransport::send_message >>> endpoint: {:?}", endpoint);
+        self.inboxes.lock().unwrap()
+            .entry(endpoint.to_string())
+            .or_insert_with(Vec::new)
+            .push(message.to_vec());
+        Ok(())
+    }
+}
+
+lazy_static! {
+    pub static ref LOOPBACK_TRANSPORT: Arc<LoopbackTransport> = Arc::new(LoopbackTransport::new());
+    static ref TRANSPORTS: RwLock<HashMap<String, Arc<dyn Transport>>> = RwLock::new(_default_transports());
+}
+
+fn _default_transports() -> HashMap<String, Arc<dyn Transport>> {
+    let http: Arc<dyn Transport> = Arc::new(HttpTransport);
+    let ws: Arc<dyn Transport> = Arc::new(WsTransport);
+    let loopback: Arc<dyn Transport> = LOOPBACK_TRANSPORT.clone();
+
+    let mut transports = HashMap::new();
+    transports.insert(String::from("http"), http.clone());
+    transports.insert(String::from("https"), http);
+    transports.insert(String::from("ws"), ws.clone());
+    transports.insert(String::from("wss"), ws);
+    transports.insert(String::from(LoopbackTransport::SCHEME), loopback);
+    transports
+}
+
+/**
+Registers transport for endpoints with given scheme, replacing the previously registered one.
+ */
+pub fn register_transport(scheme: &str, transport: Arc<dyn Transport>) {
+    trace!("register_transport >>> scheme: {:?}", scheme);
+    TRANSPORTS.write().unwrap().insert(scheme.to_lowercase(), transport);
+}
+
+/**
+Removes transport registered for the scheme, the default transport of the scheme (if any) is used again.
+ */
+pub fn unregister_transport(scheme: &str) {
+    trace!("unregister_transport >>> scheme: {:?}", scheme);
+    let scheme = scheme.to_lowercase();
+    let mut transports = TRANSPORTS.write().unwrap();
+    match _default_transports().remove(&scheme) {
+        Some(transport) => transports.insert(scheme, transport),
+        None => transports.remove(&scheme)
+    };
+}
+
+pub fn get_transport(endpoint: &str) -> VcxResult<Arc<dyn Transport>> {
+    let url = Url::parse(endpoint)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidUrl, format!("Invalid endpoint {}: {:?}", endpoint, err)))?;
+
+    TRANSPORTS.read().unwrap()
+        .get(url.scheme())
+        .cloned()
+        .ok_or(VcxError::from_msg(VcxErrorKind::InvalidUrl, format!("No transport registered for endpoint: {}", endpoint)))
+}
+
+pub fn send_packed_message(message: &[u8], endpoint: &str) -> VcxResult<()> {
+    get_transport(endpoint)?.send_message(message, endpoint)
+}
+
+pub fn send_packed_message_with_response(message: &[u8], endpoint: &str) -> VcxResult<Vec<u8>> {
+    get_transport(endpoint)?.send_message_with_response(message, endpoint)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::io::Cursor;
+
+    use tungstenite::protocol::Role;
+
+    use crate::utils::devsetup::SetupMocks;
+
+    use super::*;
+
+    // reads frames sent by the server, keeps frames written by the client
+    struct FrameStream {
+        incoming: Cursor<Vec<u8>>,
+        outgoing: Vec<u8>,
+    }
+
+    impl Read for FrameStream {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.incoming.read(buf)
+        }
+    }
+
+    impl Write for FrameStream {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.outgoing.write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn _client_socket(incoming: Vec<u8>) -> WebSocket<FrameStream> {
+        WebSocket::from_raw_socket(FrameStream { incoming: Cursor::new(incoming), outgoing: Vec::new() }, Role::Client, None)
+    }
+
+    struct FailingTransport;
+
+    impl Transport for FailingTransport {
+        fn send_message(&self, _message: &[u8], _endpoint: &str) -> VcxResult<()> {
+            Err(VcxError::from(VcxErrorKind::PostMessageFailed))
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_send_packed_message_works_for_loopback() {
+        let _setup = SetupMocks::init();
+
+        send_packed_message(b"message_1", "loopback://alice").unwrap();
+        send_packed_message(b"message_2", "loopback://alice").unwrap();
+
+        assert_eq!(vec![b"message_1".to_vec(), b"message_2".to_vec()], LOOPBACK_TRANSPORT.take_messages("loopback://alice"));
+        assert!(LOOPBACK_TRANSPORT.take_messages("loopback://alice").is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_send_packed_message_with_response_returns_empty_response_for_loopback() {
+        let _setup = SetupMocks::init();
+
+        assert!(send_packed_message_with_response(b"message", "loopback://bob").unwrap().is_empty());
+        assert_eq!(vec![b"message".to_vec()], LOOPBACK_TRANSPORT.take_messages("loopback://bob"));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_send_packed_message_works_for_http() {
+        let _setup = SetupMocks::init();
+
+        send_packed_message(b"message", "http://localhost:8080/agent").unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_send_packed_message_fails_for_unknown_scheme() {
+        let _setup = SetupMocks::init();
+
+        assert_eq!(VcxErrorKind::InvalidUrl, send_packed_message(b"message", "ftp://localhost").unwrap_err().kind());
+        assert_eq!(VcxErrorKind::InvalidUrl, send_packed_message(b"message", "not an url").unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_register_transport_works() {
+        let _setup = SetupMocks::init();
+
+        // scheme is not used by any other test, the shared registry is left as it was
+        register_transport("test-register", Arc::new(FailingTransport));
+        assert_eq!(VcxErrorKind::PostMessageFailed, send_packed_message(b"message", "test-register://agent").unwrap_err().kind());
+
+        unregister_transport("test-register");
+        assert_eq!(VcxErrorKind::InvalidUrl, send_packed_message(b"message", "test-register://agent").unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_ws_response_is_read_past_ping_and_pong() {
+        let _setup = SetupMocks::init();
+
+        // ping, pong, binary "hi"
+        let mut socket = _client_socket(vec![0x89, 0x00, 0x8a, 0x00, 0x82, 0x02, b'h', b'i']);
+        assert_eq!(b"hi".to_vec(), _read_response(&mut socket, "ws://localhost").unwrap());
+        // masked pong answering the ping
+        assert_eq!(0x8a, socket.get_ref().outgoing[0]);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_ws_response_fails_if_connection_is_closed() {
+        let _setup = SetupMocks::init();
+
+        let mut socket = _client_socket(vec![0x89, 0x00, 0x88, 0x00]);
+        assert_eq!(VcxErrorKind::PostMessageFailed, _read_response(&mut socket, "ws://localhost").unwrap_err().kind());
+    }
+}
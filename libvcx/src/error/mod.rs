@@ -161,10 +161,16 @@ pub enum VcxErrorKind {
     WalletAccessFailed,
     #[fail(display = "Invalid Wallet or Search Handle")]
     InvalidWalletHandle,
+    #[fail(display = "Invalid Profile Handle")]
+    InvalidProfileHandle,
+    #[fail(display = "Invalid Mediation Handle")]
+    InvalidMediationHandle,
     #[fail(display = "Indy wallet already exists")]
     DuplicationWallet,
     #[fail(display = "Wallet record not found")]
     WalletRecordNotFound,
+    #[fail(display = "Object has changed but its wallet record could not be written")]
+    ObjectNotPersisted,
     #[fail(display = "Record already exists in the wallet")]
     DuplicationWalletRecord,
     #[fail(display = "Wallet not found")]
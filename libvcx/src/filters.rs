@@ -10,7 +10,7 @@ fn _filter_proof_requests_by_name(requests: &str, match_name: &str) -> VcxResult
     let filtered = presentation_requests
         .into_iter()
         .filter_map(|presentation_request| {
-            match presentation_request.request_presentations_attach.content().ok() {
+            match presentation_request.attachment_content().ok() {
                 Some(content) => {
                     match serde_json::from_str::<serde_json::Value>(&content) {
                         Ok(value) => match value.get("name") {
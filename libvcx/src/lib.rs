@@ -30,12 +30,14 @@ extern crate time;
 extern crate url;
 extern crate uuid;
 extern crate tokio;
+extern crate tungstenite;
 
 #[macro_use]
 pub mod utils;
 #[macro_use]
 pub mod api_lib;
 pub mod settings;
+pub mod profile;
 pub mod init;
 pub mod error;
 
@@ -62,6 +64,7 @@ mod tests {
     use crate::api_lib::ProofStateType;
     use crate::api_lib::VcxStateType;
     use crate::filters;
+    use crate::profile::ProfileContext;
     use crate::settings;
     use crate::utils::{
         constants::{TEST_TAILS_FILE, TEST_TAILS_URL},
@@ -261,19 +264,19 @@ mod tests {
     fn revoke_credential(faber: &mut Faber, credential_handle: u32, rev_reg_id: Option<String>) {
         faber.activate().unwrap();
         // GET REV REG DELTA BEFORE REVOCATION
-        let (_, delta, timestamp) = libindy::utils::anoncreds::get_rev_reg_delta_json(&rev_reg_id.clone().unwrap(), None, None).unwrap();
+        let (_, delta, timestamp) = libindy::utils::anoncreds::get_rev_reg_delta_json(&ProfileContext::Global, &rev_reg_id.clone().unwrap(), None, None).unwrap();
         info!("revoking credential");
         issuer_credential::revoke_credential(credential_handle).unwrap();
-        let (_, delta_after_revoke, _) = libindy::utils::anoncreds::get_rev_reg_delta_json(&rev_reg_id.unwrap(), Some(timestamp + 1), None).unwrap();
+        let (_, delta_after_revoke, _) = libindy::utils::anoncreds::get_rev_reg_delta_json(&ProfileContext::Global, &rev_reg_id.unwrap(), Some(timestamp + 1), None).unwrap();
         assert_ne!(delta, delta_after_revoke);
     }
 
     fn revoke_credential_local(faber: &mut Faber, issuer_credential_handle: u32, rev_reg_id: Option<String>) {
         faber.activate().unwrap();
-        let (_, delta, timestamp) = libindy::utils::anoncreds::get_rev_reg_delta_json(&rev_reg_id.clone().unwrap(), None, None).unwrap();
+        let (_, delta, timestamp) = libindy::utils::anoncreds::get_rev_reg_delta_json(&ProfileContext::Global, &rev_reg_id.clone().unwrap(), None, None).unwrap();
         info!("revoking credential locally");
         issuer_credential::revoke_credential_local(issuer_credential_handle).unwrap();
-        let (_, delta_after_revoke, _) = libindy::utils::anoncreds::get_rev_reg_delta_json(&rev_reg_id.unwrap(), Some(timestamp + 1), None).unwrap();
+        let (_, delta_after_revoke, _) = libindy::utils::anoncreds::get_rev_reg_delta_json(&ProfileContext::Global, &rev_reg_id.unwrap(), Some(timestamp + 1), None).unwrap();
         assert_ne!(delta, delta_after_revoke); // They will not equal as we have saved the delta in cache
     }
 
@@ -289,7 +292,7 @@ mod tests {
 
     fn publish_revocation(institution: &mut Faber, rev_reg_id: String) {
         institution.activate().unwrap();
-        libindy::utils::anoncreds::publish_local_revocations(rev_reg_id.as_str()).unwrap();
+        libindy::utils::anoncreds::publish_local_revocations(&ProfileContext::Global, rev_reg_id.as_str()).unwrap();
     }
 
     fn _create_address_schema() -> (String, String, String, String, u32, Option<String>) {
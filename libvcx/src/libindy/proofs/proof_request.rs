@@ -2,12 +2,17 @@ use std::collections::HashMap;
 use std::vec::Vec;
 
 use serde_json;
+use serde_json::Value;
 
 use crate::error::prelude::*;
-use crate::libindy::proofs::proof_request_internal::{AttrInfo, NonRevokedInterval, PredicateInfo};
+use crate::libindy::proofs::proof_request_internal::{AttrInfo, NonRevokedInterval, PredicateInfo, PredicateType, Restrictions};
 use crate::libindy::utils::anoncreds;
 use crate::utils::qualifier;
 
+const RESTRICTION_TAGS: &[&str] = &["schema_id", "schema_issuer_did", "schema_name", "schema_version", "issuer_did", "cred_def_id", "rev_reg_id"];
+const IDENTIFIER_TAGS: &[&str] = &["schema_id", "schema_issuer_did", "issuer_did", "cred_def_id", "rev_reg_id"];
+const VALUE_OPERATORS: &[&str] = &["$neq", "$gt", "$gte", "$lt", "$lte", "$like", "$in"];
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct ProofRequestData {
     pub nonce: String,
@@ -41,15 +46,9 @@ impl ProofRequestData {
     pub fn set_requested_attributes(mut self, requested_attrs: String) -> VcxResult<ProofRequestData> {
         match serde_json::from_str::<HashMap<String, AttrInfo>>(&requested_attrs) {
             Ok(attrs) => self.requested_attributes = attrs,
-            Err(err) => {
+            Err(_) => {
                 let requested_attributes: Vec<AttrInfo> = ::serde_json::from_str(&requested_attrs)
                     .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Invalid Requested Attributes: {:?}, err: {:?}", requested_attrs, err)))?;
-                for attribute in requested_attributes.iter() {
-                    if attribute.name.is_some() && attribute.names.is_some() {
-                        return Err(VcxError::from_msg(VcxErrorKind::InvalidProofRequest,
-                                                      format!("Requested attribute can contain either 'name' or 'names'. Not both.")));
-                    };
-                }
                 self.requested_attributes = requested_attributes
                     .into_iter()
                     .enumerate()
@@ -57,6 +56,13 @@ impl ProofRequestData {
                     .collect();
             }
         }
+        self.validate()?;
+        Ok(self)
+    }
+
+    pub fn set_requested_attribute(mut self, referent: &str, attribute: AttrInfo) -> VcxResult<ProofRequestData> {
+        self.requested_attributes.insert(referent.to_string(), attribute);
+        self.validate()?;
         Ok(self)
     }
 
@@ -69,6 +75,13 @@ impl ProofRequestData {
             .enumerate()
             .map(|(index, attribute)| (format!("predicate_{}", index), attribute))
             .collect();
+        self.validate()?;
+        Ok(self)
+    }
+
+    pub fn set_requested_predicate(mut self, referent: &str, predicate: PredicateInfo) -> VcxResult<ProofRequestData> {
+        self.requested_predicates.insert(referent.to_string(), predicate);
+        self.validate()?;
         Ok(self)
     }
 
@@ -80,9 +93,123 @@ impl ProofRequestData {
             (None, None) => None,
             (from, to) => Some(NonRevokedInterval { from, to })
         };
+        _validate_non_revoked("proof request", &self.non_revoked)?;
 
         Ok(self)
     }
+
+    /**
+    Checks the request can be processed by the prover: attributes are requested either by `name`
+    or by `names`, restrictions are valid WQL queries over credential tags, non-revoked intervals
+    are not inverted and identifiers in restrictions are either all fully qualified or all unqualified.
+     */
+    pub fn validate(&self) -> VcxResult<()> {
+        let mut identifiers = Vec::new();
+
+        for (referent, attribute) in self.requested_attributes.iter() {
+            match (&attribute.name, &attribute.names) {
+                (Some(_), Some(_)) => return Err(VcxError::from_msg(VcxErrorKind::InvalidProofRequest,
+                                                                     format!("Requested attribute can contain either 'name' or 'names'. Not both."))),
+                (None, None) => return Err(VcxError::from_msg(VcxErrorKind::InvalidProofRequest,
+                                                              format!("Requested attribute \"{}\" has to contain either 'name' or 'names'", referent))),
+                (None, Some(names)) if names.is_empty() => return Err(VcxError::from_msg(VcxErrorKind::InvalidProofRequest,
+                                                                                          format!("Requested attribute \"{}\" has empty 'names'", referent))),
+                _ => {}
+            }
+            _validate_non_revoked(referent, &attribute.non_revoked)?;
+            _validate_restrictions(referent, &attribute.restrictions, &mut identifiers)?;
+        }
+
+        for (referent, predicate) in self.requested_predicates.iter() {
+            if predicate.name.is_empty() {
+                return Err(VcxError::from_msg(VcxErrorKind::InvalidProofRequest, format!("Requested predicate \"{}\" has empty name", referent)));
+            }
+            PredicateType::parse(&predicate.p_type)
+                .map_err(|err| err.extend(format!("Requested predicate \"{}\" has unsupported type: {}", referent, predicate.p_type)))?;
+            _validate_non_revoked(referent, &predicate.non_revoked)?;
+            _validate_restrictions(referent, &predicate.restrictions, &mut identifiers)?;
+        }
+
+        _validate_non_revoked("proof request", &self.non_revoked)?;
+
+        let qualified: Vec<&String> = identifiers.iter().filter(|id| qualifier::is_fully_qualified(id)).collect();
+        if !qualified.is_empty() && qualified.len() < identifiers.len() {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidProofRequest,
+                                          format!("Restrictions mix fully qualified {:?} and unqualified identifiers", qualified)));
+        }
+        Ok(())
+    }
+}
+
+fn _validate_non_revoked(referent: &str, non_revoked: &Option<NonRevokedInterval>) -> VcxResult<()> {
+    match non_revoked {
+        Some(NonRevokedInterval { from: Some(from), to: Some(to) }) if from > to => {
+            Err(VcxError::from_msg(VcxErrorKind::InvalidProofRequest, format!("Non-revoked interval of \"{}\" ends before it starts: {} > {}", referent, from, to)))
+        }
+        _ => Ok(())
+    }
+}
+
+fn _validate_restrictions(referent: &str, restrictions: &Option<Restrictions>, identifiers: &mut Vec<String>) -> VcxResult<()> {
+    match restrictions {
+        Some(restrictions) => _validate_query(referent, &json!(restrictions), identifiers),
+        None => Ok(())
+    }
+}
+
+fn _validate_query(referent: &str, query: &Value, identifiers: &mut Vec<String>) -> VcxResult<()> {
+    match query {
+        Value::Array(queries) => {
+            queries.iter().map(|query| _validate_query(referent, query, identifiers)).collect()
+        }
+        Value::Object(query) => {
+            for (key, value) in query.iter() {
+                match key.as_str() {
+                    "$or" | "$and" if value.is_array() => _validate_query(referent, value, identifiers)?,
+                    "$not" if value.is_object() => _validate_query(referent, value, identifiers)?,
+                    tag if _is_restriction_tag(tag) => _validate_tag_value(referent, tag, value, identifiers)?,
+                    _ => return Err(VcxError::from_msg(VcxErrorKind::InvalidProofRequest, format!("Unsupported restriction \"{}\" of \"{}\"", key, referent)))
+                }
+            }
+            Ok(())
+        }
+        _ => Err(VcxError::from_msg(VcxErrorKind::InvalidProofRequest, format!("Restrictions of \"{}\" have to be WQL query: {}", referent, query)))
+    }
+}
+
+fn _is_restriction_tag(tag: &str) -> bool {
+    if RESTRICTION_TAGS.contains(&tag) {
+        return true;
+    }
+    let parts: Vec<&str> = tag.split("::").collect();
+    match parts.as_slice() {
+        ["attr", name, "value"] | ["attr", name, "marker"] => !name.is_empty(),
+        _ => false
+    }
+}
+
+fn _validate_tag_value(referent: &str, tag: &str, value: &Value, identifiers: &mut Vec<String>) -> VcxResult<()> {
+    let values = match value {
+        Value::String(value) => vec![value.as_str()],
+        Value::Object(operators) => {
+            let mut values = Vec::new();
+            for (operator, operand) in operators.iter() {
+                match (operator.as_str(), operand) {
+                    ("$in", Value::Array(operands)) if operands.iter().all(Value::is_string) => {
+                        values.extend(operands.iter().filter_map(Value::as_str))
+                    }
+                    (operator, Value::String(operand)) if operator != "$in" && VALUE_OPERATORS.contains(&operator) => values.push(operand.as_str()),
+                    _ => return Err(VcxError::from_msg(VcxErrorKind::InvalidProofRequest, format!("Invalid value of \"{}\" restriction of \"{}\": {}", tag, referent, value)))
+                }
+            }
+            values
+        }
+        _ => return Err(VcxError::from_msg(VcxErrorKind::InvalidProofRequest, format!("Invalid value of \"{}\" restriction of \"{}\": {}", tag, referent, value)))
+    };
+    if IDENTIFIER_TAGS.contains(&tag) {
+        identifiers.extend(values.into_iter().map(String::from));
+    }
+    Ok(())
 }
 
 impl Default for ProofRequestData {
@@ -105,8 +232,9 @@ mod tests {
     use crate::utils::devsetup::SetupDefaults;
     use crate::utils::mockdata::mockdata_proof;
 
+    use crate::libindy::proofs::proof_request_internal::Filter;
+
     use super::*;
-    use serde_json::Value;
 
     fn _expected_req_attrs() -> HashMap<String, AttrInfo> {
         let mut check_req_attrs: HashMap<String, AttrInfo> = HashMap::new();
@@ -258,4 +386,90 @@ mod tests {
 
         let _proof_req: ProofRequestData = serde_json::from_str(utils::constants::INDY_PROOF_REQ_JSON).unwrap();
     }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_proof_request_built_from_restriction_builder() {
+        let _setup = SetupDefaults::init();
+
+        let restrictions = Restrictions::any_of(vec![
+            Filter::create().set_schema_name("Faber Student Info").set_issuer_did("8XFh8yBzrpJQmNyZzgoTqB").set_attr_value("degree", "Bachelor"),
+            Filter::create().set_cred_def_id("66Fh8yBzrpJQmNyZzgoTqB:3:CL:1766").set_attr_marker("degree"),
+        ]);
+        let request = ProofRequestData::create()
+            .set_requested_attribute("degree", AttrInfo::create().set_name("degree").set_restrictions(restrictions.clone()).set_non_revoked(None, Some(100))).unwrap()
+            .set_requested_predicate("age", PredicateInfo::create().set_name("age").set_p_type(PredicateType::LT).set_p_value(30).set_restrictions(restrictions)).unwrap();
+
+        let request: Value = json!(request);
+        assert_eq!(json!([
+            {"schema_name": "Faber Student Info", "issuer_did": "8XFh8yBzrpJQmNyZzgoTqB", "attr::degree::value": "Bachelor"},
+            {"cred_def_id": "66Fh8yBzrpJQmNyZzgoTqB:3:CL:1766", "attr::degree::marker": "1"}
+        ]), request["requested_attributes"]["degree"]["restrictions"]);
+        assert_eq!(json!({"from": null, "to": 100}), request["requested_attributes"]["degree"]["non_revoked"]);
+        assert_eq!("<", request["requested_predicates"]["age"]["p_type"]);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_should_return_error_for_unsupported_restriction() {
+        let _setup = SetupDefaults::init();
+
+        let requested_attrs = json!([{"name": "name", "restrictions": {"$or": [{"schema_id": "id"}, {"credential_id": "id"}]}}]).to_string();
+        let err = ProofRequestData::create().set_requested_attributes(requested_attrs).unwrap_err();
+        assert_eq!(VcxErrorKind::InvalidProofRequest, err.kind());
+
+        let requested_attrs = json!([{"name": "name", "restrictions": [{"attr::name::value": {"$regex": "Al.*"}}]}]).to_string();
+        let err = ProofRequestData::create().set_requested_attributes(requested_attrs).unwrap_err();
+        assert_eq!(VcxErrorKind::InvalidProofRequest, err.kind());
+
+        let requested_attrs = json!([{"name": "name", "restrictions": {"$not": {"attr::name::value": {"$in": ["Alice", "Bob"]}}}}]).to_string();
+        ProofRequestData::create().set_requested_attributes(requested_attrs).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_should_return_error_for_invalid_predicate_type() {
+        let _setup = SetupDefaults::init();
+
+        let requested_predicates = json!([{"name": "age", "p_type": "==", "p_value": 18}]).to_string();
+        let err = ProofRequestData::create().set_requested_predicates(requested_predicates).unwrap_err();
+        assert_eq!(VcxErrorKind::InvalidProofRequest, err.kind());
+
+        let requested_predicates = json!([{"name": "age", "p_type": "GE", "p_value": 18}]).to_string();
+        let request = ProofRequestData::create().set_requested_predicates(requested_predicates).unwrap();
+        assert_eq!("GE", request.requested_predicates["predicate_0"].p_type);
+        assert_eq!("GE", json!(request)["requested_predicates"]["predicate_0"]["p_type"]);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_should_return_error_for_mixed_qualification() {
+        let _setup = SetupDefaults::init();
+
+        let requested_attrs = json!([
+            {"name": "name", "restrictions": [{"cred_def_id": "creddef:mainnet:did:sov:V4SGRU86Z58d6TV7PBUe6f:3:CL:did:sov:V4SGRU86Z58d6TV7PBUe6f:2:name:1.0:tag"}]},
+            {"name": "age", "restrictions": [{"issuer_did": "V4SGRU86Z58d6TV7PBUe6f"}]}
+        ]).to_string();
+        let err = ProofRequestData::create().set_requested_attributes(requested_attrs).unwrap_err();
+        assert_eq!(VcxErrorKind::InvalidProofRequest, err.kind());
+
+        let requested_attrs = json!([
+            {"name": "name", "restrictions": [{"cred_def_id": "creddef:mainnet:did:sov:V4SGRU86Z58d6TV7PBUe6f:3:CL:did:sov:V4SGRU86Z58d6TV7PBUe6f:2:name:1.0:tag"}]},
+            {"name": "age", "restrictions": [{"issuer_did": "did:sov:V4SGRU86Z58d6TV7PBUe6f"}]}
+        ]).to_string();
+        ProofRequestData::create().set_requested_attributes(requested_attrs).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_should_return_error_for_inverted_non_revoked_interval() {
+        let _setup = SetupDefaults::init();
+
+        let err = ProofRequestData::create()
+            .set_requested_attribute("name", AttrInfo::create().set_name("name").set_non_revoked(Some(200), Some(100))).unwrap_err();
+        assert_eq!(VcxErrorKind::InvalidProofRequest, err.kind());
+
+        let err = ProofRequestData::create().set_not_revoked_interval(json!({"from": 200, "to": 100}).to_string()).unwrap_err();
+        assert_eq!(VcxErrorKind::InvalidProofRequest, err.kind());
+    }
 }
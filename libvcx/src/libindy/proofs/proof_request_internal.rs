@@ -1,13 +1,78 @@
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+use std::collections::BTreeMap;
+
+use crate::error::prelude::*;
+
+/**
+Restriction on the credential used for requested attribute or predicate, all set fields have to match.
+`attributes` holds `attr::<name>::value` and `attr::<name>::marker` tags.
+ */
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
 pub struct Filter {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub schema_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub schema_issuer_did: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub schema_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub schema_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub issuer_did: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub cred_def_id: Option<String>,
+    #[serde(flatten)]
+    pub attributes: BTreeMap<String, String>,
 }
 
+impl Filter {
+    pub fn create() -> Filter {
+        Filter::default()
+    }
+
+    pub fn set_schema_id(mut self, schema_id: &str) -> Filter {
+        self.schema_id = Some(schema_id.to_string());
+        self
+    }
+
+    pub fn set_schema_issuer_did(mut self, schema_issuer_did: &str) -> Filter {
+        self.schema_issuer_did = Some(schema_issuer_did.to_string());
+        self
+    }
+
+    pub fn set_schema_name(mut self, schema_name: &str) -> Filter {
+        self.schema_name = Some(schema_name.to_string());
+        self
+    }
+
+    pub fn set_schema_version(mut self, schema_version: &str) -> Filter {
+        self.schema_version = Some(schema_version.to_string());
+        self
+    }
+
+    pub fn set_issuer_did(mut self, issuer_did: &str) -> Filter {
+        self.issuer_did = Some(issuer_did.to_string());
+        self
+    }
+
+    pub fn set_cred_def_id(mut self, cred_def_id: &str) -> Filter {
+        self.cred_def_id = Some(cred_def_id.to_string());
+        self
+    }
+
+    pub fn set_attr_value(mut self, name: &str, value: &str) -> Filter {
+        self.attributes.insert(format!("attr::{}::value", name), value.to_string());
+        self
+    }
+
+    pub fn set_attr_marker(mut self, name: &str) -> Filter {
+        self.attributes.insert(format!("attr::{}::marker", name), String::from("1"));
+        self
+    }
+}
+
+/**
+Restrictions are satisfied by a credential matching any of the filters (V1) or the WQL query (V2).
+ */
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(untagged)]
 pub enum Restrictions {
@@ -15,10 +80,48 @@ pub enum Restrictions {
     V2(serde_json::Value),
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+impl Restrictions {
+    pub fn any_of(filters: Vec<Filter>) -> Restrictions {
+        Restrictions::V1(filters)
+    }
+}
+
+/**
+Predicate operators supported by indy. `p_type` of a predicate is kept as it has been given,
+`GE`, `GT`, `LE` and `LT` are accepted as aliases of the operators.
+ */
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PredicateType {
+    GE,
+    GT,
+    LE,
+    LT,
+}
+
+impl PredicateType {
+    pub fn parse(p_type: &str) -> VcxResult<PredicateType> {
+        match p_type {
+            ">=" | "GE" => Ok(PredicateType::GE),
+            ">" | "GT" => Ok(PredicateType::GT),
+            "<=" | "LE" => Ok(PredicateType::LE),
+            "<" | "LT" => Ok(PredicateType::LT),
+            _ => Err(VcxError::from_msg(VcxErrorKind::InvalidProofRequest, format!("Unsupported predicate type: {}", p_type)))
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PredicateType::GE => ">=",
+            PredicateType::GT => ">",
+            PredicateType::LE => "<=",
+            PredicateType::LT => "<",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
 pub struct PredicateInfo {
     pub name: String,
-    //Todo: Update p_type to use Enum
     pub p_type: String,
     pub p_value: i32,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -27,7 +130,38 @@ pub struct PredicateInfo {
     pub non_revoked: Option<NonRevokedInterval>,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+impl PredicateInfo {
+    pub fn create() -> PredicateInfo {
+        PredicateInfo::default()
+    }
+
+    pub fn set_name(mut self, name: &str) -> PredicateInfo {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn set_p_type(mut self, p_type: PredicateType) -> PredicateInfo {
+        self.p_type = p_type.as_str().to_string();
+        self
+    }
+
+    pub fn set_p_value(mut self, p_value: i32) -> PredicateInfo {
+        self.p_value = p_value;
+        self
+    }
+
+    pub fn set_restrictions(mut self, restrictions: Restrictions) -> PredicateInfo {
+        self.restrictions = Some(restrictions);
+        self
+    }
+
+    pub fn set_non_revoked(mut self, from: Option<u64>, to: Option<u64>) -> PredicateInfo {
+        self.non_revoked = Some(NonRevokedInterval { from, to });
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
 pub struct AttrInfo {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
@@ -41,6 +175,37 @@ pub struct AttrInfo {
     pub self_attest_allowed: Option<bool>,
 }
 
+impl AttrInfo {
+    pub fn create() -> AttrInfo {
+        AttrInfo::default()
+    }
+
+    pub fn set_name(mut self, name: &str) -> AttrInfo {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn set_names(mut self, names: Vec<String>) -> AttrInfo {
+        self.names = Some(names);
+        self
+    }
+
+    pub fn set_restrictions(mut self, restrictions: Restrictions) -> AttrInfo {
+        self.restrictions = Some(restrictions);
+        self
+    }
+
+    pub fn set_non_revoked(mut self, from: Option<u64>, to: Option<u64>) -> AttrInfo {
+        self.non_revoked = Some(NonRevokedInterval { from, to });
+        self
+    }
+
+    pub fn set_self_attest_allowed(mut self, self_attest_allowed: bool) -> AttrInfo {
+        self.self_attest_allowed = Some(self_attest_allowed);
+        self
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
 pub struct NonRevokedInterval {
     pub from: Option<u64>,
@@ -0,0 +1,408 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use serde_json::Value;
+
+use crate::error::prelude::*;
+use crate::libindy::proofs::proof_request::ProofRequestData;
+use crate::libindy::proofs::proof_request_internal::NonRevokedInterval;
+use crate::libindy::proofs::prover::credential_store;
+use crate::libindy::proofs::prover::prover_internal::_get_revocation_interval;
+use crate::libindy::utils::anoncreds;
+use crate::profile::ProfileContext;
+
+/**
+Rules used to pick a single credential for every referent of a proof request.
+
+Candidates revoked within the requested non-revoked interval of a referent are dropped for that referent.
+Credentials are then picked one at a time and each picked credential is used for all the remaining referents
+it satisfies. The credential picked next is ranked by position of its issuer in `preferred_issuers`, then by
+the number of remaining referents it satisfies (so as few credentials as possible are disclosed), then by
+issuance time, newest first. Issuance time of credentials stored before their metadata was recorded is unknown,
+such credentials rank after all credentials of known issuance time, ties are broken by wallet referent.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SelectionPolicy {
+    #[serde(default)]
+    pub preferred_issuers: Vec<String>,
+    #[serde(default = "default_true")]
+    pub check_revocation: bool,
+    #[serde(default)]
+    pub tails_dir: Option<String>,
+}
+
+fn default_true() -> bool { true }
+
+impl Default for SelectionPolicy {
+    fn default() -> SelectionPolicy {
+        SelectionPolicy {
+            preferred_issuers: Vec::new(),
+            check_revocation: true,
+            tails_dir: None,
+        }
+    }
+}
+
+impl SelectionPolicy {
+    pub fn create() -> SelectionPolicy {
+        SelectionPolicy::default()
+    }
+
+    pub fn set_preferred_issuers(mut self, preferred_issuers: Vec<String>) -> SelectionPolicy {
+        self.preferred_issuers = preferred_issuers;
+        self
+    }
+
+    pub fn set_check_revocation(mut self, check_revocation: bool) -> SelectionPolicy {
+        self.check_revocation = check_revocation;
+        self
+    }
+
+    pub fn set_tails_dir(mut self, tails_dir: String) -> SelectionPolicy {
+        self.tails_dir = Some(tails_dir);
+        self
+    }
+
+    fn issuer_rank(&self, issuer_did: &str) -> usize {
+        self.preferred_issuers.iter()
+            .position(|preferred| _same_did(preferred, issuer_did))
+            .unwrap_or(self.preferred_issuers.len())
+    }
+}
+
+/**
+Result of the selection. `credentials` is in the format accepted by `generate_indy_proof`,
+`unsatisfied` lists referents no (non-revoked) credential has been found for.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CredentialSelection {
+    pub credentials: Value,
+    pub unsatisfied: Vec<String>,
+}
+
+impl CredentialSelection {
+    pub fn is_complete(&self) -> bool {
+        self.unsatisfied.is_empty()
+    }
+
+    pub fn credentials_json(&self) -> String {
+        self.credentials.to_string()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Candidate {
+    referent: String,
+    issuer_did: String,
+    rev_reg_id: Option<String>,
+    cred_rev_id: Option<String>,
+    issued_at: Option<u64>,
+    // credential as retrieved for every proof request referent it satisfies
+    credentials: BTreeMap<String, Value>,
+}
+
+impl Candidate {
+    fn parse(credential: &Value) -> VcxResult<Candidate> {
+        let cred_info = &credential["cred_info"];
+        match (cred_info["referent"].as_str(), cred_info["cred_def_id"].as_str()) {
+            (Some(referent), Some(cred_def_id)) => Ok(Candidate {
+                referent: referent.to_string(),
+                issuer_did: _issuer_did(cred_def_id),
+                rev_reg_id: cred_info["rev_reg_id"].as_str().map(String::from),
+                cred_rev_id: cred_info["cred_rev_id"].as_str().map(String::from),
+                issued_at: None,
+                credentials: BTreeMap::new(),
+            }),
+            _ => Err(VcxError::from_msg(VcxErrorKind::InvalidProofCredentialData, format!("Cannot get identifiers of credential: {}", credential)))
+        }
+    }
+}
+
+/**
+Retrieves credentials matching the proof request from the wallet and selects them according to the policy.
+ */
+pub fn select_credentials_for_proof_req(profile: &ProfileContext, proof_req_data_json: &str, policy: &SelectionPolicy) -> VcxResult<CredentialSelection> {
+    trace!("select_credentials_for_proof_req >>> policy: {:?}", policy);
+    let proof_request: ProofRequestData = serde_json::from_str(proof_req_data_json)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize proof request: {}", err)))?;
+    let retrieved_credentials = anoncreds::libindy_prover_get_credentials_for_proof_req(profile.wallet_handle()?, proof_req_data_json)?;
+    select_credentials(profile, &proof_request, &retrieved_credentials, policy)
+}
+
+/**
+Selects credentials from candidates returned by `libindy_prover_get_credentials_for_proof_req`.
+ */
+pub fn select_credentials(profile: &ProfileContext, proof_req: &ProofRequestData, retrieved_credentials: &str, policy: &SelectionPolicy) -> VcxResult<CredentialSelection> {
+    trace!("select_credentials >>> retrieved_credentials: {}, policy: {:?}", secret!(retrieved_credentials), policy);
+    let retrieved_credentials: Value = serde_json::from_str(retrieved_credentials)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize retrieved credentials: {}", err)))?;
+
+    let referents: Vec<&String> = proof_req.requested_attributes.keys()
+        .chain(proof_req.requested_predicates.keys())
+        .collect();
+
+    let mut revoked_cache: HashMap<(String, Option<u64>), HashSet<String>> = HashMap::new();
+    let mut candidates: BTreeMap<String, Candidate> = BTreeMap::new();
+    for referent in referents.iter() {
+        let interval = _get_revocation_interval(referent, proof_req)?;
+        for credential in retrieved_credentials["attrs"][referent.as_str()].as_array().cloned().unwrap_or_default() {
+            let candidate = Candidate::parse(&credential)?;
+            if policy.check_revocation && _is_revoked(profile, &candidate, interval.as_ref(), &mut revoked_cache)? {
+                debug!("select_credentials >>> skipping credential {} revoked for referent {}", candidate.referent, referent);
+                continue;
+            }
+            candidates.entry(candidate.referent.clone())
+                .or_insert(candidate)
+                .credentials.insert(referent.to_string(), credential);
+        }
+    }
+    for candidate in candidates.values_mut() {
+        candidate.issued_at = credential_store::get_credential_metadata(profile, &candidate.referent).map(|metadata| metadata.issued_at);
+    }
+
+    let mut remaining: BTreeSet<String> = referents.into_iter().cloned().collect();
+    let mut credentials = json!({"attrs": {}});
+    while let Some(candidate) = _pick_candidate(candidates.values(), policy, &remaining) {
+        for (referent, credential) in candidate.credentials.iter() {
+            if !remaining.remove(referent) {
+                continue;
+            }
+            let mut selected = json!({"credential": credential});
+            if let (Some(_), Some(tails_dir)) = (&candidate.rev_reg_id, &policy.tails_dir) {
+                selected["tails_file"] = json!(tails_dir);
+            }
+            credentials["attrs"][referent] = selected;
+        }
+    }
+
+    Ok(CredentialSelection { credentials, unsatisfied: remaining.into_iter().collect() })
+}
+
+/**
+Picks the best ranked credential satisfying at least one of the remaining referents, see `SelectionPolicy`.
+ */
+fn _pick_candidate<'a, I>(candidates: I, policy: &SelectionPolicy, remaining: &BTreeSet<String>) -> Option<&'a Candidate>
+    where I: Iterator<Item=&'a Candidate> {
+    candidates
+        .map(|candidate| (candidate, candidate.credentials.keys().filter(|referent| remaining.contains(*referent)).count()))
+        .filter(|(_, satisfied)| *satisfied > 0)
+        .min_by_key(|(candidate, satisfied)| (
+            policy.issuer_rank(&candidate.issuer_did),
+            Reverse(*satisfied),
+            Reverse(candidate.issued_at),
+            candidate.referent.clone()
+        ))
+        .map(|(candidate, _)| candidate)
+}
+
+fn _is_revoked(profile: &ProfileContext,
+               candidate: &Candidate,
+               interval: Option<&NonRevokedInterval>,
+               revoked_cache: &mut HashMap<(String, Option<u64>), HashSet<String>>) -> VcxResult<bool> {
+    let (rev_reg_id, cred_rev_id, interval) = match (&candidate.rev_reg_id, &candidate.cred_rev_id, interval) {
+        (Some(rev_reg_id), Some(cred_rev_id), Some(interval)) => (rev_reg_id, cred_rev_id, interval),
+        _ => return Ok(false)
+    };
+    let key = (rev_reg_id.to_string(), interval.to);
+    if !revoked_cache.contains_key(&key) {
+        let (_, rev_reg_delta_json, _) = anoncreds::get_rev_reg_delta_json(profile, rev_reg_id, None, interval.to)?;
+        revoked_cache.insert(key.clone(), _revoked_ids(&rev_reg_delta_json)?);
+    }
+    Ok(revoked_cache[&key].contains(cred_rev_id))
+}
+
+fn _revoked_ids(rev_reg_delta_json: &str) -> VcxResult<HashSet<String>> {
+    let rev_reg_delta: Value = serde_json::from_str(rev_reg_delta_json)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize revocation registry delta: {}", err)))?;
+    Ok(rev_reg_delta["value"]["revoked"].as_array()
+        .map(|revoked| revoked.iter()
+            .filter_map(|id| id.as_u64().map(|id| id.to_string()).or(id.as_str().map(String::from)))
+            .collect())
+        .unwrap_or_default())
+}
+
+pub(super) fn _issuer_did(cred_def_id: &str) -> String {
+    let cred_def_id = cred_def_id.trim_start_matches("creddef:sov:");
+    match cred_def_id.find(":3:") {
+        Some(index) => cred_def_id[..index].to_string(),
+        None => cred_def_id.to_string()
+    }
+}
+
+fn _same_did(did: &str, other: &str) -> bool {
+    did == other || did.ends_with(&format!(":{}", other)) || other.ends_with(&format!(":{}", did))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::utils::constants::CREDS_FROM_PROOF_REQ;
+    use crate::utils::devsetup::SetupMocks;
+
+    use super::*;
+
+    fn _proof_req() -> ProofRequestData {
+        serde_json::from_value(json!({
+            "nonce": "123432421212",
+            "name": "proof_req_1",
+            "version": "0.1",
+            "requested_attributes": {
+                "height_1": { "name": "height" },
+                "zip_2": { "name": "zip" },
+                "name_3": { "names": ["name", "sex"] },
+                "email_4": { "name": "email" }
+            },
+            "requested_predicates": {
+                "age_5": { "name": "age", "p_type": ">=", "p_value": 18 }
+            },
+            "non_revoked": { "to": 1600000000 }
+        })).unwrap()
+    }
+
+    fn _credential(referent: &str, cred_def_id: &str) -> Value {
+        json!({
+            "cred_info": {
+                "referent": referent,
+                "attrs": {"name": "Bob", "sex": "male", "age": "111", "height": "4'11"},
+                "schema_id": "2hoqvcwupRTUNkXn6ArYzs:2:test-licence:4.4.4",
+                "cred_def_id": cred_def_id,
+                "rev_reg_id": "2hoqvcwupRTUNkXn6ArYzs:4:2hoqvcwupRTUNkXn6ArYzs:3:CL:2471:tag1:CL_ACCUM:tag1",
+                "cred_rev_id": "1"
+            },
+            "interval": null
+        })
+    }
+
+    fn _candidate(referent: &str, issuer_did: &str, issued_at: Option<u64>, satisfies: &[&str]) -> Candidate {
+        Candidate {
+            referent: referent.to_string(),
+            issuer_did: issuer_did.to_string(),
+            rev_reg_id: None,
+            cred_rev_id: None,
+            issued_at,
+            credentials: satisfies.iter().map(|referent| (referent.to_string(), json!({}))).collect(),
+        }
+    }
+
+    fn _remaining(referents: &[&str]) -> BTreeSet<String> {
+        referents.iter().map(|referent| referent.to_string()).collect()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_select_credentials_reports_unsatisfied_referents() {
+        let _setup = SetupMocks::init();
+
+        let selection = select_credentials(&ProfileContext::Global, &_proof_req(), CREDS_FROM_PROOF_REQ, &SelectionPolicy::create()).unwrap();
+
+        assert_eq!(vec!["age_5", "email_4", "name_3"], selection.unsatisfied);
+        assert!(!selection.is_complete());
+        assert_eq!("92556f60-d290-4b58-9a43-05c25aac214e", selection.credentials["attrs"]["height_1"]["credential"]["cred_info"]["referent"]);
+        assert_eq!("2dea21e2-1404-4f85-966f-d03f403aac71", selection.credentials["attrs"]["zip_2"]["credential"]["cred_info"]["referent"]);
+        assert!(selection.credentials["attrs"]["height_1"]["tails_file"].is_null());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_select_credentials_prefers_issuer_and_reuses_credentials() {
+        let _setup = SetupMocks::init();
+
+        let retrieved = json!({
+            "attrs": {
+                "height_1": [_credential("cred_a", "AAAAAAAAAAAAAAAAAAAAAA:3:CL:1:tag1"), _credential("cred_b", "BBBBBBBBBBBBBBBBBBBBBB:3:CL:1:tag1")],
+                "name_3": [_credential("cred_c", "CCCCCCCCCCCCCCCCCCCCCC:3:CL:1:tag1"), _credential("cred_b", "BBBBBBBBBBBBBBBBBBBBBB:3:CL:1:tag1")],
+                "zip_2": [_credential("cred_a", "AAAAAAAAAAAAAAAAAAAAAA:3:CL:1:tag1")],
+                "email_4": [_credential("cred_a", "AAAAAAAAAAAAAAAAAAAAAA:3:CL:1:tag1")],
+                "age_5": [_credential("cred_a", "AAAAAAAAAAAAAAAAAAAAAA:3:CL:1:tag1")]
+            }
+        }).to_string();
+        let policy = SelectionPolicy::create()
+            .set_preferred_issuers(vec!["did:sov:BBBBBBBBBBBBBBBBBBBBBB".to_string()])
+            .set_tails_dir("/tmp/tails".to_string());
+
+        let selection = select_credentials(&ProfileContext::Global, &_proof_req(), &retrieved, &policy).unwrap();
+
+        assert!(selection.is_complete());
+        assert_eq!("cred_b", selection.credentials["attrs"]["height_1"]["credential"]["cred_info"]["referent"]);
+        assert_eq!("cred_b", selection.credentials["attrs"]["name_3"]["credential"]["cred_info"]["referent"]);
+        assert_eq!("cred_a", selection.credentials["attrs"]["zip_2"]["credential"]["cred_info"]["referent"]);
+        assert_eq!("/tmp/tails", selection.credentials["attrs"]["height_1"]["tails_file"]);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_select_credentials_prefers_credential_satisfying_most_referents() {
+        let _setup = SetupMocks::init();
+
+        let retrieved = json!({
+            "attrs": {
+                "height_1": [_credential("cred_a", "AAAAAAAAAAAAAAAAAAAAAA:3:CL:1:tag1"), _credential("cred_b", "AAAAAAAAAAAAAAAAAAAAAA:3:CL:1:tag1")],
+                "zip_2": [_credential("cred_b", "AAAAAAAAAAAAAAAAAAAAAA:3:CL:1:tag1")],
+                "name_3": [_credential("cred_b", "AAAAAAAAAAAAAAAAAAAAAA:3:CL:1:tag1")]
+            }
+        }).to_string();
+
+        let selection = select_credentials(&ProfileContext::Global, &_proof_req(), &retrieved, &SelectionPolicy::create().set_check_revocation(false)).unwrap();
+
+        assert_eq!("cred_b", selection.credentials["attrs"]["height_1"]["credential"]["cred_info"]["referent"]);
+        assert_eq!("cred_b", selection.credentials["attrs"]["zip_2"]["credential"]["cred_info"]["referent"]);
+        assert_eq!(vec!["age_5", "email_4"], selection.unsatisfied);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_pick_candidate_ranking() {
+        let _setup = SetupMocks::init();
+
+        let candidates = vec![
+            _candidate("old", "AAAAAAAAAAAAAAAAAAAAAA", Some(100), &["height_1"]),
+            _candidate("newest", "AAAAAAAAAAAAAAAAAAAAAA", Some(300), &["height_1"]),
+            _candidate("unknown", "AAAAAAAAAAAAAAAAAAAAAA", None, &["height_1"]),
+            _candidate("legacy", "AAAAAAAAAAAAAAAAAAAAAA", None, &["height_1", "zip_2"]),
+            _candidate("preferred", "BBBBBBBBBBBBBBBBBBBBBB", Some(100), &["name_3"]),
+        ];
+        let policy = SelectionPolicy::create().set_preferred_issuers(vec!["BBBBBBBBBBBBBBBBBBBBBB".to_string()]);
+
+        assert_eq!("preferred", _pick_candidate(candidates.iter(), &policy, &_remaining(&["height_1", "zip_2", "name_3"])).unwrap().referent);
+        assert_eq!("legacy", _pick_candidate(candidates.iter(), &policy, &_remaining(&["height_1", "zip_2"])).unwrap().referent);
+        assert_eq!("newest", _pick_candidate(candidates.iter(), &policy, &_remaining(&["height_1"])).unwrap().referent);
+        assert_eq!("newest", _pick_candidate(candidates[1..3].iter(), &policy, &_remaining(&["height_1"])).unwrap().referent);
+        assert_eq!("legacy", _pick_candidate(candidates[2..4].iter(), &policy, &_remaining(&["height_1"])).unwrap().referent);
+        assert!(_pick_candidate(candidates.iter(), &policy, &_remaining(&["email_4"])).is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_revoked_ids() {
+        let _setup = SetupMocks::init();
+
+        let delta = json!({"ver": "1.0", "value": {"accum": "1", "issued": [1, 2, 3], "revoked": [2, 3]}}).to_string();
+        let revoked = _revoked_ids(&delta).unwrap();
+        assert!(revoked.contains("2"));
+        assert!(revoked.contains("3"));
+        assert!(!revoked.contains("1"));
+
+        assert!(_revoked_ids(r#"{"ver":"1.0","value":{"accum":"1"}}"#).unwrap().is_empty());
+        assert_eq!(VcxErrorKind::InvalidJson, _revoked_ids("not json").unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_issuer_did() {
+        let _setup = SetupMocks::init();
+
+        assert_eq!("2hoqvcwupRTUNkXn6ArYzs", _issuer_did("2hoqvcwupRTUNkXn6ArYzs:3:CL:2471:tag1"));
+        assert_eq!("did:sov:2hoqvcwupRTUNkXn6ArYzs", _issuer_did("creddef:sov:did:sov:2hoqvcwupRTUNkXn6ArYzs:3:CL:2471:tag1"));
+        assert!(_same_did("did:sov:2hoqvcwupRTUNkXn6ArYzs", "2hoqvcwupRTUNkXn6ArYzs"));
+        assert!(!_same_did("did:sov:2hoqvcwupRTUNkXn6ArYzs", "AAAAAAAAAAAAAAAAAAAAAA"));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_selection_policy_deserialization_defaults() {
+        let _setup = SetupMocks::init();
+
+        let policy: SelectionPolicy = serde_json::from_str("{}").unwrap();
+        assert_eq!(SelectionPolicy::create(), policy);
+        assert!(policy.check_revocation);
+    }
+}
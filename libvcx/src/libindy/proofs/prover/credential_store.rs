@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::aries::utils::task::block_on;
+use crate::error::prelude::*;
+use crate::libindy::proofs::prover::credential_selection::_issuer_did;
+use crate::libindy::proofs::prover::revocation_state_cache;
+use crate::libindy::utils::{anoncreds, wallet};
+use crate::profile::ProfileContext;
+
+/**
+Wallet record (keyed by credential id) holding holder side metadata of the stored credential.
+ */
+pub const CREDENTIAL_METADATA_RECORD_TYPE: &str = "vcx_credential_metadata";
+
+/**
+Wallet record (keyed by credential id) holding just the time the credential has been stored, written by
+earlier versions. It is migrated to `CREDENTIAL_METADATA_RECORD_TYPE` when the metadata is read.
+ */
+pub const LEGACY_CREDENTIAL_ISSUANCE_RECORD_TYPE: &str = "vcx_credential_issuance";
+
+/**
+Information about the exchange the credential has been received in.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CredentialMetadata {
+    pub issued_at: u64,
+    #[serde(default)]
+    pub source_id: Option<String>,
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    #[serde(default)]
+    pub pw_did: Option<String>,
+}
+
+impl CredentialMetadata {
+    pub fn create(source_id: &str, thread_id: &str, pw_did: Option<&str>) -> CredentialMetadata {
+        CredentialMetadata {
+            issued_at: time::get_time().sec as u64,
+            source_id: Some(source_id.to_string()),
+            thread_id: Some(thread_id.to_string()),
+            pw_did: pw_did.map(String::from),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct CredentialInfo {
+    referent: String,
+    attrs: HashMap<String, String>,
+    schema_id: String,
+    cred_def_id: String,
+    rev_reg_id: Option<String>,
+    cred_rev_id: Option<String>,
+}
+
+/**
+Credential stored in the wallet together with the metadata recorded when it has been received.
+`metadata` is null for credentials not stored through the holder protocol.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StoredCredential {
+    pub cred_id: String,
+    pub schema_id: String,
+    pub cred_def_id: String,
+    pub issuer_did: String,
+    pub attrs: HashMap<String, String>,
+    pub rev_reg_id: Option<String>,
+    pub cred_rev_id: Option<String>,
+    pub metadata: Option<CredentialMetadata>,
+}
+
+impl StoredCredential {
+    fn from_info(profile: &ProfileContext, info: CredentialInfo) -> StoredCredential {
+        let metadata = get_credential_metadata(profile, &info.referent);
+        StoredCredential {
+            issuer_did: _issuer_did(&info.cred_def_id),
+            cred_id: info.referent,
+            schema_id: info.schema_id,
+            cred_def_id: info.cred_def_id,
+            attrs: info.attrs,
+            rev_reg_id: info.rev_reg_id,
+            cred_rev_id: info.cred_rev_id,
+            metadata,
+        }
+    }
+}
+
+pub fn record_credential_metadata(profile: &ProfileContext, cred_id: &str, metadata: &CredentialMetadata) -> VcxResult<()> {
+    block_on(record_credential_metadata_async(profile, cred_id, metadata))
+}
+
+pub async fn record_credential_metadata_async(profile: &ProfileContext, cred_id: &str, metadata: &CredentialMetadata) -> VcxResult<()> {
+    let value = serde_json::to_string(metadata)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Cannot serialize credential metadata: {:?}", err)))?;
+    wallet::add_record_async(profile.wallet_handle()?, CREDENTIAL_METADATA_RECORD_TYPE, cred_id, &value, None).await
+}
+
+fn _get_record_value(profile: &ProfileContext, record_type: &str, cred_id: &str) -> Option<String> {
+    let options = json!({"retrieveType": false, "retrieveValue": true, "retrieveTags": false}).to_string();
+    let record: Value = wallet::get_record(profile.wallet_handle().ok()?, record_type, cred_id, &options).ok()
+        .and_then(|record| serde_json::from_str(&record).ok())?;
+    record["value"].as_str().map(String::from)
+}
+
+fn _parse_legacy_metadata(value: &str) -> Option<CredentialMetadata> {
+    let issued_at = value.parse().ok()?;
+    Some(CredentialMetadata { issued_at, source_id: None, thread_id: None, pw_did: None })
+}
+
+fn _migrate_legacy_metadata(profile: &ProfileContext, cred_id: &str) -> Option<CredentialMetadata> {
+    let metadata = _parse_legacy_metadata(&_get_record_value(profile, LEGACY_CREDENTIAL_ISSUANCE_RECORD_TYPE, cred_id)?)?;
+    match record_credential_metadata(profile, cred_id, &metadata) {
+        Ok(()) => { wallet::delete_record(profile.wallet_handle().ok()?, LEGACY_CREDENTIAL_ISSUANCE_RECORD_TYPE, cred_id).ok(); }
+        Err(err) => warn!("credential_store >>> cannot migrate metadata of credential {}: {}", cred_id, err)
+    }
+    Some(metadata)
+}
+
+pub fn get_credential_metadata(profile: &ProfileContext, cred_id: &str) -> Option<CredentialMetadata> {
+    match _get_record_value(profile, CREDENTIAL_METADATA_RECORD_TYPE, cred_id) {
+        Some(value) => serde_json::from_str(&value).ok(),
+        None => _migrate_legacy_metadata(profile, cred_id)
+    }
+}
+
+/**
+Searches stored credentials matching WQL query, all credentials are returned if the query is not set.
+
+Supported tags are `schema_id`, `schema_issuer_did`, `schema_name`, `schema_version`, `issuer_did`,
+`cred_def_id`, `rev_reg_id`, `attr::<attribute name>::value` and `attr::<attribute name>::marker`.
+ */
+pub fn search_credentials(profile: &ProfileContext, query: Option<&str>) -> VcxResult<Vec<StoredCredential>> {
+    trace!("search_credentials >>> query: {:?}", query);
+    if let Some(query) = query {
+        let query: Value = serde_json::from_str(query)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize WQL query: {}", err)))?;
+        if !query.is_object() {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidOption, "WQL query must be JSON object"));
+        }
+    }
+
+    let credentials: Vec<CredentialInfo> = serde_json::from_str(&anoncreds::libindy_prover_search_credentials(profile.wallet_handle()?, query)?)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize stored credentials: {}", err)))?;
+    Ok(credentials.into_iter().map(|info| StoredCredential::from_info(profile, info)).collect())
+}
+
+pub fn get_credential(profile: &ProfileContext, cred_id: &str) -> VcxResult<StoredCredential> {
+    trace!("get_credential >>> cred_id: {}", cred_id);
+    let credential: CredentialInfo = serde_json::from_str(&anoncreds::libindy_prover_get_credential(profile.wallet_handle()?, cred_id)?)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize stored credential: {}", err)))?;
+    Ok(StoredCredential::from_info(profile, credential))
+}
+
+/**
+Deletes the credential from the wallet together with its metadata and cached revocation state.
+ */
+pub fn delete_credential(profile: &ProfileContext, cred_id: &str) -> VcxResult<()> {
+    block_on(delete_credential_async(profile, cred_id))
+}
+
+pub async fn delete_credential_async(profile: &ProfileContext, cred_id: &str) -> VcxResult<()> {
+    trace!("delete_credential >>> cred_id: {}", cred_id);
+    anoncreds::libindy_prover_delete_credential_async(profile.wallet_handle()?, cred_id).await?;
+    wallet::delete_record_async(profile.wallet_handle()?, CREDENTIAL_METADATA_RECORD_TYPE, cred_id).await.ok();
+    wallet::delete_record_async(profile.wallet_handle()?, LEGACY_CREDENTIAL_ISSUANCE_RECORD_TYPE, cred_id).await.ok();
+    revocation_state_cache::delete_cached_rev_state_async(profile, cred_id).await.ok();
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::utils::devsetup::SetupMocks;
+
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_search_credentials() {
+        let _setup = SetupMocks::init();
+
+        let credentials = search_credentials(&ProfileContext::Global, Some(r#"{"issuer_did": "2hoqvcwupRTUNkXn6ArYzs"}"#)).unwrap();
+        assert_eq!(1, credentials.len());
+        assert_eq!("cred_id", credentials[0].cred_id);
+        assert_eq!("2hoqvcwupRTUNkXn6ArYzs", credentials[0].issuer_did);
+        assert_eq!("Bob", credentials[0].attrs["name"]);
+        assert_eq!(None, credentials[0].rev_reg_id);
+        assert_eq!(None, credentials[0].metadata);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_search_credentials_fails_for_invalid_query() {
+        let _setup = SetupMocks::init();
+
+        assert_eq!(VcxErrorKind::InvalidJson, search_credentials(&ProfileContext::Global, Some("{")).unwrap_err().kind());
+        assert_eq!(VcxErrorKind::InvalidOption, search_credentials(&ProfileContext::Global, Some("[]")).unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_get_credential() {
+        let _setup = SetupMocks::init();
+
+        let credential = get_credential(&ProfileContext::Global, "cred_id").unwrap();
+        assert_eq!("2hoqvcwupRTUNkXn6ArYzs:3:CL:2471", credential.cred_def_id);
+        assert_eq!("2hoqvcwupRTUNkXn6ArYzs:2:test-licence:4.4.4", credential.schema_id);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_credential_metadata_serialization() {
+        let _setup = SetupMocks::init();
+
+        let metadata = CredentialMetadata::create("source_id", "thread_id", Some("pw_did"));
+        let serialized = serde_json::to_string(&metadata).unwrap();
+        assert_eq!(metadata, serde_json::from_str::<CredentialMetadata>(&serialized).unwrap());
+        assert_eq!(CredentialMetadata { issued_at: 10, source_id: None, thread_id: None, pw_did: None },
+                   serde_json::from_str::<CredentialMetadata>(r#"{"issued_at": 10}"#).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_parse_legacy_metadata() {
+        let _setup = SetupMocks::init();
+
+        assert_eq!(Some(CredentialMetadata { issued_at: 1600000000, source_id: None, thread_id: None, pw_did: None }),
+                   _parse_legacy_metadata("1600000000"));
+        assert_eq!(None, _parse_legacy_metadata("record value"));
+    }
+}
@@ -1,2 +1,5 @@
+pub mod credential_selection;
+pub mod credential_store;
 pub mod prover;
-mod prover_internal;
\ No newline at end of file
+mod prover_internal;
+pub mod revocation_state_cache;
@@ -1,11 +1,17 @@
+use crate::aries::utils::task::block_on;
 use crate::error::prelude::*;
 use crate::libindy::proofs::proof_request::ProofRequestData;
-use crate::libindy::proofs::prover::prover_internal::{build_cred_defs_json_prover, build_requested_credentials_json, build_rev_states_json, build_schemas_json_prover, credential_def_identifiers};
+use crate::libindy::proofs::prover::prover_internal::{build_cred_defs_json_prover_async, build_requested_credentials_json, build_rev_states_json_async, build_schemas_json_prover_async, credential_def_identifiers};
 use crate::libindy::utils::anoncreds;
+use crate::profile::ProfileContext;
 use crate::settings;
 use crate::utils::mockdata::mock_settings::get_mock_generate_indy_proof;
 
-pub fn generate_indy_proof(credentials: &str, self_attested_attrs: &str, proof_req_data_json: &str) -> VcxResult<String> {
+pub fn generate_indy_proof(profile: &ProfileContext, credentials: &str, self_attested_attrs: &str, proof_req_data_json: &str) -> VcxResult<String> {
+    block_on(generate_indy_proof_async(profile, credentials, self_attested_attrs, proof_req_data_json))
+}
+
+pub async fn generate_indy_proof_async(profile: &ProfileContext, credentials: &str, self_attested_attrs: &str, proof_req_data_json: &str) -> VcxResult<String> {
     trace!("generate_indy_proof >>> credentials: {}, self_attested_attrs: {}", secret!(&credentials), secret!(&self_attested_attrs));
 
     match get_mock_generate_indy_proof() {
@@ -21,19 +27,20 @@ pub fn generate_indy_proof(credentials: &str, self_attested_attrs: &str, proof_r
 
     let mut credentials_identifiers = credential_def_identifiers(credentials, &proof_request)?;
 
-    let revoc_states_json = build_rev_states_json(&mut credentials_identifiers)?;
+    let revoc_states_json = build_rev_states_json_async(profile, &mut credentials_identifiers).await?;
     let requested_credentials = build_requested_credentials_json(&credentials_identifiers,
                                                                  self_attested_attrs,
                                                                  &proof_request)?;
 
-    let schemas_json = build_schemas_json_prover(&credentials_identifiers)?;
-    let credential_defs_json = build_cred_defs_json_prover(&credentials_identifiers)?;
+    let schemas_json = build_schemas_json_prover_async(profile, &credentials_identifiers).await?;
+    let credential_defs_json = build_cred_defs_json_prover_async(profile, &credentials_identifiers).await?;
 
-    let proof = anoncreds::libindy_prover_create_proof(&proof_req_data_json,
-                                                       &requested_credentials,
-                                                       settings::DEFAULT_LINK_SECRET_ALIAS,
-                                                       &schemas_json,
-                                                       &credential_defs_json,
-                                                       Some(&revoc_states_json))?;
+    let proof = anoncreds::libindy_prover_create_proof_async(profile.wallet_handle()?,
+                                                             &proof_req_data_json,
+                                                             &requested_credentials,
+                                                             settings::DEFAULT_LINK_SECRET_ALIAS,
+                                                             &schemas_json,
+                                                             &credential_defs_json,
+                                                             Some(&revoc_states_json)).await?;
     Ok(proof)
 }
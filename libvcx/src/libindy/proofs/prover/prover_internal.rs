@@ -1,12 +1,15 @@
 use std::collections::HashMap;
+use std::future::Future;
 
 use serde_json::Value;
 
+use crate::aries::utils::task::block_on;
 use crate::error::prelude::*;
 use crate::libindy::proofs::proof_request::ProofRequestData;
 use crate::libindy::proofs::proof_request_internal::NonRevokedInterval;
+use crate::libindy::proofs::prover::revocation_state_cache;
 use crate::libindy::utils::anoncreds;
-use crate::libindy::utils::anoncreds::{get_rev_reg_def_json, get_rev_reg_delta_json};
+use crate::profile::ProfileContext;
 
 #[derive(Debug, Deserialize, Serialize, PartialEq)]
 pub struct CredInfoProver {
@@ -21,13 +24,17 @@ pub struct CredInfoProver {
     pub timestamp: Option<u64>,
 }
 
-pub fn build_schemas_json_prover(credentials_identifiers: &Vec<CredInfoProver>) -> VcxResult<String> {
+pub fn build_schemas_json_prover(profile: &ProfileContext, credentials_identifiers: &Vec<CredInfoProver>) -> VcxResult<String> {
+    block_on(build_schemas_json_prover_async(profile, credentials_identifiers))
+}
+
+pub async fn build_schemas_json_prover_async(profile: &ProfileContext, credentials_identifiers: &Vec<CredInfoProver>) -> VcxResult<String> {
     trace!("build_schemas_json_prover >>> credentials_identifiers: {:?}", credentials_identifiers);
     let mut rtn: Value = json!({});
 
     for ref cred_info in credentials_identifiers {
         if rtn.get(&cred_info.schema_id).is_none() {
-            let (_, schema_json) = anoncreds::get_schema_json(&cred_info.schema_id)
+            let (_, schema_json) = anoncreds::get_schema_json_async(profile, &cred_info.schema_id).await
                 .map_err(|err| err.map(VcxErrorKind::InvalidSchema, "Cannot get schema"))?;
 
             let schema_json = serde_json::from_str(&schema_json)
@@ -39,13 +46,17 @@ pub fn build_schemas_json_prover(credentials_identifiers: &Vec<CredInfoProver>)
     Ok(rtn.to_string())
 }
 
-pub fn build_cred_defs_json_prover(credentials_identifiers: &Vec<CredInfoProver>) -> VcxResult<String> {
+pub fn build_cred_defs_json_prover(profile: &ProfileContext, credentials_identifiers: &Vec<CredInfoProver>) -> VcxResult<String> {
+    block_on(build_cred_defs_json_prover_async(profile, credentials_identifiers))
+}
+
+pub async fn build_cred_defs_json_prover_async(profile: &ProfileContext, credentials_identifiers: &Vec<CredInfoProver>) -> VcxResult<String> {
     trace!("build_cred_defs_json_prover >>> credentials_identifiers: {:?}", credentials_identifiers);
     let mut rtn: Value = json!({});
 
     for ref cred_info in credentials_identifiers {
         if rtn.get(&cred_info.cred_def_id).is_none() {
-            let (_, credential_def) = anoncreds::get_cred_def_json(&cred_info.cred_def_id)
+            let (_, credential_def) = anoncreds::get_cred_def_json_async(profile, &cred_info.cred_def_id).await
                 .map_err(|err| err.map(VcxErrorKind::InvalidProofCredentialData, "Cannot get credential definition"))?;
 
             let credential_def = serde_json::from_str(&credential_def)
@@ -102,7 +113,7 @@ pub fn credential_def_identifiers(credentials: &str, proof_req: &ProofRequestDat
     Ok(rtn)
 }
 
-fn _get_revocation_interval(attr_name: &str, proof_req: &ProofRequestData) -> VcxResult<Option<NonRevokedInterval>> {
+pub(super) fn _get_revocation_interval(attr_name: &str, proof_req: &ProofRequestData) -> VcxResult<Option<NonRevokedInterval>> {
     if let Some(attr) = proof_req.requested_attributes.get(attr_name) {
         Ok(attr.non_revoked.clone().or(proof_req.non_revoked.clone().or(None)))
     } else if let Some(attr) = proof_req.requested_predicates.get(attr_name) {
@@ -113,50 +124,50 @@ fn _get_revocation_interval(attr_name: &str, proof_req: &ProofRequestData) -> Vc
     }
 }
 
-pub fn build_rev_states_json(credentials_identifiers: &mut Vec<CredInfoProver>) -> VcxResult<String> {
+pub fn build_rev_states_json(profile: &ProfileContext, credentials_identifiers: &mut Vec<CredInfoProver>) -> VcxResult<String> {
+    block_on(build_rev_states_json_async(profile, credentials_identifiers))
+}
+
+pub async fn build_rev_states_json_async(profile: &ProfileContext, credentials_identifiers: &mut Vec<CredInfoProver>) -> VcxResult<String> {
+    _build_rev_states_json(credentials_identifiers, move |cred_id, rev_reg_id, cred_rev_id, tails_file, from, to| async move {
+        revocation_state_cache::get_rev_state_async(profile, &cred_id, &rev_reg_id, &cred_rev_id, &tails_file, from, to).await
+    }).await
+}
+
+async fn _build_rev_states_json<F, Fut>(credentials_identifiers: &mut Vec<CredInfoProver>, get_rev_state: F) -> VcxResult<String>
+    where F: Fn(String, String, String, String, Option<u64>, Option<u64>) -> Fut,
+          Fut: Future<Output=VcxResult<(u64, String)>> {
     trace!("build_rev_states_json >> credentials_identifiers: {:?}", credentials_identifiers);
     let mut rtn: Value = json!({});
-    let mut timestamps: HashMap<String, u64> = HashMap::new();
+    // referents of the same credential requested as of the same time share the timestamp
+    let mut timestamps: HashMap<(String, String, Option<u64>), u64> = HashMap::new();
 
     for cred_info in credentials_identifiers.iter_mut() {
         if let (Some(rev_reg_id), Some(cred_rev_id), Some(tails_file)) =
         (&cred_info.rev_reg_id, &cred_info.cred_rev_id, &cred_info.tails_file) {
-            if rtn.get(&rev_reg_id).is_none() { // Does this make sense in case cred_info's for same rev_reg_ids have different revocation intervals
-                let (from, to) = if let Some(ref interval) = cred_info.revocation_interval
-                { (interval.from, interval.to) } else { (None, None) };
-
-                let (_, rev_reg_def_json) = get_rev_reg_def_json(&rev_reg_id)?;
-
-                let (rev_reg_id, rev_reg_delta_json, timestamp) = get_rev_reg_delta_json(
-                    &rev_reg_id,
-                    from,
-                    to,
-                )?;
-
-                let rev_state_json = anoncreds::libindy_prover_create_revocation_state(
-                    &rev_reg_def_json,
-                    &rev_reg_delta_json,
-                    &cred_rev_id,
-                    &tails_file,
-                )?;
-
-                let rev_state_json: Value = serde_json::from_str(&rev_state_json)
-                    .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize RevocationState: {}", err)))?;
-
-                // TODO: proover should be able to create multiple states of same revocation policy for different timestamps
-                // see ticket IS-1108
-                rtn[rev_reg_id.to_string()] = json!({timestamp.to_string(): rev_state_json});
-                cred_info.timestamp = Some(timestamp);
-
-                // Cache timestamp for future attributes that have the same rev_reg_id
-                timestamps.insert(rev_reg_id.to_string(), timestamp);
-            }
-
-            // If the rev_reg_id is already in the map, timestamp may not be updated on cred_info
-            // All further credential info gets the same timestamp as the first one
-            if cred_info.timestamp.is_none() {
-                cred_info.timestamp = timestamps.get(rev_reg_id).cloned();
-            }
+            let (from, to) = if let Some(ref interval) = cred_info.revocation_interval
+            { (interval.from, interval.to) } else { (None, None) };
+
+            let key = (rev_reg_id.to_string(), cred_rev_id.to_string(), to);
+            let timestamp = match timestamps.get(&key) {
+                Some(timestamp) => *timestamp,
+                None => {
+                    let (timestamp, rev_state_json) = get_rev_state(cred_info.referent.to_string(),
+                                                                    rev_reg_id.to_string(),
+                                                                    cred_rev_id.to_string(),
+                                                                    tails_file.to_string(),
+                                                                    from,
+                                                                    to).await?;
+
+                    let rev_state_json: Value = serde_json::from_str(&rev_state_json)
+                        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize RevocationState: {}", err)))?;
+
+                    rtn[rev_reg_id.to_string()][timestamp.to_string()] = rev_state_json;
+                    timestamps.insert(key, timestamp);
+                    timestamp
+                }
+            };
+            cred_info.timestamp = Some(timestamp);
         }
     }
 
@@ -202,6 +213,10 @@ pub fn build_requested_credentials_json(credentials_identifiers: &Vec<CredInfoPr
 
 #[cfg(test)]
 pub mod tests {
+    use std::cell::RefCell;
+
+    use futures::future;
+
     use crate::libindy::proofs::proof_request_internal::NonRevokedInterval;
     use crate::libindy::proofs::prover::prover_internal::CredInfoProver;
     use crate::utils::{
@@ -260,7 +275,7 @@ pub mod tests {
         };
         let creds = vec![cred1, cred2];
 
-        let credential_def = build_cred_defs_json_prover(&creds).unwrap();
+        let credential_def = build_cred_defs_json_prover(&ProfileContext::Global, &creds).unwrap();
         assert!(credential_def.len() > 0);
         assert!(credential_def.contains(r#""id":"V4SGRU86Z58d6TV7PBUe6f:3:CL:47:tag1","schemaId":"47""#));
     }
@@ -281,7 +296,7 @@ pub mod tests {
             tails_file: None,
             timestamp: None,
         }];
-        assert_eq!(build_cred_defs_json_prover(&credential_ids).unwrap_err().kind(), VcxErrorKind::InvalidProofCredentialData);
+        assert_eq!(build_cred_defs_json_prover(&ProfileContext::Global, &credential_ids).unwrap_err().kind(), VcxErrorKind::InvalidProofCredentialData);
     }
 
     #[test]
@@ -300,7 +315,7 @@ pub mod tests {
             tails_file: None,
             timestamp: None,
         }];
-        assert_eq!(build_schemas_json_prover(&credential_ids).unwrap_err().kind(), VcxErrorKind::InvalidSchema);
+        assert_eq!(build_schemas_json_prover(&ProfileContext::Global, &credential_ids).unwrap_err().kind(), VcxErrorKind::InvalidSchema);
     }
 
     #[test]
@@ -308,7 +323,7 @@ pub mod tests {
     fn test_find_schemas() {
         let _setup = SetupMocks::init();
 
-        assert_eq!(build_schemas_json_prover(&Vec::new()).unwrap(), "{}".to_string());
+        assert_eq!(build_schemas_json_prover(&ProfileContext::Global, &Vec::new()).unwrap(), "{}".to_string());
 
         let cred1 = CredInfoProver {
             requested_attr: "height_1".to_string(),
@@ -334,7 +349,7 @@ pub mod tests {
         };
         let creds = vec![cred1, cred2];
 
-        let schemas = build_schemas_json_prover(&creds).unwrap();
+        let schemas = build_schemas_json_prover(&ProfileContext::Global, &creds).unwrap();
         assert!(schemas.len() > 0);
         assert!(schemas.contains(r#""id":"2hoqvcwupRTUNkXn6ArYzs:2:test-licence:4.4.4","name":"test-licence""#));
     }
@@ -602,20 +617,50 @@ pub mod tests {
             timestamp: None,
         };
         let mut cred_info = vec![cred1];
-        let states = build_rev_states_json(cred_info.as_mut()).unwrap();
+        let states = build_rev_states_json(&ProfileContext::Global, cred_info.as_mut()).unwrap();
         let rev_state_json: Value = serde_json::from_str(REV_STATE_JSON).unwrap();
         let expected = json!({REV_REG_ID: {"1": rev_state_json}}).to_string();
         assert_eq!(states, expected);
         assert!(cred_info[0].timestamp.is_some());
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_build_rev_states_json_for_multiple_intervals() {
+        let _setup = SetupMocks::init();
+
+        let cred_info = |requested_attr: &str, cred_rev_id: &str, to: u64| CredInfoProver {
+            requested_attr: requested_attr.to_string(),
+            referent: format!("cred_{}", cred_rev_id),
+            schema_id: SCHEMA_ID.to_string(),
+            cred_def_id: CRED_DEF_ID.to_string(),
+            rev_reg_id: Some(REV_REG_ID.to_string()),
+            cred_rev_id: Some(cred_rev_id.to_string()),
+            tails_file: Some(get_temp_dir_path(TEST_TAILS_FILE).to_str().unwrap().to_string()),
+            revocation_interval: Some(NonRevokedInterval { from: None, to: Some(to) }),
+            timestamp: None,
+        };
+        let built = RefCell::new(Vec::new());
+        let get_rev_state = |cred_id: String, _: String, cred_rev_id: String, _: String, _: Option<u64>, to: Option<u64>| {
+            built.borrow_mut().push((cred_id, to));
+            future::ready(Ok((to.unwrap(), json!({"cred_rev_id": cred_rev_id}).to_string())))
+        };
+        let mut cred_info = vec![cred_info("height", "1", 100), cred_info("name", "1", 200), cred_info("sex", "1", 100), cred_info("age", "2", 100)];
+        let states = block_on(_build_rev_states_json(cred_info.as_mut(), get_rev_state)).unwrap();
+
+        assert_eq!(vec![("cred_1".to_string(), Some(100)), ("cred_1".to_string(), Some(200)), ("cred_2".to_string(), Some(100))], built.into_inner());
+        let states: Value = serde_json::from_str(&states).unwrap();
+        assert_eq!(json!({"cred_rev_id": "1"}), states[REV_REG_ID]["200"]);
+        assert_eq!(vec![Some(100), Some(200), Some(100), Some(100)], cred_info.iter().map(|info| info.timestamp).collect::<Vec<_>>());
+    }
+
     #[cfg(feature = "pool_tests")]
     #[test]
     fn test_build_rev_states_json_empty() {
         let _setup = SetupLibraryWalletPoolZeroFees::init();
 
         // empty vector
-        assert_eq!(build_rev_states_json(Vec::new().as_mut()).unwrap(), "{}".to_string());
+        assert_eq!(build_rev_states_json(&ProfileContext::Global, Vec::new().as_mut()).unwrap(), "{}".to_string());
 
         // no rev_reg_id
         let cred1 = CredInfoProver {
@@ -629,7 +674,7 @@ pub mod tests {
             revocation_interval: None,
             timestamp: None,
         };
-        assert_eq!(build_rev_states_json(vec![cred1].as_mut()).unwrap(), "{}".to_string());
+        assert_eq!(build_rev_states_json(&ProfileContext::Global, vec![cred1].as_mut()).unwrap(), "{}".to_string());
     }
 
     #[test]
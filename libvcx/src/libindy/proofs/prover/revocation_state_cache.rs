@@ -0,0 +1,139 @@
+use crate::aries::utils::task::block_on;
+use crate::error::prelude::*;
+use crate::libindy::utils::{anoncreds, wallet};
+use crate::profile::ProfileContext;
+
+/**
+Wallet record (keyed by credential id) holding the latest revocation state built for the credential.
+ */
+pub const REV_STATE_CACHE_RECORD_TYPE: &str = "vcx_rev_state_cache";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CachedRevState {
+    pub timestamp: u64,
+    pub rev_state: String,
+}
+
+pub fn get_cached_rev_state(profile: &ProfileContext, cred_id: &str) -> Option<CachedRevState> {
+    block_on(get_cached_rev_state_async(profile, cred_id))
+}
+
+pub async fn get_cached_rev_state_async(profile: &ProfileContext, cred_id: &str) -> Option<CachedRevState> {
+    let options = json!({"retrieveType": false, "retrieveValue": true, "retrieveTags": false}).to_string();
+    let record: serde_json::Value = wallet::get_record_async(profile.wallet_handle().ok()?, REV_STATE_CACHE_RECORD_TYPE, cred_id, &options).await.ok()
+        .and_then(|record| serde_json::from_str(&record).ok())?;
+    record["value"].as_str().and_then(|value| serde_json::from_str(value).ok())
+}
+
+async fn _store_rev_state(profile: &ProfileContext, cred_id: &str, rev_state: &CachedRevState, exists: bool) -> VcxResult<()> {
+    let value = serde_json::to_string(rev_state)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Cannot serialize revocation state: {:?}", err)))?;
+    if exists {
+        wallet::update_record_value_async(profile.wallet_handle()?, REV_STATE_CACHE_RECORD_TYPE, cred_id, &value).await
+    } else {
+        wallet::add_record_async(profile.wallet_handle()?, REV_STATE_CACHE_RECORD_TYPE, cred_id, &value, None).await
+    }
+}
+
+async fn _cache_rev_state(profile: &ProfileContext, cred_id: &str, rev_state: &CachedRevState, exists: bool) {
+    if let Err(err) = _store_rev_state(profile, cred_id, rev_state, exists).await {
+        warn!("revocation_state_cache >>> cannot cache revocation state of credential {}: {}", cred_id, err);
+    }
+}
+
+pub fn delete_cached_rev_state(profile: &ProfileContext, cred_id: &str) -> VcxResult<()> {
+    block_on(delete_cached_rev_state_async(profile, cred_id))
+}
+
+pub async fn delete_cached_rev_state_async(profile: &ProfileContext, cred_id: &str) -> VcxResult<()> {
+    wallet::delete_record_async(profile.wallet_handle()?, REV_STATE_CACHE_RECORD_TYPE, cred_id).await
+}
+
+/**
+Returns revocation state of the credential as of `to` together with its ledger timestamp.
+
+The cached state is advanced by the delta accumulated since its timestamp; the state is built
+from scratch only if there is no cached state or the interval ends before the cached timestamp.
+ */
+pub fn get_rev_state(profile: &ProfileContext,
+                     cred_id: &str,
+                     rev_reg_id: &str,
+                     cred_rev_id: &str,
+                     tails_file: &str,
+                     from: Option<u64>,
+                     to: Option<u64>) -> VcxResult<(u64, String)> {
+    block_on(get_rev_state_async(profile, cred_id, rev_reg_id, cred_rev_id, tails_file, from, to))
+}
+
+pub async fn get_rev_state_async(profile: &ProfileContext,
+                                 cred_id: &str,
+                                 rev_reg_id: &str,
+                                 cred_rev_id: &str,
+                                 tails_file: &str,
+                                 from: Option<u64>,
+                                 to: Option<u64>) -> VcxResult<(u64, String)> {
+    trace!("get_rev_state >>> cred_id: {}, rev_reg_id: {}, from: {:?}, to: {:?}", cred_id, rev_reg_id, from, to);
+    let cached = get_cached_rev_state_async(profile, cred_id).await;
+
+    match cached {
+        Some(cached) if to.map_or(true, |to| to >= cached.timestamp) => {
+            let (_, rev_reg_delta_json, timestamp) = anoncreds::get_rev_reg_delta_json_async(profile, rev_reg_id, Some(cached.timestamp), to).await?;
+            if timestamp == cached.timestamp {
+                return Ok((cached.timestamp, cached.rev_state));
+            }
+
+            let (_, rev_reg_def_json) = anoncreds::get_rev_reg_def_json_async(profile, rev_reg_id).await?;
+            let rev_state = anoncreds::libindy_prover_update_revocation_state_async(&rev_reg_def_json,
+                                                                                    &cached.rev_state,
+                                                                                    &rev_reg_delta_json,
+                                                                                    cred_rev_id,
+                                                                                    tails_file).await?;
+            _cache_rev_state(profile, cred_id, &CachedRevState { timestamp, rev_state: rev_state.clone() }, true).await;
+            Ok((timestamp, rev_state))
+        }
+        cached => {
+            let (_, rev_reg_delta_json, timestamp) = anoncreds::get_rev_reg_delta_json_async(profile, rev_reg_id, from, to).await?;
+            let (_, rev_reg_def_json) = anoncreds::get_rev_reg_def_json_async(profile, rev_reg_id).await?;
+            let rev_state = anoncreds::libindy_prover_create_revocation_state_async(&rev_reg_def_json,
+                                                                                    &rev_reg_delta_json,
+                                                                                    cred_rev_id,
+                                                                                    tails_file).await?;
+            // states older than the cached one are not cached, later proofs ask for the most recent ones
+            match cached {
+                None => _cache_rev_state(profile, cred_id, &CachedRevState { timestamp, rev_state: rev_state.clone() }, false).await,
+                Some(cached) if timestamp > cached.timestamp => _cache_rev_state(profile, cred_id, &CachedRevState { timestamp, rev_state: rev_state.clone() }, true).await,
+                Some(_) => {}
+            }
+            Ok((timestamp, rev_state))
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::utils::constants::{CRED_REV_ID, REV_REG_ID, REV_STATE_JSON};
+    use crate::utils::devsetup::SetupMocks;
+
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_get_rev_state_without_cache() {
+        let _setup = SetupMocks::init();
+
+        assert_eq!(None, get_cached_rev_state(&ProfileContext::Global, "cred_id"));
+        let (timestamp, rev_state) = get_rev_state(&ProfileContext::Global, "cred_id", REV_REG_ID, CRED_REV_ID, "/tmp/tails", None, Some(100)).unwrap();
+        assert_eq!(1, timestamp);
+        assert_eq!(REV_STATE_JSON, rev_state);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_cached_rev_state_serialization() {
+        let _setup = SetupMocks::init();
+
+        let cached = CachedRevState { timestamp: 100, rev_state: REV_STATE_JSON.to_string() };
+        let serialized = serde_json::to_string(&cached).unwrap();
+        assert_eq!(cached, serde_json::from_str::<CachedRevState>(&serialized).unwrap());
+    }
+}
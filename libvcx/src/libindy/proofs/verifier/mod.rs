@@ -1,2 +1,3 @@
+pub mod verification_report;
 pub mod verifier;
-mod verifier_internal;
\ No newline at end of file
+mod verifier_internal;
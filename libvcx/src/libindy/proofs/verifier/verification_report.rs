@@ -0,0 +1,294 @@
+use std::collections::HashMap;
+
+use failure::Fail;
+
+use crate::error::prelude::*;
+use crate::libindy::proofs::proof_request::ProofRequestData;
+use crate::libindy::proofs::verifier::verifier_internal::{CredInfoVerifier, get_credential_info};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AttributeValue {
+    pub raw: String,
+    pub encoded: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RevealedAttribute {
+    pub name: Option<String>,
+    pub raw: String,
+    pub encoded: String,
+    pub sub_proof_index: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RevealedAttributeGroup {
+    pub values: HashMap<String, AttributeValue>,
+    pub sub_proof_index: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UnrevealedAttribute {
+    pub name: Option<String>,
+    pub sub_proof_index: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SelfAttestedAttribute {
+    pub name: Option<String>,
+    pub value: String,
+}
+
+/**
+Predicate requested by the verifier, `sub_proof_index` is not set if the proof does not cover it.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PredicateResult {
+    pub name: String,
+    pub p_type: String,
+    pub p_value: i32,
+    pub sub_proof_index: Option<u32>,
+    pub satisfied: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VerificationFailure {
+    pub code: u32,
+    pub reason: String,
+}
+
+/**
+Describes what has been proven by a presentation (keyed by referents of the proof request)
+and why the presentation has been rejected, if it has been.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VerificationReport {
+    pub verified: bool,
+    pub failure: Option<VerificationFailure>,
+    pub revealed_attrs: HashMap<String, RevealedAttribute>,
+    pub revealed_attr_groups: HashMap<String, RevealedAttributeGroup>,
+    pub unrevealed_attrs: HashMap<String, UnrevealedAttribute>,
+    pub self_attested_attrs: HashMap<String, SelfAttestedAttribute>,
+    pub predicates: HashMap<String, PredicateResult>,
+    pub identifiers: Vec<CredInfoVerifier>,
+}
+
+#[derive(Deserialize, Debug)]
+struct SubProofReferent {
+    sub_proof_index: u32,
+}
+
+#[derive(Deserialize, Debug)]
+struct RevealedAttributeInfo {
+    sub_proof_index: u32,
+    raw: String,
+    encoded: String,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct RequestedProof {
+    #[serde(default)]
+    revealed_attrs: HashMap<String, RevealedAttributeInfo>,
+    #[serde(default)]
+    revealed_attr_groups: HashMap<String, RevealedAttributeGroup>,
+    #[serde(default)]
+    unrevealed_attrs: HashMap<String, SubProofReferent>,
+    #[serde(default)]
+    self_attested_attrs: HashMap<String, String>,
+    #[serde(default)]
+    predicates: HashMap<String, SubProofReferent>,
+}
+
+impl RequestedProof {
+    fn covers_attribute(&self, referent: &str) -> bool {
+        self.revealed_attrs.contains_key(referent) ||
+            self.revealed_attr_groups.contains_key(referent) ||
+            self.unrevealed_attrs.contains_key(referent) ||
+            self.self_attested_attrs.contains_key(referent)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct Proof {
+    #[serde(default)]
+    requested_proof: RequestedProof,
+}
+
+/**
+Describes requested attributes and predicates the proof does not cover, sorted by referent.
+ */
+fn _unproven_referents(proof_req: &ProofRequestData, requested_proof: &RequestedProof) -> Vec<String> {
+    let mut attributes: Vec<_> = proof_req.requested_attributes.iter()
+        .filter(|(referent, _)| !requested_proof.covers_attribute(referent))
+        .map(|(referent, attr)| match (&attr.name, &attr.names) {
+            (Some(name), _) => format!("attribute \"{}\" ({})", name, referent),
+            (None, Some(names)) => format!("attributes {:?} ({})", names, referent),
+            (None, None) => format!("attribute ({})", referent),
+        })
+        .collect();
+    attributes.sort();
+    let mut predicates: Vec<_> = proof_req.requested_predicates.iter()
+        .filter(|(referent, _)| !requested_proof.predicates.contains_key(*referent))
+        .map(|(referent, predicate)| format!("predicate \"{} {} {}\" ({})", predicate.name, predicate.p_type, predicate.p_value, referent))
+        .collect();
+    predicates.sort();
+    attributes.into_iter().chain(predicates).collect()
+}
+
+/**
+Reason names the requested attributes and predicates which have not been proven (if there are such) and the cause.
+ */
+fn _verification_failure(err: &VcxError, unproven: &[String]) -> VerificationFailure {
+    let cause = err.cause().map(|cause| cause.to_string()).unwrap_or_else(|| err.kind().to_string());
+    let reason = if unproven.is_empty() {
+        cause
+    } else {
+        format!("Not proven: {}. Cause: {}", unproven.join(", "), cause)
+    };
+    VerificationFailure { code: err.kind().into(), reason }
+}
+
+impl VerificationReport {
+    /**
+    Builds report of the proof for the proof request, `result` is the outcome of the proof validation.
+     */
+    pub fn build(proof_json: &str, proof_req_json: &str, result: Result<(), &VcxError>) -> VcxResult<VerificationReport> {
+        trace!("VerificationReport::build >>> result: {:?}", result);
+        let proof: Proof = serde_json::from_str(proof_json)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize libndy proof: {}", err)))?;
+        let proof_req: ProofRequestData = serde_json::from_str(proof_req_json)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize proof request: {}", err)))?;
+        let requested_proof = proof.requested_proof;
+        let unproven = _unproven_referents(&proof_req, &requested_proof);
+
+        let attr_name = |referent: &str| proof_req.requested_attributes.get(referent).and_then(|attr| attr.name.clone());
+
+        let revealed_attrs = requested_proof.revealed_attrs.into_iter()
+            .map(|(referent, info)| {
+                let attribute = RevealedAttribute { name: attr_name(&referent), raw: info.raw, encoded: info.encoded, sub_proof_index: info.sub_proof_index };
+                (referent, attribute)
+            })
+            .collect();
+        let unrevealed_attrs = requested_proof.unrevealed_attrs.into_iter()
+            .map(|(referent, info)| {
+                let attribute = UnrevealedAttribute { name: attr_name(&referent), sub_proof_index: info.sub_proof_index };
+                (referent, attribute)
+            })
+            .collect();
+        let self_attested_attrs = requested_proof.self_attested_attrs.into_iter()
+            .map(|(referent, value)| {
+                let attribute = SelfAttestedAttribute { name: attr_name(&referent), value };
+                (referent, attribute)
+            })
+            .collect();
+
+        let proven_predicates = requested_proof.predicates;
+        let verified = result.is_ok();
+        let predicates = proof_req.requested_predicates.iter()
+            .map(|(referent, predicate)| {
+                let sub_proof_index = proven_predicates.get(referent).map(|info| info.sub_proof_index);
+                let result = PredicateResult {
+                    name: predicate.name.clone(),
+                    p_type: predicate.p_type.clone(),
+                    p_value: predicate.p_value,
+                    sub_proof_index,
+                    satisfied: verified && sub_proof_index.is_some(),
+                };
+                (referent.to_string(), result)
+            })
+            .collect();
+
+        Ok(VerificationReport {
+            verified,
+            failure: result.err().map(|err| _verification_failure(err, &unproven)),
+            revealed_attrs,
+            revealed_attr_groups: requested_proof.revealed_attr_groups,
+            unrevealed_attrs,
+            self_attested_attrs,
+            predicates,
+            identifiers: get_credential_info(proof_json)?,
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::utils::constants::PROOF_JSON;
+    use crate::utils::devsetup::SetupMocks;
+
+    use super::*;
+
+    fn _proof_req() -> String {
+        json!({
+            "nonce": "123432421212",
+            "name": "proof_req_1",
+            "version": "0.1",
+            "requested_attributes": {
+                "zip_2": { "name": "zip" },
+                "height_1": { "name": "height" },
+                "self_attest_3": { "name": "self_attest" }
+            },
+            "requested_predicates": {
+                "age_1": { "name": "age", "p_type": ">=", "p_value": 18 }
+            }
+        }).to_string()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_verification_report_for_valid_proof() {
+        let _setup = SetupMocks::init();
+
+        let report = VerificationReport::build(PROOF_JSON, &_proof_req(), Ok(())).unwrap();
+        assert!(report.verified);
+        assert_eq!(None, report.failure);
+        assert_eq!(RevealedAttribute { name: Some("zip".to_string()), raw: "87121".to_string(), encoded: "87121".to_string(), sub_proof_index: 0 },
+                   report.revealed_attrs["zip_2"]);
+        assert_eq!(Some("height".to_string()), report.revealed_attrs["height_1"].name);
+        assert_eq!("my_self_attested_val", report.self_attested_attrs["self_attest_3"].value);
+        assert_eq!(None, report.predicates["age_1"].sub_proof_index);
+        assert!(!report.predicates["age_1"].satisfied);
+        assert_eq!(2, report.identifiers.len());
+        assert_eq!("2hoqvcwupRTUNkXn6ArYzs:3:CL:2479", report.identifiers[0].cred_def_id);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_verification_report_for_rejected_proof() {
+        let _setup = SetupMocks::init();
+
+        let err = VcxError::from_msg(VcxErrorKind::InvalidProof, "Presentation verification failed");
+        let report = VerificationReport::build(PROOF_JSON, &_proof_req(), Err(&err)).unwrap();
+        assert!(!report.verified);
+        let failure = report.failure.unwrap();
+        assert_eq!(u32::from(VcxErrorKind::InvalidProof), failure.code);
+        assert_eq!("Not proven: predicate \"age >= 18\" (age_1). Cause: Presentation verification failed", failure.reason);
+        assert_eq!(2, report.revealed_attrs.len());
+        assert_eq!(">=", report.predicates["age_1"].p_type);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_verification_report_fails_for_invalid_proof_json() {
+        let _setup = SetupMocks::init();
+
+        assert_eq!(VcxErrorKind::InvalidJson, VerificationReport::build("{", &_proof_req(), Ok(())).unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_verification_report_names_missing_attribute() {
+        let _setup = SetupMocks::init();
+
+        let mut proof_req: serde_json::Value = serde_json::from_str(&_proof_req()).unwrap();
+        proof_req["requested_predicates"] = json!({});
+
+        let err = VcxError::from_msg(VcxErrorKind::InvalidProof, "Encoded value of \"zip_2\" attribute does not match its raw value");
+        let report = VerificationReport::build(PROOF_JSON, &proof_req.to_string(), Err(&err)).unwrap();
+        assert_eq!("Encoded value of \"zip_2\" attribute does not match its raw value", report.failure.unwrap().reason);
+
+        proof_req["requested_attributes"]["address_4"] = json!({"name": "address"});
+        let err = VcxError::from_msg(VcxErrorKind::InvalidProof, "Proof has been rejected");
+        let report = VerificationReport::build(PROOF_JSON, &proof_req.to_string(), Err(&err)).unwrap();
+        assert_eq!("Not proven: attribute \"address\" (address_4). Cause: Proof has been rejected", report.failure.unwrap().reason);
+    }
+}
@@ -1,9 +1,15 @@
+use crate::aries::utils::task::block_on;
 use crate::error::prelude::*;
-use crate::libindy::proofs::verifier::verifier_internal::{build_cred_defs_json_verifier, build_rev_reg_defs_json, build_rev_reg_json, build_schemas_json_verifier, get_credential_info, validate_proof_revealed_attributes};
+use crate::libindy::proofs::verifier::verifier_internal::{build_cred_defs_json_verifier_async, build_rev_reg_defs_json_async, build_rev_reg_json_async, build_schemas_json_verifier_async, get_credential_info, validate_proof_revealed_attributes};
 use crate::libindy::utils::anoncreds;
+use crate::profile::ProfileContext;
 use crate::utils::mockdata::mock_settings::get_mock_result_for_validate_indy_proof;
 
-pub fn validate_indy_proof(proof_json: &str, proof_req_json: &str) -> VcxResult<bool> {
+pub fn validate_indy_proof(profile: &ProfileContext, proof_json: &str, proof_req_json: &str) -> VcxResult<bool> {
+    block_on(validate_indy_proof_async(profile, proof_json, proof_req_json))
+}
+
+pub async fn validate_indy_proof_async(profile: &ProfileContext, proof_json: &str, proof_req_json: &str) -> VcxResult<bool> {
     if let Some(mock_result) = get_mock_result_for_validate_indy_proof() {
         return mock_result;
     }
@@ -12,13 +18,13 @@ pub fn validate_indy_proof(proof_json: &str, proof_req_json: &str) -> VcxResult<
 
     let credential_data = get_credential_info(&proof_json)?;
 
-    let credential_defs_json = build_cred_defs_json_verifier(&credential_data)
+    let credential_defs_json = build_cred_defs_json_verifier_async(profile, &credential_data).await
         .unwrap_or(json!({}).to_string());
-    let schemas_json = build_schemas_json_verifier(&credential_data)
+    let schemas_json = build_schemas_json_verifier_async(profile, &credential_data).await
         .unwrap_or(json!({}).to_string());
-    let rev_reg_defs_json = build_rev_reg_defs_json(&credential_data)
+    let rev_reg_defs_json = build_rev_reg_defs_json_async(profile, &credential_data).await
         .unwrap_or(json!({}).to_string());
-    let rev_regs_json = build_rev_reg_json(&credential_data)
+    let rev_regs_json = build_rev_reg_json_async(profile, &credential_data).await
         .unwrap_or(json!({}).to_string());
 
     debug!("*******\n{}\n********", credential_defs_json);
@@ -27,18 +33,19 @@ pub fn validate_indy_proof(proof_json: &str, proof_req_json: &str) -> VcxResult<
     debug!("*******\n{}\n********", proof_req_json);
     debug!("*******\n{}\n********", rev_reg_defs_json);
     debug!("*******\n{}\n********", rev_regs_json);
-    anoncreds::libindy_verifier_verify_proof(proof_req_json,
-                                             proof_json,
-                                             &schemas_json,
-                                             &credential_defs_json,
-                                             &rev_reg_defs_json,
-                                             &rev_regs_json)
+    anoncreds::libindy_verifier_verify_proof_async(proof_req_json,
+                                                   proof_json,
+                                                   &schemas_json,
+                                                   &credential_defs_json,
+                                                   &rev_reg_defs_json,
+                                                   &rev_regs_json).await
 }
 
 #[cfg(test)]
 pub mod tests {
     use crate::{libindy, utils, settings};
     use crate::libindy::proofs::proof_request::ProofRequestData;
+    use crate::libindy::utils::wallet::get_wallet_handle;
     use crate::utils::devsetup::SetupLibraryWalletPoolZeroFees;
 
     use super::*;
@@ -72,6 +79,7 @@ pub mod tests {
         let proof_req_json = serde_json::to_string(&proof_req_json).unwrap();
 
         let prover_proof_json = libindy::utils::anoncreds::libindy_prover_create_proof(
+            get_wallet_handle(),
             &proof_req_json,
             &json!({
               "self_attested_attributes":{
@@ -86,7 +94,7 @@ pub mod tests {
             &json!({}).to_string(),
             None).unwrap();
 
-        assert_eq!(validate_indy_proof(&prover_proof_json, &proof_req_json.to_string()).unwrap(), true);
+        assert_eq!(validate_indy_proof(&ProfileContext::Global, &prover_proof_json, &proof_req_json.to_string()).unwrap(), true);
     }
 
     #[test]
@@ -126,6 +134,7 @@ pub mod tests {
         let schema_json: serde_json::Value = serde_json::from_str(&schema_json).unwrap();
 
         let prover_proof_json = libindy::utils::anoncreds::libindy_prover_create_proof(
+            get_wallet_handle(),
             &proof_req_json,
             &json!({
                 "self_attested_attributes":{
@@ -141,11 +150,11 @@ pub mod tests {
             &json!({schema_id: schema_json}).to_string(),
             &json!({cred_def_id: cred_def_json}).to_string(),
             None).unwrap();
-        assert_eq!(validate_indy_proof(&prover_proof_json, &proof_req_json).unwrap_err().kind(), VcxErrorKind::LibndyError(405)); // AnoncredsProofRejected
+        assert_eq!(validate_indy_proof(&ProfileContext::Global, &prover_proof_json, &proof_req_json).unwrap_err().kind(), VcxErrorKind::LibndyError(405)); // AnoncredsProofRejected
 
         let mut proof_req_json: serde_json::Value = serde_json::from_str(&proof_req_json).unwrap();
         proof_req_json["requested_attributes"]["attribute_0"]["restrictions"] = json!({});
-        assert_eq!(validate_indy_proof(&prover_proof_json, &proof_req_json.to_string()).unwrap(), true);
+        assert_eq!(validate_indy_proof(&ProfileContext::Global, &prover_proof_json, &proof_req_json.to_string()).unwrap(), true);
     }
 
     #[test]
@@ -187,6 +196,7 @@ pub mod tests {
         let schema_json: serde_json::Value = serde_json::from_str(&schema_json).unwrap();
 
         let prover_proof_json = libindy::utils::anoncreds::libindy_prover_create_proof(
+            get_wallet_handle(),
             &proof_req_json,
             &json!({
                 "self_attested_attributes":{
@@ -202,20 +212,20 @@ pub mod tests {
             &json!({schema_id: schema_json}).to_string(),
             &json!({cred_def_id: cred_def_json}).to_string(),
             None).unwrap();
-        assert_eq!(validate_indy_proof(&prover_proof_json, &proof_req_json).unwrap(), true);
+        assert_eq!(validate_indy_proof(&ProfileContext::Global, &prover_proof_json, &proof_req_json).unwrap(), true);
 
         let mut proof_obj: serde_json::Value = serde_json::from_str(&prover_proof_json).unwrap();
         {
             proof_obj["requested_proof"]["revealed_attrs"]["address1_1"]["raw"] = json!("Other Value");
             let prover_proof_json = serde_json::to_string(&proof_obj).unwrap();
 
-            assert_eq!(validate_indy_proof(&prover_proof_json, &proof_req_json).unwrap_err().kind(), VcxErrorKind::InvalidProof);
+            assert_eq!(validate_indy_proof(&ProfileContext::Global, &prover_proof_json, &proof_req_json).unwrap_err().kind(), VcxErrorKind::InvalidProof);
         }
         {
             proof_obj["requested_proof"]["revealed_attrs"]["address1_1"]["encoded"] = json!("1111111111111111111111111111111111111111111111111111111111");
             let prover_proof_json = serde_json::to_string(&proof_obj).unwrap();
 
-            assert_eq!(validate_indy_proof(&prover_proof_json, &proof_req_json).unwrap_err().kind(), VcxErrorKind::InvalidProof);
+            assert_eq!(validate_indy_proof(&ProfileContext::Global, &prover_proof_json, &proof_req_json).unwrap_err().kind(), VcxErrorKind::InvalidProof);
         }
     }
 }
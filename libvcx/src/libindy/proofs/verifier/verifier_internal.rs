@@ -1,12 +1,14 @@
 use serde_json;
 use serde_json::Value;
 
+use crate::aries::utils::task::block_on;
 use crate::error::prelude::*;
 use crate::libindy::utils::anoncreds;
+use crate::profile::ProfileContext;
 use crate::settings;
 use crate::utils::openssl::encode;
 
-#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub struct CredInfoVerifier {
     pub schema_id: String,
     pub cred_def_id: String,
@@ -62,20 +64,24 @@ pub fn validate_proof_revealed_attributes(proof_json: &str) -> VcxResult<()> {
         let expected_encoded = encode(&raw)?;
 
         if expected_encoded != encoded_.to_string() {
-            return Err(VcxError::from_msg(VcxErrorKind::InvalidProof, format!("Encoded values are different. Expected: {}. From Proof: {}", expected_encoded, encoded_)));
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidProof, format!("Encoded value of \"{}\" attribute does not match its raw value. Expected: {}. From Proof: {}", attr1_referent, expected_encoded, encoded_)));
         }
     }
 
     Ok(())
 }
 
-pub fn build_cred_defs_json_verifier(credential_data: &Vec<CredInfoVerifier>) -> VcxResult<String> {
+pub fn build_cred_defs_json_verifier(profile: &ProfileContext, credential_data: &Vec<CredInfoVerifier>) -> VcxResult<String> {
+    block_on(build_cred_defs_json_verifier_async(profile, credential_data))
+}
+
+pub async fn build_cred_defs_json_verifier_async(profile: &ProfileContext, credential_data: &Vec<CredInfoVerifier>) -> VcxResult<String> {
     debug!("building credential_def_json for proof validation");
     let mut credential_json = json!({});
 
     for ref cred_info in credential_data.iter() {
         if credential_json.get(&cred_info.cred_def_id).is_none() {
-            let (id, credential_def) = anoncreds::get_cred_def_json(&cred_info.cred_def_id)?;
+            let (id, credential_def) = anoncreds::get_cred_def_json_async(profile, &cred_info.cred_def_id).await?;
 
             let credential_def = serde_json::from_str(&credential_def)
                 .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidProofCredentialData, format!("Cannot deserialize credential definition: {}", err)))?;
@@ -87,14 +93,18 @@ pub fn build_cred_defs_json_verifier(credential_data: &Vec<CredInfoVerifier>) ->
     Ok(credential_json.to_string())
 }
 
-pub fn build_schemas_json_verifier(credential_data: &Vec<CredInfoVerifier>) -> VcxResult<String> {
+pub fn build_schemas_json_verifier(profile: &ProfileContext, credential_data: &Vec<CredInfoVerifier>) -> VcxResult<String> {
+    block_on(build_schemas_json_verifier_async(profile, credential_data))
+}
+
+pub async fn build_schemas_json_verifier_async(profile: &ProfileContext, credential_data: &Vec<CredInfoVerifier>) -> VcxResult<String> {
     debug!("building schemas json for proof validation");
 
     let mut schemas_json = json!({});
 
     for ref cred_info in credential_data.iter() {
         if schemas_json.get(&cred_info.schema_id).is_none() {
-            let (id, schema_json) = anoncreds::get_schema_json(&cred_info.schema_id)
+            let (id, schema_json) = anoncreds::get_schema_json_async(profile, &cred_info.schema_id).await
                 .map_err(|err| err.map(VcxErrorKind::InvalidSchema, "Cannot get schema"))?;
 
             let schema_val = serde_json::from_str(&schema_json)
@@ -107,7 +117,11 @@ pub fn build_schemas_json_verifier(credential_data: &Vec<CredInfoVerifier>) -> V
     Ok(schemas_json.to_string())
 }
 
-pub fn build_rev_reg_defs_json(credential_data: &Vec<CredInfoVerifier>) -> VcxResult<String> {
+pub fn build_rev_reg_defs_json(profile: &ProfileContext, credential_data: &Vec<CredInfoVerifier>) -> VcxResult<String> {
+    block_on(build_rev_reg_defs_json_async(profile, credential_data))
+}
+
+pub async fn build_rev_reg_defs_json_async(profile: &ProfileContext, credential_data: &Vec<CredInfoVerifier>) -> VcxResult<String> {
     debug!("building rev_reg_def_json for proof validation");
 
     let mut rev_reg_defs_json = json!({});
@@ -119,7 +133,7 @@ pub fn build_rev_reg_defs_json(credential_data: &Vec<CredInfoVerifier>) -> VcxRe
             .ok_or(VcxError::from(VcxErrorKind::InvalidRevocationDetails))?;
 
         if rev_reg_defs_json.get(rev_reg_id).is_none() {
-            let (id, json) = anoncreds::get_rev_reg_def_json(rev_reg_id)
+            let (id, json) = anoncreds::get_rev_reg_def_json_async(profile, rev_reg_id).await
                 .or(Err(VcxError::from(VcxErrorKind::InvalidRevocationDetails)))?;
 
             let rev_reg_def_json = serde_json::from_str(&json)
@@ -132,7 +146,11 @@ pub fn build_rev_reg_defs_json(credential_data: &Vec<CredInfoVerifier>) -> VcxRe
     Ok(rev_reg_defs_json.to_string())
 }
 
-pub fn build_rev_reg_json(credential_data: &Vec<CredInfoVerifier>) -> VcxResult<String> {
+pub fn build_rev_reg_json(profile: &ProfileContext, credential_data: &Vec<CredInfoVerifier>) -> VcxResult<String> {
+    block_on(build_rev_reg_json_async(profile, credential_data))
+}
+
+pub async fn build_rev_reg_json_async(profile: &ProfileContext, credential_data: &Vec<CredInfoVerifier>) -> VcxResult<String> {
     debug!("building rev_reg_json for proof validation");
 
     let mut rev_regs_json = json!({});
@@ -149,7 +167,7 @@ pub fn build_rev_reg_json(credential_data: &Vec<CredInfoVerifier>) -> VcxResult<
             .ok_or(VcxError::from(VcxErrorKind::InvalidRevocationTimestamp))?;
 
         if rev_regs_json.get(rev_reg_id).is_none() {
-            let (id, json, timestamp) = anoncreds::get_rev_reg(rev_reg_id, timestamp.to_owned())
+            let (id, json, timestamp) = anoncreds::get_rev_reg_async(profile, rev_reg_id, timestamp.to_owned()).await
                 .or(Err(VcxError::from(VcxErrorKind::InvalidRevocationDetails)))?;
 
             let rev_reg_json: Value = serde_json::from_str(&json)
@@ -188,7 +206,7 @@ pub mod tests {
             timestamp: None,
         };
         let credentials = vec![cred1, cred2];
-        let credential_json = build_cred_defs_json_verifier(&credentials).unwrap();
+        let credential_json = build_cred_defs_json_verifier(&ProfileContext::Global, &credentials).unwrap();
 
         let json: Value = serde_json::from_str(CRED_DEF_JSON).unwrap();
         let expected = json!({CRED_DEF_ID:json}).to_string();
@@ -213,7 +231,7 @@ pub mod tests {
             timestamp: None,
         };
         let credentials = vec![cred1, cred2];
-        let schema_json = build_schemas_json_verifier(&credentials).unwrap();
+        let schema_json = build_schemas_json_verifier(&ProfileContext::Global, &credentials).unwrap();
 
         let json: Value = serde_json::from_str(SCHEMA_JSON).unwrap();
         let expected = json!({SCHEMA_ID:json}).to_string();
@@ -238,7 +256,7 @@ pub mod tests {
             timestamp: None,
         };
         let credentials = vec![cred1, cred2];
-        let rev_reg_defs_json = build_rev_reg_defs_json(&credentials).unwrap();
+        let rev_reg_defs_json = build_rev_reg_defs_json(&ProfileContext::Global, &credentials).unwrap();
 
         let json: Value = serde_json::from_str(&rev_def_json()).unwrap();
         let expected = json!({REV_REG_ID:json}).to_string();
@@ -263,7 +281,7 @@ pub mod tests {
             timestamp: Some(2),
         };
         let credentials = vec![cred1, cred2];
-        let rev_reg_json = build_rev_reg_json(&credentials).unwrap();
+        let rev_reg_json = build_rev_reg_json(&ProfileContext::Global, &credentials).unwrap();
 
         let json: Value = serde_json::from_str(REV_REG_JSON).unwrap();
         let expected = json!({REV_REG_ID:{"1":json}}).to_string();
@@ -1,16 +1,19 @@
-use indy::{anoncreds, blob_storage, ledger};
+use indy::{anoncreds, blob_storage, ledger, WalletHandle};
+use futures::compat::Future01CompatExt;
 use indy::future::Future;
 use serde_json;
 use serde_json::{map::Map, Value};
 use time;
 
 use crate::{libindy, settings, utils};
+use crate::aries::utils::task::block_on;
 use crate::error::prelude::*;
-use crate::libindy::utils::{LibindyMock, wallet::get_wallet_handle};
-use crate::libindy::utils::cache::{clear_rev_reg_delta_cache, get_rev_reg_delta_cache, set_rev_reg_delta_cache};
+use crate::libindy::utils::LibindyMock;
+use crate::libindy::utils::cache::{clear_rev_reg_delta_cache, get_rev_reg_delta_cache, get_rev_reg_delta_cache_async, record_rev_reg_issuance_async, set_rev_reg_delta_cache_async};
 use crate::libindy::utils::ledger::*;
 use crate::libindy::utils::payments::{pay_for_txn, PaymentTxn};
-use crate::utils::constants::{ATTRS, LIBINDY_CRED_OFFER, PROOF_REQUESTED_PREDICATES, REQUESTED_ATTRIBUTES, REV_STATE_JSON};
+use crate::profile::ProfileContext;
+use crate::utils::constants::{ATTRS, LIBINDY_CRED_OFFER, PROOF_REQUESTED_PREDICATES, REQUESTED_ATTRIBUTES, REV_STATE_JSON, STORED_CREDENTIAL_INFO};
 use crate::utils::constants::{CREATE_CRED_DEF_ACTION, CREATE_REV_REG_DEF_ACTION, CREATE_REV_REG_DELTA_ACTION, CREATE_SCHEMA_ACTION, CRED_DEF_ID, CRED_DEF_JSON, CRED_DEF_REQ, rev_def_json, REV_REG_DELTA_JSON, REV_REG_ID, REV_REG_JSON, REVOC_REG_TYPE, SCHEMA_ID, SCHEMA_JSON, SCHEMA_TXN};
 use crate::utils::mockdata::mock_settings::get_mock_creds_retrieved_for_proof_request;
 
@@ -23,17 +26,27 @@ pub fn libindy_verifier_verify_proof(proof_req_json: &str,
                                      credential_defs_json: &str,
                                      rev_reg_defs_json: &str,
                                      rev_regs_json: &str) -> VcxResult<bool> {
+    block_on(libindy_verifier_verify_proof_async(proof_req_json, proof_json, schemas_json, credential_defs_json, rev_reg_defs_json, rev_regs_json))
+}
+
+pub async fn libindy_verifier_verify_proof_async(proof_req_json: &str,
+                                                 proof_json: &str,
+                                                 schemas_json: &str,
+                                                 credential_defs_json: &str,
+                                                 rev_reg_defs_json: &str,
+                                                 rev_regs_json: &str) -> VcxResult<bool> {
     anoncreds::verifier_verify_proof(proof_req_json,
                                      proof_json,
                                      schemas_json,
                                      credential_defs_json,
                                      rev_reg_defs_json,
                                      rev_regs_json)
-        .wait()
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
-pub fn libindy_create_and_store_revoc_reg(issuer_did: &str, cred_def_id: &str, tails_path: &str, max_creds: u32, tag: &str) -> VcxResult<(String, String, String)> {
+pub fn libindy_create_and_store_revoc_reg(wallet_handle: WalletHandle, issuer_did: &str, cred_def_id: &str, tails_path: &str, max_creds: u32, tag: &str) -> VcxResult<(String, String, String)> {
     trace!("creating revocation: {}, {}, {}", cred_def_id, tails_path, max_creds);
 
     let tails_config = json!({"base_dir": tails_path,"uri_pattern": ""}).to_string();
@@ -43,17 +56,18 @@ pub fn libindy_create_and_store_revoc_reg(issuer_did: &str, cred_def_id: &str, t
 
     let revoc_config = json!({"max_cred_num": max_creds, "issuance_type": REVOCATION_REGISTRY_TYPE}).to_string();
 
-    anoncreds::issuer_create_and_store_revoc_reg(get_wallet_handle(), issuer_did, None, tag, cred_def_id, &revoc_config, writer)
+    anoncreds::issuer_create_and_store_revoc_reg(wallet_handle, issuer_did, None, tag, cred_def_id, &revoc_config, writer)
         .wait()
         .map_err(VcxError::from)
 }
 
-pub fn libindy_create_and_store_credential_def(issuer_did: &str,
+pub fn libindy_create_and_store_credential_def(wallet_handle: WalletHandle,
+                                               issuer_did: &str,
                                                schema_json: &str,
                                                tag: &str,
                                                sig_type: Option<&str>,
                                                config_json: &str) -> VcxResult<(String, String)> {
-    anoncreds::issuer_create_and_store_credential_def(get_wallet_handle(),
+    anoncreds::issuer_create_and_store_credential_def(wallet_handle,
                                                       issuer_did,
                                                       schema_json,
                                                       tag,
@@ -63,73 +77,109 @@ pub fn libindy_create_and_store_credential_def(issuer_did: &str,
         .map_err(VcxError::from)
 }
 
-pub fn libindy_issuer_create_credential_offer(cred_def_id: &str) -> VcxResult<String> {
+pub fn libindy_issuer_create_credential_offer(wallet_handle: WalletHandle, cred_def_id: &str) -> VcxResult<String> {
+    block_on(libindy_issuer_create_credential_offer_async(wallet_handle, cred_def_id))
+}
+
+pub async fn libindy_issuer_create_credential_offer_async(wallet_handle: WalletHandle, cred_def_id: &str) -> VcxResult<String> {
     if settings::indy_mocks_enabled() {
         let rc = LibindyMock::get_result();
         if rc != 0 { return Err(VcxError::from(VcxErrorKind::InvalidState)); };
         return Ok(LIBINDY_CRED_OFFER.to_string());
     }
-    anoncreds::issuer_create_credential_offer(get_wallet_handle(),
+    anoncreds::issuer_create_credential_offer(wallet_handle,
                                               cred_def_id)
-        .wait()
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
-fn blob_storage_open_reader(base_dir: &str) -> VcxResult<i32> {
+async fn blob_storage_open_reader_async(base_dir: &str) -> VcxResult<i32> {
     let tails_config = json!({"base_dir": base_dir,"uri_pattern": ""}).to_string();
     blob_storage::open_reader("default", &tails_config)
-        .wait()
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
-pub fn libindy_issuer_create_credential(cred_offer_json: &str,
+pub fn libindy_issuer_create_credential(wallet_handle: WalletHandle,
+                                        cred_offer_json: &str,
                                         cred_req_json: &str,
                                         cred_values_json: &str,
                                         rev_reg_id: Option<String>,
                                         tails_file: Option<String>) -> VcxResult<(String, Option<String>, Option<String>)> {
+    block_on(libindy_issuer_create_credential_async(wallet_handle, cred_offer_json, cred_req_json, cred_values_json, rev_reg_id, tails_file))
+}
+
+pub async fn libindy_issuer_create_credential_async(wallet_handle: WalletHandle,
+                                                    cred_offer_json: &str,
+                                                    cred_req_json: &str,
+                                                    cred_values_json: &str,
+                                                    rev_reg_id: Option<String>,
+                                                    tails_file: Option<String>) -> VcxResult<(String, Option<String>, Option<String>)> {
     if settings::indy_mocks_enabled() { return Ok((utils::constants::CREDENTIAL_JSON.to_owned(), None, None)); }
 
     let revocation = rev_reg_id.as_ref().map(String::as_str);
 
     let blob_handle = match tails_file {
-        Some(x) => blob_storage_open_reader(&x)?,
+        Some(x) => blob_storage_open_reader_async(&x).await?,
         None => -1,
     };
-    anoncreds::issuer_create_credential(get_wallet_handle(),
-                                        cred_offer_json,
-                                        cred_req_json,
-                                        cred_values_json,
-                                        revocation,
-                                        blob_handle)
-        .wait()
-        .map_err(VcxError::from)
+    let (credential, cred_rev_id, rev_reg_delta) = anoncreds::issuer_create_credential(wallet_handle,
+                                                                                     cred_offer_json,
+                                                                                     cred_req_json,
+                                                                                     cred_values_json,
+                                                                                     revocation,
+                                                                                     blob_handle)
+        .compat()
+        .await
+        .map_err(VcxError::from)?;
+
+    if let (Some(rev_reg_id), Some(cred_rev_id)) = (revocation, &cred_rev_id) {
+        if let Err(err) = record_rev_reg_issuance_async(wallet_handle, rev_reg_id, cred_rev_id).await {
+            warn!("libindy_issuer_create_credential_async >>> cannot record issuance for rev_reg_id: {}, error: {}", rev_reg_id, err);
+        }
+    }
+    Ok((credential, cred_rev_id, rev_reg_delta))
 }
 
-pub fn libindy_prover_create_proof(proof_req_json: &str,
+pub fn libindy_prover_create_proof(wallet_handle: WalletHandle,
+                                   proof_req_json: &str,
                                    requested_credentials_json: &str,
                                    master_secret_id: &str,
                                    schemas_json: &str,
                                    credential_defs_json: &str,
                                    revoc_states_json: Option<&str>) -> VcxResult<String> {
+    block_on(libindy_prover_create_proof_async(wallet_handle, proof_req_json, requested_credentials_json, master_secret_id, schemas_json, credential_defs_json, revoc_states_json))
+}
+
+pub async fn libindy_prover_create_proof_async(wallet_handle: WalletHandle,
+                                               proof_req_json: &str,
+                                               requested_credentials_json: &str,
+                                               master_secret_id: &str,
+                                               schemas_json: &str,
+                                               credential_defs_json: &str,
+                                               revoc_states_json: Option<&str>) -> VcxResult<String> {
     if settings::indy_mocks_enabled() { return Ok(utils::constants::PROOF_JSON.to_owned()); }
 
     let revoc_states_json = revoc_states_json.unwrap_or("{}");
-    anoncreds::prover_create_proof(get_wallet_handle(),
+    anoncreds::prover_create_proof(wallet_handle,
                                    proof_req_json,
                                    requested_credentials_json,
                                    master_secret_id,
                                    schemas_json,
                                    credential_defs_json,
                                    revoc_states_json)
-        .wait()
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
-fn fetch_credentials(search_handle: i32, requested_attributes: Map<String, Value>) -> VcxResult<String> {
+async fn fetch_credentials_async(search_handle: i32, requested_attributes: Map<String, Value>) -> VcxResult<String> {
     let mut v: Value = json!({});
     for item_referent in requested_attributes.keys().into_iter() {
         v[ATTRS][item_referent] =
-            serde_json::from_str(&anoncreds::prover_fetch_credentials_for_proof_req(search_handle, item_referent, 100).wait()?)
+            serde_json::from_str(&anoncreds::prover_fetch_credentials_for_proof_req(search_handle, item_referent, 100).compat().await?)
                 .map_err(|_| {
                     error!("Invalid Json Parsing of Object Returned from Libindy. Did Libindy change its structure?");
                     VcxError::from_msg(VcxErrorKind::InvalidConfiguration, "Invalid Json Parsing of Object Returned from Libindy. Did Libindy change its structure?")
@@ -139,24 +189,14 @@ fn fetch_credentials(search_handle: i32, requested_attributes: Map<String, Value
     Ok(v.to_string())
 }
 
-fn close_search_handle(search_handle: i32) -> VcxResult<()> {
+async fn close_search_handle_async(search_handle: i32) -> VcxResult<()> {
     anoncreds::prover_close_credentials_search_for_proof_req(search_handle)
-        .wait()
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
-pub fn libindy_prover_get_credentials_for_proof_req(proof_req: &str) -> VcxResult<String> {
-    trace!("libindy_prover_get_credentials_for_proof_req >>> proof_req: {}", proof_req);
-    match get_mock_creds_retrieved_for_proof_request() {
-        None => {}
-        Some(mocked_creds) => {
-            warn!("get_mock_creds_retrieved_for_proof_request  returning mocked response");
-            return Ok(mocked_creds);
-        }
-    }
-
-    let wallet_handle = get_wallet_handle();
-
+fn _attributes_to_fetch(proof_req: &str) -> VcxResult<Map<String, Value>> {
     // this may be too redundant since Prover::search_credentials will validate the proof reqeuest already.
     let proof_request_json: Map<String, Value> = serde_json::from_str(proof_req)
         .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidProofRequest, format!("Cannot deserialize ProofRequest: {:?}", err)))?;
@@ -189,88 +229,189 @@ pub fn libindy_prover_get_credentials_for_proof_req(proof_req: &str) -> VcxResul
         Some(attrs) => fetch_attrs.extend(attrs),
         None => ()
     }
+    Ok(fetch_attrs)
+}
+
+pub fn libindy_prover_get_credentials_for_proof_req(wallet_handle: WalletHandle, proof_req: &str) -> VcxResult<String> {
+    block_on(libindy_prover_get_credentials_for_proof_req_async(wallet_handle, proof_req))
+}
+
+pub async fn libindy_prover_get_credentials_for_proof_req_async(wallet_handle: WalletHandle, proof_req: &str) -> VcxResult<String> {
+    trace!("libindy_prover_get_credentials_for_proof_req_async >>> proof_req: {}", proof_req);
+    match get_mock_creds_retrieved_for_proof_request() {
+        None => {}
+        Some(mocked_creds) => {
+            warn!("get_mock_creds_retrieved_for_proof_request  returning mocked response");
+            return Ok(mocked_creds);
+        }
+    }
+
+    let fetch_attrs = _attributes_to_fetch(proof_req)?;
     if 0 < fetch_attrs.len() {
         let search_handle = anoncreds::prover_search_credentials_for_proof_req(wallet_handle, proof_req, None)
-            .wait()
+            .compat()
+            .await
             .map_err(|ec| {
                 error!("Opening Indy Search for Credentials Failed");
                 ec
             })?;
-        let creds: String = fetch_credentials(search_handle, fetch_attrs)?;
+        let creds: String = fetch_credentials_async(search_handle, fetch_attrs).await?;
 
         // should an error on closing a search handle throw an error, or just a warning?
         // for now we're are just outputting to the user that there is an issue, and continuing on.
-        let _ = close_search_handle(search_handle);
+        let _ = close_search_handle_async(search_handle).await;
         Ok(creds)
     } else {
         Ok("{}".to_string())
     }
 }
 
-pub fn libindy_prover_create_credential_req(prover_did: &str,
+pub fn libindy_prover_create_credential_req(wallet_handle: WalletHandle,
+                                            prover_did: &str,
                                             credential_offer_json: &str,
                                             credential_def_json: &str) -> VcxResult<(String, String)> {
+    block_on(libindy_prover_create_credential_req_async(wallet_handle, prover_did, credential_offer_json, credential_def_json))
+}
+
+pub async fn libindy_prover_create_credential_req_async(wallet_handle: WalletHandle,
+                                                        prover_did: &str,
+                                                        credential_offer_json: &str,
+                                                        credential_def_json: &str) -> VcxResult<(String, String)> {
     if settings::indy_mocks_enabled() { return Ok((utils::constants::CREDENTIAL_REQ_STRING.to_owned(), String::new())); }
 
     let master_secret_name = settings::DEFAULT_LINK_SECRET_ALIAS;
-    anoncreds::prover_create_credential_req(get_wallet_handle(),
+    anoncreds::prover_create_credential_req(wallet_handle,
                                             prover_did,
                                             credential_offer_json,
                                             credential_def_json,
                                             master_secret_name)
-        .wait()
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
 pub fn libindy_prover_create_revocation_state(rev_reg_def_json: &str, rev_reg_delta_json: &str, cred_rev_id: &str, tails_file: &str) -> VcxResult<String> {
+    block_on(libindy_prover_create_revocation_state_async(rev_reg_def_json, rev_reg_delta_json, cred_rev_id, tails_file))
+}
+
+pub async fn libindy_prover_create_revocation_state_async(rev_reg_def_json: &str, rev_reg_delta_json: &str, cred_rev_id: &str, tails_file: &str) -> VcxResult<String> {
     if settings::indy_mocks_enabled() { return Ok(REV_STATE_JSON.to_string()); }
 
-    let blob_handle = blob_storage_open_reader(tails_file)?;
+    let blob_handle = blob_storage_open_reader_async(tails_file).await?;
 
     anoncreds::create_revocation_state(blob_handle, rev_reg_def_json, rev_reg_delta_json, 100, cred_rev_id)
-        .wait()
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
 pub fn libindy_prover_update_revocation_state(rev_reg_def_json: &str, rev_state_json: &str, rev_reg_delta_json: &str, cred_rev_id: &str, tails_file: &str) -> VcxResult<String> {
+    block_on(libindy_prover_update_revocation_state_async(rev_reg_def_json, rev_state_json, rev_reg_delta_json, cred_rev_id, tails_file))
+}
+
+pub async fn libindy_prover_update_revocation_state_async(rev_reg_def_json: &str, rev_state_json: &str, rev_reg_delta_json: &str, cred_rev_id: &str, tails_file: &str) -> VcxResult<String> {
     if settings::indy_mocks_enabled() { return Ok(REV_STATE_JSON.to_string()); }
 
-    let blob_handle = blob_storage_open_reader(tails_file)?;
+    let blob_handle = blob_storage_open_reader_async(tails_file).await?;
 
     anoncreds::update_revocation_state(blob_handle, rev_state_json, rev_reg_def_json, rev_reg_delta_json, 100, cred_rev_id)
-        .wait()
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
-pub fn libindy_prover_store_credential(cred_id: Option<&str>,
+pub fn libindy_prover_store_credential(wallet_handle: WalletHandle,
+                                       cred_id: Option<&str>,
                                        cred_req_meta: &str,
                                        cred_json: &str,
                                        cred_def_json: &str,
                                        rev_reg_def_json: Option<&str>) -> VcxResult<String> {
-    trace!("libindy_prover_store_credential >>> cred_id: {:?}, cred_req_meta: {}, cred_json: {}, cred_def_json: {}, rev_reg_def_json: {:?}", cred_id, cred_req_meta, cred_json, cred_def_json, rev_reg_def_json);
+    block_on(libindy_prover_store_credential_async(wallet_handle, cred_id, cred_req_meta, cred_json, cred_def_json, rev_reg_def_json))
+}
+
+pub async fn libindy_prover_store_credential_async(wallet_handle: WalletHandle,
+                                                   cred_id: Option<&str>,
+                                                   cred_req_meta: &str,
+                                                   cred_json: &str,
+                                                   cred_def_json: &str,
+                                                   rev_reg_def_json: Option<&str>) -> VcxResult<String> {
+    trace!("libindy_prover_store_credential_async >>> cred_id: {:?}, cred_req_meta: {}, cred_json: {}, cred_def_json: {}, rev_reg_def_json: {:?}", cred_id, cred_req_meta, cred_json, cred_def_json, rev_reg_def_json);
     if settings::indy_mocks_enabled() { return Ok("cred_id".to_string()); }
 
-    anoncreds::prover_store_credential(get_wallet_handle(),
+    anoncreds::prover_store_credential(wallet_handle,
                                        cred_id,
                                        cred_req_meta,
                                        cred_json,
                                        cred_def_json,
                                        rev_reg_def_json)
-        .wait()
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
-pub fn libindy_prover_delete_credential(cred_id: &str) -> VcxResult<()> {
-    anoncreds::prover_delete_credential(get_wallet_handle(),
+pub fn libindy_prover_delete_credential(wallet_handle: WalletHandle, cred_id: &str) -> VcxResult<()> {
+    block_on(libindy_prover_delete_credential_async(wallet_handle, cred_id))
+}
+
+pub async fn libindy_prover_delete_credential_async(wallet_handle: WalletHandle, cred_id: &str) -> VcxResult<()> {
+    trace!("libindy_prover_delete_credential_async >>> cred_id: {}", cred_id);
+    if settings::indy_mocks_enabled() { return Ok(()); }
+
+    anoncreds::prover_delete_credential(wallet_handle,
                                         cred_id)
-        .wait()
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
-pub fn libindy_prover_create_master_secret(master_secret_id: &str) -> VcxResult<String> {
+pub fn libindy_prover_get_credential(wallet_handle: WalletHandle, cred_id: &str) -> VcxResult<String> {
+    block_on(libindy_prover_get_credential_async(wallet_handle, cred_id))
+}
+
+pub async fn libindy_prover_get_credential_async(wallet_handle: WalletHandle, cred_id: &str) -> VcxResult<String> {
+    trace!("libindy_prover_get_credential_async >>> cred_id: {}", cred_id);
+    if settings::indy_mocks_enabled() { return Ok(STORED_CREDENTIAL_INFO.to_string()); }
+
+    anoncreds::prover_get_credential(wallet_handle, cred_id)
+        .compat()
+        .await
+        .map_err(VcxError::from)
+}
+
+/**
+Searches credentials stored in the wallet matching WQL query, returns JSON array of credential infos.
+ */
+pub fn libindy_prover_search_credentials(wallet_handle: WalletHandle, query_json: Option<&str>) -> VcxResult<String> {
+    trace!("libindy_prover_search_credentials >>> query_json: {:?}", query_json);
+    if settings::indy_mocks_enabled() { return Ok(format!("[{}]", STORED_CREDENTIAL_INFO)); }
+
+    let (search_handle, total_count) = anoncreds::prover_search_credentials(wallet_handle, query_json.unwrap_or("{}"))
+        .wait()?;
+
+    let credentials = fetch_all_credentials(search_handle, total_count);
+    if let Err(err) = anoncreds::prover_close_credentials_search(search_handle).wait() {
+        warn!("libindy_prover_search_credentials >>> cannot close search handle: {:?}", err);
+    }
+    Ok(json!(credentials?).to_string())
+}
+
+fn fetch_all_credentials(search_handle: i32, total_count: usize) -> VcxResult<Vec<Value>> {
+    let mut credentials: Vec<Value> = Vec::new();
+    while credentials.len() < total_count {
+        let fetched: Vec<Value> = serde_json::from_str(&anoncreds::prover_fetch_credentials(search_handle, 100).wait()?)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize fetched credentials: {}", err)))?;
+        if fetched.is_empty() {
+            break;
+        }
+        credentials.extend(fetched);
+    }
+    Ok(credentials)
+}
+
+pub fn libindy_prover_create_master_secret(wallet_handle: WalletHandle, master_secret_id: &str) -> VcxResult<String> {
     if settings::indy_mocks_enabled() { return Ok(settings::DEFAULT_LINK_SECRET_ALIAS.to_string()); }
 
-    anoncreds::prover_create_master_secret(get_wallet_handle(),
+    anoncreds::prover_create_master_secret(wallet_handle,
                                            Some(master_secret_id))
         .wait()
         .map_err(VcxError::from)
@@ -290,17 +431,27 @@ pub fn libindy_issuer_create_schema(issuer_did: &str,
         .map_err(VcxError::from)
 }
 
-pub fn libindy_issuer_revoke_credential(tails_file: &str, rev_reg_id: &str, cred_rev_id: &str) -> VcxResult<String> {
-    let blob_handle = blob_storage_open_reader(tails_file)?;
+pub fn libindy_issuer_revoke_credential(wallet_handle: WalletHandle, tails_file: &str, rev_reg_id: &str, cred_rev_id: &str) -> VcxResult<String> {
+    block_on(libindy_issuer_revoke_credential_async(wallet_handle, tails_file, rev_reg_id, cred_rev_id))
+}
 
-    anoncreds::issuer_revoke_credential(get_wallet_handle(), blob_handle, rev_reg_id, cred_rev_id)
-        .wait()
+pub async fn libindy_issuer_revoke_credential_async(wallet_handle: WalletHandle, tails_file: &str, rev_reg_id: &str, cred_rev_id: &str) -> VcxResult<String> {
+    let blob_handle = blob_storage_open_reader_async(tails_file).await?;
+
+    anoncreds::issuer_revoke_credential(wallet_handle, blob_handle, rev_reg_id, cred_rev_id)
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
 pub fn libindy_issuer_merge_revocation_registry_deltas(old_delta: &str, new_delta: &str) -> VcxResult<String> {
+    block_on(libindy_issuer_merge_revocation_registry_deltas_async(old_delta, new_delta))
+}
+
+pub async fn libindy_issuer_merge_revocation_registry_deltas_async(old_delta: &str, new_delta: &str) -> VcxResult<String> {
     anoncreds::issuer_merge_revocation_registry_deltas(old_delta, new_delta)
-        .wait()
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
@@ -325,14 +476,24 @@ pub fn libindy_build_revoc_reg_entry_request(submitter_did: &str,
 }
 
 pub fn libindy_build_get_revoc_reg_def_request(submitter_did: &str, rev_reg_id: &str) -> VcxResult<String> {
+    block_on(libindy_build_get_revoc_reg_def_request_async(submitter_did, rev_reg_id))
+}
+
+pub async fn libindy_build_get_revoc_reg_def_request_async(submitter_did: &str, rev_reg_id: &str) -> VcxResult<String> {
     ledger::build_get_revoc_reg_def_request(Some(submitter_did), rev_reg_id)
-        .wait()
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
 pub fn libindy_parse_get_revoc_reg_def_response(rev_reg_def_json: &str) -> VcxResult<(String, String)> {
+    block_on(libindy_parse_get_revoc_reg_def_response_async(rev_reg_def_json))
+}
+
+pub async fn libindy_parse_get_revoc_reg_def_response_async(rev_reg_def_json: &str) -> VcxResult<(String, String)> {
     ledger::parse_get_revoc_reg_def_response(rev_reg_def_json)
-        .wait()
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
@@ -340,25 +501,35 @@ pub fn libindy_build_get_revoc_reg_delta_request(submitter_did: &str,
                                                  rev_reg_id: &str,
                                                  from: i64,
                                                  to: i64) -> VcxResult<String> {
+    block_on(libindy_build_get_revoc_reg_delta_request_async(submitter_did, rev_reg_id, from, to))
+}
+
+pub async fn libindy_build_get_revoc_reg_delta_request_async(submitter_did: &str,
+                                                             rev_reg_id: &str,
+                                                             from: i64,
+                                                             to: i64) -> VcxResult<String> {
     ledger::build_get_revoc_reg_delta_request(Some(submitter_did),
                                               rev_reg_id,
                                               from,
                                               to)
-        .wait()
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
-fn libindy_build_get_revoc_reg_request(submitter_did: &str, rev_reg_id: &str, timestamp: u64) -> VcxResult<String> {
+async fn libindy_build_get_revoc_reg_request_async(submitter_did: &str, rev_reg_id: &str, timestamp: u64) -> VcxResult<String> {
     ledger::build_get_revoc_reg_request(Some(submitter_did),
                                         rev_reg_id,
                                         timestamp as i64)
-        .wait()
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
-fn libindy_parse_get_revoc_reg_response(get_cred_def_resp: &str) -> VcxResult<(String, String, u64)> {
+async fn libindy_parse_get_revoc_reg_response_async(get_cred_def_resp: &str) -> VcxResult<(String, String, u64)> {
     ledger::parse_get_revoc_reg_response(get_cred_def_resp)
-        .wait()
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
@@ -370,33 +541,39 @@ fn libindy_parse_get_cred_def_response(get_rev_reg_resp: &str) -> VcxResult<(Str
 
 pub fn libindy_parse_get_revoc_reg_delta_response(get_rev_reg_delta_response: &str)
                                                   -> VcxResult<(String, String, u64)> {
+    block_on(libindy_parse_get_revoc_reg_delta_response_async(get_rev_reg_delta_response))
+}
+
+pub async fn libindy_parse_get_revoc_reg_delta_response_async(get_rev_reg_delta_response: &str)
+                                                              -> VcxResult<(String, String, u64)> {
     ledger::parse_get_revoc_reg_delta_response(get_rev_reg_delta_response)
-        .wait()
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
-pub fn create_schema(name: &str, version: &str, data: &str) -> VcxResult<(String, String)> {
-    trace!("create_schema >>> name: {}, version: {}, data: {}", name, version, data);
+pub fn create_schema(profile: &ProfileContext, name: &str, version: &str, data: &str) -> VcxResult<(String, String)> {
+    trace!("create_schema >>> profile: {:?}, name: {}, version: {}, data: {}", profile, name, version, data);
 
     if settings::indy_mocks_enabled() {
         return Ok((SCHEMA_ID.to_string(), SCHEMA_JSON.to_string()));
     }
 
-    let submitter_did = settings::get_config_value(settings::CONFIG_INSTITUTION_DID)?;
+    let submitter_did = profile.institution_did()?;
 
     let (id, create_schema) = libindy_issuer_create_schema(&submitter_did, name, version, data)?;
 
     Ok((id, create_schema))
 }
 
-pub fn build_schema_request(schema: &str) -> VcxResult<String> {
-    trace!("build_schema_request >>> schema: {}", schema);
+pub fn build_schema_request(profile: &ProfileContext, schema: &str) -> VcxResult<String> {
+    trace!("build_schema_request >>> profile: {:?}, schema: {}", profile, schema);
 
     if settings::indy_mocks_enabled() {
         return Ok(SCHEMA_TXN.to_string());
     }
 
-    let submitter_did = settings::get_config_value(settings::CONFIG_INSTITUTION_DID).unwrap();
+    let submitter_did = profile.institution_did()?;
 
     let request = libindy_build_schema_request(&submitter_did, schema)?;
 
@@ -405,8 +582,8 @@ pub fn build_schema_request(schema: &str) -> VcxResult<String> {
     Ok(request)
 }
 
-pub fn publish_schema(schema: &str) -> VcxResult<Option<PaymentTxn>> {
-    trace!("publish_schema >>> schema: {}", schema);
+pub fn publish_schema(profile: &ProfileContext, schema: &str) -> VcxResult<Option<PaymentTxn>> {
+    trace!("publish_schema >>> profile: {:?}, schema: {}", profile, schema);
 
     if settings::indy_mocks_enabled() {
         let inputs = vec!["pay:null:9UFgyjuJxi1i1HD".to_string()];
@@ -414,26 +591,31 @@ pub fn publish_schema(schema: &str) -> VcxResult<Option<PaymentTxn>> {
         return Ok(Some(PaymentTxn::from_parts(inputs, outputs, 1, false)));
     }
 
-    let request = build_schema_request(schema)?;
+    let request = build_schema_request(profile, schema)?;
 
-    let (payment, response) = pay_for_txn(&request, CREATE_SCHEMA_ACTION)?;
+    let (payment, response) = pay_for_txn(profile, &request, CREATE_SCHEMA_ACTION)?;
 
     _check_schema_response(&response)?;
 
     Ok(payment)
 }
 
-pub fn get_schema_json(schema_id: &str) -> VcxResult<(String, String)> {
+pub fn get_schema_json(profile: &ProfileContext, schema_id: &str) -> VcxResult<(String, String)> {
+    block_on(get_schema_json_async(profile, schema_id))
+}
+
+pub async fn get_schema_json_async(profile: &ProfileContext, schema_id: &str) -> VcxResult<(String, String)> {
     if settings::indy_mocks_enabled() { return Ok((SCHEMA_ID.to_string(), SCHEMA_JSON.to_string())); }
 
     let submitter_did = crate::utils::random::generate_random_did();
 
-    let schema_json = libindy_get_schema(&submitter_did, schema_id)?;
+    let schema_json = libindy_get_schema_async(profile, &submitter_did, schema_id).await?;
 
     Ok((schema_id.to_string(), schema_json))
 }
 
-pub fn generate_cred_def(issuer_did: &str,
+pub fn generate_cred_def(wallet_handle: WalletHandle,
+                         issuer_did: &str,
                          schema_json: &str,
                          tag: &str,
                          sig_type: Option<&str>,
@@ -444,7 +626,8 @@ pub fn generate_cred_def(issuer_did: &str,
 
     let config_json = json!({"support_revocation": support_revocation.unwrap_or(false)}).to_string();
 
-    libindy_create_and_store_credential_def(issuer_did,
+    libindy_create_and_store_credential_def(wallet_handle,
+                                            issuer_did,
                                             schema_json,
                                             tag,
                                             sig_type,
@@ -463,8 +646,8 @@ pub fn build_cred_def_request(issuer_did: &str, cred_def_json: &str) -> VcxResul
     Ok(cred_def_req)
 }
 
-pub fn publish_cred_def(issuer_did: &str, cred_def_json: &str) -> VcxResult<Option<PaymentTxn>> {
-    trace!("publish_cred_def >>> issuer_did: {}, cred_def_json: {}", issuer_did, cred_def_json);
+pub fn publish_cred_def(profile: &ProfileContext, issuer_did: &str, cred_def_json: &str) -> VcxResult<Option<PaymentTxn>> {
+    trace!("publish_cred_def >>> profile: {:?}, issuer_did: {}, cred_def_json: {}", profile, issuer_did, cred_def_json);
     if settings::indy_mocks_enabled() {
         let inputs = vec!["pay:null:9UFgyjuJxi1i1HD".to_string()];
         let outputs = serde_json::from_str::<Vec<libindy::utils::payments::Output>>(r#"[{"amount":4,"extra":null,"recipient":"pay:null:xkIsxem0YNtHrRO"}]"#).unwrap();
@@ -473,25 +656,31 @@ pub fn publish_cred_def(issuer_did: &str, cred_def_json: &str) -> VcxResult<Opti
 
     let cred_def_req = build_cred_def_request(issuer_did, &cred_def_json)?;
 
-    let (payment, _) = pay_for_txn(&cred_def_req, CREATE_CRED_DEF_ACTION)?;
+    let (payment, _) = pay_for_txn(profile, &cred_def_req, CREATE_CRED_DEF_ACTION)?;
 
     Ok(payment)
 }
 
-pub fn get_cred_def_json(cred_def_id: &str) -> VcxResult<(String, String)> {
+pub fn get_cred_def_json(profile: &ProfileContext, cred_def_id: &str) -> VcxResult<(String, String)> {
+    block_on(get_cred_def_json_async(profile, cred_def_id))
+}
+
+pub async fn get_cred_def_json_async(profile: &ProfileContext, cred_def_id: &str) -> VcxResult<(String, String)> {
     if settings::indy_mocks_enabled() { return Ok((CRED_DEF_ID.to_string(), CRED_DEF_JSON.to_string())); }
 
-    let cred_def_json = libindy_get_cred_def(cred_def_id)?;
+    let cred_def_json = libindy_get_cred_def_async(profile, cred_def_id).await?;
 
     Ok((cred_def_id.to_string(), cred_def_json))
 }
 
-pub fn generate_rev_reg(issuer_did: &str, cred_def_id: &str, tails_file: &str, max_creds: u32, tag: &str)
+pub fn generate_rev_reg(wallet_handle: WalletHandle,
+                        issuer_did: &str, cred_def_id: &str, tails_file: &str, max_creds: u32, tag: &str)
                         -> VcxResult<(String, String, String)> {
     if settings::indy_mocks_enabled() { return Ok((REV_REG_ID.to_string(), rev_def_json(), "".to_string())); }
 
     let (rev_reg_id, rev_reg_def_json, rev_reg_entry_json) =
-        libindy_create_and_store_revoc_reg(issuer_did,
+        libindy_create_and_store_revoc_reg(wallet_handle,
+                                           issuer_did,
                                            cred_def_id,
                                            tails_file,
                                            max_creds,
@@ -508,25 +697,30 @@ pub fn build_rev_reg_request(issuer_did: &str, rev_reg_def_json: &str) -> VcxRes
     Ok(rev_reg_def_req)
 }
 
-pub fn publish_rev_reg_def(issuer_did: &str, rev_reg_def_json: &str) -> VcxResult<Option<PaymentTxn>> {
-    trace!("publish_rev_reg_def >>> issuer_did: {}, rev_reg_def_json: ...", issuer_did);
+pub fn publish_rev_reg_def(profile: &ProfileContext, issuer_did: &str, rev_reg_def_json: &str) -> VcxResult<Option<PaymentTxn>> {
+    trace!("publish_rev_reg_def >>> profile: {:?}, issuer_did: {}, rev_reg_def_json: ...", profile, issuer_did);
     if settings::indy_mocks_enabled() { return Ok(None); }
 
     let rev_reg_def_req = build_rev_reg_request(issuer_did, &rev_reg_def_json)?;
-    let (payment, _) = pay_for_txn(&rev_reg_def_req, CREATE_REV_REG_DEF_ACTION)?;
+    let (payment, _) = pay_for_txn(profile, &rev_reg_def_req, CREATE_REV_REG_DEF_ACTION)?;
     Ok(payment)
 }
 
-pub fn get_rev_reg_def_json(rev_reg_id: &str) -> VcxResult<(String, String)> {
+pub fn get_rev_reg_def_json(profile: &ProfileContext, rev_reg_id: &str) -> VcxResult<(String, String)> {
+    block_on(get_rev_reg_def_json_async(profile, rev_reg_id))
+}
+
+pub async fn get_rev_reg_def_json_async(profile: &ProfileContext, rev_reg_id: &str) -> VcxResult<(String, String)> {
     if settings::indy_mocks_enabled() { return Ok((REV_REG_ID.to_string(), rev_def_json())); }
 
     let submitter_did = crate::utils::random::generate_random_did();
 
-    libindy_build_get_revoc_reg_def_request(&submitter_did, rev_reg_id)
-        .and_then(|req| libindy_submit_request(&req))
-        .and_then(|response| libindy_parse_get_revoc_reg_def_response(&response))
+    let request = libindy_build_get_revoc_reg_def_request_async(&submitter_did, rev_reg_id).await?;
+    let response = libindy_submit_request_async(profile, &request).await?;
+    libindy_parse_get_revoc_reg_def_response_async(&response).await
 }
 
+
 pub fn build_rev_reg_delta_request(issuer_did: &str, rev_reg_id: &str, rev_reg_entry_json: &str)
                                    -> VcxResult<String> {
     trace!("build_rev_reg_delta_request >>> issuer_did: {}, rev_reg_id: {}, rev_reg_entry_json: {}", issuer_did, rev_reg_id, rev_reg_entry_json);
@@ -535,16 +729,21 @@ pub fn build_rev_reg_delta_request(issuer_did: &str, rev_reg_id: &str, rev_reg_e
     Ok(request)
 }
 
-pub fn publish_rev_reg_delta(issuer_did: &str, rev_reg_id: &str, rev_reg_entry_json: &str)
+pub fn publish_rev_reg_delta(profile: &ProfileContext, issuer_did: &str, rev_reg_id: &str, rev_reg_entry_json: &str)
                              -> VcxResult<(Option<PaymentTxn>, String)> {
-    trace!("publish_rev_reg_delta >>> issuer_did: {}, rev_reg_id: {}, rev_reg_entry_json: {}", issuer_did, rev_reg_id, rev_reg_entry_json);
+    trace!("publish_rev_reg_delta >>> profile: {:?}, issuer_did: {}, rev_reg_id: {}, rev_reg_entry_json: {}", profile, issuer_did, rev_reg_id, rev_reg_entry_json);
     let request = build_rev_reg_delta_request(issuer_did, rev_reg_id, rev_reg_entry_json)?;
-    pay_for_txn(&request, CREATE_REV_REG_DELTA_ACTION)
+    pay_for_txn(profile, &request, CREATE_REV_REG_DELTA_ACTION)
 }
 
-pub fn get_rev_reg_delta_json(rev_reg_id: &str, from: Option<u64>, to: Option<u64>)
+pub fn get_rev_reg_delta_json(profile: &ProfileContext, rev_reg_id: &str, from: Option<u64>, to: Option<u64>)
                               -> VcxResult<(String, String, u64)> {
-    trace!("get_rev_reg_delta_json >>> rev_reg_id: {}, from: {:?}, to: {:?}", rev_reg_id, from, to);
+    block_on(get_rev_reg_delta_json_async(profile, rev_reg_id, from, to))
+}
+
+pub async fn get_rev_reg_delta_json_async(profile: &ProfileContext, rev_reg_id: &str, from: Option<u64>, to: Option<u64>)
+                                          -> VcxResult<(String, String, u64)> {
+    trace!("get_rev_reg_delta_json_async >>> rev_reg_id: {}, from: {:?}, to: {:?}", rev_reg_id, from, to);
     if settings::indy_mocks_enabled() { return Ok((REV_REG_ID.to_string(), REV_REG_DELTA_JSON.to_string(), 1)); }
 
     let submitter_did = crate::utils::random::generate_random_did();
@@ -552,67 +751,77 @@ pub fn get_rev_reg_delta_json(rev_reg_id: &str, from: Option<u64>, to: Option<u6
     let from: i64 = if let Some(_from) = from { _from as i64 } else { -1 };
     let to = if let Some(_to) = to { _to as i64 } else { time::get_time().sec };
 
-    libindy_build_get_revoc_reg_delta_request(&submitter_did, rev_reg_id, from, to)
-        .and_then(|req| libindy_submit_request(&req))
-        .and_then(|response| libindy_parse_get_revoc_reg_delta_response(&response))
+    let request = libindy_build_get_revoc_reg_delta_request_async(&submitter_did, rev_reg_id, from, to).await?;
+    let response = libindy_submit_request_async(profile, &request).await?;
+    libindy_parse_get_revoc_reg_delta_response_async(&response).await
 }
 
-pub fn get_rev_reg(rev_reg_id: &str, timestamp: u64) -> VcxResult<(String, String, u64)> {
+
+pub fn get_rev_reg(profile: &ProfileContext, rev_reg_id: &str, timestamp: u64) -> VcxResult<(String, String, u64)> {
+    block_on(get_rev_reg_async(profile, rev_reg_id, timestamp))
+}
+
+pub async fn get_rev_reg_async(profile: &ProfileContext, rev_reg_id: &str, timestamp: u64) -> VcxResult<(String, String, u64)> {
     if settings::indy_mocks_enabled() { return Ok((REV_REG_ID.to_string(), REV_REG_JSON.to_string(), 1)); }
 
     let submitter_did = crate::utils::random::generate_random_did();
 
-    libindy_build_get_revoc_reg_request(&submitter_did, rev_reg_id, timestamp)
-        .and_then(|req| libindy_submit_request(&req))
-        .and_then(|response| libindy_parse_get_revoc_reg_response(&response))
+    let request = libindy_build_get_revoc_reg_request_async(&submitter_did, rev_reg_id, timestamp).await?;
+    let response = libindy_submit_request_async(profile, &request).await?;
+    libindy_parse_get_revoc_reg_response_async(&response).await
 }
 
-pub fn get_cred_def(issuer_did: Option<&str>, cred_def_id: &str) -> VcxResult<(String, String)> {
+
+pub fn get_cred_def(profile: &ProfileContext, issuer_did: Option<&str>, cred_def_id: &str) -> VcxResult<(String, String)> {
     if settings::indy_mocks_enabled() { return Err(VcxError::from(VcxErrorKind::LibndyError(309))); }
     libindy_build_get_cred_def_request(issuer_did, &cred_def_id)
-        .and_then(|req| libindy_submit_request(&req))
+        .and_then(|req| libindy_submit_request(profile, &req))
         .and_then(|response| libindy_parse_get_cred_def_response(&response))
 }
 
-pub fn is_cred_def_on_ledger(issuer_did: Option<&str>, cred_def_id: &str) -> VcxResult<bool> {
-    match get_cred_def(issuer_did, cred_def_id) {
+pub fn is_cred_def_on_ledger(profile: &ProfileContext, issuer_did: Option<&str>, cred_def_id: &str) -> VcxResult<bool> {
+    match get_cred_def(profile, issuer_did, cred_def_id) {
         Ok(_) => Ok(true),
         Err(err) if err.kind() == VcxErrorKind::LibndyError(309) => Ok(false),
         Err(err) => Err(VcxError::from_msg(VcxErrorKind::InvalidLedgerResponse, format!("Failed to check presence of credential definition id {} on the ledger\nError: {}", cred_def_id, err)))
     }
 }
 
-pub fn revoke_credential(tails_file: &str, rev_reg_id: &str, cred_rev_id: &str) -> VcxResult<(Option<PaymentTxn>, String)> {
+pub fn revoke_credential(profile: &ProfileContext, tails_file: &str, rev_reg_id: &str, cred_rev_id: &str) -> VcxResult<(Option<PaymentTxn>, String)> {
     if settings::indy_mocks_enabled() {
         let inputs = vec!["pay:null:9UFgyjuJxi1i1HD".to_string()];
         let outputs = serde_json::from_str::<Vec<libindy::utils::payments::Output>>(r#"[{"amount":4,"extra":null,"recipient":"pay:null:xkIsxem0YNtHrRO"}]"#).unwrap();
         return Ok((Some(PaymentTxn::from_parts(inputs, outputs, 1, false)), REV_REG_DELTA_JSON.to_string()));
     }
 
-    let submitter_did = settings::get_config_value(settings::CONFIG_INSTITUTION_DID).unwrap();
+    let submitter_did = profile.institution_did()?;
 
-    let delta = libindy_issuer_revoke_credential(tails_file, rev_reg_id, cred_rev_id)?;
-    let (payment, _) = publish_rev_reg_delta(&submitter_did, rev_reg_id, &delta)?;
+    let delta = libindy_issuer_revoke_credential(profile.wallet_handle()?, tails_file, rev_reg_id, cred_rev_id)?;
+    let (payment, _) = publish_rev_reg_delta(profile, &submitter_did, rev_reg_id, &delta)?;
 
     Ok((payment, delta))
 }
 
-pub fn revoke_credential_local(tails_file: &str, rev_reg_id: &str, cred_rev_id: &str) -> VcxResult<()> {
-    let mut new_delta = libindy_issuer_revoke_credential(tails_file, rev_reg_id, cred_rev_id)?;
-    if let Some(old_delta) = get_rev_reg_delta_cache(rev_reg_id) {
-        new_delta = libindy_issuer_merge_revocation_registry_deltas(old_delta.as_str(), new_delta.as_str())?;
+pub fn revoke_credential_local(profile: &ProfileContext, tails_file: &str, rev_reg_id: &str, cred_rev_id: &str) -> VcxResult<()> {
+    block_on(revoke_credential_local_async(profile, tails_file, rev_reg_id, cred_rev_id))
+}
+
+pub async fn revoke_credential_local_async(profile: &ProfileContext, tails_file: &str, rev_reg_id: &str, cred_rev_id: &str) -> VcxResult<()> {
+    let mut new_delta = libindy_issuer_revoke_credential_async(profile.wallet_handle()?, tails_file, rev_reg_id, cred_rev_id).await?;
+    if let Some(old_delta) = get_rev_reg_delta_cache_async(profile.wallet_handle()?, rev_reg_id).await {
+        new_delta = libindy_issuer_merge_revocation_registry_deltas_async(old_delta.as_str(), new_delta.as_str()).await?;
     }
-    set_rev_reg_delta_cache(rev_reg_id, &new_delta)
+    set_rev_reg_delta_cache_async(profile.wallet_handle()?, rev_reg_id, &new_delta).await
 }
 
-pub fn publish_local_revocations(rev_reg_id: &str)
+
+pub fn publish_local_revocations(profile: &ProfileContext, rev_reg_id: &str)
                                  -> VcxResult<(Option<PaymentTxn>, String)> {
-    let submitter_did = settings::get_config_value(settings::CONFIG_INSTITUTION_DID).unwrap();
-    if let Some(delta) = get_rev_reg_delta_cache(rev_reg_id) {
-        match clear_rev_reg_delta_cache(rev_reg_id) {
-            Ok(_) => Ok(publish_rev_reg_delta(&submitter_did, rev_reg_id, &delta)?),
-            Err(err) => Err(err)
-        }
+    let submitter_did = profile.institution_did()?;
+    if let Some(delta) = get_rev_reg_delta_cache(profile.wallet_handle()?, rev_reg_id) {
+        let published = publish_rev_reg_delta(profile, &submitter_did, rev_reg_id, &delta)?;
+        clear_rev_reg_delta_cache(profile.wallet_handle()?, rev_reg_id)?;
+        Ok(published)
     } else {
         Err(VcxError::from(VcxErrorKind::RevDeltaNotFound))
     }
@@ -651,6 +860,7 @@ pub mod tests {
     #[cfg(feature = "pool_tests")]
     use crate::utils::constants::{TEST_TAILS_FILE, TEST_TAILS_URL};
     use crate::utils::devsetup::*;
+    use crate::libindy::utils::wallet::get_wallet_handle;
     use crate::utils::get_temp_dir_path;
 
     use super::*;
@@ -675,7 +885,7 @@ pub mod tests {
     pub fn create_and_write_test_schema(attr_list: &str) -> (String, String) {
         let (schema_id, schema_json) = create_schema(attr_list);
         let req = create_schema_req(&schema_json);
-        libindy::utils::payments::pay_for_txn(&req, CREATE_SCHEMA_ACTION).unwrap();
+        libindy::utils::payments::pay_for_txn(&ProfileContext::Global, &req, CREATE_SCHEMA_ACTION).unwrap();
         thread::sleep(Duration::from_millis(1000));
         (schema_id, schema_json)
     }
@@ -704,7 +914,7 @@ pub mod tests {
         thread::sleep(Duration::from_millis(1000));
         let cred_def_id = credential_def::get_cred_def_id(handle).unwrap();
         thread::sleep(Duration::from_millis(1000));
-        let (_, cred_def_json) = get_cred_def_json(&cred_def_id).unwrap();
+        let (_, cred_def_json) = get_cred_def_json(&ProfileContext::Global, &cred_def_id).unwrap();
         let rev_reg_id = credential_def::get_rev_reg_id(handle).ok();
         (schema_id, schema_json, cred_def_id, cred_def_json, handle, rev_reg_id)
     }
@@ -712,14 +922,14 @@ pub mod tests {
     pub fn create_credential_offer(attr_list: &str, revocation: bool) -> (String, String, String, String, String, Option<String>) {
         let (schema_id, schema_json, cred_def_id, cred_def_json, _, rev_reg_id) = create_and_store_credential_def(attr_list, revocation);
 
-        let offer = libindy::utils::anoncreds::libindy_issuer_create_credential_offer(&cred_def_id).unwrap();
+        let offer = libindy::utils::anoncreds::libindy_issuer_create_credential_offer(get_wallet_handle(), &cred_def_id).unwrap();
         (schema_id, schema_json, cred_def_id, cred_def_json, offer, rev_reg_id)
     }
 
     pub fn create_credential_req(attr_list: &str, revocation: bool) -> (String, String, String, String, String, String, String, Option<String>) {
         let (schema_id, schema_json, cred_def_id, cred_def_json, offer, rev_reg_id) = create_credential_offer(attr_list, revocation);
         let institution_did = settings::get_config_value(settings::CONFIG_INSTITUTION_DID).unwrap();
-        let (req, req_meta) = libindy::utils::anoncreds::libindy_prover_create_credential_req(&institution_did, &offer, &cred_def_json).unwrap();
+        let (req, req_meta) = libindy::utils::anoncreds::libindy_prover_create_credential_req(get_wallet_handle(), &institution_did, &offer, &cred_def_json).unwrap();
         (schema_id, schema_json, cred_def_id, cred_def_json, offer, req, req_meta, rev_reg_id)
     }
 
@@ -730,13 +940,13 @@ pub mod tests {
         let credential_data = r#"{"address1": ["123 Main St"], "address2": ["Suite 3"], "city": ["Draper"], "state": ["UT"], "zip": ["84000"]}"#;
         let encoded_attributes = encode_attributes(&credential_data).unwrap();
         let (rev_def_json, tails_file) = if revocation {
-            let (_id, json) = get_rev_reg_def_json(&rev_reg_id.clone().unwrap()).unwrap();
+            let (_id, json) = get_rev_reg_def_json(&ProfileContext::Global, &rev_reg_id.clone().unwrap()).unwrap();
             (Some(json), Some(get_temp_dir_path(TEST_TAILS_FILE).to_str().unwrap().to_string().to_string()))
         } else { (None, None) };
 
-        let (cred, cred_rev_id, _) = libindy::utils::anoncreds::libindy_issuer_create_credential(&offer, &req, &encoded_attributes, rev_reg_id.clone(), tails_file).unwrap();
+        let (cred, cred_rev_id, _) = libindy::utils::anoncreds::libindy_issuer_create_credential(get_wallet_handle(), &offer, &req, &encoded_attributes, rev_reg_id.clone(), tails_file).unwrap();
         /* store cred */
-        let cred_id = libindy::utils::anoncreds::libindy_prover_store_credential(None, &req_meta, &cred, &cred_def_json, rev_def_json.as_ref().map(String::as_str)).unwrap();
+        let cred_id = libindy::utils::anoncreds::libindy_prover_store_credential(get_wallet_handle(), None, &req_meta, &cred, &cred_def_json, rev_def_json.as_ref().map(String::as_str)).unwrap();
         (schema_id, schema_json, cred_def_id, cred_def_json, offer, req, req_meta, cred_id, rev_reg_id, cred_rev_id)
     }
 
@@ -784,9 +994,10 @@ pub mod tests {
             cred_def_id: cred_def_json,
         }).to_string();
 
-        libindy_prover_get_credentials_for_proof_req(&proof_req).unwrap();
+        libindy_prover_get_credentials_for_proof_req(get_wallet_handle(), &proof_req).unwrap();
 
         let proof = libindy_prover_create_proof(
+            get_wallet_handle(),
             &proof_req,
             &requested_credentials_json,
             "main",
@@ -855,9 +1066,10 @@ pub mod tests {
             cred_def_id: cred_def_json,
         }).to_string();
 
-        libindy_prover_get_credentials_for_proof_req(&proof_req).unwrap();
+        libindy_prover_get_credentials_for_proof_req(get_wallet_handle(), &proof_req).unwrap();
 
         let proof = libindy_prover_create_proof(
+            get_wallet_handle(),
             &proof_req,
             &requested_credentials_json,
             "main",
@@ -928,7 +1140,7 @@ pub mod tests {
         let _setup = SetupLibraryWallet::init();
 
         let proof_req = "{";
-        let result = libindy_prover_get_credentials_for_proof_req(&proof_req);
+        let result = libindy_prover_get_credentials_for_proof_req(get_wallet_handle(), &proof_req);
         assert_eq!(result.unwrap_err().kind(), VcxErrorKind::InvalidProofRequest);
 
         let proof_req = json!({
@@ -945,9 +1157,9 @@ pub mod tests {
            }),
            "requested_predicates": json!({}),
         }).to_string();
-        let _result = libindy_prover_get_credentials_for_proof_req(&proof_req).unwrap();
+        let _result = libindy_prover_get_credentials_for_proof_req(get_wallet_handle(), &proof_req).unwrap();
 
-        let result_malformed_json = libindy_prover_get_credentials_for_proof_req("{}").unwrap_err();
+        let result_malformed_json = libindy_prover_get_credentials_for_proof_req(get_wallet_handle(), "{}").unwrap_err();
         assert_eq!(result_malformed_json.kind(), VcxErrorKind::InvalidAttributesStructure);
     }
 
@@ -956,12 +1168,12 @@ pub mod tests {
     fn test_issuer_revoke_credential() {
         let _setup = SetupLibraryWalletPoolZeroFees::init();
 
-        let rc = libindy_issuer_revoke_credential(get_temp_dir_path(TEST_TAILS_FILE).to_str().unwrap(), "", "");
+        let rc = libindy_issuer_revoke_credential(get_wallet_handle(), get_temp_dir_path(TEST_TAILS_FILE).to_str().unwrap(), "", "");
         assert!(rc.is_err());
 
         let (_, _, _, _, _, _, _, _, rev_reg_id, cred_rev_id)
             = create_and_store_credential(utils::constants::DEFAULT_SCHEMA_ATTRS, true);
-        let rc = libindy::utils::anoncreds::libindy_issuer_revoke_credential(get_temp_dir_path(TEST_TAILS_FILE).to_str().unwrap(), &rev_reg_id.unwrap(), &cred_rev_id.unwrap());
+        let rc = libindy::utils::anoncreds::libindy_issuer_revoke_credential(get_wallet_handle(), get_temp_dir_path(TEST_TAILS_FILE).to_str().unwrap(), &rev_reg_id.unwrap(), &cred_rev_id.unwrap());
 
         assert!(rc.is_ok());
     }
@@ -971,7 +1183,7 @@ pub mod tests {
     fn test_create_cred_def() {
         let _setup = SetupMocks::init();
 
-        let (id, _) = generate_cred_def("did", SCHEMAS_JSON, "tag_1", None, Some(false)).unwrap();
+        let (id, _) = generate_cred_def(get_wallet_handle(), "did", SCHEMAS_JSON, "tag_1", None, Some(false)).unwrap();
         assert_eq!(id, CRED_DEF_ID);
     }
 
@@ -981,11 +1193,11 @@ pub mod tests {
         let _setup = SetupLibraryWalletPoolZeroFees::init();
 
         let (schema_id, _) = libindy::utils::anoncreds::tests::create_and_write_test_schema(utils::constants::DEFAULT_SCHEMA_ATTRS);
-        let (_, schema_json) = get_schema_json(&schema_id).unwrap();
+        let (_, schema_json) = get_schema_json(&ProfileContext::Global, &schema_id).unwrap();
         let did = settings::get_config_value(settings::CONFIG_INSTITUTION_DID).unwrap();
 
-        let (_, cred_def_json) = generate_cred_def(&did, &schema_json, "tag_1", None, Some(true)).unwrap();
-        publish_cred_def(&did, &cred_def_json).unwrap();
+        let (_, cred_def_json) = generate_cred_def(get_wallet_handle(), &did, &schema_json, "tag_1", None, Some(true)).unwrap();
+        publish_cred_def(&ProfileContext::Global, &did, &cred_def_json).unwrap();
     }
 
     #[cfg(feature = "pool_tests")]
@@ -997,7 +1209,7 @@ pub mod tests {
         // revoc_reg_def will fail in libindy because cred_Def doesn't have revocation keys
         let (_, _, cred_def_id, _, _, _) = libindy::utils::anoncreds::tests::create_and_store_credential_def(utils::constants::DEFAULT_SCHEMA_ATTRS, false);
         let did = settings::get_config_value(settings::CONFIG_INSTITUTION_DID).unwrap();
-        let rc = generate_rev_reg(&did, &cred_def_id, get_temp_dir_path("path.txt").to_str().unwrap(), 2, "tag1");
+        let rc = generate_rev_reg(get_wallet_handle(), &did, &cred_def_id, get_temp_dir_path("path.txt").to_str().unwrap(), 2, "tag1");
 
         assert_eq!(rc.unwrap_err().kind(), VcxErrorKind::LibindyInvalidStructure);
     }
@@ -1008,14 +1220,14 @@ pub mod tests {
         let _setup = SetupLibraryWalletPoolZeroFees::init();
 
         let (schema_id, _) = libindy::utils::anoncreds::tests::create_and_write_test_schema(utils::constants::DEFAULT_SCHEMA_ATTRS);
-        let (_, schema_json) = get_schema_json(&schema_id).unwrap();
+        let (_, schema_json) = get_schema_json(&ProfileContext::Global, &schema_id).unwrap();
         let did = settings::get_config_value(settings::CONFIG_INSTITUTION_DID).unwrap();
 
-        let (cred_def_id, cred_def_json) = generate_cred_def(&did, &schema_json, "tag_1", None, Some(true)).unwrap();
-        publish_cred_def(&did, &cred_def_json).unwrap();
-        let (rev_reg_def_id, rev_reg_def_json, rev_reg_entry_json) = generate_rev_reg(&did, &cred_def_id, "tails.txt", 2, "tag1").unwrap();
-        publish_rev_reg_def(&did, &rev_reg_def_json).unwrap();
-        publish_rev_reg_delta(&did, &rev_reg_def_id, &rev_reg_entry_json).unwrap();
+        let (cred_def_id, cred_def_json) = generate_cred_def(get_wallet_handle(), &did, &schema_json, "tag_1", None, Some(true)).unwrap();
+        publish_cred_def(&ProfileContext::Global, &did, &cred_def_json).unwrap();
+        let (rev_reg_def_id, rev_reg_def_json, rev_reg_entry_json) = generate_rev_reg(get_wallet_handle(), &did, &cred_def_id, "tails.txt", 2, "tag1").unwrap();
+        publish_rev_reg_def(&ProfileContext::Global, &did, &rev_reg_def_json).unwrap();
+        publish_rev_reg_delta(&ProfileContext::Global, &did, &rev_reg_def_id, &rev_reg_entry_json).unwrap();
     }
 
     #[cfg(feature = "pool_tests")]
@@ -1028,7 +1240,7 @@ pub mod tests {
             libindy::utils::anoncreds::tests::create_and_store_credential_def(attrs, true);
 
         let rev_reg_id = rev_reg_id.unwrap();
-        let (id, _json) = get_rev_reg_def_json(&rev_reg_id).unwrap();
+        let (id, _json) = get_rev_reg_def_json(&ProfileContext::Global, &rev_reg_id).unwrap();
         assert_eq!(id, rev_reg_id);
     }
 
@@ -1042,7 +1254,7 @@ pub mod tests {
             libindy::utils::anoncreds::tests::create_and_store_credential_def(attrs, true);
         let rev_reg_id = rev_reg_id.unwrap();
 
-        let (id, _delta, _timestamp) = get_rev_reg_delta_json(&rev_reg_id, None, None).unwrap();
+        let (id, _delta, _timestamp) = get_rev_reg_delta_json(&ProfileContext::Global, &rev_reg_id, None, None).unwrap();
         assert_eq!(id, rev_reg_id);
     }
 
@@ -1056,7 +1268,7 @@ pub mod tests {
             libindy::utils::anoncreds::tests::create_and_store_credential_def(attrs, true);
         let rev_reg_id = rev_reg_id.unwrap();
 
-        let (id, _rev_reg, _timestamp) = get_rev_reg(&rev_reg_id, time::get_time().sec as u64).unwrap();
+        let (id, _rev_reg, _timestamp) = get_rev_reg(&ProfileContext::Global, &rev_reg_id, time::get_time().sec as u64).unwrap();
         assert_eq!(id, rev_reg_id);
     }
 
@@ -1069,7 +1281,7 @@ pub mod tests {
         let (_, _, cred_def_id, cred_def_json, _, _) =
             libindy::utils::anoncreds::tests::create_and_store_credential_def(attrs, true);
 
-        let (id, cred_def) = get_cred_def(None, &cred_def_id).unwrap();
+        let (id, cred_def) = get_cred_def(&ProfileContext::Global, None, &cred_def_id).unwrap();
         assert_eq!(id, cred_def_id);
         assert_eq!(serde_json::from_str::<serde_json::Value>(&cred_def).unwrap(), serde_json::from_str::<serde_json::Value>(&cred_def_json).unwrap());
     }
@@ -1079,7 +1291,7 @@ pub mod tests {
     fn test_is_cred_def_on_ledger() {
         let _setup = SetupLibraryWalletPoolZeroFees::init();
 
-        assert_eq!(is_cred_def_on_ledger(None, "V4SGRU86Z58d6TV7PBUe6f:3:CL:194:tag7").unwrap(), false);
+        assert_eq!(is_cred_def_on_ledger(&ProfileContext::Global, None, "V4SGRU86Z58d6TV7PBUe6f:3:CL:194:tag7").unwrap(), false);
     }
 
     #[cfg(feature = "pool_tests")]
@@ -1089,7 +1301,7 @@ pub mod tests {
 
         let (schema_id, _schema_json) = libindy::utils::anoncreds::tests::create_and_write_test_schema(utils::constants::DEFAULT_SCHEMA_ATTRS);
 
-        let rc = get_schema_json(&schema_id);
+        let rc = get_schema_json(&ProfileContext::Global, &schema_id);
 
         let (_id, retrieved_schema) = rc.unwrap();
         assert!(retrieved_schema.contains(&schema_id));
@@ -1100,7 +1312,7 @@ pub mod tests {
     fn from_ledger_schema_id() {
         let _setup = SetupMocks::init();
 
-        let (id, retrieved_schema) = get_schema_json(SCHEMA_ID).unwrap();
+        let (id, retrieved_schema) = get_schema_json(&ProfileContext::Global, SCHEMA_ID).unwrap();
         assert_eq!(&retrieved_schema, SCHEMA_JSON);
         assert_eq!(&id, SCHEMA_ID);
     }
@@ -1114,16 +1326,16 @@ pub mod tests {
             = libindy::utils::anoncreds::tests::create_and_store_credential(utils::constants::DEFAULT_SCHEMA_ATTRS, true);
 
         let rev_reg_id = rev_reg_id.unwrap();
-        let (_, first_rev_reg_delta, first_timestamp) = get_rev_reg_delta_json(&rev_reg_id, None, None).unwrap();
-        let (_, test_same_delta, test_same_timestamp) = get_rev_reg_delta_json(&rev_reg_id, None, None).unwrap();
+        let (_, first_rev_reg_delta, first_timestamp) = get_rev_reg_delta_json(&ProfileContext::Global, &rev_reg_id, None, None).unwrap();
+        let (_, test_same_delta, test_same_timestamp) = get_rev_reg_delta_json(&ProfileContext::Global, &rev_reg_id, None, None).unwrap();
 
         assert_eq!(first_rev_reg_delta, test_same_delta);
         assert_eq!(first_timestamp, test_same_timestamp);
 
-        let (payment, _revoked_rev_reg_delta) = revoke_credential(get_temp_dir_path(TEST_TAILS_FILE).to_str().unwrap(), &rev_reg_id, cred_rev_id.unwrap().as_str()).unwrap();
+        let (payment, _revoked_rev_reg_delta) = revoke_credential(&ProfileContext::Global, get_temp_dir_path(TEST_TAILS_FILE).to_str().unwrap(), &rev_reg_id, cred_rev_id.unwrap().as_str()).unwrap();
 
         // Delta should change after revocation
-        let (_, second_rev_reg_delta, _) = get_rev_reg_delta_json(&rev_reg_id, Some(first_timestamp + 1), None).unwrap();
+        let (_, second_rev_reg_delta, _) = get_rev_reg_delta_json(&ProfileContext::Global, &rev_reg_id, Some(first_timestamp + 1), None).unwrap();
 
         assert_ne!(first_rev_reg_delta, second_rev_reg_delta);
     }
@@ -1,11 +1,20 @@
+use futures::lock::Mutex;
+use indy::WalletHandle;
 use serde_json;
 
+use crate::aries::utils::task::block_on;
 use crate::error::{VcxError, VcxErrorKind, VcxResult};
-use crate::libindy::utils::wallet::{add_record, delete_record, get_record, update_record_value};
+use crate::libindy::utils::wallet::{add_record, add_record_async, delete_record, get_record, get_record_async, update_record_value, update_record_value_async};
 
 static CACHE_TYPE: &str = "cache";
 static REV_REG_DELTA_CACHE_PREFIX: &str = "rev_reg_delta:";
 static REV_REG_IDS_CACHE_PREFIX: &str = "rev_reg_ids:";
+static PENDING_REV_REG_IDS_CACHE_ID: &str = "pending_rev_reg_ids";
+static REV_REG_ISSUED_COUNT_CACHE_PREFIX: &str = "rev_reg_issued_count:";
+
+lazy_static! {
+    static ref PENDING_REV_REG_IDS_LOCK: Mutex<()> = Mutex::new(());
+}
 
 // TODO: Maybe we need to persist more info
 #[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
@@ -13,13 +22,32 @@ pub struct RevRegIdsCache {
     pub rev_reg_ids: Vec<String>
 }
 
-fn set_rev_reg_ids_cache(cred_def_id: &str, cache: &str) -> VcxResult<()> {
+/**
+Rev reg ids were stored as a plain list by earlier versions.
+ */
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredRevRegIds {
+    Cache(RevRegIdsCache),
+    Legacy(Vec<String>),
+}
+
+impl From<StoredRevRegIds> for RevRegIdsCache {
+    fn from(stored: StoredRevRegIds) -> RevRegIdsCache {
+        match stored {
+            StoredRevRegIds::Cache(cache) => cache,
+            StoredRevRegIds::Legacy(rev_reg_ids) => RevRegIdsCache { rev_reg_ids }
+        }
+    }
+}
+
+fn set_rev_reg_ids_cache(wallet_handle: WalletHandle, cred_def_id: &str, cache: &str) -> VcxResult<()> {
     debug!("Setting rev_reg_ids for cred_def_id {}, cache {}", cred_def_id, cache);
     match serde_json::to_string(cache) {
         Ok(json) => {
             let wallet_id = format!("{}{}", REV_REG_IDS_CACHE_PREFIX, cred_def_id);
-            match update_record_value(CACHE_TYPE, &wallet_id, &json)
-                .or(add_record(CACHE_TYPE, &wallet_id, &json, None)) {
+            match update_record_value(wallet_handle, CACHE_TYPE, &wallet_id, &json)
+                .or(add_record(wallet_handle, CACHE_TYPE, &wallet_id, &json, None)) {
                 Ok(_) => Ok(()),
                 Err(err) => Err(err)
             }
@@ -30,16 +58,22 @@ fn set_rev_reg_ids_cache(cred_def_id: &str, cache: &str) -> VcxResult<()> {
     }
 }
 
-fn get_rev_reg_ids_cache(cred_def_id: &str) -> Option<RevRegIdsCache> {
+fn _parse_rev_reg_ids_record(json: &str) -> serde_json::Result<RevRegIdsCache> {
+    serde_json::from_str(json)
+        .and_then(|x: serde_json::Value|
+            serde_json::from_str::<String>(x.get("value").unwrap_or(&serde_json::Value::Null).as_str().unwrap_or("")))
+        .and_then(|cache| serde_json::from_str::<StoredRevRegIds>(&cache))
+        .map(RevRegIdsCache::from)
+}
+
+fn get_rev_reg_ids_cache(wallet_handle: WalletHandle, cred_def_id: &str) -> Option<RevRegIdsCache> {
     debug!("Getting rev_reg_delta_cache for cred_def_id {}", cred_def_id);
     let wallet_id = format!("{}{}", REV_REG_IDS_CACHE_PREFIX, cred_def_id);
 
-    match get_record(CACHE_TYPE, &wallet_id, &json!({"retrieveType": false, "retrieveValue": true, "retrieveTags": false}).to_string()) {
+    match get_record(wallet_handle, CACHE_TYPE, &wallet_id, &json!({"retrieveType": false, "retrieveValue": true, "retrieveTags": false}).to_string()) {
         Ok(json) => {
-            match serde_json::from_str(&json)
-                .and_then(|x: serde_json::Value|
-                    serde_json::from_str(x.get("value").unwrap_or(&serde_json::Value::Null).as_str().unwrap_or(""))) {
-                Ok(cache) => cache,
+            match _parse_rev_reg_ids_record(&json) {
+                Ok(cache) => Some(cache),
                 Err(err) => {
                     warn!("Unable to convert rev_reg_ids cache for cred_def_id: {}, json: {}, error: {}", cred_def_id, json, err);
                     None
@@ -57,16 +91,21 @@ fn get_rev_reg_ids_cache(cred_def_id: &str) -> Option<RevRegIdsCache> {
 /// Returns the rev reg delta cache.
 ///
 /// # Arguments
+/// `wallet_handle`: wallet of the issuer
 /// `rev_reg_id`: revocation registry id
 ///
 /// # Returns
 /// Revocation registry delta json as a string
-pub fn get_rev_reg_delta_cache(rev_reg_id: &str) -> Option<String> {
+pub fn get_rev_reg_delta_cache(wallet_handle: WalletHandle, rev_reg_id: &str) -> Option<String> {
+    block_on(get_rev_reg_delta_cache_async(wallet_handle, rev_reg_id))
+}
+
+pub async fn get_rev_reg_delta_cache_async(wallet_handle: WalletHandle, rev_reg_id: &str) -> Option<String> {
     debug!("Getting rev_reg_delta_cache for rev_reg_id {}", rev_reg_id);
 
     let wallet_id = format!("{}{}", REV_REG_DELTA_CACHE_PREFIX, rev_reg_id);
 
-    match get_record(CACHE_TYPE, &wallet_id, &json!({"retrieveType": false, "retrieveValue": true, "retrieveTags": false}).to_string()) {
+    match get_record_async(wallet_handle, CACHE_TYPE, &wallet_id, &json!({"retrieveType": false, "retrieveValue": true, "retrieveTags": false}).to_string()).await {
         Ok(json) => {
             match serde_json::from_str(&json)
                 .and_then(|x: serde_json::Value|
@@ -85,42 +124,110 @@ pub fn get_rev_reg_delta_cache(rev_reg_id: &str) -> Option<String> {
     }
 }
 
-pub fn update_rev_reg_ids_cache(cred_def_id: &str, rev_reg_id: &str) -> VcxResult<()> {
+pub fn update_rev_reg_ids_cache(wallet_handle: WalletHandle, cred_def_id: &str, rev_reg_id: &str) -> VcxResult<()> {
     debug!("Setting rev_reg_ids cache for cred_def_id {}, rev_reg_id {}", cred_def_id, rev_reg_id);
-    match get_rev_reg_ids_cache(cred_def_id) {
+    match get_rev_reg_ids_cache(wallet_handle, cred_def_id) {
         Some(mut old_vec) => {
             old_vec.rev_reg_ids.push(String::from(rev_reg_id));
             match serde_json::to_string(&old_vec) {
-                Ok(ser_new_vec) => set_rev_reg_ids_cache(cred_def_id, ser_new_vec.as_str()),
+                Ok(ser_new_vec) => set_rev_reg_ids_cache(wallet_handle, cred_def_id, ser_new_vec.as_str()),
                 Err(_) => Err(VcxError::from(VcxErrorKind::SerializationError))
             }
         }
         None => {
-            match serde_json::to_string(&vec![rev_reg_id]) {
-                Ok(ser_new_vec) => set_rev_reg_ids_cache(cred_def_id, ser_new_vec.as_str()),
+            match serde_json::to_string(&RevRegIdsCache { rev_reg_ids: vec![String::from(rev_reg_id)] }) {
+                Ok(ser_new_vec) => set_rev_reg_ids_cache(wallet_handle, cred_def_id, ser_new_vec.as_str()),
                 Err(_) => Err(VcxError::from(VcxErrorKind::SerializationError))
             }
         }
     }
 }
 
+///
+/// Returns ids of all revocation registries created for the credential definition.
+///
+/// # Arguments
+/// `wallet_handle`: wallet of the issuer
+/// `cred_def_id`: credential definition id
+pub fn get_rev_reg_ids(wallet_handle: WalletHandle, cred_def_id: &str) -> Vec<String> {
+    get_rev_reg_ids_cache(wallet_handle, cred_def_id)
+        .map(|cache| cache.rev_reg_ids)
+        .unwrap_or_default()
+}
+
+///
+/// Returns number of credentials issued with the revocation registry, 0 if none have been recorded.
+///
+/// # Arguments
+/// `wallet_handle`: wallet of the issuer
+/// `rev_reg_id`: revocation registry id
+pub fn get_rev_reg_issued_count(wallet_handle: WalletHandle, rev_reg_id: &str) -> u32 {
+    block_on(get_rev_reg_issued_count_async(wallet_handle, rev_reg_id))
+}
+
+pub async fn get_rev_reg_issued_count_async(wallet_handle: WalletHandle, rev_reg_id: &str) -> u32 {
+    let wallet_id = format!("{}{}", REV_REG_ISSUED_COUNT_CACHE_PREFIX, rev_reg_id);
+
+    match get_record_async(wallet_handle, CACHE_TYPE, &wallet_id, &json!({"retrieveType": false, "retrieveValue": true, "retrieveTags": false}).to_string()).await {
+        Ok(json) => {
+            match serde_json::from_str::<serde_json::Value>(&json)
+                .map(|x| x["value"].as_str().and_then(|count| count.parse::<u32>().ok())) {
+                Ok(Some(count)) => count,
+                _ => {
+                    warn!("Unable to convert issued count for rev_reg_id: {}, json: {}", rev_reg_id, json);
+                    0
+                }
+            }
+        }
+        Err(_) => 0
+    }
+}
+
+///
+/// Records that credential with index `cred_rev_id` has been issued with the revocation registry.
+/// Indexes are assigned in order, so the highest one is the number of issued credentials.
+///
+/// # Arguments
+/// `wallet_handle`: wallet of the issuer
+/// `rev_reg_id`: revocation registry id
+/// `cred_rev_id`: revocation index of the issued credential
+pub fn record_rev_reg_issuance(wallet_handle: WalletHandle, rev_reg_id: &str, cred_rev_id: &str) -> VcxResult<()> {
+    block_on(record_rev_reg_issuance_async(wallet_handle, rev_reg_id, cred_rev_id))
+}
+
+pub async fn record_rev_reg_issuance_async(wallet_handle: WalletHandle, rev_reg_id: &str, cred_rev_id: &str) -> VcxResult<()> {
+    debug!("Recording issuance for rev_reg_id {}, cred_rev_id {}", rev_reg_id, cred_rev_id);
+    let cred_rev_id = cred_rev_id.parse::<u32>()
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidRevocationDetails, format!("Invalid cred_rev_id: {}, error: {}", cred_rev_id, err)))?;
+    if cred_rev_id <= get_rev_reg_issued_count_async(wallet_handle, rev_reg_id).await {
+        return Ok(());
+    }
+    let wallet_id = format!("{}{}", REV_REG_ISSUED_COUNT_CACHE_PREFIX, rev_reg_id);
+    let count = cred_rev_id.to_string();
+    _update_or_add_record_async(wallet_handle, &wallet_id, &count).await
+}
+
 ///
 ///
 /// Saves rev reg delta cache.
 /// Errors are silently ignored.
 ///
 /// # Arguments
+/// `wallet_handle`: wallet of the issuer
 /// `rev_reg_id`: revocation registry id.
 /// `cache`: Cache object.
 ///
-pub fn set_rev_reg_delta_cache(rev_reg_id: &str, cache: &str) -> VcxResult<()> {
+pub fn set_rev_reg_delta_cache(wallet_handle: WalletHandle, rev_reg_id: &str, cache: &str) -> VcxResult<()> {
+    block_on(set_rev_reg_delta_cache_async(wallet_handle, rev_reg_id, cache))
+}
+
+pub async fn set_rev_reg_delta_cache_async(wallet_handle: WalletHandle, rev_reg_id: &str, cache: &str) -> VcxResult<()> {
     debug!("Setting rev_reg_delta_cache for rev_reg_id {}, cache {}", rev_reg_id, cache);
     match serde_json::to_string(cache) {
         Ok(json) => {
             let wallet_id = format!("{}{}", REV_REG_DELTA_CACHE_PREFIX, rev_reg_id);
-            match update_record_value(CACHE_TYPE, &wallet_id, &json)
-                .or(add_record(CACHE_TYPE, &wallet_id, &json, None)) {
-                Ok(_) => Ok(()),
+            match _update_or_add_record_async(wallet_handle, &wallet_id, &json).await {
+                Ok(_) => _add_pending_rev_reg_id_async(wallet_handle, rev_reg_id).await,
                 Err(err) => Err(err)
             }
         }
@@ -136,18 +243,91 @@ pub fn set_rev_reg_delta_cache(rev_reg_id: &str, cache: &str) -> VcxResult<()> {
 /// Errors are silently ignored.
 ///
 /// # Arguments
+/// `wallet_handle`: wallet of the issuer
 /// `rev_reg_id`: revocation registry id.
 /// `cache`: Cache object.
 ///
-pub fn clear_rev_reg_delta_cache(rev_reg_id: &str) -> VcxResult<String> {
+pub fn clear_rev_reg_delta_cache(wallet_handle: WalletHandle, rev_reg_id: &str) -> VcxResult<String> {
     debug!("Clearing rev_reg_delta_cache for rev_reg_id {}", rev_reg_id);
-    if let Some(last_delta) = get_rev_reg_delta_cache(rev_reg_id) {
+    if let Some(last_delta) = get_rev_reg_delta_cache(wallet_handle, rev_reg_id) {
         debug!("Got last delta = {}", last_delta);
         let wallet_id = format!("{}{}", REV_REG_DELTA_CACHE_PREFIX, rev_reg_id);
-        delete_record(CACHE_TYPE, &wallet_id)?;
+        delete_record(wallet_handle, CACHE_TYPE, &wallet_id)?;
         debug!("Record with id {} deleted", wallet_id);
+        _remove_pending_rev_reg_id(wallet_handle, rev_reg_id)?;
         Ok(last_delta)
     } else {
         Err(VcxError::from(VcxErrorKind::IOError))
     }
 }
+
+///
+/// Returns ids of revocation registries having rev reg delta cache,
+/// i.e. revocations which have been stored locally but not published yet.
+pub fn get_pending_rev_reg_ids(wallet_handle: WalletHandle) -> Vec<String> {
+    block_on(get_pending_rev_reg_ids_async(wallet_handle))
+}
+
+pub async fn get_pending_rev_reg_ids_async(wallet_handle: WalletHandle) -> Vec<String> {
+    match get_record_async(wallet_handle, CACHE_TYPE, PENDING_REV_REG_IDS_CACHE_ID, &json!({"retrieveType": false, "retrieveValue": true, "retrieveTags": false}).to_string()).await {
+        Ok(json) => {
+            match serde_json::from_str(&json)
+                .and_then(|x: serde_json::Value|
+                    serde_json::from_str(x.get("value").unwrap_or(&serde_json::Value::Null).as_str().unwrap_or(""))) {
+                Ok(rev_reg_ids) => rev_reg_ids,
+                Err(err) => {
+                    warn!("Unable to convert pending rev_reg_ids cache, json: {}, error: {}", json, err);
+                    Vec::new()
+                }
+            }
+        }
+        Err(_) => Vec::new()
+    }
+}
+
+async fn _update_or_add_record_async(wallet_handle: WalletHandle, id: &str, value: &str) -> VcxResult<()> {
+    match update_record_value_async(wallet_handle, CACHE_TYPE, id, value).await {
+        Ok(()) => Ok(()),
+        Err(_) => add_record_async(wallet_handle, CACHE_TYPE, id, value, None).await
+    }
+}
+
+async fn _set_pending_rev_reg_ids_async(wallet_handle: WalletHandle, rev_reg_ids: &[String]) -> VcxResult<()> {
+    let json = serde_json::to_string(rev_reg_ids)
+        .map_err(|_| VcxError::from(VcxErrorKind::SerializationError))?;
+    _update_or_add_record_async(wallet_handle, PENDING_REV_REG_IDS_CACHE_ID, &json).await
+}
+
+async fn _add_pending_rev_reg_id_async(wallet_handle: WalletHandle, rev_reg_id: &str) -> VcxResult<()> {
+    let _lock = PENDING_REV_REG_IDS_LOCK.lock().await;
+    let mut rev_reg_ids = get_pending_rev_reg_ids_async(wallet_handle).await;
+    if rev_reg_ids.iter().any(|id| id == rev_reg_id) {
+        return Ok(());
+    }
+    rev_reg_ids.push(String::from(rev_reg_id));
+    _set_pending_rev_reg_ids_async(wallet_handle, &rev_reg_ids).await
+}
+
+fn _remove_pending_rev_reg_id(wallet_handle: WalletHandle, rev_reg_id: &str) -> VcxResult<()> {
+    block_on(async {
+        let _lock = PENDING_REV_REG_IDS_LOCK.lock().await;
+        let mut rev_reg_ids = get_pending_rev_reg_ids_async(wallet_handle).await;
+        rev_reg_ids.retain(|id| id != rev_reg_id);
+        _set_pending_rev_reg_ids_async(wallet_handle, &rev_reg_ids).await
+    })
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_parse_rev_reg_ids_record_accepts_legacy_list() {
+        let legacy = json!({"value": serde_json::to_string(r#"["rev_reg_id_1"]"#).unwrap()}).to_string();
+        assert_eq!(vec![String::from("rev_reg_id_1")], _parse_rev_reg_ids_record(&legacy).unwrap().rev_reg_ids);
+
+        let cache = json!({"value": serde_json::to_string(r#"{"rev_reg_ids":["rev_reg_id_1","rev_reg_id_2"]}"#).unwrap()}).to_string();
+        assert_eq!(vec![String::from("rev_reg_id_1"), String::from("rev_reg_id_2")], _parse_rev_reg_ids_record(&cache).unwrap().rev_reg_ids);
+    }
+}
@@ -1,46 +1,69 @@
 /* test isn't ready until > libindy 1.0.1 */
+use futures::compat::Future01CompatExt;
 use indy::future::Future;
 use indy::crypto;
+use indy::WalletHandle;
 
-use crate::{libindy, settings};
+use crate::aries::utils::task::block_on;
+use crate::settings;
 use crate::error::prelude::*;
 
-pub fn sign(my_vk: &str, msg: &[u8]) -> VcxResult<Vec<u8>> {
+pub fn sign(wallet_handle: WalletHandle, my_vk: &str, msg: &[u8]) -> VcxResult<Vec<u8>> {
+    block_on(sign_async(wallet_handle, my_vk, msg))
+}
+
+pub async fn sign_async(wallet_handle: WalletHandle, my_vk: &str, msg: &[u8]) -> VcxResult<Vec<u8>> {
     if settings::indy_mocks_enabled() { return Ok(Vec::from(msg).to_owned()); }
 
-    crypto::sign(libindy::utils::wallet::get_wallet_handle(), my_vk, msg)
-        .wait()
+    crypto::sign(wallet_handle, my_vk, msg)
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
 pub fn verify(vk: &str, msg: &[u8], signature: &[u8]) -> VcxResult<bool> {
+    block_on(verify_async(vk, msg, signature))
+}
+
+pub async fn verify_async(vk: &str, msg: &[u8], signature: &[u8]) -> VcxResult<bool> {
     if settings::indy_mocks_enabled() { return Ok(true); }
 
     crypto::verify(vk, msg, signature)
-        .wait()
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
-pub fn pack_message(sender_vk: Option<&str>, receiver_keys: &str, msg: &[u8]) -> VcxResult<Vec<u8>> {
+pub fn pack_message(wallet_handle: WalletHandle, sender_vk: Option<&str>, receiver_keys: &str, msg: &[u8]) -> VcxResult<Vec<u8>> {
+    block_on(pack_message_async(wallet_handle, sender_vk, receiver_keys, msg))
+}
+
+pub async fn pack_message_async(wallet_handle: WalletHandle, sender_vk: Option<&str>, receiver_keys: &str, msg: &[u8]) -> VcxResult<Vec<u8>> {
     if settings::indy_mocks_enabled() { return Ok(msg.to_vec()); }
 
-    crypto::pack_message(libindy::utils::wallet::get_wallet_handle(), msg, receiver_keys, sender_vk)
-        .wait()
+    crypto::pack_message(wallet_handle, msg, receiver_keys, sender_vk)
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
-pub fn unpack_message(msg: &[u8]) -> VcxResult<Vec<u8>> {
+pub fn unpack_message(wallet_handle: WalletHandle, msg: &[u8]) -> VcxResult<Vec<u8>> {
+    block_on(unpack_message_async(wallet_handle, msg))
+}
+
+pub async fn unpack_message_async(wallet_handle: WalletHandle, msg: &[u8]) -> VcxResult<Vec<u8>> {
     if settings::indy_mocks_enabled() { return Ok(Vec::from(msg).to_owned()); }
 
-    crypto::unpack_message(libindy::utils::wallet::get_wallet_handle(), msg)
-        .wait()
+    crypto::unpack_message(wallet_handle, msg)
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
-pub fn create_key(seed: Option<&str>) -> VcxResult<String> {
+pub fn create_key(wallet_handle: WalletHandle, seed: Option<&str>) -> VcxResult<String> {
     let key_json = json!({"seed": seed}).to_string();
 
-    crypto::create_key(libindy::utils::wallet::get_wallet_handle(), Some(&key_json))
+    crypto::create_key(wallet_handle, Some(&key_json))
         .wait()
         .map_err(VcxError::from)
-}
\ No newline at end of file
+}
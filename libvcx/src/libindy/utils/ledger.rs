@@ -1,45 +1,56 @@
 use std::collections::HashMap;
 
+use futures::compat::Future01CompatExt;
 use indy::future::Future;
 use indy::cache;
+use indy::did;
 use indy::ledger;
+use indy::WalletHandle;
+use rust_base58::{FromBase58, ToBase58};
 use serde_json;
 
 use crate::{settings, utils};
+use crate::aries::utils::task::block_on;
 use crate::error::prelude::*;
-use crate::libindy::utils::pool::get_pool_handle;
-use crate::libindy::utils::wallet::get_wallet_handle;
+use crate::libindy::utils::payments::{pay_for_txn, PaymentTxn};
+use crate::profile::ProfileContext;
+use crate::utils::constants::{CREATE_ATTRIB_ACTION, EDIT_NYM_VERKEY_ACTION, NYM_TXN_TYPE};
 use crate::utils::random::generate_random_did;
 
-pub fn multisign_request(did: &str, request: &str) -> VcxResult<String> {
-    ledger::multi_sign_request(get_wallet_handle(), did, request)
+pub fn multisign_request(profile: &ProfileContext, did: &str, request: &str) -> VcxResult<String> {
+    ledger::multi_sign_request(profile.wallet_handle()?, did, request)
         .wait()
         .map_err(VcxError::from)
 }
 
-pub fn libindy_sign_request(did: &str, request: &str) -> VcxResult<String> {
-    ledger::sign_request(get_wallet_handle(), did, request)
+pub fn libindy_sign_request(wallet_handle: WalletHandle, did: &str, request: &str) -> VcxResult<String> {
+    ledger::sign_request(wallet_handle, did, request)
         .wait()
         .map_err(VcxError::from)
 }
 
-pub fn libindy_sign_and_submit_request(issuer_did: &str, request_json: &str) -> VcxResult<String> {
-    trace!("libindy_sign_and_submit_request >>> issuer_did: {}, request_json: {}", issuer_did, request_json);
+pub fn libindy_sign_and_submit_request(profile: &ProfileContext, issuer_did: &str, request_json: &str) -> VcxResult<String> {
+    trace!("libindy_sign_and_submit_request >>> profile: {:?}, issuer_did: {}, request_json: {}", profile, issuer_did, request_json);
     if settings::indy_mocks_enabled() { return Ok(r#"{"rc":"success"}"#.to_string()); }
 
-    let pool_handle = get_pool_handle()?;
-    let wallet_handle = get_wallet_handle();
+    let pool_handle = profile.pool_handle()?;
+    let wallet_handle = profile.wallet_handle()?;
 
     ledger::sign_and_submit_request(pool_handle, wallet_handle, issuer_did, request_json)
         .wait()
         .map_err(VcxError::from)
 }
 
-pub fn libindy_submit_request(request_json: &str) -> VcxResult<String> {
-    let pool_handle = get_pool_handle()?;
+pub fn libindy_submit_request(profile: &ProfileContext, request_json: &str) -> VcxResult<String> {
+    block_on(libindy_submit_request_async(profile, request_json))
+}
+
+pub async fn libindy_submit_request_async(profile: &ProfileContext, request_json: &str) -> VcxResult<String> {
+    let pool_handle = profile.pool_handle()?;
 
     ledger::submit_request(pool_handle, request_json)
-        .wait()
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
@@ -66,7 +77,7 @@ pub fn libindy_get_txn_author_agreement() -> VcxResult<String> {
     let get_author_agreement_request = ledger::build_get_txn_author_agreement_request(Some(&did), None)
         .wait()?;
 
-    let get_author_agreement_response = libindy_submit_request(&get_author_agreement_request)?;
+    let get_author_agreement_response = libindy_submit_request(&ProfileContext::Global, &get_author_agreement_request)?;
 
     let get_author_agreement_response = serde_json::from_str::<serde_json::Value>(&get_author_agreement_response)
         .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidLedgerResponse, format!("{:?}", err)))?;
@@ -77,7 +88,7 @@ pub fn libindy_get_txn_author_agreement() -> VcxResult<String> {
     let get_acceptance_mechanism_request = ledger::build_get_acceptance_mechanisms_request(Some(&did), None, None)
         .wait()?;
 
-    let get_acceptance_mechanism_response = libindy_submit_request(&get_acceptance_mechanism_request)?;
+    let get_acceptance_mechanism_response = libindy_submit_request(&ProfileContext::Global, &get_acceptance_mechanism_request)?;
 
     let get_acceptance_mechanism_response = serde_json::from_str::<serde_json::Value>(&get_acceptance_mechanism_response)
         .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidLedgerResponse, format!("{:?}", err)))?;
@@ -119,11 +130,41 @@ pub fn libindy_build_get_auth_rule_request(submitter_did: Option<&str>, txn_type
 }
 
 pub fn libindy_build_get_nym_request(submitter_did: Option<&str>, did: &str) -> VcxResult<String> {
+    block_on(libindy_build_get_nym_request_async(submitter_did, did))
+}
+
+pub async fn libindy_build_get_nym_request_async(submitter_did: Option<&str>, did: &str) -> VcxResult<String> {
     ledger::build_get_nym_request(submitter_did, did)
+        .compat()
+        .await
+        .map_err(VcxError::from)
+}
+
+pub fn libindy_build_nym_request(submitter_did: &str, target_did: &str, verkey: Option<&str>, alias: Option<&str>, role: Option<&str>) -> VcxResult<String> {
+    trace!("libindy_build_nym_request >>> submitter_did: {}, target_did: {}, verkey: {:?}, alias: {:?}, role: {:?}", submitter_did, target_did, verkey, alias, role);
+    ledger::build_nym_request(submitter_did, target_did, verkey, alias, role)
         .wait()
         .map_err(VcxError::from)
 }
 
+pub fn libindy_build_attrib_request(submitter_did: &str, target_did: &str, hash: Option<&str>, raw: Option<&str>, enc: Option<&str>) -> VcxResult<String> {
+    trace!("libindy_build_attrib_request >>> submitter_did: {}, target_did: {}, hash: {:?}, raw: {:?}, enc: {:?}", submitter_did, target_did, hash, raw, enc);
+    ledger::build_attrib_request(submitter_did, target_did, hash, raw, enc)
+        .wait()
+        .map_err(VcxError::from)
+}
+
+pub fn libindy_build_get_attrib_request(submitter_did: Option<&str>, target_did: &str, raw: Option<&str>, hash: Option<&str>, enc: Option<&str>) -> VcxResult<String> {
+    block_on(libindy_build_get_attrib_request_async(submitter_did, target_did, raw, hash, enc))
+}
+
+pub async fn libindy_build_get_attrib_request_async(submitter_did: Option<&str>, target_did: &str, raw: Option<&str>, hash: Option<&str>, enc: Option<&str>) -> VcxResult<String> {
+    ledger::build_get_attrib_request(submitter_did, target_did, raw, hash, enc)
+        .compat()
+        .await
+        .map_err(VcxError::from)
+}
+
 pub mod auth_rule {
     use std::collections::HashMap;
     use std::sync::Mutex;
@@ -257,7 +298,7 @@ pub mod auth_rule {
     }
 
     // Helpers to set fee alias for auth rules
-    pub fn set_actions_fee_aliases(submitter_did: &str, rules_fee: &str) -> VcxResult<()> {
+    pub fn set_actions_fee_aliases(profile: &ProfileContext, submitter_did: &str, rules_fee: &str) -> VcxResult<()> {
         _get_default_ledger_auth_rules();
 
         let auth_rules = AUTH_RULES.lock().unwrap();
@@ -275,16 +316,16 @@ pub mod auth_rule {
                 }
             });
 
-        _send_auth_rules(submitter_did, &auth_rules)
+        _send_auth_rules(profile, submitter_did, &auth_rules)
     }
 
-    fn _send_auth_rules(submitter_did: &str, data: &Vec<AuthRule>) -> VcxResult<()> {
+    fn _send_auth_rules(profile: &ProfileContext, submitter_did: &str, data: &Vec<AuthRule>) -> VcxResult<()> {
         let data = serde_json::to_string(&data)
             .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot serialize auth rules: {:?}", err)))?;
 
         let auth_rules_request = libindy_build_auth_rules_request(submitter_did, &data)?;
 
-        let response = ledger::sign_and_submit_request(get_pool_handle()?, get_wallet_handle(), submitter_did, &auth_rules_request)
+        let response = ledger::sign_and_submit_request(profile.pool_handle()?, profile.wallet_handle()?, submitter_did, &auth_rules_request)
             .wait()?;
 
         let response: serde_json::Value = serde_json::from_str(&response)
@@ -304,7 +345,7 @@ pub mod auth_rule {
 
         GET_DEFAULT_AUTH_CONSTRAINTS.call_once(|| {
             let get_auth_rule_request = indy::ledger::build_get_auth_rule_request(None, None, None, None, None, None).wait().unwrap();
-            let get_auth_rule_response = libindy::utils::ledger::libindy_submit_request(&get_auth_rule_request).unwrap();
+            let get_auth_rule_response = libindy::utils::ledger::libindy_submit_request(&ProfileContext::Global, &get_auth_rule_request).unwrap();
 
             let response: GetAuthRuleResponse = serde_json::from_str(&get_auth_rule_response)
                 .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidLedgerResponse, err)).unwrap();
@@ -328,7 +369,7 @@ pub mod auth_rule {
         }
     }
 
-    pub fn get_action_auth_rule(action: (&str, &str, &str, Option<&str>, Option<&str>)) -> VcxResult<String> {
+    pub fn get_action_auth_rule(profile: &ProfileContext, action: (&str, &str, &str, Option<&str>, Option<&str>)) -> VcxResult<String> {
         let (txn_type, action, field, old_value, new_value) = action;
 
         if settings::indy_mocks_enabled() { return Ok(json!({"result":{"data":[{"new_value":"0","constraint":{"need_to_be_owner":false,"sig_count":1,"metadata":{"fees":txn_type},"role":"0","constraint_id":"ROLE"},"field":"role","auth_type":"1","auth_action":"ADD"}],"identifier":"LibindyDid111111111111","auth_action":"ADD","new_value":"0","reqId":15616,"auth_type":"1","type":"121","field":"role"},"op":"REPLY"}).to_string()); }
@@ -338,7 +379,7 @@ pub mod auth_rule {
 
         let request = libindy_build_get_auth_rule_request(Some(&did), Some(txn_type), Some(action), Some(field), old_value, new_value)?;
 
-        let response_json = libindy_submit_request(&request)?;
+        let response_json = libindy_submit_request(profile, &request)?;
 
         let response: serde_json::Value = serde_json::from_str(&response_json)
             .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidLedgerResponse, format!("{:?}", err)))?;
@@ -350,17 +391,21 @@ pub mod auth_rule {
     }
 }
 
-pub fn get_nym(did: &str) -> VcxResult<String> {
+pub fn get_nym(profile: &ProfileContext, did: &str) -> VcxResult<String> {
+    block_on(get_nym_async(profile, did))
+}
+
+pub async fn get_nym_async(profile: &ProfileContext, did: &str) -> VcxResult<String> {
     let submitter_did = generate_random_did();
 
-    let get_nym_req = libindy_build_get_nym_request(Some(&submitter_did), &did)?;
-    libindy_submit_request(&get_nym_req)
+    let get_nym_req = libindy_build_get_nym_request_async(Some(&submitter_did), &did).await?;
+    libindy_submit_request_async(profile, &get_nym_req).await
 }
 
-pub fn get_role(did: &str) -> VcxResult<String> {
+pub fn get_role(profile: &ProfileContext, did: &str) -> VcxResult<String> {
     if settings::indy_mocks_enabled() { return Ok(settings::DEFAULT_ROLE.to_string()); }
 
-    let get_nym_resp = get_nym(&did)?;
+    let get_nym_resp = get_nym(profile, &did)?;
     let get_nym_resp: serde_json::Value = serde_json::from_str(&get_nym_resp)
         .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidLedgerResponse, format!("{:?}", err)))?;
     let data: serde_json::Value = serde_json::from_str(&get_nym_resp["result"]["data"].as_str().unwrap_or("{}"))
@@ -369,17 +414,249 @@ pub fn get_role(did: &str) -> VcxResult<String> {
     Ok(role)
 }
 
+pub fn build_nym_request(profile: &ProfileContext, target_did: &str, verkey: Option<&str>, alias: Option<&str>, role: Option<&str>) -> VcxResult<String> {
+    if settings::indy_mocks_enabled() { return Ok(utils::constants::NYM_REQUEST.to_string()); }
+
+    let submitter_did = profile.institution_did()?;
+    let request = libindy_build_nym_request(&submitter_did, target_did, verkey, alias, role)?;
+    append_txn_author_agreement_to_request(&request)
+}
+
+/**
+Writes NYM of `target_did` to the ledger on behalf of the institution DID, which pays the fees if there are any.
+ */
+pub fn publish_nym(profile: &ProfileContext, target_did: &str, verkey: Option<&str>, alias: Option<&str>, role: Option<&str>) -> VcxResult<Option<PaymentTxn>> {
+    trace!("publish_nym >>> profile: {:?}, target_did: {}, verkey: {:?}, alias: {:?}, role: {:?}", profile, target_did, verkey, alias, role);
+    let action = _create_nym_action(role)?;
+    let request = build_nym_request(profile, target_did, verkey, alias, role)?;
+    let (payment, response) = pay_for_txn(profile, &request, action)?;
+    _check_write_response(&response)?;
+    Ok(payment)
+}
+
+/**
+Builds NYM transaction signed by the institution DID which has to be written to the ledger by the `endorser`.
+ */
+pub fn prepare_nym_for_endorser(profile: &ProfileContext, target_did: &str, verkey: Option<&str>, alias: Option<&str>, role: Option<&str>, endorser: &str) -> VcxResult<String> {
+    trace!("prepare_nym_for_endorser >>> profile: {:?}, target_did: {}, verkey: {:?}, alias: {:?}, role: {:?}, endorser: {}", profile, target_did, verkey, alias, role, endorser);
+    let request = build_nym_request(profile, target_did, verkey, alias, role)?;
+    set_endorser(profile, &request, endorser)
+}
+
+/**
+Fees of a NYM are set by the auth rule of the role given to the target DID, identity owner has an empty role code.
+ */
+fn _create_nym_action(role: Option<&str>) -> VcxResult<(&'static str, &'static str, &'static str, Option<&'static str>, Option<&'static str>)> {
+    let role_code = match role.unwrap_or_default() {
+        "" => "",
+        "TRUSTEE" | "0" => "0",
+        "STEWARD" | "2" => "2",
+        "TRUST_ANCHOR" | "ENDORSER" | "101" => "101",
+        "NETWORK_MONITOR" | "201" => "201",
+        role => return Err(VcxError::from_msg(VcxErrorKind::InvalidOption, format!("Unknown NYM role: {}", role)))
+    };
+    Ok((NYM_TXN_TYPE, "ADD", "role", None, Some(role_code)))
+}
+
+/**
+Replaces verkey of the institution DID. The new key is used by the wallet only after the ledger accepts it.
+ */
+pub fn rotate_verkey(profile: &ProfileContext) -> VcxResult<(String, Option<PaymentTxn>)> {
+    trace!("rotate_verkey >>> profile: {:?}", profile);
+    if settings::indy_mocks_enabled() { return Ok((utils::constants::VERKEY.to_string(), None)); }
+
+    let did = profile.institution_did()?;
+    let verkey = did::replace_keys_start(profile.wallet_handle()?, &did, "{}")
+        .wait()?;
+
+    let request = build_nym_request(profile, &did, Some(&verkey), None, None)?;
+    let (payment, response) = pay_for_txn(profile, &request, EDIT_NYM_VERKEY_ACTION)?;
+    _check_write_response(&response)?;
+
+    let verkey = apply_verkey_rotation(profile)?;
+    Ok((verkey, payment))
+}
+
+/**
+Starts replacing verkey of the institution DID, returns the new verkey and NYM transaction signed by the current key
+which has to be written to the ledger by the `endorser`. Once it is written, the rotation is finished by `apply_verkey_rotation`.
+ */
+pub fn prepare_verkey_rotation_for_endorser(profile: &ProfileContext, endorser: &str) -> VcxResult<(String, String)> {
+    trace!("prepare_verkey_rotation_for_endorser >>> profile: {:?}, endorser: {}", profile, endorser);
+    if settings::indy_mocks_enabled() {
+        return Ok((utils::constants::VERKEY.to_string(), utils::constants::REQUEST_WITH_ENDORSER.to_string()));
+    }
+
+    let did = profile.institution_did()?;
+    let verkey = did::replace_keys_start(profile.wallet_handle()?, &did, "{}")
+        .wait()?;
+
+    let request = build_nym_request(profile, &did, Some(&verkey), None, None)?;
+    let transaction = set_endorser(profile, &request, endorser)?;
+    Ok((verkey, transaction))
+}
+
+/**
+Makes the wallet use the verkey the institution DID has been rotated to, returns the verkey.
+In the global context the verkey in the settings is updated too.
+ */
+pub fn apply_verkey_rotation(profile: &ProfileContext) -> VcxResult<String> {
+    trace!("apply_verkey_rotation >>> profile: {:?}", profile);
+    if settings::indy_mocks_enabled() { return Ok(utils::constants::VERKEY.to_string()); }
+
+    let did = profile.institution_did()?;
+    did::replace_keys_apply(profile.wallet_handle()?, &did)
+        .wait()?;
+    let verkey = did::key_for_local_did(profile.wallet_handle()?, &did)
+        .wait()?;
+    if let ProfileContext::Global = profile {
+        settings::set_config_value(settings::CONFIG_INSTITUTION_VERKEY, &verkey);
+    }
+    Ok(verkey)
+}
+
+pub fn build_attrib_request(profile: &ProfileContext, target_did: &str, attrib_json: &str) -> VcxResult<String> {
+    if settings::indy_mocks_enabled() { return Ok(utils::constants::ATTRIB_REQUEST.to_string()); }
+
+    let attrib: serde_json::Value = serde_json::from_str(attrib_json)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize ATTRIB data: {}", err)))?;
+    if !attrib.is_object() {
+        return Err(VcxError::from_msg(VcxErrorKind::InvalidJson, "ATTRIB data must be JSON object"));
+    }
+
+    let submitter_did = profile.institution_did()?;
+    let request = libindy_build_attrib_request(&submitter_did, target_did, None, Some(attrib_json), None)?;
+    append_txn_author_agreement_to_request(&request)
+}
+
+/**
+Writes raw ATTRIB (e.g. `{"endpoint":{"endpoint":"https://agent.example.com"}}`) of `target_did` to the ledger.
+ */
+pub fn publish_attrib(profile: &ProfileContext, target_did: &str, attrib_json: &str) -> VcxResult<Option<PaymentTxn>> {
+    trace!("publish_attrib >>> profile: {:?}, target_did: {}, attrib_json: {}", profile, target_did, attrib_json);
+    let request = build_attrib_request(profile, target_did, attrib_json)?;
+    let (payment, response) = pay_for_txn(profile, &request, CREATE_ATTRIB_ACTION)?;
+    _check_write_response(&response)?;
+    Ok(payment)
+}
+
+pub fn prepare_attrib_for_endorser(profile: &ProfileContext, target_did: &str, attrib_json: &str, endorser: &str) -> VcxResult<String> {
+    trace!("prepare_attrib_for_endorser >>> profile: {:?}, target_did: {}, attrib_json: {}, endorser: {}", profile, target_did, attrib_json, endorser);
+    let request = build_attrib_request(profile, target_did, attrib_json)?;
+    set_endorser(profile, &request, endorser)
+}
+
+/**
+Reads raw ATTRIB `attr_name` of `target_did`, returns None if it is not set on the ledger.
+ */
+pub fn get_attrib(profile: &ProfileContext, target_did: &str, attr_name: &str) -> VcxResult<Option<String>> {
+    block_on(get_attrib_async(profile, target_did, attr_name))
+}
+
+pub async fn get_attrib_async(profile: &ProfileContext, target_did: &str, attr_name: &str) -> VcxResult<Option<String>> {
+    trace!("get_attrib_async >>> profile: {:?}, target_did: {}, attr_name: {}", profile, target_did, attr_name);
+    if settings::indy_mocks_enabled() { return Ok(Some(utils::constants::ATTRIB_DATA.to_string())); }
+
+    let submitter_did = generate_random_did();
+    let request = libindy_build_get_attrib_request_async(Some(&submitter_did), target_did, Some(attr_name), None, None).await?;
+    let response = libindy_submit_request_async(profile, &request).await?;
+
+    match parse_response(&response)? {
+        Response::Reply(Reply::ReplyV0(reply)) => Ok(reply.result["data"].as_str().map(String::from)),
+        Response::Reply(Reply::ReplyV1(reply)) => Ok(reply.data.result["data"].as_str().map(String::from)),
+        Response::Reject(res) | Response::ReqNACK(res) => Err(VcxError::from_msg(VcxErrorKind::InvalidLedgerResponse, format!("{:?}", res.reason))),
+    }
+}
+
+/**
+Endpoint of the DID published in its `endpoint` ATTRIB.
+ */
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct EndpointAttrib {
+    pub endpoint: String,
+    #[serde(default)]
+    #[serde(rename = "routingKeys")]
+    pub routing_keys: Vec<String>,
+}
+
+/**
+Reads verkey of the DID from its NYM, abbreviated verkey is expanded to the full one.
+ */
+pub fn get_verkey_from_ledger(profile: &ProfileContext, did: &str) -> VcxResult<String> {
+    block_on(get_verkey_from_ledger_async(profile, did))
+}
+
+pub async fn get_verkey_from_ledger_async(profile: &ProfileContext, did: &str) -> VcxResult<String> {
+    trace!("get_verkey_from_ledger_async >>> profile: {:?}, did: {}", profile, did);
+    if settings::indy_mocks_enabled() { return Ok(utils::constants::VERKEY.to_string()); }
+
+    let get_nym_resp: serde_json::Value = serde_json::from_str(&get_nym_async(profile, did).await?)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidLedgerResponse, format!("{:?}", err)))?;
+    let data: serde_json::Value = serde_json::from_str(get_nym_resp["result"]["data"].as_str().unwrap_or("{}"))
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidLedgerResponse, format!("{:?}", err)))?;
+    let verkey = data["verkey"].as_str()
+        .ok_or(VcxError::from_msg(VcxErrorKind::InvalidDid, format!("Verkey of DID {} not found on the ledger", did)))?;
+    _expand_verkey(did, verkey)
+}
+
+fn _expand_verkey(did: &str, verkey: &str) -> VcxResult<String> {
+    if !verkey.starts_with('~') {
+        return Ok(verkey.to_string());
+    }
+    let mut bytes = did.from_base58()
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::NotBase58, format!("Invalid DID {}, err: {:?}", did, err)))?;
+    bytes.extend(verkey[1..].from_base58()
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::NotBase58, format!("Invalid verkey {}, err: {:?}", verkey, err)))?);
+    Ok(bytes.to_base58())
+}
+
+pub fn get_service_endpoint(profile: &ProfileContext, did: &str) -> VcxResult<EndpointAttrib> {
+    block_on(get_service_endpoint_async(profile, did))
+}
+
+pub async fn get_service_endpoint_async(profile: &ProfileContext, did: &str) -> VcxResult<EndpointAttrib> {
+    trace!("get_service_endpoint_async >>> profile: {:?}, did: {}", profile, did);
+    let attrib = get_attrib_async(profile, did, "endpoint").await?
+        .ok_or(VcxError::from_msg(VcxErrorKind::InvalidDid, format!("Endpoint of DID {} not found on the ledger", did)))?;
+    let attrib: serde_json::Value = serde_json::from_str(&attrib)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidLedgerResponse, format!("Cannot deserialize ATTRIB: {:?}", err)))?;
+    serde_json::from_value(attrib["endpoint"].clone())
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidLedgerResponse, format!("Invalid endpoint ATTRIB of DID {}: {:?}", did, err)))
+}
+
+pub fn publish_service_endpoint(profile: &ProfileContext, did: &str, endpoint: &EndpointAttrib) -> VcxResult<Option<PaymentTxn>> {
+    trace!("publish_service_endpoint >>> profile: {:?}, did: {}, endpoint: {:?}", profile, did, endpoint);
+    publish_attrib(profile, did, &json!({"endpoint": endpoint}).to_string())
+}
+
+pub fn prepare_service_endpoint_for_endorser(profile: &ProfileContext, did: &str, endpoint: &EndpointAttrib, endorser: &str) -> VcxResult<String> {
+    trace!("prepare_service_endpoint_for_endorser >>> profile: {:?}, did: {}, endpoint: {:?}, endorser: {}", profile, did, endpoint, endorser);
+    prepare_attrib_for_endorser(profile, did, &json!({"endpoint": endpoint}).to_string(), endorser)
+}
+
+fn _check_write_response(response: &str) -> VcxResult<()> {
+    match parse_response(response)? {
+        Response::Reply(_) => Ok(()),
+        Response::Reject(res) | Response::ReqNACK(res) => Err(VcxError::from_msg(VcxErrorKind::InvalidLedgerResponse, format!("Transaction rejected: {:?}", res.reason))),
+    }
+}
+
 pub fn parse_response(response: &str) -> VcxResult<Response> {
     serde_json::from_str::<Response>(response)
         .to_vcx(VcxErrorKind::InvalidJson, "Cannot deserialize transaction response")
 }
 
-pub fn libindy_get_schema(submitter_did: &str, schema_id: &str) -> VcxResult<String> {
-    let pool_handle = get_pool_handle()?;
-    let wallet_handle = get_wallet_handle();
+pub fn libindy_get_schema(profile: &ProfileContext, submitter_did: &str, schema_id: &str) -> VcxResult<String> {
+    block_on(libindy_get_schema_async(profile, submitter_did, schema_id))
+}
+
+pub async fn libindy_get_schema_async(profile: &ProfileContext, submitter_did: &str, schema_id: &str) -> VcxResult<String> {
+    let pool_handle = profile.pool_handle()?;
+    let wallet_handle = profile.wallet_handle()?;
 
     cache::get_schema(pool_handle, wallet_handle, submitter_did, schema_id, "{}")
-        .wait()
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
@@ -389,38 +666,43 @@ pub fn libindy_build_get_cred_def_request(submitter_did: Option<&str>, cred_def_
         .map_err(VcxError::from)
 }
 
-pub fn libindy_get_cred_def(cred_def_id: &str) -> VcxResult<String> {
-    let pool_handle = get_pool_handle()?;
-    let wallet_handle = get_wallet_handle();
+pub fn libindy_get_cred_def(profile: &ProfileContext, cred_def_id: &str) -> VcxResult<String> {
+    block_on(libindy_get_cred_def_async(profile, cred_def_id))
+}
+
+pub async fn libindy_get_cred_def_async(profile: &ProfileContext, cred_def_id: &str) -> VcxResult<String> {
+    let pool_handle = profile.pool_handle()?;
+    let wallet_handle = profile.wallet_handle()?;
     let submitter_did = generate_random_did();
-    trace!("libindy_get_cred_def >>> pool_handle: {}, wallet_handle: {:?}, submitter_did: {}", pool_handle, wallet_handle, submitter_did);
+    trace!("libindy_get_cred_def_async >>> pool_handle: {}, wallet_handle: {:?}, submitter_did: {}", pool_handle, wallet_handle, submitter_did);
 
     cache::get_cred_def(pool_handle, wallet_handle, &submitter_did, cred_def_id, "{}")
-        .wait()
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
-pub fn set_endorser(request: &str, endorser: &str) -> VcxResult<String> {
+pub fn set_endorser(profile: &ProfileContext, request: &str, endorser: &str) -> VcxResult<String> {
     if settings::indy_mocks_enabled() { return Ok(utils::constants::REQUEST_WITH_ENDORSER.to_string()); }
 
-    let did = settings::get_config_value(settings::CONFIG_INSTITUTION_DID)?;
+    let did = profile.institution_did()?;
 
     let request = ledger::append_request_endorser(request, endorser)
         .wait()?;
 
-    multisign_request(&did, &request)
+    multisign_request(profile, &did, &request)
 }
 
-pub fn endorse_transaction(transaction_json: &str) -> VcxResult<()> {
+pub fn endorse_transaction(profile: &ProfileContext, transaction_json: &str) -> VcxResult<()> {
     //TODO Potentially VCX should handle case when endorser would like to pay fee
     if settings::indy_mocks_enabled() { return Ok(()); }
 
-    let submitter_did = settings::get_config_value(settings::CONFIG_INSTITUTION_DID)?;
+    let submitter_did = profile.institution_did()?;
 
     _verify_transaction_can_be_endorsed(transaction_json, &submitter_did)?;
 
-    let transaction = multisign_request(&submitter_did, transaction_json)?;
-    let response = libindy_submit_request(&transaction)?;
+    let transaction = multisign_request(profile, &submitter_did, transaction_json)?;
+    let response = libindy_submit_request(profile, &transaction)?;
 
     match parse_response(&response)? {
         Response::Reply(_) => Ok(()),
@@ -473,6 +755,84 @@ mod test {
         assert!(_verify_transaction_can_be_endorsed(transaction, "EbP4aYNeTHL6q385GuVpRV").is_err());
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_publish_nym_and_attrib() {
+        let _setup = SetupMocks::init();
+
+        publish_nym(&ProfileContext::Global, "V4SGRU86Z58d6TV7PBUe6f", Some(utils::constants::VERKEY), None, None).unwrap();
+        assert_eq!(utils::constants::REQUEST_WITH_ENDORSER,
+                   prepare_nym_for_endorser(&ProfileContext::Global, "V4SGRU86Z58d6TV7PBUe6f", None, None, None, "NcYxiDXkpYi6ov5FcYDi1e").unwrap());
+
+        publish_attrib(&ProfileContext::Global, "V4SGRU86Z58d6TV7PBUe6f", utils::constants::ATTRIB_DATA).unwrap();
+        assert_eq!(Some(utils::constants::ATTRIB_DATA.to_string()), get_attrib(&ProfileContext::Global, "V4SGRU86Z58d6TV7PBUe6f", "endpoint").unwrap());
+        assert_eq!(utils::constants::VERKEY, rotate_verkey(&ProfileContext::Global).unwrap().0);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_rotate_verkey_for_endorser() {
+        let _setup = SetupMocks::init();
+
+        let (verkey, transaction) = prepare_verkey_rotation_for_endorser(&ProfileContext::Global, "NcYxiDXkpYi6ov5FcYDi1e").unwrap();
+        assert_eq!(utils::constants::VERKEY, verkey);
+        assert_eq!(utils::constants::REQUEST_WITH_ENDORSER, transaction);
+        assert_eq!(utils::constants::VERKEY, apply_verkey_rotation(&ProfileContext::Global).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_create_nym_action_uses_role_code() {
+        let _setup = SetupDefaults::init();
+
+        assert_eq!(Some(""), _create_nym_action(None).unwrap().4);
+        assert_eq!(Some(""), _create_nym_action(Some("")).unwrap().4);
+        assert_eq!(Some("101"), _create_nym_action(Some("ENDORSER")).unwrap().4);
+        assert_eq!(Some("101"), _create_nym_action(Some("TRUST_ANCHOR")).unwrap().4);
+        assert_eq!(Some("0"), _create_nym_action(Some("TRUSTEE")).unwrap().4);
+        assert_eq!(VcxErrorKind::InvalidOption, _create_nym_action(Some("*")).unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_expand_verkey() {
+        let _setup = SetupDefaults::init();
+
+        assert_eq!("GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL", _expand_verkey("V4SGRU86Z58d6TV7PBUe6f", "GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL").unwrap());
+        assert_eq!("GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL", _expand_verkey("V4SGRU86Z58d6TV7PBUe6f", "~CoRER63DVYnWZtK8uAzNbx").unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_get_service_endpoint() {
+        let _setup = SetupMocks::init();
+
+        let endpoint = get_service_endpoint(&ProfileContext::Global, "V4SGRU86Z58d6TV7PBUe6f").unwrap();
+        assert_eq!(EndpointAttrib { endpoint: String::from("http://localhost:8080"), routing_keys: vec![] }, endpoint);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_check_write_response() {
+        let _setup = SetupDefaults::init();
+
+        assert!(_check_write_response(utils::constants::SUBMIT_SCHEMA_RESPONSE).is_ok());
+        let err = _check_write_response(r#"{"op":"REJECT","reason":"client request invalid"}"#).unwrap_err();
+        assert_eq!(VcxErrorKind::InvalidLedgerResponse, err.kind());
+    }
+
+    #[cfg(feature = "pool_tests")]
+    #[test]
+    fn test_publish_and_get_attrib() {
+        let _setup = SetupLibraryWalletPoolZeroFees::init();
+
+        let did = settings::get_config_value(settings::CONFIG_INSTITUTION_DID).unwrap();
+        publish_attrib(&ProfileContext::Global, &did, r#"{"endpoint":{"endpoint":"http://localhost:8080"}}"#).unwrap();
+
+        let attrib: serde_json::Value = serde_json::from_str(&get_attrib(&ProfileContext::Global, &did, "endpoint").unwrap().unwrap()).unwrap();
+        assert_eq!("http://localhost:8080", attrib["endpoint"]["endpoint"]);
+    }
+
     #[cfg(feature = "pool_tests")]
     #[test]
     fn test_endorse_transaction() {
@@ -487,9 +847,9 @@ mod test {
 
         let schema_request = libindy_build_schema_request(&author_did, utils::constants::SCHEMA_DATA).unwrap();
         let schema_request = ledger::append_request_endorser(&schema_request, &endorser_did).wait().unwrap();
-        let schema_request = multisign_request(&author_did, &schema_request).unwrap();
+        let schema_request = multisign_request(&ProfileContext::Global, &author_did, &schema_request).unwrap();
 
-        endorse_transaction(&schema_request).unwrap();
+        endorse_transaction(&ProfileContext::Global, &schema_request).unwrap();
     }
 }
 
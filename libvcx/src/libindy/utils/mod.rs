@@ -15,6 +15,7 @@ pub mod pool;
 pub mod crypto;
 pub mod payments;
 pub mod cache;
+pub mod revocation_manager;
 pub mod logger;
 
 pub mod error_codes;
@@ -9,6 +9,7 @@ use crate::{libindy, settings, utils};
 use crate::error::prelude::*;
 use crate::libindy::utils::ledger::{append_txn_author_agreement_to_request, auth_rule, libindy_sign_and_submit_request, libindy_sign_request, libindy_submit_request};
 use crate::libindy::utils::wallet::get_wallet_handle;
+use crate::profile::ProfileContext;
 use crate::utils::constants::{CREATE_TRANSFER_ACTION, SUBMIT_SCHEMA_RESPONSE};
 
 static DEFAULT_FEES: &str = r#"{"0":0, "1":0, "3":0, "100":0, "101":2, "102":42, "103":0, "104":0, "105":0, "107":0, "108":0, "109":0, "110":0, "111":0, "112":0, "113":2, "114":2, "115":0, "116":0, "117":0, "118":0, "119":0, "10001":0}"#;
@@ -86,8 +87,8 @@ pub fn build_test_address(address: &str) -> String {
     format!("pay:{}:{}", settings::get_payment_method(), address)
 }
 
-pub fn create_address(seed: Option<String>) -> VcxResult<String> {
-    trace!("create_address >>> seed: {:?}", seed);
+pub fn create_address(profile: &ProfileContext, seed: Option<String>) -> VcxResult<String> {
+    trace!("create_address >>> profile: {:?}, seed: {:?}", profile, seed);
 
     if settings::indy_mocks_enabled() {
         return Ok(build_test_address("J81AxU9hVHYFtJc"));
@@ -98,17 +99,17 @@ pub fn create_address(seed: Option<String>) -> VcxResult<String> {
         None => "{}".to_string(),
     };
 
-    payments::create_payment_address(get_wallet_handle(), settings::get_payment_method().as_str(), &config)
+    payments::create_payment_address(profile.wallet_handle()?, settings::get_payment_method().as_str(), &config)
         .wait()
         .map_err(VcxError::from)
 }
 
-pub fn sign_with_address(address: &str, message: &[u8]) -> VcxResult<Vec<u8>> {
-    trace!("sign_with_address >>> address: {:?}, message: {:?}", address, message);
+pub fn sign_with_address(profile: &ProfileContext, address: &str, message: &[u8]) -> VcxResult<Vec<u8>> {
+    trace!("sign_with_address >>> profile: {:?}, address: {:?}, message: {:?}", profile, address, message);
 
     if settings::indy_mocks_enabled() { return Ok(Vec::from(message).to_owned()); }
 
-    payments::sign_with_address(get_wallet_handle(), address, message).wait().map_err(VcxError::from)
+    payments::sign_with_address(profile.wallet_handle()?, address, message).wait().map_err(VcxError::from)
 }
 
 pub fn verify_with_address(address: &str, message: &[u8], signature: &[u8]) -> VcxResult<bool> {
@@ -119,7 +120,7 @@ pub fn verify_with_address(address: &str, message: &[u8], signature: &[u8]) -> V
     payments::verify_with_address(address, message, signature).wait().map_err(VcxError::from)
 }
 
-pub fn get_address_info(address: &str) -> VcxResult<AddressInfo> {
+pub fn get_address_info(profile: &ProfileContext, address: &str) -> VcxResult<AddressInfo> {
     if settings::indy_mocks_enabled() {
         let utxos = json!(
             [
@@ -143,12 +144,12 @@ pub fn get_address_info(address: &str) -> VcxResult<AddressInfo> {
         return Ok(AddressInfo { address: address.to_string(), balance: _address_balance(&utxo), utxo });
     }
 
-    let did = settings::get_config_value(settings::CONFIG_INSTITUTION_DID)?;
+    let did = profile.institution_did()?;
 
-    let (txn, _) = payments::build_get_payment_sources_with_from_request(get_wallet_handle(), Some(&did), address, None)
+    let (txn, _) = payments::build_get_payment_sources_with_from_request(profile.wallet_handle()?, Some(&did), address, None)
         .wait()?;
 
-    let response = libindy_sign_and_submit_request(&did, &txn)?;
+    let response = libindy_sign_and_submit_request(profile, &did, &txn)?;
 
     let (response, next) = payments::parse_get_payment_sources_with_from_response(settings::get_payment_method().as_str(), &response)
         .wait()?;
@@ -158,10 +159,10 @@ pub fn get_address_info(address: &str) -> VcxResult<AddressInfo> {
     let mut next_seqno = next;
 
     while next_seqno.is_some() {
-        let (txn, _) = payments::build_get_payment_sources_with_from_request(get_wallet_handle(), Some(&did), address, next_seqno)
+        let (txn, _) = payments::build_get_payment_sources_with_from_request(profile.wallet_handle()?, Some(&did), address, next_seqno)
             .wait()?;
 
-        let response = libindy_sign_and_submit_request(&did, &txn)?;
+        let response = libindy_sign_and_submit_request(profile, &did, &txn)?;
 
         let (response, next) = payments::parse_get_payment_sources_with_from_response(settings::get_payment_method().as_str(), &response)
             .wait()?;
@@ -177,7 +178,7 @@ pub fn get_address_info(address: &str) -> VcxResult<AddressInfo> {
     Ok(info)
 }
 
-pub fn list_addresses() -> VcxResult<Vec<String>> {
+pub fn list_addresses(profile: &ProfileContext) -> VcxResult<Vec<String>> {
     if settings::indy_mocks_enabled() {
         let addresses = json!([
                 build_test_address("9UFgyjuJxi1i1HD"),
@@ -186,7 +187,7 @@ pub fn list_addresses() -> VcxResult<Vec<String>> {
         return Ok(serde_json::from_value(addresses).unwrap());
     }
 
-    let addresses = payments::list_payment_addresses(get_wallet_handle())
+    let addresses = payments::list_payment_addresses(profile.wallet_handle()?)
         .wait()?;
 
     serde_json::from_str(&addresses)
@@ -198,10 +199,10 @@ fn is_valid_address(address: &str, method: &str) -> bool {
     address.starts_with(&prefix)
 }
 
-pub fn get_wallet_token_info() -> VcxResult<WalletInfo> {
-    trace!("get_wallet_token_info >>>");
+pub fn get_wallet_token_info(profile: &ProfileContext) -> VcxResult<WalletInfo> {
+    trace!("get_wallet_token_info >>> profile: {:?}", profile);
 
-    let addresses = list_addresses()?;
+    let addresses = list_addresses(profile)?;
     let method = settings::get_payment_method();
 
     let mut wallet_info = Vec::new();
@@ -210,7 +211,7 @@ pub fn get_wallet_token_info() -> VcxResult<WalletInfo> {
     for address in addresses.iter() {
         if is_valid_address(&address, &method) {
             debug!("getting address info for {}", address);
-            let info = get_address_info(&address)?;
+            let info = get_address_info(profile, &address)?;
 
             for utxo in info.utxo.iter() { balance += utxo.amount as u64; }
 
@@ -227,39 +228,42 @@ pub fn get_wallet_token_info() -> VcxResult<WalletInfo> {
     Ok(info)
 }
 
-pub fn get_ledger_fees() -> VcxResult<String> {
-    trace!("get_ledger_fees >>>");
+pub fn get_ledger_fees(profile: &ProfileContext) -> VcxResult<String> {
+    trace!("get_ledger_fees >>> profile: {:?}", profile);
 
     if settings::indy_mocks_enabled() { return Ok(DEFAULT_FEES.to_string()); }
 
-    let did = settings::get_config_value(settings::CONFIG_INSTITUTION_DID)?;
+    let did = profile.institution_did()?;
 
-    let txn = payments::build_get_txn_fees_req(get_wallet_handle(), Some(&did), settings::get_payment_method().as_str())
+    let txn = payments::build_get_txn_fees_req(profile.wallet_handle()?, Some(&did), settings::get_payment_method().as_str())
         .wait()?;
 
-    let response = libindy_sign_and_submit_request(&did, &txn)?;
+    let response = libindy_sign_and_submit_request(profile, &did, &txn)?;
 
     payments::parse_get_txn_fees_response(settings::get_payment_method().as_str(), &response)
         .wait()
         .map_err(VcxError::from)
 }
 
-pub fn pay_for_txn(req: &str, txn_action: (&str, &str, &str, Option<&str>, Option<&str>)) -> VcxResult<(Option<PaymentTxn>, String)> {
-    debug!("pay_for_txn(req: {}, txn_action: {:?})", req, txn_action);
+/**
+Submits the request signed by the institution DID of the profile, fees of the action are paid from the payment addresses of its wallet.
+ */
+pub fn pay_for_txn(profile: &ProfileContext, req: &str, txn_action: (&str, &str, &str, Option<&str>, Option<&str>)) -> VcxResult<(Option<PaymentTxn>, String)> {
+    debug!("pay_for_txn(profile: {:?}, req: {}, txn_action: {:?})", profile, req, txn_action);
     if settings::indy_mocks_enabled() {
         let inputs = vec!["pay:null:9UFgyjuJxi1i1HD".to_string()];
         let outputs = serde_json::from_str::<Vec<libindy::utils::payments::Output>>(r#"[{"amount":1,"extra":null,"recipient":"pay:null:xkIsxem0YNtHrRO"}]"#).unwrap();
         return Ok((Some(PaymentTxn::from_parts(inputs, outputs, 1, false)), SUBMIT_SCHEMA_RESPONSE.to_string()));
     }
-    let txn_price = get_action_price(txn_action, None)?;
+    let txn_price = get_action_price(profile, txn_action, None)?;
     if txn_price == 0 {
-        let did = settings::get_config_value(settings::CONFIG_INSTITUTION_DID)?;
-        let txn_response = libindy_sign_and_submit_request(&did, req)?;
+        let did = profile.institution_did()?;
+        let txn_response = libindy_sign_and_submit_request(profile, &did, req)?;
         Ok((None, txn_response))
     } else {
-        let (refund, inputs, refund_address) = inputs(txn_price)?;
+        let (refund, inputs, refund_address) = inputs(profile, txn_price)?;
         let output = outputs(refund, &refund_address, None, None)?;
-        let (_fee_response, txn_response) = _submit_fees_request(req, &inputs, &output)?;
+        let (_fee_response, txn_response) = _submit_fees_request(profile, req, &inputs, &output)?;
         let payment = PaymentTxn::from_parts(inputs, output, txn_price, false);
         Ok((Some(payment), txn_response))
     }
@@ -273,15 +277,15 @@ fn _serialize_inputs_and_outputs(inputs: &Vec<String>, outputs: &Vec<Output>) ->
     Ok((inputs, outputs))
 }
 
-fn _submit_fees_request(req: &str, inputs: &Vec<String>, outputs: &Vec<Output>) -> VcxResult<(String, String)> {
-    let did = settings::get_config_value(settings::CONFIG_INSTITUTION_DID)?;
+fn _submit_fees_request(profile: &ProfileContext, req: &str, inputs: &Vec<String>, outputs: &Vec<Output>) -> VcxResult<(String, String)> {
+    let did = profile.institution_did()?;
 
     let (inputs, outputs) = _serialize_inputs_and_outputs(inputs, outputs)?;
 
-    let req = libindy_sign_request(&did, req)?;
+    let req = libindy_sign_request(profile.wallet_handle()?, &did, req)?;
 
     let (req, payment_method) =
-        payments::add_request_fees(get_wallet_handle(),
+        payments::add_request_fees(profile.wallet_handle()?,
                                    Some(&did),
                                    &req,
                                    &inputs,
@@ -289,7 +293,7 @@ fn _submit_fees_request(req: &str, inputs: &Vec<String>, outputs: &Vec<Output>)
                                    None)
             .wait()?;
 
-    let response = libindy_submit_request(&req)?;
+    let response = libindy_submit_request(profile, &req)?;
 
     let parsed_response = payments::parse_response_with_fees(&payment_method, &response)
         .wait()
@@ -298,15 +302,15 @@ fn _submit_fees_request(req: &str, inputs: &Vec<String>, outputs: &Vec<Output>)
     Ok((parsed_response, response))
 }
 
-pub fn pay_a_payee(price: u64, address: &str) -> VcxResult<(PaymentTxn, String)> {
-    trace!("pay_a_payee >>> price: {}, address {}", price, address);
+pub fn pay_a_payee(profile: &ProfileContext, price: u64, address: &str) -> VcxResult<(PaymentTxn, String)> {
+    trace!("pay_a_payee >>> profile: {:?}, price: {}, address {}", profile, price, address);
     debug!("sending {} tokens to address {}", price, address);
 
-    let ledger_cost = get_action_price(CREATE_TRANSFER_ACTION, None)?;
-    let (remainder, input, refund_address) = inputs(price + ledger_cost)?;
+    let ledger_cost = get_action_price(profile, CREATE_TRANSFER_ACTION, None)?;
+    let (remainder, input, refund_address) = inputs(profile, price + ledger_cost)?;
     let outputs = outputs(remainder, &refund_address, Some(address.to_string()), Some(price))?;
 
-    let my_did = settings::get_config_value(settings::CONFIG_INSTITUTION_DID)?;
+    let my_did = profile.institution_did()?;
 
     if settings::indy_mocks_enabled() {
         let inputs = vec![build_test_address("9UFgyjuJxi1i1HD")];
@@ -331,10 +335,10 @@ pub fn pay_a_payee(price: u64, address: &str) -> VcxResult<(PaymentTxn, String)>
     };
 
     let (request, _payment_method) =
-        payments::build_payment_req(get_wallet_handle(), Some(&my_did), &inputs_json, &outputs_json, extra.as_ref().map(String::as_str))
+        payments::build_payment_req(profile.wallet_handle()?, Some(&my_did), &inputs_json, &outputs_json, extra.as_ref().map(String::as_str))
             .wait()?;
 
-    let result = libindy_submit_request(&request)?;
+    let result = libindy_submit_request(profile, &request)?;
     let payment = PaymentTxn::from_parts(input, outputs, price, false);
     Ok((payment, result))
 }
@@ -353,11 +357,12 @@ fn get_request_info(get_auth_rule_resp_json: &str, requester_info_json: &str, fe
         .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, err))
 }
 
-pub fn get_request_price(action_json: String, requester_info_json: Option<String>) -> VcxResult<u64> {
+pub fn get_request_price(profile: &ProfileContext, action_json: String, requester_info_json: Option<String>) -> VcxResult<u64> {
     let action: auth_rule::Action = serde_json::from_str(&action_json)
         .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize Action: {:?}", err)))?;
 
-    get_action_price((&action.auth_type,
+    get_action_price(profile,
+                     (&action.auth_type,
                       &action.auth_action,
                       &action.field,
                       action.old_value.as_ref().map(String::as_str),
@@ -365,29 +370,36 @@ pub fn get_request_price(action_json: String, requester_info_json: Option<String
                      requester_info_json)
 }
 
-fn get_action_price(action: (&str, &str, &str, Option<&str>, Option<&str>), requester_info_json: Option<String>) -> VcxResult<u64> {
-    let get_auth_rule_resp = match auth_rule::get_action_auth_rule(action) {
+fn get_action_price(profile: &ProfileContext, action: (&str, &str, &str, Option<&str>, Option<&str>), requester_info_json: Option<String>) -> VcxResult<u64> {
+    let get_auth_rule_resp = match auth_rule::get_action_auth_rule(profile, action) {
         // TODO: Huck to save backward compatibility
         Ok(resp) => resp,
         Err(_) => return Ok(0)
     };
 
-    let ledger_fees = get_ledger_fees()?;
+    let ledger_fees = get_ledger_fees(profile)?;
 
-    let requester_info = get_requester_info(requester_info_json)?;
+    let requester_info = get_requester_info(profile, requester_info_json)?;
 
     let req_info = get_request_info(&get_auth_rule_resp, &requester_info, &ledger_fees)?;
 
     Ok(req_info.price)
 }
 
-fn get_requester_info(requester_info_json: Option<String>) -> VcxResult<String> {
+fn get_requester_info(profile: &ProfileContext, requester_info_json: Option<String>) -> VcxResult<String> {
     // TODO: THINK better
-    let role = match settings::get_config_value(settings::CONFIG_SDK_TO_REMOTE_ROLE) {
-        Ok(role) => role,
-        Err(_) => {
-            let role_ = libindy::utils::ledger::get_role(&settings::get_config_value(settings::CONFIG_INSTITUTION_DID)?)?;
-            settings::set_config_value(settings::CONFIG_SDK_TO_REMOTE_ROLE, &role_);
+    // role is cached in the settings only for the global context
+    let cached_role = match profile {
+        ProfileContext::Global => settings::get_config_value(settings::CONFIG_SDK_TO_REMOTE_ROLE).ok(),
+        ProfileContext::Profile(..) => None
+    };
+    let role = match cached_role {
+        Some(role) => role,
+        None => {
+            let role_ = libindy::utils::ledger::get_role(profile, &profile.institution_did()?)?;
+            if profile.handle().is_none() {
+                settings::set_config_value(settings::CONFIG_SDK_TO_REMOTE_ROLE, &role_);
+            }
             role_
         }
     };
@@ -410,12 +422,12 @@ fn _address_balance(address: &Vec<UTXO>) -> u64 {
     address.iter().fold(0, |balance, utxo| balance + utxo.amount)
 }
 
-pub fn inputs(cost: u64) -> VcxResult<(u64, Vec<String>, String)> {
+pub fn inputs(profile: &ProfileContext, cost: u64) -> VcxResult<(u64, Vec<String>, String)> {
     let mut inputs: Vec<String> = Vec::new();
     let mut balance = 0;
     let mut refund_address = String::new();
 
-    let wallet_info: WalletInfo = get_wallet_token_info()?;
+    let wallet_info: WalletInfo = get_wallet_token_info(profile)?;
 
     if wallet_info.balance < cost {
         warn!("not enough tokens in wallet to pay: balance: {}, cost: {}", wallet_info.balance, cost);
@@ -478,7 +490,7 @@ pub fn mint_tokens_and_set_fees(number_of_addresses: Option<u32>, tokens_per_add
         let mut addresses = Vec::new();
 
         for _n in 0..number_of_addresses {
-            addresses.push(create_address(seed.clone()).unwrap())
+            addresses.push(create_address(&ProfileContext::Global, seed.clone()).unwrap())
         }
 
         let mint: Vec<Value> = addresses.clone().into_iter().enumerate().map(|(_i, payment_address)|
@@ -487,12 +499,12 @@ pub fn mint_tokens_and_set_fees(number_of_addresses: Option<u32>, tokens_per_add
         let outputs = serde_json::to_string(&mint).unwrap();
         let (req, _) = payments::build_mint_req(get_wallet_handle(), Some(&did_1), &outputs, None).wait().unwrap();
 
-        let sign1 = libindy::utils::ledger::multisign_request(&did_1, &req).unwrap();
-        let sign2 = libindy::utils::ledger::multisign_request(&did_2, &sign1).unwrap();
-        let sign3 = libindy::utils::ledger::multisign_request(&did_3, &sign2).unwrap();
-        let sign4 = libindy::utils::ledger::multisign_request(&did_4, &sign3).unwrap();
+        let sign1 = libindy::utils::ledger::multisign_request(&ProfileContext::Global, &did_1, &req).unwrap();
+        let sign2 = libindy::utils::ledger::multisign_request(&ProfileContext::Global, &did_2, &sign1).unwrap();
+        let sign3 = libindy::utils::ledger::multisign_request(&ProfileContext::Global, &did_3, &sign2).unwrap();
+        let sign4 = libindy::utils::ledger::multisign_request(&ProfileContext::Global, &did_4, &sign3).unwrap();
 
-        match libindy::utils::ledger::libindy_submit_request(&sign4) {
+        match libindy::utils::ledger::libindy_submit_request(&ProfileContext::Global, &sign4) {
             Ok(_) => (),
             Err(x) => error!("failure minting tokens: {}", x),
         };
@@ -502,12 +514,12 @@ pub fn mint_tokens_and_set_fees(number_of_addresses: Option<u32>, tokens_per_add
         let txn = payments::build_set_txn_fees_req(get_wallet_handle(), Some(&did_1), settings::get_payment_method().as_str(), fees_)
             .wait()?;
 
-        let sign1 = libindy::utils::ledger::multisign_request(&did_1, &txn).unwrap();
-        let sign2 = libindy::utils::ledger::multisign_request(&did_2, &sign1).unwrap();
-        let sign3 = libindy::utils::ledger::multisign_request(&did_3, &sign2).unwrap();
-        let sign4 = libindy::utils::ledger::multisign_request(&did_4, &sign3).unwrap();
+        let sign1 = libindy::utils::ledger::multisign_request(&ProfileContext::Global, &did_1, &txn).unwrap();
+        let sign2 = libindy::utils::ledger::multisign_request(&ProfileContext::Global, &did_2, &sign1).unwrap();
+        let sign3 = libindy::utils::ledger::multisign_request(&ProfileContext::Global, &did_3, &sign2).unwrap();
+        let sign4 = libindy::utils::ledger::multisign_request(&ProfileContext::Global, &did_4, &sign3).unwrap();
 
-        libindy::utils::ledger::libindy_submit_request(&sign4).unwrap();
+        libindy::utils::ledger::libindy_submit_request(&ProfileContext::Global, &sign4).unwrap();
 
         let txn_fees: HashMap<String, String> =
             serde_json::from_str::<HashMap<String, u64>>(fees_).unwrap()
@@ -515,7 +527,7 @@ pub fn mint_tokens_and_set_fees(number_of_addresses: Option<u32>, tokens_per_add
                 .map(|(k, _v)| (k.to_string(), k.to_string()))
                 .collect();
 
-        auth_rule::set_actions_fee_aliases(&did_1, &json!(txn_fees).to_string()).unwrap();
+        auth_rule::set_actions_fee_aliases(&ProfileContext::Global, &did_1, &json!(txn_fees).to_string()).unwrap();
     }
 
     Ok(())
@@ -526,12 +538,12 @@ pub fn add_new_did(role: Option<&str>) -> (String, String) {
 
     let institution_did = settings::get_config_value(settings::CONFIG_INSTITUTION_DID).unwrap();
 
-    let (did, verkey) = libindy::utils::signus::create_and_store_my_did(None, None).unwrap();
+    let (did, verkey) = libindy::utils::signus::create_and_store_my_did(get_wallet_handle(), None, None).unwrap();
     let mut req_nym = ledger::build_nym_request(&institution_did, &did, Some(&verkey), None, role).wait().unwrap();
 
     req_nym = append_txn_author_agreement_to_request(&req_nym).unwrap();
 
-    libindy::utils::ledger::libindy_sign_and_submit_request(&institution_did, &req_nym).unwrap();
+    libindy::utils::ledger::libindy_sign_and_submit_request(&ProfileContext::Global, &institution_did, &req_nym).unwrap();
     (did, verkey)
 }
 
@@ -550,7 +562,7 @@ pub mod tests {
 
     #[allow(dead_code)]
     fn get_my_balance() -> u64 {
-        let info: WalletInfo = get_wallet_token_info().unwrap();
+        let info: WalletInfo = get_wallet_token_info(&ProfileContext::Global).unwrap();
         info.balance
     }
 
@@ -559,7 +571,7 @@ pub mod tests {
     fn test_create_address() {
         let _setup = SetupMocks::init();
 
-        create_address(None).unwrap();
+        create_address(&ProfileContext::Global, None).unwrap();
     }
 
 
@@ -568,7 +580,7 @@ pub mod tests {
     fn test_sign_with_address() {
         let _setup = SetupMocks::init();
 
-        let res = sign_with_address("test", &[1, 2, 3]).unwrap();
+        let res = sign_with_address(&ProfileContext::Global, "test", &[1, 2, 3]).unwrap();
         assert_eq!(res, vec![1, 2, 3])
     }
 
@@ -586,8 +598,8 @@ pub mod tests {
     fn test_get_addresses() {
         let _setup = SetupMocks::init();
 
-        create_address(None).unwrap();
-        let _addresses = list_addresses().unwrap();
+        create_address(&ProfileContext::Global, None).unwrap();
+        let _addresses = list_addresses(&ProfileContext::Global).unwrap();
     }
 
     #[test]
@@ -595,8 +607,8 @@ pub mod tests {
     fn test_get_wallet_token_info() {
         let _setup = SetupMocks::init();
 
-        create_address(None).unwrap();
-        let balance = get_wallet_token_info().unwrap().to_string();
+        create_address(&ProfileContext::Global, None).unwrap();
+        let balance = get_wallet_token_info(&ProfileContext::Global).unwrap().to_string();
 
         let expected_balance = json!({
             "balance":6,
@@ -651,7 +663,7 @@ pub mod tests {
     fn test_get_wallet_token_info_real() {
         let _setup = SetupLibraryWalletPoolZeroFees::init();
 
-        let wallet_info = get_wallet_token_info().unwrap();
+        let wallet_info = get_wallet_token_info(&ProfileContext::Global).unwrap();
         assert_eq!(wallet_info.balance, 50000000000);
     }
 
@@ -660,7 +672,7 @@ pub mod tests {
     fn test_get_ledger_fees() {
         let _setup = SetupMocks::init();
 
-        let fees = get_ledger_fees().unwrap();
+        let fees = get_ledger_fees(&ProfileContext::Global).unwrap();
         assert!(fees.contains(r#""101":2"#));
         assert!(fees.contains(r#""1":0"#));
     }
@@ -688,18 +700,18 @@ pub mod tests {
 
         // Success - Exact amount
         let expected_inputs = vec![pay_addr_1.clone(), pay_addr_2.clone(), pay_addr_1.clone(), pay_addr_2.clone()];
-        assert_eq!(inputs(6).unwrap(), (0, expected_inputs, build_test_address("zR3GN9lfbCVtHjp")));
+        assert_eq!(inputs(&ProfileContext::Global, 6).unwrap(), (0, expected_inputs, build_test_address("zR3GN9lfbCVtHjp")));
 
         // Success - utxo with remainder tokens
         let expected_inputs = vec![pay_addr_1.clone(), pay_addr_2.clone(), pay_addr_1.clone(), pay_addr_2.clone()];
-        assert_eq!(inputs(5).unwrap(), (1, expected_inputs, build_test_address("zR3GN9lfbCVtHjp")));
+        assert_eq!(inputs(&ProfileContext::Global, 5).unwrap(), (1, expected_inputs, build_test_address("zR3GN9lfbCVtHjp")));
 
         // Success - requesting amount that partial address (1 of 2 utxos) can satisfy
         let expected_inputs = vec![pay_addr_1.clone()];
-        assert_eq!(inputs(1).unwrap(), (0, expected_inputs, build_test_address("9UFgyjuJxi1i1HD")));
+        assert_eq!(inputs(&ProfileContext::Global, 1).unwrap(), (0, expected_inputs, build_test_address("9UFgyjuJxi1i1HD")));
 
         // Err - request more than wallet contains
-        assert_eq!(inputs(7).err().unwrap().kind(), VcxErrorKind::InsufficientTokenAmount);
+        assert_eq!(inputs(&ProfileContext::Global, 7).err().unwrap().kind(), VcxErrorKind::InsufficientTokenAmount);
     }
 
     #[test]
@@ -708,14 +720,14 @@ pub mod tests {
         let _setup = SetupMocks::init();
 
         let mut cost = 5;
-        let (remainder, _, refund_address) = inputs(cost).unwrap();
+        let (remainder, _, refund_address) = inputs(&ProfileContext::Global, cost).unwrap();
         let mut expected_output: Vec<Output> = serde_json::from_str(&format!(r#"[{{"amount":1,"recipient":"{}"}}]"#, refund_address)).unwrap();
         assert_eq!(outputs(remainder, &refund_address, None, None).unwrap(), expected_output);
 
         // No remainder so don't create an address in outputs
         cost = 6;
         expected_output = vec![];
-        let (remainder, _, refund_address) = inputs(cost).unwrap();
+        let (remainder, _, refund_address) = inputs(&ProfileContext::Global, cost).unwrap();
         assert_eq!(remainder, 0);
         assert_eq!(outputs(remainder, &refund_address, None, None).unwrap(), expected_output);
     }
@@ -737,11 +749,11 @@ pub mod tests {
     fn test_get_txn_cost() {
         let _setup = SetupMocks::init();
 
-        assert_eq!(get_action_price(utils::constants::CREATE_SCHEMA_ACTION, None).unwrap(), 2);
-        assert_eq!(get_action_price(utils::constants::CREATE_CRED_DEF_ACTION, None).unwrap(), 42);
+        assert_eq!(get_action_price(&ProfileContext::Global, utils::constants::CREATE_SCHEMA_ACTION, None).unwrap(), 2);
+        assert_eq!(get_action_price(&ProfileContext::Global, utils::constants::CREATE_CRED_DEF_ACTION, None).unwrap(), 42);
 
         let unknown_action = ("unknown txn", "ADD", "*", None, Some("*"));
-        assert_eq!(get_action_price(unknown_action, None).unwrap(), 0);
+        assert_eq!(get_action_price(&ProfileContext::Global, unknown_action, None).unwrap(), 0);
     }
 
     #[cfg(feature = "pool_tests")]
@@ -753,19 +765,19 @@ pub mod tests {
         let payment_address = payment_address.as_str();
 
         let price = get_my_balance();
-        let result_from_paying = pay_a_payee(price, payment_address);
+        let result_from_paying = pay_a_payee(&ProfileContext::Global, price, payment_address);
         assert!(result_from_paying.is_ok());
         assert_eq!(get_my_balance(), 0);
         mint_tokens_and_set_fees(None, None, None, None).unwrap();
         assert_eq!(get_my_balance(), 50000000000);
 
         let price = get_my_balance() - 5;
-        let result_from_paying = pay_a_payee(price, payment_address);
+        let result_from_paying = pay_a_payee(&ProfileContext::Global, price, payment_address);
         assert!(result_from_paying.is_ok());
         assert_eq!(get_my_balance(), 5);
 
         let price = get_my_balance() + 5;
-        let result_from_paying = pay_a_payee(price, payment_address);
+        let result_from_paying = pay_a_payee(&ProfileContext::Global, price, payment_address);
         assert_eq!(result_from_paying.err().unwrap().kind(), VcxErrorKind::InsufficientTokenAmount);
         assert_eq!(get_my_balance(), 5);
     }
@@ -790,7 +802,7 @@ pub mod tests {
         let _setup = SetupLibraryWalletPoolZeroFees::init();
 
         mint_tokens_and_set_fees(Some(0), Some(0), Some("{\"101\":0, \"102\":0}".to_string()), None).unwrap();
-        let _fees = get_ledger_fees().unwrap();
+        let _fees = get_ledger_fees(&ProfileContext::Global).unwrap();
         libindy::utils::anoncreds::tests::create_and_write_test_schema(utils::constants::DEFAULT_SCHEMA_ATTRS);
     }
 
@@ -814,7 +826,7 @@ pub mod tests {
             "sig_count":1,
         }).to_string();
 
-        let price = get_request_price(_action(), Some(requester_info)).unwrap();
+        let price = get_request_price(&ProfileContext::Global, _action(), Some(requester_info)).unwrap();
         assert_eq!(2, price);
     }
 
@@ -831,7 +843,7 @@ pub mod tests {
             "sig_count":1,
         }).to_string();
 
-        let res = get_request_price(action_json, Some(requester_info));
+        let res = get_request_price(&ProfileContext::Global, action_json, Some(requester_info));
         assert!(res.is_err());
     }
 }
@@ -0,0 +1,242 @@
+use std::collections::BTreeMap;
+
+use crate::error::prelude::*;
+use crate::libindy::utils::anoncreds;
+use crate::libindy::utils::cache::{get_pending_rev_reg_ids, get_rev_reg_delta_cache, set_rev_reg_delta_cache};
+use crate::libindy::utils::payments::PaymentTxn;
+use crate::profile::ProfileContext;
+use crate::settings;
+
+/**
+Issued credential identified by its index in the revocation registry.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CredentialRevocation {
+    pub rev_reg_id: String,
+    pub tails_file: String,
+    pub cred_rev_id: String,
+}
+
+/**
+Delta written to the ledger, `revoked` holds indexes of credentials it revokes.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PublishedDelta {
+    pub rev_reg_id: String,
+    pub revoked: Vec<u32>,
+    pub payment_txn: Option<PaymentTxn>,
+}
+
+/**
+Delta which could not be written to the ledger, it stays pending.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FailedDelta {
+    pub rev_reg_id: String,
+    pub code: u32,
+    pub reason: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct PublishReport {
+    pub published: Vec<PublishedDelta>,
+    pub failed: Vec<FailedDelta>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RevocationRegistryDeltaValue {
+    #[serde(default)]
+    revoked: Vec<u32>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RevocationRegistryDelta {
+    value: RevocationRegistryDeltaValue,
+}
+
+fn _revoked_indexes(delta: &str) -> VcxResult<Vec<u32>> {
+    serde_json::from_str::<RevocationRegistryDelta>(delta)
+        .map(|delta| delta.value.revoked)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize revocation registry delta: {}", err)))
+}
+
+/**
+Revokes credentials of one registry one by one and merges their deltas into `delta`. Credentials revoked
+before a failure are already revoked in the wallet, the merged delta is returned along with the failure
+so they are not lost.
+ */
+fn _merge_revocations(mut delta: Option<String>,
+                      revocations: &[&CredentialRevocation],
+                      revoke: impl Fn(&CredentialRevocation) -> VcxResult<String>,
+                      merge: impl Fn(&str, &str) -> VcxResult<String>) -> (Option<String>, Option<VcxError>) {
+    for revocation in revocations {
+        let result = revoke(revocation)
+            .and_then(|new_delta| match delta {
+                Some(ref old_delta) => merge(old_delta, &new_delta),
+                None => Ok(new_delta)
+            });
+        match result {
+            Ok(merged_delta) => delta = Some(merged_delta),
+            Err(err) => {
+                let err = err.extend(format!("Cannot revoke credential {} of revocation registry {}", revocation.cred_rev_id, revocation.rev_reg_id));
+                return (delta, Some(err));
+            }
+        }
+    }
+    (delta, None)
+}
+
+/**
+Revokes credentials in the wallet without publishing, revocations of each registry are merged
+into the single pending delta.
+ */
+pub fn queue_revocations(profile: &ProfileContext, revocations: &[CredentialRevocation]) -> VcxResult<()> {
+    trace!("queue_revocations >>> revocations: {:?}", revocations);
+    if settings::indy_mocks_enabled() { return Ok(()); }
+
+    let wallet_handle = profile.wallet_handle()?;
+    let mut registries: BTreeMap<&str, Vec<&CredentialRevocation>> = BTreeMap::new();
+    for revocation in revocations {
+        registries.entry(revocation.rev_reg_id.as_str()).or_default().push(revocation);
+    }
+
+    for (rev_reg_id, revocations) in registries {
+        let (delta, failure) = _merge_revocations(
+            get_rev_reg_delta_cache(wallet_handle, rev_reg_id),
+            &revocations,
+            |revocation| anoncreds::libindy_issuer_revoke_credential(wallet_handle, &revocation.tails_file, rev_reg_id, &revocation.cred_rev_id),
+            anoncreds::libindy_issuer_merge_revocation_registry_deltas,
+        );
+        if let Some(delta) = delta {
+            set_rev_reg_delta_cache(wallet_handle, rev_reg_id, &delta)?;
+        }
+        if let Some(err) = failure {
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+/**
+Returns number of revoked credentials waiting to be published, per revocation registry.
+ */
+pub fn get_pending_revocations(profile: &ProfileContext) -> VcxResult<BTreeMap<String, usize>> {
+    trace!("get_pending_revocations >>>");
+    let wallet_handle = profile.wallet_handle()?;
+    let mut pending = BTreeMap::new();
+    for rev_reg_id in get_pending_rev_reg_ids(wallet_handle) {
+        if let Some(delta) = get_rev_reg_delta_cache(wallet_handle, &rev_reg_id) {
+            pending.insert(rev_reg_id, _revoked_indexes(&delta)?.len());
+        }
+    }
+    Ok(pending)
+}
+
+/**
+Writes one delta per revocation registry to the ledger, all registries with pending revocations
+are published if `rev_reg_ids` is not set. Failing registries do not stop publishing of others.
+ */
+pub fn publish_pending_revocations(profile: &ProfileContext, rev_reg_ids: Option<&[String]>) -> VcxResult<PublishReport> {
+    trace!("publish_pending_revocations >>> rev_reg_ids: {:?}", rev_reg_ids);
+    let wallet_handle = profile.wallet_handle()?;
+    let rev_reg_ids = rev_reg_ids.map(|rev_reg_ids| rev_reg_ids.to_vec()).unwrap_or_else(|| get_pending_rev_reg_ids(wallet_handle));
+
+    let mut report = PublishReport::default();
+    for rev_reg_id in rev_reg_ids {
+        let revoked = match get_rev_reg_delta_cache(wallet_handle, &rev_reg_id) {
+            Some(delta) => _revoked_indexes(&delta)?,
+            None => Vec::new()
+        };
+        match anoncreds::publish_local_revocations(profile, &rev_reg_id) {
+            Ok((payment_txn, _)) => report.published.push(PublishedDelta { rev_reg_id, revoked, payment_txn }),
+            Err(err) => report.failed.push(FailedDelta { rev_reg_id, code: err.kind().into(), reason: err.to_string().trim().to_string() })
+        }
+    }
+    trace!("publish_pending_revocations <<< report: {:?}", report);
+    Ok(report)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::utils::constants::REV_REG_DELTA_JSON;
+    use crate::utils::devsetup::SetupMocks;
+
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_revoked_indexes() {
+        let _setup = SetupMocks::init();
+
+        assert_eq!(Vec::<u32>::new(), _revoked_indexes(REV_REG_DELTA_JSON).unwrap());
+        assert_eq!(vec![1, 5], _revoked_indexes(r#"{"ver":"1.0","value":{"accum":"1 0","issued":[],"revoked":[1,5]}}"#).unwrap());
+        assert_eq!(VcxErrorKind::InvalidJson, _revoked_indexes("{}").unwrap_err().kind());
+    }
+
+    fn _revocation(cred_rev_id: &str) -> CredentialRevocation {
+        CredentialRevocation { rev_reg_id: "rev_reg_id".to_string(), tails_file: "tails_file".to_string(), cred_rev_id: cred_rev_id.to_string() }
+    }
+
+    fn _revoke(revocation: &CredentialRevocation) -> VcxResult<String> {
+        match revocation.cred_rev_id.as_str() {
+            "fail" => Err(VcxError::from(VcxErrorKind::InvalidRevocationDetails)),
+            cred_rev_id => Ok(cred_rev_id.to_string())
+        }
+    }
+
+    fn _merge(old_delta: &str, new_delta: &str) -> VcxResult<String> {
+        match new_delta {
+            "unmergeable" => Err(VcxError::from(VcxErrorKind::InvalidJson)),
+            _ => Ok(format!("{},{}", old_delta, new_delta))
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_merge_revocations() {
+        let _setup = SetupMocks::init();
+
+        let (revocation_1, revocation_2) = (_revocation("1"), _revocation("2"));
+        let (delta, failure) = _merge_revocations(Some("0".to_string()), &[&revocation_1, &revocation_2], _revoke, _merge);
+        assert_eq!(Some("0,1,2".to_string()), delta);
+        assert!(failure.is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_merge_revocations_keeps_delta_on_revoke_failure() {
+        let _setup = SetupMocks::init();
+
+        let (revocation_1, revocation_2, revocation_3) = (_revocation("1"), _revocation("fail"), _revocation("3"));
+        let (delta, failure) = _merge_revocations(None, &[&revocation_1, &revocation_2, &revocation_3], _revoke, _merge);
+        assert_eq!(Some("1".to_string()), delta);
+        assert_eq!(VcxErrorKind::InvalidRevocationDetails, failure.unwrap().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_merge_revocations_keeps_delta_on_merge_failure() {
+        let _setup = SetupMocks::init();
+
+        let (revocation_1, revocation_2, revocation_3) = (_revocation("1"), _revocation("unmergeable"), _revocation("3"));
+        let (delta, failure) = _merge_revocations(Some("0".to_string()), &[&revocation_1, &revocation_2, &revocation_3], _revoke, _merge);
+        assert_eq!(Some("0,1".to_string()), delta);
+        assert_eq!(VcxErrorKind::InvalidJson, failure.unwrap().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_publish_pending_revocations_reports_missing_deltas() {
+        let _setup = SetupMocks::init();
+
+        queue_revocations(&ProfileContext::Global, &[CredentialRevocation { rev_reg_id: "rev_reg_id".to_string(), tails_file: "tails_file".to_string(), cred_rev_id: "1".to_string() }]).unwrap();
+        assert!(get_pending_revocations(&ProfileContext::Global).unwrap().is_empty());
+
+        let report = publish_pending_revocations(&ProfileContext::Global, Some(&["rev_reg_id".to_string()])).unwrap();
+        assert!(report.published.is_empty());
+        assert_eq!("rev_reg_id", report.failed[0].rev_reg_id);
+        assert_eq!(u32::from(VcxErrorKind::RevDeltaNotFound), report.failed[0].code);
+
+        assert_eq!(PublishReport::default(), publish_pending_revocations(&ProfileContext::Global, None).unwrap());
+    }
+}
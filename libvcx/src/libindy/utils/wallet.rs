@@ -1,11 +1,16 @@
+use std::sync::atomic::{AtomicI32, Ordering};
+
 use indy::{ErrorCode, wallet};
 use indy::{INVALID_WALLET_HANDLE, SearchHandle, WalletHandle};
 use indy::future::Future;
+use futures::compat::Future01CompatExt;
 
+use crate::aries::utils::task::block_on;
 use crate::error::prelude::*;
 use crate::init::open_as_main_wallet;
 use crate::libindy::utils::{anoncreds, signus};
 use crate::settings;
+use crate::utils::mockdata::mock_settings;
 use crate::settings::Actors::Issuer;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -49,7 +54,7 @@ pub struct WalletRecord {
     #[serde(rename = "type")]
     record_type: Option<String>,
     pub value: Option<String>,
-    tags: Option<String>,
+    pub tags: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -69,16 +74,17 @@ impl RestoreWalletConfigs {
     }
 }
 
-pub static mut WALLET_HANDLE: WalletHandle = INVALID_WALLET_HANDLE;
+// wallet of the global (non-profile) context, profiles carry their own wallet handles
+static WALLET_HANDLE: AtomicI32 = AtomicI32::new(INVALID_WALLET_HANDLE.0);
 
 pub fn set_wallet_handle(handle: WalletHandle) -> WalletHandle {
     trace!("set_wallet_handle >>> handle: {:?}", handle);
-    unsafe { WALLET_HANDLE = handle; }
+    WALLET_HANDLE.store(handle.0, Ordering::SeqCst);
     settings::get_agency_client_mut().unwrap().set_wallet_handle(handle.0);
-    unsafe { WALLET_HANDLE }
+    handle
 }
 
-pub fn get_wallet_handle() -> WalletHandle { unsafe { WALLET_HANDLE } }
+pub fn get_wallet_handle() -> WalletHandle { WalletHandle(WALLET_HANDLE.load(Ordering::SeqCst)) }
 
 pub fn reset_wallet_handle() -> VcxResult<()> {
     set_wallet_handle(INVALID_WALLET_HANDLE);
@@ -91,14 +97,14 @@ pub fn create_wallet(config: &WalletConfig) -> VcxResult<()> {
     trace!("Created wallet with handle {:?}", wh);
 
     // If MS is already in wallet then just continue
-    anoncreds::libindy_prover_create_master_secret(settings::DEFAULT_LINK_SECRET_ALIAS).ok();
+    anoncreds::libindy_prover_create_master_secret(wh, settings::DEFAULT_LINK_SECRET_ALIAS).ok();
     
     close_main_wallet()?;
     Ok(())
 }
 
 pub fn configure_issuer_wallet(enterprise_seed: &str) -> VcxResult<IssuerConfig> {
-    let (institution_did, institution_verkey) = signus::create_and_store_my_did(Some(enterprise_seed), None)?;
+    let (institution_did, institution_verkey) = signus::create_and_store_my_did(get_wallet_handle(), Some(enterprise_seed), None)?;
     Ok(IssuerConfig {
         institution_did,
         institution_verkey
@@ -210,105 +216,128 @@ pub fn delete_wallet(wallet_config: &WalletConfig) -> VcxResult<()> {
     Ok(())
 }
 
-pub fn add_record(xtype: &str, id: &str, value: &str, tags: Option<&str>) -> VcxResult<()> {
-    trace!("add_record >>> xtype: {}, id: {}, value: {}, tags: {:?}", secret!(&xtype), secret!(&id), secret!(&value), secret!(&tags));
+pub fn add_record(wallet_handle: WalletHandle, xtype: &str, id: &str, value: &str, tags: Option<&str>) -> VcxResult<()> {
+    block_on(add_record_async(wallet_handle, xtype, id, value, tags))
+}
+
+pub async fn add_record_async(wallet_handle: WalletHandle, xtype: &str, id: &str, value: &str, tags: Option<&str>) -> VcxResult<()> {
+    trace!("add_record_async >>> wallet_handle: {:?}, xtype: {}, id: {}, value: {}, tags: {:?}", wallet_handle, secret!(&xtype), secret!(&id), secret!(&value), secret!(&tags));
 
     if settings::indy_mocks_enabled() { return Ok(()); }
 
-    wallet::add_wallet_record(get_wallet_handle(), xtype, id, value, tags)
-        .wait()
+    wallet::add_wallet_record(wallet_handle, xtype, id, value, tags)
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
-pub fn get_record(xtype: &str, id: &str, options: &str) -> VcxResult<String> {
-    trace!("get_record >>> xtype: {}, id: {}, options: {}", secret!(&xtype), secret!(&id), options);
+pub fn get_record(wallet_handle: WalletHandle, xtype: &str, id: &str, options: &str) -> VcxResult<String> {
+    block_on(get_record_async(wallet_handle, xtype, id, options))
+}
+
+pub async fn get_record_async(wallet_handle: WalletHandle, xtype: &str, id: &str, options: &str) -> VcxResult<String> {
+    trace!("get_record_async >>> wallet_handle: {:?}, xtype: {}, id: {}, options: {}", wallet_handle, secret!(&xtype), secret!(&id), options);
 
     if settings::indy_mocks_enabled() {
+        if let Some(record) = mock_settings::get_mock_wallet_record(xtype, id) {
+            return Ok(record);
+        }
         return Ok(r#"{"id":"123","type":"record type","value":"record value","tags":null}"#.to_string());
     }
 
-    wallet::get_wallet_record(get_wallet_handle(), xtype, id, options)
-        .wait()
+    wallet::get_wallet_record(wallet_handle, xtype, id, options)
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
-pub fn delete_record(xtype: &str, id: &str) -> VcxResult<()> {
-    trace!("delete_record >>> xtype: {}, id: {}", secret!(&xtype), secret!(&id));
+pub fn delete_record(wallet_handle: WalletHandle, xtype: &str, id: &str) -> VcxResult<()> {
+    block_on(delete_record_async(wallet_handle, xtype, id))
+}
+
+pub async fn delete_record_async(wallet_handle: WalletHandle, xtype: &str, id: &str) -> VcxResult<()> {
+    trace!("delete_record_async >>> wallet_handle: {:?}, xtype: {}, id: {}", wallet_handle, secret!(&xtype), secret!(&id));
 
     if settings::indy_mocks_enabled() { return Ok(()); }
 
-    wallet::delete_wallet_record(get_wallet_handle(), xtype, id)
-        .wait()
+    wallet::delete_wallet_record(wallet_handle, xtype, id)
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
 
-pub fn update_record_value(xtype: &str, id: &str, value: &str) -> VcxResult<()> {
-    trace!("update_record_value >>> xtype: {}, id: {}, value: {}", secret!(&xtype), secret!(&id), secret!(&value));
+pub fn update_record_value(wallet_handle: WalletHandle, xtype: &str, id: &str, value: &str) -> VcxResult<()> {
+    block_on(update_record_value_async(wallet_handle, xtype, id, value))
+}
+
+pub async fn update_record_value_async(wallet_handle: WalletHandle, xtype: &str, id: &str, value: &str) -> VcxResult<()> {
+    trace!("update_record_value_async >>> wallet_handle: {:?}, xtype: {}, id: {}, value: {}", wallet_handle, secret!(&xtype), secret!(&id), secret!(&value));
 
     if settings::indy_mocks_enabled() { return Ok(()); }
 
-    wallet::update_wallet_record_value(get_wallet_handle(), xtype, id, value)
-        .wait()
+    wallet::update_wallet_record_value(wallet_handle, xtype, id, value)
+        .compat()
+        .await
         .map_err(VcxError::from)
 }
 
-pub fn add_record_tags(xtype: &str, id: &str, tags: &str) -> VcxResult<()> {
-    trace!("add_record_tags >>> xtype: {}, id: {}, tags: {:?}", secret!(&xtype), secret!(&id), secret!(&tags));
+pub fn add_record_tags(wallet_handle: WalletHandle, xtype: &str, id: &str, tags: &str) -> VcxResult<()> {
+    trace!("add_record_tags >>> wallet_handle: {:?}, xtype: {}, id: {}, tags: {:?}", wallet_handle, secret!(&xtype), secret!(&id), secret!(&tags));
 
     if settings::indy_mocks_enabled() {
         return Ok(());
     }
 
-    wallet::add_wallet_record_tags(get_wallet_handle(), xtype, id, tags)
+    wallet::add_wallet_record_tags(wallet_handle, xtype, id, tags)
         .wait()
         .map_err(VcxError::from)
 }
 
-pub fn update_record_tags(xtype: &str, id: &str, tags: &str) -> VcxResult<()> {
-    trace!("update_record_tags >>> xtype: {}, id: {}, tags: {}", secret!(&xtype), secret!(&id), secret!(&tags));
+pub fn update_record_tags(wallet_handle: WalletHandle, xtype: &str, id: &str, tags: &str) -> VcxResult<()> {
+    trace!("update_record_tags >>> wallet_handle: {:?}, xtype: {}, id: {}, tags: {}", wallet_handle, secret!(&xtype), secret!(&id), secret!(&tags));
 
     if settings::indy_mocks_enabled() {
         return Ok(());
     }
 
-    wallet::update_wallet_record_tags(get_wallet_handle(), xtype, id, tags)
+    wallet::update_wallet_record_tags(wallet_handle, xtype, id, tags)
         .wait()
         .map_err(VcxError::from)
 }
 
-pub fn delete_record_tags(xtype: &str, id: &str, tag_names: &str) -> VcxResult<()> {
-    trace!("delete_record_tags >>> xtype: {}, id: {}, tag_names: {}", secret!(&xtype), secret!(&id), secret!(&tag_names));
+pub fn delete_record_tags(wallet_handle: WalletHandle, xtype: &str, id: &str, tag_names: &str) -> VcxResult<()> {
+    trace!("delete_record_tags >>> wallet_handle: {:?}, xtype: {}, id: {}, tag_names: {}", wallet_handle, secret!(&xtype), secret!(&id), secret!(&tag_names));
 
     if settings::indy_mocks_enabled() {
         return Ok(());
     }
 
-    wallet::delete_wallet_record_tags(get_wallet_handle(), xtype, id, tag_names)
+    wallet::delete_wallet_record_tags(wallet_handle, xtype, id, tag_names)
         .wait()
         .map_err(VcxError::from)
 }
 
-pub fn open_search(xtype: &str, query: &str, options: &str) -> VcxResult<SearchHandle> {
-    trace!("open_search >>> xtype: {}, query: {}, options: {}", secret!(&xtype), query, options);
+pub fn open_search(wallet_handle: WalletHandle, xtype: &str, query: &str, options: &str) -> VcxResult<SearchHandle> {
+    trace!("open_search >>> wallet_handle: {:?}, xtype: {}, query: {}, options: {}", wallet_handle, secret!(&xtype), query, options);
 
     if settings::indy_mocks_enabled() {
         return Ok(1);
     }
 
-    wallet::open_wallet_search(get_wallet_handle(), xtype, query, options)
+    wallet::open_wallet_search(wallet_handle, xtype, query, options)
         .wait()
         .map_err(VcxError::from)
 }
 
-pub fn fetch_next_records(search_handle: SearchHandle, count: usize) -> VcxResult<String> {
-    trace!("fetch_next_records >>> search_handle: {}, count: {}", search_handle, count);
+pub fn fetch_next_records(wallet_handle: WalletHandle, search_handle: SearchHandle, count: usize) -> VcxResult<String> {
+    trace!("fetch_next_records >>> wallet_handle: {:?}, search_handle: {}, count: {}", wallet_handle, search_handle, count);
 
     if settings::indy_mocks_enabled() {
         return Ok(String::from("{}"));
     }
 
-    wallet::fetch_wallet_search_next_records(get_wallet_handle(), search_handle, count)
+    wallet::fetch_wallet_search_next_records(wallet_handle, search_handle, count)
         .wait()
         .map_err(VcxError::from)
 }
@@ -386,7 +415,7 @@ pub mod tests {
         };
         let _handle = create_and_open_as_main_wallet(&wallet_config).unwrap();
 
-        let (my_did, my_vk) = create_and_store_my_did(None, None).unwrap();
+        let (my_did, my_vk) = create_and_store_my_did(get_wallet_handle(), None, None).unwrap();
 
         settings::set_config_value(settings::CONFIG_INSTITUTION_DID, &my_did);
         settings::get_agency_client_mut().unwrap().set_my_vk(&my_vk);
@@ -394,7 +423,7 @@ pub mod tests {
         let backup_key = settings::get_config_value(settings::CONFIG_WALLET_BACKUP_KEY).unwrap();
 
         let (type_, id, value) = _record();
-        add_record(type_, id, value, None).unwrap();
+        add_record(get_wallet_handle(), type_, id, value, None).unwrap();
 
         export_main_wallet(&export_file.path, &backup_key).unwrap();
 
@@ -481,7 +510,7 @@ pub mod tests {
         };
         let _handle = create_and_open_as_main_wallet(&wallet_config).unwrap();
 
-        let (my_did, my_vk) = create_and_store_my_did(None, None).unwrap();
+        let (my_did, my_vk) = create_and_store_my_did(get_wallet_handle(), None, None).unwrap();
 
         settings::set_config_value(settings::CONFIG_INSTITUTION_DID, &my_did);
         settings::get_agency_client_mut().unwrap().set_my_vk(&my_vk);
@@ -494,13 +523,13 @@ pub mod tests {
         let (record_type, id, value) = _record();
         let expected_retrieved_record = format!(r#"{{"type":"{}","id":"{}","value":"{}","tags":null}}"#, record_type, id, value);
 
-        add_record(record_type, id, value, None).unwrap();
-        let retrieved_record = get_record(record_type, id, &options).unwrap();
+        add_record(get_wallet_handle(), record_type, id, value, None).unwrap();
+        let retrieved_record = get_record(get_wallet_handle(), record_type, id, &options).unwrap();
         assert_eq!(retrieved_record, expected_retrieved_record);
         close_main_wallet().unwrap();
 
         open_as_main_wallet(&wallet_config).unwrap();
-        let retrieved_record = get_record(record_type, id, &options).unwrap();
+        let retrieved_record = get_record(get_wallet_handle(), record_type, id, &options).unwrap();
         assert_eq!(retrieved_record, expected_retrieved_record);
         close_main_wallet().unwrap();
 
@@ -509,7 +538,7 @@ pub mod tests {
         wallet_config2.rekey = Some(wallet_key2.into());
         wallet_config2.rekey_derivation_method = Some(settings::WALLET_KDF_ARGON2I_INT.into());
         open_as_main_wallet(&wallet_config2).unwrap();
-        let retrieved_record = get_record(record_type, id, &options).unwrap();
+        let retrieved_record = get_record(get_wallet_handle(), record_type, id, &options).unwrap();
         assert_eq!(retrieved_record, expected_retrieved_record);
         close_main_wallet().unwrap();
 
@@ -521,9 +550,9 @@ pub mod tests {
         wallet_config3.wallet_key_derivation = settings::WALLET_KDF_ARGON2I_INT.into();
         open_as_main_wallet(&wallet_config3).unwrap();
 
-        let retrieved_record = get_record(record_type, id, &options).unwrap();
+        let retrieved_record = get_record(get_wallet_handle(), record_type, id, &options).unwrap();
         assert_eq!(retrieved_record, expected_retrieved_record);
-        delete_record(record_type, id).unwrap();
+        delete_record(get_wallet_handle(), record_type, id).unwrap();
         close_main_wallet().unwrap();
 
         settings::clear_config();
@@ -568,7 +597,7 @@ pub mod tests {
         open_as_main_wallet(&wallet_config_2).unwrap();
 
         // If wallet was successfully imported, there will be an error trying to add this duplicate record
-        assert_eq!(add_record(xtype, id, value, None).unwrap_err().kind(), VcxErrorKind::DuplicationWalletRecord);
+        assert_eq!(add_record(get_wallet_handle(), xtype, id, value, None).unwrap_err().kind(), VcxErrorKind::DuplicationWalletRecord);
 
         close_main_wallet();
         delete_wallet(&wallet_config_2).unwrap();
@@ -607,7 +636,7 @@ pub mod tests {
         open_as_main_wallet(&wallet_config).unwrap();
 
         // If wallet was successfully imported, there will be an error trying to add this duplicate record
-        assert_eq!(add_record(type_, id, value, None).unwrap_err().kind(), VcxErrorKind::DuplicationWalletRecord);
+        assert_eq!(add_record(get_wallet_handle(), type_, id, value, None).unwrap_err().kind(), VcxErrorKind::DuplicationWalletRecord);
 
         close_main_wallet().unwrap();
         delete_wallet(&wallet_config).unwrap();
@@ -677,7 +706,7 @@ pub mod tests {
 
         let (record_type, id, record) = _record();
 
-        add_record(record_type, id, record, None).unwrap();
+        add_record(get_wallet_handle(), record_type, id, record, None).unwrap();
     }
 
     #[test]
@@ -687,9 +716,9 @@ pub mod tests {
 
         let (record_type, id, record) = _record();
 
-        add_record(record_type, id, record, None).unwrap();
+        add_record(get_wallet_handle(), record_type, id, record, None).unwrap();
 
-        let rc = add_record(record_type, id, record, None);
+        let rc = add_record(get_wallet_handle(), record_type, id, record, None);
         assert_eq!(rc.unwrap_err().kind(), VcxErrorKind::DuplicationWalletRecord);
     }
 
@@ -703,8 +732,8 @@ pub mod tests {
         let record_type = "Type";
         let record_type2 = "Type2";
 
-        add_record(record_type, id, record, None).unwrap();
-        add_record(record_type2, id, record, None).unwrap();
+        add_record(get_wallet_handle(), record_type, id, record, None).unwrap();
+        add_record(get_wallet_handle(), record_type2, id, record, None).unwrap();
     }
 
     #[test]
@@ -720,7 +749,7 @@ pub mod tests {
             "retrieveTags": false
         }).to_string();
 
-        let rc = get_record(record_type, id, &options);
+        let rc = get_record(get_wallet_handle(), record_type, id, &options);
         assert_eq!(rc.unwrap_err().kind(), VcxErrorKind::WalletRecordNotFound);
     }
 
@@ -738,8 +767,8 @@ pub mod tests {
         }).to_string();
         let expected_retrieved_record = format!(r#"{{"type":"{}","id":"{}","value":"{}","tags":null}}"#, record_type, id, record);
 
-        add_record(record_type, id, record, None).unwrap();
-        let retrieved_record = get_record(record_type, id, &options).unwrap();
+        add_record(get_wallet_handle(), record_type, id, record, None).unwrap();
+        let retrieved_record = get_record(get_wallet_handle(), record_type, id, &options).unwrap();
 
         assert_eq!(retrieved_record, expected_retrieved_record);
     }
@@ -751,7 +780,7 @@ pub mod tests {
 
         let (record_type, id, _) = _record();
 
-        let rc = delete_record(record_type, id);
+        let rc = delete_record(get_wallet_handle(), record_type, id);
         assert_eq!(rc.unwrap_err().kind(), VcxErrorKind::WalletRecordNotFound);
     }
 
@@ -768,9 +797,9 @@ pub mod tests {
             "retrieveTags": false
         }).to_string();
 
-        add_record(record_type, id, record, None).unwrap();
-        delete_record(record_type, id).unwrap();
-        let rc = get_record(record_type, id, &options);
+        add_record(get_wallet_handle(), record_type, id, record, None).unwrap();
+        delete_record(get_wallet_handle(), record_type, id).unwrap();
+        let rc = get_record(get_wallet_handle(), record_type, id, &options);
         assert_eq!(rc.unwrap_err().kind(), VcxErrorKind::WalletRecordNotFound);
     }
 
@@ -781,7 +810,7 @@ pub mod tests {
 
         let (record_type, id, record) = _record();
 
-        let rc = update_record_value(record_type, id, record);
+        let rc = update_record_value(get_wallet_handle(), record_type, id, record);
         assert_eq!(rc.unwrap_err().kind(), VcxErrorKind::WalletRecordNotFound);
     }
 
@@ -802,10 +831,10 @@ pub mod tests {
         let expected_initial_record = format!(r#"{{"type":"{}","id":"{}","value":"{}","tags":null}}"#, record_type, id, initial_record);
         let expected_updated_record = format!(r#"{{"type":"{}","id":"{}","value":"{}","tags":null}}"#, record_type, id, changed_record);
 
-        add_record(record_type, id, initial_record, None).unwrap();
-        let initial_record = get_record(record_type, id, &options).unwrap();
-        update_record_value(record_type, id, changed_record).unwrap();
-        let changed_record = get_record(record_type, id, &options).unwrap();
+        add_record(get_wallet_handle(), record_type, id, initial_record, None).unwrap();
+        let initial_record = get_record(get_wallet_handle(), record_type, id, &options).unwrap();
+        update_record_value(get_wallet_handle(), record_type, id, changed_record).unwrap();
+        let changed_record = get_record(get_wallet_handle(), record_type, id, &options).unwrap();
 
         assert_eq!(initial_record, expected_initial_record);
         assert_eq!(changed_record, expected_updated_record);
@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use indy::did;
+use indy::future::Future;
+use indy::WalletHandle;
+use rand::Rng;
+use serde_json::Value;
+
+use agency_client::agency_client::AgencyClient;
+
+use crate::error::prelude::*;
+use crate::libindy::utils::{pool, wallet};
+use crate::settings;
+use crate::utils::{constants, validation};
+
+/**
+Context of a single agent (tenant) served by the library: its wallet, agency client, institution DID
+and pool. Objects are created in a profile by passing its handle explicitly, they stay bound to it and every
+operation on them runs within it, so one process can serve many agents at once.
+ */
+#[derive(Clone)]
+pub struct Profile {
+    pub wallet_handle: WalletHandle,
+    pub pool_handle: Option<i32>,
+    pub institution_did: String,
+    pub agency_client: AgencyClient,
+}
+
+impl Profile {
+    pub fn create(config: &str, wallet_handle: WalletHandle, pool_handle: Option<i32>) -> VcxResult<Profile> {
+        trace!("Profile::create >>> wallet_handle: {:?}, pool_handle: {:?}", wallet_handle, pool_handle);
+
+        let configuration: HashMap<String, Value> = serde_json::from_str(config)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot parse profile config: {}", err)))?;
+
+        let institution_did = configuration.get(settings::CONFIG_INSTITUTION_DID)
+            .and_then(Value::as_str)
+            .ok_or(VcxError::from_msg(VcxErrorKind::InvalidConfiguration, format!("Profile config is missing \"{}\"", settings::CONFIG_INSTITUTION_DID)))?;
+        let institution_did = validation::validate_did(institution_did)?;
+
+        let agency_client = AgencyClient::new(config, wallet_handle.0, true)?;
+
+        Ok(Profile { wallet_handle, pool_handle, institution_did, agency_client })
+    }
+}
+
+lazy_static! {
+    static ref PROFILES: RwLock<HashMap<u32, Profile>> = RwLock::new(HashMap::new());
+}
+
+pub fn create_profile(config: &str, wallet_handle: WalletHandle, pool_handle: Option<i32>) -> VcxResult<u32> {
+    let profile = Profile::create(config, wallet_handle, pool_handle)?;
+
+    let mut profiles = PROFILES.write()?;
+    let mut handle = rand::thread_rng().gen::<u32>();
+    while handle == 0 || profiles.contains_key(&handle) {
+        handle = rand::thread_rng().gen::<u32>();
+    }
+    profiles.insert(handle, profile);
+    Ok(handle)
+}
+
+pub fn is_valid_handle(handle: u32) -> bool {
+    PROFILES.read().map(|profiles| profiles.contains_key(&handle)).unwrap_or(false)
+}
+
+pub fn release(handle: u32) -> VcxResult<()> {
+    PROFILES.write()?
+        .remove(&handle)
+        .map(|_| ())
+        .ok_or(VcxError::from_msg(VcxErrorKind::InvalidProfileHandle, format!("Profile not found for handle: {}", handle)))
+}
+
+pub fn release_all() {
+    PROFILES.write().map(|mut profiles| profiles.clear()).ok();
+}
+
+/**
+Context an object operates in: the global context configured by `vcx_init` or one of the profiles.
+It is given to the object when the object is created and is passed explicitly to every wallet, ledger
+and agency operation the object makes. A profile is looked up by its handle on every use, so objects
+of a released profile fail with `InvalidProfileHandle`.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub enum ProfileContext {
+    Global,
+    Profile(u32),
+}
+
+impl Default for ProfileContext {
+    fn default() -> ProfileContext { ProfileContext::Global }
+}
+
+fn with_profile<F, T>(handle: u32, closure: F) -> VcxResult<T> where F: FnOnce(&Profile) -> VcxResult<T> {
+    PROFILES.read()?
+        .get(&handle)
+        .ok_or(VcxError::from_msg(VcxErrorKind::InvalidProfileHandle, format!("Profile not found for handle: {}", handle)))
+        .and_then(closure)
+}
+
+impl ProfileContext {
+    pub fn from_handle(handle: Option<u32>) -> VcxResult<ProfileContext> {
+        match handle {
+            Some(handle) => with_profile(handle, |_| Ok(ProfileContext::Profile(handle))),
+            None => Ok(ProfileContext::Global)
+        }
+    }
+
+    pub fn handle(&self) -> Option<u32> {
+        match self {
+            ProfileContext::Global => None,
+            ProfileContext::Profile(handle) => Some(*handle),
+        }
+    }
+
+    pub fn wallet_handle(&self) -> VcxResult<WalletHandle> {
+        match self {
+            ProfileContext::Global => Ok(wallet::get_wallet_handle()),
+            ProfileContext::Profile(handle) => with_profile(*handle, |profile| Ok(profile.wallet_handle)),
+        }
+    }
+
+    pub fn pool_handle(&self) -> VcxResult<i32> {
+        match self {
+            ProfileContext::Global => pool::get_pool_handle(),
+            ProfileContext::Profile(handle) => with_profile(*handle, |profile| profile.pool_handle
+                .ok_or(VcxError::from_msg(VcxErrorKind::NoPoolOpen, format!("There is no pool opened for profile {}", handle)))),
+        }
+    }
+
+    pub fn institution_did(&self) -> VcxResult<String> {
+        match self {
+            ProfileContext::Global => settings::get_config_value(settings::CONFIG_INSTITUTION_DID),
+            ProfileContext::Profile(handle) => with_profile(*handle, |profile| Ok(profile.institution_did.clone())),
+        }
+    }
+
+    /**
+    Verkey of the institution DID, profiles read it from their wallet.
+     */
+    pub fn institution_verkey(&self) -> VcxResult<String> {
+        match self {
+            ProfileContext::Global => settings::get_config_value(settings::CONFIG_INSTITUTION_VERKEY),
+            ProfileContext::Profile(_) => {
+                let (wallet_handle, institution_did) = (self.wallet_handle()?, self.institution_did()?);
+                if settings::indy_mocks_enabled() { return Ok(constants::VERKEY.to_string()); }
+                did::key_for_local_did(wallet_handle, &institution_did)
+                    .wait()
+                    .map_err(VcxError::from)
+            }
+        }
+    }
+
+    /**
+    Agency client of the profile, `None` in the global context which uses the global agency settings.
+     */
+    pub fn agency_client(&self) -> VcxResult<Option<AgencyClient>> {
+        match self {
+            ProfileContext::Global => Ok(None),
+            ProfileContext::Profile(handle) => with_profile(*handle, |profile| Ok(Some(profile.agency_client.clone()))),
+        }
+    }
+
+    pub fn get_agency_client(&self) -> VcxResult<AgencyClient> {
+        match self {
+            ProfileContext::Global => Ok(settings::get_agency_client()?.clone()),
+            ProfileContext::Profile(handle) => with_profile(*handle, |profile| Ok(profile.agency_client.clone())),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::libindy::utils::wallet::get_wallet_handle;
+    use crate::utils::devsetup::SetupMocks;
+
+    use super::*;
+
+    pub fn _profile_config(institution_did: &str) -> String {
+        json!({
+            "institution_did": institution_did,
+            "agency_endpoint": "http://localhost:8081",
+            "agency_did": constants::DID,
+            "agency_verkey": constants::VERKEY,
+            "remote_to_sdk_did": constants::DID,
+            "remote_to_sdk_verkey": constants::VERKEY,
+            "sdk_to_remote_did": constants::DID,
+            "sdk_to_remote_verkey": constants::VERKEY,
+        }).to_string()
+    }
+
+    pub fn _create_profile() -> u32 {
+        create_profile(&_profile_config("V4SGRU86Z58d6TV7PBUe6f"), WalletHandle(42), Some(7)).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_create_profile_fails_for_invalid_config() {
+        let _setup = SetupMocks::init();
+
+        assert_eq!(VcxErrorKind::InvalidJson, create_profile("not json", WalletHandle(42), None).unwrap_err().kind());
+        assert_eq!(VcxErrorKind::InvalidConfiguration, create_profile("{}", WalletHandle(42), None).unwrap_err().kind());
+        assert_eq!(VcxErrorKind::InvalidDid, create_profile(&_profile_config("V4SGRU86Z58d"), WalletHandle(42), None).unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_profile_context_resolves_values_of_profile() {
+        let _setup = SetupMocks::init();
+
+        let handle = _create_profile();
+        let context = ProfileContext::from_handle(Some(handle)).unwrap();
+        assert_eq!(Some(handle), context.handle());
+        assert_eq!(WalletHandle(42), context.wallet_handle().unwrap());
+        assert_eq!(7, context.pool_handle().unwrap());
+        assert_eq!("V4SGRU86Z58d6TV7PBUe6f", context.institution_did().unwrap());
+        assert_eq!("http://localhost:8081/agency/msg", context.agency_client().unwrap().unwrap().get_agency_url().unwrap());
+
+        let global = ProfileContext::from_handle(None).unwrap();
+        assert_eq!(None, global.handle());
+        assert_eq!(get_wallet_handle(), global.wallet_handle().unwrap());
+        assert!(global.agency_client().unwrap().is_none());
+        assert_eq!(settings::get_config_value(settings::CONFIG_INSTITUTION_DID).unwrap(), global.institution_did().unwrap());
+
+        release(handle).unwrap();
+        assert_eq!(VcxErrorKind::InvalidProfileHandle, ProfileContext::from_handle(Some(handle)).unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_profile_leaves_global_context_unchanged() {
+        let _setup = SetupMocks::init();
+
+        let global_wallet_handle = get_wallet_handle();
+        let global_institution_did = settings::get_config_value(settings::CONFIG_INSTITUTION_DID).unwrap();
+
+        let handle = _create_profile();
+        let context = ProfileContext::from_handle(Some(handle)).unwrap();
+        assert_eq!(WalletHandle(42), context.wallet_handle().unwrap());
+
+        assert_eq!(global_wallet_handle, get_wallet_handle());
+        assert_eq!(global_institution_did, settings::get_config_value(settings::CONFIG_INSTITUTION_DID).unwrap());
+        assert_eq!(global_wallet_handle, ProfileContext::Global.wallet_handle().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_profile_context_fails_for_released_profile() {
+        let _setup = SetupMocks::init();
+
+        let handle = _create_profile();
+        let context = ProfileContext::from_handle(Some(handle)).unwrap();
+        release(handle).unwrap();
+
+        assert_eq!(VcxErrorKind::InvalidProfileHandle, ProfileContext::from_handle(Some(handle)).unwrap_err().kind());
+        assert_eq!(VcxErrorKind::InvalidProfileHandle, context.wallet_handle().unwrap_err().kind());
+        assert_eq!(VcxErrorKind::InvalidProfileHandle, context.institution_did().unwrap_err().kind());
+        assert_eq!(VcxErrorKind::InvalidProfileHandle, release(handle).unwrap_err().kind());
+    }
+}
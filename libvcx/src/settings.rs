@@ -42,6 +42,12 @@ pub static CONFIG_POOL_CONFIG: &'static str = "pool_config";
 pub static CONFIG_DID_METHOD: &str = "did_method";
 // proprietary or aries
 pub static CONFIG_ACTORS: &str = "actors";
+// routing granted by mediator (coordinate-mediation protocol), used by new connections
+// "true" to persist handler state into wallet records on every change
+pub static CONFIG_PERSIST_OBJECTS: &str = "persist_objects";
+// seconds to wait for the other party of issue-credential and present-proof exchanges, exchanges do not expire if not set
+pub static CONFIG_ISSUE_CREDENTIAL_EXPIRY: &str = "issue_credential_expiry";
+pub static CONFIG_PRESENT_PROOF_EXPIRY: &str = "present_proof_expiry";
 
 pub static DEFAULT_PROTOCOL_VERSION: usize = 2;
 pub static MAX_SUPPORTED_PROTOCOL_VERSION: usize = 2;
@@ -119,6 +125,19 @@ pub fn indy_mocks_enabled() -> bool {
     }
 }
 
+pub fn persist_objects_enabled() -> bool {
+    get_config_value(CONFIG_PERSIST_OBJECTS)
+        .map(|value| value == "true")
+        .unwrap_or(false)
+}
+
+pub fn get_expiry(key: &str) -> Option<u32> {
+    get_config_value(key).ok()
+        .and_then(|expiry| expiry.parse::<u32>()
+            .map_err(|err| warn!("Can't parse value of {} from config ({}), exchanges do not expire", key, err))
+            .ok())
+}
+
 pub fn get_config_value(key: &str) -> VcxResult<String> {
     trace!("get_config_value >>> key: {}", key);
 
@@ -98,6 +98,7 @@ pub static CRED_REQ: &str = r#"{"prover_did":"2hoqvcwupRTUNkXn6ArYzs","cred_def_
 pub static CRED_REQ_META: &str = r#"{"master_secret_blinding_data":{"v_prime":"19573554835481719662327485122688893711456991477879921695470731620175963787279917341526369852398210114401207141951797741891847253211319668203346462590568438671120943726162783813341598838616013039004762423956877028539225355867586807673681018234178116101643797916210905197387018359780257940149589162122784199178788814187547780152684853122014747482921656188183260370150999742557975345375106137123621426061675848590309427394874048446416740808489978625893734432529086470382099078632291038405367083882596203500659091849643476443635802557200596085378755820180062431900445542883509174786917819553164472263849777903881905876531213020487201635195790520","vr_prime":null},"nonce":"143213049816807095013964","master_secret_name":"main"}"#;
 pub static CRED_MSG: &str = r#"{"libindy_cred":"{\"schema_id\":\"2hoqvcwupRTUNkXn6ArYzs:2:schema_name:0.0.11\",\"cred_def_id\":\"2hoqvcwupRTUNkXn6ArYzs:3:CL:1766\",\"rev_reg_id\":null,\"values\":{\"height\":{\"raw\":\"4'11\",\"encoded\":\"25730877424947290072821310314181366395232879096832067784637233452620527354832\"},\"age\":{\"raw\":\"111\",\"encoded\":\"111\"},\"sex\":{\"raw\":\"male\",\"encoded\":\"5944657099558967239210949258394887428692050081607692519917050011144233115103\"},\"name\":{\"raw\":\"Bob\",\"encoded\":\"93006290325627508022776103386395994712401809437930957652111221015872244345185\"}},\"signature\":{\"p_credential\":{\"m_2\":\"31700338570294708736115754102769589522052428093121126330650183539696104868123\",\"a\":\"10777649052904447971899236694871368615157106927137883243983784973724349774915878204190601305259309589996988012735617563373033607709078907060449376941073338535388140957053144620511038735390585352398809313788054894621970622962097379200139814737879331234340443432491207812590825372988648847512086844348645931065426804353485775147746746850653036793108739563282161226029489872217064496590096990996410375663681099302996640966261859643618526555960651408715258210076488491742907031110655225420976262789193112889439595882291621507322209956202063945312604763840144988947123849968934460179482607183670360949359821454772971820091\",\"e\":\"259344723055062059907025491480697571938277889515152306249728583105665800713306759149981690559193987143012367913206299323899696942213235956742930226791220947134161266625378239251141\",\"v\":\"7160478880681489530685862580760235815690858242009735266356112612110531077619018018744043866754230561900079704139707892346188258175742757433839011445634508511903733556129176372339461109974281812535163844969408357722949237345514578452747464596905366813789049296203267070506446149025051172379040959761841090727724798945098115057584230172106482217166053964970319273092742014147315899348492822710345385893980919314665444664429040406728835710379367961220002388750496482799359536328531598808901788276665120213222621777995199496941581486741108559747481685600546431381718103907044451091043539280123106649794109913982938490727549883865702810163586252395063675836236109438822521688323977901722596298354615024259460544081204452640402841343661689933452404340637850647367306402860427277243229048988096792242220128886520666550722580134\"},\"r_credential\":null},\"signature_correctness_proof\":{\"se\":\"18062198058440854120810610625451590757963491440128115225422806009942350909306158512388123981508665484690252474351987301071051987945324357904950477248786064911802058100232994530383060574083901579841043178811110977049370234755264015098980663707351501791255018614113725688784339672744140961024678477581256784699212629381680205164276918652123852380815787068178236680303525175583034099258492257694970206425311460126364558914028413305726055746979620971531243376004995305402532327579706724246771202804790956714271731586625803214770535637064539642240021230950311414542750938384933247250254390883187655604134427458185611530857\",\"c\":\"7209681799349211936664438159371869376664205836919843273264901864121026304303\"},\"rev_reg\":null,\"witness\":null}","rev_reg_def_json":"","cred_def_id":"2hoqvcwupRTUNkXn6ArYzs:3:CL:1766","msg_type":"CRED","claim_offer_id":"1234","version":"0.1","from_did":"44oqvcwupRTUNkXn6ArYzs"}"#;
 pub static CREDS_FROM_PROOF_REQ: &str = r#"{"attrs":{"height_1":[{"cred_info":{"referent":"92556f60-d290-4b58-9a43-05c25aac214e","attrs":{"name":"Bob","height":"4'11","sex":"male","age":"111"},"schema_id":"2hoqvcwupRTUNkXn6ArYzs:2:test-licence:4.4.4","cred_def_id":"2hoqvcwupRTUNkXn6ArYzs:3:CL:2471","rev_reg_id":null,"cred_rev_id":null},"interval":null}],"zip_2":[{"cred_info":{"referent":"2dea21e2-1404-4f85-966f-d03f403aac71","attrs":{"address2":"101 Wilson Lane","city":"SLC","state":"UT","zip":"87121","address1":"101 Tela Lane"},"schema_id":"2hoqvcwupRTUNkXn6ArYzs:2:Home Address:5.5.5","cred_def_id":"2hoqvcwupRTUNkXn6ArYzs:3:CL:2479","rev_reg_id":null,"cred_rev_id":null},"interval":null}]},"predicates":{}}"#;
+pub static STORED_CREDENTIAL_INFO: &str = r#"{"referent":"cred_id","attrs":{"name":"Bob","height":"4'11","sex":"male","age":"111"},"schema_id":"2hoqvcwupRTUNkXn6ArYzs:2:test-licence:4.4.4","cred_def_id":"2hoqvcwupRTUNkXn6ArYzs:3:CL:2471","rev_reg_id":null,"cred_rev_id":null}"#;
 pub static PROOF_JSON: &str = r#"{"proof":{"proofs":[{"primary_proof":{"eq_proof":{"revealed_attrs":{"zip":"87121"},"a_prime":"11385117131660364111896731574172401420546708937324515452576064042574669257712348081839601624888211708194648786566821283532355289865761814401811668762111208667245892385556461650028028744704055636249982477585075400271187055151098908619974783653495410195833182515968533296209260588771695756312205872111048687853205561923237816546964283713422785468941977105067099251171604215251944317080214747243003427107934144216501594603687728398269433535428273623697427762663203253444115676455803364347007933198640450232624037803599295212149889810488912469008187381827942935958271186223675811120227514991467360019909918168868871310826","e":"106416440968489307688477111652744547884453520724896945262209396020249946843791285682305811237667052208528018943270519839389621717609114567","v":"693333598587517819941352847365742544380142765574139951146061816085618155189452020698413593997300585596324426252632314355582268506939303400407496417606249958981732261864957022478367092130677367027387866707732300909963513115315021003243992979679627258775140835220412197296938533762295871549731992640856382276196325998522049034267838563447330191852382971065392091768243880823490111370897368424139935395298937408562086499759656814447622881298133126653711399509169280794404022383866082757555249917245259236759269242823638516805150417229733278877402638802562166688388004908477637031034437462247846682697897200600573773942810246023809243280772183646809858924033191135463766964917504748987154189779729311869624825798007137022205256426446605413391018202025830072404371317952188965637832374449641811319605909172576074628353298939272848787572418960647922046317299399246159851863001665989283575050230911587927811099747218793149167944","m":{"address1":"14383563842714000336694832849665594946445962739184223209071037007452629790699757287294972813779623472744803572582328437911448950215408915191131611402522120804333222273489132084886","city":"6335807846068782600189687454462436112078812829004884052293133660597927372142081774735780601789659420890039727382522297770947316366004790684358611659096944452253560380208620107157","address2":"14091589660350349984354459530985480819537335363987179151561762220365706941537938554719525480994821015753311971694921036448081399741287968054078818802947721994040284668811562798267","state":"3374323397845656383843625203828356603754667474607034126894741033080120461511932284334653947059306129056440817463723283235350691497796837255669792651105389390523206636040269228223"},"m1":"3855360575146368990379338878340437444760062086797499842306857866860002517711237747809252335775444064433909759766687399940398272328893322787415703512272117087096813054972554563968","m2":"829849307958330576060854040909379039690330674646288918248299874016648994971333520820812771094141855582854026260700068274261655287053826960354567228953144532668188875028174450750"},"ge_proofs":[]},"non_revoc_proof":null},{"primary_proof":{"eq_proof":{"revealed_attrs":{"height":"25730877424947290072821310314181366395232879096832067784637233452620527354832"},"a_prime":"19606523040238141145717779531277522663224725021451964954928837778213780167806130611283656444876346090324217222925322088211429761142256636233006676888209824950866753616039040159779454392004510214888982466524158456130352445810766077072116343308981883033612830486699333857960241899822662839721374841002668163948377275672875472144246128931473522911282089521505706783701020863536724574924376299714351850171426752924728784363669376534908759391351622381020289812523865512890582404247961101220209552503431785599610046609564493669749669261122419593784559612666376852938604194178973814343952986182892452430023827671762557476463","e":"175708676962718808956603450728506825597108526784886396727551101010180796035520261116334855856024649318365254638362385900327196683643205289","v":"259807837216236716040272227118473612683891972149132456558837821820045873391789606085689340379415983105482747915525402053563208559407596040401913772550177102524653456118110816872909394038065296113425708018233090717854181564661116753447052574450590706580409613994542437680615027500439312381591222697969343626799892433263884943265734866198270469445076231573689904631155795742013664414011861042682562430778840652138660305373152231267819984539617728384058547342678165579739778850312059485316741860467804248135416832291520168882232179984203367316142165755286256284689566686572380792365871911853060464498285167571443335788402725298771375480014651263894303375604516169554253834245685148604580043733043610372908803488088857487861826680931152125459941500902596987258417619816375861312684684210554655250852226060290197739094390988802816264926238846473088662123137199115232833530286814846710257219453988118104609524238769962408378467","m":{"age":"7439137780003632501472198844883943985851982687398948370748383175062107337760437828394880407813732765383124554153414377359168765440779597987864714528157062929739375601687952057680","name":"478729181402353180485622154597836495776273289714890210090026576459958696376861534830836701841321719749324218544889558530446121758555251583232291309241803053682268055213713398699","sex":"3475750043210272427962584518900024357879025980293153402384602896340153685772192528112892451091903360990446424944281039234223910308784253682458410982729333916158905947811280570097"},"m1":"3855360575146368990379338878340437444760062086797499842306857866860002517711237747809252335775444064433909759766687399940398272328893322787415703512272117087096813054972554563968","m2":"11250551913673338582984048217906733348080383121220187035974642609915309422920937161820959912087373627684234183422675877388269370474604625896553325837450381771318770783786394439756"},"ge_proofs":[]},"non_revoc_proof":null}],"aggregated_proof":{"c_hash":"98289858372176739486785426465663295860443703464650074572637147012182597972573","c_list":[[90,48,0,0,86,54,61,179,29,65,240,170,216,13,100,217,170,179,191,79,71,2,133,94,84,120,87,90,167,161,166,221,56,137,73,156,8,139,61,114,10,131,241,112,150,110,102,170,132,231,229,250,177,131,6,33,158,228,233,190,125,211,244,238,143,232,12,25,154,52,34,54,194,237,16,20,239,207,37,3,211,34,39,70,253,226,25,2,235,164,40,227,87,103,108,40,93,125,174,74,139,95,111,175,124,1,26,204,120,172,3,125,18,149,107,209,82,168,46,185,96,173,161,80,33,165,32,40,166,106,141,41,55,143,50,219,37,210,114,90,104,64,249,193,195,157,51,34,174,127,83,251,254,102,199,221,199,239,204,13,221,216,8,254,107,100,14,31,49,38,187,243,247,12,116,110,169,98,85,202,127,237,149,126,10,115,200,229,74,131,202,195,188,176,253,132,25,117,104,197,73,252,211,192,235,136,7,83,136,235,152,98,99,208,232,50,165,117,245,89,138,255,143,241,113,14,130,94,125,253,252,74,45,73,67,49,87,12,106,218,197,238,82,134,231,210,33,228,189,181,71,168,252,217,141,234],[155,80,70,194,115,251,48,64,107,148,172,180,25,185,82,75,129,60,47,228,47,102,33,64,158,83,190,172,149,37,66,189,237,26,120,207,50,163,29,130,142,226,11,83,96,172,63,161,188,131,75,138,14,180,56,139,244,111,163,136,89,239,13,161,204,20,204,227,236,219,156,33,217,141,114,76,47,221,129,186,123,46,16,6,158,238,205,66,208,252,108,184,252,72,229,52,89,54,68,23,39,130,57,231,96,236,130,157,65,41,42,21,8,247,206,179,36,244,164,132,158,56,191,73,212,192,234,95,143,141,194,71,197,58,172,122,170,220,53,59,64,201,234,28,74,198,125,206,10,240,192,145,14,207,68,227,132,185,85,112,173,156,71,73,80,60,152,100,252,45,249,201,153,5,159,61,27,105,97,57,61,35,233,190,23,158,46,141,42,41,225,141,26,41,123,60,243,10,81,251,234,32,192,181,135,122,167,231,106,8,123,92,196,197,191,104,119,235,205,181,178,62,248,146,71,221,250,251,193,217,14,213,13,121,37,76,209,150,22,216,155,84,212,58,58,38,148,170,178,86,185,107,84,152,30,111]]}},"requested_proof":{"revealed_attrs":{"zip_2":{"sub_proof_index":0,"raw":"87121","encoded":"87121"},"height_1":{"sub_proof_index":1,"raw":"4'11","encoded":"25730877424947290072821310314181366395232879096832067784637233452620527354832"}},"self_attested_attrs":{"self_attest_3":"my_self_attested_val"},"unrevealed_attrs":{},"predicates":{}},"identifiers":[{"schema_id":"2hoqvcwupRTUNkXn6ArYzs:2:Home Address:5.5.5","cred_def_id":"2hoqvcwupRTUNkXn6ArYzs:3:CL:2479","rev_reg_id":null,"timestamp":null},{"schema_id":"2hoqvcwupRTUNkXn6ArYzs:2:test-licence:4.4.4","cred_def_id":"2hoqvcwupRTUNkXn6ArYzs:3:CL:2471","rev_reg_id":null,"timestamp":null}]}"#;
 pub static REQUESTED_ATTRS: &str = r#"[
     {
@@ -192,6 +193,10 @@ pub static CREATE_REV_REG_DEF_ACTION: (&str, &str, &str, Option<&str>, Option<&s
 pub static REV_REG_DELTA_TXN_TYPE: &str = r#"114"#;
 pub static CREATE_REV_REG_DELTA_ACTION: (&str, &str, &str, Option<&str>, Option<&str>) = (REV_REG_DELTA_TXN_TYPE, "ADD", "*", None, Some("*"));
 pub static EDIT_REV_REG_DELTA_ACTION: (&str, &str, &str, Option<&str>, Option<&str>) = (REV_REG_DELTA_TXN_TYPE, "EDIT", "*", Some("*"), Some("*"));
+pub static NYM_TXN_TYPE: &str = r#"1"#;
+pub static EDIT_NYM_VERKEY_ACTION: (&str, &str, &str, Option<&str>, Option<&str>) = (NYM_TXN_TYPE, "EDIT", "verkey", Some("*"), Some("*"));
+pub static ATTRIB_TXN_TYPE: &str = r#"100"#;
+pub static CREATE_ATTRIB_ACTION: (&str, &str, &str, Option<&str>, Option<&str>) = (ATTRIB_TXN_TYPE, "ADD", "*", None, Some("*"));
 pub static TRANSFER_TXN_TYPE: &str = r#"10001"#;
 pub static CREATE_TRANSFER_ACTION: (&str, &str, &str, Option<&str>, Option<&str>) = (TRANSFER_TXN_TYPE, "ADD", "*", None, Some("*"));
 pub const REVOC_REG_TYPE: &str = "CL_ACCUM";
@@ -215,6 +220,9 @@ pub static TEST_TAILS_FILE: &str = r#"tails_file"#;
 pub static TEST_TAILS_LOCATION: &str = r#"/var/folders/kr/9gkxsj_s01b6fvx_72trl3mm0000gp/T/tails_file/5R6BWXL3vPrbJPKe9FsHAVG9hqKdDvVxonBuj3ETYuZh"#;
 pub static TEST_TAILS_HASH: &str = r#"5R6BWXL3vPrbJPKe9FsHAVG9hqKdDvVxonBuj3ETYuZh"#;
 pub static TEST_TAILS_URL: &str = r#"https://get-tails-here.org"#;
+pub static NYM_REQUEST: &str = r#"{"reqId":1522866729726860308,"identifier":"2hoqvcwupRTUNkXn6ArYzs","operation":{"type":"1","dest":"V4SGRU86Z58d6TV7PBUe6f","verkey":"GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL"},"protocolVersion":2}"#;
+pub static ATTRIB_REQUEST: &str = r#"{"reqId":1522866729726860309,"identifier":"2hoqvcwupRTUNkXn6ArYzs","operation":{"type":"100","dest":"2hoqvcwupRTUNkXn6ArYzs","raw":"{\"endpoint\":{\"endpoint\":\"http://localhost:8080\"}}"},"protocolVersion":2}"#;
+pub static ATTRIB_DATA: &str = r#"{"endpoint":{"endpoint":"http://localhost:8080"}}"#;
 pub static REQUEST_WITH_ENDORSER: &str = r#"{"seqNo":344,"reqId":1522866729726860308,"identifier":"VsKV7grR1BUE29mG2Fm2kX","txnTime":1516284381,"type":"101","endorser":"V4SGRU86Z58d6TV7PBUe6f"}"#;
 
 pub fn rev_def_json() -> String {
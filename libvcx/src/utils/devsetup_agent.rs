@@ -14,6 +14,7 @@ pub mod test {
     use crate::error::{VcxError, VcxErrorKind, VcxResult};
     use crate::init::{create_agency_client_for_main_wallet, init_issuer_config, open_as_main_wallet};
     use crate::libindy::utils::wallet::*;
+    use crate::profile::ProfileContext;
     use crate::utils::constants;
     use crate::utils::devsetup::*;
     use crate::utils::provision::{AgencyClientConfig, AgentProvisionConfig, provision_cloud_agent};
@@ -144,7 +145,7 @@ pub mod test {
                 config_issuer,
                 schema_handle: 0,
                 cred_def_handle: 0,
-                connection: Connection::create("alice", true).unwrap(),
+                connection: Connection::create(ProfileContext::Global, "alice", true).unwrap(),
                 credential_handle: 0,
                 presentation_handle: 0,
             };
@@ -326,7 +327,7 @@ pub mod test {
                 is_active: false,
                 config_wallet,
                 config_agency,
-                connection: Connection::create("tmp_empoty", true).unwrap(),
+                connection: Connection::create(ProfileContext::Global, "tmp_empoty", true).unwrap(),
                 credential_handle: 0,
                 presentation_handle: 0,
             };
@@ -336,7 +337,7 @@ pub mod test {
 
         pub fn accept_invite(&mut self, invite: &str) {
             self.activate().unwrap();
-            self.connection = Connection::create_with_invite("faber", serde_json::from_str(invite).unwrap(), true).unwrap();
+            self.connection = Connection::create_with_invite(ProfileContext::Global, "faber", serde_json::from_str(invite).unwrap(), true).unwrap();
             self.connection.connect().unwrap();
             self.connection.update_state().unwrap();
             assert_eq!(ConnectionState::Invitee(InviteeState::Requested), self.connection.get_state());
@@ -117,6 +117,9 @@ pub static NO_AGENT_INFO: Error = Error { code_num: 1106, message: "Agent pairwi
 pub static REV_REG_DEF_NOT_FOUND: Error = Error { code_num: 1107, message: "No revocation definition found" };
 pub static REV_DELTA_NOT_FOUND: Error = Error { code_num: 1108, message: "No revocation delta found in storage for this revocation registry. Were any credentials locally revoked?" };
 pub static POISONED_LOCK: Error = Error { code_num: 1109, message: "Attempted to lock a poisoned lock" };
+pub static INVALID_PROFILE_HANDLE: Error = Error { code_num: 1110, message: "Invalid Profile Handle" };
+pub static INVALID_MEDIATION_HANDLE: Error = Error { code_num: 1111, message: "Invalid Mediation Handle" };
+pub static OBJECT_NOT_PERSISTED: Error = Error { code_num: 1112, message: "Object has changed but its wallet record could not be written" };
 
 lazy_static! {
     static ref ERROR_C_MESSAGES: HashMap<u32, CString> = {
@@ -223,6 +226,9 @@ lazy_static! {
         insert_c_message(&mut m, &ACTION_NOT_SUPPORTED);
         insert_c_message(&mut m, &INVALID_REDIRECT_DETAILS);
         insert_c_message(&mut m, &NO_AGENT_INFO);
+        insert_c_message(&mut m, &INVALID_PROFILE_HANDLE);
+        insert_c_message(&mut m, &INVALID_MEDIATION_HANDLE);
+        insert_c_message(&mut m, &OBJECT_NOT_PERSISTED);
 
         m
     };
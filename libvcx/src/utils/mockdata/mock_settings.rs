@@ -6,6 +6,7 @@ use crate::error::{VcxError, VcxResult};
 static MOCKED_GENERATED_PROOF: &str = "mocked_proof";
 static MOCKED_RETRIEVED_CREDS: &str = "mocked_retrieved_creds";
 static MOCKED_VALIDATE_INDY_PROOF: &str = "mocked_validate_indy_proof";
+static MOCKED_WALLET_RECORD: &str = "mocked_wallet_record";
 
 lazy_static! {
     static ref MOCK_SETTINGS: RwLock<HashMap<String, String>> = RwLock::new(HashMap::new());
@@ -40,6 +41,13 @@ impl MockBuilder {
         self
     }
 
+    pub fn set_mock_wallet_record(self, record_type: &str, id: &str, record: &str) -> MockBuilder {
+        warn!("MockBuilder::set_mock_wallet_record >>> record_type: {}, id: {}", record_type, id);
+        let mut settings = MOCK_SETTINGS.write().unwrap();
+        settings.insert(format!("{}:{}:{}", MOCKED_WALLET_RECORD, record_type, id), record.into());
+        self
+    }
+
     pub fn reset_mock_settings(&self) {
         warn!("MockBuilder::reset_mock_settings >>>");
         let mut config = MOCK_SETTINGS.write().unwrap();
@@ -68,6 +76,13 @@ pub fn get_mock_creds_retrieved_for_proof_request() -> Option<String> {
         .map(|s| String::from(s))
 }
 
+pub fn get_mock_wallet_record(record_type: &str, id: &str) -> Option<String> {
+    let config = MOCK_SETTINGS.read().unwrap();
+    config
+        .get(&format!("{}:{}:{}", MOCKED_WALLET_RECORD, record_type, id))
+        .map(String::from)
+}
+
 pub fn get_mock_result_for_validate_indy_proof() -> Option<VcxResult<bool>> {
     let config = MOCK_SETTINGS_RESULT_BOOL.read().unwrap();
     config
@@ -17,6 +17,28 @@ pub const CREDENTIAL_ISSUER_SM_INITIAL: &str = r#"
     }
 }"#;
 
+// Alice proposes Cred to Faber
+pub const ARIES_CREDENTIAL_PROPOSAL: &str = r#"{
+    "@id": "3a4ac5cd-2d55-4ba0-b3fb-3b1b25d94a4b",
+    "@type": "did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/issue-credential/1.0/propose-credential",
+    "comment": "I would like to get degree credential",
+    "credential_proposal": {
+        "@type": "did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/issue-credential/1.0/credential-preview",
+        "attributes": [
+            {
+                "name": "degree",
+                "value": "maths"
+            },
+            {
+                "name": "name",
+                "value": "alice"
+            }
+        ]
+    },
+    "schema_id": "V4SGRU86Z58d6TV7PBUe6f:2:FaberVcx:83.23.62",
+    "cred_def_id": "V4SGRU86Z58d6TV7PBUe6f:3:CL:31:tag1"
+}"#;
+
 // Faber send Cred offer to Alice
 pub const ARIES_CREDENTIAL_OFFER: &str = r#"{
     "@id": "57b3f85d-7673-4e6f-bb09-cc27cf2653c0",
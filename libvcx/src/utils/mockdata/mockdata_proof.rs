@@ -1,3 +1,29 @@
+// Alice proposes presentation to Faber
+pub const ARIES_PRESENTATION_PROPOSAL: &str = r#"
+{
+    "@id": "0b8e8e34-7b5d-4e5a-9f09-7e6a8e4f9d1c",
+    "@type": "did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/present-proof/1.0/propose-presentation",
+    "comment": "I can share my degree and prove I am of age",
+    "presentation_proposal": {
+        "@type": "did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/present-proof/1.0/presentation-preview",
+        "attributes": [
+            {
+                "name": "degree",
+                "cred_def_id": "V4SGRU86Z58d6TV7PBUe6f:3:CL:31:tag1"
+            }
+        ],
+        "predicates": [
+            {
+                "name": "age",
+                "cred_def_id": "V4SGRU86Z58d6TV7PBUe6f:3:CL:31:tag1",
+                "predicate": ">=",
+                "threshold": 18
+            }
+        ]
+    }
+}
+"#;
+
 // Faber send Cred offer to Alice
 pub const ARIES_PROOF_REQUEST_PRESENTATION: &str = r#"
 {